@@ -0,0 +1,193 @@
+use git_provider::{ChangedFile, FileStatus};
+
+/// Splits the output of `git diff --find-renames` into one entry per file, with patches
+/// starting at the first hunk header as the hosts' APIs return them
+pub(crate) fn parse(output: &str) -> Vec<ChangedFile> {
+    let mut files = Vec::new();
+
+    for section in output.split("\ndiff --git ") {
+        let section = section.strip_prefix("diff --git ").unwrap_or(section);
+        if section.trim().is_empty() {
+            continue;
+        }
+        files.push(parse_section(section));
+    }
+
+    files
+}
+
+fn parse_section(section: &str) -> ChangedFile {
+    let (header, patch) = match section.find("\n@@") {
+        Some(index) => (&section[..index], Some(&section[index + 1..])),
+        None => (section, None),
+    };
+
+    let mut lines = header.lines();
+    // "a/old b/new", ambiguous with spaces in names, only used when nothing better is present
+    let first = lines.next().unwrap_or_default();
+    let (mut old_path, mut new_path) = split_header_paths(first);
+
+    let mut status = FileStatus::Modified;
+    let mut similarity = None;
+    for line in lines {
+        if line.starts_with("new file mode") {
+            status = FileStatus::Added;
+        } else if line.starts_with("deleted file mode") {
+            status = FileStatus::Removed;
+        } else if let Some(path) = line.strip_prefix("rename from ") {
+            status = FileStatus::Renamed;
+            old_path = path.to_string();
+        } else if let Some(path) = line.strip_prefix("rename to ") {
+            new_path = path.to_string();
+        } else if let Some(percent) = line
+            .strip_prefix("similarity index ")
+            .and_then(|p| p.strip_suffix('%'))
+        {
+            similarity = percent.parse().ok();
+        } else if let Some(path) = line.strip_prefix("--- a/") {
+            old_path = path.to_string();
+        } else if let Some(path) = line.strip_prefix("+++ b/") {
+            new_path = path.to_string();
+        }
+    }
+
+    let patch = patch.map(|patch| patch.trim_end_matches('\n').to_string());
+    let (additions, deletions) = patch.as_deref().map(count_changes).unwrap_or_default();
+
+    ChangedFile {
+        path: if status == FileStatus::Removed {
+            old_path.clone()
+        } else {
+            new_path
+        },
+        previous_path: (status == FileStatus::Renamed).then_some(old_path),
+        similarity: similarity.filter(|_| status == FileStatus::Renamed),
+        status,
+        additions,
+        deletions,
+        patch,
+    }
+}
+
+fn split_header_paths(line: &str) -> (String, String) {
+    let line = line.strip_prefix("a/").unwrap_or(line);
+    // Without a rename both halves are the same path, around the separator in the middle
+    if let Some(index) = line.len().checked_sub(3).map(|length| length / 2)
+        && line.get(index..index + 3) == Some(" b/")
+        && line[..index] == line[index + 3..]
+    {
+        return (line[..index].to_string(), line[index + 3..].to_string());
+    }
+    let half = line.len() / 2;
+    match line
+        .get(half..)
+        .and_then(|rest| rest.find(" b/").map(|i| half + i))
+    {
+        Some(index) => (line[..index].to_string(), line[index + 3..].to_string()),
+        None => match line.split_once(" b/") {
+            Some((old, new)) => (old.to_string(), new.to_string()),
+            None => (line.to_string(), line.to_string()),
+        },
+    }
+}
+
+fn count_changes(patch: &str) -> (usize, usize) {
+    patch.lines().fold((0, 0), |(additions, deletions), line| {
+        if line.starts_with('+') {
+            (additions + 1, deletions)
+        } else if line.starts_with('-') {
+            (additions, deletions + 1)
+        } else {
+            (additions, deletions)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "diff --git a/src/lib.rs b/src/lib.rs
+index 83db48f..bf269f4 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1,3 @@
+ fn a() {}
+-fn b() {}
++fn b() -> u8 { 1 }
++fn c() {}
+diff --git a/docs/new guide.md b/docs/new guide.md
+new file mode 100644
+index 0000000..e69de29
+--- /dev/null
++++ b/docs/new guide.md
+@@ -0,0 +1 @@
++# Guide
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+index e69de29..0000000
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+diff --git a/before.rs b/after.rs
+similarity index 91%
+rename from before.rs
+rename to after.rs
+diff --git a/logo.png b/logo.png
+index 1111111..2222222 100644
+Binary files a/logo.png and b/logo.png differ
+";
+
+    #[test]
+    fn sections_become_changed_files() {
+        let files = parse(OUTPUT);
+        assert_eq!(files.len(), 5);
+
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].status, FileStatus::Modified);
+        assert_eq!((files[0].additions, files[0].deletions), (2, 1));
+        assert!(
+            files[0]
+                .patch
+                .as_deref()
+                .unwrap()
+                .starts_with("@@ -1,2 +1,3 @@")
+        );
+        assert!(files[0].patch.as_deref().unwrap().ends_with("+fn c() {}"));
+
+        assert_eq!(files[1].path, "docs/new guide.md");
+        assert_eq!(files[1].status, FileStatus::Added);
+        assert_eq!((files[1].additions, files[1].deletions), (1, 0));
+
+        assert_eq!(files[2].path, "old.txt");
+        assert_eq!(files[2].status, FileStatus::Removed);
+        assert_eq!((files[2].additions, files[2].deletions), (0, 1));
+
+        assert_eq!(files[3].path, "after.rs");
+        assert_eq!(files[3].status, FileStatus::Renamed);
+        assert_eq!(files[3].previous_path.as_deref(), Some("before.rs"));
+        assert_eq!(files[3].similarity, Some(91));
+        assert!(files[3].patch.is_none());
+
+        assert_eq!(files[4].path, "logo.png");
+        assert!(files[4].patch.is_none());
+    }
+
+    #[test]
+    fn header_paths_split_on_the_middle_separator() {
+        assert_eq!(
+            split_header_paths("a/x b/y.txt b/x b/y.txt"),
+            ("x b/y.txt".to_string(), "x b/y.txt".to_string())
+        );
+        assert_eq!(
+            split_header_paths("a/before.rs b/after.rs"),
+            ("before.rs".to_string(), "after.rs".to_string())
+        );
+    }
+
+    #[test]
+    fn empty_output_has_no_files() {
+        assert!(parse("").is_empty());
+    }
+}
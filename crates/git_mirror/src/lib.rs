@@ -0,0 +1,795 @@
+mod diff;
+mod planner;
+mod provider;
+mod remote;
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::SystemTime,
+};
+
+use anyhow::{Result, anyhow};
+use git_provider::{
+    ChangedFile, CloneRemote, CommitDetails, CommitInfo, CompareMode, GitRef, RefComparison,
+    RepoItem, RepoItemType,
+    middleware::{DryRun, PlannedCall},
+    pii,
+};
+use tokio::{
+    process::Command,
+    sync::{Mutex, OnceCell},
+};
+
+pub use crate::{
+    planner::{Decision, Planner, Strategy},
+    provider::MirroredProvider,
+    remote::RemoteGitProvider,
+};
+
+/// Oldest git mirrors are run with, the first reading configuration from the environment
+const MIN_GIT_VERSION: (u32, u32) = (2, 31);
+
+/// Hash of the empty tree, the parent root commits are compared against
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// Separates the fields, then the records, of `git log` output
+const FIELD_SEPARATOR: char = '\x1f';
+const RECORD_SEPARATOR: char = '\x1e';
+const LOG_FORMAT: &str = "--format=%H%x1f%an%x1f%ae%x1f%aI%x1f%B%x1e";
+
+/// Lengths of the commit hashes accepted, from abbreviated ones to full SHA-256 ones
+const MIN_SHA_LENGTH: usize = 4;
+const MAX_SHA_LENGTH: usize = 64;
+
+/// How much of a repository a mirror holds, to bound disk and bandwidth usage
+#[derive(Debug, Clone, Default)]
+pub struct CloneOptions {
+    /// Commits kept per reference, full history when `None`
+    pub depth: Option<u32>,
+    /// Partial clone filter (e.g., "blob:none"), file contents are then fetched on demand
+    pub filter: Option<String>,
+}
+
+impl CloneOptions {
+    /// Reads `GITINGEST_CLONE_DEPTH` and `GITINGEST_CLONE_FILTER`
+    pub fn from_env() -> Result<Self> {
+        let depth = match env::var("GITINGEST_CLONE_DEPTH") {
+            Ok(depth) => Some(
+                depth
+                    .parse::<u32>()
+                    .map_err(|_| anyhow!("Invalid GITINGEST_CLONE_DEPTH: {}", depth))?,
+            )
+            .filter(|depth| *depth > 0),
+            Err(_) => None,
+        };
+        let filter = env::var("GITINGEST_CLONE_FILTER")
+            .ok()
+            .filter(|f| !f.is_empty());
+
+        Self { depth, filter }.validated()
+    }
+
+    /// Rejects filters git would take for another option
+    pub fn validated(self) -> Result<Self> {
+        if let Some(filter) = &self.filter
+            && !["blob:", "tree:", "object:type="]
+                .iter()
+                .any(|prefix| filter.starts_with(prefix))
+        {
+            return Err(anyhow!(
+                "Invalid clone filter '{}': expected e.g. 'blob:none', 'blob:limit=1m' or 'tree:0'",
+                filter
+            ));
+        }
+        Ok(self)
+    }
+}
+
+/// What a mirror looks like after being cloned or fetched
+#[derive(Debug, Clone)]
+pub struct MirrorStatus {
+    pub path: PathBuf,
+    /// Whether the mirror was cloned, rather than updated, by this call
+    pub cloned: bool,
+    pub default_branch: String,
+    pub branches: usize,
+    pub tags: usize,
+    /// Whether history is truncated by a clone depth
+    pub shallow: bool,
+    /// The partial clone filter contents are fetched with
+    pub filter: Option<String>,
+}
+
+/// Bare mirror clones of repositories kept on disk, so history and diff operations can run on
+/// local git instead of the hosts' rate-limited APIs
+pub struct MirrorCache {
+    root: PathBuf,
+    clone_options: CloneOptions,
+    /// Clones and fetches run one at a time, git locks the repository while writing
+    sync_lock: Mutex<()>,
+}
+
+impl MirrorCache {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            clone_options: CloneOptions::default(),
+            sync_lock: Mutex::new(()),
+        }
+    }
+
+    /// Options of the clones made without explicit ones, such as the planner's
+    pub fn with_clone_options(mut self, clone_options: CloneOptions) -> Self {
+        self.clone_options = clone_options;
+        self
+    }
+
+    pub fn clone_options(&self) -> &CloneOptions {
+        &self.clone_options
+    }
+
+    /// The directory mirrors are kept in: `GITINGEST_CACHE_DIR`, else the user's cache directory
+    pub fn default_root() -> PathBuf {
+        if let Some(dir) = env::var_os("GITINGEST_CACHE_DIR") {
+            return PathBuf::from(dir);
+        }
+
+        env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .unwrap_or_else(env::temp_dir)
+            .join("gitingest-mcp")
+    }
+
+    /// Where the mirror of a repository lives, whether it exists or not
+    pub fn mirror_path(&self, provider: &str, repo_path: &str) -> PathBuf {
+        let repo_path = repo_path.split("/-/").next().unwrap_or(repo_path);
+        // Repositories identified by their URL are kept under their host
+        let repo_path = repo_path
+            .split_once("://")
+            .map_or(repo_path, |(_, location)| location);
+        let mut path = self.root.join("mirrors").join(provider);
+        for segment in repo_path
+            .split('/')
+            .filter(|s| !s.is_empty() && *s != "." && *s != "..")
+        {
+            path.push(segment);
+        }
+        path.set_extension("git");
+        path
+    }
+
+    /// The mirror of a repository, if it was cloned, authenticating the contents a partial
+    /// clone fetches on demand with the remote's credentials
+    pub fn mirror(&self, provider: &str, repo_path: &str, remote: &CloneRemote) -> Option<Mirror> {
+        let path = self.mirror_path(provider, repo_path);
+        path.join("HEAD").is_file().then(|| Mirror {
+            path,
+            credentials: remote.credentials.clone(),
+        })
+    }
+
+    /// Clones the repository as a bare mirror, or fetches its new commits when already cloned.
+    /// A fetch keeps history within `options.depth`, and deepens a shallow mirror to the full
+    /// history when no depth is given.
+    pub async fn sync(
+        &self,
+        provider: &str,
+        repo_path: &str,
+        remote: &CloneRemote,
+        options: &CloneOptions,
+    ) -> Result<MirrorStatus> {
+        let _guard = self.sync_lock.lock().await;
+
+        let (mirror, cloned) = match self.mirror(provider, repo_path, remote) {
+            Some(mirror) => {
+                plan_transfer(&remote.url)?;
+                let depth = options.depth.map(|depth| format!("--depth={}", depth));
+                let mut args = vec!["fetch", "--prune", "--quiet"];
+                match &depth {
+                    Some(depth) => args.push(depth),
+                    None if mirror.is_shallow().await? => args.push("--unshallow"),
+                    None => {}
+                }
+                args.push("origin");
+
+                mirror.git(&args).await?;
+                mirror.touch().await;
+                (mirror, false)
+            }
+            None => (
+                self.clone_mirror(self.mirror_path(provider, repo_path), remote, options)
+                    .await?,
+                true,
+            ),
+        };
+
+        mirror.status(cloned).await
+    }
+
+    /// The mirror of a repository, cloning it first with the default options when missing but
+    /// never fetching
+    pub async fn ensure(
+        &self,
+        provider: &str,
+        repo_path: &str,
+        remote: &CloneRemote,
+    ) -> Result<Mirror> {
+        let _guard = self.sync_lock.lock().await;
+
+        // Concurrent calls wait for the first one to clone
+        match self.mirror(provider, repo_path, remote) {
+            Some(mirror) => Ok(mirror),
+            None => {
+                self.clone_mirror(
+                    self.mirror_path(provider, repo_path),
+                    remote,
+                    &self.clone_options,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn clone_mirror(
+        &self,
+        path: PathBuf,
+        remote: &CloneRemote,
+        options: &CloneOptions,
+    ) -> Result<Mirror> {
+        plan_transfer(&remote.url)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let path_arg = path.to_string_lossy().into_owned();
+        let depth = options.depth.map(|depth| format!("--depth={}", depth));
+        let filter = options
+            .filter
+            .as_ref()
+            .map(|filter| format!("--filter={}", filter));
+
+        let mut args = vec!["clone", "--mirror", "--quiet"];
+        if let Some(depth) = &depth {
+            // A depth otherwise implies cloning the default branch only
+            args.extend([depth.as_str(), "--no-single-branch"]);
+        }
+        args.extend(filter.as_deref());
+        args.extend(["--", remote.url.as_str(), path_arg.as_str()]);
+        run_git(None, remote.credentials.as_ref(), &args).await?;
+
+        let mirror = Mirror {
+            path,
+            credentials: remote.credentials.clone(),
+        };
+        mirror.touch().await;
+        Ok(mirror)
+    }
+}
+
+/// A bare mirror clone of a single repository
+#[derive(Debug, Clone)]
+pub struct Mirror {
+    path: PathBuf,
+    credentials: Option<(String, String)>,
+}
+
+impl Mirror {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// When the mirror was last cloned or fetched
+    pub fn last_synced(&self) -> Option<SystemTime> {
+        std::fs::metadata(self.path.join("gitingest-synced"))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    async fn touch(&self) {
+        // Only the modification time matters, a failure just reports the mirror as never synced
+        let _ = tokio::fs::write(self.path.join("gitingest-synced"), b"").await;
+    }
+
+    async fn status(&self, cloned: bool) -> Result<MirrorStatus> {
+        let refs = self
+            .git(&[
+                "for-each-ref",
+                "--format=%(refname)",
+                "refs/heads",
+                "refs/tags",
+            ])
+            .await?;
+        // Unset configuration makes git exit with an error
+        let filter = self
+            .git(&["config", "--get", "remote.origin.partialclonefilter"])
+            .await
+            .ok()
+            .map(|filter| filter.trim().to_string())
+            .filter(|filter| !filter.is_empty());
+
+        Ok(MirrorStatus {
+            path: self.path.clone(),
+            cloned,
+            default_branch: self.default_branch().await?,
+            branches: refs
+                .lines()
+                .filter(|r| r.starts_with("refs/heads/"))
+                .count(),
+            tags: refs.lines().filter(|r| r.starts_with("refs/tags/")).count(),
+            shallow: self.is_shallow().await?,
+            filter,
+        })
+    }
+
+    async fn is_shallow(&self) -> Result<bool> {
+        Ok(self
+            .git(&["rev-parse", "--is-shallow-repository"])
+            .await?
+            .trim()
+            == "true")
+    }
+
+    pub async fn default_branch(&self) -> Result<String> {
+        Ok(self
+            .git(&["symbolic-ref", "--short", "HEAD"])
+            .await?
+            .trim()
+            .to_string())
+    }
+
+    /// The name of a reference as git resolves it within the mirror, and as it is reported
+    async fn resolve(&self, git_ref: Option<GitRef>) -> Result<(String, String)> {
+        if let Some(git_ref) = &git_ref {
+            check_ref(git_ref).await?;
+        }
+
+        Ok(match git_ref {
+            Some(GitRef::Branch(name)) => (format!("refs/heads/{}", name), name),
+            Some(GitRef::Tag(name)) => (format!("refs/tags/{}", name), name),
+            Some(GitRef::Commit(sha)) => (sha.clone(), sha),
+            Some(GitRef::Default) | None => ("HEAD".to_string(), self.default_branch().await?),
+        })
+    }
+
+    pub async fn list_commits(
+        &self,
+        git_ref: Option<GitRef>,
+        path: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        let (rev, _) = self.resolve(git_ref).await?;
+        let limit = format!("--max-count={}", limit);
+
+        let mut args = vec!["log", LOG_FORMAT, &limit, "--end-of-options", &rev, "--"];
+        args.extend(path);
+        parse_log(&self.git(&args).await?)
+    }
+
+    pub async fn list_commits_since(
+        &self,
+        git_ref: Option<GitRef>,
+        since: &str,
+        max_commits: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        let (rev, _) = self.resolve(git_ref).await?;
+        let since = format!("--since={}", since);
+        let limit = format!("--max-count={}", max_commits);
+
+        parse_log(
+            &self
+                .git(&[
+                    "log",
+                    LOG_FORMAT,
+                    &since,
+                    &limit,
+                    "--end-of-options",
+                    &rev,
+                    "--",
+                ])
+                .await?,
+        )
+    }
+
+    pub async fn get_commit(&self, sha: &str) -> Result<CommitDetails> {
+        check_sha(sha)?;
+        let commit = parse_log(
+            &self
+                .git(&[
+                    "log",
+                    LOG_FORMAT,
+                    "--max-count=1",
+                    "--end-of-options",
+                    sha,
+                    "--",
+                ])
+                .await?,
+        )?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("Commit not found: {}", sha))?;
+
+        // Merge commits are compared against their first parent, as the hosts show them
+        let parent = self
+            .git(&[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                "--end-of-options",
+                &format!("{}^", sha),
+            ])
+            .await
+            .map(|parent| parent.trim().to_string())
+            .unwrap_or_else(|_| EMPTY_TREE.to_string());
+
+        Ok(CommitDetails {
+            files: self.diff(&parent, &commit.sha).await?,
+            commit,
+        })
+    }
+
+    pub async fn compare_refs(
+        &self,
+        base: Option<GitRef>,
+        head: Option<GitRef>,
+        mode: CompareMode,
+    ) -> Result<RefComparison> {
+        let (base_rev, base) = self.resolve(base).await?;
+        let (head_rev, head) = self.resolve(head).await?;
+
+        let merge_base = match mode {
+            CompareMode::MergeBase => Some(
+                self.git(&["merge-base", "--end-of-options", &base_rev, &head_rev])
+                    .await?
+                    .trim()
+                    .to_string(),
+            ),
+            CompareMode::Direct => None,
+        };
+
+        let range = format!("{}..{}", base_rev, head_rev);
+        let commits = parse_log(
+            &self
+                .git(&[
+                    "log",
+                    LOG_FORMAT,
+                    "--reverse",
+                    "--end-of-options",
+                    &range,
+                    "--",
+                ])
+                .await?,
+        )?;
+
+        let files = self
+            .diff(merge_base.as_deref().unwrap_or(&base_rev), &head_rev)
+            .await?;
+
+        Ok(RefComparison {
+            base,
+            head,
+            merge_base,
+            commits,
+            files,
+        })
+    }
+
+    /// Whether the commit a reference points at is in the mirror
+    pub async fn has_revision(&self, git_ref: Option<GitRef>) -> bool {
+        match self.resolve(git_ref).await {
+            Ok((rev, _)) => self
+                .git(&[
+                    "rev-parse",
+                    "--verify",
+                    "--quiet",
+                    "--end-of-options",
+                    &format!("{}^{{commit}}", rev),
+                ])
+                .await
+                .is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    /// Fetches a single reference, or a commit by its hash when the host allows it, keeping
+    /// history within `depth`
+    pub async fn fetch_ref(&self, git_ref: &GitRef, depth: Option<u32>) -> Result<()> {
+        check_ref(git_ref).await?;
+        plan_transfer(
+            self.git(&["config", "--get", "remote.origin.url"])
+                .await?
+                .trim(),
+        )?;
+        let refspec = match git_ref {
+            GitRef::Branch(name) => format!("+refs/heads/{0}:refs/heads/{0}", name),
+            GitRef::Tag(name) => format!("+refs/tags/{0}:refs/tags/{0}", name),
+            GitRef::Commit(sha) => sha.clone(),
+            GitRef::Default => "HEAD".to_string(),
+        };
+        let depth = depth.map(|depth| format!("--depth={}", depth));
+
+        let mut args = vec!["fetch", "--quiet"];
+        args.extend(depth.as_deref());
+        args.extend(["--end-of-options", "origin", refspec.as_str()]);
+        self.git(&args).await?;
+        Ok(())
+    }
+
+    /// Every file and directory at a reference, from tree objects only so a blobless clone
+    /// fetches no contents. Sizes are left unknown for the same reason.
+    pub async fn list_tree(&self, git_ref: Option<GitRef>) -> Result<Vec<RepoItem>> {
+        let (rev, _) = self.resolve(git_ref).await?;
+        let output = self
+            .git(&["ls-tree", "-r", "-t", "-z", "--end-of-options", &rev])
+            .await?;
+
+        output
+            .split('\0')
+            .filter(|record| !record.is_empty())
+            .filter_map(|record| {
+                // "<mode> <type> <object>\t<path>", submodules are commits and are skipped
+                let (info, path) = match record.split_once('\t') {
+                    Some(entry) => entry,
+                    None => return Some(Err(anyhow!("Malformed git ls-tree output"))),
+                };
+                let mut fields = info.split(' ').skip(1);
+                let item_type = match fields.next() {
+                    Some("blob") => RepoItemType::File,
+                    Some("tree") => RepoItemType::Directory,
+                    _ => return None,
+                };
+
+                Some(Ok(RepoItem {
+                    name: path.rsplit('/').next().unwrap_or(path).to_string(),
+                    path: path.to_string(),
+                    item_type,
+                    size: None,
+                    sha: fields.next().map(str::to_string),
+                }))
+            })
+            .collect()
+    }
+
+    /// The content of a file at a reference, fetched on demand by a partial clone
+    pub async fn file_content(&self, git_ref: Option<GitRef>, path: &str) -> Result<String> {
+        let (rev, _) = self.resolve(git_ref).await?;
+        self.git(&[
+            "cat-file",
+            "blob",
+            "--end-of-options",
+            &format!("{}:{}", rev, path.trim_start_matches('/')),
+        ])
+        .await
+    }
+
+    async fn diff(&self, from: &str, to: &str) -> Result<Vec<ChangedFile>> {
+        let output = self
+            .git(&[
+                "-c",
+                "core.quotePath=false",
+                "diff",
+                "--no-color",
+                "--no-ext-diff",
+                "--find-renames",
+                "--end-of-options",
+                from,
+                to,
+            ])
+            .await?;
+
+        Ok(diff::parse(&output))
+    }
+
+    async fn git(&self, args: &[&str]) -> Result<String> {
+        run_git(Some(&self.path), self.credentials.as_ref(), args).await
+    }
+}
+
+/// Checks a commit hash is one, so it is never read as an option or a revision expression
+fn check_sha(sha: &str) -> Result<()> {
+    if (MIN_SHA_LENGTH..=MAX_SHA_LENGTH).contains(&sha.len())
+        && sha.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Invalid commit hash '{}', expected {} to {} hexadecimal characters",
+            sha,
+            MIN_SHA_LENGTH,
+            MAX_SHA_LENGTH
+        ))
+    }
+}
+
+/// Checks a reference given by the caller before it reaches the git command line: hashes must
+/// be hexadecimal, branch and tag names valid reference names that cannot pass for options
+async fn check_ref(git_ref: &GitRef) -> Result<()> {
+    let (kind, name) = match git_ref {
+        GitRef::Commit(sha) => return check_sha(sha),
+        GitRef::Default => return Ok(()),
+        GitRef::Branch(name) => ("heads", name),
+        GitRef::Tag(name) => ("tags", name),
+    };
+    if name.starts_with('-')
+        || run_git(
+            None,
+            None,
+            &["check-ref-format", &format!("refs/{}/{}", kind, name)],
+        )
+        .await
+        .is_err()
+    {
+        return Err(anyhow!("Invalid reference name '{}'", name));
+    }
+
+    Ok(())
+}
+
+/// Under a dry run, records the request a clone or fetch from `url` starts with and refuses
+/// the transfer
+fn plan_transfer(url: &str) -> Result<()> {
+    DryRun::intercept(PlannedCall::get(&format!(
+        "{}/info/refs?service=git-upload-pack",
+        url.trim_end_matches('/')
+    ))?)
+}
+
+/// The major and minor version in the output of `git --version`, e.g. "git version 2.39.3
+/// (Apple Git-145)"
+fn parse_git_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.trim().strip_prefix("git version ")?;
+    let mut numbers = version.split(['.', ' ']);
+    let major = numbers.next()?.parse().ok()?;
+    let minor = numbers.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// Fails when the installed git is missing or older than [`MIN_GIT_VERSION`], which would
+/// ignore the configuration `run_git` gives it. Checked once
+async fn check_git_version() -> Result<()> {
+    static CHECKED: OnceCell<Result<(), String>> = OnceCell::const_new();
+
+    CHECKED
+        .get_or_init(|| async {
+            let output = Command::new("git")
+                .arg("--version")
+                .stdin(Stdio::null())
+                .output()
+                .await
+                .map_err(|e| format!("Error running git, is it installed? {}", e))?;
+            let output = String::from_utf8_lossy(&output.stdout);
+            match parse_git_version(&output) {
+                Some(version) if version >= MIN_GIT_VERSION => Ok(()),
+                _ => Err(format!(
+                    "git {}.{} or later is needed to mirror repositories, found '{}'",
+                    MIN_GIT_VERSION.0,
+                    MIN_GIT_VERSION.1,
+                    output.trim()
+                )),
+            }
+        })
+        .await
+        .clone()
+        .map_err(|e| anyhow!(e))
+}
+
+/// Runs git, within the bare repository at `git_dir` when given, authenticating any network
+/// access with `credentials` without storing them in the repository configuration. Needs git
+/// 2.31 or later, for configuration given in the environment
+async fn run_git(
+    git_dir: Option<&Path>,
+    credentials: Option<&(String, String)>,
+    args: &[&str],
+) -> Result<String> {
+    check_git_version().await?;
+
+    let mut command = Command::new("git");
+    command
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    // Redirects could take a clone to another host than the one checked, and only HTTP(S)
+    // transports are used, whatever the URL or a redirect asks for
+    let mut config = vec![("http.followRedirects".to_string(), "false".to_string())];
+    command.env("GIT_ALLOW_PROTOCOL", "https:http");
+
+    // Given in the environment, which unlike the command line other users cannot read
+    if let Some((user, token)) = credentials {
+        config.push((
+            "http.extraHeader".to_string(),
+            format!(
+                "Authorization: Basic {}",
+                base64::encode(format!("{}:{}", user, token))
+            ),
+        ));
+    }
+    command.env("GIT_CONFIG_COUNT", config.len().to_string());
+    for (index, (key, value)) in config.iter().enumerate() {
+        command
+            .env(format!("GIT_CONFIG_KEY_{}", index), key)
+            .env(format!("GIT_CONFIG_VALUE_{}", index), value);
+    }
+    // Contents missing from a partial clone would be fetched on demand
+    if DryRun::in_progress() {
+        command.env("GIT_NO_LAZY_FETCH", "1");
+    }
+    if let Some(git_dir) = git_dir {
+        command.arg("--git-dir").arg(git_dir);
+    }
+
+    let output = command
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| anyhow!("Error running git: {}", e))?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn parse_log(output: &str) -> Result<Vec<CommitInfo>> {
+    output
+        .split(RECORD_SEPARATOR)
+        .map(|record| record.trim_start_matches('\n'))
+        .filter(|record| !record.is_empty())
+        .map(|record| {
+            let mut fields = record.splitn(5, FIELD_SEPARATOR);
+            let mut next = || {
+                fields
+                    .next()
+                    .ok_or_else(|| anyhow!("Malformed git log output"))
+            };
+
+            Ok(CommitInfo {
+                sha: next()?.to_string(),
+                author: pii::author(next()?.to_string()),
+                author_email: Some(next()?.to_string()).filter(|e| !e.is_empty()),
+                date: next()?.to_string(),
+                message: next()?.trim_end().to_string(),
+                verification: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_hexadecimal_hashes_are_commits() {
+        assert!(check_sha("4b825dc").is_ok());
+        assert!(check_sha(EMPTY_TREE).is_ok());
+        assert!(check_sha("--output=/tmp/written").is_err());
+        assert!(check_sha("HEAD~1").is_err());
+        assert!(check_sha("abc").is_err());
+        assert!(check_sha(&"a".repeat(MAX_SHA_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn reads_git_versions() {
+        assert_eq!(parse_git_version("git version 2.43.0\n"), Some((2, 43)));
+        assert_eq!(
+            parse_git_version("git version 2.39.3 (Apple Git-145)"),
+            Some((2, 39))
+        );
+        assert_eq!(
+            parse_git_version("git version 2.31.1.windows.1"),
+            Some((2, 31))
+        );
+        assert!(parse_git_version("git version 2.30.9").unwrap() < MIN_GIT_VERSION);
+        assert_eq!(parse_git_version("command not found"), None);
+    }
+}
@@ -0,0 +1,222 @@
+use std::{cell::RefCell, collections::HashMap, fmt, sync::Arc};
+
+use git_provider::{GitProvider, middleware::RateLimits};
+use parking_lot::Mutex;
+
+use crate::{Mirror, MirrorCache};
+
+/// Share of a host's rate limit kept for calls that cannot run locally
+const RESERVED_HEADROOM: f64 = 0.1;
+
+/// The same share for hosts called without a token, whose whole budget is a few dozen calls
+/// an hour, so repositories are cloned once rather than read call by call
+const ANONYMOUS_RESERVED_HEADROOM: f64 = 0.5;
+
+/// Repositories larger than this are never cloned without being asked to
+const DEFAULT_MAX_CLONE_KB: u64 = 500 * 1024;
+
+/// The backend serving a provider call. Every API call goes through the host's REST API, the
+/// planner does not pick GraphQL queries or archive downloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// The host's REST API
+    Api,
+    /// A mirror clone already in the cache
+    Mirror,
+    /// A mirror cloned to serve this call
+    Clone,
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Strategy::Api => "api",
+            Strategy::Mirror => "mirror",
+            Strategy::Clone => "clone",
+        })
+    }
+}
+
+tokio::task_local! {
+    /// The decisions made for the tool call the current task serves
+    static DECISIONS: RefCell<Vec<Decision>>;
+}
+
+/// A strategy chosen for one provider call, and why
+#[derive(Debug, Clone)]
+pub struct Decision {
+    pub provider: String,
+    pub repo_path: String,
+    pub operation: &'static str,
+    pub strategy: Strategy,
+    pub reason: String,
+}
+
+/// Chooses between the REST API and a local mirror for each commit, comparison or file read
+/// call. A mirror already in the cache is always used; otherwise the repository is cloned only
+/// when the call would leave less than the reserved share of the host's rate limit, and only
+/// if its size is known and within the clone limit. Other operations always use the API.
+pub struct Planner {
+    rate_limits: Arc<RateLimits>,
+    max_clone_kb: u64,
+    /// Repository sizes looked up so far, keyed by "provider:path"
+    sizes: Mutex<HashMap<String, Option<u64>>>,
+}
+
+impl Planner {
+    pub fn new(rate_limits: Arc<RateLimits>) -> Self {
+        Self {
+            rate_limits,
+            max_clone_kb: DEFAULT_MAX_CLONE_KB,
+            sizes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Largest repository cloned automatically, 0 never clones
+    pub fn with_max_clone_size(mut self, max_clone_kb: u64) -> Self {
+        self.max_clone_kb = max_clone_kb;
+        self
+    }
+
+    /// Runs `future` as one tool call, returning its output with the decisions made for it,
+    /// oldest first. Concurrent calls keep their own, and background refreshes record none.
+    pub async fn collect<F: Future>(future: F) -> (F::Output, Vec<Decision>) {
+        DECISIONS
+            .scope(RefCell::new(Vec::new()), async {
+                let output = future.await;
+                (output, DECISIONS.with(|decisions| decisions.take()))
+            })
+            .await
+    }
+
+    /// Plans a call to `operation` expected to cost `cost` API requests, returning the mirror
+    /// to run it on, or `None` to use the API
+    pub(crate) async fn plan(
+        &self,
+        provider: &dyn GitProvider,
+        cache: &MirrorCache,
+        repo_path: &str,
+        operation: &'static str,
+        cost: usize,
+    ) -> Option<Mirror> {
+        let (strategy, reason, mirror) = self
+            .choose(provider, cache, repo_path, cost)
+            .await
+            .map_or_else(
+                |reason| (Strategy::Api, reason, None),
+                |(strategy, reason, mirror)| (strategy, reason, Some(mirror)),
+            );
+
+        let decision = Decision {
+            provider: provider.name().to_string(),
+            repo_path: repo_path.to_string(),
+            operation,
+            strategy,
+            reason,
+        };
+        let _ = DECISIONS.try_with(|decisions| decisions.borrow_mut().push(decision));
+        mirror
+    }
+
+    /// A local strategy with its mirror, or the reason to use the API
+    async fn choose(
+        &self,
+        provider: &dyn GitProvider,
+        cache: &MirrorCache,
+        repo_path: &str,
+        cost: usize,
+    ) -> Result<(Strategy, String, Mirror), String> {
+        let remote = provider
+            .clone_remote(repo_path)
+            .await
+            .map_err(|_| format!("{} repositories cannot be cloned", provider.name()))?;
+
+        if let Some(mirror) = cache.mirror(provider.name(), repo_path, &remote) {
+            return Ok((
+                Strategy::Mirror,
+                "repository mirrored in the cache".into(),
+                mirror,
+            ));
+        }
+
+        let domain = remote
+            .url
+            .split("://")
+            .nth(1)
+            .and_then(|rest| rest.split('/').next())
+            .unwrap_or_default();
+
+        let Some(headroom) = self.rate_limits.headroom(domain) else {
+            return Err("rate limit not known yet".into());
+        };
+        let (share, budget) = if provider.is_anonymous() {
+            (
+                ANONYMOUS_RESERVED_HEADROOM,
+                format!(
+                    "{} of {} unauthenticated API requests left",
+                    headroom.remaining, headroom.limit
+                ),
+            )
+        } else {
+            (
+                RESERVED_HEADROOM,
+                format!(
+                    "{} of {} API requests left",
+                    headroom.remaining, headroom.limit
+                ),
+            )
+        };
+        let reserved = (headroom.limit as f64 * share) as u64;
+        if headroom.remaining >= reserved + cost as u64 {
+            return Err(budget);
+        }
+
+        if self.max_clone_kb == 0 {
+            return Err(format!("{}, automatic cloning is disabled", budget));
+        }
+
+        let size_kb = self.size_kb(provider, repo_path).await;
+        match size_kb {
+            Some(size_kb) if size_kb <= self.max_clone_kb => {}
+            Some(size_kb) => {
+                return Err(format!(
+                    "{}, but the repository ({} KB) is too large to clone",
+                    budget, size_kb
+                ));
+            }
+            None => {
+                return Err(format!("{}, but the repository size is unknown", budget));
+            }
+        }
+
+        let mirror = cache
+            .ensure(provider.name(), repo_path, &remote)
+            .await
+            .map_err(|e| format!("cloning failed: {}", e))?;
+
+        Ok((
+            Strategy::Clone,
+            format!(
+                "{}, cloned the repository ({} KB)",
+                budget,
+                size_kb.unwrap_or_default()
+            ),
+            mirror,
+        ))
+    }
+
+    async fn size_kb(&self, provider: &dyn GitProvider, repo_path: &str) -> Option<u64> {
+        let key = format!("{}:{}", provider.name(), repo_path);
+        if let Some(size_kb) = self.sizes.lock().get(&key) {
+            return *size_kb;
+        }
+
+        let size_kb = provider
+            .get_repository_info(repo_path)
+            .await
+            .ok()
+            .and_then(|info| info.size_kb);
+        self.sizes.lock().insert(key, size_kb);
+        size_kb
+    }
+}
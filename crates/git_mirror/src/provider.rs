@@ -0,0 +1,390 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use git_provider::{
+    BranchDivergence, ChangedFile, CheckAnnotation, CiArtifact, CloneRemote, CommentTarget,
+    CommitDetails, CommitInfo, CompareMode, ContributorInfo, FileChange, GitProvider, GitRef,
+    IssueComment, IssueDetails, IssueDraft, IssueFilter, LanguageShare, LicenseFile, Milestone,
+    ProjectBoard, PullRequestDetails, PullRequestDraft, PullRequestSummary, Readme, RefComparison,
+    ReleaseInfo, RepoEvent, RepoItem, RepoLabel, RepoSearchResult, RepositoryInfo, ReviewComment,
+    TagInfo, TreeOptions,
+    warnings::{self, WarningKind},
+};
+
+use crate::{Mirror, MirrorCache, Planner};
+
+/// A provider answering history and diff requests from the local mirror of a repository when
+/// the planner picks one, and from the wrapped provider otherwise
+pub struct MirroredProvider {
+    inner: Arc<dyn GitProvider>,
+    cache: Arc<MirrorCache>,
+    planner: Arc<Planner>,
+}
+
+impl MirroredProvider {
+    pub fn new(
+        inner: Arc<dyn GitProvider>,
+        cache: Arc<MirrorCache>,
+        planner: Arc<Planner>,
+    ) -> Self {
+        Self {
+            inner,
+            cache,
+            planner,
+        }
+    }
+
+    /// The mirror to run `operation` on, `cost` being the API requests it would take otherwise
+    async fn route(&self, repo_path: &str, operation: &'static str, cost: usize) -> Option<Mirror> {
+        self.planner
+            .plan(self.inner.as_ref(), &self.cache, repo_path, operation, cost)
+            .await
+    }
+}
+
+#[async_trait]
+impl GitProvider for MirroredProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn kind(&self) -> &str {
+        self.inner.kind()
+    }
+
+    fn web_url(&self) -> Option<String> {
+        self.inner.web_url()
+    }
+
+    fn is_anonymous(&self) -> bool {
+        self.inner.is_anonymous()
+    }
+
+    async fn compare_refs(
+        &self,
+        repo_path: &str,
+        base: Option<GitRef>,
+        head: Option<GitRef>,
+        mode: CompareMode,
+    ) -> Result<RefComparison> {
+        // Resolving both references, then up to five pages of commits
+        match self.route(repo_path, "compare_refs", 7).await {
+            Some(mirror) => mirror.compare_refs(base, head, mode).await,
+            None => self.inner.compare_refs(repo_path, base, head, mode).await,
+        }
+    }
+
+    async fn list_commits(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        path: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        match self.route(repo_path, "list_commits", 2).await {
+            Some(mirror) => mirror.list_commits(git_ref, path, limit).await,
+            None => {
+                self.inner
+                    .list_commits(repo_path, git_ref, path, limit)
+                    .await
+            }
+        }
+    }
+
+    async fn get_commit(&self, repo_path: &str, sha: &str) -> Result<CommitDetails> {
+        match self.route(repo_path, "get_commit", 1).await {
+            Some(mirror) => mirror.get_commit(sha).await,
+            None => self.inner.get_commit(repo_path, sha).await,
+        }
+    }
+
+    async fn list_commits_since(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        since: &str,
+        max_commits: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        let cost = 1 + max_commits.div_ceil(100);
+        match self.route(repo_path, "list_commits_since", cost).await {
+            Some(mirror) => mirror.list_commits_since(git_ref, since, max_commits).await,
+            None => {
+                self.inner
+                    .list_commits_since(repo_path, git_ref, since, max_commits)
+                    .await
+            }
+        }
+    }
+
+    async fn get_file_content(
+        &self,
+        repo_path: &str,
+        file_path: &str,
+        git_ref: Option<GitRef>,
+    ) -> Result<String> {
+        // Tools read files by the dozen, which an unauthenticated budget only affords from a clone
+        if !self.inner.is_anonymous() {
+            return self
+                .inner
+                .get_file_content(repo_path, file_path, git_ref)
+                .await;
+        }
+
+        match self.route(repo_path, "get_file_content", 1).await {
+            // A reference pushed after the clone is still read from the host
+            Some(mirror) => match mirror.file_content(git_ref.clone(), file_path).await {
+                Ok(content) => Ok(content),
+                Err(e) => {
+                    warnings::warn(
+                        WarningKind::Fallback,
+                        format!(
+                            "{} was read from {} rather than the local clone: {}",
+                            file_path,
+                            self.inner.name(),
+                            e
+                        ),
+                    );
+                    self.inner
+                        .get_file_content(repo_path, file_path, git_ref)
+                        .await
+                }
+            },
+            None => {
+                self.inner
+                    .get_file_content(repo_path, file_path, git_ref)
+                    .await
+            }
+        }
+    }
+
+    // Everything else goes to the host
+
+    async fn get_tree_structure(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: TreeOptions,
+    ) -> Result<String> {
+        self.inner
+            .get_tree_structure(repo_path, git_ref, options)
+            .await
+    }
+
+    async fn find_repositories(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<RepoSearchResult>> {
+        self.inner.find_repositories(query, limit).await
+    }
+
+    async fn fork_repository(&self, repo_path: &str) -> Result<String> {
+        self.inner.fork_repository(repo_path).await
+    }
+
+    async fn create_branch(
+        &self,
+        repo_path: &str,
+        branch: &str,
+        from: Option<GitRef>,
+    ) -> Result<()> {
+        self.inner.create_branch(repo_path, branch, from).await
+    }
+
+    async fn commit_files(
+        &self,
+        repo_path: &str,
+        branch: &str,
+        message: &str,
+        changes: Vec<FileChange>,
+    ) -> Result<String> {
+        self.inner
+            .commit_files(repo_path, branch, message, changes)
+            .await
+    }
+
+    async fn create_pull_request(
+        &self,
+        repo_path: &str,
+        request: PullRequestDraft,
+    ) -> Result<String> {
+        self.inner.create_pull_request(repo_path, request).await
+    }
+
+    async fn create_issue(&self, repo_path: &str, issue: IssueDraft) -> Result<String> {
+        self.inner.create_issue(repo_path, issue).await
+    }
+
+    async fn create_comment(
+        &self,
+        repo_path: &str,
+        target: CommentTarget,
+        body: &str,
+    ) -> Result<String> {
+        self.inner.create_comment(repo_path, target, body).await
+    }
+
+    async fn get_check_annotations(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+    ) -> Result<Vec<CheckAnnotation>> {
+        self.inner.get_check_annotations(repo_path, git_ref).await
+    }
+
+    async fn get_job_log(&self, repo_path: &str, job_id: &str) -> Result<String> {
+        self.inner.get_job_log(repo_path, job_id).await
+    }
+
+    async fn list_artifacts(&self, repo_path: &str, run_id: &str) -> Result<Vec<CiArtifact>> {
+        self.inner.list_artifacts(repo_path, run_id).await
+    }
+
+    async fn download_artifact(&self, repo_path: &str, artifact_id: &str) -> Result<Vec<u8>> {
+        self.inner.download_artifact(repo_path, artifact_id).await
+    }
+
+    async fn list_events(&self, repo_path: &str, since: Option<&str>) -> Result<Vec<RepoEvent>> {
+        self.inner.list_events(repo_path, since).await
+    }
+
+    async fn list_review_requests(&self, limit: Option<usize>) -> Result<Vec<PullRequestSummary>> {
+        self.inner.list_review_requests(limit).await
+    }
+
+    async fn list_pull_requests(
+        &self,
+        repo_path: &str,
+        state: &str,
+        target_branch: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<PullRequestSummary>> {
+        self.inner
+            .list_pull_requests(repo_path, state, target_branch, limit)
+            .await
+    }
+
+    async fn list_milestones(&self, repo_path: &str, state: &str) -> Result<Vec<Milestone>> {
+        self.inner.list_milestones(repo_path, state).await
+    }
+
+    async fn list_boards(&self, repo_path: &str) -> Result<Vec<ProjectBoard>> {
+        self.inner.list_boards(repo_path).await
+    }
+
+    async fn list_labels(&self, repo_path: &str) -> Result<Vec<RepoLabel>> {
+        self.inner.list_labels(repo_path).await
+    }
+
+    async fn list_files(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: &TreeOptions,
+    ) -> Result<Vec<RepoItem>> {
+        self.inner.list_files(repo_path, git_ref, options).await
+    }
+
+    async fn get_readme(&self, repo_path: &str, git_ref: Option<GitRef>) -> Result<Readme> {
+        self.inner.get_readme(repo_path, git_ref).await
+    }
+
+    async fn get_license(&self, repo_path: &str, git_ref: Option<GitRef>) -> Result<LicenseFile> {
+        self.inner.get_license(repo_path, git_ref).await
+    }
+
+    async fn list_issues(
+        &self,
+        repo_path: &str,
+        filter: &IssueFilter,
+        page: usize,
+        per_page: usize,
+    ) -> Result<Vec<IssueDetails>> {
+        self.inner
+            .list_issues(repo_path, filter, page, per_page)
+            .await
+    }
+
+    async fn get_issue(&self, repo_path: &str, number: u64) -> Result<IssueDetails> {
+        self.inner.get_issue(repo_path, number).await
+    }
+
+    async fn list_issue_comments(&self, repo_path: &str, number: u64) -> Result<Vec<IssueComment>> {
+        self.inner.list_issue_comments(repo_path, number).await
+    }
+
+    async fn get_pull_request(&self, repo_path: &str, number: u64) -> Result<PullRequestDetails> {
+        self.inner.get_pull_request(repo_path, number).await
+    }
+
+    async fn list_pull_request_files(
+        &self,
+        repo_path: &str,
+        number: u64,
+    ) -> Result<Vec<ChangedFile>> {
+        self.inner.list_pull_request_files(repo_path, number).await
+    }
+
+    async fn list_pull_request_comments(
+        &self,
+        repo_path: &str,
+        number: u64,
+    ) -> Result<Vec<ReviewComment>> {
+        self.inner
+            .list_pull_request_comments(repo_path, number)
+            .await
+    }
+
+    async fn list_refs(&self, repo_path: &str, prefix: &str) -> Result<Vec<String>> {
+        self.inner.list_refs(repo_path, prefix).await
+    }
+
+    async fn search_code(&self, repo_path: &str, query: &str, limit: usize) -> Result<Vec<String>> {
+        self.inner.search_code(repo_path, query, limit).await
+    }
+
+    async fn list_tags(&self, repo_path: &str, limit: usize) -> Result<Vec<TagInfo>> {
+        self.inner.list_tags(repo_path, limit).await
+    }
+
+    async fn list_releases(&self, repo_path: &str, limit: usize) -> Result<Vec<ReleaseInfo>> {
+        self.inner.list_releases(repo_path, limit).await
+    }
+
+    async fn list_contributors(
+        &self,
+        repo_path: &str,
+        limit: usize,
+    ) -> Result<Vec<ContributorInfo>> {
+        self.inner.list_contributors(repo_path, limit).await
+    }
+
+    async fn list_languages(&self, repo_path: &str) -> Result<Vec<LanguageShare>> {
+        self.inner.list_languages(repo_path).await
+    }
+
+    async fn get_repository_info(&self, repo_path: &str) -> Result<RepositoryInfo> {
+        self.inner.get_repository_info(repo_path).await
+    }
+
+    async fn find_template_repositories(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<RepoSearchResult>> {
+        self.inner.find_template_repositories(query, limit).await
+    }
+
+    async fn branch_divergence(
+        &self,
+        repo_path: &str,
+        limit: usize,
+    ) -> Result<(String, Vec<BranchDivergence>)> {
+        self.inner.branch_divergence(repo_path, limit).await
+    }
+
+    async fn clone_remote(&self, repo_path: &str) -> Result<CloneRemote> {
+        self.inner.clone_remote(repo_path).await
+    }
+}
@@ -0,0 +1,448 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use git_provider::{
+    CloneRemote, GitProvider, GitRef, RepoItem, RepoItemType, RepoNode, RepoSearchResult,
+    TreeOptions, depth_limited_directory, ignore_patterns::DEFAULT_IGNORE_PATTERNS, is_readme,
+    middleware::HostAllowlist, patterns::PathFilter, readme_summary, render_tree,
+};
+
+use crate::{CloneOptions, Mirror, MirrorCache};
+
+const MAX_FILES: usize = 500;
+
+/// How long a clone is served before its references are fetched again
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// URL schemes repositories may be cloned from, local paths, `file://` and the unauthenticated
+/// `git://` protocol are refused
+const ALLOWED_SCHEMES: &[&str] = &["https://"];
+
+/// Also allowed once plain HTTP is, e.g. for servers on a trusted network
+const ALLOWED_SCHEMES_WITH_HTTP: &[&str] = &["https://", "http://"];
+
+/// Provider for any git server, identified by the repository URL (e.g.,
+/// `git:https://example.com/foo/bar.git`), for hosts without a supported API. Repositories are
+/// cloned shallow and blobless into the mirror cache, and served from the local object store,
+/// file contents being fetched as they are read.
+pub struct RemoteGitProvider {
+    cache: Arc<MirrorCache>,
+    clone_options: CloneOptions,
+    /// Hosts repositories may be cloned from, any host unless restricted
+    allowlist: Arc<HostAllowlist>,
+    /// Whether repositories may be cloned over plain HTTP
+    allow_http: bool,
+}
+
+impl RemoteGitProvider {
+    pub fn new(cache: Arc<MirrorCache>) -> Self {
+        Self {
+            cache,
+            clone_options: CloneOptions {
+                depth: Some(1),
+                filter: Some("blob:none".to_string()),
+            },
+            allowlist: Arc::default(),
+            allow_http: false,
+        }
+    }
+
+    /// Also clones from `http://` URLs, whose contents anyone on the path may read or change
+    pub fn with_http(mut self, allow_http: bool) -> Self {
+        self.allow_http = allow_http;
+        self
+    }
+
+    /// Clones only from the hosts of an allowlist, the one the HTTP calls go through
+    pub fn with_allowlist(mut self, allowlist: Arc<HostAllowlist>) -> Self {
+        self.allowlist = allowlist;
+        self
+    }
+
+    /// The clone of a repository holding `git_ref`, cloning or refreshing it when needed
+    async fn checkout(&self, repo_path: &str, git_ref: Option<&GitRef>) -> Result<Mirror> {
+        let remote = self.clone_remote(repo_path).await?;
+
+        let stale = match self.cache.mirror(self.name(), repo_path, &remote) {
+            Some(mirror) => mirror
+                .last_synced()
+                .and_then(|synced| synced.elapsed().ok())
+                .is_none_or(|age| age > REFRESH_INTERVAL),
+            None => true,
+        };
+        if stale {
+            self.cache
+                .sync(self.name(), repo_path, &remote, &self.clone_options)
+                .await?;
+        }
+
+        let mirror = self
+            .cache
+            .mirror(self.name(), repo_path, &remote)
+            .ok_or_else(|| anyhow!("Failed to clone {}", repo_path))?;
+
+        // Commits outside the shallow history are fetched one by one
+        if let Some(git_ref) = git_ref
+            && !mirror.has_revision(Some(git_ref.clone())).await
+        {
+            let _guard = self.cache.sync_lock.lock().await;
+            mirror
+                .fetch_ref(git_ref, self.clone_options.depth)
+                .await
+                .map_err(|e| anyhow!("Cannot fetch {:?} from {}: {}", git_ref, repo_path, e))?;
+        }
+
+        Ok(mirror)
+    }
+
+    fn ignore_patterns(&self, options: &TreeOptions) -> Vec<String> {
+        match &options.ignore_patterns {
+            Some(ignore_patterns) => ignore_patterns.clone(),
+            None => DEFAULT_IGNORE_PATTERNS
+                .iter()
+                .map(|&s| s.to_string())
+                .collect(),
+        }
+    }
+
+    /// The entries kept by the filter, without the contents of dropped directories
+    fn filter_entries(&self, entries: Vec<RepoItem>, options: &TreeOptions) -> Vec<RepoItem> {
+        let ignore_patterns = self.ignore_patterns(options);
+        let filter = PathFilter::from_options(options, &ignore_patterns);
+
+        // git lists directories before their contents
+        let mut dropped: HashSet<String> = HashSet::new();
+        entries
+            .into_iter()
+            .filter(|entry| {
+                let parent = entry.path.rsplit_once('/').map(|(parent, _)| parent);
+                if parent.is_some_and(|parent| dropped.contains(parent)) {
+                    if entry.item_type == RepoItemType::Directory {
+                        dropped.insert(entry.path.clone());
+                    }
+                    return false;
+                }
+
+                match entry.item_type {
+                    RepoItemType::Directory if !filter.keeps_directory(&entry.path) => {
+                        dropped.insert(entry.path.clone());
+                        false
+                    }
+                    RepoItemType::Directory => true,
+                    RepoItemType::File => filter.keeps(&entry.path),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Nest the listed entries below the directory at `path`
+fn build_tree(
+    path: &str,
+    children_by_parent: &BTreeMap<&str, Vec<&RepoItem>>,
+    descriptions: &HashMap<&str, String>,
+    depth: usize,
+    max_depth: usize,
+) -> RepoNode {
+    let mut children = Vec::new();
+    let mut file_count = 0;
+    let mut dir_count = 1; // Count self
+
+    for entry in children_by_parent.get(path).into_iter().flatten() {
+        match entry.item_type {
+            RepoItemType::File => {
+                // Check file limit
+                if file_count >= MAX_FILES {
+                    continue;
+                }
+
+                file_count += 1;
+                children.push(RepoNode {
+                    name: entry.name.clone(),
+                    node_type: RepoItemType::File,
+                    size: 0,
+                    children: vec![],
+                    file_count: 1,
+                    dir_count: 0,
+                    description: None,
+                });
+            }
+            RepoItemType::Directory if depth >= max_depth => {
+                dir_count += 1;
+                children.push(depth_limited_directory(&entry.name));
+            }
+            RepoItemType::Directory => {
+                let mut child = build_tree(
+                    &entry.path,
+                    children_by_parent,
+                    descriptions,
+                    depth + 1,
+                    max_depth,
+                );
+                child.name = entry.name.clone();
+                file_count += child.file_count;
+                dir_count += child.dir_count;
+                children.push(child);
+            }
+        }
+    }
+
+    // Sort children: directories first, then files, both alphabetically
+    children.sort_by(|a, b| match (a.node_type, b.node_type) {
+        (RepoItemType::Directory, RepoItemType::File) => std::cmp::Ordering::Less,
+        (RepoItemType::File, RepoItemType::Directory) => std::cmp::Ordering::Greater,
+        _ => a.name.cmp(&b.name),
+    });
+
+    RepoNode {
+        name: "root".to_string(),
+        node_type: RepoItemType::Directory,
+        // Blob sizes are not known without fetching the blobs
+        size: 0,
+        children,
+        file_count,
+        dir_count,
+        description: descriptions.get(path).cloned(),
+    }
+}
+
+#[async_trait]
+impl GitProvider for RemoteGitProvider {
+    fn name(&self) -> &str {
+        "git"
+    }
+
+    async fn get_tree_structure(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: TreeOptions,
+    ) -> Result<String> {
+        let mirror = self.checkout(repo_path, git_ref.as_ref()).await?;
+        let entries = self.filter_entries(mirror.list_tree(git_ref.clone()).await?, &options);
+
+        let mut children_by_parent: BTreeMap<&str, Vec<&RepoItem>> = BTreeMap::new();
+        for entry in &entries {
+            let parent = entry.path.rsplit_once('/').map_or("", |(parent, _)| parent);
+            children_by_parent.entry(parent).or_default().push(entry);
+        }
+
+        let subtree = options.subtree();
+        if !subtree.is_empty() && !children_by_parent.contains_key(subtree) {
+            return Err(anyhow!("No directory '{}' in {}", subtree, repo_path));
+        }
+
+        // READMEs are the only contents fetched, one per directory
+        let mut descriptions = HashMap::new();
+        if options.describe_directories {
+            for (parent, children) in &children_by_parent {
+                let Some(readme) = children
+                    .iter()
+                    .find(|entry| entry.item_type == RepoItemType::File && is_readme(&entry.name))
+                else {
+                    continue;
+                };
+                if let Some(summary) = mirror
+                    .file_content(git_ref.clone(), &readme.path)
+                    .await
+                    .ok()
+                    .and_then(|content| readme_summary(&content))
+                {
+                    descriptions.insert(*parent, summary);
+                }
+            }
+        }
+
+        let mut tree_node = build_tree(
+            subtree,
+            &children_by_parent,
+            &descriptions,
+            0,
+            options.max_depth(),
+        );
+        tree_node.name = options.root_name(
+            repo_path
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .map(|name| name.trim_end_matches(".git"))
+                .unwrap_or(repo_path),
+        );
+
+        Ok(render_tree(&tree_node, &options))
+    }
+
+    async fn get_file_content(
+        &self,
+        repo_path: &str,
+        file_path: &str,
+        git_ref: Option<GitRef>,
+    ) -> Result<String> {
+        let mirror = self.checkout(repo_path, git_ref.as_ref()).await?;
+        mirror
+            .file_content(git_ref, file_path)
+            .await
+            .map_err(|e| anyhow!("Cannot read {}: {}", file_path, e))
+    }
+
+    async fn find_repositories(
+        &self,
+        _query: &str,
+        _limit: Option<usize>,
+    ) -> Result<Vec<RepoSearchResult>> {
+        Err(anyhow!(
+            "Searching repositories is not supported by git, pass the URL of a repository"
+        ))
+    }
+
+    async fn list_files(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: &TreeOptions,
+    ) -> Result<Vec<RepoItem>> {
+        let mirror = self.checkout(repo_path, git_ref.as_ref()).await?;
+
+        let mut files: Vec<RepoItem> = self
+            .filter_entries(mirror.list_tree(git_ref).await?, options)
+            .into_iter()
+            .filter(|entry| entry.item_type == RepoItemType::File)
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(files)
+    }
+
+    async fn clone_remote(&self, repo_path: &str) -> Result<CloneRemote> {
+        let schemes = if self.allow_http {
+            ALLOWED_SCHEMES_WITH_HTTP
+        } else {
+            ALLOWED_SCHEMES
+        };
+        if !schemes.iter().any(|scheme| repo_path.starts_with(scheme)) {
+            return Err(anyhow!(
+                "Invalid repository URL '{}': expected one of {}{}",
+                repo_path,
+                schemes.join(", "),
+                if self.allow_http || !repo_path.starts_with("http://") {
+                    ""
+                } else {
+                    ", set GITINGEST_ALLOW_HTTP=1 to clone over plain HTTP"
+                }
+            ));
+        }
+        self.allowlist.check_url(repo_path)?;
+
+        Ok(CloneRemote {
+            url: repo_path.to_string(),
+            credentials: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, item_type: RepoItemType) -> RepoItem {
+        RepoItem {
+            name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            path: path.to_string(),
+            item_type,
+            size: None,
+            sha: None,
+        }
+    }
+
+    fn provider() -> RemoteGitProvider {
+        RemoteGitProvider::new(Arc::new(MirrorCache::new(
+            std::env::temp_dir().join("git-mirror-remote-tests"),
+        )))
+    }
+
+    /// The entries of a small repository, directories first as git lists them
+    fn entries() -> Vec<RepoItem> {
+        vec![
+            entry("node_modules", RepoItemType::Directory),
+            entry("node_modules/left-pad", RepoItemType::Directory),
+            entry("node_modules/left-pad/index.js", RepoItemType::File),
+            entry("src", RepoItemType::Directory),
+            entry("src/parser", RepoItemType::Directory),
+            entry("src/parser/mod.rs", RepoItemType::File),
+            entry("src/lib.rs", RepoItemType::File),
+            entry("CHANGELOG.md", RepoItemType::File),
+            entry("Cargo.toml", RepoItemType::File),
+        ]
+    }
+
+    #[test]
+    fn filtering_drops_the_contents_of_dropped_directories() {
+        let options = TreeOptions {
+            exclude_patterns: vec!["*.md".to_string()],
+            ..TreeOptions::default()
+        };
+
+        let kept: Vec<String> = provider()
+            .filter_entries(entries(), &options)
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect();
+        assert_eq!(
+            kept,
+            [
+                "src",
+                "src/parser",
+                "src/parser/mod.rs",
+                "src/lib.rs",
+                "Cargo.toml"
+            ]
+        );
+    }
+
+    #[test]
+    fn trees_nest_and_count_their_entries() {
+        let entries = entries();
+        let mut children_by_parent: BTreeMap<&str, Vec<&RepoItem>> = BTreeMap::new();
+        for entry in &entries {
+            let parent = entry.path.rsplit_once('/').map_or("", |(parent, _)| parent);
+            children_by_parent.entry(parent).or_default().push(entry);
+        }
+
+        let tree = build_tree("", &children_by_parent, &HashMap::new(), 0, 10);
+        let names: Vec<&str> = tree
+            .children
+            .iter()
+            .map(|node| node.name.as_str())
+            .collect();
+        assert_eq!(names, ["node_modules", "src", "CHANGELOG.md", "Cargo.toml"]);
+        assert_eq!(tree.file_count, 5);
+        assert_eq!(tree.dir_count, 5);
+
+        let src = build_tree("src", &children_by_parent, &HashMap::new(), 0, 0);
+        assert_eq!(src.children[0].name, "parser");
+        assert!(src.children[0].children.is_empty());
+        assert!(src.children[0].description.is_some());
+        assert_eq!(src.file_count, 1);
+    }
+
+    #[test]
+    fn plain_http_is_refused_unless_allowed() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let clone = |provider: RemoteGitProvider, url: &'static str| {
+            runtime.block_on(async move { provider.clone_remote(url).await.map(|r| r.url) })
+        };
+
+        assert!(clone(provider(), "https://example.com/a/b.git").is_ok());
+        assert!(clone(provider(), "http://example.com/a/b.git").is_err());
+        assert!(clone(provider().with_http(true), "http://example.com/a/b.git").is_ok());
+        assert!(clone(provider().with_http(true), "file:///etc").is_err());
+        assert!(clone(provider().with_http(true), "git://example.com/a/b.git").is_err());
+    }
+}
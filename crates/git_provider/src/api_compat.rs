@@ -0,0 +1,346 @@
+//! API versions the providers are written against, and the shims reading responses shaped by
+//! other versions of those APIs
+//!
+//! Hosts keep answering with older shapes: self-managed GitLab instances lag years behind
+//! gitlab.com and GitHub Enterprise Server serves the REST API of its own release. Responses are
+//! upgraded to the shape of the pinned version before being parsed, and a response that still
+//! does not fit is an error naming the endpoint and version rather than an empty result.
+//! Lenient mode brings back empty results for listings, see [`set_lenient`].
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{Result, anyhow};
+use http_client::http::HeaderMap;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::warnings::{self, WarningKind};
+
+/// Version of GitHub's REST API requested with the `X-GitHub-Api-Version` header
+pub const GITHUB_API_VERSION: &str = "2022-11-28";
+
+/// Media type of GitHub's REST API responses
+pub const GITHUB_MEDIA_TYPE: &str = "application/vnd.github+json";
+
+/// Version of GitLab's REST API, part of its URLs
+pub const GITLAB_API_VERSION: &str = "v4";
+
+/// Longest part of a response body quoted in errors
+const SNIPPET_LENGTH: usize = 200;
+
+static LENIENT: AtomicBool = AtomicBool::new(false);
+
+/// Reads listings that fail to parse as empty instead of failing, as the providers once did.
+/// Errors sent by the host still fail
+pub fn set_lenient(lenient: bool) {
+    LENIENT.store(lenient, Ordering::Relaxed);
+}
+
+/// An API whose responses go through [`parse`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Api {
+    GitHub,
+    GitLab,
+}
+
+impl Api {
+    fn describe(self) -> String {
+        match self {
+            Api::GitHub => format!("GitHub REST API {}", GITHUB_API_VERSION),
+            Api::GitLab => format!("GitLab API {}", GITLAB_API_VERSION),
+        }
+    }
+
+    /// Fields renamed between versions, as (older name, name in the pinned version)
+    fn renamed_fields(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            // Early v3 responses named the default branch after its usual value
+            Api::GitHub => &[("master_branch", "default_branch")],
+            // GitLab 14.5 renamed the tags of a project to topics, v4 the builds of v3 to jobs
+            Api::GitLab => &[("tag_list", "topics"), ("builds_enabled", "jobs_enabled")],
+        }
+    }
+}
+
+/// Adds the headers pinning the version of GitHub's REST API
+pub fn pin_github_version(headers: &mut HeaderMap) -> Result<()> {
+    headers.insert("Accept", GITHUB_MEDIA_TYPE.parse()?);
+    headers.insert("X-GitHub-Api-Version", GITHUB_API_VERSION.parse()?);
+    Ok(())
+}
+
+/// Base URL of the API of a GitLab instance
+pub fn gitlab_api_base(host: &str) -> String {
+    format!("{}/api/{}", host.trim_end_matches('/'), GITLAB_API_VERSION)
+}
+
+/// Rewrites a response to the shape of the pinned version, in place. Objects are upgraded at
+/// the top level and as items of a top-level array, where the providers read them
+pub fn upgrade(api: Api, value: &mut Value) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                upgrade_object(api, item);
+            }
+        }
+        _ => upgrade_object(api, value),
+    }
+}
+
+fn upgrade_object(api: Api, value: &mut Value) {
+    let Value::Object(object) = value else {
+        return;
+    };
+
+    for (old, new) in api.renamed_fields() {
+        if object.contains_key(*new) {
+            continue;
+        }
+        if let Some(field) = object.get(*old).cloned() {
+            object.insert(new.to_string(), field);
+        }
+    }
+
+    // v3 only told whether a GitLab project was public
+    if api == Api::GitLab
+        && !object.contains_key("visibility")
+        && let Some(public) = object.get("public").and_then(Value::as_bool)
+    {
+        let visibility = if public { "public" } else { "private" };
+        object.insert("visibility".into(), visibility.into());
+    }
+}
+
+/// The message of an error sent with a success status, e.g. `{"message": "404 Not Found"}`
+fn error_message(value: &Value) -> Option<String> {
+    const ERROR_FIELDS: &[&str] = &["message", "error", "error_description", "documentation_url"];
+
+    let object = value.as_object()?;
+    if object.is_empty()
+        || !object
+            .keys()
+            .all(|key| ERROR_FIELDS.contains(&key.as_str()))
+    {
+        return None;
+    }
+
+    let message = object.get("message").or_else(|| object.get("error"))?;
+    Some(
+        message
+            .as_str()
+            .map(String::from)
+            .unwrap_or(message.to_string()),
+    )
+}
+
+/// The start of a response body, on one line
+fn snippet(body: &str) -> String {
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    match body.char_indices().nth(SNIPPET_LENGTH) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body,
+    }
+}
+
+/// Parses the body of a successful response from `endpoint`, upgraded to the pinned version
+pub fn parse<T: DeserializeOwned>(api: Api, endpoint: &str, body: &str) -> Result<T> {
+    // Some endpoints answer with an empty body (e.g., 204 No Content)
+    let mut value = if body.trim().is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_str(body).map_err(|e| {
+            anyhow!(
+                "{} sent invalid JSON for {}: {}. Response: {}",
+                api.describe(),
+                endpoint,
+                e,
+                snippet(body)
+            )
+        })?
+    };
+
+    if let Some(message) = error_message(&value) {
+        return Err(anyhow!(
+            "{} error for {}: {}",
+            api.describe(),
+            endpoint,
+            message
+        ));
+    }
+
+    upgrade(api, &mut value);
+
+    serde_json::from_value(value).map_err(|e| {
+        anyhow!(
+            "Unexpected response from {} for {}, the host may serve another API version: {}. Response: {}",
+            api.describe(),
+            endpoint,
+            e,
+            snippet(body)
+        )
+    })
+}
+
+/// Parses a listing like [`parse`], reading it as empty in lenient mode when it does not fit
+pub fn parse_listing<T: DeserializeOwned + Default>(
+    api: Api,
+    endpoint: &str,
+    body: &str,
+) -> Result<T> {
+    let is_host_error = || {
+        serde_json::from_str(body)
+            .ok()
+            .as_ref()
+            .and_then(error_message)
+            .is_some()
+    };
+
+    match parse(api, endpoint, body) {
+        Err(e) if LENIENT.load(Ordering::Relaxed) && !is_host_error() => {
+            warnings::warn(
+                WarningKind::Fallback,
+                format!(
+                    "The response from {} for {} was read as empty in lenient mode: {}",
+                    api.describe(),
+                    endpoint,
+                    e
+                ),
+            );
+            Ok(T::default())
+        }
+        result => result,
+    }
+}
+
+/// The path of a URL, naming an endpoint in errors without its host or query
+pub fn endpoint(url: &str) -> &str {
+    let path = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest.find('/').map_or("/", |i| &rest[i..]));
+    path.split('?').next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> &'static str {
+        match name {
+            "github/repo-2022-11-28" => include_str!("../fixtures/api/github/repo-2022-11-28.json"),
+            "github/repo-v3-legacy" => include_str!("../fixtures/api/github/repo-v3-legacy.json"),
+            "gitlab/project-16" => include_str!("../fixtures/api/gitlab/project-16.json"),
+            "gitlab/project-13" => include_str!("../fixtures/api/gitlab/project-13.json"),
+            "gitlab/projects-13" => include_str!("../fixtures/api/gitlab/projects-13.json"),
+            _ => panic!("unknown fixture {}", name),
+        }
+    }
+
+    #[test]
+    fn github_versions_read_alike() {
+        let current: Value = parse(Api::GitHub, "/repos/o/r", fixture("github/repo-2022-11-28"))
+            .expect("current shape");
+        let legacy: Value = parse(Api::GitHub, "/repos/o/r", fixture("github/repo-v3-legacy"))
+            .expect("legacy shape");
+
+        assert_eq!(current["default_branch"], "main");
+        assert_eq!(legacy["default_branch"], "master");
+    }
+
+    #[test]
+    fn gitlab_versions_read_alike() {
+        let current: Value =
+            parse(Api::GitLab, "/projects/1", fixture("gitlab/project-16")).expect("16.x");
+        let older: Value =
+            parse(Api::GitLab, "/projects/1", fixture("gitlab/project-13")).expect("13.x");
+
+        assert_eq!(current["topics"], older["topics"]);
+        assert_eq!(older["visibility"], "public");
+
+        let projects: Vec<Value> =
+            parse(Api::GitLab, "/projects", fixture("gitlab/projects-13")).expect("list");
+        assert!(projects.iter().all(|project| project["topics"].is_array()));
+    }
+
+    #[test]
+    fn renamed_fields_do_not_override_current_ones() {
+        let value: Value = parse(
+            Api::GitLab,
+            "/projects/1",
+            r#"{"tag_list": ["old"], "topics": ["new"]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(value["topics"], serde_json::json!(["new"]));
+    }
+
+    #[test]
+    fn error_bodies_are_errors() {
+        let error = parse::<Vec<Value>>(Api::GitLab, "/projects", r#"{"message":"403 Forbidden"}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("403 Forbidden"), "{}", error);
+        assert!(error.contains("GitLab API v4"), "{}", error);
+
+        let error = parse::<Value>(
+            Api::GitHub,
+            "/search/repositories",
+            r#"{"message":"API rate limit exceeded","documentation_url":"https://docs.github.com"}"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("rate limit"), "{}", error);
+    }
+
+    #[test]
+    fn unexpected_shapes_name_the_version() {
+        let error = parse::<Vec<Value>>(Api::GitHub, "/repos/o/r/tags", r#"{"tags": []}"#)
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("/repos/o/r/tags"), "{}", error);
+        assert!(error.contains(GITHUB_API_VERSION), "{}", error);
+    }
+
+    #[test]
+    fn errors_quote_the_response() {
+        let body = format!(r#"{{"items": "{}"}}"#, "x".repeat(500));
+        let error = parse::<Vec<Value>>(Api::GitHub, "/search/repositories", &body)
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains(r#"Response: {"items": "xxx"#), "{}", error);
+        assert!(error.ends_with("..."), "{}", error);
+    }
+
+    #[test]
+    fn lenient_listings_read_as_empty() {
+        set_lenient(true);
+        let listing = parse_listing::<Vec<Value>>(Api::GitLab, "/projects", r#"{"id": 1}"#);
+        let host_error =
+            parse_listing::<Vec<Value>>(Api::GitLab, "/projects", r#"{"message": "401"}"#);
+        set_lenient(false);
+        let strict = parse_listing::<Vec<Value>>(Api::GitLab, "/projects", r#"{"id": 1}"#);
+
+        assert!(listing.unwrap().is_empty());
+        assert!(host_error.is_err());
+        assert!(strict.is_err());
+    }
+
+    #[test]
+    fn empty_bodies_are_null() {
+        assert_eq!(parse::<Option<Value>>(Api::GitHub, "/", " ").unwrap(), None);
+    }
+
+    #[test]
+    fn endpoints_drop_host_and_query() {
+        assert_eq!(
+            endpoint("https://gitlab.com/api/v4/projects?search=x"),
+            "/api/v4/projects"
+        );
+        assert_eq!(endpoint("https://api.github.com"), "/");
+        assert_eq!(
+            gitlab_api_base("https://gitlab.com/"),
+            "https://gitlab.com/api/v4"
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! Percent-encoding of repository paths and references for API URLs
+//!
+//! File names may hold spaces, non-ASCII characters, `#` or `?`, all of which truncate or
+//! corrupt a URL unless encoded.
+
+/// Bytes left as they are by RFC 3986: letters, digits and `-._~`
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+fn encode(value: &str, keep_slashes: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if is_unreserved(byte) || (keep_slashes && byte == b'/') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    encoded
+}
+
+/// Encodes a value used as a single URL segment or query value, including its slashes,
+/// e.g. a GitLab project or file path
+pub fn encode_component(value: &str) -> String {
+    encode(value, false)
+}
+
+/// Encodes a path appended to a URL, keeping its slashes as segment separators,
+/// e.g. a GitHub contents path
+pub fn encode_path(path: &str) -> String {
+    encode(path, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_plain_paths_alone() {
+        assert_eq!(encode_path("src/main.rs"), "src/main.rs");
+        assert_eq!(encode_component("v1.2_rc-1~x"), "v1.2_rc-1~x");
+    }
+
+    #[test]
+    fn encodes_slashes_only_in_components() {
+        assert_eq!(encode_component("group/sub/repo"), "group%2Fsub%2Frepo");
+        assert_eq!(encode_path("group/sub/repo"), "group/sub/repo");
+    }
+
+    #[test]
+    fn encodes_spaces_and_url_delimiters() {
+        assert_eq!(encode_path("docs/my notes.md"), "docs/my%20notes.md");
+        assert_eq!(encode_path("issue#12?.md"), "issue%2312%3F.md");
+        assert_eq!(encode_component("a&b=c+d%"), "a%26b%3Dc%2Bd%25");
+    }
+
+    #[test]
+    fn encodes_non_ascii_as_utf8_bytes() {
+        assert_eq!(encode_path("docs/café.md"), "docs/caf%C3%A9.md");
+        assert_eq!(encode_component("日本/語"), "%E6%97%A5%E6%9C%AC%2F%E8%AA%9E");
+    }
+}
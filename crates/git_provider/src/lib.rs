@@ -1,11 +1,20 @@
+pub mod api_compat;
+pub mod encoding;
 pub mod ignore_patterns;
+pub mod middleware;
+pub mod pattern_presets;
+pub mod patterns;
+pub mod pii;
+pub mod provenance;
+pub mod warnings;
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub enum GitRef {
     /// Default branch (usually main or master)
+    #[default]
     Default,
     /// A specific branch name
     Branch(String),
@@ -15,24 +24,34 @@ pub enum GitRef {
     Commit(String),
 }
 
-impl Default for GitRef {
-    fn default() -> Self {
-        Self::Default
-    }
-}
-
 #[async_trait]
 pub trait GitProvider: Send + Sync {
     /// Returns the name of the provider (e.g., "github", "gitlab")
     fn name(&self) -> &str;
 
+    /// The hosting service the provider talks to, shared by every configured instance of it
+    /// (e.g., "gitlab" for a self-managed GitLab registered as "gitlab-work")
+    fn kind(&self) -> &str {
+        self.name()
+    }
+
+    /// Base URL of the provider's web interface (e.g., "https://github.com"), if it has one
+    fn web_url(&self) -> Option<String> {
+        None
+    }
+
+    /// Whether calls reach the host without credentials, under its much smaller unauthenticated
+    /// rate limit. Providers that never call an API are not anonymous.
+    fn is_anonymous(&self) -> bool {
+        false
+    }
+
     /// Process a repository and return the tree structure
     async fn get_tree_structure(
         &self,
         repo_path: &str,
         git_ref: Option<GitRef>,
-        exclude_patterns: Vec<String>,
-        include_patterns: Vec<String>,
+        options: TreeOptions,
     ) -> Result<String>;
 
     /// Retrieve file content from a repository
@@ -49,6 +68,399 @@ pub trait GitProvider: Send + Sync {
         query: &str,
         limit: Option<usize>,
     ) -> Result<Vec<RepoSearchResult>>;
+
+    /// Fork a repository into the authenticated user's namespace, returning the fork's path
+    async fn fork_repository(&self, _repo_path: &str) -> Result<String> {
+        Err(anyhow!("Forking is not supported by {}", self.name()))
+    }
+
+    /// Create a branch pointing at the given reference
+    async fn create_branch(
+        &self,
+        _repo_path: &str,
+        _branch: &str,
+        _from: Option<GitRef>,
+    ) -> Result<()> {
+        Err(anyhow!(
+            "Creating branches is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// Commit a set of file changes on top of a branch, returning the new commit SHA
+    async fn commit_files(
+        &self,
+        _repo_path: &str,
+        _branch: &str,
+        _message: &str,
+        _changes: Vec<FileChange>,
+    ) -> Result<String> {
+        Err(anyhow!(
+            "Committing files is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// Open a pull request (merge request), returning its web URL
+    async fn create_pull_request(
+        &self,
+        _repo_path: &str,
+        _request: PullRequestDraft,
+    ) -> Result<String> {
+        Err(anyhow!(
+            "Opening pull requests is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// Open an issue, returning its web URL
+    async fn create_issue(&self, _repo_path: &str, _issue: IssueDraft) -> Result<String> {
+        Err(anyhow!(
+            "Creating issues is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// Comment on an issue or pull request, returning the comment's web URL
+    async fn create_comment(
+        &self,
+        _repo_path: &str,
+        _target: CommentTarget,
+        _body: &str,
+    ) -> Result<String> {
+        Err(anyhow!("Commenting is not supported by {}", self.name()))
+    }
+
+    /// Retrieve the annotations CI checks reported for a commit
+    async fn get_check_annotations(
+        &self,
+        _repo_path: &str,
+        _git_ref: Option<GitRef>,
+    ) -> Result<Vec<CheckAnnotation>> {
+        Err(anyhow!(
+            "Check annotations are not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// Download the raw log of a CI job (GitHub Actions job, GitLab pipeline job)
+    async fn get_job_log(&self, _repo_path: &str, _job_id: &str) -> Result<String> {
+        Err(anyhow!("CI job logs are not supported by {}", self.name()))
+    }
+
+    /// List the artifacts produced by a CI run (GitHub workflow run, GitLab pipeline)
+    async fn list_artifacts(&self, _repo_path: &str, _run_id: &str) -> Result<Vec<CiArtifact>> {
+        Err(anyhow!("CI artifacts are not supported by {}", self.name()))
+    }
+
+    /// Download an artifact as a zip archive
+    async fn download_artifact(&self, _repo_path: &str, _artifact_id: &str) -> Result<Vec<u8>> {
+        Err(anyhow!("CI artifacts are not supported by {}", self.name()))
+    }
+
+    /// List recent repository events, newest first, optionally only those after an ISO 8601 date
+    async fn list_events(&self, _repo_path: &str, _since: Option<&str>) -> Result<Vec<RepoEvent>> {
+        Err(anyhow!(
+            "Activity events are not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// List open pull requests (merge requests) awaiting the authenticated user's review
+    async fn list_review_requests(&self, _limit: Option<usize>) -> Result<Vec<PullRequestSummary>> {
+        Err(anyhow!(
+            "Review requests are not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// List the pull requests (merge requests) of a repository in a state ("open", "closed",
+    /// "merged" or "all"), optionally only those targeting a branch, most recently updated
+    /// first
+    async fn list_pull_requests(
+        &self,
+        _repo_path: &str,
+        _state: &str,
+        _target_branch: Option<&str>,
+        _limit: usize,
+    ) -> Result<Vec<PullRequestSummary>> {
+        Err(anyhow!(
+            "Listing pull requests is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// List milestones with their progress; `state` is "open", "closed" or "all"
+    async fn list_milestones(&self, _repo_path: &str, _state: &str) -> Result<Vec<Milestone>> {
+        Err(anyhow!("Milestones are not supported by {}", self.name()))
+    }
+
+    /// Read the planning boards of a repository (GitHub Projects, GitLab issue boards)
+    async fn list_boards(&self, _repo_path: &str) -> Result<Vec<ProjectBoard>> {
+        Err(anyhow!(
+            "Project boards are not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// List the labels of a repository with the number of open issues using each one
+    async fn list_labels(&self, _repo_path: &str) -> Result<Vec<RepoLabel>> {
+        Err(anyhow!("Labels are not supported by {}", self.name()))
+    }
+
+    /// Compare two references, returning the commits reachable from `head` but not from `base`
+    /// and the files changed between `head` and either the merge base or `base` itself
+    async fn compare_refs(
+        &self,
+        _repo_path: &str,
+        _base: Option<GitRef>,
+        _head: Option<GitRef>,
+        _mode: CompareMode,
+    ) -> Result<RefComparison> {
+        Err(anyhow!(
+            "Comparing references is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// List every file of a repository recursively, filtered by the patterns of `options` and
+    /// sorted by path
+    async fn list_files(
+        &self,
+        _repo_path: &str,
+        _git_ref: Option<GitRef>,
+        _options: &TreeOptions,
+    ) -> Result<Vec<RepoItem>> {
+        Err(anyhow!("Listing files is not supported by {}", self.name()))
+    }
+
+    /// Find and read the README of a repository whatever its name, at the root or in `docs/`
+    /// or `.github/`. Hosts without a README endpoint are searched through [`Self::list_files`]
+    async fn get_readme(&self, repo_path: &str, git_ref: Option<GitRef>) -> Result<Readme> {
+        let files = self
+            .list_files(repo_path, git_ref.clone(), &TreeOptions::default())
+            .await?;
+        let path = find_readme(files.iter().map(|file| file.path.as_str()))
+            .ok_or_else(|| anyhow!("No README found in {}", repo_path))?
+            .to_string();
+        let content = self.get_file_content(repo_path, &path, git_ref).await?;
+
+        Ok(Readme { path, content })
+    }
+
+    /// Find and read the license file at the root of a repository, with its SPDX identifier when
+    /// the host detects it. Hosts without a license endpoint are searched by file name
+    async fn get_license(&self, repo_path: &str, git_ref: Option<GitRef>) -> Result<LicenseFile> {
+        let files = self
+            .list_files(repo_path, git_ref.clone(), &TreeOptions::default())
+            .await?;
+        let path = find_license_file(files.iter().map(|file| file.path.as_str()))
+            .ok_or_else(|| anyhow!("No license file found in {}", repo_path))?
+            .to_string();
+        let content = self.get_file_content(repo_path, &path, git_ref).await?;
+
+        Ok(LicenseFile {
+            path,
+            spdx_id: None,
+            content,
+        })
+    }
+
+    /// Search the default branch with the host's code search, returning the paths of up to
+    /// `limit` files containing `query`
+    async fn search_code(
+        &self,
+        _repo_path: &str,
+        _query: &str,
+        _limit: usize,
+    ) -> Result<Vec<String>> {
+        Err(anyhow!("Code search is not supported by {}", self.name()))
+    }
+
+    /// List the most recent commits of a reference, optionally only those touching `path`
+    async fn list_commits(
+        &self,
+        _repo_path: &str,
+        _git_ref: Option<GitRef>,
+        _path: Option<&str>,
+        _limit: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        Err(anyhow!(
+            "Listing commits is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// Retrieve a single commit with the files it changed
+    async fn get_commit(&self, _repo_path: &str, _sha: &str) -> Result<CommitDetails> {
+        Err(anyhow!(
+            "Reading commits is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// List the issues of a repository matching a filter, most recently updated first. Pages
+    /// of `per_page` issues are numbered from 1
+    async fn list_issues(
+        &self,
+        _repo_path: &str,
+        _filter: &IssueFilter,
+        _page: usize,
+        _per_page: usize,
+    ) -> Result<Vec<IssueDetails>> {
+        Err(anyhow!(
+            "Listing issues is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// Retrieve a single issue
+    async fn get_issue(&self, _repo_path: &str, _number: u64) -> Result<IssueDetails> {
+        Err(anyhow!(
+            "Reading issues is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// List the comments of an issue, oldest first
+    async fn list_issue_comments(
+        &self,
+        _repo_path: &str,
+        _number: u64,
+    ) -> Result<Vec<IssueComment>> {
+        Err(anyhow!(
+            "Reading issue comments is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// Retrieve a single pull request (merge request)
+    async fn get_pull_request(&self, _repo_path: &str, _number: u64) -> Result<PullRequestDetails> {
+        Err(anyhow!(
+            "Reading pull requests is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// List the files a pull request (merge request) changes, with their diffs
+    async fn list_pull_request_files(
+        &self,
+        _repo_path: &str,
+        _number: u64,
+    ) -> Result<Vec<ChangedFile>> {
+        Err(anyhow!(
+            "Reading pull request changes is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// List the comments of a pull request (merge request), on its conversation and on lines
+    /// of its diff, oldest first
+    async fn list_pull_request_comments(
+        &self,
+        _repo_path: &str,
+        _number: u64,
+    ) -> Result<Vec<ReviewComment>> {
+        Err(anyhow!(
+            "Reading pull request comments is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// List branch names and tags (as "tag:name") of a repository starting with `prefix`
+    async fn list_refs(&self, _repo_path: &str, _prefix: &str) -> Result<Vec<String>> {
+        Err(anyhow!(
+            "Listing references is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// List the most recent tags of a repository, up to `limit`, with their annotations
+    async fn list_tags(&self, _repo_path: &str, _limit: usize) -> Result<Vec<TagInfo>> {
+        Err(anyhow!("Listing tags is not supported by {}", self.name()))
+    }
+
+    /// List the most recent releases of a repository, up to `limit`, with their notes
+    async fn list_releases(&self, _repo_path: &str, _limit: usize) -> Result<Vec<ReleaseInfo>> {
+        Err(anyhow!(
+            "Listing releases is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// List the contributors with the most commits to a repository's default branch, up to
+    /// `limit`, most active first
+    async fn list_contributors(
+        &self,
+        _repo_path: &str,
+        _limit: usize,
+    ) -> Result<Vec<ContributorInfo>> {
+        Err(anyhow!(
+            "Listing contributors is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// The languages of a repository's code, largest share first
+    async fn list_languages(&self, _repo_path: &str) -> Result<Vec<LanguageShare>> {
+        Err(anyhow!(
+            "Listing languages is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// Retrieve the metadata of a repository, including its template and fork origins
+    async fn get_repository_info(&self, _repo_path: &str) -> Result<RepositoryInfo> {
+        Err(anyhow!(
+            "Repository information is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// Search for repositories marked as templates matching a query
+    async fn find_template_repositories(
+        &self,
+        _query: &str,
+        _limit: Option<usize>,
+    ) -> Result<Vec<RepoSearchResult>> {
+        Err(anyhow!(
+            "Searching template repositories is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// List the commits of a reference made since an ISO 8601 timestamp, newest first, up to
+    /// `max_commits`
+    async fn list_commits_since(
+        &self,
+        _repo_path: &str,
+        _git_ref: Option<GitRef>,
+        _since: &str,
+        _max_commits: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        Err(anyhow!(
+            "Listing commits by date is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// Count how many commits each branch is ahead of and behind the default branch, returning
+    /// the default branch name alongside at most `limit` other branches
+    async fn branch_divergence(
+        &self,
+        _repo_path: &str,
+        _limit: usize,
+    ) -> Result<(String, Vec<BranchDivergence>)> {
+        Err(anyhow!(
+            "Branch divergence is not supported by {}",
+            self.name()
+        ))
+    }
+
+    /// Describe how to clone a repository with git, for operations run on a local copy
+    async fn clone_remote(&self, _repo_path: &str) -> Result<CloneRemote> {
+        Err(anyhow!("Cloning is not supported by {}", self.name()))
+    }
 }
 
 /// Represents a file or directory in a repository
@@ -58,6 +470,8 @@ pub struct RepoItem {
     pub path: String,
     pub item_type: RepoItemType, // file or directory
     pub size: Option<u64>,
+    /// Git object ID of the blob or tree, when reported by the provider
+    pub sha: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Copy)]
@@ -75,8 +489,59 @@ pub struct RepoNode {
     pub children: Vec<RepoNode>,
     pub file_count: usize,
     pub dir_count: usize,
+    /// Short description shown next to the node, e.g. taken from a directory README
+    pub description: Option<String>,
+}
+
+/// Options controlling how a repository tree is walked and rendered
+#[derive(Debug, Clone, Default)]
+pub struct TreeOptions {
+    pub exclude_patterns: Vec<String>,
+    pub include_patterns: Vec<String>,
+    /// Replaces the provider's default ignore patterns, an empty list disables them
+    pub ignore_patterns: Option<Vec<String>>,
+    /// Match every pattern regardless of case
+    pub case_insensitive: bool,
+    /// Inline the first paragraph of each directory's README as its description
+    pub describe_directories: bool,
+    /// Deepest level of entries listed, the root's entries being at level 0.
+    /// [`DEFAULT_MAX_DEPTH`] when not given, at most [`MAX_TREE_DEPTH`]
+    pub max_depth: Option<usize>,
+    /// Directory the tree is walked from, the repository root when not given.
+    /// Levels are counted from it
+    pub subtree: Option<String>,
+}
+
+impl TreeOptions {
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+            .unwrap_or(DEFAULT_MAX_DEPTH)
+            .min(MAX_TREE_DEPTH)
+    }
+
+    /// Path of the directory walked, empty for the repository root
+    pub fn subtree(&self) -> &str {
+        self.subtree.as_deref().unwrap_or("").trim_matches('/')
+    }
+
+    /// Name of the tree's top node, the repository's followed by the directory walked
+    pub fn root_name(&self, name: &str) -> String {
+        match self.subtree() {
+            "" => name.to_string(),
+            subtree => format!("{}/{}", name, subtree),
+        }
+    }
 }
 
+/// How deep trees are walked unless asked otherwise
+pub const DEFAULT_MAX_DEPTH: usize = 10;
+
+/// Bound on the depth of a tree walk, each level costing a request per directory on most hosts
+pub const MAX_TREE_DEPTH: usize = 32;
+
+/// Description marking the directories left unexpanded past the depth limit
+pub const DEPTH_LIMITED: &str = "not expanded, past max_depth";
+
 /// Represents a repository search result
 #[derive(Debug, Clone)]
 pub struct RepoSearchResult {
@@ -86,13 +551,440 @@ pub struct RepoSearchResult {
     pub stargazers_count: usize,
 }
 
+/// Metadata describing a repository
+#[derive(Debug, Clone)]
+pub struct RepositoryInfo {
+    pub full_name: String,
+    pub description: Option<String>,
+    pub url: String,
+    pub default_branch: Option<String>,
+    pub stars: u64,
+    pub forks: u64,
+    pub open_issues: Option<u64>,
+    pub language: Option<String>,
+    pub license: Option<String>,
+    pub topics: Vec<String>,
+    pub archived: bool,
+    /// Size of the repository in kilobytes, when the host discloses it
+    pub size_kb: Option<u64>,
+    /// Whether new repositories can be generated from this one
+    pub is_template: bool,
+    /// Template repository this one was generated from
+    pub generated_from: Option<String>,
+    /// Repository this one is a fork of
+    pub forked_from: Option<String>,
+    /// Signs that the repository is an automated mirror, with its likely canonical origin
+    pub mirror: Option<provenance::MirrorOrigin>,
+    pub created_at: String,
+    pub last_activity_at: Option<String>,
+}
+
+/// Where and how to clone a repository with git
+#[derive(Debug, Clone)]
+pub struct CloneRemote {
+    /// HTTPS URL of the repository
+    pub url: String,
+    /// User name and token to authenticate with, for private repositories
+    pub credentials: Option<(String, String)>,
+}
+
+/// How far a branch moved away from the default branch
+#[derive(Debug, Clone)]
+pub struct BranchDivergence {
+    pub branch: String,
+    /// Commits on the branch missing from the default branch
+    pub ahead: usize,
+    /// Commits on the default branch missing from the branch
+    pub behind: usize,
+    /// Date of the branch's latest commit
+    pub last_commit_date: Option<String>,
+}
+
+/// A change to a single file, as part of a commit
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    pub path: String,
+    /// New file content, `None` deletes the file
+    pub content: Option<String>,
+}
+
+/// The parameters of a pull request (merge request) to open
+#[derive(Debug, Clone)]
+pub struct PullRequestDraft {
+    pub title: String,
+    pub body: Option<String>,
+    /// Branch containing the changes
+    pub head_branch: String,
+    /// Repository holding the head branch when it differs from the target (e.g., a fork)
+    pub head_repo: Option<String>,
+    /// Branch to merge into, the default branch when omitted
+    pub base_branch: Option<String>,
+}
+
+/// The parameters of an issue to open
+#[derive(Debug, Clone)]
+pub struct IssueDraft {
+    pub title: String,
+    pub body: Option<String>,
+    pub labels: Vec<String>,
+}
+
+/// Which issues [`GitProvider::list_issues`] returns
+#[derive(Debug, Clone)]
+pub struct IssueFilter {
+    /// "open", "closed" or "all"
+    pub state: String,
+    /// Labels every issue listed carries
+    pub labels: Vec<String>,
+    /// Username of someone every issue listed is assigned to
+    pub assignee: Option<String>,
+}
+
+/// What a comment is posted on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommentTarget {
+    /// An issue, by number (GitLab: iid)
+    Issue(u64),
+    /// A pull request (merge request), by number (GitLab: iid)
+    PullRequest(u64),
+}
+
+/// A line-level finding reported by a CI check
+#[derive(Debug, Clone)]
+pub struct CheckAnnotation {
+    pub check_name: String,
+    pub path: String,
+    pub start_line: u64,
+    pub end_line: u64,
+    /// Severity, e.g. "failure", "warning" or "notice"
+    pub level: String,
+    pub title: Option<String>,
+    pub message: String,
+}
+
+/// An artifact archive produced by a CI run
+#[derive(Debug, Clone)]
+pub struct CiArtifact {
+    pub id: String,
+    pub name: String,
+    pub size: Option<u64>,
+    pub expired: bool,
+}
+
+/// Normalized kinds of repository activity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RepoEventKind {
+    Push,
+    Release,
+    Issue,
+    PullRequest,
+    Comment,
+    Ref,
+    Other,
+}
+
+/// A single entry of a repository's activity feed
+#[derive(Debug, Clone)]
+pub struct RepoEvent {
+    pub kind: RepoEventKind,
+    pub actor: String,
+    /// ISO 8601 timestamp
+    pub created_at: String,
+    /// Human readable description, e.g. "opened issue #12: Crash on start"
+    pub summary: String,
+}
+
+/// Summary of a pull request (merge request)
+#[derive(Debug, Clone)]
+pub struct PullRequestSummary {
+    /// Full path of the repository, e.g. "owner/repo"
+    pub repository: String,
+    pub number: u64,
+    pub title: String,
+    pub author: String,
+    pub state: String,
+    pub draft: bool,
+    pub source_branch: Option<String>,
+    pub target_branch: Option<String>,
+    pub labels: Vec<String>,
+    pub url: String,
+    /// ISO 8601 timestamp
+    pub updated_at: String,
+}
+
+/// A pull request with its description
+#[derive(Debug, Clone)]
+pub struct PullRequestDetails {
+    pub summary: PullRequestSummary,
+    pub body: Option<String>,
+}
+
+/// A comment on a pull request (merge request), reviews included
+#[derive(Debug, Clone)]
+pub struct ReviewComment {
+    pub author: String,
+    pub body: String,
+    /// File commented on, `None` for the conversation
+    pub path: Option<String>,
+    /// Line of the file in the new version, or in the old one for removed lines
+    pub line: Option<u64>,
+    /// ISO 8601 timestamp
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct IssueDetails {
+    pub number: u64,
+    pub title: String,
+    pub body: Option<String>,
+    pub state: String,
+    pub author: String,
+    pub labels: Vec<String>,
+    pub url: String,
+    pub created_at: String,
+}
+
+/// A comment on an issue
+#[derive(Debug, Clone)]
+pub struct IssueComment {
+    pub author: String,
+    pub body: String,
+    /// ISO 8601 timestamp
+    pub created_at: String,
+    /// ISO 8601 timestamp of the last edit, `None` for comments never edited
+    pub updated_at: Option<String>,
+}
+
+/// A milestone and how many of its issues are done
+#[derive(Debug, Clone)]
+pub struct Milestone {
+    pub title: String,
+    pub state: String,
+    pub description: Option<String>,
+    /// ISO 8601 due date
+    pub due_on: Option<String>,
+    pub open_issues: u64,
+    pub closed_issues: u64,
+    pub url: Option<String>,
+}
+
+/// A planning board with its columns
+#[derive(Debug, Clone)]
+pub struct ProjectBoard {
+    pub name: String,
+    pub url: Option<String>,
+    pub columns: Vec<BoardColumn>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BoardColumn {
+    pub name: String,
+    pub items: Vec<BoardItem>,
+}
+
+/// A card on a board: an issue, a pull request or a draft note
+#[derive(Debug, Clone)]
+pub struct BoardItem {
+    pub title: String,
+    pub number: Option<u64>,
+    pub state: Option<String>,
+}
+
+/// An issue label
+#[derive(Debug, Clone)]
+pub struct RepoLabel {
+    pub name: String,
+    /// Hex color without the leading '#'
+    pub color: String,
+    pub description: Option<String>,
+    pub open_issues: u64,
+}
+
+/// What the files of `head` are compared against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompareMode {
+    /// The merge base of both references (triple-dot), as pull requests show it
+    #[default]
+    MergeBase,
+    /// The tree of `base` itself (double-dot)
+    Direct,
+}
+
+/// The result of comparing two references
+#[derive(Debug, Clone)]
+pub struct RefComparison {
+    /// Resolved name of the base reference
+    pub base: String,
+    /// Resolved name of the head reference
+    pub head: String,
+    /// Commit the files were compared against when comparing with the merge base
+    pub merge_base: Option<String>,
+    /// Commits in chronological order, oldest first
+    pub commits: Vec<CommitInfo>,
+    pub files: Vec<ChangedFile>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CommitInfo {
+    pub sha: String,
+    /// Full commit message, subject line first
+    pub message: String,
+    pub author: String,
+    /// Author email, `None` when the host does not expose it
+    pub author_email: Option<String>,
+    pub date: String,
+    /// Signature of the commit, `None` when the host does not report it
+    pub verification: Option<SignatureVerification>,
+}
+
+impl CommitInfo {
+    /// The first line of the commit message
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+}
+
+/// Whether a commit or tag is signed, and whether the host could verify the signature
+#[derive(Debug, Clone)]
+pub struct SignatureVerification {
+    pub verified: bool,
+    /// The host's verdict (e.g., "valid", "unsigned", "unknown_key")
+    pub reason: String,
+    /// Signature format ("gpg", "ssh" or "x509"), `None` when unsigned
+    pub format: Option<String>,
+    /// Who signed, when the host discloses it
+    pub signer: Option<String>,
+}
+
+impl SignatureVerification {
+    pub fn unsigned() -> Self {
+        Self {
+            verified: false,
+            reason: "unsigned".to_string(),
+            format: None,
+            signer: None,
+        }
+    }
+
+    /// A description fitting on one line, e.g. "verified gpg signature by octocat" or "unsigned"
+    pub fn describe(&self) -> String {
+        let Some(format) = &self.format else {
+            let description = if self.verified {
+                "verified signature"
+            } else {
+                "unsigned"
+            };
+            return description.to_string();
+        };
+
+        let status = if self.verified {
+            "verified"
+        } else {
+            "unverified"
+        };
+        let mut description = format!("{} {} signature", status, format);
+        if let Some(signer) = &self.signer {
+            description.push_str(&format!(" by {}", signer));
+        }
+        if !self.verified {
+            description.push_str(&format!(" ({})", self.reason));
+        }
+        description
+    }
+}
+
+/// A tag, with the message, tagger and signature of its annotation
+#[derive(Debug, Clone)]
+pub struct TagInfo {
+    pub name: String,
+    /// Commit the tag points at
+    pub commit_sha: String,
+    /// Whether the tag is a tag object rather than a lightweight reference
+    pub annotated: bool,
+    pub message: Option<String>,
+    pub tagger: Option<String>,
+    pub date: Option<String>,
+    /// Signature of the tag object, `None` when lightweight or when the host does not report it
+    pub verification: Option<SignatureVerification>,
+}
+
+/// A release published from a tag, with its notes
+#[derive(Debug, Clone)]
+pub struct ReleaseInfo {
+    pub tag_name: String,
+    /// Title of the release, often the tag name again
+    pub name: Option<String>,
+    /// `None` for drafts and releases still to come
+    pub published_at: Option<String>,
+    /// Release notes, in Markdown
+    pub notes: Option<String>,
+    pub prerelease: bool,
+    pub draft: bool,
+    pub url: Option<String>,
+}
+
+/// Someone who committed to a repository, with how many commits the host credits them with
+#[derive(Debug, Clone)]
+pub struct ContributorInfo {
+    /// Account login on hosts matching commits to accounts, the commit author name otherwise
+    pub login: String,
+    pub email: Option<String>,
+    pub contributions: u64,
+    /// Whether the account is a bot, as far as the host tells
+    pub bot: bool,
+}
+
+/// The share of a repository's code written in one language
+#[derive(Debug, Clone)]
+pub struct LanguageShare {
+    pub name: String,
+    pub percent: f64,
+    /// Bytes of code in the language, on hosts that count them
+    pub bytes: Option<u64>,
+}
+
+/// A commit together with the changes it introduced
+#[derive(Debug, Clone)]
+pub struct CommitDetails {
+    pub commit: CommitInfo,
+    pub files: Vec<ChangedFile>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    Added,
+    Modified,
+    Removed,
+    Renamed,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChangedFile {
+    pub path: String,
+    /// Path before a rename
+    pub previous_path: Option<String>,
+    /// Percentage of content kept across a rename, when known
+    pub similarity: Option<u8>,
+    pub status: FileStatus,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Unified diff of the file, absent for binary or oversized changes
+    pub patch: Option<String>,
+}
+
 /// Helper function to create a formatted tree structure
 pub fn create_tree_structure(node: &RepoNode, prefix: &str, is_last: bool) -> String {
     let mut result = String::new();
     let marker = if is_last { "└── " } else { "├── " };
 
     // Add the current node with appropriate prefix
-    result.push_str(&format!("{}{}{}\n", prefix, marker, node.name));
+    match &node.description {
+        Some(description) => result.push_str(&format!(
+            "{}{}{}  # {}\n",
+            prefix, marker, node.name, description
+        )),
+        None => result.push_str(&format!("{}{}{}\n", prefix, marker, node.name)),
+    }
 
     // Calculate the prefix for children
     let child_prefix = if is_last { "    " } else { "│   " };
@@ -108,3 +1000,192 @@ pub fn create_tree_structure(node: &RepoNode, prefix: &str, is_last: bool) -> St
 
     result
 }
+
+/// A directory left unexpanded because it lies past the depth limit of the walk
+pub fn depth_limited_directory(name: &str) -> RepoNode {
+    RepoNode {
+        name: name.to_string(),
+        node_type: RepoItemType::Directory,
+        size: 0,
+        children: vec![],
+        file_count: 0,
+        dir_count: 1,
+        description: Some(DEPTH_LIMITED.to_string()),
+    }
+}
+
+/// Unexpanded directories named in the note ending a tree, the others being counted
+const LISTED_DEPTH_LIMITED: usize = 20;
+
+/// Renders a tree walked with `options`, with a note naming the directories left unexpanded
+pub fn render_tree(node: &RepoNode, options: &TreeOptions) -> String {
+    fn depth_limited(node: &RepoNode, path: &str, paths: &mut Vec<String>) {
+        for child in &node.children {
+            let child_path = match path {
+                "" => child.name.clone(),
+                path => format!("{}/{}", path, child.name),
+            };
+            if child.description.as_deref() == Some(DEPTH_LIMITED) {
+                paths.push(child_path);
+            } else {
+                depth_limited(child, &child_path, paths);
+            }
+        }
+    }
+
+    let mut tree = create_tree_structure(node, "", true);
+    let mut paths = Vec::new();
+    depth_limited(node, options.subtree(), &mut paths);
+    if !paths.is_empty() {
+        tree.push_str(&format!(
+            "\n{} directories at depth {} were not expanded, raise max_depth (at most {}) or walk one of them as a subtree:\n",
+            paths.len(),
+            options.max_depth(),
+            MAX_TREE_DEPTH
+        ));
+        for path in paths.iter().take(LISTED_DEPTH_LIMITED) {
+            tree.push_str(&format!("- {}\n", path));
+        }
+        if paths.len() > LISTED_DEPTH_LIMITED {
+            tree.push_str(&format!(
+                "- and {} more\n",
+                paths.len() - LISTED_DEPTH_LIMITED
+            ));
+        }
+    }
+
+    tree
+}
+
+/// The README of a repository
+#[derive(Debug, Clone)]
+pub struct Readme {
+    pub path: String,
+    pub content: String,
+}
+
+/// Directories searched for a README, in order of preference
+const README_DIRECTORIES: &[&str] = &["", "docs/", "doc/", ".github/"];
+
+/// README extensions in order of preference, any other one coming last
+const README_EXTENSIONS: &[&str] = &["md", "markdown", "rst", "adoc", "org", "txt", ""];
+
+/// Picks the README among the paths of a repository: the one closest to the root, then the one
+/// in the most common format
+pub fn find_readme<'a>(paths: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let (directory, name) = path.rsplit_once('/').unwrap_or(("", path));
+            if !is_readme(name) {
+                return None;
+            }
+            let directory_rank = README_DIRECTORIES
+                .iter()
+                .position(|candidate| candidate.trim_end_matches('/') == directory)?;
+            let extension = name.split_once('.').map_or("", |(_, extension)| extension);
+            let extension_rank = README_EXTENSIONS
+                .iter()
+                .position(|candidate| extension.eq_ignore_ascii_case(candidate))
+                .unwrap_or(README_EXTENSIONS.len());
+            Some(((directory_rank, extension_rank), path))
+        })
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, path)| path)
+}
+
+/// The license file of a repository
+#[derive(Debug, Clone)]
+pub struct LicenseFile {
+    pub path: String,
+    /// SPDX identifier of the license, when the host recognized it
+    pub spdx_id: Option<String>,
+    pub content: String,
+}
+
+/// Names of license files without extension, in order of preference
+const LICENSE_FILE_NAMES: &[&str] = &["license", "licence", "copying", "unlicense"];
+
+/// Picks the license file among the paths of a repository: "LICENSE" and its variants at the
+/// root, e.g. "LICENSE.md", "licence.txt", "COPYING" or "LICENSE-MIT", the most common name first
+pub fn find_license_file<'a>(paths: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    paths
+        .into_iter()
+        .filter(|path| !path.contains('/'))
+        .filter_map(|path| {
+            let name = path.to_ascii_lowercase();
+            let (stem, extension) = name.split_once('.').unwrap_or((&name, ""));
+            let (stem, variant) = stem.split_once(['-', '_']).unwrap_or((stem, ""));
+            let rank = LICENSE_FILE_NAMES
+                .iter()
+                .position(|candidate| *candidate == stem)?;
+            Some(((rank, variant.len(), extension.len()), path))
+        })
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, path)| path)
+}
+
+/// READMEs larger than this are not worth fetching for a one-line description
+pub const MAX_README_SIZE: u64 = 64 * 1024;
+
+const MAX_DESCRIPTION_CHARS: usize = 100;
+
+/// Returns whether a file name looks like a README (README, README.md, readme.rst, ...)
+pub fn is_readme(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name == "readme" || name.starts_with("readme.")
+}
+
+/// Extracts the first prose paragraph of a README, skipping front matter, headings,
+/// badges, HTML and code blocks, shortened to fit on a single tree line
+pub fn readme_summary(content: &str) -> Option<String> {
+    let mut lines = content.lines().map(str::trim).peekable();
+
+    // YAML front matter
+    if lines.peek() == Some(&"---") {
+        lines.next();
+        for line in lines.by_ref() {
+            if line == "---" {
+                break;
+            }
+        }
+    }
+
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut in_code_block = false;
+
+    for line in lines {
+        if line.starts_with("```") || line.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+
+        let is_prose = !in_code_block
+            && !line.is_empty()
+            && !line.starts_with('#')
+            && !line.starts_with('<')
+            && !line.starts_with("![")
+            && !line.starts_with("[![")
+            && !line.starts_with('|')
+            // Setext and reStructuredText heading underlines
+            && !line.chars().all(|c| matches!(c, '=' | '-' | '~' | '*' | '^'));
+
+        if is_prose {
+            paragraph.push(line);
+        } else if !paragraph.is_empty() {
+            break;
+        }
+    }
+
+    if paragraph.is_empty() {
+        return None;
+    }
+
+    let summary = paragraph.join(" ");
+    if summary.chars().count() <= MAX_DESCRIPTION_CHARS {
+        return Some(summary);
+    }
+
+    let truncated: String = summary.chars().take(MAX_DESCRIPTION_CHARS - 1).collect();
+    Some(format!("{}…", truncated.trim_end()))
+}
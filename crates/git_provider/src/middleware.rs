@@ -0,0 +1,800 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    env,
+    io::Read,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use flate2::read::{DeflateDecoder, MultiGzDecoder, ZlibDecoder};
+use futures::future::{BoxFuture, FutureExt, Shared};
+use http_client::{
+    AsyncBody, HttpClient, Method, Request, Response, ResponseAsyncBodyExt,
+    http::{
+        HeaderMap, StatusCode, Uri, Version,
+        header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH},
+    },
+};
+use parking_lot::Mutex;
+
+use crate::warnings::{self, WarningKind};
+
+/// A layer around every HTTP call a provider makes
+///
+/// Layers run in registration order before a request and in reverse order after the
+/// response, so the first layer registered is the outermost one.
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Inspects or rewrites an outgoing request. Returning a response short-circuits the
+    /// call: neither the inner layers nor the network are reached.
+    async fn before_request(
+        &self,
+        _request: &mut Request<AsyncBody>,
+    ) -> Result<Option<Response<AsyncBody>>> {
+        Ok(None)
+    }
+
+    /// Inspects or rewrites a response before it reaches the provider
+    async fn after_response(
+        &self,
+        _method: &Method,
+        _uri: &Uri,
+        _response: &mut Response<AsyncBody>,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// An HTTP client running a stack of middleware around another client
+pub struct MiddlewareStack {
+    inner: Arc<dyn HttpClient>,
+    layers: Vec<Arc<dyn Middleware>>,
+}
+
+impl MiddlewareStack {
+    pub fn new(inner: Arc<dyn HttpClient>) -> Self {
+        Self {
+            inner,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Adds a layer inside the ones already registered
+    pub fn with(mut self, layer: Arc<dyn Middleware>) -> Self {
+        self.layers.push(layer);
+        self
+    }
+}
+
+#[async_trait]
+impl HttpClient for MiddlewareStack {
+    async fn send(&self, mut request: Request<AsyncBody>) -> Result<Response<AsyncBody>> {
+        let method = request.method().clone();
+        let uri = request.uri().clone();
+
+        let mut reached = 0;
+        let mut short_circuit = None;
+        for layer in &self.layers {
+            reached += 1;
+            if let Some(response) = layer.before_request(&mut request).await? {
+                short_circuit = Some(response);
+                break;
+            }
+        }
+
+        let mut response = match short_circuit {
+            Some(response) => response,
+            None => self.inner.send(request).await?,
+        };
+
+        // Only the layers that saw the request see its response
+        for layer in self.layers[..reached].iter().rev() {
+            layer.after_response(&method, &uri, &mut response).await?;
+        }
+
+        Ok(response)
+    }
+}
+
+/// A response read whole, so every caller sharing it gets its own copy
+struct BufferedResponse {
+    status: StatusCode,
+    version: Version,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl BufferedResponse {
+    async fn read(response: Response<AsyncBody>) -> Result<Self> {
+        Ok(Self {
+            status: response.status(),
+            version: response.version(),
+            headers: response.headers().clone(),
+            body: response.bytes().await?,
+        })
+    }
+
+    fn to_response(&self) -> Result<Response<AsyncBody>> {
+        let mut response = Response::builder()
+            .status(self.status)
+            .version(self.version)
+            .body(AsyncBody::from(self.body.clone()))?;
+        *response.headers_mut() = self.headers.clone();
+        Ok(response)
+    }
+}
+
+/// A request with its URL and headers, the headers telling apart providers calling the same
+/// host with different tokens
+type FlightKey = (String, Vec<(String, Vec<u8>)>);
+
+/// A request in progress, errors kept as text since they are shared too
+type Flight = Shared<BoxFuture<'static, Result<Arc<BufferedResponse>, String>>>;
+
+/// An HTTP client sending concurrent identical GET requests once, every caller getting a copy
+/// of the same response (e.g., the repository metadata a tree and a read need at the same
+/// time). Requests are only shared while in flight, nothing is cached once answered.
+pub struct SingleFlight {
+    inner: Arc<dyn HttpClient>,
+    in_flight: Arc<Mutex<HashMap<FlightKey, Flight>>>,
+}
+
+impl SingleFlight {
+    pub fn new(inner: Arc<dyn HttpClient>) -> Self {
+        Self {
+            inner,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+#[async_trait]
+impl HttpClient for SingleFlight {
+    async fn send(&self, request: Request<AsyncBody>) -> Result<Response<AsyncBody>> {
+        // Other methods change state, sending them twice is meant. Dry runs neither join nor
+        // lead calls actually sent.
+        if request.method() != Method::GET || DryRun::in_progress() {
+            return self.inner.send(request).await;
+        }
+
+        let key: FlightKey = (
+            request.uri().to_string(),
+            request
+                .headers()
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.as_bytes().to_vec()))
+                .collect(),
+        );
+        let flight = self
+            .in_flight
+            .lock()
+            .entry(key.clone())
+            .or_insert_with(|| {
+                let inner = self.inner.clone();
+                let in_flight = self.in_flight.clone();
+                async move {
+                    let response = match inner.send(request).await {
+                        Ok(response) => BufferedResponse::read(response).await,
+                        Err(e) => Err(e),
+                    };
+                    // Removed by the flight itself, so callers giving up cannot leave it behind
+                    in_flight.lock().remove(&key);
+                    response.map(Arc::new).map_err(|e| e.to_string())
+                }
+                .boxed()
+                .shared()
+            })
+            .clone();
+
+        flight.await.map_err(|e| anyhow!(e))?.to_response()
+    }
+}
+
+/// Logs every provider call and its status to stderr
+#[derive(Default)]
+pub struct AuditLog;
+
+#[async_trait]
+impl Middleware for AuditLog {
+    async fn after_response(
+        &self,
+        method: &Method,
+        uri: &Uri,
+        response: &mut Response<AsyncBody>,
+    ) -> Result<()> {
+        // Query strings may carry tokens, only the path is logged
+        eprintln!(
+            "[audit] {} {}{} -> {}",
+            method,
+            uri.host().unwrap_or_default(),
+            uri.path(),
+            response.status()
+        );
+        Ok(())
+    }
+}
+
+/// Restricts the hosts the server contacts, so repository identifiers and configured base URLs
+/// cannot point it at internal services. An entry names a host, or its subdomains when it
+/// starts with "*." (e.g., "*.example.com"). Until an entry is added every host is allowed.
+#[derive(Default)]
+pub struct HostAllowlist {
+    hosts: Mutex<Option<Vec<String>>>,
+}
+
+impl HostAllowlist {
+    /// The hosts listed in the comma-separated GITINGEST_ALLOWED_HOSTS, unrestricted when unset
+    pub fn from_env() -> Self {
+        let allowlist = Self::default();
+        if let Ok(hosts) = env::var("GITINGEST_ALLOWED_HOSTS") {
+            allowlist.restrict();
+            for host in hosts.split(',').map(str::trim).filter(|h| !h.is_empty()) {
+                allowlist.allow(host);
+            }
+        }
+        allowlist
+    }
+
+    /// Allows no host but the ones added afterwards, even if none is
+    pub fn restrict(&self) {
+        self.hosts.lock().get_or_insert_with(Vec::new);
+    }
+
+    /// Adds a host, or "*.domain" for the subdomains of a domain, restricting every other one
+    pub fn allow(&self, host: &str) {
+        let host = host.to_ascii_lowercase();
+        let mut hosts = self.hosts.lock();
+        let hosts = hosts.get_or_insert_with(Vec::new);
+        if !hosts.contains(&host) {
+            hosts.push(host);
+        }
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        self.hosts.lock().is_some()
+    }
+
+    pub fn allows(&self, host: &str) -> bool {
+        let host = host.to_ascii_lowercase();
+        let hosts = self.hosts.lock();
+        let Some(hosts) = hosts.as_ref() else {
+            return true;
+        };
+
+        hosts.iter().any(|entry| match entry.strip_prefix("*.") {
+            Some(domain) => host.ends_with(&format!(".{}", domain)),
+            None => *entry == host,
+        })
+    }
+
+    /// Fails unless `url` is absolute and its host is allowed
+    pub fn check_url(&self, url: &str) -> Result<()> {
+        let uri: Uri = url
+            .parse()
+            .map_err(|e| anyhow!("Invalid URL '{}': {}", url, e))?;
+        let host = uri
+            .host()
+            .ok_or_else(|| anyhow!("Invalid URL '{}': no host", url))?;
+        if !self.allows(host) {
+            return Err(anyhow!(
+                "Host {} is not in the allowlist, add it to GITINGEST_ALLOWED_HOSTS or to the allowed_hosts of the providers file",
+                host
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Middleware for HostAllowlist {
+    async fn before_request(
+        &self,
+        request: &mut Request<AsyncBody>,
+    ) -> Result<Option<Response<AsyncBody>>> {
+        self.check_url(&request.uri().to_string())?;
+        Ok(None)
+    }
+}
+
+/// Redirects followed for one call before giving up
+const MAX_REDIRECTS: usize = 10;
+
+/// The HTTP client sending the calls of every provider. Redirects are followed only to the
+/// hosts of the allowlist, each hop being checked as the first URL is by the middleware, so an
+/// allowed host cannot send a call anywhere else.
+pub struct ReqwestClient {
+    client: reqwest::Client,
+}
+
+impl ReqwestClient {
+    pub fn new(allowlist: Arc<HostAllowlist>) -> Result<Self> {
+        let policy = reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                return attempt.error(format!("more than {} redirects", MAX_REDIRECTS));
+            }
+            match allowlist.check_url(attempt.url().as_str()) {
+                Ok(()) => attempt.follow(),
+                Err(e) => attempt.error(e.to_string()),
+            }
+        });
+
+        Ok(Self {
+            client: reqwest::Client::builder().redirect(policy).build()?,
+        })
+    }
+}
+
+#[async_trait]
+impl HttpClient for ReqwestClient {
+    async fn send(&self, request: Request<AsyncBody>) -> Result<Response<AsyncBody>> {
+        let (parts, body) = request.into_parts();
+        let body = Response::new(body).bytes().await?;
+
+        let response = self
+            .client
+            .request(parts.method, parts.uri.to_string())
+            .headers(parts.headers)
+            .body(body)
+            .send()
+            .await
+            // The cause tells which redirect was refused
+            .map_err(|e| match std::error::Error::source(&e) {
+                Some(cause) => anyhow!("{}: {}", e, cause),
+                None => anyhow!("{}", e),
+            })?;
+
+        let status = response.status();
+        let version = response.version();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?.to_vec();
+
+        let mut response = Response::builder()
+            .status(status)
+            .version(version)
+            .body(AsyncBody::from(body))?;
+        *response.headers_mut() = headers;
+        Ok(response)
+    }
+}
+
+/// Bytes moved for the calls to one host
+#[derive(Debug, Clone, Default)]
+pub struct Transfer {
+    pub host: String,
+    pub requests: u64,
+    /// Size of the bodies as received, compressed when the host compressed them
+    pub received_bytes: u64,
+    /// Size of the bodies once decompressed
+    pub decoded_bytes: u64,
+}
+
+tokio::task_local! {
+    /// The bytes moved for the tool call the current task serves, by host
+    static TRANSFERS: RefCell<BTreeMap<String, Transfer>>;
+}
+
+/// Asks every host for gzip or deflate compressed responses, decompresses them, and counts
+/// the bytes received and decoded per host. Clients decompressing on their own hand over
+/// bodies already decoded, which are then counted at their decoded size.
+#[derive(Default)]
+pub struct Compression;
+
+impl Compression {
+    /// Runs `future` as one tool call, returning its output with the bytes it moved, by host.
+    /// Concurrent calls keep their own, a request they share being counted once, for the call
+    /// that sent it.
+    pub async fn collect<F: Future>(future: F) -> (F::Output, Vec<Transfer>) {
+        TRANSFERS
+            .scope(RefCell::new(BTreeMap::new()), async {
+                let output = future.await;
+                let transfers = TRANSFERS.with(|transfers| transfers.take());
+                (output, transfers.into_values().collect())
+            })
+            .await
+    }
+}
+
+/// Decompresses a body sent with `Content-Encoding: encoding`, `None` for encodings left as is
+fn decode(encoding: &str, body: &[u8]) -> Option<Result<Vec<u8>>> {
+    let mut decoded = Vec::new();
+    let result = match encoding {
+        "gzip" | "x-gzip" => MultiGzDecoder::new(body).read_to_end(&mut decoded),
+        // Meant to be zlib-wrapped, though some servers send raw deflate
+        "deflate" => ZlibDecoder::new(body)
+            .read_to_end(&mut decoded)
+            .or_else(|_| {
+                decoded.clear();
+                DeflateDecoder::new(body).read_to_end(&mut decoded)
+            }),
+        _ => return None,
+    };
+    Some(
+        result
+            .map(|_| decoded)
+            .map_err(|e| anyhow!("Cannot decode {} response: {}", encoding, e)),
+    )
+}
+
+#[async_trait]
+impl Middleware for Compression {
+    async fn before_request(
+        &self,
+        request: &mut Request<AsyncBody>,
+    ) -> Result<Option<Response<AsyncBody>>> {
+        if !request.headers().contains_key(ACCEPT_ENCODING) {
+            request
+                .headers_mut()
+                .insert(ACCEPT_ENCODING, "gzip, deflate".parse()?);
+        }
+        Ok(None)
+    }
+
+    async fn after_response(
+        &self,
+        _method: &Method,
+        uri: &Uri,
+        response: &mut Response<AsyncBody>,
+    ) -> Result<()> {
+        let encoding = response
+            .headers()
+            .get(CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_ascii_lowercase());
+
+        // The body can only be measured and decoded whole
+        let mut buffered = BufferedResponse::read(std::mem::replace(
+            response,
+            Response::new(AsyncBody::from(Vec::new())),
+        ))
+        .await?;
+        let received_bytes = buffered.body.len() as u64;
+        if let Some(decoded) = encoding
+            .as_deref()
+            .and_then(|encoding| decode(encoding, &buffered.body))
+        {
+            buffered.body = decoded?;
+            buffered.headers.remove(CONTENT_ENCODING);
+            buffered.headers.remove(CONTENT_LENGTH);
+        }
+
+        {
+            let host = uri.host().unwrap_or_default().to_string();
+            let decoded_bytes = buffered.body.len() as u64;
+            // Background refreshes serve no call, their transfers are not reported
+            let _ = TRANSFERS.try_with(|transfers| {
+                let mut transfers = transfers.borrow_mut();
+                let transfer = transfers.entry(host.clone()).or_insert_with(|| Transfer {
+                    host,
+                    ..Transfer::default()
+                });
+                transfer.requests += 1;
+                transfer.received_bytes += received_bytes;
+                transfer.decoded_bytes += decoded_bytes;
+            });
+        }
+
+        *response = buffered.to_response()?;
+        Ok(())
+    }
+}
+
+/// The most recent rate limit a host reported, in requests
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub remaining: u64,
+    pub limit: u64,
+}
+
+/// How long an assumed budget lasts before it is counted down from its limit again, the
+/// window hosts use for unauthenticated calls
+const ASSUMED_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Whether `host` is `domain` or one of its subdomains
+fn within(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+/// Records the rate limit headers of every response, so callers can tell how many requests
+/// a host still accepts before spending them
+#[derive(Default)]
+pub struct RateLimits {
+    /// Keyed by host, then by the bucket GitHub reports in `x-ratelimit-resource`
+    limits: Mutex<BTreeMap<(String, String), RateLimit>>,
+    /// Budgets counted down locally for domains that did not report theirs yet, with the
+    /// start of their current window
+    assumed: Mutex<BTreeMap<String, (RateLimit, Instant)>>,
+}
+
+impl RateLimits {
+    /// Assumes `domain` accepts `limit` requests an hour until it reports a limit itself,
+    /// counting its responses locally. Meant for hosts called without a token, whose budget is
+    /// small enough that even the first calls of a session should be planned.
+    pub fn assume(&self, domain: &str, limit: u64) {
+        self.assumed.lock().insert(
+            domain.to_string(),
+            (
+                RateLimit {
+                    remaining: limit,
+                    limit,
+                },
+                Instant::now(),
+            ),
+        );
+    }
+
+    /// The tightest limit reported by `domain` or one of its subdomains, falling back to the
+    /// budgets assumed for them, `None` until one of their responses carried rate limit headers
+    /// when none was assumed
+    pub fn headroom(&self, domain: &str) -> Option<RateLimit> {
+        let reported = self
+            .limits
+            .lock()
+            .iter()
+            .filter(|((host, _), _)| within(host, domain))
+            // Search has its own, much smaller, bucket unrelated to everyday calls
+            .filter(|((_, resource), _)| resource != "search")
+            .map(|(_, limit)| *limit)
+            .min_by_key(|limit| limit.remaining);
+
+        reported.or_else(|| {
+            self.assumed
+                .lock()
+                .iter_mut()
+                .filter(|(assumed, _)| within(assumed, domain))
+                .map(|(_, (limit, since))| {
+                    if since.elapsed() >= ASSUMED_WINDOW {
+                        limit.remaining = limit.limit;
+                        *since = Instant::now();
+                    }
+                    *limit
+                })
+                .min_by_key(|limit| limit.remaining)
+        })
+    }
+}
+
+#[async_trait]
+impl Middleware for RateLimits {
+    async fn after_response(
+        &self,
+        _method: &Method,
+        uri: &Uri,
+        response: &mut Response<AsyncBody>,
+    ) -> Result<()> {
+        let header = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                response
+                    .headers()
+                    .get(*name)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.trim().parse::<u64>().ok())
+            })
+        };
+
+        // GitHub prefixes its headers with "x-", GitLab does not
+        let host = uri.host().unwrap_or_default();
+        let (Some(remaining), Some(limit)) = (
+            header(&["x-ratelimit-remaining", "ratelimit-remaining"]),
+            header(&["x-ratelimit-limit", "ratelimit-limit"]),
+        ) else {
+            // Responses without headers still spend the budget assumed for their host
+            for (domain, (limit, _)) in self.assumed.lock().iter_mut() {
+                if within(host, domain) {
+                    limit.remaining = limit.remaining.saturating_sub(1);
+                }
+            }
+            return Ok(());
+        };
+        let resource = response
+            .headers()
+            .get("x-ratelimit-resource")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        if remaining == 0 {
+            warnings::warn(
+                WarningKind::RateLimited,
+                format!("{} has no API requests left of its {}", host, limit),
+            );
+        }
+
+        self.limits
+            .lock()
+            .insert((host.to_string(), resource), RateLimit { remaining, limit });
+        Ok(())
+    }
+}
+
+/// A call a dry run kept from reaching the network
+#[derive(Debug, Clone)]
+pub struct PlannedCall {
+    pub method: Method,
+    pub host: String,
+    /// Path and query string of the call
+    pub path: String,
+}
+
+impl PlannedCall {
+    /// A GET of `url`, for transfers made outside of the HTTP client
+    pub fn get(url: &str) -> Result<Self> {
+        let uri: Uri = url
+            .parse()
+            .map_err(|e| anyhow!("Invalid URL '{}': {}", url, e))?;
+        Ok(Self {
+            method: Method::GET,
+            host: uri.host().unwrap_or_default().to_string(),
+            path: uri
+                .path_and_query()
+                .map_or_else(|| uri.path().to_string(), |p| p.to_string()),
+        })
+    }
+}
+
+tokio::task_local! {
+    /// The calls kept from the network by the dry run of the current tool call
+    static PLANNED_CALLS: RefCell<Vec<PlannedCall>>;
+}
+
+/// Records the calls made while a dry run is in progress and fails them before they are sent,
+/// so the cost of a tool call can be read before spending it. A dry run covers the task of one
+/// tool call, calls made meanwhile by other tasks (e.g., background refreshes) go through
+/// untouched.
+#[derive(Default)]
+pub struct DryRun;
+
+impl DryRun {
+    /// Runs `future` as a dry run, returning its output with the calls kept from the network in
+    /// the order they were made
+    pub async fn plan<F: Future>(future: F) -> (F::Output, Vec<PlannedCall>) {
+        PLANNED_CALLS
+            .scope(RefCell::new(Vec::new()), async {
+                let output = future.await;
+                (output, PLANNED_CALLS.with(|calls| calls.take()))
+            })
+            .await
+    }
+
+    /// Whether the current task runs a dry run
+    pub fn in_progress() -> bool {
+        PLANNED_CALLS.try_with(|_| ()).is_ok()
+    }
+
+    /// Records `call` and fails it when a dry run is in progress, lets it through otherwise.
+    /// Also meant for transfers not going through the HTTP client, such as git clones.
+    pub fn intercept(call: PlannedCall) -> Result<()> {
+        let error = anyhow!(
+            "Dry run: {} {}{} was not sent",
+            call.method,
+            call.host,
+            call.path
+        );
+        match PLANNED_CALLS.try_with(|calls| calls.borrow_mut().push(call)) {
+            Ok(()) => Err(error),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl Middleware for DryRun {
+    async fn before_request(
+        &self,
+        request: &mut Request<AsyncBody>,
+    ) -> Result<Option<Response<AsyncBody>>> {
+        let uri = request.uri();
+        DryRun::intercept(PlannedCall {
+            method: request.method().clone(),
+            host: uri.host().unwrap_or_default().to_string(),
+            path: uri
+                .path_and_query()
+                .map_or_else(|| uri.path().to_string(), |p| p.to_string()),
+        })?;
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Write,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use flate2::{
+        Compression as Level,
+        write::{GzEncoder, ZlibEncoder},
+    };
+
+    use super::*;
+
+    #[test]
+    fn decodes_gzip_and_deflate_bodies() {
+        let body = br#"{"tree":[{"path":"src/lib.rs"}]}"#.repeat(20);
+
+        let mut gzip = GzEncoder::new(Vec::new(), Level::default());
+        gzip.write_all(&body).unwrap();
+        let gzip = gzip.finish().unwrap();
+        assert!(gzip.len() < body.len());
+        assert_eq!(decode("gzip", &gzip).unwrap().unwrap(), body);
+
+        let mut zlib = ZlibEncoder::new(Vec::new(), Level::default());
+        zlib.write_all(&body).unwrap();
+        assert_eq!(
+            decode("deflate", &zlib.finish().unwrap()).unwrap().unwrap(),
+            body
+        );
+    }
+
+    #[test]
+    fn allowlists_name_hosts_or_subdomains() {
+        let allowlist = HostAllowlist::default();
+        assert!(allowlist.check_url("http://169.254.169.254/latest").is_ok());
+
+        allowlist.allow("api.github.com");
+        allowlist.allow("*.example.com");
+        assert!(allowlist.check_url("https://API.github.com/repos").is_ok());
+        assert!(
+            allowlist
+                .check_url("https://git.example.com/a/b.git")
+                .is_ok()
+        );
+        assert!(allowlist.check_url("https://example.com").is_err());
+        assert!(allowlist.check_url("https://github.com.evil.test").is_err());
+        assert!(
+            allowlist
+                .check_url("http://169.254.169.254/latest")
+                .is_err()
+        );
+        assert!(allowlist.check_url("/relative").is_err());
+
+        let empty = HostAllowlist::default();
+        empty.restrict();
+        assert!(!empty.allows("gitlab.com"));
+    }
+
+    /// Answers every request after yielding once, counting the requests it was sent
+    #[derive(Default)]
+    struct CountingClient {
+        sent: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl HttpClient for CountingClient {
+        async fn send(&self, _request: Request<AsyncBody>) -> Result<Response<AsyncBody>> {
+            self.sent.fetch_add(1, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            Ok(Response::new(AsyncBody::from("{}")))
+        }
+    }
+
+    #[test]
+    fn concurrent_identical_requests_are_sent_once() {
+        let inner = Arc::new(CountingClient::default());
+        let client = SingleFlight::new(inner.clone());
+        let get = || {
+            Request::get("https://api.github.com/repos/a/b")
+                .body(AsyncBody::from(()))
+                .unwrap()
+        };
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let (first, second) =
+            runtime.block_on(async { futures::join!(client.send(get()), client.send(get())) });
+
+        assert_eq!(first.unwrap().status(), StatusCode::OK);
+        assert_eq!(second.unwrap().status(), StatusCode::OK);
+        assert_eq!(inner.sent.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn leaves_other_encodings_alone() {
+        assert!(decode("br", b"...").is_none());
+        assert!(decode("gzip", b"not gzip").unwrap().is_err());
+    }
+}
@@ -0,0 +1,107 @@
+/// Include and exclude patterns for the sources of one ecosystem
+#[derive(Debug, Clone, Copy)]
+pub struct PatternPreset {
+    pub name: &'static str,
+    /// Source file extensions, without the dot
+    pub extensions: &'static [&'static str],
+    /// Manifests and build files kept next to the sources, matched at any depth
+    pub files: &'static [&'static str],
+    /// Vendored and build output directories, matched at any depth
+    pub exclude_dirs: &'static [&'static str],
+}
+
+impl PatternPreset {
+    /// Glob patterns keeping the ecosystem's sources and manifests
+    pub fn include_patterns(&self) -> Vec<String> {
+        self.extensions
+            .iter()
+            .map(|extension| format!("*.{}", extension))
+            .chain(self.files.iter().map(|file| format!("**/{}", file)))
+            .collect()
+    }
+
+    /// Glob patterns dropping the ecosystem's vendored and generated directories, both the
+    /// directories themselves and everything beneath them
+    pub fn exclude_patterns(&self) -> Vec<String> {
+        self.exclude_dirs
+            .iter()
+            .flat_map(|dir| [format!("**/{}", dir), format!("**/{}/**", dir)])
+            .collect()
+    }
+}
+
+pub const PATTERN_PRESETS: [PatternPreset; 5] = [
+    PatternPreset {
+        name: "rust",
+        extensions: &["rs"],
+        files: &[
+            "Cargo.toml",
+            "Cargo.lock",
+            "build.rs",
+            "rust-toolchain",
+            "rust-toolchain.toml",
+        ],
+        exclude_dirs: &["target"],
+    },
+    PatternPreset {
+        name: "node",
+        extensions: &["js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts"],
+        files: &["package.json", "tsconfig.json", ".nvmrc"],
+        exclude_dirs: &[
+            "node_modules",
+            "bower_components",
+            "dist",
+            "build",
+            "coverage",
+            ".next",
+            ".nuxt",
+            ".turbo",
+        ],
+    },
+    PatternPreset {
+        name: "python",
+        extensions: &["py", "pyi"],
+        files: &[
+            "pyproject.toml",
+            "setup.py",
+            "setup.cfg",
+            "requirements.txt",
+            "tox.ini",
+        ],
+        exclude_dirs: &[
+            "__pycache__",
+            ".venv",
+            "venv",
+            "build",
+            "dist",
+            "site-packages",
+            ".tox",
+            ".mypy_cache",
+            ".pytest_cache",
+        ],
+    },
+    PatternPreset {
+        name: "go",
+        extensions: &["go"],
+        files: &["go.mod", "go.sum", "go.work"],
+        exclude_dirs: &["vendor", "bin"],
+    },
+    PatternPreset {
+        name: "java",
+        extensions: &["java", "kt", "kts", "groovy"],
+        files: &[
+            "pom.xml",
+            "build.gradle",
+            "settings.gradle",
+            "gradle.properties",
+        ],
+        exclude_dirs: &["target", "build", "out", ".gradle", ".mvn"],
+    },
+];
+
+/// Looks up a preset by name, ignoring case
+pub fn find_preset(name: &str) -> Option<&'static PatternPreset> {
+    PATTERN_PRESETS
+        .iter()
+        .find(|preset| preset.name.eq_ignore_ascii_case(name))
+}
@@ -0,0 +1,334 @@
+//! Path filtering shared by the providers
+//!
+//! A path is decided by three pattern lists:
+//!
+//! - Include patterns narrow: when any plain include pattern is given, a file must match one
+//!   of them to be kept. Directories are never dropped by includes, since files below them
+//!   may still match.
+//! - Exclude patterns override includes: an excluded path is dropped even if it is included.
+//!   The default ignore patterns behave as excludes listed before the user's own.
+//! - Later patterns win: within a list, the last matching pattern decides. A `!pattern`
+//!   include drops the paths it matches, and a `!pattern` exclude keeps them, undoing an
+//!   earlier exclude or default ignore pattern.
+
+use crate::TreeOptions;
+
+/// The list a pattern was given in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternSource {
+    Include,
+    Exclude,
+    /// Default ignore patterns, matched as substrings of the path
+    Ignore,
+}
+
+impl PatternSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PatternSource::Include => "include",
+            PatternSource::Exclude => "exclude",
+            PatternSource::Ignore => "ignore",
+        }
+    }
+}
+
+/// One entry of a pattern list
+#[derive(Debug, Clone)]
+pub struct PatternRule {
+    pub source: PatternSource,
+    /// The pattern as given, without its `!` prefix
+    pub pattern: String,
+    /// Whether the pattern was given as `!pattern`, inverting its effect
+    pub negated: bool,
+    glob: Option<glob::Pattern>,
+    case_sensitive: bool,
+}
+
+impl PatternRule {
+    fn new(source: PatternSource, pattern: &str, case_sensitive: bool) -> Self {
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(rest) if source != PatternSource::Ignore => (true, rest),
+            _ => (false, pattern),
+        };
+
+        Self {
+            source,
+            pattern: pattern.to_string(),
+            negated,
+            // Invalid globs never match, as before patterns were shared
+            glob: glob::Pattern::new(pattern).ok(),
+            case_sensitive,
+        }
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        match self.source {
+            PatternSource::Ignore if self.case_sensitive => path.contains(self.pattern.as_str()),
+            PatternSource::Ignore => path
+                .to_lowercase()
+                .contains(self.pattern.to_lowercase().as_str()),
+            _ => self.glob.as_ref().is_some_and(|glob| {
+                glob.matches_with(
+                    path,
+                    glob::MatchOptions {
+                        case_sensitive: self.case_sensitive,
+                        ..glob::MatchOptions::new()
+                    },
+                )
+            }),
+        }
+    }
+
+    /// Whether a match keeps the path rather than dropping it
+    pub fn keeps(&self) -> bool {
+        match self.source {
+            PatternSource::Include => !self.negated,
+            PatternSource::Exclude | PatternSource::Ignore => self.negated,
+        }
+    }
+}
+
+impl std::fmt::Display for PatternRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let negation = if self.negated { "!" } else { "" };
+        write!(f, "{} {}{}", self.source.as_str(), negation, self.pattern)
+    }
+}
+
+/// The outcome of filtering one path
+#[derive(Debug, Clone, Copy)]
+pub struct FilterDecision<'a> {
+    pub keep: bool,
+    /// The rule that decided, or `None` when no pattern matched
+    pub rule: Option<&'a PatternRule>,
+}
+
+/// Decides which paths a walk keeps from include, exclude and ignore patterns
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    includes: Vec<PatternRule>,
+    /// Ignore patterns followed by exclude patterns, so later user patterns win
+    excludes: Vec<PatternRule>,
+}
+
+impl PathFilter {
+    pub fn new(
+        include_patterns: &[String],
+        exclude_patterns: &[String],
+        ignore_patterns: &[String],
+    ) -> Self {
+        Self::with_case_sensitivity(include_patterns, exclude_patterns, ignore_patterns, true)
+    }
+
+    /// Like `new`, optionally ignoring case when matching every pattern
+    pub fn with_case_sensitivity(
+        include_patterns: &[String],
+        exclude_patterns: &[String],
+        ignore_patterns: &[String],
+        case_sensitive: bool,
+    ) -> Self {
+        let includes = include_patterns
+            .iter()
+            .map(|pattern| PatternRule::new(PatternSource::Include, pattern, case_sensitive))
+            .collect();
+
+        let excludes =
+            ignore_patterns
+                .iter()
+                .map(|pattern| PatternRule::new(PatternSource::Ignore, pattern, case_sensitive))
+                .chain(exclude_patterns.iter().map(|pattern| {
+                    PatternRule::new(PatternSource::Exclude, pattern, case_sensitive)
+                }))
+                .collect();
+
+        Self { includes, excludes }
+    }
+
+    pub fn from_options(options: &TreeOptions, ignore_patterns: &[String]) -> Self {
+        Self::with_case_sensitivity(
+            &options.include_patterns,
+            &options.exclude_patterns,
+            ignore_patterns,
+            !options.case_insensitive,
+        )
+    }
+
+    /// Decides whether a file is kept and which rule decided it
+    pub fn decide(&self, path: &str) -> FilterDecision<'_> {
+        let excluded = Self::last_match(&self.excludes, path);
+        if let Some(rule) = excluded.filter(|rule| !rule.keeps()) {
+            return FilterDecision {
+                keep: false,
+                rule: Some(rule),
+            };
+        }
+
+        match Self::last_match(&self.includes, path) {
+            Some(rule) => FilterDecision {
+                keep: rule.keeps(),
+                rule: Some(rule),
+            },
+            None if self.includes.iter().any(|rule| rule.keeps()) => FilterDecision {
+                keep: false,
+                rule: None,
+            },
+            // Unmatched by any include, so the negated exclude (if any) is what kept it
+            None => FilterDecision {
+                keep: true,
+                rule: excluded,
+            },
+        }
+    }
+
+    /// Decides whether a directory is walked, which only excludes can prevent
+    pub fn decide_directory(&self, path: &str) -> FilterDecision<'_> {
+        let rule = Self::last_match(&self.excludes, path);
+        FilterDecision {
+            keep: rule.is_none_or(|rule| rule.keeps()),
+            rule,
+        }
+    }
+
+    pub fn keeps(&self, path: &str) -> bool {
+        self.decide(path).keep
+    }
+
+    pub fn keeps_directory(&self, path: &str) -> bool {
+        self.decide_directory(path).keep
+    }
+
+    fn last_match<'a>(rules: &'a [PatternRule], path: &str) -> Option<&'a PatternRule> {
+        rules.iter().rev().find(|rule| rule.matches(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(list: &[&str]) -> Vec<String> {
+        list.iter().map(|pattern| pattern.to_string()).collect()
+    }
+
+    fn path_filter(includes: &[&str], excludes: &[&str], ignores: &[&str]) -> PathFilter {
+        PathFilter::new(&patterns(includes), &patterns(excludes), &patterns(ignores))
+    }
+
+    #[test]
+    fn keeps_everything_without_patterns() {
+        let filter = path_filter(&[], &[], &[]);
+        assert!(filter.keeps("src/main.rs"));
+        assert!(filter.keeps("README.md"));
+        assert!(filter.decide("README.md").rule.is_none());
+    }
+
+    #[test]
+    fn includes_narrow_files() {
+        let filter = path_filter(&["*.rs"], &[], &[]);
+        assert!(filter.keeps("src/main.rs"));
+        assert!(!filter.keeps("README.md"));
+        assert!(filter.decide("README.md").rule.is_none());
+    }
+
+    #[test]
+    fn includes_do_not_drop_directories() {
+        let filter = path_filter(&["*.rs"], &[], &[]);
+        assert!(filter.keeps_directory("src"));
+    }
+
+    #[test]
+    fn excludes_override_includes() {
+        let filter = path_filter(&["*.rs"], &["tests/**"], &[]);
+        assert!(filter.keeps("src/lib.rs"));
+        assert!(!filter.keeps("tests/golden.rs"));
+
+        let decision = filter.decide("tests/golden.rs");
+        assert_eq!(decision.rule.unwrap().source, PatternSource::Exclude);
+    }
+
+    #[test]
+    fn negated_include_drops_matches() {
+        let filter = path_filter(&["*.rs", "!**/generated/*"], &[], &[]);
+        assert!(filter.keeps("src/lib.rs"));
+        assert!(!filter.keeps("src/generated/schema.rs"));
+    }
+
+    #[test]
+    fn negated_includes_alone_keep_unmatched_paths() {
+        let filter = path_filter(&["!*.lock"], &[], &[]);
+        assert!(filter.keeps("src/lib.rs"));
+        assert!(!filter.keeps("Cargo.lock"));
+    }
+
+    #[test]
+    fn later_includes_win() {
+        let filter = path_filter(&["!docs/*", "docs/index.md"], &[], &[]);
+        assert!(filter.keeps("docs/index.md"));
+        assert!(!filter.keeps("docs/guide.md"));
+
+        let filter = path_filter(&["docs/index.md", "!docs/*"], &[], &[]);
+        assert!(!filter.keeps("docs/index.md"));
+    }
+
+    #[test]
+    fn negated_exclude_undoes_earlier_exclude() {
+        let filter = path_filter(&[], &["vendor/**", "!vendor/patched/**"], &[]);
+        assert!(!filter.keeps("vendor/lib/a.go"));
+        assert!(filter.keeps("vendor/patched/a.go"));
+        assert!(!filter.keeps_directory("vendor/lib"));
+    }
+
+    #[test]
+    fn negated_exclude_undoes_ignore_patterns() {
+        let filter = path_filter(&[], &["!**/build/**"], &["build/"]);
+        assert!(filter.keeps("app/build/output.txt"));
+
+        let decision = filter.decide("app/build/output.txt");
+        assert!(decision.rule.unwrap().negated);
+    }
+
+    #[test]
+    fn rescued_paths_still_need_an_include() {
+        let filter = path_filter(&["*.rs"], &["!**/build/**"], &["build/"]);
+        assert!(filter.keeps("build/script.rs"));
+        assert!(!filter.keeps("build/notes.txt"));
+    }
+
+    #[test]
+    fn ignore_patterns_match_substrings_and_are_never_negated() {
+        let filter = path_filter(&[], &[], &["node_modules", "!keep"]);
+        assert!(!filter.keeps("web/node_modules/react/index.js"));
+        assert!(!filter.keeps("!keep/file.txt"));
+        assert!(filter.keeps("keep/file.txt"));
+    }
+
+    #[test]
+    fn invalid_globs_never_match() {
+        let filter = path_filter(&["[*.rs"], &["[broken"], &[]);
+        assert!(!filter.keeps("src/main.rs"));
+        assert!(filter.keeps_directory("src"));
+    }
+
+    #[test]
+    fn case_insensitive_matching_covers_every_list() {
+        let filter = PathFilter::with_case_sensitivity(
+            &patterns(&["*.md"]),
+            &patterns(&["docs/**"]),
+            &patterns(&["changelog"]),
+            false,
+        );
+        assert!(filter.keeps("README.MD"));
+        assert!(!filter.keeps("Docs/GUIDE.md"));
+        assert!(!filter.keeps("CHANGELOG.md"));
+
+        let filter = path_filter(&["*.md"], &[], &[]);
+        assert!(!filter.keeps("README.MD"));
+    }
+
+    #[test]
+    fn rules_display_with_source_and_negation() {
+        let filter = path_filter(&["!*.md"], &[], &[]);
+        let rule = filter.decide("README.md").rule.unwrap();
+        assert_eq!(rule.to_string(), "include !*.md");
+    }
+}
@@ -0,0 +1,190 @@
+//! Masking of personal data in what the server returns, for organizations whose policies keep
+//! it out of model inputs. Email addresses and phone numbers are found by their shape; names
+//! are the ones git metadata carries, remembered as providers read commits, and the ones in
+//! commit trailers such as "Signed-off-by".
+
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{
+        Arc, LazyLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use aho_corasick::{AhoCorasick, MatchKind};
+use anyhow::Result;
+use parking_lot::Mutex;
+use regex::{Captures, Regex};
+
+/// Names shorter than this are left alone, they are as likely to be words or initials
+const MIN_NAME_LENGTH: usize = 3;
+
+/// Names remembered at most, the oldest being forgotten first so long sessions stay bounded
+const MAX_NAMES: usize = 10_000;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static SCRUBBER: LazyLock<Scrubber> = LazyLock::new(Scrubber::default);
+
+static TRAILER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^([A-Za-z-]+-by:[ \t]*)([^<\n]+?)([ \t]*<)").expect("valid trailer pattern")
+});
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}")
+        .expect("valid email pattern")
+});
+
+static PHONE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\+\d{1,3}[ .-]?\(?\d{1,4}\)?(?:[ .-]?\d{2,4}){2,4}\b|\(\d{3}\) ?\d{3}-\d{4}\b|\b\d{3}-\d{3}-\d{4}\b",
+    )
+    .expect("valid phone pattern")
+});
+
+/// Turns scrubbing on for the rest of the session
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Passes the name of a commit author or committer through, remembering it to be masked
+/// wherever it appears once scrubbing is on
+pub fn author(name: String) -> String {
+    if is_enabled() {
+        SCRUBBER.remember(&name);
+    }
+    name
+}
+
+/// Masks the email addresses, phone numbers and known names of a text
+pub fn scrub(text: &str) -> Result<String> {
+    SCRUBBER.scrub(text)
+}
+
+/// The names met so far, and the automaton finding them, built again once they change
+#[derive(Default)]
+struct Names {
+    known: HashSet<String>,
+    order: VecDeque<String>,
+    matcher: Option<Arc<AhoCorasick>>,
+}
+
+/// Masks personal data, the names to mask being remembered as they are met
+#[derive(Default)]
+pub struct Scrubber {
+    names: Mutex<Names>,
+}
+
+impl Scrubber {
+    /// Remembers a name to mask, unless it is too short to tell apart from words
+    pub fn remember(&self, name: &str) {
+        let name = name.trim();
+        if name.chars().count() < MIN_NAME_LENGTH || name == "unknown" {
+            return;
+        }
+
+        let mut names = self.names.lock();
+        if !names.known.insert(name.to_string()) {
+            return;
+        }
+        names.order.push_back(name.to_string());
+        if names.order.len() > MAX_NAMES
+            && let Some(oldest) = names.order.pop_front()
+        {
+            names.known.remove(&oldest);
+        }
+        names.matcher = None;
+    }
+
+    /// Masks the email addresses, phone numbers and remembered names of a text
+    pub fn scrub(&self, text: &str) -> Result<String> {
+        let text = TRAILER.replace_all(text, |captures: &Captures| {
+            format!("{}[name]{}", &captures[1], &captures[3])
+        });
+        let text = EMAIL.replace_all(&text, "[email]");
+        let text = PHONE.replace_all(&text, "[phone]");
+
+        let Some(matcher) = self.matcher()? else {
+            return Ok(text.into_owned());
+        };
+
+        // Word boundaries only hold next to word characters, e.g. not after "[bot]"
+        let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let mut scrubbed = String::with_capacity(text.len());
+        let mut copied = 0;
+        for found in matcher.find_iter(text.as_ref()) {
+            let name = &text[found.range()];
+            let before = text[..found.start()].chars().next_back();
+            let after = text[found.end()..].chars().next();
+            if (is_word(name.chars().next()) && is_word(before))
+                || (is_word(name.chars().next_back()) && is_word(after))
+            {
+                continue;
+            }
+            scrubbed.push_str(&text[copied..found.start()]);
+            scrubbed.push_str("[name]");
+            copied = found.end();
+        }
+        scrubbed.push_str(&text[copied..]);
+
+        Ok(scrubbed)
+    }
+
+    /// The automaton finding the remembered names, none when no name is known yet
+    fn matcher(&self) -> Result<Option<Arc<AhoCorasick>>> {
+        let mut names = self.names.lock();
+        if names.order.is_empty() {
+            return Ok(None);
+        }
+        if names.matcher.is_none() {
+            // Longest first, so a full name is masked before the first name it starts with
+            let matcher = AhoCorasick::builder()
+                .match_kind(MatchKind::LeftmostLongest)
+                .build(&names.order)?;
+            names.matcher = Some(Arc::new(matcher));
+        }
+        Ok(names.matcher.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_emails_phones_and_author_names() {
+        let scrubber = Scrubber::default();
+        scrubber.remember("Jane Q. Doe");
+        scrubber.remember("dependabot[bot]");
+        scrubber.remember("Al");
+
+        let scrubbed = scrubber
+            .scrub(
+                "Fix parser (thanks Jane Q. Doe)\n\nCall +44 20 7946 0958 or (555) 123-4567.\nSigned-off-by: John Roe <john@example.com>\nVersion 1.2.3, 2024-01-01, 192.168.100.200\nBumped by dependabot[bot]s, Jane Q. Doesn't, Al",
+            )
+            .unwrap();
+
+        assert_eq!(
+            scrubbed,
+            "Fix parser (thanks [name])\n\nCall [phone] or [phone].\nSigned-off-by: [name] <[email]>\nVersion 1.2.3, 2024-01-01, 192.168.100.200\nBumped by [name]s, Jane Q. Doesn't, Al"
+        );
+    }
+
+    #[test]
+    fn forgets_the_oldest_names_past_the_limit() {
+        let scrubber = Scrubber::default();
+        for n in 0..=MAX_NAMES {
+            scrubber.remember(&format!("Author {n}"));
+        }
+
+        assert_eq!(scrubber.scrub("Author 0.").unwrap(), "Author 0.");
+        assert_eq!(
+            scrubber.scrub(&format!("Author {MAX_NAMES}.")).unwrap(),
+            "[name]."
+        );
+    }
+}
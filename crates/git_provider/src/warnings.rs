@@ -0,0 +1,111 @@
+//! Non-fatal issues met while serving a tool call: files left out, output cut short, budgets
+//! running out and fallbacks taken. Providers and tools record them as they happen, and the
+//! server hands them to the client alongside the result, so degraded output does not pass for
+//! complete output. Warnings belong to the task serving the call, concurrent calls and
+//! background refreshes keep their own.
+
+use std::cell::RefCell;
+
+/// What kind of degradation a warning reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningKind {
+    /// A file could not be read and is missing from the output
+    SkippedFile,
+    /// The output stops short of everything that matched
+    Truncated,
+    /// A host's rate limit lowered the amount of work done
+    RateLimited,
+    /// A backend failed and another one answered instead
+    Fallback,
+    /// The host left out results the token may not see, which the tools cannot tell
+    Withheld,
+}
+
+impl WarningKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarningKind::SkippedFile => "skipped_file",
+            WarningKind::Truncated => "truncated",
+            WarningKind::RateLimited => "rate_limited",
+            WarningKind::Fallback => "fallback",
+            WarningKind::Withheld => "withheld",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub message: String,
+}
+
+tokio::task_local! {
+    /// The warnings recorded by the tool call the current task serves
+    static WARNINGS: RefCell<Vec<Warning>>;
+}
+
+/// Runs `future` as one tool call, returning its output with the warnings it recorded, oldest
+/// first
+pub async fn collect<F: Future>(future: F) -> (F::Output, Vec<Warning>) {
+    WARNINGS
+        .scope(RefCell::new(Vec::new()), async {
+            let output = future.await;
+            (output, WARNINGS.with(|warnings| warnings.take()))
+        })
+        .await
+}
+
+/// Records a warning for the current tool call, once however many times it is met. Outside of
+/// a tool call, such as during background refreshes, it is only logged.
+pub fn warn(kind: WarningKind, message: impl Into<String>) {
+    let warning = Warning {
+        kind,
+        message: message.into(),
+    };
+
+    let logged = warning.clone();
+    let recorded = WARNINGS.try_with(|warnings| {
+        let mut warnings = warnings.borrow_mut();
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    });
+    if recorded.is_err() {
+        eprintln!("Warning ({}): {}", logged.kind.as_str(), logged.message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    #[test]
+    fn repeated_warnings_are_recorded_once() {
+        let message = "src/lib.rs: not valid UTF-8";
+        let ((), warnings) = block_on(collect(async {
+            warn(WarningKind::SkippedFile, message);
+            warn(WarningKind::SkippedFile, message);
+            warn(WarningKind::Truncated, message);
+        }));
+
+        let kinds: Vec<WarningKind> = warnings.into_iter().map(|warning| warning.kind).collect();
+        assert_eq!(kinds, [WarningKind::SkippedFile, WarningKind::Truncated]);
+    }
+
+    #[test]
+    fn warnings_belong_to_their_call() {
+        let ((), first) = block_on(collect(async {
+            warn(WarningKind::Truncated, "first call");
+        }));
+        let ((), second) = block_on(collect(async {
+            warn(WarningKind::Fallback, "second call");
+        }));
+
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].message, "first call");
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].message, "second call");
+    }
+}
@@ -0,0 +1,667 @@
+use std::{env, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use futures::future;
+use git_provider::{
+    CloneRemote, GitProvider, GitRef, MAX_README_SIZE, RepoItem, RepoItemType, RepoNode,
+    RepoSearchResult, RepositoryInfo, TreeOptions, depth_limited_directory,
+    encoding::{encode_component, encode_path},
+    ignore_patterns::DEFAULT_IGNORE_PATTERNS,
+    is_readme,
+    patterns::PathFilter,
+    provenance::MirrorOrigin,
+    readme_summary, render_tree,
+};
+use http_client::{HttpClient, Request, RequestBuilderExt, ResponseAsyncBodyExt, http::HeaderMap};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+const MAX_FILES: usize = 500;
+/// Used when GITEA_HOST is not set
+const DEFAULT_HOST: &str = "https://gitea.com";
+/// Entries requested per page of a recursive tree listing
+const TREE_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, serde::Deserialize)]
+struct GiteaRepository {
+    full_name: String,
+    #[serde(default)]
+    description: Option<String>,
+    html_url: String,
+    #[serde(default)]
+    default_branch: Option<String>,
+    #[serde(default)]
+    stars_count: u64,
+    #[serde(default)]
+    forks_count: u64,
+    #[serde(default)]
+    open_issues_count: Option<u64>,
+    #[serde(default)]
+    language: Option<String>,
+    /// Only reported by recent Gitea and Forgejo versions
+    #[serde(default)]
+    licenses: Vec<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+    #[serde(default)]
+    archived: bool,
+    /// In kilobytes
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    template: bool,
+    #[serde(default)]
+    parent: Option<Box<GiteaRepository>>,
+    /// Whether Gitea pulls the repository from another one
+    #[serde(default)]
+    mirror: bool,
+    #[serde(default)]
+    original_url: Option<String>,
+    created_at: String,
+    #[serde(default)]
+    updated_at: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GiteaSearchResponse {
+    #[serde(default)]
+    data: Vec<GiteaSearchItem>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GiteaSearchItem {
+    full_name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    stars_count: usize,
+}
+
+/// An entry of the contents API, either a directory listing item or a file with its content
+#[derive(Debug, serde::Deserialize)]
+struct GiteaContent {
+    name: String,
+    path: String,
+    #[serde(rename = "type")]
+    content_type: String,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    sha: Option<String>,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    encoding: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GiteaGitTree {
+    #[serde(default)]
+    tree: Vec<GiteaGitTreeEntry>,
+    #[serde(default)]
+    truncated: bool,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GiteaGitTreeEntry {
+    path: String,
+    #[serde(rename = "type")]
+    entry_type: String,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    sha: Option<String>,
+}
+
+/// Provider for Gitea and Forgejo instances, such as gitea.com or codeberg.org
+pub struct GiteaProvider {
+    http_client: Arc<dyn HttpClient>,
+    gitea_token: Option<String>,
+    name: String,
+    /// Base URL of the instance, without a trailing slash
+    host: String,
+}
+
+impl GiteaProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        let host = env::var("GITEA_HOST")
+            .ok()
+            .filter(|host| !host.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_HOST.to_string());
+
+        Self::with_host(http_client, "gitea", &host, env::var("GITEA_TOKEN").ok())
+    }
+
+    /// A provider for another Gitea or Forgejo instance, registered under its own name
+    pub fn with_host(
+        http_client: Arc<dyn HttpClient>,
+        name: &str,
+        host: &str,
+        gitea_token: Option<String>,
+    ) -> Self {
+        Self {
+            http_client,
+            gitea_token,
+            name: name.to_string(),
+            host: normalize_host(host),
+        }
+    }
+
+    /// Split a repository path into owner, repository and the branch of a "/src/branch/..." suffix
+    fn parse_repo_path(&self, repo_path: &str) -> Result<(String, String, Option<String>)> {
+        let parts: Vec<&str> = repo_path.trim_matches('/').split('/').collect();
+        if parts.len() < 2 || parts[0].is_empty() || parts[1].is_empty() {
+            return Err(anyhow!(
+                "Invalid repository path '{}', expected 'owner/repo'",
+                repo_path
+            ));
+        }
+
+        let branch = match parts.get(2..4) {
+            Some(["src", "branch"]) if parts.len() > 4 => Some(parts[4..].join("/")),
+            _ => None,
+        };
+
+        Ok((parts[0].to_string(), parts[1].to_string(), branch))
+    }
+
+    /// Base API URL of a repository
+    fn repo_url(&self, owner: &str, repo: &str) -> String {
+        format!(
+            "{}/api/v1/repos/{}/{}",
+            self.host,
+            encode_component(owner),
+            encode_component(repo)
+        )
+    }
+
+    fn api_headers(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.insert("User-Agent", "GitIngest-MCP".parse()?);
+        headers.insert("Accept", "application/json".parse()?);
+
+        if let Some(gitea_token) = &self.gitea_token {
+            headers.insert("Authorization", format!("token {}", gitea_token).parse()?);
+        }
+
+        Ok(headers)
+    }
+
+    /// Send an API request, failing with Gitea's error message on non-success statuses
+    async fn api_request<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let request = Request::builder()
+            .uri(url)
+            .method("GET")
+            .headers(self.api_headers()?)
+            .end()?;
+
+        let response = self.http_client.send(request).await?;
+        let status = response.status();
+        let response_text = response.text().await?;
+
+        if !status.is_success() {
+            let message = serde_json::from_str::<Value>(&response_text)
+                .ok()
+                .and_then(|v| v.get("message").and_then(|m| m.as_str()).map(String::from))
+                .unwrap_or(response_text);
+
+            return Err(anyhow!("Gitea API error ({}): {}", status, message));
+        }
+
+        Ok(serde_json::from_str(&response_text)?)
+    }
+
+    async fn fetch_repository(&self, owner: &str, repo: &str) -> Result<GiteaRepository> {
+        self.api_request(&self.repo_url(owner, repo)).await
+    }
+
+    /// Resolve an optional git reference to a name the API accepts
+    async fn resolve_ref_name(
+        &self,
+        owner: &str,
+        repo: &str,
+        git_ref: Option<GitRef>,
+    ) -> Result<String> {
+        match git_ref {
+            Some(GitRef::Branch(name)) | Some(GitRef::Tag(name)) | Some(GitRef::Commit(name)) => {
+                Ok(name)
+            }
+            Some(GitRef::Default) | None => self
+                .fetch_repository(owner, repo)
+                .await?
+                .default_branch
+                .ok_or_else(|| anyhow!("Repository {}/{} has no default branch", owner, repo)),
+        }
+    }
+
+    /// List the entries of a directory
+    async fn fetch_contents(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        ref_name: &str,
+    ) -> Result<Vec<RepoItem>> {
+        let contents: Vec<GiteaContent> = self
+            .api_request(&format!(
+                "{}/contents/{}?ref={}",
+                self.repo_url(owner, repo),
+                encode_path(path),
+                encode_component(ref_name)
+            ))
+            .await?;
+
+        let items = contents
+            .into_iter()
+            .map(|item| RepoItem {
+                name: item.name,
+                path: item.path,
+                item_type: match item.content_type.as_str() {
+                    "dir" => RepoItemType::Directory,
+                    _ => RepoItemType::File,
+                },
+                size: item.size,
+                sha: item.sha,
+            })
+            .collect();
+
+        Ok(items)
+    }
+
+    async fn fetch_file_content(
+        &self,
+        owner: &str,
+        repo: &str,
+        file_path: &str,
+        ref_name: &str,
+    ) -> Result<String> {
+        let file: GiteaContent = self
+            .api_request(&format!(
+                "{}/contents/{}?ref={}",
+                self.repo_url(owner, repo),
+                encode_path(file_path),
+                encode_component(ref_name)
+            ))
+            .await?;
+
+        if file.content_type != "file" {
+            return Err(anyhow!("{} is not a file", file_path));
+        }
+
+        let content = file.content.unwrap_or_default();
+        match file.encoding.as_deref() {
+            Some("base64") => {
+                let content_bytes = base64::decode(content.replace('\n', ""))?;
+                Ok(String::from_utf8(content_bytes)?)
+            }
+            _ => Ok(content),
+        }
+    }
+
+    fn ignore_patterns(&self, options: &TreeOptions) -> Vec<String> {
+        match &options.ignore_patterns {
+            Some(ignore_patterns) => ignore_patterns.clone(),
+            None => DEFAULT_IGNORE_PATTERNS
+                .iter()
+                .map(|&s| s.to_string())
+                .collect(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn build_tree(
+        &self,
+        owner: String,
+        repo: String,
+        ref_name: String,
+        path: String,
+        options: TreeOptions,
+        ignore_patterns: Vec<String>,
+        depth: usize,
+        max_depth: usize,
+    ) -> Result<RepoNode> {
+        if depth > max_depth {
+            return Ok(depth_limited_directory(
+                path.split('/').next_back().unwrap_or(&path),
+            ));
+        }
+
+        let contents = self.fetch_contents(&owner, &repo, &path, &ref_name).await?;
+
+        let description = if options.describe_directories {
+            self.fetch_readme_summary(&owner, &repo, &ref_name, &contents)
+                .await
+        } else {
+            None
+        };
+
+        let mut children = Vec::new();
+        let mut file_count = 0;
+        let mut dir_count = 1; // Count self
+        let mut total_size = 0;
+
+        let mut tasks = Vec::new();
+
+        let filter = PathFilter::from_options(&options, &ignore_patterns);
+        for item in contents {
+            let keep = match item.item_type {
+                RepoItemType::File => filter.keeps(&item.path),
+                RepoItemType::Directory => filter.keeps_directory(&item.path),
+            };
+            if !keep {
+                continue;
+            }
+
+            match item.item_type {
+                RepoItemType::File => {
+                    let size = item.size.unwrap_or(0);
+                    total_size += size;
+                    file_count += 1;
+
+                    children.push(RepoNode {
+                        name: item.name,
+                        node_type: RepoItemType::File,
+                        size,
+                        children: vec![],
+                        file_count: 1,
+                        dir_count: 0,
+                        description: None,
+                    });
+                }
+                RepoItemType::Directory => {
+                    tasks.push(self.build_tree(
+                        owner.clone(),
+                        repo.clone(),
+                        ref_name.clone(),
+                        item.path,
+                        options.clone(),
+                        ignore_patterns.clone(),
+                        depth + 1,
+                        max_depth,
+                    ));
+                }
+            }
+
+            // Check file limit
+            if file_count > MAX_FILES {
+                break;
+            }
+        }
+
+        let results = future::join_all(tasks).await;
+        for result in results {
+            match result {
+                Ok(child_node) => {
+                    file_count += child_node.file_count;
+                    dir_count += child_node.dir_count;
+                    total_size += child_node.size;
+                    children.push(child_node);
+                }
+                Err(e) => eprintln!("Error building tree: {:?}", e),
+            }
+        }
+
+        // Sort children: directories first, then files, both alphabetically
+        children.sort_by(|a, b| match (a.node_type, b.node_type) {
+            (RepoItemType::Directory, RepoItemType::File) => std::cmp::Ordering::Less,
+            (RepoItemType::File, RepoItemType::Directory) => std::cmp::Ordering::Greater,
+            _ => a.name.cmp(&b.name),
+        });
+
+        Ok(RepoNode {
+            name: if path.is_empty() {
+                "root".to_string()
+            } else {
+                path.split('/').next_back().unwrap_or(&path).to_string()
+            },
+            node_type: RepoItemType::Directory,
+            size: total_size,
+            children,
+            file_count,
+            dir_count,
+            description,
+        })
+    }
+
+    /// Summarize the README found among a directory's entries, if any
+    async fn fetch_readme_summary(
+        &self,
+        owner: &str,
+        repo: &str,
+        ref_name: &str,
+        contents: &[RepoItem],
+    ) -> Option<String> {
+        let readme = contents.iter().find(|item| {
+            item.item_type == RepoItemType::File
+                && is_readme(&item.name)
+                && item.size.unwrap_or(0) <= MAX_README_SIZE
+        })?;
+
+        let content = self
+            .fetch_file_content(owner, repo, &readme.path, ref_name)
+            .await
+            .ok()?;
+
+        readme_summary(&content)
+    }
+}
+
+/// Base URL of an instance, defaulting to HTTPS when no scheme is given
+fn normalize_host(host: &str) -> String {
+    let host = host.trim().trim_end_matches('/');
+    if host.starts_with("http://") || host.starts_with("https://") {
+        host.to_string()
+    } else {
+        format!("https://{}", host)
+    }
+}
+
+#[async_trait]
+impl GitProvider for GiteaProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> &str {
+        "gitea"
+    }
+
+    fn web_url(&self) -> Option<String> {
+        Some(self.host.clone())
+    }
+
+    fn is_anonymous(&self) -> bool {
+        self.gitea_token.is_none()
+    }
+
+    async fn get_tree_structure(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: TreeOptions,
+    ) -> Result<String> {
+        let (owner, repo, path_branch) = self.parse_repo_path(repo_path)?;
+        let git_ref = git_ref.or(path_branch.map(GitRef::Branch));
+        let ref_name = self.resolve_ref_name(&owner, &repo, git_ref).await?;
+        let ignore_patterns = self.ignore_patterns(&options);
+
+        // Build repository tree
+        let max_depth = options.max_depth();
+        let root_node = self
+            .build_tree(
+                owner,
+                repo.clone(),
+                ref_name,
+                options.subtree().to_string(),
+                options.clone(),
+                ignore_patterns,
+                0,
+                max_depth,
+            )
+            .await?;
+
+        let tree_node = RepoNode {
+            name: options.root_name(&repo),
+            node_type: RepoItemType::Directory,
+            size: root_node.size,
+            children: root_node.children,
+            file_count: root_node.file_count,
+            dir_count: root_node.dir_count,
+            description: root_node.description,
+        };
+
+        Ok(render_tree(&tree_node, &options))
+    }
+
+    async fn get_file_content(
+        &self,
+        repo_path: &str,
+        file_path: &str,
+        git_ref: Option<GitRef>,
+    ) -> Result<String> {
+        let (owner, repo, path_branch) = self.parse_repo_path(repo_path)?;
+        let git_ref = git_ref.or(path_branch.map(GitRef::Branch));
+        let ref_name = self.resolve_ref_name(&owner, &repo, git_ref).await?;
+
+        self.fetch_file_content(&owner, &repo, file_path, &ref_name)
+            .await
+    }
+
+    async fn find_repositories(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<RepoSearchResult>> {
+        if query.trim().is_empty() {
+            return Err(anyhow!("Empty search query is not allowed"));
+        }
+
+        let mut url = format!(
+            "{}/api/v1/repos/search?q={}&sort=stars&order=desc",
+            self.host,
+            encode_component(query)
+        );
+        if let Some(limit) = limit {
+            // Instances cap pages at 50 items unless configured otherwise
+            url.push_str(&format!("&limit={}", limit.min(50)));
+        }
+
+        let response: GiteaSearchResponse = self.api_request(&url).await?;
+
+        let results = response
+            .data
+            .into_iter()
+            .map(|repo| RepoSearchResult {
+                provider: self.name.clone(),
+                full_name: repo.full_name,
+                description: repo.description.filter(|d| !d.is_empty()),
+                stargazers_count: repo.stars_count,
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    async fn list_files(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: &TreeOptions,
+    ) -> Result<Vec<RepoItem>> {
+        let (owner, repo, path_branch) = self.parse_repo_path(repo_path)?;
+        let git_ref = git_ref.or(path_branch.map(GitRef::Branch));
+        let ref_name = self.resolve_ref_name(&owner, &repo, git_ref).await?;
+        let ignore_patterns = self.ignore_patterns(options);
+        let filter = PathFilter::from_options(options, &ignore_patterns);
+
+        // Recursive listings are paginated, `truncated` tells whether more pages follow
+        let mut files = Vec::new();
+        for page in 1..=50 {
+            let tree: GiteaGitTree = self
+                .api_request(&format!(
+                    "{}/git/trees/{}?recursive=true&per_page={}&page={}",
+                    self.repo_url(&owner, &repo),
+                    encode_component(&ref_name),
+                    TREE_PAGE_SIZE,
+                    page
+                ))
+                .await?;
+
+            files.extend(
+                tree.tree
+                    .into_iter()
+                    .filter(|entry| entry.entry_type == "blob")
+                    .filter(|entry| filter.keeps(&entry.path))
+                    .map(|entry| RepoItem {
+                        name: entry
+                            .path
+                            .rsplit('/')
+                            .next()
+                            .unwrap_or(&entry.path)
+                            .to_string(),
+                        path: entry.path,
+                        item_type: RepoItemType::File,
+                        size: entry.size,
+                        sha: entry.sha,
+                    }),
+            );
+
+            if !tree.truncated {
+                break;
+            }
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(files)
+    }
+
+    async fn get_repository_info(&self, repo_path: &str) -> Result<RepositoryInfo> {
+        let (owner, repo, _) = self.parse_repo_path(repo_path)?;
+        let info = self.fetch_repository(&owner, &repo).await?;
+
+        let description = info.description.filter(|d| !d.is_empty());
+        let mirror = if info.mirror {
+            Some(MirrorOrigin::flagged(info.original_url.as_deref()))
+        } else {
+            description
+                .as_deref()
+                .and_then(MirrorOrigin::from_description)
+        };
+
+        Ok(RepositoryInfo {
+            full_name: info.full_name,
+            description,
+            url: info.html_url,
+            default_branch: info.default_branch,
+            stars: info.stars_count,
+            forks: info.forks_count,
+            open_issues: info.open_issues_count,
+            language: info.language.filter(|l| !l.is_empty()),
+            license: info.licenses.into_iter().next(),
+            topics: info.topics,
+            archived: info.archived,
+            size_kb: info.size,
+            is_template: info.template,
+            // Gitea does not record which template a repository was generated from
+            generated_from: None,
+            forked_from: info.parent.map(|parent| parent.full_name),
+            mirror,
+            created_at: info.created_at,
+            last_activity_at: info.updated_at,
+        })
+    }
+
+    async fn clone_remote(&self, repo_path: &str) -> Result<CloneRemote> {
+        let (owner, repo, _) = self.parse_repo_path(repo_path)?;
+
+        Ok(CloneRemote {
+            url: format!("{}/{}/{}.git", self.host, owner, repo),
+            // Access tokens authenticate git over HTTPS with any user name
+            credentials: self
+                .gitea_token
+                .clone()
+                .map(|token| ("oauth2".to_string(), token)),
+        })
+    }
+}
@@ -0,0 +1,386 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use git_provider::{
+    CloneRemote, CommitInfo, GitProvider, GitRef, RepoItem, RepoItemType, RepoNode,
+    RepoSearchResult, RepositoryInfo, TreeOptions, create_tree_structure,
+    encoding::encode_component, ignore_patterns::DEFAULT_IGNORE_PATTERNS, patterns::PathFilter,
+};
+use http_client::{HttpClient, Request, RequestBuilderExt, ResponseAsyncBodyExt};
+
+use crate::GitHubProvider;
+
+#[derive(Debug, serde::Deserialize)]
+struct Gist {
+    id: String,
+    #[serde(default)]
+    description: Option<String>,
+    html_url: String,
+    #[serde(default)]
+    owner: Option<GistOwner>,
+    files: BTreeMap<String, GistFile>,
+    #[serde(default)]
+    history: Vec<GistRevision>,
+    #[serde(default)]
+    forks: Vec<serde_json::Value>,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GistOwner {
+    login: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GistFile {
+    #[serde(default)]
+    size: u64,
+    raw_url: String,
+    #[serde(default)]
+    language: Option<String>,
+    /// Contents over a megabyte are cut, the full file is then read from `raw_url`
+    #[serde(default)]
+    truncated: bool,
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GistRevision {
+    version: String,
+    committed_at: String,
+    #[serde(default)]
+    user: Option<GistOwner>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GistSummary {
+    id: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// Provider for GitHub Gists, identified as "github-gist:user/gist_id". A gist is a flat list
+/// of files whose revisions are addressed as commits.
+pub struct GistProvider {
+    github: GitHubProvider,
+}
+
+impl GistProvider {
+    /// Gists of the host the "github" provider talks to
+    pub fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        Self {
+            github: GitHubProvider::new(http_client),
+        }
+    }
+
+    /// Split a gist path into the owner and the gist id
+    fn parse_gist_path<'a>(&self, repo_path: &'a str) -> Result<(&'a str, &'a str)> {
+        match repo_path.trim_matches('/').split('/').collect::<Vec<_>>()[..] {
+            [owner, id] if !owner.is_empty() && !id.is_empty() => Ok((owner, id)),
+            _ => Err(anyhow!(
+                "Invalid gist path '{}'. Expected 'user/gist_id'",
+                repo_path
+            )),
+        }
+    }
+
+    /// Fetch a gist at its latest revision, or at the revision a commit reference names
+    async fn fetch_gist(&self, repo_path: &str, git_ref: Option<GitRef>) -> Result<Gist> {
+        let (owner, id) = self.parse_gist_path(repo_path)?;
+        let url = match git_ref {
+            Some(GitRef::Commit(revision)) => format!(
+                "{}/gists/{}/{}",
+                self.github.api_base,
+                encode_component(id),
+                encode_component(&revision)
+            ),
+            Some(GitRef::Default) | None => {
+                format!("{}/gists/{}", self.github.api_base, encode_component(id))
+            }
+            Some(GitRef::Branch(_)) | Some(GitRef::Tag(_)) => {
+                return Err(anyhow!(
+                    "Gists have no branches or tags, pass a revision as 'commit:<sha>'"
+                ));
+            }
+        };
+
+        let gist: Gist = self.github.api_request("GET", &url, None).await?;
+
+        // The id alone finds the gist, a mismatching owner means a wrong identifier
+        if let Some(gist_owner) = &gist.owner
+            && !gist_owner.login.eq_ignore_ascii_case(owner)
+        {
+            return Err(anyhow!(
+                "Gist {} belongs to {}, not {}",
+                id,
+                gist_owner.login,
+                owner
+            ));
+        }
+
+        Ok(gist)
+    }
+
+    /// The files of a gist kept by the filter, sorted by name
+    fn filtered_files<'a>(
+        &self,
+        gist: &'a Gist,
+        options: &TreeOptions,
+    ) -> Vec<(&'a String, &'a GistFile)> {
+        let ignore_patterns = match &options.ignore_patterns {
+            Some(ignore_patterns) => ignore_patterns.clone(),
+            None => DEFAULT_IGNORE_PATTERNS
+                .iter()
+                .map(|&s| s.to_string())
+                .collect(),
+        };
+        let filter = PathFilter::from_options(options, &ignore_patterns);
+
+        gist.files
+            .iter()
+            .filter(|(name, _)| filter.keeps(name))
+            .collect()
+    }
+
+    /// The web host gists are browsed on: gist.github.com, or /gist of an Enterprise Server
+    fn gist_host(&self) -> String {
+        if self.github.web_base == "https://github.com" {
+            "https://gist.github.com".to_string()
+        } else {
+            format!("{}/gist", self.github.web_base)
+        }
+    }
+}
+
+#[async_trait]
+impl GitProvider for GistProvider {
+    fn name(&self) -> &str {
+        "github-gist"
+    }
+
+    fn web_url(&self) -> Option<String> {
+        Some(self.gist_host())
+    }
+
+    fn is_anonymous(&self) -> bool {
+        self.github.is_anonymous()
+    }
+
+    async fn get_tree_structure(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: TreeOptions,
+    ) -> Result<String> {
+        let gist = self.fetch_gist(repo_path, git_ref).await?;
+
+        let children: Vec<RepoNode> = self
+            .filtered_files(&gist, &options)
+            .into_iter()
+            .map(|(name, file)| RepoNode {
+                name: name.clone(),
+                node_type: RepoItemType::File,
+                size: file.size,
+                children: vec![],
+                file_count: 1,
+                dir_count: 0,
+                description: None,
+            })
+            .collect();
+
+        let tree_node = RepoNode {
+            name: gist.id.clone(),
+            node_type: RepoItemType::Directory,
+            size: children.iter().map(|child| child.size).sum(),
+            file_count: children.len(),
+            dir_count: 1,
+            children,
+            description: options
+                .describe_directories
+                .then(|| gist.description.clone())
+                .flatten()
+                .filter(|description| !description.is_empty()),
+        };
+
+        Ok(create_tree_structure(&tree_node, "", true))
+    }
+
+    async fn get_file_content(
+        &self,
+        repo_path: &str,
+        file_path: &str,
+        git_ref: Option<GitRef>,
+    ) -> Result<String> {
+        let gist = self.fetch_gist(repo_path, git_ref).await?;
+        let file = gist
+            .files
+            .get(file_path.trim_start_matches('/'))
+            .ok_or_else(|| anyhow!("File {} not found in gist {}", file_path, gist.id))?;
+
+        match &file.content {
+            Some(content) if !file.truncated => Ok(content.clone()),
+            _ => {
+                let response = self
+                    .github
+                    .send(
+                        Request::builder()
+                            .uri(&file.raw_url)
+                            .method("GET")
+                            .headers(self.github.api_headers()?)
+                            .end()?,
+                    )
+                    .await?;
+
+                let status = response.status();
+                if !status.is_success() {
+                    return Err(anyhow!("GitHub error ({}) reading {}", status, file_path));
+                }
+                Ok(response.text().await?)
+            }
+        }
+    }
+
+    async fn find_repositories(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<RepoSearchResult>> {
+        // Gists cannot be searched through the API, the query names the user to list
+        let user = query.trim();
+        if user.is_empty() || user.contains(char::is_whitespace) {
+            return Err(anyhow!(
+                "Gists are listed by user, pass a GitHub user name as the query"
+            ));
+        }
+
+        let gists: Vec<GistSummary> = self
+            .github
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/users/{}/gists?per_page={}",
+                    self.github.api_base,
+                    encode_component(user),
+                    limit.unwrap_or(30).clamp(1, 100)
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(gists
+            .into_iter()
+            .map(|gist| RepoSearchResult {
+                provider: self.name().to_string(),
+                full_name: format!("{}/{}", user, gist.id),
+                description: gist.description.filter(|d| !d.is_empty()),
+                stargazers_count: 0,
+            })
+            .collect())
+    }
+
+    async fn list_files(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: &TreeOptions,
+    ) -> Result<Vec<RepoItem>> {
+        let gist = self.fetch_gist(repo_path, git_ref).await?;
+
+        Ok(self
+            .filtered_files(&gist, options)
+            .into_iter()
+            .map(|(name, file)| RepoItem {
+                name: name.clone(),
+                path: name.clone(),
+                item_type: RepoItemType::File,
+                size: Some(file.size),
+                sha: None,
+            })
+            .collect())
+    }
+
+    async fn get_repository_info(&self, repo_path: &str) -> Result<RepositoryInfo> {
+        let gist = self.fetch_gist(repo_path, None).await?;
+        let (owner, _) = self.parse_gist_path(repo_path)?;
+
+        // The language of the largest file stands for the gist's
+        let language = gist
+            .files
+            .values()
+            .filter(|file| file.language.is_some())
+            .max_by_key(|file| file.size)
+            .and_then(|file| file.language.clone());
+
+        Ok(RepositoryInfo {
+            full_name: format!(
+                "{}/{}",
+                gist.owner.as_ref().map_or(owner, |o| o.login.as_str()),
+                gist.id
+            ),
+            description: gist.description.filter(|d| !d.is_empty()),
+            url: gist.html_url,
+            default_branch: None,
+            stars: 0,
+            forks: gist.forks.len() as u64,
+            open_issues: None,
+            language,
+            license: None,
+            topics: vec![],
+            archived: false,
+            size_kb: Some(gist.files.values().map(|file| file.size).sum::<u64>() / 1024),
+            is_template: false,
+            generated_from: None,
+            forked_from: None,
+            mirror: None,
+            created_at: gist.created_at,
+            last_activity_at: Some(gist.updated_at),
+        })
+    }
+
+    async fn list_commits(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        path: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        if path.is_some() {
+            return Err(anyhow!("Gist revisions cannot be filtered by file"));
+        }
+        let gist = self.fetch_gist(repo_path, git_ref).await?;
+
+        // Revisions have no message, the API lists them newest first
+        Ok(gist
+            .history
+            .into_iter()
+            .take(limit)
+            .map(|revision| CommitInfo {
+                sha: revision.version,
+                message: String::new(),
+                author: revision
+                    .user
+                    .map(|user| user.login)
+                    .unwrap_or_else(|| "unknown".to_string()),
+                author_email: None,
+                date: revision.committed_at,
+                verification: None,
+            })
+            .collect())
+    }
+
+    async fn clone_remote(&self, repo_path: &str) -> Result<CloneRemote> {
+        let (_, id) = self.parse_gist_path(repo_path)?;
+
+        Ok(CloneRemote {
+            url: format!("{}/{}.git", self.gist_host(), id),
+            credentials: self
+                .github
+                .github_token
+                .clone()
+                .map(|token| ("x-access-token".to_string(), token)),
+        })
+    }
+}
@@ -1,16 +1,45 @@
-use std::{env, sync::Arc};
+mod gist;
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    env,
+    sync::Arc,
+};
 
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use futures::future::join_all;
 use git_provider::{
-    GitProvider, GitRef, RepoItem, RepoItemType, RepoNode, RepoSearchResult, create_tree_structure,
+    BoardColumn, BoardItem, BranchDivergence, ChangedFile, CheckAnnotation, CiArtifact,
+    CloneRemote, CommentTarget, CommitDetails, CommitInfo, CompareMode, ContributorInfo,
+    FileChange, FileStatus, GitProvider, GitRef, IssueComment, IssueDetails, IssueDraft,
+    IssueFilter, LanguageShare, LicenseFile, MAX_README_SIZE, Milestone, ProjectBoard,
+    PullRequestDetails, PullRequestDraft, PullRequestSummary, Readme, RefComparison, ReleaseInfo,
+    RepoEvent, RepoEventKind, RepoItem, RepoItemType, RepoLabel, RepoNode, RepoSearchResult,
+    RepositoryInfo, ReviewComment, SignatureVerification, TagInfo, TreeOptions,
+    api_compat::{self, Api},
+    depth_limited_directory,
+    encoding::{encode_component, encode_path},
     ignore_patterns::DEFAULT_IGNORE_PATTERNS,
+    is_readme,
+    patterns::PathFilter,
+    pii,
+    provenance::MirrorOrigin,
+    readme_summary, render_tree,
+    warnings::{self, WarningKind},
 };
-use http_client::{HttpClient, Request, RequestBuilderExt, ResponseAsyncBodyExt, http::HeaderMap};
+use http_client::{
+    AsyncBody, HttpClient, Request, RequestBuilderExt, Response, ResponseAsyncBodyExt,
+    http::{HeaderMap, StatusCode},
+};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+pub use crate::gist::GistProvider;
 
 // GitHub search repositories API response model
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Default, serde::Deserialize)]
 struct GitHubSearchRepoResponse {
     items: Vec<GitHubRepoItem>,
 }
@@ -25,6 +54,16 @@ struct GitHubRepoItem {
 
 const MAX_FILES: usize = 500;
 
+/// Git modes of the blobs a commit may write: regular files, executables and symbolic links
+const BLOB_MODES: &[&str] = &["100644", "100755", "120000"];
+const DEFAULT_BLOB_MODE: &str = "100644";
+
+/// Prefix of fine-grained personal access tokens, classic ones start with "ghp_"
+const FINE_GRAINED_TOKEN_PREFIX: &str = "github_pat_";
+
+/// Pages of open issues, a hundred each, over which label usage is counted
+const MAX_LABEL_COUNT_PAGES: usize = 10;
+
 #[derive(Debug, serde::Deserialize)]
 struct GitHubContent {
     #[serde(default)]
@@ -35,6 +74,8 @@ struct GitHubContent {
     content_type: String,
     #[serde(default)]
     size: Option<u64>,
+    #[serde(default)]
+    sha: Option<String>,
 }
 
 // GitHub API can return either an array of contents or a single content object
@@ -46,622 +87,3073 @@ enum GitHubContentResponse {
 }
 
 #[derive(Debug, serde::Deserialize)]
-struct GitHubRepo {
-    default_branch: String,
-    // Other fields are not needed for tree structure
+struct GitHubCheckRuns {
+    check_runs: Vec<GitHubCheckRun>,
 }
 
-pub struct GitHubProvider {
-    http_client: Arc<dyn HttpClient>,
-    github_token: Option<String>,
+#[derive(Debug, serde::Deserialize)]
+struct GitHubCheckRun {
+    id: u64,
+    name: String,
+    #[serde(default)]
+    output: Option<GitHubCheckRunOutput>,
 }
 
-impl GitHubProvider {
-    pub fn new(http_client: Arc<dyn HttpClient>) -> Self {
-        Self {
-            http_client,
-            github_token: env::var("GITHUB_TOKEN").ok(),
-        }
-    }
+#[derive(Debug, serde::Deserialize)]
+struct GitHubCheckRunOutput {
+    #[serde(default)]
+    annotations_count: u64,
+}
 
-    async fn search_repositories(
-        &self,
-        query: &str,
-        limit: Option<usize>,
-    ) -> Result<Vec<GitHubRepoItem>> {
-        // Check for empty query
-        if query.trim().is_empty() {
-            return Err(anyhow!("Empty search query is not allowed"));
-        }
+#[derive(Debug, serde::Deserialize)]
+struct GitHubCheckAnnotation {
+    path: String,
+    start_line: u64,
+    end_line: u64,
+    #[serde(default)]
+    annotation_level: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    message: String,
+}
 
-        let encoded_query = urlencoding::encode(query);
-        let mut url = format!(
-            "https://api.github.com/search/repositories?q={}",
-            encoded_query
-        );
-        eprintln!("Searching GitHub repositories with URL: {}", url);
+#[derive(Debug, serde::Deserialize)]
+struct GitHubArtifacts {
+    artifacts: Vec<GitHubArtifact>,
+}
 
-        // Add per_page parameter if limit is provided
-        if let Some(per_page) = limit {
-            url.push_str(&format!("&per_page={}", per_page.min(100))); // GitHub API limits to 100 per page
-            eprintln!("Limited results to {} repositories", per_page.min(100));
-        }
+#[derive(Debug, serde::Deserialize)]
+struct GitHubArtifact {
+    id: u64,
+    name: String,
+    #[serde(default)]
+    size_in_bytes: Option<u64>,
+    #[serde(default)]
+    expired: bool,
+}
 
-        let mut headers = HeaderMap::new();
-        headers.insert("User-Agent", "GitIngest-MCP-Agent/1.0".parse()?);
-        headers.insert("Accept", "application/vnd.github+json".parse()?);
-        headers.insert("X-GitHub-Api-Version", "2022-11-28".parse()?);
+#[derive(Debug, serde::Deserialize)]
+struct GitHubEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    actor: GitHubEventActor,
+    created_at: String,
+    #[serde(default)]
+    payload: Value,
+}
 
-        if let Some(github_token) = &self.github_token {
-            headers.insert("Authorization", format!("Bearer {}", github_token).parse()?);
-            eprintln!("Using GitHub token for authentication");
-        } else {
-            eprintln!("No GitHub token provided - API rate limits may apply");
-        }
+#[derive(Debug, serde::Deserialize)]
+struct GitHubEventActor {
+    login: String,
+}
 
-        eprintln!("Sending request to GitHub API... {:?}", headers);
-        let response = self
-            .http_client
-            .send(
-                Request::builder()
-                    .uri(&url)
-                    .method("GET")
-                    .headers(headers)
-                    .end()?,
-            )
-            .await?;
-        eprintln!("Received response with status: {}", response.status());
+impl GitHubEvent {
+    fn into_repo_event(self) -> RepoEvent {
+        let payload = &self.payload;
+        let text = |pointer: &str| {
+            payload
+                .pointer(pointer)
+                .map(|v| match v {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .unwrap_or_default()
+        };
 
-        // Check response status
-        if !response.status().is_success() {
-            return match response.status().as_u16() {
-                422 => Err(anyhow!("Invalid query syntax or empty query")),
-                403 => Err(anyhow!("GitHub API rate limit exceeded or access denied")),
-                404 => Err(anyhow!("Resource not found")),
-                _ => Err(anyhow!("GitHub API error: {}", response.status())),
-            };
+        let (kind, summary) = match self.event_type.as_str() {
+            "PushEvent" => (
+                RepoEventKind::Push,
+                format!(
+                    "pushed {} commit(s) to {}",
+                    payload
+                        .get("size")
+                        .and_then(|s| s.as_u64())
+                        .unwrap_or_default(),
+                    text("/ref").trim_start_matches("refs/heads/")
+                ),
+            ),
+            "ReleaseEvent" => (
+                RepoEventKind::Release,
+                format!("{} release {}", text("/action"), text("/release/tag_name")),
+            ),
+            "IssuesEvent" => (
+                RepoEventKind::Issue,
+                format!(
+                    "{} issue #{}: {}",
+                    text("/action"),
+                    text("/issue/number"),
+                    text("/issue/title")
+                ),
+            ),
+            "PullRequestEvent" => {
+                let merged = payload
+                    .pointer("/pull_request/merged")
+                    .and_then(|m| m.as_bool())
+                    .unwrap_or(false);
+                let action = if text("/action") == "closed" && merged {
+                    "merged".to_string()
+                } else {
+                    text("/action")
+                };
+
+                (
+                    RepoEventKind::PullRequest,
+                    format!(
+                        "{} pull request #{}: {}",
+                        action,
+                        text("/number"),
+                        text("/pull_request/title")
+                    ),
+                )
+            }
+            "IssueCommentEvent" | "PullRequestReviewCommentEvent" | "CommitCommentEvent" => (
+                RepoEventKind::Comment,
+                match payload.pointer("/issue/number") {
+                    Some(number) => format!("commented on #{}: {}", number, text("/issue/title")),
+                    None => "commented".to_string(),
+                },
+            ),
+            "PullRequestReviewEvent" => (
+                RepoEventKind::Comment,
+                format!(
+                    "reviewed pull request #{}: {}",
+                    text("/pull_request/number"),
+                    text("/pull_request/title")
+                ),
+            ),
+            "CreateEvent" | "DeleteEvent" => (
+                RepoEventKind::Ref,
+                format!(
+                    "{} {} {}",
+                    if self.event_type == "CreateEvent" {
+                        "created"
+                    } else {
+                        "deleted"
+                    },
+                    text("/ref_type"),
+                    text("/ref")
+                ),
+            ),
+            other => (
+                RepoEventKind::Other,
+                other.trim_end_matches("Event").to_lowercase(),
+            ),
+        };
+
+        RepoEvent {
+            kind,
+            actor: self.actor.login,
+            created_at: self.created_at,
+            summary,
         }
+    }
+}
 
-        let response_text = response.text().await?;
+#[derive(Debug, serde::Deserialize)]
+struct GitHubIssueSearchResponse {
+    items: Vec<GitHubIssueItem>,
+}
 
-        // Parse the search response
-        let search_response: Result<GitHubSearchRepoResponse, _> =
-            serde_json::from_str(&response_text);
+#[derive(Debug, serde::Deserialize)]
+struct GitHubIssueItem {
+    number: u64,
+    title: String,
+    user: GitHubEventActor,
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    labels: Vec<GitHubLabelRef>,
+    html_url: String,
+    updated_at: String,
+    repository_url: String,
+}
 
-        match search_response {
-            Ok(response) => Ok(response.items),
-            Err(e) => {
-                // Check for common API errors
-                if response_text.contains("rate limit") {
-                    return Err(anyhow!(
-                        "GitHub API rate limit exceeded. Consider adding a GITHUB_TOKEN"
-                    ));
-                }
+#[derive(Debug, serde::Deserialize)]
+struct GitHubLabelRef {
+    name: String,
+}
 
-                // Return empty vector for empty results to avoid breaking tests
-                if response_text.contains("\"items\":[]") {
-                    return Ok(Vec::new());
-                }
+#[derive(Debug, serde::Deserialize)]
+struct GitHubIssue {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    user: GitHubEventActor,
+    #[serde(default)]
+    labels: Vec<GitHubLabelRef>,
+    html_url: String,
+    created_at: String,
+    /// Set on the pull requests the issues endpoints list alongside issues
+    #[serde(default)]
+    pull_request: Option<Value>,
+}
 
-                Err(anyhow!(
-                    "Failed to parse GitHub repository search API response: {}",
-                    e
-                ))
-            }
+impl GitHubIssue {
+    fn into_issue_details(self) -> IssueDetails {
+        IssueDetails {
+            number: self.number,
+            title: self.title,
+            body: self.body.filter(|b| !b.is_empty()),
+            state: self.state,
+            author: self.user.login,
+            labels: self.labels.into_iter().map(|l| l.name).collect(),
+            url: self.html_url,
+            created_at: self.created_at,
         }
     }
+}
 
-    async fn fetch_repo_metadata(&self, owner: &str, repo: &str) -> Result<GitHubRepo> {
-        let url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+#[derive(Debug, serde::Deserialize)]
+struct GitHubReadme {
+    path: String,
+    /// Base64, wrapped over several lines
+    content: String,
+}
 
-        let mut headers = HeaderMap::new();
-        headers.insert("User-Agent", "GitIngest-MCP-Agent/1.0".parse()?);
-        headers.insert("Accept", "application/vnd.github+json".parse()?);
-        headers.insert("X-GitHub-Api-Version", "2022-11-28".parse()?);
+#[derive(Debug, serde::Deserialize)]
+struct GitHubLicenseFile {
+    path: String,
+    /// Base64, wrapped over several lines
+    content: String,
+    license: Option<GitHubLicense>,
+}
 
-        if let Some(github_token) = &self.github_token {
-            headers.insert("Authorization", format!("Bearer {}", github_token).parse()?);
-        }
+#[derive(Debug, serde::Deserialize)]
+struct GitHubIssueComment {
+    user: GitHubEventActor,
+    #[serde(default)]
+    body: Option<String>,
+    created_at: String,
+    #[serde(default)]
+    updated_at: Option<String>,
+}
 
-        let response = self
-            .http_client
-            .send(
-                Request::builder()
-                    .uri(&url)
-                    .method("GET")
-                    .headers(headers)
-                    .end()?,
-            )
-            .await?;
+#[derive(Debug, serde::Deserialize)]
+struct GitHubPullRequest {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    merged: bool,
+    /// Listings carry the merge date rather than the flag
+    #[serde(default)]
+    merged_at: Option<String>,
+    user: GitHubEventActor,
+    head: GitHubBranchRef,
+    base: GitHubBranchRef,
+    #[serde(default)]
+    labels: Vec<GitHubLabelRef>,
+    html_url: String,
+    updated_at: String,
+}
 
-        let response_text = response.text().await?;
-        let repo_info: GitHubRepo = serde_json::from_str(&response_text)?;
+/// A comment on the conversation or the diff of a pull request, or a review
+#[derive(Debug, serde::Deserialize)]
+struct GitHubPullComment {
+    user: GitHubEventActor,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    line: Option<u64>,
+    #[serde(default)]
+    original_line: Option<u64>,
+    /// Reviews carry a state and a submission date rather than a creation date
+    #[serde(default)]
+    state: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    submitted_at: Option<String>,
+}
 
-        Ok(repo_info)
-    }
+impl GitHubPullComment {
+    fn into_review_comment(self) -> Option<ReviewComment> {
+        let body = self.body.unwrap_or_default();
+        // A review without a verdict or a summary only groups its line comments
+        let body = match self.state.as_deref() {
+            Some("APPROVED") => format!("Approved. {}", body).trim_end().to_string(),
+            Some("CHANGES_REQUESTED") => format!("Requested changes. {}", body)
+                .trim_end()
+                .to_string(),
+            _ if body.trim().is_empty() => return None,
+            _ => body,
+        };
 
-    fn parse_repo_path(
-        &self,
-        repo_path: &str,
-    ) -> Result<(String, String, Option<String>, Option<String>)> {
-        let segments: Vec<&str> = repo_path.split('/').filter(|s| !s.is_empty()).collect();
+        Some(ReviewComment {
+            author: self.user.login,
+            body,
+            path: self.path,
+            line: self.line.or(self.original_line),
+            created_at: self.created_at.or(self.submitted_at).unwrap_or_default(),
+        })
+    }
+}
 
-        if segments.len() < 2 {
-            return Err(anyhow!("Invalid repository path: {}", repo_path));
-        }
+#[derive(Debug, serde::Deserialize)]
+struct GitHubBranchRef {
+    #[serde(rename = "ref")]
+    ref_name: String,
+}
 
-        let user_name = segments[0].to_string();
-        let repo_name = segments[1].to_string();
+#[derive(Debug, serde::Deserialize)]
+struct GitHubGitTree {
+    tree: Vec<GitHubGitTreeEntry>,
+    #[serde(default)]
+    truncated: bool,
+}
 
-        let branch = if segments.len() > 3 && (segments[2] == "tree" || segments[2] == "blob") {
-            Some(segments[3].to_string())
-        } else {
-            None
-        };
+#[derive(Debug, serde::Deserialize)]
+struct GitHubGitTreeEntry {
+    path: String,
+    #[serde(default)]
+    mode: Option<String>,
+    #[serde(rename = "type")]
+    entry_type: String,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    sha: Option<String>,
+}
 
-        let path = if segments.len() > 4 {
-            Some(segments[4..].join("/"))
-        } else {
-            None
-        };
+#[derive(Debug, serde::Deserialize)]
+struct GitHubComparison {
+    merge_base_commit: Option<GitHubCommitSha>,
+    #[serde(default)]
+    commits: Vec<GitHubCommitEntry>,
+    #[serde(default)]
+    files: Vec<GitHubChangedFile>,
+}
 
-        Ok((user_name, repo_name, branch, path))
-    }
+#[derive(Debug, serde::Deserialize)]
+struct GitHubCommitSha {
+    sha: String,
+}
 
-    fn api_url(&self, owner: &str, repo: &str, path: &str, branch: Option<String>) -> String {
-        let mut url = format!("https://api.github.com/repos/{}/{}/contents", owner, repo);
+#[derive(Debug, serde::Deserialize)]
+struct GitHubCommitEntry {
+    sha: String,
+    commit: GitHubCommitDetails,
+}
 
-        if !path.is_empty() {
-            url.push_str(&format!("/{}", path));
-        }
+#[derive(Debug, serde::Deserialize)]
+struct GitHubCommitWithFiles {
+    #[serde(flatten)]
+    entry: GitHubCommitEntry,
+    #[serde(default)]
+    files: Vec<GitHubChangedFile>,
+}
 
-        if let Some(branch) = branch {
-            url.push_str(&format!("?ref={}", branch));
-        }
+#[derive(Debug, serde::Deserialize)]
+struct GitHubCommitDetails {
+    message: String,
+    author: GitHubCommitAuthor,
+    #[serde(default)]
+    verification: Option<GitHubVerification>,
+}
 
-        url
-    }
+#[derive(Debug, serde::Deserialize)]
+struct GitHubVerification {
+    verified: bool,
+    reason: String,
+    #[serde(default)]
+    signature: Option<String>,
+}
 
-    async fn fetch_contents(
-        &self,
-        owner: &str,
-        repo: &str,
-        path: &str,
-        branch: Option<String>,
-    ) -> Result<Vec<RepoItem>> {
-        let url = self.api_url(owner, repo, path, branch);
+#[derive(Debug, serde::Deserialize)]
+struct GitHubCodeSearch {
+    items: Vec<GitHubCodeSearchItem>,
+}
 
-        let mut headers = HeaderMap::new();
-        headers.insert("User-Agent", "GitIngest-MCP-Agent/1.0".parse()?);
-        headers.insert("Accept", "application/vnd.github+json".parse()?);
-        headers.insert("X-GitHub-Api-Version", "2022-11-28".parse()?);
+#[derive(Debug, serde::Deserialize)]
+struct GitHubCodeSearchItem {
+    path: String,
+}
 
-        if let Some(github_token) = &self.github_token {
-            headers.insert("Authorization", format!("Bearer {}", github_token).parse()?);
-        }
+#[derive(Debug, serde::Deserialize)]
+struct GitHubTag {
+    name: String,
+    commit: GitHubCommitSha,
+}
 
-        let response = self
-            .http_client
-            .send(
-                Request::builder()
-                    .uri(&url)
+#[derive(Debug, serde::Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    draft: bool,
+    html_url: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubContributor {
+    login: String,
+    contributions: u64,
+    #[serde(rename = "type", default)]
+    account_type: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubGitRef {
+    object: GitHubGitObject,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubGitObject {
+    #[serde(rename = "type")]
+    object_type: String,
+    sha: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubTagObject {
+    message: String,
+    tagger: GitHubCommitAuthor,
+    #[serde(default)]
+    verification: Option<GitHubVerification>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubCommitAuthor {
+    name: String,
+    email: Option<String>,
+    date: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubChangedFile {
+    filename: String,
+    status: String,
+    #[serde(default)]
+    additions: usize,
+    #[serde(default)]
+    deletions: usize,
+    #[serde(default)]
+    patch: Option<String>,
+    #[serde(default)]
+    previous_filename: Option<String>,
+}
+
+impl GitHubCommitEntry {
+    fn into_commit_info(self) -> CommitInfo {
+        CommitInfo {
+            sha: self.sha,
+            message: self.commit.message,
+            author: pii::author(self.commit.author.name),
+            author_email: self.commit.author.email.filter(|e| !e.is_empty()),
+            date: self.commit.author.date,
+            verification: self
+                .commit
+                .verification
+                .map(GitHubVerification::into_verification),
+        }
+    }
+}
+
+impl GitHubVerification {
+    fn into_verification(self) -> SignatureVerification {
+        // The API does not name the format, the armor of the signature does
+        let format = self.signature.map(|signature| {
+            let format = if signature.contains("BEGIN SSH SIGNATURE") {
+                "ssh"
+            } else if signature.contains("BEGIN PGP SIGNATURE") {
+                "gpg"
+            } else if signature.contains("BEGIN SIGNED MESSAGE") {
+                "x509"
+            } else {
+                "unknown"
+            };
+            format.to_string()
+        });
+
+        SignatureVerification {
+            verified: self.verified,
+            reason: self.reason,
+            format,
+            signer: None,
+        }
+    }
+}
+
+impl GitHubChangedFile {
+    fn into_changed_file(self) -> ChangedFile {
+        let status = match self.status.as_str() {
+            "added" => FileStatus::Added,
+            "removed" => FileStatus::Removed,
+            "renamed" => FileStatus::Renamed,
+            _ => FileStatus::Modified,
+        };
+
+        // A rename without changes is an exact move
+        let similarity =
+            (status == FileStatus::Renamed && self.additions == 0 && self.deletions == 0)
+                .then_some(100);
+
+        ChangedFile {
+            path: self.filename,
+            previous_path: self.previous_filename,
+            similarity,
+            status,
+            additions: self.additions,
+            deletions: self.deletions,
+            patch: self.patch,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubNamedRef {
+    name: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubCompareCounts {
+    ahead_by: usize,
+    behind_by: usize,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubLabel {
+    name: String,
+    color: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubIssueLabels {
+    #[serde(default)]
+    labels: Vec<GitHubLabelRef>,
+    /// Only present when the issue is a pull request
+    #[serde(default)]
+    pull_request: Option<Value>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubMilestone {
+    title: String,
+    state: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    due_on: Option<String>,
+    #[serde(default)]
+    open_issues: u64,
+    #[serde(default)]
+    closed_issues: u64,
+    html_url: String,
+}
+
+const PROJECTS_QUERY: &str = r#"
+query($owner: String!, $name: String!) {
+  repository(owner: $owner, name: $name) {
+    projectsV2(first: 10) {
+      nodes {
+        title
+        url
+        items(first: 100) {
+          nodes {
+            status: fieldValueByName(name: "Status") {
+              ... on ProjectV2ItemFieldSingleSelectValue { name }
+            }
+            content {
+              ... on Issue { title number state }
+              ... on PullRequest { title number state }
+              ... on DraftIssue { title }
+            }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubRepo {
+    default_branch: String,
+    // Other fields are not needed for tree structure
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubRepoInfo {
+    full_name: String,
+    description: Option<String>,
+    html_url: String,
+    default_branch: String,
+    #[serde(default)]
+    stargazers_count: u64,
+    #[serde(default)]
+    forks_count: u64,
+    #[serde(default)]
+    open_issues_count: u64,
+    language: Option<String>,
+    license: Option<GitHubLicense>,
+    #[serde(default)]
+    topics: Vec<String>,
+    #[serde(default)]
+    archived: bool,
+    /// In kilobytes
+    size: Option<u64>,
+    #[serde(default)]
+    is_template: bool,
+    template_repository: Option<GitHubRepoName>,
+    parent: Option<GitHubRepoName>,
+    /// Origin of the repositories GitHub mirrors itself
+    mirror_url: Option<String>,
+    created_at: String,
+    pushed_at: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubLicense {
+    name: String,
+    spdx_id: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitHubRepoName {
+    full_name: String,
+}
+
+/// Used when GITHUB_API_URL is not set
+const DEFAULT_API_URL: &str = "https://api.github.com";
+
+pub struct GitHubProvider {
+    http_client: Arc<dyn HttpClient>,
+    github_token: Option<String>,
+    /// Name repositories of this host are identified with, e.g. "ghe" in "ghe:owner/repo"
+    name: String,
+    /// Base URL of the REST API, without a trailing slash
+    api_base: String,
+    /// Base URL of the web interface, without a trailing slash
+    web_base: String,
+}
+
+impl GitHubProvider {
+    /// The "github" provider, for github.com unless GITHUB_API_URL points to a GitHub
+    /// Enterprise Server (e.g., "https://ghe.example.com/api/v3")
+    pub fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        let api_url = env::var("GITHUB_API_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_API_URL.to_string());
+
+        Self::with_host(
+            http_client,
+            "github",
+            &api_url,
+            env::var("GITHUB_TOKEN").ok(),
+        )
+    }
+
+    /// A provider for another GitHub-compatible host, registered under its own name
+    pub fn with_host(
+        http_client: Arc<dyn HttpClient>,
+        name: &str,
+        api_url: &str,
+        github_token: Option<String>,
+    ) -> Self {
+        let api_base = normalize_url(api_url);
+        let web_base = if api_base == DEFAULT_API_URL {
+            "https://github.com".to_string()
+        } else {
+            // GitHub Enterprise Server serves its API under /api/v3 of the web host
+            api_base
+                .strip_suffix("/api/v3")
+                .unwrap_or(&api_base)
+                .to_string()
+        };
+
+        Self {
+            http_client,
+            github_token,
+            name: name.to_string(),
+            api_base,
+            web_base,
+        }
+    }
+
+    /// Base URL of the REST API, e.g. "https://api.github.com"
+    pub fn api_base(&self) -> &str {
+        &self.api_base
+    }
+
+    /// Additional hosts listed in GITHUB_HOSTS as comma-separated `name=api_url` pairs (e.g.,
+    /// "ghe=https://ghe.example.com/api/v3"), each authenticated with the token in
+    /// `<NAME>_TOKEN`
+    pub fn hosts_from_env(http_client: Arc<dyn HttpClient>) -> Result<Vec<Self>> {
+        let Ok(hosts) = env::var("GITHUB_HOSTS") else {
+            return Ok(Vec::new());
+        };
+
+        hosts
+            .split(',')
+            .map(str::trim)
+            .filter(|host| !host.is_empty())
+            .map(|host| {
+                let (name, api_url) = host
+                    .split_once('=')
+                    .map(|(name, api_url)| (name.trim(), api_url.trim()))
+                    .filter(|(name, api_url)| {
+                        !api_url.is_empty()
+                            && !name.is_empty()
+                            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                    })
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Invalid GITHUB_HOSTS entry '{}': expected 'name=https://host/api/v3'",
+                            host
+                        )
+                    })?;
+                let token = env::var(format!("{}_TOKEN", name.to_ascii_uppercase())).ok();
+
+                Ok(Self::with_host(http_client.clone(), name, api_url, token))
+            })
+            .collect()
+    }
+
+    /// The GraphQL endpoint, /api/graphql rather than /api/v3/graphql on Enterprise Server
+    fn graphql_url(&self) -> String {
+        match self.api_base.strip_suffix("/api/v3") {
+            Some(host) => format!("{}/api/graphql", host),
+            None => format!("{}/graphql", self.api_base),
+        }
+    }
+
+    async fn search_repositories(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<GitHubRepoItem>> {
+        // Check for empty query
+        if query.trim().is_empty() {
+            return Err(anyhow!("Empty search query is not allowed"));
+        }
+
+        let encoded_query = urlencoding::encode(query);
+        let mut url = format!("{}/search/repositories?q={}", self.api_base, encoded_query);
+        eprintln!("Searching GitHub repositories with URL: {}", url);
+
+        // Add per_page parameter if limit is provided
+        if let Some(per_page) = limit {
+            url.push_str(&format!("&per_page={}", per_page.min(100))); // GitHub API limits to 100 per page
+            eprintln!("Limited results to {} repositories", per_page.min(100));
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert("User-Agent", "GitIngest-MCP-Agent/1.0".parse()?);
+        api_compat::pin_github_version(&mut headers)?;
+
+        if let Some(github_token) = &self.github_token {
+            headers.insert("Authorization", format!("Bearer {}", github_token).parse()?);
+            eprintln!("Using GitHub token for authentication");
+        } else {
+            eprintln!("No GitHub token provided - API rate limits may apply");
+        }
+
+        eprintln!("Sending request to GitHub API... {:?}", headers);
+        let response = self
+            .send(
+                Request::builder()
+                    .uri(&url)
+                    .method("GET")
+                    .headers(headers)
+                    .end()?,
+            )
+            .await?;
+        eprintln!("Received response with status: {}", response.status());
+
+        // Check response status
+        if !response.status().is_success() {
+            return match response.status().as_u16() {
+                422 => Err(anyhow!("Invalid query syntax or empty query")),
+                403 => Err(anyhow!("GitHub API rate limit exceeded or access denied")),
+                404 => Err(anyhow!("Resource not found")),
+                _ => Err(anyhow!("GitHub API error: {}", response.status())),
+            };
+        }
+
+        let response_text = response.text().await?;
+
+        match api_compat::parse_listing::<GitHubSearchRepoResponse>(
+            Api::GitHub,
+            api_compat::endpoint(&url),
+            &response_text,
+        ) {
+            Ok(response) => Ok(response.items),
+            Err(_) if response_text.contains("rate limit") => Err(anyhow!(
+                "GitHub API rate limit exceeded. Consider adding a GITHUB_TOKEN"
+            )),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn fetch_repo_metadata(&self, owner: &str, repo: &str) -> Result<GitHubRepo> {
+        let url = format!("{}/repos/{}/{}", self.api_base, owner, repo);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("User-Agent", "GitIngest-MCP-Agent/1.0".parse()?);
+        api_compat::pin_github_version(&mut headers)?;
+
+        if let Some(github_token) = &self.github_token {
+            headers.insert("Authorization", format!("Bearer {}", github_token).parse()?);
+        }
+
+        let response = self
+            .send(
+                Request::builder()
+                    .uri(&url)
                     .method("GET")
                     .headers(headers)
                     .end()?,
             )
             .await?;
 
-        // First get the response as text so we can debug it
-        let response_text = response.text().await?;
+        let response_text = response.text().await?;
+
+        api_compat::parse(Api::GitHub, api_compat::endpoint(&url), &response_text)
+    }
+
+    fn parse_repo_path(
+        &self,
+        repo_path: &str,
+    ) -> Result<(String, String, Option<String>, Option<String>)> {
+        let segments: Vec<&str> = repo_path.split('/').filter(|s| !s.is_empty()).collect();
+
+        if segments.len() < 2 {
+            return Err(anyhow!("Invalid repository path: {}", repo_path));
+        }
+
+        let user_name = segments[0].to_string();
+        let repo_name = segments[1].to_string();
+
+        let branch = if segments.len() > 3 && (segments[2] == "tree" || segments[2] == "blob") {
+            Some(segments[3].to_string())
+        } else {
+            None
+        };
+
+        let path = if segments.len() > 4 {
+            Some(segments[4..].join("/"))
+        } else {
+            None
+        };
+
+        Ok((user_name, repo_name, branch, path))
+    }
+
+    fn api_url(&self, owner: &str, repo: &str, path: &str, branch: Option<String>) -> String {
+        let mut url = format!("{}/repos/{}/{}/contents", self.api_base, owner, repo);
+
+        if !path.is_empty() {
+            url.push_str(&format!("/{}", encode_path(path)));
+        }
+
+        if let Some(branch) = branch {
+            url.push_str(&format!("?ref={}", encode_component(&branch)));
+        }
+
+        url
+    }
+
+    async fn fetch_contents(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        branch: Option<String>,
+    ) -> Result<Vec<RepoItem>> {
+        let url = self.api_url(owner, repo, path, branch);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("User-Agent", "GitIngest-MCP-Agent/1.0".parse()?);
+        api_compat::pin_github_version(&mut headers)?;
+
+        if let Some(github_token) = &self.github_token {
+            headers.insert("Authorization", format!("Bearer {}", github_token).parse()?);
+        }
+
+        let response = self
+            .send(
+                Request::builder()
+                    .uri(&url)
+                    .method("GET")
+                    .headers(headers)
+                    .end()?,
+            )
+            .await?;
+
+        // First get the response as text so we can debug it
+        let response_text = response.text().await?;
+
+        // Lenient mode reads an unexpected listing as no listing
+        let content_response: Option<GitHubContentResponse> = match api_compat::parse_listing(
+            Api::GitHub,
+            api_compat::endpoint(&url),
+            &response_text,
+        ) {
+            Ok(parsed) => parsed,
+            Err(_) if response_text.contains("Not Found") => {
+                return Err(anyhow!("Repository or path not found"));
+            }
+            Err(_) if response_text.contains("rate limit") => {
+                return Err(anyhow!(
+                    "GitHub API rate limit exceeded. Consider adding a GITHUB_TOKEN"
+                ));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let github_contents = match content_response {
+            Some(GitHubContentResponse::Single(content)) => vec![content],
+            Some(GitHubContentResponse::Multiple(contents)) => contents,
+            None => Vec::new(),
+        };
+
+        let items: Vec<RepoItem> = github_contents
+            .into_iter()
+            .map(|content| {
+                RepoItem {
+                    name: content.name,
+                    path: content.path,
+                    item_type: match content.content_type.as_str() {
+                        "file" => RepoItemType::File,
+                        "dir" => RepoItemType::Directory,
+                        _ => RepoItemType::File, // Default to file for anything else
+                    },
+                    size: content.size,
+                    sha: content.sha,
+                }
+            })
+            .collect();
+
+        Ok(items)
+    }
+
+    async fn fetch_file_content(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        git_ref: Option<String>,
+    ) -> Result<String> {
+        let url = self.api_url(owner, repo, path, git_ref);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("User-Agent", "GitIngest-MCP-Agent/1.0".parse()?);
+        api_compat::pin_github_version(&mut headers)?;
+
+        if let Some(github_token) = &self.github_token {
+            headers.insert("Authorization", format!("Bearer {}", github_token).parse()?);
+        }
+
+        let response = self
+            .send(
+                Request::builder()
+                    .uri(&url)
+                    .method("GET")
+                    .headers(headers)
+                    .end()?,
+            )
+            .await?;
+
+        let response_text = response.text().await?;
+
+        // GitHub API returns content differently based on the file size
+        // For smaller files, it returns a JSON object with base64-encoded content
+        let content_response: Result<GitHubContentResponse, _> =
+            serde_json::from_str(&response_text);
+
+        match content_response {
+            Ok(GitHubContentResponse::Single(_)) => {
+                // Check if this is a file response with content
+                // Parse the response again to get the content safely
+                let response_value: serde_json::Value = serde_json::from_str(&response_text)?;
+                if let Some(content_value) = response_value.get("content").and_then(|c| c.as_str())
+                {
+                    // Content is base64 encoded
+                    let content_bytes = base64::decode(content_value.replace("\n", ""))?;
+                    return Ok(String::from_utf8(content_bytes)?);
+                }
+
+                Err(anyhow!("File content not found in response"))
+            }
+            Ok(GitHubContentResponse::Multiple(_)) => {
+                Err(anyhow!("Expected a file but got a directory"))
+            }
+            Err(_) => {
+                // Check if this is a not found error
+                if response_text.contains("Not Found") {
+                    return Err(anyhow!("File not found: {}", path));
+                }
+
+                // For rate limiting
+                if response_text.contains("rate limit") {
+                    return Err(anyhow!(
+                        "GitHub API rate limit exceeded. Consider adding a GITHUB_TOKEN"
+                    ));
+                }
+
+                Err(anyhow!(
+                    "Failed to parse GitHub API response for file content"
+                ))
+            }
+        }
+    }
+
+    async fn set_ignore_patterns(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: Option<String>,
+    ) -> Result<Vec<String>> {
+        let ignore_patterns = DEFAULT_IGNORE_PATTERNS
+            .iter()
+            .map(|&s| s.to_string())
+            .collect::<Vec<String>>();
+
+        // Try to get .gitignore
+        if let Ok(ignore_items) = self.fetch_contents(owner, repo, ".gitignore", branch).await
+            && !ignore_items.is_empty()
+        {
+            // For simplicity, we'll just use the default ignore patterns
+            // A real implementation would download and parse the .gitignore file
+        }
+
+        Ok(ignore_patterns)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn build_tree(
+        &self,
+        owner: String,
+        repo: String,
+        branch: Option<String>,
+        path: String,
+        options: TreeOptions,
+        ignore_patterns: Vec<String>,
+        depth: usize,
+        max_depth: usize,
+    ) -> Result<RepoNode> {
+        if depth > max_depth {
+            return Ok(depth_limited_directory(
+                path.split('/').next_back().unwrap_or(&path),
+            ));
+        }
+
+        let contents = self
+            .fetch_contents(&owner, &repo, &path, branch.clone())
+            .await?;
+
+        let description = if options.describe_directories {
+            self.fetch_readme_summary(&owner, &repo, branch.clone(), &contents)
+                .await
+        } else {
+            None
+        };
+
+        let mut children = Vec::new();
+        let mut file_count = 0;
+        let mut dir_count = 1; // Count self
+        let mut total_size = 0;
+
+        let mut tasks = Vec::new();
+
+        let filter = PathFilter::from_options(&options, &ignore_patterns);
+        for item in contents {
+            let keep = match item.item_type {
+                RepoItemType::File => filter.keeps(&item.path),
+                RepoItemType::Directory => filter.keeps_directory(&item.path),
+            };
+            if !keep {
+                continue;
+            }
+
+            match item.item_type {
+                RepoItemType::File => {
+                    let size = item.size.unwrap_or(0);
+                    total_size += size;
+                    file_count += 1;
+
+                    children.push(RepoNode {
+                        name: item.name,
+                        node_type: RepoItemType::File,
+                        size,
+                        children: vec![],
+                        file_count: 1,
+                        dir_count: 0,
+                        description: None,
+                    });
+                }
+                RepoItemType::Directory => {
+                    let owner = owner.to_string();
+                    let repo = repo.to_string();
+                    let branch = branch.clone();
+                    let path = item.path;
+                    let options = options.clone();
+                    let ignore_patterns = ignore_patterns.to_vec();
+
+                    tasks.push(self.build_tree(
+                        owner,
+                        repo,
+                        branch,
+                        path,
+                        options,
+                        ignore_patterns,
+                        depth + 1,
+                        max_depth,
+                    ));
+                }
+            }
+
+            // Check file limit
+            if file_count > MAX_FILES {
+                break;
+            }
+        }
+
+        let results = join_all(tasks).await;
+        for result in results {
+            match result {
+                Ok(child_node) => {
+                    file_count += child_node.file_count;
+                    dir_count += child_node.dir_count;
+                    total_size += child_node.size;
+                    children.push(child_node);
+                }
+                Err(e) => eprintln!("Task join error: {:?}", e),
+            }
+        }
+
+        // Sort children: directories first, then files, both alphabetically
+        children.sort_by(|a, b| match (a.node_type, b.node_type) {
+            (RepoItemType::Directory, RepoItemType::File) => std::cmp::Ordering::Less,
+            (RepoItemType::File, RepoItemType::Directory) => std::cmp::Ordering::Greater,
+            _ => a.name.cmp(&b.name),
+        });
+
+        Ok(RepoNode {
+            name: path.split('/').next_back().unwrap_or(&path).to_string(),
+            node_type: RepoItemType::Directory,
+            size: total_size,
+            children,
+            file_count,
+            dir_count,
+            description,
+        })
+    }
+
+    /// Summarize the README found among a directory's entries, if any
+    async fn fetch_readme_summary(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: Option<String>,
+        contents: &[RepoItem],
+    ) -> Option<String> {
+        let readme = contents.iter().find(|item| {
+            item.item_type == RepoItemType::File
+                && is_readme(&item.name)
+                && item.size.unwrap_or(0) <= MAX_README_SIZE
+        })?;
+
+        let content = self
+            .fetch_file_content(owner, repo, &readme.path, branch)
+            .await
+            .ok()?;
+
+        readme_summary(&content)
+    }
+
+    fn api_headers(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.insert("User-Agent", "GitIngest-MCP-Agent/1.0".parse()?);
+        api_compat::pin_github_version(&mut headers)?;
+
+        if let Some(github_token) = &self.github_token {
+            headers.insert("Authorization", format!("Bearer {}", github_token).parse()?);
+        }
+
+        Ok(headers)
+    }
+
+    /// Whether the token is a fine-grained personal access token, limited to the repositories
+    /// and permissions chosen when it was created
+    fn has_fine_grained_token(&self) -> bool {
+        self.github_token
+            .as_deref()
+            .is_some_and(|token| token.starts_with(FINE_GRAINED_TOKEN_PREFIX))
+    }
+
+    /// Send a request, failing early when an organization's SAML single sign-on or the
+    /// permissions of a fine-grained token block it, since GitHub words both like any other
+    /// denied access
+    async fn send(&self, request: Request<AsyncBody>) -> Result<Response<AsyncBody>> {
+        let response = self.http_client.send(request).await?;
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+
+        if let Some(sso) = header("x-github-sso") {
+            if response.status() == StatusCode::FORBIDDEN
+                && let Some(url) = sso.strip_prefix("required; url=")
+            {
+                let organization = url
+                    .split("/orgs/")
+                    .nth(1)
+                    .and_then(|rest| rest.split('/').next())
+                    .unwrap_or("of the repository");
+                return Err(anyhow!(
+                    "GitHub token not authorized for organization {}, which enforces SAML single sign-on: authorize the token for it at {}",
+                    organization,
+                    url
+                ));
+            }
+            // Listings leave out what the token may not see, without failing
+            if let Some(organizations) = sso.strip_prefix("partial-results; organizations=") {
+                warnings::warn(
+                    WarningKind::Withheld,
+                    format!(
+                        "GitHub omitted results from organizations {} whose SAML single sign-on the token is not authorized for",
+                        organizations
+                    ),
+                );
+            }
+        }
+
+        // Rate limits are denied with the same status, and say so in their headers
+        let rate_limited =
+            header("x-ratelimit-remaining") == Some("0") || header("retry-after").is_some();
+        if response.status() == StatusCode::FORBIDDEN
+            && !rate_limited
+            && self.has_fine_grained_token()
+        {
+            return Err(match header("x-accepted-github-permissions") {
+                Some(permissions) if !permissions.trim().is_empty() => anyhow!(
+                    "GitHub fine-grained token lacks the permissions this call needs ({}): grant them to the token in its settings, along with the repository if it is not selected",
+                    permissions
+                ),
+                _ => anyhow!(
+                    "GitHub fine-grained token not allowed this call: check the repositories and permissions granted to it in its settings"
+                ),
+            });
+        }
+
+        Ok(response)
+    }
+
+    /// Send an API request, failing with GitHub's error message on non-success statuses
+    async fn api_request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        url: &str,
+        body: Option<Value>,
+    ) -> Result<T> {
+        let builder = Request::builder()
+            .uri(url)
+            .method(method)
+            .headers(self.api_headers()?);
+
+        let request = match body {
+            Some(body) => builder.json(body)?,
+            None => builder.end()?,
+        };
+
+        let response = self.send(request).await?;
+        let status = response.status();
+        let response_text = response.text().await?;
+
+        if !status.is_success() {
+            let message = serde_json::from_str::<Value>(&response_text)
+                .ok()
+                .and_then(|v| v.get("message").and_then(|m| m.as_str()).map(String::from))
+                .unwrap_or(response_text);
+
+            return Err(anyhow!("GitHub API error ({}): {}", status, message));
+        }
+
+        api_compat::parse(Api::GitHub, api_compat::endpoint(url), &response_text)
+    }
+
+    /// The tag object of an annotated tag, `None` for a lightweight tag
+    async fn tag_annotation(
+        &self,
+        owner: &str,
+        repo: &str,
+        name: &str,
+    ) -> Result<Option<GitHubTagObject>> {
+        let reference: GitHubGitRef = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/git/ref/tags/{}",
+                    self.api_base,
+                    owner,
+                    repo,
+                    encode_path(name)
+                ),
+                None,
+            )
+            .await?;
+
+        if reference.object.object_type != "tag" {
+            return Ok(None);
+        }
+
+        let tag = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/git/tags/{}",
+                    self.api_base, owner, repo, reference.object.sha
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(Some(tag))
+    }
+
+    /// Resolve an optional git reference to a name the API accepts
+    async fn resolve_ref_name(
+        &self,
+        owner: &str,
+        repo: &str,
+        git_ref: Option<GitRef>,
+    ) -> Result<String> {
+        match git_ref {
+            Some(GitRef::Branch(name)) | Some(GitRef::Tag(name)) | Some(GitRef::Commit(name)) => {
+                Ok(name)
+            }
+            Some(GitRef::Default) | None => {
+                Ok(self.fetch_repo_metadata(owner, repo).await?.default_branch)
+            }
+        }
+    }
+
+    /// The entries of `paths` in the tree `tree_sha`, from its recursive listing, or walking down
+    /// to each path left out when GitHub truncates the listing. Paths missing from the tree are
+    /// left out.
+    async fn tree_entries(
+        &self,
+        owner: &str,
+        repo: &str,
+        tree_sha: &str,
+        paths: &[&str],
+    ) -> Result<HashMap<String, GitHubGitTreeEntry>> {
+        let tree_url = |sha: &str, query: &str| {
+            format!(
+                "{}/repos/{}/{}/git/trees/{}{}",
+                self.api_base, owner, repo, sha, query
+            )
+        };
+
+        let listing: GitHubGitTree = self
+            .api_request("GET", &tree_url(tree_sha, "?recursive=1"), None)
+            .await?;
+        let truncated = listing.truncated;
+        let mut entries: HashMap<String, GitHubGitTreeEntry> = listing
+            .tree
+            .into_iter()
+            .filter(|entry| paths.contains(&entry.path.as_str()))
+            .map(|entry| (entry.path.clone(), entry))
+            .collect();
+        if !truncated {
+            return Ok(entries);
+        }
+
+        for path in paths {
+            if entries.contains_key(*path) {
+                continue;
+            }
+
+            let mut sha = tree_sha.to_string();
+            let mut names = path.split('/').peekable();
+            while let Some(name) = names.next() {
+                let level: GitHubGitTree =
+                    self.api_request("GET", &tree_url(&sha, ""), None).await?;
+                let Some(entry) = level.tree.into_iter().find(|entry| entry.path == name) else {
+                    break;
+                };
+                if names.peek().is_none() {
+                    entries.insert(path.to_string(), entry);
+                    break;
+                }
+                match entry.sha {
+                    Some(next) if entry.entry_type == "tree" => sha = next,
+                    _ => break,
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    async fn resolve_commit_sha(&self, owner: &str, repo: &str, ref_name: &str) -> Result<String> {
+        let commit: Value = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/commits/{}",
+                    self.api_base, owner, repo, ref_name
+                ),
+                None,
+            )
+            .await?;
+
+        commit
+            .get("sha")
+            .and_then(|s| s.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow!("Could not resolve '{}' to a commit", ref_name))
+    }
+}
+
+/// Adds https:// to URLs given without a scheme and drops trailing slashes
+fn normalize_url(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    if url.starts_with("http://") || url.starts_with("https://") {
+        url.to_string()
+    } else {
+        format!("https://{}", url)
+    }
+}
+
+/// The `ref` query parameter of the contents endpoints, none reading the default branch
+fn ref_query(git_ref: Option<GitRef>, path_branch: Option<String>) -> String {
+    let ref_name = match git_ref {
+        Some(GitRef::Branch(name) | GitRef::Tag(name) | GitRef::Commit(name)) => Some(name),
+        Some(GitRef::Default) => None,
+        None => path_branch,
+    };
+    match ref_name {
+        Some(ref_name) => format!("?ref={}", encode_component(&ref_name)),
+        None => String::new(),
+    }
+}
+
+/// Decodes file content returned in base64, wrapped over several lines
+fn decode_content(content: &str) -> Result<String> {
+    Ok(String::from_utf8(base64::decode(
+        content.replace('\n', ""),
+    )?)?)
+}
+
+#[async_trait]
+impl GitProvider for GitHubProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> &str {
+        "github"
+    }
+
+    fn web_url(&self) -> Option<String> {
+        Some(self.web_base.clone())
+    }
+
+    fn is_anonymous(&self) -> bool {
+        self.github_token.is_none()
+    }
+
+    async fn get_tree_structure(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: TreeOptions,
+    ) -> Result<String> {
+        // Parse the repository path
+        let (owner, repo, mut path_branch, _path) = self.parse_repo_path(repo_path)?;
+
+        // Fetch repository metadata
+        let metadata = self.fetch_repo_metadata(&owner, &repo).await?;
+
+        // Determine which reference to use
+        let ref_name = match git_ref {
+            Some(GitRef::Branch(branch)) => Some(branch),
+            Some(GitRef::Tag(tag)) => Some(tag),
+            Some(GitRef::Commit(commit)) => Some(commit),
+            Some(GitRef::Default) => Some(metadata.default_branch.clone()),
+            None => {
+                if path_branch.is_none() {
+                    Some(metadata.default_branch.clone())
+                } else {
+                    path_branch.take()
+                }
+            }
+        };
+
+        // Set up ignored patterns from .gitignore, unless the caller gave its own
+        let ignore_patterns = match &options.ignore_patterns {
+            Some(ignore_patterns) => ignore_patterns.clone(),
+            None => {
+                self.set_ignore_patterns(&owner, &repo, ref_name.clone())
+                    .await?
+            }
+        };
+
+        // Build the repository tree
+        let max_depth = options.max_depth();
+        let root_node = Box::pin(self.build_tree(
+            owner,
+            repo.clone(),
+            ref_name,
+            options.subtree().to_string(),
+            options.clone(),
+            ignore_patterns,
+            0,
+            max_depth,
+        ))
+        .await?;
+
+        // Add the repo name as the root
+        let tree_node = RepoNode {
+            name: options.root_name(&repo),
+            node_type: RepoItemType::Directory,
+            size: root_node.size,
+            children: root_node.children,
+            file_count: root_node.file_count,
+            dir_count: root_node.dir_count,
+            description: root_node.description,
+        };
+
+        Ok(render_tree(&tree_node, &options))
+    }
+
+    async fn get_file_content(
+        &self,
+        repo_path: &str,
+        file_path: &str,
+        git_ref: Option<GitRef>,
+    ) -> Result<String> {
+        // Parse the repository path
+        let (owner, repo, mut path_branch, _) = self.parse_repo_path(repo_path)?;
+
+        // Fetch repository metadata to get default branch if needed
+        let metadata = self.fetch_repo_metadata(&owner, &repo).await?;
+
+        // Determine which reference to use
+        let ref_name = match git_ref {
+            Some(GitRef::Branch(branch)) => Some(branch),
+            Some(GitRef::Tag(tag)) => Some(tag),
+            Some(GitRef::Commit(commit)) => Some(commit),
+            Some(GitRef::Default) => Some(metadata.default_branch.clone()),
+            None => {
+                if path_branch.is_none() {
+                    Some(metadata.default_branch.clone())
+                } else {
+                    path_branch.take()
+                }
+            }
+        };
+
+        // Fetch the file content
+        self.fetch_file_content(&owner, &repo, file_path, ref_name)
+            .await
+    }
+
+    async fn get_readme(&self, repo_path: &str, git_ref: Option<GitRef>) -> Result<Readme> {
+        let (owner, repo, path_branch, _) = self.parse_repo_path(repo_path)?;
+
+        // The readme endpoint looks in the root, docs/ and .github/ and knows every name
+        let url = format!(
+            "{}/repos/{}/{}/readme{}",
+            self.api_base,
+            owner,
+            repo,
+            ref_query(git_ref, path_branch)
+        );
+        let readme: GitHubReadme = self.api_request("GET", &url, None).await?;
+
+        Ok(Readme {
+            path: readme.path,
+            content: decode_content(&readme.content)?,
+        })
+    }
+
+    async fn get_license(&self, repo_path: &str, git_ref: Option<GitRef>) -> Result<LicenseFile> {
+        let (owner, repo, path_branch, _) = self.parse_repo_path(repo_path)?;
+
+        let url = format!(
+            "{}/repos/{}/{}/license{}",
+            self.api_base,
+            owner,
+            repo,
+            ref_query(git_ref, path_branch)
+        );
+        let license: GitHubLicenseFile = self.api_request("GET", &url, None).await?;
+
+        Ok(LicenseFile {
+            path: license.path,
+            // Licenses GitHub does not recognize are reported as "NOASSERTION"
+            spdx_id: license
+                .license
+                .and_then(|license| license.spdx_id)
+                .filter(|spdx_id| spdx_id != "NOASSERTION"),
+            content: decode_content(&license.content)?,
+        })
+    }
+
+    async fn find_repositories(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<RepoSearchResult>> {
+        // Perform the GitHub repository search
+        let repos = self.search_repositories(query, limit).await?;
+
+        // Convert GitHub repository items to our common format
+        let results = repos
+            .into_iter()
+            .map(|repo| RepoSearchResult {
+                provider: self.name.clone(),
+                full_name: repo.full_name,
+                description: repo.description,
+                stargazers_count: repo.stargazers_count,
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    async fn fork_repository(&self, repo_path: &str) -> Result<String> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let fork: Value = self
+            .api_request(
+                "POST",
+                &format!("{}/repos/{}/{}/forks", self.api_base, owner, repo),
+                Some(json!({})),
+            )
+            .await?;
+
+        fork.get("full_name")
+            .and_then(|n| n.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow!("Fork response did not include the repository name"))
+    }
+
+    async fn create_branch(
+        &self,
+        repo_path: &str,
+        branch: &str,
+        from: Option<GitRef>,
+    ) -> Result<()> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let ref_name = self.resolve_ref_name(&owner, &repo, from).await?;
+        let sha = self.resolve_commit_sha(&owner, &repo, &ref_name).await?;
+
+        let _: Value = self
+            .api_request(
+                "POST",
+                &format!("{}/repos/{}/{}/git/refs", self.api_base, owner, repo),
+                Some(json!({
+                    "ref": format!("refs/heads/{}", branch),
+                    "sha": sha,
+                })),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn commit_files(
+        &self,
+        repo_path: &str,
+        branch: &str,
+        message: &str,
+        changes: Vec<FileChange>,
+    ) -> Result<String> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+        let base_url = format!("{}/repos/{}/{}/git", self.api_base, owner, repo);
+
+        // Build a single commit through the Git data API so multi-file changes stay atomic
+        let parent_sha = self.resolve_commit_sha(&owner, &repo, branch).await?;
+        let parent: Value = self
+            .api_request("GET", &format!("{}/commits/{}", base_url, parent_sha), None)
+            .await?;
+        let base_tree = parent
+            .pointer("/tree/sha")
+            .and_then(|s| s.as_str())
+            .ok_or_else(|| anyhow!("Could not read the tree of commit {}", parent_sha))?;
+
+        // Changed files keep their mode, so executables and symbolic links stay what they are
+        let paths: Vec<&str> = changes.iter().map(|change| change.path.as_str()).collect();
+        let existing = self.tree_entries(&owner, &repo, base_tree, &paths).await?;
+
+        let entries: Vec<Value> = changes
+            .into_iter()
+            .map(|change| {
+                let entry = existing.get(&change.path);
+                let mode = entry
+                    .and_then(|entry| entry.mode.as_deref())
+                    .filter(|mode| BLOB_MODES.contains(mode));
+                match change.content {
+                    Some(content) => json!({
+                        "path": change.path,
+                        "mode": mode.unwrap_or(DEFAULT_BLOB_MODE),
+                        "type": "blob",
+                        "content": content,
+                    }),
+                    // Deleted as whatever they are, submodules included
+                    None => json!({
+                        "path": change.path,
+                        "mode": entry
+                            .and_then(|entry| entry.mode.as_deref())
+                            .unwrap_or(DEFAULT_BLOB_MODE),
+                        "type": entry.map_or("blob", |entry| entry.entry_type.as_str()),
+                        "sha": null,
+                    }),
+                }
+            })
+            .collect();
+
+        let tree: Value = self
+            .api_request(
+                "POST",
+                &format!("{}/trees", base_url),
+                Some(json!({ "base_tree": base_tree, "tree": entries })),
+            )
+            .await?;
+        let tree_sha = tree
+            .get("sha")
+            .and_then(|s| s.as_str())
+            .ok_or_else(|| anyhow!("Tree creation did not return a SHA"))?;
+
+        let commit: Value = self
+            .api_request(
+                "POST",
+                &format!("{}/commits", base_url),
+                Some(json!({
+                    "message": message,
+                    "tree": tree_sha,
+                    "parents": [parent_sha],
+                })),
+            )
+            .await?;
+        let commit_sha = commit
+            .get("sha")
+            .and_then(|s| s.as_str())
+            .ok_or_else(|| anyhow!("Commit creation did not return a SHA"))?
+            .to_string();
+
+        let _: Value = self
+            .api_request(
+                "PATCH",
+                &format!("{}/refs/heads/{}", base_url, branch),
+                Some(json!({ "sha": commit_sha })),
+            )
+            .await?;
+
+        Ok(commit_sha)
+    }
+
+    async fn create_pull_request(
+        &self,
+        repo_path: &str,
+        request: PullRequestDraft,
+    ) -> Result<String> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let base = match request.base_branch {
+            Some(base) => base,
+            None => {
+                self.fetch_repo_metadata(&owner, &repo)
+                    .await?
+                    .default_branch
+            }
+        };
+
+        // Cross-repository pull requests reference the head as "owner:branch"
+        let head = match &request.head_repo {
+            Some(head_repo) => {
+                let (head_owner, _, _, _) = self.parse_repo_path(head_repo)?;
+                format!("{}:{}", head_owner, request.head_branch)
+            }
+            None => request.head_branch.clone(),
+        };
+
+        let pull_request: Value = self
+            .api_request(
+                "POST",
+                &format!("{}/repos/{}/{}/pulls", self.api_base, owner, repo),
+                Some(json!({
+                    "title": request.title,
+                    "body": request.body,
+                    "head": head,
+                    "base": base,
+                })),
+            )
+            .await?;
+
+        pull_request
+            .get("html_url")
+            .and_then(|u| u.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow!("Pull request response did not include a URL"))
+    }
 
-        // Parse the response using serde_json from the text
-        let content_response: GitHubContentResponse = match serde_json::from_str(&response_text) {
-            Ok(parsed) => parsed,
-            Err(e) => {
-                eprintln!("Error parsing GitHub API response: {}", e);
-                // Try to interpret the error response
-                if response_text.contains("Not Found") {
-                    return Err(anyhow!("Repository or path not found"));
-                }
-                if response_text.contains("rate limit") {
-                    return Err(anyhow!(
-                        "GitHub API rate limit exceeded. Consider adding a GITHUB_TOKEN"
-                    ));
-                }
-                // Return empty list to avoid breaking tests during development
-                return Ok(Vec::new());
-            }
-        };
+    async fn create_issue(&self, repo_path: &str, issue: IssueDraft) -> Result<String> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let created: Value = self
+            .api_request(
+                "POST",
+                &format!("{}/repos/{}/{}/issues", self.api_base, owner, repo),
+                Some(json!({
+                    "title": issue.title,
+                    "body": issue.body,
+                    "labels": issue.labels,
+                })),
+            )
+            .await?;
 
-        let github_contents = match content_response {
-            GitHubContentResponse::Single(content) => vec![content],
-            GitHubContentResponse::Multiple(contents) => contents,
+        created
+            .get("html_url")
+            .and_then(|u| u.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow!("Issue response did not include a URL"))
+    }
+
+    async fn create_comment(
+        &self,
+        repo_path: &str,
+        target: CommentTarget,
+        body: &str,
+    ) -> Result<String> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        // Pull requests share the issue comments endpoint for conversation comments
+        let number = match target {
+            CommentTarget::Issue(number) | CommentTarget::PullRequest(number) => number,
         };
 
-        let items: Vec<RepoItem> = github_contents
+        let comment: Value = self
+            .api_request(
+                "POST",
+                &format!(
+                    "{}/repos/{}/{}/issues/{}/comments",
+                    self.api_base, owner, repo, number
+                ),
+                Some(json!({ "body": body })),
+            )
+            .await?;
+
+        comment
+            .get("html_url")
+            .and_then(|u| u.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow!("Comment response did not include a URL"))
+    }
+
+    async fn get_check_annotations(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+    ) -> Result<Vec<CheckAnnotation>> {
+        let (owner, repo, path_branch, _) = self.parse_repo_path(repo_path)?;
+        let ref_name = self
+            .resolve_ref_name(&owner, &repo, git_ref.or(path_branch.map(GitRef::Branch)))
+            .await?;
+
+        let check_runs: GitHubCheckRuns = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/commits/{}/check-runs?per_page=100",
+                    self.api_base, owner, repo, ref_name
+                ),
+                None,
+            )
+            .await?;
+
+        // Only query check runs that actually reported annotations
+        let runs: Vec<GitHubCheckRun> = check_runs
+            .check_runs
             .into_iter()
-            .map(|content| {
-                RepoItem {
-                    name: content.name,
-                    path: content.path,
-                    item_type: match content.content_type.as_str() {
-                        "file" => RepoItemType::File,
-                        "dir" => RepoItemType::Directory,
-                        _ => RepoItemType::File, // Default to file for anything else
-                    },
-                    size: content.size,
-                }
-            })
+            .filter(|run| run.output.as_ref().is_some_and(|o| o.annotations_count > 0))
             .collect();
 
-        Ok(items)
+        let responses = join_all(runs.iter().map(|run| {
+            let url = format!(
+                "{}/repos/{}/{}/check-runs/{}/annotations?per_page=100",
+                self.api_base, owner, repo, run.id
+            );
+            async move {
+                self.api_request::<Vec<GitHubCheckAnnotation>>("GET", &url, None)
+                    .await
+            }
+        }))
+        .await;
+
+        let mut annotations = Vec::new();
+        for (run, response) in runs.iter().zip(responses) {
+            for annotation in response? {
+                annotations.push(CheckAnnotation {
+                    check_name: run.name.clone(),
+                    path: annotation.path,
+                    start_line: annotation.start_line,
+                    end_line: annotation.end_line,
+                    level: annotation.annotation_level,
+                    title: annotation.title,
+                    message: annotation.message,
+                });
+            }
+        }
+
+        Ok(annotations)
     }
 
-    async fn fetch_file_content(
-        &self,
-        owner: &str,
-        repo: &str,
-        path: &str,
-        git_ref: Option<String>,
-    ) -> Result<String> {
-        let url = self.api_url(owner, repo, path, git_ref);
+    async fn get_job_log(&self, repo_path: &str, job_id: &str) -> Result<String> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+        let url = format!(
+            "{}/repos/{}/{}/actions/jobs/{}/logs",
+            self.api_base,
+            owner,
+            repo,
+            urlencoding::encode(job_id)
+        );
 
-        let mut headers = HeaderMap::new();
-        headers.insert("User-Agent", "GitIngest-MCP-Agent/1.0".parse()?);
-        headers.insert("Accept", "application/vnd.github+json".parse()?);
-        headers.insert("X-GitHub-Api-Version", "2022-11-28".parse()?);
+        // The API redirects to a short-lived plain text download
+        let response = self
+            .send(
+                Request::builder()
+                    .uri(&url)
+                    .method("GET")
+                    .headers(self.api_headers()?)
+                    .end()?,
+            )
+            .await?;
 
-        if let Some(github_token) = &self.github_token {
-            headers.insert("Authorization", format!("Bearer {}", github_token).parse()?);
+        let status = response.status();
+        let log = response.text().await?;
+
+        match status.as_u16() {
+            200..=299 => Ok(log),
+            404 => Err(anyhow!("Job {} not found", job_id)),
+            410 => Err(anyhow!("Logs for job {} have expired", job_id)),
+            _ => Err(anyhow!("GitHub API error: {}", status)),
         }
+    }
+
+    async fn list_artifacts(&self, repo_path: &str, run_id: &str) -> Result<Vec<CiArtifact>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let response: GitHubArtifacts = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/actions/runs/{}/artifacts?per_page=100",
+                    self.api_base,
+                    owner,
+                    repo,
+                    urlencoding::encode(run_id)
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(response
+            .artifacts
+            .into_iter()
+            .map(|artifact| CiArtifact {
+                id: artifact.id.to_string(),
+                name: artifact.name,
+                size: artifact.size_in_bytes,
+                expired: artifact.expired,
+            })
+            .collect())
+    }
+
+    async fn download_artifact(&self, repo_path: &str, artifact_id: &str) -> Result<Vec<u8>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+        let url = format!(
+            "{}/repos/{}/{}/actions/artifacts/{}/zip",
+            self.api_base,
+            owner,
+            repo,
+            urlencoding::encode(artifact_id)
+        );
 
+        // The API redirects to a short-lived archive download
         let response = self
-            .http_client
             .send(
                 Request::builder()
                     .uri(&url)
                     .method("GET")
-                    .headers(headers)
+                    .headers(self.api_headers()?)
                     .end()?,
             )
             .await?;
 
-        let response_text = response.text().await?;
+        let status = response.status();
+        match status.as_u16() {
+            200..=299 => Ok(response.bytes().await?),
+            404 => Err(anyhow!("Artifact {} not found", artifact_id)),
+            410 => Err(anyhow!("Artifact {} has expired", artifact_id)),
+            _ => Err(anyhow!("GitHub API error: {}", status)),
+        }
+    }
 
-        // GitHub API returns content differently based on the file size
-        // For smaller files, it returns a JSON object with base64-encoded content
-        let content_response: Result<GitHubContentResponse, _> =
-            serde_json::from_str(&response_text);
+    async fn list_events(&self, repo_path: &str, since: Option<&str>) -> Result<Vec<RepoEvent>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        // The events API serves at most 300 events, in pages of up to 100
+        let mut events = Vec::new();
+        for page in 1..=3 {
+            let batch: Vec<GitHubEvent> = self
+                .api_request(
+                    "GET",
+                    &format!(
+                        "{}/repos/{}/{}/events?per_page=100&page={}",
+                        self.api_base, owner, repo, page
+                    ),
+                    None,
+                )
+                .await?;
+
+            let exhausted = batch.len() < 100
+                || since.is_some_and(|since| {
+                    batch
+                        .last()
+                        .is_some_and(|event| event.created_at.as_str() < since)
+                });
+
+            events.extend(
+                batch
+                    .into_iter()
+                    .filter(|event| since.is_none_or(|since| event.created_at.as_str() >= since))
+                    .map(GitHubEvent::into_repo_event),
+            );
+
+            if exhausted {
+                break;
+            }
+        }
 
-        match content_response {
-            Ok(GitHubContentResponse::Single(_)) => {
-                // Check if this is a file response with content
-                // Parse the response again to get the content safely
-                let response_value: serde_json::Value = serde_json::from_str(&response_text)?;
-                if let Some(content_value) = response_value.get("content").and_then(|c| c.as_str())
+        Ok(events)
+    }
+
+    async fn list_review_requests(&self, limit: Option<usize>) -> Result<Vec<PullRequestSummary>> {
+        if self.github_token.is_none() {
+            return Err(anyhow!(
+                "A GITHUB_TOKEN is required to list review requests"
+            ));
+        }
+
+        let response: GitHubIssueSearchResponse = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/search/issues?q={}&sort=updated&per_page={}",
+                    self.api_base,
+                    urlencoding::encode("is:open is:pr review-requested:@me archived:false"),
+                    limit.unwrap_or(50).min(100)
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(response
+            .items
+            .into_iter()
+            .map(|item| PullRequestSummary {
+                repository: item
+                    .repository_url
+                    .trim_start_matches(&format!("{}/repos/", self.api_base))
+                    .to_string(),
+                number: item.number,
+                title: item.title,
+                author: item.user.login,
+                state: item.state,
+                draft: item.draft,
+                source_branch: None,
+                target_branch: None,
+                labels: item.labels.into_iter().map(|l| l.name).collect(),
+                url: item.html_url,
+                updated_at: item.updated_at,
+            })
+            .collect())
+    }
+
+    async fn list_pull_requests(
+        &self,
+        repo_path: &str,
+        state: &str,
+        target_branch: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<PullRequestSummary>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        // Merged pull requests are the closed ones with a merge date
+        let mut url = format!(
+            "{}/repos/{}/{}/pulls?state={}&sort=updated&direction=desc&per_page={}",
+            self.api_base,
+            owner,
+            repo,
+            if state == "merged" { "closed" } else { state },
+            limit.min(100)
+        );
+        if let Some(target_branch) = target_branch {
+            url.push_str(&format!("&base={}", encode_component(target_branch)));
+        }
+
+        let pulls: Vec<GitHubPullRequest> = self.api_request("GET", &url, None).await?;
+
+        Ok(pulls
+            .into_iter()
+            .filter(|pull| state != "merged" || pull.merged_at.is_some())
+            .map(|pull| PullRequestSummary {
+                repository: format!("{}/{}", owner, repo),
+                number: pull.number,
+                title: pull.title,
+                author: pull.user.login,
+                state: if pull.merged_at.is_some() {
+                    "merged".to_string()
+                } else {
+                    pull.state
+                },
+                draft: pull.draft,
+                source_branch: Some(pull.head.ref_name),
+                target_branch: Some(pull.base.ref_name),
+                labels: pull.labels.into_iter().map(|l| l.name).collect(),
+                url: pull.html_url,
+                updated_at: pull.updated_at,
+            })
+            .collect())
+    }
+
+    async fn list_milestones(&self, repo_path: &str, state: &str) -> Result<Vec<Milestone>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let milestones: Vec<GitHubMilestone> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/milestones?state={}&sort=due_on&per_page=100",
+                    self.api_base, owner, repo, state
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(milestones
+            .into_iter()
+            .map(|milestone| Milestone {
+                title: milestone.title,
+                state: milestone.state,
+                description: milestone.description.filter(|d| !d.is_empty()),
+                due_on: milestone.due_on,
+                open_issues: milestone.open_issues,
+                closed_issues: milestone.closed_issues,
+                url: Some(milestone.html_url),
+            })
+            .collect())
+    }
+
+    async fn list_boards(&self, repo_path: &str) -> Result<Vec<ProjectBoard>> {
+        if self.github_token.is_none() {
+            return Err(anyhow!(
+                "A GITHUB_TOKEN is required to read GitHub Projects"
+            ));
+        }
+
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        // Projects (v2) are only exposed through the GraphQL API
+        let response: Value = self
+            .api_request(
+                "POST",
+                &self.graphql_url(),
+                Some(json!({
+                    "query": PROJECTS_QUERY,
+                    "variables": { "owner": owner, "name": repo },
+                })),
+            )
+            .await?;
+
+        if let Some(errors) = response.get("errors").and_then(|e| e.as_array()) {
+            let messages: Vec<&str> = errors
+                .iter()
+                .filter_map(|e| e.get("message").and_then(|m| m.as_str()))
+                .collect();
+            return Err(anyhow!("GitHub GraphQL error: {}", messages.join("; ")));
+        }
+
+        let projects = response
+            .pointer("/data/repository/projectsV2/nodes")
+            .and_then(|n| n.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(projects
+            .into_iter()
+            .map(|project| {
+                let mut columns: Vec<BoardColumn> = Vec::new();
+
+                for item in project
+                    .pointer("/items/nodes")
+                    .and_then(|n| n.as_array())
+                    .into_iter()
+                    .flatten()
                 {
-                    // Content is base64 encoded
-                    let content_bytes = base64::decode(content_value.replace("\n", ""))?;
-                    return Ok(String::from_utf8(content_bytes)?);
+                    let status = item
+                        .pointer("/status/name")
+                        .and_then(|s| s.as_str())
+                        .unwrap_or("No Status");
+                    let content = item.get("content").cloned().unwrap_or(Value::Null);
+
+                    let board_item = BoardItem {
+                        title: content
+                            .get("title")
+                            .and_then(|t| t.as_str())
+                            .unwrap_or("(untitled)")
+                            .to_string(),
+                        number: content.get("number").and_then(|n| n.as_u64()),
+                        state: content
+                            .get("state")
+                            .and_then(|s| s.as_str())
+                            .map(|s| s.to_lowercase()),
+                    };
+
+                    match columns.iter_mut().find(|c| c.name == status) {
+                        Some(column) => column.items.push(board_item),
+                        None => columns.push(BoardColumn {
+                            name: status.to_string(),
+                            items: vec![board_item],
+                        }),
+                    }
                 }
 
-                Err(anyhow!("File content not found in response"))
-            }
-            Ok(GitHubContentResponse::Multiple(_)) => {
-                Err(anyhow!("Expected a file but got a directory"))
-            }
-            Err(_) => {
-                // Check if this is a not found error
-                if response_text.contains("Not Found") {
-                    return Err(anyhow!("File not found: {}", path));
+                ProjectBoard {
+                    name: project
+                        .get("title")
+                        .and_then(|t| t.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    url: project
+                        .get("url")
+                        .and_then(|u| u.as_str())
+                        .map(String::from),
+                    columns,
                 }
+            })
+            .collect())
+    }
 
-                // For rate limiting
-                if response_text.contains("rate limit") {
-                    return Err(anyhow!(
-                        "GitHub API rate limit exceeded. Consider adding a GITHUB_TOKEN"
-                    ));
+    async fn list_labels(&self, repo_path: &str) -> Result<Vec<RepoLabel>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let mut labels: Vec<GitHubLabel> = Vec::new();
+        for page in 1.. {
+            let batch: Vec<GitHubLabel> = self
+                .api_request(
+                    "GET",
+                    &format!(
+                        "{}/repos/{}/{}/labels?per_page=100&page={}",
+                        self.api_base, owner, repo, page
+                    ),
+                    None,
+                )
+                .await?;
+
+            let done = batch.len() < 100;
+            labels.extend(batch);
+            if done {
+                break;
+            }
+        }
+
+        // Labels carry no usage counts, tally them over the open issues instead
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        for page in 1..=MAX_LABEL_COUNT_PAGES {
+            let issues: Vec<GitHubIssueLabels> = self
+                .api_request(
+                    "GET",
+                    &format!(
+                        "{}/repos/{}/{}/issues?state=open&per_page=100&page={}",
+                        self.api_base, owner, repo, page
+                    ),
+                    None,
+                )
+                .await?;
+
+            let done = issues.len() < 100;
+            for issue in issues.into_iter().filter(|i| i.pull_request.is_none()) {
+                for label in issue.labels {
+                    *counts.entry(label.name).or_default() += 1;
                 }
+            }
+            if done {
+                break;
+            }
+            if page == MAX_LABEL_COUNT_PAGES {
+                warnings::warn(
+                    WarningKind::Truncated,
+                    format!(
+                        "Label counts of {} only cover its {} most recently created open issues and pull requests",
+                        repo_path,
+                        MAX_LABEL_COUNT_PAGES * 100
+                    ),
+                );
+            }
+        }
 
-                Err(anyhow!(
-                    "Failed to parse GitHub API response for file content"
-                ))
+        Ok(labels
+            .into_iter()
+            .map(|label| RepoLabel {
+                open_issues: counts.get(&label.name).copied().unwrap_or_default(),
+                name: label.name,
+                color: label.color,
+                description: label.description.filter(|d| !d.is_empty()),
+            })
+            .collect())
+    }
+
+    async fn compare_refs(
+        &self,
+        repo_path: &str,
+        base: Option<GitRef>,
+        head: Option<GitRef>,
+        mode: CompareMode,
+    ) -> Result<RefComparison> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+        let base = self.resolve_ref_name(&owner, &repo, base).await?;
+        let head = self.resolve_ref_name(&owner, &repo, head).await?;
+
+        // The three-dot form compares against the merge base of both references
+        let separator = match mode {
+            CompareMode::MergeBase => "...",
+            CompareMode::Direct => "..",
+        };
+        let url = format!(
+            "{}/repos/{}/{}/compare/{}{}{}",
+            self.api_base,
+            owner,
+            repo,
+            urlencoding::encode(&base),
+            separator,
+            urlencoding::encode(&head)
+        );
+
+        let mut comparison: GitHubComparison = self
+            .api_request("GET", &format!("{}?per_page=100", url), None)
+            .await?;
+
+        // Only the commit list is paginated, files are capped at 300 by the API
+        for page in 2..=5 {
+            if comparison.commits.len() < (page - 1) * 100 {
+                break;
+            }
+
+            let next: GitHubComparison = self
+                .api_request("GET", &format!("{}?per_page=100&page={}", url, page), None)
+                .await?;
+            if next.commits.is_empty() {
+                break;
             }
+            comparison.commits.extend(next.commits);
         }
+
+        let merge_base = match mode {
+            CompareMode::MergeBase => comparison.merge_base_commit.map(|commit| commit.sha),
+            CompareMode::Direct => None,
+        };
+
+        Ok(RefComparison {
+            base,
+            head,
+            merge_base,
+            commits: comparison
+                .commits
+                .into_iter()
+                .map(GitHubCommitEntry::into_commit_info)
+                .collect(),
+            files: comparison
+                .files
+                .into_iter()
+                .map(GitHubChangedFile::into_changed_file)
+                .collect(),
+        })
     }
 
-    async fn set_ignore_patterns(
+    async fn list_files(
         &self,
-        owner: &str,
-        repo: &str,
-        branch: Option<String>,
-    ) -> Result<Vec<String>> {
-        let ignore_patterns = DEFAULT_IGNORE_PATTERNS
-            .iter()
-            .map(|&s| s.to_string())
-            .collect::<Vec<String>>();
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: &TreeOptions,
+    ) -> Result<Vec<RepoItem>> {
+        let (owner, repo, path_branch, _) = self.parse_repo_path(repo_path)?;
+        let git_ref = git_ref.or(path_branch.map(GitRef::Branch));
+        let ref_name = self.resolve_ref_name(&owner, &repo, git_ref).await?;
+
+        let tree: GitHubGitTree = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/git/trees/{}?recursive=1",
+                    self.api_base,
+                    owner,
+                    repo,
+                    urlencoding::encode(&ref_name)
+                ),
+                None,
+            )
+            .await?;
 
-        // Try to get .gitignore
-        if let Ok(ignore_items) = self.fetch_contents(owner, repo, ".gitignore", branch).await {
-            if !ignore_items.is_empty() {
-                // For simplicity, we'll just use the default ignore patterns
-                // A real implementation would download and parse the .gitignore file
+        if tree.truncated {
+            warnings::warn(
+                WarningKind::Truncated,
+                format!(
+                    "The file listing of {} was truncated by the GitHub API",
+                    repo_path
+                ),
+            );
+        }
+
+        let ignore_patterns = match &options.ignore_patterns {
+            Some(ignore_patterns) => ignore_patterns.clone(),
+            None => {
+                self.set_ignore_patterns(&owner, &repo, Some(ref_name))
+                    .await?
             }
+        };
+
+        let filter = PathFilter::from_options(options, &ignore_patterns);
+        let mut files: Vec<RepoItem> = tree
+            .tree
+            .into_iter()
+            .filter(|entry| entry.entry_type == "blob")
+            .filter(|entry| filter.keeps(&entry.path))
+            .map(|entry| RepoItem {
+                name: entry
+                    .path
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(&entry.path)
+                    .to_string(),
+                path: entry.path,
+                item_type: RepoItemType::File,
+                size: entry.size,
+                sha: entry.sha,
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(files)
+    }
+
+    async fn list_commits(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        path: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+        let ref_name = self.resolve_ref_name(&owner, &repo, git_ref).await?;
+
+        let mut url = format!(
+            "{}/repos/{}/{}/commits?sha={}&per_page={}",
+            self.api_base,
+            owner,
+            repo,
+            urlencoding::encode(&ref_name),
+            limit.clamp(1, 100)
+        );
+        if let Some(path) = path {
+            url.push_str(&format!("&path={}", urlencoding::encode(path)));
+        }
+
+        let commits: Vec<GitHubCommitEntry> = self.api_request("GET", &url, None).await?;
+
+        Ok(commits
+            .into_iter()
+            .map(GitHubCommitEntry::into_commit_info)
+            .collect())
+    }
+
+    async fn get_commit(&self, repo_path: &str, sha: &str) -> Result<CommitDetails> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let commit: GitHubCommitWithFiles = self
+            .api_request(
+                "GET",
+                &format!("{}/repos/{}/{}/commits/{}", self.api_base, owner, repo, sha),
+                None,
+            )
+            .await?;
+
+        Ok(CommitDetails {
+            commit: commit.entry.into_commit_info(),
+            files: commit
+                .files
+                .into_iter()
+                .map(GitHubChangedFile::into_changed_file)
+                .collect(),
+        })
+    }
+
+    async fn list_issues(
+        &self,
+        repo_path: &str,
+        filter: &IssueFilter,
+        page: usize,
+        per_page: usize,
+    ) -> Result<Vec<IssueDetails>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let mut url = format!(
+            "{}/repos/{}/{}/issues?state={}&sort=updated&direction=desc&page={}&per_page={}",
+            self.api_base,
+            owner,
+            repo,
+            filter.state,
+            page.max(1),
+            per_page.min(100)
+        );
+        if !filter.labels.is_empty() {
+            let labels: Vec<String> = filter.labels.iter().map(|l| encode_component(l)).collect();
+            url.push_str(&format!("&labels={}", labels.join(",")));
+        }
+        if let Some(assignee) = &filter.assignee {
+            url.push_str(&format!("&assignee={}", encode_component(assignee)));
         }
 
-        Ok(ignore_patterns)
+        let issues: Vec<GitHubIssue> = self.api_request("GET", &url, None).await?;
+
+        // Pull requests are issues to GitHub, and share their numbering
+        Ok(issues
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .map(GitHubIssue::into_issue_details)
+            .collect())
     }
 
-    fn should_include(&self, path: &str, include_patterns: Vec<String>) -> bool {
-        if include_patterns.is_empty() {
-            return true;
-        }
+    async fn get_issue(&self, repo_path: &str, number: u64) -> Result<IssueDetails> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let issue: GitHubIssue = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/issues/{}",
+                    self.api_base, owner, repo, number
+                ),
+                None,
+            )
+            .await?;
 
-        include_patterns.iter().any(|pattern| {
-            if let Ok(glob) = glob::Pattern::new(pattern) {
-                glob.matches(path)
-            } else {
-                false
-            }
+        Ok(issue.into_issue_details())
+    }
+
+    async fn list_issue_comments(&self, repo_path: &str, number: u64) -> Result<Vec<IssueComment>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let comments: Vec<GitHubIssueComment> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/issues/{}/comments?per_page=100",
+                    self.api_base, owner, repo, number
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(comments
+            .into_iter()
+            .map(|comment| IssueComment {
+                author: comment.user.login,
+                body: comment.body.unwrap_or_default(),
+                updated_at: comment
+                    .updated_at
+                    .filter(|updated_at| *updated_at != comment.created_at),
+                created_at: comment.created_at,
+            })
+            .collect())
+    }
+
+    async fn get_pull_request(&self, repo_path: &str, number: u64) -> Result<PullRequestDetails> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let pull: GitHubPullRequest = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/pulls/{}",
+                    self.api_base, owner, repo, number
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(PullRequestDetails {
+            summary: PullRequestSummary {
+                repository: format!("{}/{}", owner, repo),
+                number: pull.number,
+                title: pull.title,
+                author: pull.user.login,
+                state: if pull.merged {
+                    "merged".to_string()
+                } else {
+                    pull.state
+                },
+                draft: pull.draft,
+                source_branch: Some(pull.head.ref_name),
+                target_branch: Some(pull.base.ref_name),
+                labels: pull.labels.into_iter().map(|l| l.name).collect(),
+                url: pull.html_url,
+                updated_at: pull.updated_at,
+            },
+            body: pull.body.filter(|b| !b.is_empty()),
         })
     }
 
-    fn should_exclude(
+    async fn list_pull_request_files(
         &self,
-        path: &str,
-        exclude_patterns: Vec<String>,
-        ignore_patterns: Vec<String>,
-    ) -> bool {
-        exclude_patterns.iter().any(|pattern| {
-            if let Ok(glob) = glob::Pattern::new(pattern) {
-                glob.matches(&path)
-            } else {
-                false
-            }
-        }) || ignore_patterns.iter().any(|p| path.contains(p.as_str()))
+        repo_path: &str,
+        number: u64,
+    ) -> Result<Vec<ChangedFile>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let files: Vec<GitHubChangedFile> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/pulls/{}/files?per_page=100",
+                    self.api_base, owner, repo, number
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(files
+            .into_iter()
+            .map(GitHubChangedFile::into_changed_file)
+            .collect())
     }
 
-    async fn build_tree(
+    async fn list_pull_request_comments(
         &self,
-        owner: String,
-        repo: String,
-        branch: Option<String>,
-        path: String,
-        exclude_patterns: Vec<String>,
-        include_patterns: Vec<String>,
-        ignore_patterns: Vec<String>,
-        depth: usize,
-        max_depth: usize,
-    ) -> Result<RepoNode> {
-        if depth > max_depth {
-            return Ok(RepoNode {
-                name: path.split('/').last().unwrap_or(&path).to_string(),
-                node_type: RepoItemType::Directory,
-                size: 0,
-                children: vec![],
-                file_count: 0,
-                dir_count: 1,
-            });
-        }
+        repo_path: &str,
+        number: u64,
+    ) -> Result<Vec<ReviewComment>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        // The conversation, the line comments and the reviews are three separate listings
+        let url = |path: String| {
+            format!(
+                "{}/repos/{}/{}/{}?per_page=100",
+                self.api_base, owner, repo, path
+            )
+        };
+        let conversation_url = url(format!("issues/{}/comments", number));
+        let lines_url = url(format!("pulls/{}/comments", number));
+        let reviews_url = url(format!("pulls/{}/reviews", number));
+        let (conversation, lines, reviews): (
+            Vec<GitHubPullComment>,
+            Vec<GitHubPullComment>,
+            Vec<GitHubPullComment>,
+        ) = futures::try_join!(
+            self.api_request("GET", &conversation_url, None),
+            self.api_request("GET", &lines_url, None),
+            self.api_request("GET", &reviews_url, None)
+        )?;
+
+        let mut comments: Vec<ReviewComment> = conversation
+            .into_iter()
+            .chain(lines)
+            .chain(reviews)
+            .filter_map(GitHubPullComment::into_review_comment)
+            .collect();
+        comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
 
-        let contents = self
-            .fetch_contents(&owner, &repo, &path, branch.clone())
-            .await?;
+        Ok(comments)
+    }
 
-        let mut children = Vec::new();
-        let mut file_count = 0;
-        let mut dir_count = 1; // Count self
-        let mut total_size = 0;
+    async fn list_refs(&self, repo_path: &str, prefix: &str) -> Result<Vec<String>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
 
-        let mut tasks = Vec::new();
+        // The REST API cannot filter by prefix, the first page of each is enough to complete
+        let branches_url = format!(
+            "{}/repos/{}/{}/branches?per_page=100",
+            self.api_base, owner, repo
+        );
+        let tags_url = format!(
+            "{}/repos/{}/{}/tags?per_page=100",
+            self.api_base, owner, repo
+        );
 
-        for item in contents {
-            if !self.should_include(&item.path, include_patterns.clone())
-                || self.should_exclude(
-                    &item.path,
-                    exclude_patterns.clone(),
-                    ignore_patterns.clone(),
-                )
-            {
-                continue;
-            }
+        let (branches, tags): (Vec<GitHubNamedRef>, Vec<GitHubNamedRef>) = futures::try_join!(
+            self.api_request("GET", &branches_url, None),
+            self.api_request("GET", &tags_url, None)
+        )?;
 
-            match item.item_type {
-                RepoItemType::File => {
-                    let size = item.size.unwrap_or(0);
-                    total_size += size;
-                    file_count += 1;
+        Ok(branches
+            .into_iter()
+            .map(|branch| branch.name)
+            .chain(tags.into_iter().map(|tag| format!("tag:{}", tag.name)))
+            .filter(|name| name.starts_with(prefix))
+            .collect())
+    }
 
-                    children.push(RepoNode {
-                        name: item.name,
-                        node_type: RepoItemType::File,
-                        size,
-                        children: vec![],
-                        file_count: 1,
-                        dir_count: 0,
-                    });
-                }
-                RepoItemType::Directory => {
-                    let owner = owner.to_string();
-                    let repo = repo.to_string();
-                    let branch = branch.clone();
-                    let path = item.path;
-                    let exclude_patterns = exclude_patterns.to_vec();
-                    let include_patterns = include_patterns.to_vec();
-                    let ignore_patterns = ignore_patterns.to_vec();
+    async fn search_code(&self, repo_path: &str, query: &str, limit: usize) -> Result<Vec<String>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        // Quoted, the query matches the exact string rather than each of its words
+        let query = format!("\"{}\" repo:{}/{}", query.replace('"', "\\\""), owner, repo);
+        let results: GitHubCodeSearch = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/search/code?q={}&per_page={}",
+                    self.api_base,
+                    urlencoding::encode(&query),
+                    limit.clamp(1, 100)
+                ),
+                None,
+            )
+            .await?;
 
-                    tasks.push(self.build_tree(
-                        owner,
-                        repo,
-                        branch,
-                        path,
-                        exclude_patterns,
-                        include_patterns,
-                        ignore_patterns,
-                        depth + 1,
-                        max_depth,
-                    ));
-                }
-            }
+        Ok(results.items.into_iter().map(|item| item.path).collect())
+    }
 
-            // Check file limit
-            if file_count > MAX_FILES {
-                break;
-            }
-        }
+    async fn list_tags(&self, repo_path: &str, limit: usize) -> Result<Vec<TagInfo>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        let tags: Vec<GitHubTag> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/tags?per_page={}",
+                    self.api_base,
+                    owner,
+                    repo,
+                    limit.clamp(1, 100)
+                ),
+                None,
+            )
+            .await?;
 
-        let results = join_all(tasks).await;
-        for result in results {
-            match result {
-                Ok(child_node) => {
-                    file_count += child_node.file_count;
-                    dir_count += child_node.dir_count;
-                    total_size += child_node.size;
-                    children.push(child_node);
-                }
-                Err(e) => eprintln!("Task join error: {:?}", e),
+        // The tag list does not tell annotated tags apart, each costs one or two more requests
+        futures::future::try_join_all(tags.into_iter().take(limit).map(|tag| {
+            let (owner, repo) = (&owner, &repo);
+            async move {
+                let annotation = self.tag_annotation(owner, repo, &tag.name).await?;
+
+                Ok::<_, anyhow::Error>(TagInfo {
+                    name: tag.name,
+                    commit_sha: tag.commit.sha,
+                    annotated: annotation.is_some(),
+                    message: annotation
+                        .as_ref()
+                        .map(|a| a.message.trim_end().to_string())
+                        .filter(|m| !m.is_empty()),
+                    tagger: annotation.as_ref().map(|a| a.tagger.name.clone()),
+                    date: annotation.as_ref().map(|a| a.tagger.date.clone()),
+                    verification: annotation
+                        .and_then(|a| a.verification)
+                        .map(GitHubVerification::into_verification),
+                })
             }
-        }
-
-        // Sort children: directories first, then files, both alphabetically
-        children.sort_by(|a, b| match (a.node_type, b.node_type) {
-            (RepoItemType::Directory, RepoItemType::File) => std::cmp::Ordering::Less,
-            (RepoItemType::File, RepoItemType::Directory) => std::cmp::Ordering::Greater,
-            _ => a.name.cmp(&b.name),
-        });
-
-        Ok(RepoNode {
-            name: path.split('/').last().unwrap_or(&path).to_string(),
-            node_type: RepoItemType::Directory,
-            size: total_size,
-            children,
-            file_count,
-            dir_count,
-        })
+        }))
+        .await
     }
-}
 
-#[async_trait]
-impl GitProvider for GitHubProvider {
-    fn name(&self) -> &str {
-        "github"
+    async fn list_releases(&self, repo_path: &str, limit: usize) -> Result<Vec<ReleaseInfo>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        // Drafts are only listed to tokens with push access
+        let releases: Vec<GitHubRelease> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/releases?per_page={}",
+                    self.api_base,
+                    owner,
+                    repo,
+                    limit.clamp(1, 100)
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(releases
+            .into_iter()
+            .take(limit)
+            .map(|release| ReleaseInfo {
+                tag_name: release.tag_name,
+                name: release.name.filter(|name| !name.trim().is_empty()),
+                published_at: release.published_at,
+                notes: release
+                    .body
+                    .map(|body| body.trim_end().to_string())
+                    .filter(|body| !body.is_empty()),
+                prerelease: release.prerelease,
+                draft: release.draft,
+                url: Some(release.html_url),
+            })
+            .collect())
     }
 
-    async fn get_tree_structure(
+    async fn list_contributors(
         &self,
         repo_path: &str,
-        git_ref: Option<GitRef>,
-        exclude_patterns: Vec<String>,
-        include_patterns: Vec<String>,
-    ) -> Result<String> {
-        // Parse the repository path
-        let (owner, repo, mut path_branch, _path) = self.parse_repo_path(repo_path)?;
-
-        // Fetch repository metadata
-        let metadata = self.fetch_repo_metadata(&owner, &repo).await?;
-
-        // Determine which reference to use
-        let ref_name = match git_ref {
-            Some(GitRef::Branch(branch)) => Some(branch),
-            Some(GitRef::Tag(tag)) => Some(tag),
-            Some(GitRef::Commit(commit)) => Some(commit),
-            Some(GitRef::Default) => Some(metadata.default_branch.clone()),
-            None => {
-                if path_branch.is_none() {
-                    Some(metadata.default_branch.clone())
-                } else {
-                    path_branch.take()
-                }
-            }
-        };
-
-        // Set up ignored patterns from .gitignore
-        let ignore_patterns = self
-            .set_ignore_patterns(&owner, &repo, ref_name.clone())
+        limit: usize,
+    ) -> Result<Vec<ContributorInfo>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        // Already sorted by contributions, commits not matched to an account are left out
+        let contributors: Vec<GitHubContributor> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/contributors?per_page={}",
+                    self.api_base,
+                    owner,
+                    repo,
+                    limit.clamp(1, 100)
+                ),
+                None,
+            )
             .await?;
 
-        // Build the repository tree
-        let max_depth = 10; // Limit recursion depth
-        let root_node = Box::pin(self.build_tree(
-            owner,
-            repo.clone(),
-            ref_name,
-            "".into(),
-            exclude_patterns,
-            include_patterns,
-            ignore_patterns,
-            0,
-            max_depth,
-        ))
-        .await?;
+        Ok(contributors
+            .into_iter()
+            .take(limit)
+            .map(|contributor| ContributorInfo {
+                bot: contributor.account_type.as_deref() == Some("Bot"),
+                login: contributor.login,
+                email: None,
+                contributions: contributor.contributions,
+            })
+            .collect())
+    }
 
-        // Add the repo name as the root
-        let tree_node = RepoNode {
-            name: repo,
-            node_type: RepoItemType::Directory,
-            size: root_node.size,
-            children: root_node.children,
-            file_count: root_node.file_count,
-            dir_count: root_node.dir_count,
-        };
+    async fn list_languages(&self, repo_path: &str) -> Result<Vec<LanguageShare>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
 
-        // Create the tree structure string
-        let tree_str = create_tree_structure(&tree_node, "", true);
+        let languages: BTreeMap<String, u64> = self
+            .api_request(
+                "GET",
+                &format!("{}/repos/{}/{}/languages", self.api_base, owner, repo),
+                None,
+            )
+            .await?;
 
-        Ok(tree_str)
+        let total: u64 = languages.values().sum();
+        let mut shares: Vec<LanguageShare> = languages
+            .into_iter()
+            .map(|(name, bytes)| LanguageShare {
+                name,
+                percent: bytes as f64 * 100.0 / total.max(1) as f64,
+                bytes: Some(bytes),
+            })
+            .collect();
+        shares.sort_by_key(|share| Reverse(share.bytes));
+        Ok(shares)
     }
 
-    async fn get_file_content(
-        &self,
-        repo_path: &str,
-        file_path: &str,
-        git_ref: Option<GitRef>,
-    ) -> Result<String> {
-        // Parse the repository path
-        let (owner, repo, mut path_branch, _) = self.parse_repo_path(repo_path)?;
+    async fn get_repository_info(&self, repo_path: &str) -> Result<RepositoryInfo> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
 
-        // Fetch repository metadata to get default branch if needed
-        let metadata = self.fetch_repo_metadata(&owner, &repo).await?;
+        let info: GitHubRepoInfo = self
+            .api_request(
+                "GET",
+                &format!("{}/repos/{}/{}", self.api_base, owner, repo),
+                None,
+            )
+            .await?;
 
-        // Determine which reference to use
-        let ref_name = match git_ref {
-            Some(GitRef::Branch(branch)) => Some(branch),
-            Some(GitRef::Tag(tag)) => Some(tag),
-            Some(GitRef::Commit(commit)) => Some(commit),
-            Some(GitRef::Default) => Some(metadata.default_branch.clone()),
-            None => {
-                if path_branch.is_none() {
-                    Some(metadata.default_branch.clone())
-                } else {
-                    path_branch.take()
-                }
-            }
+        let description = info.description.filter(|d| !d.is_empty());
+        let mirror = match &info.mirror_url {
+            Some(url) => Some(MirrorOrigin::flagged(Some(url))),
+            None => description
+                .as_deref()
+                .and_then(MirrorOrigin::from_description),
         };
 
-        // Fetch the file content
-        self.fetch_file_content(&owner, &repo, file_path, ref_name)
-            .await
+        Ok(RepositoryInfo {
+            full_name: info.full_name,
+            description,
+            url: info.html_url,
+            default_branch: Some(info.default_branch),
+            stars: info.stargazers_count,
+            forks: info.forks_count,
+            open_issues: Some(info.open_issues_count),
+            language: info.language,
+            license: info.license.map(|license| license.name),
+            topics: info.topics,
+            archived: info.archived,
+            size_kb: info.size,
+            is_template: info.is_template,
+            generated_from: info.template_repository.map(|r| r.full_name),
+            forked_from: info.parent.map(|r| r.full_name),
+            mirror,
+            created_at: info.created_at,
+            last_activity_at: info.pushed_at,
+        })
     }
 
-    async fn find_repositories(
+    async fn find_template_repositories(
         &self,
         query: &str,
         limit: Option<usize>,
     ) -> Result<Vec<RepoSearchResult>> {
-        // Perform the GitHub repository search
-        let repos = self.search_repositories(query, limit).await?;
+        let repos = self
+            .search_repositories(&format!("{} template:true", query), limit)
+            .await?;
 
-        // Convert GitHub repository items to our common format
-        let results = repos
+        Ok(repos
             .into_iter()
             .map(|repo| RepoSearchResult {
-                provider: "github".into(),
+                provider: self.name.clone(),
                 full_name: repo.full_name,
                 description: repo.description,
                 stargazers_count: repo.stargazers_count,
             })
-            .collect();
+            .collect())
+    }
 
-        Ok(results)
+    async fn list_commits_since(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        since: &str,
+        max_commits: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+        let ref_name = self.resolve_ref_name(&owner, &repo, git_ref).await?;
+
+        let mut commits = Vec::new();
+        for page in 1..=max_commits.div_ceil(100) {
+            let batch: Vec<GitHubCommitEntry> = self
+                .api_request(
+                    "GET",
+                    &format!(
+                        "{}/repos/{}/{}/commits?sha={}&since={}&per_page=100&page={}",
+                        self.api_base,
+                        owner,
+                        repo,
+                        urlencoding::encode(&ref_name),
+                        urlencoding::encode(since),
+                        page
+                    ),
+                    None,
+                )
+                .await?;
+
+            let exhausted = batch.len() < 100;
+            commits.extend(batch.into_iter().map(GitHubCommitEntry::into_commit_info));
+            if exhausted {
+                break;
+            }
+        }
+
+        commits.truncate(max_commits);
+        Ok(commits)
+    }
+
+    async fn branch_divergence(
+        &self,
+        repo_path: &str,
+        limit: usize,
+    ) -> Result<(String, Vec<BranchDivergence>)> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+        let default_branch = self.resolve_ref_name(&owner, &repo, None).await?;
+
+        let branches: Vec<GitHubNamedRef> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repos/{}/{}/branches?per_page=100",
+                    self.api_base, owner, repo
+                ),
+                None,
+            )
+            .await?;
+
+        let divergences = join_all(
+            branches
+                .into_iter()
+                .filter(|branch| branch.name != default_branch)
+                .take(limit)
+                .map(|branch| {
+                    let owner = &owner;
+                    let repo = &repo;
+                    let default_branch = &default_branch;
+                    async move {
+                        // One commit per page keeps the comparison cheap, only the counts are read
+                        let compare_url = format!(
+                            "{}/repos/{}/{}/compare/{}...{}?per_page=1", self.api_base,
+                            owner,
+                            repo,
+                            urlencoding::encode(default_branch),
+                            urlencoding::encode(&branch.name)
+                        );
+                        let commits_url = format!(
+                            "{}/repos/{}/{}/commits?sha={}&per_page=1", self.api_base,
+                            owner,
+                            repo,
+                            urlencoding::encode(&branch.name)
+                        );
+
+                        let (counts, latest): (GitHubCompareCounts, Vec<GitHubCommitEntry>) = futures::try_join!(
+                            self.api_request("GET", &compare_url, None),
+                            self.api_request("GET", &commits_url, None)
+                        )?;
+
+                        Ok::<_, anyhow::Error>(BranchDivergence {
+                            branch: branch.name,
+                            ahead: counts.ahead_by,
+                            behind: counts.behind_by,
+                            last_commit_date: latest
+                                .into_iter()
+                                .next()
+                                .map(|commit| commit.into_commit_info().date),
+                        })
+                    }
+                }),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        Ok((default_branch, divergences))
+    }
+
+    async fn clone_remote(&self, repo_path: &str) -> Result<CloneRemote> {
+        let (owner, repo, _, _) = self.parse_repo_path(repo_path)?;
+
+        Ok(CloneRemote {
+            url: format!("{}/{}/{}.git", self.web_base, owner, repo),
+            credentials: self
+                .github_token
+                .clone()
+                .map(|token| ("x-access-token".to_string(), token)),
+        })
     }
 }
@@ -0,0 +1,119 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::{GitProvider, RepoEventKind};
+use serde_json::{Value, json};
+
+use crate::common::{
+    iso_timestamp_days_ago, repo_argument, required_arguments, supported_providers, usize_argument,
+};
+
+const DEFAULT_WINDOW_DAYS: usize = 7;
+
+fn kind_label(kind: RepoEventKind) -> &'static str {
+    match kind {
+        RepoEventKind::Push => "Pushes",
+        RepoEventKind::Release => "Releases",
+        RepoEventKind::Issue => "Issues",
+        RepoEventKind::PullRequest => "Pull requests",
+        RepoEventKind::Comment => "Comments and reviews",
+        RepoEventKind::Ref => "Branches and tags",
+        RepoEventKind::Other => "Other",
+    }
+}
+
+pub struct RepositoryActivity {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositoryActivity {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositoryActivity {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let days = usize_argument(&args, "days").unwrap_or(DEFAULT_WINDOW_DAYS);
+        let since = iso_timestamp_days_ago(days as u64);
+
+        let events = provider
+            .list_events(repo_path, Some(&since))
+            .await
+            .map_err(|e| anyhow!("Error getting repository activity: {}", e))?;
+
+        if events.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!(
+                    "No activity found for {} in the last {} days",
+                    repo_path, days
+                ),
+            }]);
+        }
+
+        // Group events by kind, keeping each group newest first
+        let mut groups: BTreeMap<RepoEventKind, Vec<_>> = BTreeMap::new();
+        for event in &events {
+            groups.entry(event.kind).or_default().push(event);
+        }
+
+        let mut formatted_output = format!(
+            "Activity for {} in the last {} days ({} events)\n\n",
+            repo_path,
+            days,
+            events.len()
+        );
+
+        for (kind, group) in &groups {
+            formatted_output.push_str(&format!("- {}: {}\n", kind_label(*kind), group.len()));
+        }
+
+        for (kind, group) in groups {
+            formatted_output.push_str(&format!("\n## {}\n\n", kind_label(kind)));
+            for event in group {
+                formatted_output.push_str(&format!(
+                    "- {} {} {}\n",
+                    event.created_at.get(..10).unwrap_or(&event.created_at),
+                    event.actor,
+                    event.summary
+                ));
+            }
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_activity".into(),
+            description: Some(format!(
+                "Summarize recent activity of a Git repository (pushes, releases, issues and pull requests opened or closed, comments) over a time window. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "days": {
+                        "type": "integer",
+                        "description": format!("Optional size of the time window in days. Default: {}", DEFAULT_WINDOW_DAYS)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
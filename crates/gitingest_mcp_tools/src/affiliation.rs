@@ -0,0 +1,204 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::common::{
+    git_ref_argument, iso_timestamp_days_ago, repo_argument, required_arguments,
+    supported_providers, usize_argument,
+};
+
+const DEFAULT_WINDOW_DAYS: usize = 365;
+const DEFAULT_MAX_COMMITS: usize = 1000;
+/// Each page of 100 commits costs one request
+const MAX_COMMITS: usize = 5000;
+
+/// Free email providers, whose users say nothing about who employs them
+const PERSONAL_DOMAINS: &[&str] = &[
+    "gmail.com",
+    "googlemail.com",
+    "outlook.com",
+    "hotmail.com",
+    "live.com",
+    "yahoo.com",
+    "icloud.com",
+    "me.com",
+    "protonmail.com",
+    "proton.me",
+    "pm.me",
+    "gmx.com",
+    "gmx.de",
+    "web.de",
+    "mail.ru",
+    "yandex.ru",
+    "qq.com",
+    "163.com",
+    "126.com",
+    "fastmail.com",
+];
+
+/// Domains of addresses hiding the author's real one
+const PRIVATE_DOMAINS: &[&str] = &["users.noreply.github.com", "users.noreply.gitlab.com"];
+
+/// The affiliation group an email address counts towards
+fn affiliation(email: Option<&str>) -> String {
+    let Some(domain) = email
+        .and_then(|email| email.rsplit_once('@'))
+        .map(|(_, domain)| domain.trim().to_lowercase())
+        .filter(|domain| !domain.is_empty())
+    else {
+        return "(unknown)".to_string();
+    };
+
+    if PRIVATE_DOMAINS.contains(&domain.as_str()) || domain == "noreply.github.com" {
+        "(private address)".to_string()
+    } else if PERSONAL_DOMAINS.contains(&domain.as_str()) {
+        "(personal address)".to_string()
+    } else {
+        domain
+    }
+}
+
+#[derive(Default)]
+struct Affiliation {
+    commits: usize,
+    authors: BTreeSet<String>,
+}
+
+pub struct ContributorAffiliation {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ContributorAffiliation {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ContributorAffiliation {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let window_days = usize_argument(&args, "days").unwrap_or(DEFAULT_WINDOW_DAYS);
+        let max_commits = usize_argument(&args, "max_commits")
+            .unwrap_or(DEFAULT_MAX_COMMITS)
+            .clamp(1, MAX_COMMITS);
+
+        let since = iso_timestamp_days_ago(window_days as u64);
+        let commits = provider
+            .list_commits_since(repo_path, git_ref, &since, max_commits)
+            .await
+            .map_err(|e| anyhow!("Error listing commits: {}", e))?;
+
+        if commits.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!(
+                    "No commits found for {} in the last {} days",
+                    repo_path, window_days
+                ),
+            }]);
+        }
+
+        let mut affiliations: BTreeMap<String, Affiliation> = BTreeMap::new();
+        let mut authors = BTreeSet::new();
+        for commit in &commits {
+            // Authors are told apart by address, names are spelled inconsistently
+            let author = commit
+                .author_email
+                .as_deref()
+                .unwrap_or(&commit.author)
+                .to_lowercase();
+            authors.insert(author.clone());
+
+            let entry = affiliations
+                .entry(affiliation(commit.author_email.as_deref()))
+                .or_default();
+            entry.commits += 1;
+            entry.authors.insert(author);
+        }
+
+        let mut ranked: Vec<(String, Affiliation)> = affiliations.into_iter().collect();
+        ranked.sort_by(|a, b| {
+            b.1.commits
+                .cmp(&a.1.commits)
+                .then_with(|| b.1.authors.len().cmp(&a.1.authors.len()))
+                .then_with(|| a.0.cmp(&b.0))
+        });
+
+        let mut formatted_output = format!(
+            "Contributor affiliation of {} over the last {} days ({} commits, {} authors)\n\n",
+            repo_path,
+            window_days,
+            commits.len(),
+            authors.len()
+        );
+        if commits.len() == max_commits {
+            formatted_output.push_str(&format!(
+                "Note: stopped at {} commits, older commits in the window are not counted\n\n",
+                max_commits
+            ));
+        }
+
+        formatted_output.push_str("| Email domain | Commits | Share | Authors |\n");
+        formatted_output.push_str("|---|---|---|---|\n");
+        for (domain, affiliation) in ranked {
+            formatted_output.push_str(&format!(
+                "| {} | {} | {:.1}% | {} |\n",
+                domain,
+                affiliation.commits,
+                affiliation.commits as f64 * 100.0 / commits.len() as f64,
+                affiliation.authors.len()
+            ));
+        }
+        formatted_output.push_str(
+            "\nPersonal and private addresses do not reveal an employer, the domain of an address is only a hint of affiliation.\n",
+        );
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "contributor_affiliation".into(),
+            description: Some(format!(
+                "Summarize who backs a project by grouping commit authors by email domain over a time window. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "days": {
+                        "type": "integer",
+                        "description": format!("Optional number of days looked back. Default: {}", DEFAULT_WINDOW_DAYS)
+                    },
+                    "max_commits": {
+                        "type": "integer",
+                        "description": format!("Optional number of most recent commits counted, at most {}. Default: {}", MAX_COMMITS, DEFAULT_MAX_COMMITS)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::{
+    common::{
+        bool_argument, git_ref_argument, repo_argument, required_arguments, supported_providers,
+    },
+    patch::{ApplyOutcome, apply_file_patch, parse_unified_diff},
+};
+
+pub struct ApplyPatchPreview {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ApplyPatchPreview {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ApplyPatchPreview {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+
+        let diff = args
+            .get("patch")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing or invalid patch"))?;
+
+        let include_content = bool_argument(&args, "include_content").unwrap_or(true);
+
+        let patches = parse_unified_diff(diff)?;
+
+        // Fetch every original blob the patch touches in parallel
+        let originals = join_all(patches.iter().map(|patch| {
+            let git_ref = git_ref.clone();
+            async move {
+                match (&patch.old_path, &patch.new_path) {
+                    (Some(path), _) => provider
+                        .get_file_content(repo_path, path, git_ref)
+                        .await
+                        .map(Some),
+                    // A file the patch creates is expected to be missing, reading it only tells
+                    // whether it is already there
+                    (None, Some(path)) => Ok(provider
+                        .get_file_content(repo_path, path, git_ref)
+                        .await
+                        .ok()),
+                    (None, None) => Ok(None),
+                }
+            }
+        }))
+        .await;
+
+        let mut applied = 0;
+        let mut output = String::new();
+
+        for (patch, original) in patches.iter().zip(originals) {
+            let original = match original {
+                Ok(original) => original,
+                Err(e) => {
+                    output.push_str(&format!(
+                        "## {} — conflict\n\nCould not read the original file: {}\n\n",
+                        patch.path(),
+                        e
+                    ));
+                    continue;
+                }
+            };
+
+            match apply_file_patch(original.as_deref(), patch) {
+                ApplyOutcome::Applied(None) => {
+                    applied += 1;
+                    output.push_str(&format!("## {} — deleted\n\n", patch.path()));
+                }
+                ApplyOutcome::Applied(Some(content)) => {
+                    applied += 1;
+                    let status = match (&patch.old_path, &patch.new_path) {
+                        (None, _) => "created".to_string(),
+                        (Some(old), Some(new)) if old != new => format!("renamed from {}", old),
+                        _ => "applied".to_string(),
+                    };
+                    output.push_str(&format!("## {} — {}\n\n", patch.path(), status));
+
+                    if include_content {
+                        let extension = patch.path().rsplit('.').next().unwrap_or("");
+                        output.push_str(&format!(
+                            "```{}\n{}\n```\n\n",
+                            extension,
+                            content.trim_end_matches('\n')
+                        ));
+                    }
+                }
+                ApplyOutcome::AlreadyExists => {
+                    output.push_str(&format!(
+                        "## {} — conflict\n\nThe patch creates this file, but it already exists\n\n",
+                        patch.path()
+                    ));
+                }
+                ApplyOutcome::Conflict(conflicts) => {
+                    output.push_str(&format!(
+                        "## {} — conflict ({} of {} hunks failed)\n\n",
+                        patch.path(),
+                        conflicts.len(),
+                        patch.hunks.len()
+                    ));
+
+                    for conflict in conflicts {
+                        output.push_str(&format!(
+                            "{}\nExpected to find:\n```\n{}\n```\n\n",
+                            conflict.header,
+                            conflict.expected.join("\n")
+                        ));
+                    }
+                }
+            }
+        }
+
+        let summary = format!(
+            "Patch preview: {} of {} files applied cleanly\n\n",
+            applied,
+            patches.len()
+        );
+
+        Ok(vec![ToolContent::Text {
+            text: format!("{}{}", summary, output),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "apply_patch_preview".into(),
+            description: Some(format!(
+                "Apply a unified diff in memory against a Git repository and return the resulting files or conflicts, without writing anything. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "patch": {
+                        "type": "string",
+                        "description": "Unified diff to apply, as produced by 'git diff' or 'diff -u'"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "include_content": {
+                        "type": "boolean",
+                        "description": "Optional flag to include the full content of patched files. Default: true"
+                    }
+                },
+                "required": ["repo", "patch"]
+            }),
+        }
+    }
+}
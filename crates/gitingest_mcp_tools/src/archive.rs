@@ -0,0 +1,255 @@
+use std::{fs, path::Path, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use flate2::{Compression, write::GzEncoder};
+use futures::{StreamExt, stream};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::{
+    common::{
+        ANONYMOUS_MAX_FILES, FETCH_CONCURRENCY, anonymous_limit_note, bool_argument,
+        check_repository_size, git_ref_argument, max_files_argument, preset_names, repo_argument,
+        required_arguments, supported_providers, tree_options_argument, warn_skipped,
+        warn_truncated,
+    },
+    i18n::Message,
+    write_roots::WriteRoots,
+};
+
+pub(crate) const DEFAULT_MAX_FILES: usize = 500;
+/// Largest archive returned inline as base64, bigger ones must be written to a file
+const MAX_INLINE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Packs files into a gzipped tarball whose bytes only depend on the paths and contents:
+/// entries are sorted and carry neither timestamps nor owners
+fn build_tarball(files: &[(String, String)], prefix: &str) -> Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (path, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(0);
+        header.set_uid(0);
+        header.set_gid(0);
+        builder.append_data(
+            &mut header,
+            format!("{}{}", prefix, path),
+            content.as_bytes(),
+        )?;
+    }
+
+    Ok(builder.into_inner()?.finish()?)
+}
+
+pub struct RepositoryArchive {
+    providers: Vec<Arc<dyn GitProvider>>,
+    write_roots: WriteRoots,
+}
+
+impl RepositoryArchive {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>, write_roots: WriteRoots) -> Self {
+        Self {
+            providers,
+            write_roots,
+        }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositoryArchive {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let max_files = max_files_argument(provider, &args, DEFAULT_MAX_FILES);
+        // Refused before anything is fetched, archives returned inline need no write access
+        let target = args
+            .get("target_path")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|target| self.write_roots.resolve(Path::new(target)))
+            .transpose()?;
+        // Entries live below a single directory when a prefix is given, like `git archive`
+        let prefix = match args
+            .get("prefix")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim_matches('/'))
+        {
+            Some(prefix) if !prefix.is_empty() => format!("{}/", prefix),
+            _ => String::new(),
+        };
+
+        let options = tree_options_argument(&args)?;
+
+        let force = bool_argument(&args, "force").unwrap_or(false);
+        let size_warning = check_repository_size(provider, repo_path, force).await?;
+
+        let mut files = provider
+            .list_files(repo_path, git_ref.clone(), &options)
+            .await
+            .map_err(|e| anyhow!("Error listing repository files: {}", e))?;
+
+        if files.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("No files of {} match the patterns", repo_path),
+            }]);
+        }
+
+        let total_files = files.len();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files.truncate(max_files);
+
+        // Owned paths, so the stream stays `Send` without borrowing `files`
+        let paths: Vec<String> = files.iter().map(|file| file.path.clone()).collect();
+        let contents: Vec<Result<String>> = stream::iter(paths)
+            .map(|path| {
+                let git_ref = git_ref.clone();
+                async move { provider.get_file_content(repo_path, &path, git_ref).await }
+            })
+            .buffered(FETCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut entries = Vec::new();
+        let mut skipped = Vec::new();
+        for (file, content) in files.iter().zip(contents) {
+            match content {
+                Ok(content) => entries.push((file.path.clone(), content)),
+                Err(e) => {
+                    warn_skipped(&file.path, &e);
+                    skipped.push(format!("{}: {}", file.path, e));
+                }
+            }
+        }
+
+        let archive = build_tarball(&entries, &prefix)?;
+
+        let mut formatted_output = size_warning.unwrap_or_default();
+        match &target {
+            Some(target) => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(target, &archive)?;
+
+                formatted_output.push_str(&format!(
+                    "Wrote {} files of {} to {} ({} bytes, tar.gz)\n",
+                    entries.len(),
+                    repo_path,
+                    target.display(),
+                    archive.len()
+                ));
+            }
+            None => {
+                if archive.len() > MAX_INLINE_BYTES {
+                    return Err(anyhow!(
+                        "The archive is {} bytes, above the {} bytes returned inline. Pass target_path to write it to a file, or narrow the patterns",
+                        archive.len(),
+                        MAX_INLINE_BYTES
+                    ));
+                }
+
+                formatted_output.push_str(&format!(
+                    "Archive of {} files of {} ({} bytes, base64-encoded tar.gz)\n",
+                    entries.len(),
+                    repo_path,
+                    archive.len()
+                ));
+            }
+        }
+
+        if !skipped.is_empty() {
+            formatted_output.push_str(&format!(
+                "\nLeft out {} files that could not be read as text:\n",
+                skipped.len()
+            ));
+            for failure in skipped {
+                formatted_output.push_str(&format!("- {}\n", failure));
+            }
+        }
+
+        if let Some(note) = anonymous_limit_note(provider, &args, files.len(), total_files) {
+            formatted_output.push_str(&format!("\n({})\n", note));
+        } else if total_files > files.len() {
+            warn_truncated(files.len(), total_files, Message::Files, "max_files");
+            formatted_output.push_str(&format!(
+                "\n({} more files not archived, raise max_files or narrow the patterns)\n",
+                total_files - files.len()
+            ));
+        }
+
+        if target.is_none() {
+            formatted_output.push_str(&format!("\n{}\n", base64::encode(&archive)));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_archive".into(),
+            description: Some(format!(
+                "Pack the files of a repository matching the patterns into a reproducible tar.gz, written to a local file or returned base64-encoded. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "target_path": {
+                        "type": "string",
+                        "description": "Optional local file the archive is written to, below the directories of GITINGEST_WRITE_ROOTS and only when the server runs with --enable-write. When omitted, the archive is returned base64-encoded"
+                    },
+                    "prefix": {
+                        "type": "string",
+                        "description": "Optional directory every entry of the archive is placed under"
+                    },
+                    "exclude_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to exclude"
+                    },
+                    "include_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to include"
+                    },
+                    "preset": {
+                        "type": "string",
+                        "description": format!("Optional comma-separated list of ecosystem presets adding their source and exclude patterns: {}", preset_names())
+                    },
+                    "extensions": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of file extensions to include, e.g. 'rs,toml,md'"
+                    },
+                    "case_insensitive": {
+                        "type": "boolean",
+                        "description": "Optional flag to match every pattern regardless of case. Default: false"
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "description": format!("Optional maximum number of files to archive. Default: {}, or {} for providers called without a token", DEFAULT_MAX_FILES, ANONYMOUS_MAX_FILES)
+                    },
+                    "force": {
+                        "type": "boolean",
+                        "description": "Optional flag to archive repositories above the server's size limit. Default: false"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{CompareMode, GitProvider};
+use serde_json::{Value, json};
+
+use crate::{
+    common::{
+        git_ref_argument, parse_git_ref, repo_argument, required_arguments, string_argument,
+        supported_providers, usize_argument,
+    },
+    diff::{diff_render_argument, render_file_diff, touches},
+};
+
+pub(crate) const DEFAULT_MAX_COMMITS: usize = 20;
+/// Each listed commit costs one request for its diff
+pub(crate) const MAX_COMMITS: usize = 100;
+
+pub struct BisectHelper {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl BisectHelper {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for BisectHelper {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let path = string_argument(&args, "path")?.trim_matches('/');
+        if path.is_empty() {
+            return Err(anyhow!("The path must name a file or directory"));
+        }
+        let good = parse_git_ref(string_argument(&args, "good")?);
+        let bad = git_ref_argument(&args, "bad");
+        let max_commits = usize_argument(&args, "max_commits")
+            .unwrap_or(DEFAULT_MAX_COMMITS)
+            .clamp(1, MAX_COMMITS);
+        let render = diff_render_argument(&args)?;
+
+        let comparison = provider
+            .compare_refs(repo_path, Some(good), bad.clone(), CompareMode::Direct)
+            .await
+            .map_err(|e| anyhow!("Error comparing references: {}", e))?;
+
+        // The history of the path on the bad side, narrowed down to the compared range, spares
+        // reading every commit of the range
+        let in_range = provider
+            .list_commits(repo_path, bad, Some(path), comparison.commits.len().max(1))
+            .await
+            .map_err(|e| anyhow!("Error listing commits: {}", e))?
+            .into_iter()
+            .map(|commit| commit.sha)
+            .collect::<HashSet<_>>();
+        let mut commits: Vec<_> = comparison
+            .commits
+            .iter()
+            .filter(|commit| in_range.contains(&commit.sha))
+            .collect();
+
+        if commits.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!(
+                    "No commits between {} and {} touched {} ({} commits in range)",
+                    comparison.base,
+                    comparison.head,
+                    path,
+                    comparison.commits.len()
+                ),
+            }]);
+        }
+
+        // Testing the middle commit halves the candidates, as `git bisect` would
+        let total = commits.len();
+        let midpoint = commits[(total - 1) / 2];
+        commits.truncate(max_commits);
+
+        let details = join_all(
+            commits
+                .iter()
+                .map(|commit| provider.get_commit(repo_path, &commit.sha)),
+        )
+        .await;
+
+        let mut output = format!(
+            "Commits touching {} between {} and {}: {} of {} commits in range, oldest first\n",
+            path,
+            comparison.base,
+            comparison.head,
+            total,
+            comparison.commits.len()
+        );
+        if total > commits.len() {
+            output.push_str(&format!(
+                "Only the first {} are shown, raise max_commits or narrow the range\n",
+                commits.len()
+            ));
+        }
+        if total > 1 {
+            output.push_str(&format!(
+                "Midpoint to test next: {} ({})\n",
+                midpoint.sha,
+                midpoint.subject()
+            ));
+        }
+
+        for (index, (commit, details)) in commits.iter().zip(details).enumerate() {
+            output.push_str(&format!(
+                "\n# {}. {} {}\n{}, {}",
+                index + 1,
+                &commit.sha[..commit.sha.len().min(7)],
+                commit.subject(),
+                commit.author,
+                commit.date
+            ));
+            if let Some(verification) = &commit.verification {
+                output.push_str(&format!(", {}", verification.describe()));
+            }
+            output.push('\n');
+
+            match details {
+                Ok(details) => {
+                    for file in details.files.iter().filter(|file| touches(file, path)) {
+                        output.push('\n');
+                        output.push_str(&render_file_diff(file, render, None));
+                    }
+                }
+                Err(e) => output.push_str(&format!("\n(diff unavailable: {})\n", e)),
+            }
+        }
+
+        Ok(vec![ToolContent::Text { text: output }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "bisect_helper".into(),
+            description: Some(format!(
+                "List the commits between a known-good and a known-bad reference that touched a file or directory, oldest first, with their diffs to that path and the midpoint to test next. Helps hunting regressions without a local checkout. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "File or directory path the regression is suspected in (e.g., 'src/parser.rs')"
+                    },
+                    "good": {
+                        "type": "string",
+                        "description": "Git reference known to work: branch name, 'tag:name', or 'commit:sha'"
+                    },
+                    "bad": {
+                        "type": "string",
+                        "description": "Optional git reference known to be broken: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "max_commits": {
+                        "type": "integer",
+                        "description": format!("Optional number of commits shown with their diffs, at most {}. Default: {}", MAX_COMMITS, DEFAULT_MAX_COMMITS)
+                    },
+                    "render": {
+                        "type": "string",
+                        "enum": ["unified", "summary", "stats"],
+                        "description": "Optional diff rendering: complete patches, hunk headers only, or line counts only. Default: unified"
+                    }
+                },
+                "required": ["repo", "path", "good"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::common::{
+    iso_timestamp_days_ago, repo_argument, required_arguments, supported_providers, usize_argument,
+};
+
+const DEFAULT_LIMIT: usize = 30;
+/// Each branch costs two requests
+const MAX_LIMIT: usize = 100;
+const DEFAULT_STALE_DAYS: usize = 90;
+
+pub struct BranchDivergence {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl BranchDivergence {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for BranchDivergence {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let limit = usize_argument(&args, "limit")
+            .unwrap_or(DEFAULT_LIMIT)
+            .clamp(1, MAX_LIMIT);
+        let stale_days = usize_argument(&args, "stale_days").unwrap_or(DEFAULT_STALE_DAYS);
+
+        let (default_branch, mut branches) = provider
+            .branch_divergence(repo_path, limit)
+            .await
+            .map_err(|e| anyhow!("Error comparing branches: {}", e))?;
+
+        if branches.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("{} has no branches besides {}", repo_path, default_branch),
+            }]);
+        }
+
+        // Longest-running work first, branches without unique commits last
+        branches.sort_by(|a, b| {
+            b.ahead
+                .cmp(&a.ahead)
+                .then_with(|| b.behind.cmp(&a.behind))
+                .then_with(|| a.branch.cmp(&b.branch))
+        });
+
+        // Dates are compared on their day, hosts disagree on offsets and fractional seconds
+        let stale_before = iso_timestamp_days_ago(stale_days as u64);
+        let stale_before = &stale_before[..10];
+
+        let mut formatted_output = format!(
+            "{} branches of {} compared with {}\n\n",
+            branches.len(),
+            repo_path,
+            default_branch
+        );
+        formatted_output.push_str("| Branch | Ahead | Behind | Last commit | Notes |\n");
+        formatted_output.push_str("|---|---|---|---|---|\n");
+        for branch in branches {
+            let day = branch
+                .last_commit_date
+                .as_deref()
+                .map(|date| date.get(..10).unwrap_or(date));
+
+            let mut notes = Vec::new();
+            if branch.ahead == 0 {
+                notes.push("merged");
+            }
+            if day.is_some_and(|day| day < stale_before) {
+                notes.push("stale");
+            }
+
+            formatted_output.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                branch.branch,
+                branch.ahead,
+                branch.behind,
+                day.unwrap_or("-"),
+                notes.join(", ")
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "branch_divergence".into(),
+            description: Some(format!(
+                "List how many commits every branch is ahead of and behind the default branch, flagging merged and stale branches. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": format!("Optional number of branches compared, at most {}. Default: {}", MAX_LIMIT, DEFAULT_LIMIT)
+                    },
+                    "stale_days": {
+                        "type": "integer",
+                        "description": format!("Optional number of days without commits after which a branch is flagged as stale. Default: {}", DEFAULT_STALE_DAYS)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
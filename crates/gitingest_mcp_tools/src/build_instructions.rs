@@ -0,0 +1,680 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{GitProvider, TreeOptions};
+use regex::Regex;
+use serde_json::{Value, json};
+
+use crate::common::{git_ref_argument, repo_argument, required_arguments, supported_providers};
+
+/// File stems of the documents describing how to work on a project
+const GUIDE_STEMS: &[&str] = &[
+    "readme",
+    "contributing",
+    "building",
+    "build",
+    "install",
+    "hacking",
+    "development",
+];
+
+/// Fence languages of shell snippets, an untagged fence included
+const SHELL_LANGUAGES: &[&str] = &[
+    "",
+    "sh",
+    "bash",
+    "shell",
+    "console",
+    "zsh",
+    "fish",
+    "powershell",
+    "ps",
+    "cmd",
+];
+
+/// Top-level keys of .gitlab-ci.yml that are not jobs
+const GITLAB_RESERVED: &[&str] = &[
+    "default",
+    "include",
+    "stages",
+    "variables",
+    "workflow",
+    "image",
+    "services",
+    "cache",
+    "before_script",
+    "after_script",
+];
+
+/// What a command is for, in the order they are listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Category {
+    Setup,
+    Build,
+    Test,
+    Lint,
+    Run,
+    Other,
+}
+
+impl Category {
+    const ALL: [Category; 6] = [
+        Self::Setup,
+        Self::Build,
+        Self::Test,
+        Self::Lint,
+        Self::Run,
+        Self::Other,
+    ];
+
+    /// Guesses the purpose of a command from its words and the name of its target or script
+    fn of(name: &str, command: &str) -> Self {
+        let text = format!("{} {}", name, command).to_lowercase();
+        let words: BTreeSet<&str> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        let any = |candidates: &[&str]| candidates.iter().any(|word| words.contains(word));
+
+        if any(&[
+            "lint", "clippy", "fmt", "format", "eslint", "prettier", "ruff", "black", "flake8",
+            "mypy", "vet",
+        ]) {
+            Self::Lint
+        } else if any(&[
+            "test", "tests", "pytest", "jest", "vitest", "spec", "coverage", "nextest",
+        ]) {
+            Self::Test
+        } else if any(&[
+            "install",
+            "setup",
+            "bootstrap",
+            "deps",
+            "venv",
+            "requirements",
+            "sync",
+        ]) {
+            Self::Setup
+        } else if any(&[
+            "build", "compile", "make", "tsc", "bundle", "package", "dist", "release",
+        ]) {
+            Self::Build
+        } else if any(&["run", "start", "serve", "dev", "up", "watch"]) {
+            Self::Run
+        } else {
+            Self::Other
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::Setup => "Setup",
+            Self::Build => "Build",
+            Self::Test => "Test",
+            Self::Lint => "Lint and format",
+            Self::Run => "Run",
+            Self::Other => "Other",
+        }
+    }
+}
+
+/// A command found in the repository, with what it runs when it names a target or script
+struct Command {
+    text: String,
+    runs: Option<String>,
+    category: Category,
+    /// Where the command was found, e.g. "Makefile:12"
+    sources: Vec<String>,
+}
+
+/// Commands gathered from every source, merged by their text
+#[derive(Default)]
+struct Commands(Vec<Command>);
+
+impl Commands {
+    fn add(&mut self, name: &str, text: &str, runs: Option<&str>, source: String) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+
+        match self.0.iter_mut().find(|command| command.text == text) {
+            Some(command) => {
+                if !command.sources.contains(&source) {
+                    command.sources.push(source);
+                }
+            }
+            None => self.0.push(Command {
+                text: text.to_string(),
+                runs: runs.map(|runs| runs.trim().to_string()),
+                category: Category::of(name, runs.unwrap_or(text)),
+                sources: vec![source],
+            }),
+        }
+    }
+}
+
+/// Joins the lines of a snippet ending with a backslash to the next one
+fn logical_lines(snippet: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+    for (index, line) in snippet.lines().enumerate() {
+        let (start, mut text) = current.take().unwrap_or((index, String::new()));
+        match line.trim_end().strip_suffix('\\') {
+            Some(continued) => {
+                text.push_str(continued.trim());
+                text.push(' ');
+                current = Some((start, text));
+            }
+            None => {
+                text.push_str(line.trim());
+                lines.push((start, text));
+            }
+        }
+    }
+    lines.extend(current);
+    lines
+}
+
+/// A fenced block being read from a guide
+enum Fence {
+    /// A shell snippet, whose lines need a prompt to be commands in a console session
+    Shell { console: bool },
+    /// A snippet in another language
+    Skipped,
+}
+
+/// Shell commands of the fenced snippets under headings about building, installing, testing
+/// or running the project
+fn guide_commands(path: &str, markdown: &str, commands: &mut Commands) -> Result<()> {
+    let relevant = Regex::new(
+        r"(?i)build|install|setup|set up|getting started|quick ?start|develop|test|running|usage|compil|contribut",
+    )?;
+
+    let mut heading = String::new();
+    let mut fence: Option<Fence> = None;
+    let mut snippet_start = 0;
+    let mut snippet = String::new();
+    for (index, line) in markdown.lines().enumerate() {
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            match fence.take() {
+                Some(Fence::Shell { console }) => {
+                    if relevant.is_match(&heading) {
+                        for (offset, command) in logical_lines(&snippet) {
+                            // Console sessions mix commands, prefixed by a prompt, with output
+                            let command = match command.strip_prefix("$ ") {
+                                Some(command) => command,
+                                None if console => continue,
+                                None => command.trim_start_matches("> "),
+                            };
+                            if command.is_empty() || command.starts_with('#') {
+                                continue;
+                            }
+                            commands.add(
+                                "",
+                                command,
+                                None,
+                                format!("{}:{} ({})", path, snippet_start + offset + 1, heading),
+                            );
+                        }
+                    }
+                    snippet.clear();
+                }
+                Some(Fence::Skipped) => snippet.clear(),
+                None => {
+                    let language = trimmed.trim_start_matches(['`', '~']).trim().to_lowercase();
+                    fence = Some(if SHELL_LANGUAGES.contains(&language.as_str()) {
+                        Fence::Shell {
+                            console: language == "console",
+                        }
+                    } else {
+                        Fence::Skipped
+                    });
+                    snippet_start = index + 1;
+                }
+            }
+            continue;
+        }
+
+        match &fence {
+            Some(_) => {
+                snippet.push_str(line);
+                snippet.push('\n');
+            }
+            None if trimmed.starts_with('#') => {
+                heading = trimmed.trim_start_matches('#').trim().to_string();
+            }
+            None => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Targets of a Makefile or recipes of a justfile, with the first command they run
+fn recipe_commands(path: &str, content: &str, tool: &str, commands: &mut Commands) -> Result<()> {
+    let target = Regex::new(r"^([A-Za-z0-9][A-Za-z0-9_.-]*)(?:\s+[^:=]*)?:(?:[^=]|$)")?;
+
+    let lines: Vec<&str> = content.lines().collect();
+    for (index, line) in lines.iter().enumerate() {
+        let Some(captures) = target.captures(line) else {
+            continue;
+        };
+        let name = &captures[1];
+        // Variables and pattern rules are not meant to be invoked
+        if name.contains('.') || name.contains('%') {
+            continue;
+        }
+
+        let recipe = lines[index + 1..]
+            .iter()
+            .take_while(|line| line.starts_with([' ', '\t']) || line.trim().is_empty())
+            .map(|line| line.trim().trim_start_matches(['@', '-']))
+            .find(|line| !line.is_empty() && !line.starts_with('#'));
+
+        commands.add(
+            name,
+            &format!("{} {}", tool, name),
+            recipe,
+            format!("{}:{}", path, index + 1),
+        );
+    }
+
+    Ok(())
+}
+
+/// The scripts of a package.json, run with the package manager its lockfile belongs to
+fn package_commands(path: &str, content: &str, runner: &str, commands: &mut Commands) {
+    let Ok(manifest) = serde_json::from_str::<Value>(content) else {
+        return;
+    };
+    let Some(scripts) = manifest.get("scripts").and_then(|v| v.as_object()) else {
+        return;
+    };
+
+    for (name, script) in scripts {
+        if let Some(script) = script.as_str() {
+            commands.add(
+                name,
+                &format!("{} run {}", runner, name),
+                Some(script),
+                format!("{} (scripts.{})", path, name),
+            );
+        }
+    }
+}
+
+/// The shell lines of GitHub Actions `run` steps and GitLab CI `script` entries
+fn ci_commands(path: &str, content: &str, commands: &mut Commands) {
+    let Ok(document) = serde_yaml::from_str::<Value>(content) else {
+        return;
+    };
+
+    let mut add_script = |job: &str, script: &str| {
+        for (_, line) in logical_lines(script) {
+            if !line.is_empty() && !line.starts_with('#') {
+                commands.add("", &line, None, format!("{} (job {})", path, job));
+            }
+        }
+    };
+
+    if path.starts_with(".github/") {
+        let jobs = document.get("jobs").and_then(|v| v.as_object());
+        for (job, definition) in jobs.into_iter().flatten() {
+            let steps = definition.get("steps").and_then(|v| v.as_array());
+            for step in steps.into_iter().flatten() {
+                if let Some(run) = step.get("run").and_then(|v| v.as_str()) {
+                    add_script(job, run);
+                }
+            }
+        }
+    } else if let Some(jobs) = document.as_object() {
+        for (job, definition) in jobs {
+            if job.starts_with('.') || GITLAB_RESERVED.contains(&job.as_str()) {
+                continue;
+            }
+            for key in ["before_script", "script"] {
+                match definition.get(key) {
+                    Some(Value::Array(lines)) => lines
+                        .iter()
+                        .filter_map(|line| line.as_str())
+                        .for_each(|line| add_script(job, line)),
+                    Some(Value::String(script)) => add_script(job, script),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// How a file contributes commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SourceKind {
+    Guide,
+    Makefile,
+    Justfile,
+    PackageJson,
+    Ci,
+}
+
+impl SourceKind {
+    fn from_path(path: &str) -> Option<Self> {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let stem = name
+            .rsplit_once('.')
+            .map_or(name, |(stem, _)| stem)
+            .to_ascii_lowercase();
+        let is_markdown = name.to_ascii_lowercase().ends_with(".md") || !name.contains('.');
+        let top_level = !path.contains('/');
+
+        match name {
+            "Makefile" | "makefile" | "GNUmakefile" if top_level => Some(Self::Makefile),
+            "justfile" | "Justfile" | ".justfile" if top_level => Some(Self::Justfile),
+            "package.json" if top_level => Some(Self::PackageJson),
+            ".gitlab-ci.yml" if top_level => Some(Self::Ci),
+            _ if path.starts_with(".github/workflows/")
+                && (name.ends_with(".yml") || name.ends_with(".yaml")) =>
+            {
+                Some(Self::Ci)
+            }
+            _ if is_markdown
+                && GUIDE_STEMS.contains(&stem.as_str())
+                && (top_level || path.starts_with("docs/") && path.matches('/').count() == 1) =>
+            {
+                Some(Self::Guide)
+            }
+            _ => None,
+        }
+    }
+}
+
+pub struct BuildInstructions {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl BuildInstructions {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for BuildInstructions {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+
+        // Workflows live in a hidden directory, so the default ignore patterns are bypassed
+        let files = provider
+            .list_files(
+                repo_path,
+                git_ref.clone(),
+                &TreeOptions {
+                    ignore_patterns: Some(vec![]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| anyhow!("Error listing files: {}", e))?;
+        let paths: BTreeSet<&str> = files.iter().map(|file| file.path.as_str()).collect();
+
+        // Guides first, so their wording names the commands the other sources repeat
+        let mut sources: Vec<(&str, SourceKind)> = paths
+            .iter()
+            .filter_map(|path| SourceKind::from_path(path).map(|kind| (*path, kind)))
+            .collect();
+        sources.sort_by_key(|(path, kind)| (*kind != SourceKind::Guide, *path));
+
+        if sources.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!(
+                    "No README, Makefile, justfile, package.json or CI configuration found in {}",
+                    repo_path
+                ),
+            }]);
+        }
+
+        let runner = if paths.contains("pnpm-lock.yaml") {
+            "pnpm"
+        } else if paths.contains("yarn.lock") {
+            "yarn"
+        } else if paths.contains("bun.lockb") || paths.contains("bun.lock") {
+            "bun"
+        } else {
+            "npm"
+        };
+
+        let contents = join_all(
+            sources
+                .iter()
+                .map(|(path, _)| provider.get_file_content(repo_path, path, git_ref.clone())),
+        )
+        .await;
+
+        let mut commands = Commands::default();
+        let mut read = Vec::new();
+        for ((path, kind), content) in sources.iter().zip(contents) {
+            let Ok(content) = content else {
+                continue;
+            };
+            read.push(*path);
+            match kind {
+                SourceKind::Guide => guide_commands(path, &content, &mut commands)?,
+                SourceKind::Makefile => recipe_commands(path, &content, "make", &mut commands)?,
+                SourceKind::Justfile => recipe_commands(path, &content, "just", &mut commands)?,
+                SourceKind::PackageJson => package_commands(path, &content, runner, &mut commands),
+                SourceKind::Ci => ci_commands(path, &content, &mut commands),
+            }
+        }
+
+        let mut formatted_output = format!(
+            "# How to build, test and run {}\n\nSources: {}\n",
+            repo_path,
+            read.join(", ")
+        );
+        if commands.0.is_empty() {
+            formatted_output.push_str("\nNo commands found in these files\n");
+        }
+
+        for category in Category::ALL {
+            let listed: Vec<&Command> = commands
+                .0
+                .iter()
+                .filter(|command| command.category == category)
+                .collect();
+            if listed.is_empty() {
+                continue;
+            }
+
+            formatted_output.push_str(&format!("\n## {}\n\n", category.title()));
+            for command in listed {
+                formatted_output.push_str(&format!("- `{}`", command.text));
+                if let Some(runs) = &command.runs {
+                    formatted_output.push_str(&format!(" → `{}`", runs));
+                }
+                formatted_output.push_str(&format!(" ({})\n", command.sources.join("; ")));
+            }
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "build_instructions".into(),
+            description: Some(format!(
+                "Summarize how to set up, build, test, lint and run a repository from the exact commands of its README and contributing guides, Makefile or justfile targets, package.json scripts and CI steps, each cited with the file it was found in. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The commands gathered, with what they run, their category and where they were found
+    fn listed(commands: &Commands) -> Vec<(&str, Option<&str>, Category, String)> {
+        commands
+            .0
+            .iter()
+            .map(|command| {
+                (
+                    command.text.as_str(),
+                    command.runs.as_deref(),
+                    command.category,
+                    command.sources.join(", "),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn guides_give_the_shell_snippets_of_relevant_sections() {
+        let readme = "# Project\n```sh\necho skipped\n```\n## Building\n```console\n$ cargo build --release\n   Compiling project\n```\n## Testing\n```bash\ncargo test \\\n  --all\n# comment\n```\n```rust\nfn main() {}\n```\n";
+
+        let mut commands = Commands::default();
+        guide_commands("README.md", readme, &mut commands).unwrap();
+        assert_eq!(
+            listed(&commands),
+            [
+                (
+                    "cargo build --release",
+                    None,
+                    Category::Build,
+                    "README.md:7 (Building)".to_string()
+                ),
+                (
+                    "cargo test --all",
+                    None,
+                    Category::Test,
+                    "README.md:12 (Testing)".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn recipes_and_scripts_name_what_they_run() {
+        let makefile = "CC := gcc\n.PHONY: build test\nbuild: deps\n\t@cargo build\n\ntest:\n\t-cargo test\n%.o: %.c\n\t$(CC) -c $<\n";
+
+        let mut commands = Commands::default();
+        recipe_commands("Makefile", makefile, "make", &mut commands).unwrap();
+        package_commands(
+            "package.json",
+            r#"{"scripts": {"lint": "eslint .", "dev": "vite"}}"#,
+            "pnpm",
+            &mut commands,
+        );
+        assert_eq!(
+            listed(&commands),
+            [
+                (
+                    "make build",
+                    Some("cargo build"),
+                    Category::Build,
+                    "Makefile:3".to_string()
+                ),
+                (
+                    "make test",
+                    Some("cargo test"),
+                    Category::Test,
+                    "Makefile:6".to_string()
+                ),
+                (
+                    "pnpm run dev",
+                    Some("vite"),
+                    Category::Run,
+                    "package.json (scripts.dev)".to_string()
+                ),
+                (
+                    "pnpm run lint",
+                    Some("eslint ."),
+                    Category::Lint,
+                    "package.json (scripts.lint)".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn ci_jobs_give_their_script_lines() {
+        let workflow = "jobs:\n  check:\n    steps:\n      - uses: actions/checkout@v4\n      - run: |\n          cargo fmt --check\n          cargo test\n";
+        let gitlab = "variables:\n  X: y\n.template:\n  script: [make hidden]\ntest:\n  before_script: npm ci\n  script:\n    - cargo test\n";
+
+        let mut commands = Commands::default();
+        ci_commands(".github/workflows/ci.yml", workflow, &mut commands);
+        ci_commands(".gitlab-ci.yml", gitlab, &mut commands);
+        assert_eq!(
+            listed(&commands),
+            [
+                (
+                    "cargo fmt --check",
+                    None,
+                    Category::Lint,
+                    ".github/workflows/ci.yml (job check)".to_string()
+                ),
+                (
+                    "cargo test",
+                    None,
+                    Category::Test,
+                    ".github/workflows/ci.yml (job check), .gitlab-ci.yml (job test)".to_string()
+                ),
+                (
+                    "npm ci",
+                    None,
+                    Category::Other,
+                    ".gitlab-ci.yml (job test)".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn sources_are_recognized_by_path() {
+        assert_eq!(
+            SourceKind::from_path("Makefile"),
+            Some(SourceKind::Makefile)
+        );
+        assert_eq!(SourceKind::from_path("sub/Makefile"), None);
+        assert_eq!(
+            SourceKind::from_path("docs/BUILDING.md"),
+            Some(SourceKind::Guide)
+        );
+        assert_eq!(SourceKind::from_path("docs/api/README.md"), None);
+        assert_eq!(
+            SourceKind::from_path(".github/workflows/release.yaml"),
+            Some(SourceKind::Ci)
+        );
+        assert_eq!(
+            SourceKind::from_path("CONTRIBUTING"),
+            Some(SourceKind::Guide)
+        );
+    }
+}
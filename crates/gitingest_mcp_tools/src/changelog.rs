@@ -0,0 +1,274 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::{CommitInfo, CompareMode, GitProvider, RefComparison};
+use serde_json::{Value, json};
+
+use crate::common::{
+    bool_argument, iso_timestamp_days_ago, parse_git_ref, repo_argument, required_arguments,
+    string_argument, supported_providers,
+};
+
+/// Keep-a-changelog sections, in the order they are rendered
+const SECTIONS: [&str; 7] = [
+    "Added",
+    "Changed",
+    "Deprecated",
+    "Removed",
+    "Fixed",
+    "Security",
+    "Other",
+];
+
+/// A commit subject parsed according to the Conventional Commits specification
+struct ConventionalCommit<'a> {
+    kind: &'a str,
+    scope: Option<&'a str>,
+    breaking: bool,
+    description: &'a str,
+}
+
+fn parse_conventional(message: &str) -> Option<ConventionalCommit<'_>> {
+    let subject = message.lines().next()?;
+    let (header, description) = subject.split_once(": ")?;
+
+    let (header, breaking) = match header.strip_suffix('!') {
+        Some(header) => (header, true),
+        None => (header, false),
+    };
+
+    let (kind, scope) = match header.split_once('(') {
+        Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?)),
+        None => (header, None),
+    };
+
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let breaking = breaking
+        || message
+            .lines()
+            .any(|l| l.starts_with("BREAKING CHANGE:") || l.starts_with("BREAKING-CHANGE:"));
+
+    Some(ConventionalCommit {
+        kind,
+        scope,
+        breaking,
+        description: description.trim(),
+    })
+}
+
+/// Maps a conventional commit type to its changelog section, `None` for maintenance commits
+fn section_for(kind: &str) -> Option<&'static str> {
+    match kind.to_ascii_lowercase().as_str() {
+        "feat" | "feature" => Some("Added"),
+        "fix" | "bugfix" => Some("Fixed"),
+        "perf" | "refactor" => Some("Changed"),
+        "deprecate" | "deprecation" => Some("Deprecated"),
+        "remove" | "revert" => Some("Removed"),
+        "security" | "sec" => Some("Security"),
+        _ => None,
+    }
+}
+
+fn is_merge_commit(commit: &CommitInfo) -> bool {
+    let subject = commit.subject();
+    subject.starts_with("Merge pull request") || subject.starts_with("Merge branch")
+}
+
+/// Renders the commits of a comparison as a keep-a-changelog entry
+pub(crate) fn render_changelog(
+    comparison: &RefComparison,
+    version: Option<&str>,
+    include_all: bool,
+) -> String {
+    let mut sections: Vec<Vec<String>> = vec![Vec::new(); SECTIONS.len()];
+
+    // Newest changes first, as changelogs are usually read
+    for commit in comparison.commits.iter().rev() {
+        if is_merge_commit(commit) {
+            continue;
+        }
+
+        let short_sha = commit.sha.get(..7).unwrap_or(&commit.sha);
+
+        let (section, entry) = match parse_conventional(&commit.message) {
+            Some(parsed) => {
+                let section = match section_for(parsed.kind) {
+                    Some(section) => section,
+                    // Breaking changes always make it into the changelog
+                    None if parsed.breaking => "Changed",
+                    None if include_all => "Other",
+                    None => continue,
+                };
+
+                let mut entry = String::new();
+                if parsed.breaking {
+                    entry.push_str("**BREAKING** ");
+                }
+                if let Some(scope) = parsed.scope {
+                    entry.push_str(&format!("**{}:** ", scope));
+                }
+                entry.push_str(&format!("{} ({})", parsed.description, short_sha));
+
+                (section, entry)
+            }
+            None if include_all => ("Other", format!("{} ({})", commit.subject(), short_sha)),
+            None => continue,
+        };
+
+        let index = SECTIONS.iter().position(|s| *s == section).unwrap_or(0);
+        sections[index].push(entry);
+    }
+
+    // Dated after the newest commit rather than today, so a pinned range renders identically
+    let today = iso_timestamp_days_ago(0);
+    let date = comparison
+        .commits
+        .last()
+        .and_then(|commit| commit.date.get(..10))
+        .unwrap_or(&today[..10]);
+
+    let heading = match version {
+        Some(version) => format!("## [{}] - {}", version.trim_start_matches('v'), date),
+        None => "## [Unreleased]".to_string(),
+    };
+
+    let mut changelog = format!(
+        "{}\n\nChanges from {} to {} ({} commits)\n",
+        heading,
+        comparison.base,
+        comparison.head,
+        comparison.commits.len()
+    );
+
+    if sections.iter().all(|s| s.is_empty()) {
+        changelog.push_str("\nNo notable changes found\n");
+        return changelog;
+    }
+
+    for (name, entries) in SECTIONS.iter().zip(sections) {
+        if entries.is_empty() {
+            continue;
+        }
+
+        changelog.push_str(&format!("\n### {}\n\n", name));
+        for entry in entries {
+            changelog.push_str(&format!("- {}\n", entry));
+        }
+    }
+
+    changelog
+}
+
+pub struct SynthesizeChangelog {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl SynthesizeChangelog {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for SynthesizeChangelog {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let from = parse_git_ref(string_argument(&args, "from")?);
+        let to = args.get("to").and_then(|v| v.as_str()).map(parse_git_ref);
+        let version = args.get("version").and_then(|v| v.as_str());
+        let include_all = bool_argument(&args, "include_all").unwrap_or(false);
+
+        let comparison = provider
+            .compare_refs(repo_path, Some(from), to, CompareMode::MergeBase)
+            .await
+            .map_err(|e| anyhow!("Error comparing references: {}", e))?;
+
+        Ok(vec![ToolContent::Text {
+            text: render_changelog(&comparison, version, include_all),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "synthesize_changelog".into(),
+            description: Some(format!(
+                "Draft a changelog entry in keep-a-changelog format from the commits between two references, grouped by conventional-commit type. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "from": {
+                        "type": "string",
+                        "description": "Starting git reference, usually the previous release: branch name, 'tag:name', or 'commit:sha'"
+                    },
+                    "to": {
+                        "type": "string",
+                        "description": "Optional ending git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "version": {
+                        "type": "string",
+                        "description": "Optional version to title the entry with. Default: Unreleased"
+                    },
+                    "include_all": {
+                        "type": "boolean",
+                        "description": "Optional flag to also list maintenance and non-conventional commits under 'Other'. Default: false"
+                    }
+                },
+                "required": ["repo", "from"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conventional_subjects_are_parsed() {
+        let parsed = parse_conventional("feat(parser)!: accept trailing commas\n\nBody").unwrap();
+        assert_eq!(parsed.kind, "feat");
+        assert_eq!(parsed.scope, Some("parser"));
+        assert!(parsed.breaking);
+        assert_eq!(parsed.description, "accept trailing commas");
+
+        let parsed =
+            parse_conventional("fix: drop the cache\n\nBREAKING CHANGE: rebuild it").unwrap();
+        assert_eq!(parsed.scope, None);
+        assert!(parsed.breaking);
+
+        let parsed = parse_conventional("docs: typo").unwrap();
+        assert!(!parsed.breaking);
+    }
+
+    #[test]
+    fn other_subjects_are_not_conventional() {
+        assert!(parse_conventional("Update README.md").is_none());
+        assert!(parse_conventional("Release 1.0: the big one").is_none());
+        assert!(parse_conventional("feat(parser: unclosed scope").is_none());
+        assert!(parse_conventional("").is_none());
+    }
+
+    #[test]
+    fn types_map_to_sections() {
+        assert_eq!(section_for("Feat"), Some("Added"));
+        assert_eq!(section_for("perf"), Some("Changed"));
+        assert_eq!(section_for("revert"), Some("Removed"));
+        assert_eq!(section_for("sec"), Some("Security"));
+        assert_eq!(section_for("chore"), None);
+    }
+}
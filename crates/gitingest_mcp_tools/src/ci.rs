@@ -0,0 +1,540 @@
+use std::{
+    fs::{self, File},
+    io::{self, Cursor, Read},
+    path::Path,
+    sync::Arc,
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::GitProvider;
+use regex::Regex;
+use serde_json::{Value, json};
+use zip::ZipArchive;
+
+use crate::{
+    common::{
+        git_ref_argument, repo_argument, required_arguments, string_argument, supported_providers,
+        usize_argument,
+    },
+    write_roots::WriteRoots,
+};
+
+/// Default number of log lines returned when neither `tail_lines` nor `grep` is given
+const DEFAULT_TAIL_LINES: usize = 200;
+
+/// Maximum amount of artifact text returned inline
+const MAX_INLINE_ARTIFACT_BYTES: usize = 64 * 1024;
+
+/// Largest artifact archive downloaded, as the whole archive is held in memory
+const MAX_ARTIFACT_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Largest amount of data extracted from an artifact, whatever sizes its entries declare
+const MAX_EXTRACTED_BYTES: u64 = 1024 * 1024 * 1024;
+
+pub struct CheckAnnotations {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl CheckAnnotations {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for CheckAnnotations {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+
+        let mut annotations = provider
+            .get_check_annotations(repo_path, git_ref)
+            .await
+            .map_err(|e| anyhow!("Error getting check annotations: {}", e))?;
+
+        if annotations.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("No check annotations found for {}", repo_path),
+            }]);
+        }
+
+        // Group findings by file so related lines read together
+        annotations.sort_by(|a, b| {
+            a.path
+                .cmp(&b.path)
+                .then(a.start_line.cmp(&b.start_line))
+                .then(a.check_name.cmp(&b.check_name))
+        });
+
+        let mut formatted_output = format!(
+            "{} check annotations for {}\n\n",
+            annotations.len(),
+            repo_path
+        );
+
+        for annotation in annotations {
+            let lines = if annotation.start_line == annotation.end_line {
+                format!("L{}", annotation.start_line)
+            } else {
+                format!("L{}-L{}", annotation.start_line, annotation.end_line)
+            };
+
+            formatted_output.push_str(&format!(
+                "- {}:{} [{}] ({})",
+                annotation.path, lines, annotation.level, annotation.check_name
+            ));
+            if let Some(title) = annotation.title.filter(|t| !t.is_empty()) {
+                formatted_output.push_str(&format!(" {}", title));
+            }
+            formatted_output.push_str(&format!(
+                "\n  {}\n",
+                annotation.message.trim().replace('\n', "\n  ")
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "check_annotations".into(),
+            description: Some(format!(
+                "List the line-level annotations (lint errors, test failures) CI checks reported for a commit. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+pub struct CiJobLog {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl CiJobLog {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for CiJobLog {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let job_id = string_argument(&args, "job_id")?;
+
+        let grep = args
+            .get("grep")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| anyhow!("Invalid grep pattern: {}", e))?;
+
+        let tail_lines = usize_argument(&args, "tail_lines");
+
+        let log = provider
+            .get_job_log(repo_path, job_id)
+            .await
+            .map_err(|e| anyhow!("Error getting job log: {}", e))?;
+
+        // Filter and trim server side so only the relevant lines reach the model
+        let lines: Vec<(usize, &str)> = log
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| grep.as_ref().is_none_or(|re| re.is_match(line)))
+            .collect();
+        let total = lines.len();
+
+        let keep = match (tail_lines, &grep) {
+            (Some(tail), _) => tail,
+            (None, Some(_)) => total,
+            (None, None) => DEFAULT_TAIL_LINES,
+        };
+        let shown = &lines[total.saturating_sub(keep)..];
+
+        let mut formatted_output = match &grep {
+            Some(re) => format!(
+                "Job {}: {} lines matching /{}/, showing {}\n\n",
+                job_id,
+                total,
+                re.as_str(),
+                shown.len()
+            ),
+            None => format!(
+                "Job {}: {} lines, showing the last {}\n\n",
+                job_id,
+                total,
+                shown.len()
+            ),
+        };
+
+        formatted_output.push_str("```\n");
+        for (number, line) in shown {
+            formatted_output.push_str(&format!("{:>6}: {}\n", number + 1, line));
+        }
+        formatted_output.push_str("```");
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "ci_job_log".into(),
+            description: Some(format!(
+                "Retrieve the log of a CI job (GitHub Actions job or GitLab pipeline job), filtered and trimmed server-side. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "job_id": {
+                        "type": "string",
+                        "description": "Identifier of the CI job"
+                    },
+                    "tail_lines": {
+                        "type": "integer",
+                        "description": format!("Optional number of trailing lines to return (after grep filtering). Default: {} without grep, all matches with grep", DEFAULT_TAIL_LINES)
+                    },
+                    "grep": {
+                        "type": "string",
+                        "description": "Optional regular expression; only matching lines are returned"
+                    }
+                },
+                "required": ["repo", "job_id"]
+            }),
+        }
+    }
+}
+
+pub struct CiArtifacts {
+    providers: Vec<Arc<dyn GitProvider>>,
+    write_roots: WriteRoots,
+}
+
+impl CiArtifacts {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>, write_roots: WriteRoots) -> Self {
+        Self {
+            providers,
+            write_roots,
+        }
+    }
+
+    /// Extracts the files of an artifact below `target`, returning how many were written and
+    /// their size. Entries whose path would leave `target` are refused, and reads stop at
+    /// `MAX_EXTRACTED_BYTES` since the sizes an archive declares can lie
+    fn extract_entries(archive: &[u8], target: &Path) -> Result<(usize, u64)> {
+        let mut zip = ZipArchive::new(Cursor::new(archive))?;
+        let mut files = 0;
+        let mut extracted = 0;
+
+        for i in 0..zip.len() {
+            let entry = zip.by_index(i)?;
+            let name = entry.name().to_string();
+            let Some(relative) = entry.enclosed_name() else {
+                return Err(anyhow!(
+                    "Artifact entry '{}' escapes the target directory",
+                    name
+                ));
+            };
+            let destination = target.join(relative);
+
+            if entry.is_dir() {
+                fs::create_dir_all(&destination)?;
+                continue;
+            }
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            let remaining = MAX_EXTRACTED_BYTES - extracted;
+            let mut file = File::create(&destination)?;
+            let written = io::copy(&mut entry.take(remaining + 1), &mut file)?;
+            if written > remaining {
+                return Err(anyhow!(
+                    "The artifact holds more than {} bytes once extracted, stopped at '{}'",
+                    MAX_EXTRACTED_BYTES,
+                    name
+                ));
+            }
+
+            extracted += written;
+            files += 1;
+        }
+
+        Ok((files, extracted))
+    }
+
+    fn list_text_entries(archive: &[u8]) -> Result<String> {
+        let mut zip = ZipArchive::new(Cursor::new(archive))?;
+        let mut formatted_output = String::new();
+        let mut budget = MAX_INLINE_ARTIFACT_BYTES;
+
+        for i in 0..zip.len() {
+            let mut entry = zip.by_index(i)?;
+            if entry.is_dir() {
+                continue;
+            }
+
+            let name = entry.name().to_string();
+            let size = entry.size();
+
+            // Only inline small text files, listing the rest. Reads stop past the budget, as
+            // the declared size can lie
+            let mut content = Vec::new();
+            let inlined = size <= budget as u64
+                && entry
+                    .by_ref()
+                    .take(budget as u64 + 1)
+                    .read_to_end(&mut content)
+                    .is_ok()
+                && content.len() <= budget;
+            if let Some(content) = inlined.then(|| String::from_utf8(content).ok()).flatten() {
+                budget -= content.len();
+                let extension = name.rsplit('.').next().unwrap_or("");
+                formatted_output.push_str(&format!(
+                    "### {}\n\n```{}\n{}\n```\n\n",
+                    name,
+                    extension,
+                    content.trim_end()
+                ));
+            } else {
+                formatted_output
+                    .push_str(&format!("### {} ({} bytes, not inlined)\n\n", name, size));
+            }
+        }
+
+        Ok(formatted_output)
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for CiArtifacts {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let run_id = string_argument(&args, "run_id")?;
+
+        let artifacts = provider
+            .list_artifacts(repo_path, run_id)
+            .await
+            .map_err(|e| anyhow!("Error listing artifacts: {}", e))?;
+
+        let Some(name) = args
+            .get("artifact")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+        else {
+            if artifacts.is_empty() {
+                return Ok(vec![ToolContent::Text {
+                    text: format!("No artifacts found for run {}", run_id),
+                }]);
+            }
+
+            let mut formatted_output = format!("Artifacts for run {}\n\n", run_id);
+            for artifact in &artifacts {
+                formatted_output.push_str(&format!(
+                    "- {} (id: {}, {}){}\n",
+                    artifact.name,
+                    artifact.id,
+                    artifact
+                        .size
+                        .map(|size| format!("{} bytes", size))
+                        .unwrap_or_else(|| "unknown size".into()),
+                    if artifact.expired { " [expired]" } else { "" }
+                ));
+            }
+
+            return Ok(vec![ToolContent::Text {
+                text: formatted_output,
+            }]);
+        };
+
+        let artifact = artifacts
+            .iter()
+            .find(|a| a.name == name || a.id == name)
+            .ok_or_else(|| anyhow!("Artifact '{}' not found in run {}", name, run_id))?;
+
+        // Refused before anything is downloaded, listing entries inline needs no write access
+        let target = args
+            .get("target_path")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|target| self.write_roots.resolve(Path::new(target)))
+            .transpose()?;
+
+        if let Some(size) = artifact.size
+            && size > MAX_ARTIFACT_BYTES
+        {
+            return Err(anyhow!(
+                "Artifact '{}' is {} bytes, above the {} bytes downloaded",
+                artifact.name,
+                size,
+                MAX_ARTIFACT_BYTES
+            ));
+        }
+
+        let archive = provider
+            .download_artifact(repo_path, &artifact.id)
+            .await
+            .map_err(|e| anyhow!("Error downloading artifact: {}", e))?;
+
+        // Hosts do not always report the size up front
+        if archive.len() as u64 > MAX_ARTIFACT_BYTES {
+            return Err(anyhow!(
+                "Artifact '{}' is {} bytes, above the {} bytes downloaded",
+                artifact.name,
+                archive.len(),
+                MAX_ARTIFACT_BYTES
+            ));
+        }
+
+        match target {
+            Some(target) => {
+                fs::create_dir_all(&target)?;
+                let (files, extracted) = Self::extract_entries(&archive, &target)?;
+
+                Ok(vec![ToolContent::Text {
+                    text: format!(
+                        "Extracted {} files ({} bytes) of artifact '{}' ({} bytes) to {}",
+                        files,
+                        extracted,
+                        artifact.name,
+                        archive.len(),
+                        target.display()
+                    ),
+                }])
+            }
+            None => Ok(vec![ToolContent::Text {
+                text: format!(
+                    "Artifact '{}' ({} bytes)\n\n{}",
+                    artifact.name,
+                    archive.len(),
+                    Self::list_text_entries(&archive)?
+                ),
+            }]),
+        }
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "ci_artifacts".into(),
+            description: Some(format!(
+                "List the artifacts of a CI run (GitHub workflow run or GitLab pipeline), or fetch a named artifact: extracted to a local directory, or with its small text files returned inline. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "run_id": {
+                        "type": "string",
+                        "description": "Identifier of the workflow run (GitHub) or pipeline (GitLab)"
+                    },
+                    "artifact": {
+                        "type": "string",
+                        "description": "Optional name or id of the artifact to fetch. When omitted, artifacts are listed"
+                    },
+                    "target_path": {
+                        "type": "string",
+                        "description": "Optional local directory to extract the artifact into, below the directories of GITINGEST_WRITE_ROOTS and only when the server runs with --enable-write. When omitted, small text files are returned inline"
+                    }
+                },
+                "required": ["repo", "run_id"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, io::Write};
+
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    use super::*;
+
+    fn zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn small_text_entries_are_inlined() {
+        let archive = zip(&[
+            ("report.txt", b"all green"),
+            ("coverage.bin", &[0xff, 0xfe, 0x00]),
+            ("large.log", &[b'x'; MAX_INLINE_ARTIFACT_BYTES + 1]),
+        ]);
+
+        let output = CiArtifacts::list_text_entries(&archive).unwrap();
+        assert!(output.contains("### report.txt\n\n```txt\nall green\n```"));
+        assert!(output.contains("### coverage.bin (3 bytes, not inlined)"));
+        assert!(output.contains(&format!(
+            "### large.log ({} bytes, not inlined)",
+            MAX_INLINE_ARTIFACT_BYTES + 1
+        )));
+    }
+
+    #[test]
+    fn entries_are_extracted_below_the_target_only() {
+        let target = env::temp_dir().join(format!("ci-artifacts-{}", std::process::id()));
+        fs::create_dir_all(&target).unwrap();
+
+        let archive = zip(&[("logs/test.log", b"ok")]);
+        assert_eq!(
+            CiArtifacts::extract_entries(&archive, &target).unwrap(),
+            (1, 2)
+        );
+        assert_eq!(fs::read(target.join("logs/test.log")).unwrap(), b"ok");
+
+        let archive = zip(&[("../escaped.log", b"no")]);
+        assert!(CiArtifacts::extract_entries(&archive, &target).is_err());
+        assert!(!target.join("../escaped.log").exists());
+
+        fs::remove_dir_all(&target).unwrap();
+    }
+}
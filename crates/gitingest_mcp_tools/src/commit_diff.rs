@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::{ChangedFile, FileStatus, GitProvider};
+use serde_json::{Value, json};
+
+use crate::{
+    common::{
+        list_argument, repo_argument, required_arguments, string_argument, supported_providers,
+    },
+    diff::touches,
+};
+
+/// The git headers of one file of a patch, as `git show` writes them minus the file modes
+fn patch_header(file: &ChangedFile) -> String {
+    let old_path = file.previous_path.as_deref().unwrap_or(&file.path);
+    let mut header = format!("diff --git a/{} b/{}\n", old_path, file.path);
+
+    match file.status {
+        FileStatus::Added => {
+            header.push_str(&format!("--- /dev/null\n+++ b/{}\n", file.path));
+        }
+        FileStatus::Removed => {
+            header.push_str(&format!("--- a/{}\n+++ /dev/null\n", file.path));
+        }
+        FileStatus::Renamed => {
+            if let Some(similarity) = file.similarity {
+                header.push_str(&format!("similarity index {}%\n", similarity));
+            }
+            header.push_str(&format!(
+                "rename from {}\nrename to {}\n--- a/{}\n+++ b/{}\n",
+                old_path, file.path, old_path, file.path
+            ));
+        }
+        FileStatus::Modified => {
+            header.push_str(&format!("--- a/{}\n+++ b/{}\n", file.path, file.path));
+        }
+    }
+
+    header
+}
+
+pub struct CommitDiff {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl CommitDiff {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for CommitDiff {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let sha = string_argument(&args, "sha")?.trim();
+        let sha = sha.strip_prefix("commit:").unwrap_or(sha);
+        let paths: Vec<String> = list_argument(&args, "paths")
+            .into_iter()
+            .map(|path| path.trim_matches('/').to_string())
+            .filter(|path| !path.is_empty())
+            .collect();
+
+        let details = provider
+            .get_commit(repo_path, sha)
+            .await
+            .map_err(|e| anyhow!("Error reading commit {}: {}", sha, e))?;
+        let commit = &details.commit;
+
+        let files: Vec<&ChangedFile> = details
+            .files
+            .iter()
+            .filter(|file| paths.is_empty() || paths.iter().any(|path| touches(file, path)))
+            .collect();
+
+        let mut output = format!("commit {}\n", commit.sha);
+        match &commit.author_email {
+            Some(email) => output.push_str(&format!("Author: {} <{}>\n", commit.author, email)),
+            None => output.push_str(&format!("Author: {}\n", commit.author)),
+        }
+        output.push_str(&format!("Date:   {}\n", commit.date));
+        if let Some(verification) = &commit.verification {
+            output.push_str(&format!("Signature: {}\n", verification.describe()));
+        }
+        output.push('\n');
+        // Indented as `git show` does, blank lines left empty
+        for line in commit.message.trim_end().lines() {
+            match line.trim_end() {
+                "" => output.push('\n'),
+                line => output.push_str(&format!("    {}\n", line)),
+            }
+        }
+        output.push('\n');
+
+        let (additions, deletions) = files.iter().fold((0, 0), |(a, d), file| {
+            (a + file.additions, d + file.deletions)
+        });
+        if paths.is_empty() {
+            output.push_str(&format!(
+                "{} files changed, +{} -{}\n",
+                files.len(),
+                additions,
+                deletions
+            ));
+        } else {
+            output.push_str(&format!(
+                "{} of {} changed files under {}, +{} -{}\n",
+                files.len(),
+                details.files.len(),
+                paths.join(", "),
+                additions,
+                deletions
+            ));
+        }
+        if files.is_empty() {
+            return Ok(vec![ToolContent::Text { text: output }]);
+        }
+
+        // Binary and oversized changes come without a patch, they are named outside the block
+        let (with_patch, without_patch): (Vec<&ChangedFile>, Vec<&ChangedFile>) =
+            files.into_iter().partition(|file| file.patch.is_some());
+        if !without_patch.is_empty() {
+            let names: Vec<&str> = without_patch
+                .iter()
+                .map(|file| file.path.as_str())
+                .collect();
+            output.push_str(&format!(
+                "No textual diff available for: {}\n",
+                names.join(", ")
+            ));
+        }
+
+        if !with_patch.is_empty() {
+            output.push_str("\n```diff\n");
+            for file in with_patch {
+                output.push_str(&patch_header(file));
+                if let Some(patch) = &file.patch {
+                    output.push_str(patch.trim_end_matches('\n'));
+                    output.push('\n');
+                }
+            }
+            output.push_str("```\n");
+        }
+
+        Ok(vec![ToolContent::Text { text: output }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "commit_diff".into(),
+            description: Some(format!(
+                "Get the patch of a single commit as one fenced diff block with git headers, preceded by its author, date and message, optionally limited to some files or directories. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "sha": {
+                        "type": "string",
+                        "description": "SHA of the commit, full or abbreviated"
+                    },
+                    "paths": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of files or directories the patch is limited to (e.g., 'src/parser.rs,docs'). Default: every changed file"
+                    }
+                },
+                "required": ["repo", "sha"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,370 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Result, anyhow};
+use git_provider::{
+    GitProvider, GitRef, TreeOptions,
+    pattern_presets::{PATTERN_PRESETS, find_preset},
+    warnings::{self, WarningKind},
+};
+use serde_json::Value;
+
+use crate::i18n::Message;
+
+/// The arguments of a tool call, which every tool requires
+pub(crate) fn required_arguments(arguments: Option<Value>) -> Result<Value> {
+    arguments.ok_or_else(|| anyhow!(Message::MissingArguments.text(&[])))
+}
+
+/// Returns the names of all registered providers
+pub(crate) fn supported_providers(providers: &[Arc<dyn GitProvider>]) -> Vec<String> {
+    providers.iter().map(|p| p.name().to_string()).collect()
+}
+
+/// Looks up a provider by name, listing the supported ones on failure
+pub(crate) fn find_provider<'a>(
+    providers: &'a [Arc<dyn GitProvider>],
+    provider_name: &str,
+) -> Result<&'a dyn GitProvider> {
+    providers
+        .iter()
+        .find(|p| p.name() == provider_name)
+        .map(|p| p.as_ref())
+        .ok_or_else(|| {
+            anyhow!(
+                Message::UnsupportedProvider
+                    .text(&[&provider_name, &supported_providers(providers).join(", ")])
+            )
+        })
+}
+
+/// Splits a "gitprovider:username/reponame" identifier into provider and path
+pub(crate) fn parse_repo_identifier(repo_identifier: &str) -> Result<(&str, &str)> {
+    // Only the first colon separates the provider, repository URLs contain more
+    match repo_identifier.split_once(':') {
+        Some((git_provider, repo_path)) if !git_provider.is_empty() && repo_path.contains('/') => {
+            Ok((git_provider, repo_path))
+        }
+        _ => Err(anyhow!(Message::InvalidRepositoryFormat.text(&[]))),
+    }
+}
+
+/// Reads the `repo` argument and resolves it to a provider and repository path
+pub(crate) fn repo_argument<'a, 'b>(
+    providers: &'a [Arc<dyn GitProvider>],
+    args: &'b Value,
+) -> Result<(&'a dyn GitProvider, &'b str)> {
+    let repo_identifier = args
+        .get("repo")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!(Message::MissingRepository.text(&[])))?;
+
+    let (git_provider, repo_path) = parse_repo_identifier(repo_identifier)?;
+
+    Ok((find_provider(providers, git_provider)?, repo_path))
+}
+
+/// Parses a git reference: branch name, 'tag:name', or 'commit:sha'
+pub(crate) fn parse_git_ref(ref_str: &str) -> GitRef {
+    if ref_str.is_empty() {
+        return GitRef::Default;
+    }
+
+    let parts: Vec<&str> = ref_str.split(':').collect();
+    if parts.len() != 2 {
+        return GitRef::Branch(ref_str.to_string());
+    }
+
+    match parts[0] {
+        "tag" => GitRef::Tag(parts[1].to_string()),
+        "commit" => GitRef::Commit(parts[1].to_string()),
+        "branch" => GitRef::Branch(parts[1].to_string()),
+        _ => GitRef::Branch(ref_str.to_string()),
+    }
+}
+
+/// Reads a required, non-empty string argument
+pub(crate) fn string_argument<'a>(args: &'a Value, key: &str) -> Result<&'a str> {
+    args.get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| anyhow!(Message::MissingArgument.text(&[&key.replace('_', " ")])))
+}
+
+/// Reads an optional git reference argument
+pub(crate) fn git_ref_argument(args: &Value, key: &str) -> Option<GitRef> {
+    args.get(key).and_then(|v| v.as_str()).map(parse_git_ref)
+}
+
+/// Reads an optional numeric argument, accepting both numbers and strings
+pub(crate) fn usize_argument(args: &Value, key: &str) -> Option<usize> {
+    args.get(key).and_then(|v| {
+        if let Some(str_val) = v.as_str() {
+            str_val.parse::<usize>().ok()
+        } else {
+            v.as_u64().map(|n| n as usize)
+        }
+    })
+}
+
+/// Files read by default by the tools walking a repository, for providers without a token
+pub(crate) const ANONYMOUS_MAX_FILES: usize = 25;
+
+/// Files fetched at once by the tools reading many of them, so hosts never see hundreds of
+/// requests in parallel
+pub(crate) const FETCH_CONCURRENCY: usize = 20;
+
+/// Reads the `max_files` argument, defaulting to `default`, or to the few files an
+/// unauthenticated rate limit affords for providers called without a token
+pub(crate) fn max_files_argument(
+    provider: &dyn GitProvider,
+    args: &Value,
+    default: usize,
+) -> usize {
+    usize_argument(args, "max_files").unwrap_or(if provider.is_anonymous() {
+        default.min(ANONYMOUS_MAX_FILES)
+    } else {
+        default
+    })
+}
+
+/// Explains output cut short by the lowered default of [`max_files_argument`], `None` when
+/// all `total` files were read or `max_files` was given
+pub(crate) fn anonymous_limit_note(
+    provider: &dyn GitProvider,
+    args: &Value,
+    read: usize,
+    total: usize,
+) -> Option<String> {
+    if !provider.is_anonymous() || usize_argument(args, "max_files").is_some() || read >= total {
+        return None;
+    }
+
+    warnings::warn(
+        WarningKind::RateLimited,
+        Message::AnonymousLimitWarning.text(&[&read, &total, &provider.name()]),
+    );
+    Some(Message::AnonymousLimit.text(&[&read, &total, &provider.name()]))
+}
+
+/// Records output cut short of `total` items after `kept`, naming the argument that raises it
+pub(crate) fn warn_truncated(kept: usize, total: usize, what: Message, limit: &str) {
+    warnings::warn(
+        WarningKind::Truncated,
+        Message::ItemsLeftOut.text(&[&(total - kept), &total, &what.text(&[]), &limit]),
+    );
+}
+
+/// Records a file missing from the output because it could not be read
+pub(crate) fn warn_skipped(path: &str, error: impl std::fmt::Display) {
+    warnings::warn(WarningKind::SkippedFile, format!("{}: {}", path, error));
+}
+
+/// Reads an optional boolean argument, accepting both booleans and strings
+pub(crate) fn bool_argument(args: &Value, key: &str) -> Option<bool> {
+    args.get(key).and_then(|v| {
+        if let Some(str_val) = v.as_str() {
+            str_val.parse::<bool>().ok()
+        } else {
+            v.as_bool()
+        }
+    })
+}
+
+/// File extensions of the programming and markup languages told apart by file name, under
+/// the names hosts give them
+pub(crate) const LANGUAGE_EXTENSIONS: &[(&str, &[&str])] = &[
+    ("Rust", &["rs"]),
+    ("Go", &["go"]),
+    ("Python", &["py"]),
+    ("JavaScript", &["js", "mjs", "cjs"]),
+    ("JavaScript (JSX)", &["jsx"]),
+    ("TypeScript", &["ts", "mts", "cts"]),
+    ("TypeScript (TSX)", &["tsx"]),
+    ("Java", &["java"]),
+    ("Kotlin", &["kt", "kts"]),
+    ("C", &["c", "h"]),
+    ("C++", &["cc", "cpp", "cxx", "hpp"]),
+    ("C#", &["cs"]),
+    ("Ruby", &["rb"]),
+    ("PHP", &["php"]),
+    ("Swift", &["swift"]),
+    ("Scala", &["scala"]),
+    ("Elixir", &["ex", "exs"]),
+    ("Dart", &["dart"]),
+    ("Zig", &["zig"]),
+    ("Lua", &["lua"]),
+    ("Shell", &["sh", "bash", "zsh"]),
+    ("SQL", &["sql"]),
+    ("HTML", &["html", "htm"]),
+    ("CSS", &["css"]),
+    ("SCSS", &["scss"]),
+    ("Markdown", &["md", "markdown"]),
+    ("JSON", &["json"]),
+    ("YAML", &["yaml", "yml"]),
+    ("TOML", &["toml"]),
+    ("XML", &["xml"]),
+    ("Nix", &["nix"]),
+    ("Protocol Buffers", &["proto"]),
+];
+
+/// Names the programming or markup language of a file from its name
+pub(crate) fn language_for_path(path: &str) -> Option<&'static str> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name {
+        "Dockerfile" => return Some("Dockerfile"),
+        "Makefile" | "justfile" => return Some("Makefile"),
+        _ => {}
+    }
+
+    let extension = name.rsplit_once('.')?.1.to_ascii_lowercase();
+    LANGUAGE_EXTENSIONS
+        .iter()
+        .find(|(_, extensions)| extensions.contains(&extension.as_str()))
+        .map(|(language, _)| *language)
+}
+
+/// Reads an optional comma-separated list argument
+pub(crate) fn list_argument(args: &Value, key: &str) -> Vec<String> {
+    args.get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Reads the pattern arguments: the globs of `extensions` and of the comma-separated
+/// ecosystem presets named in `preset`, followed by the include and exclude patterns
+pub(crate) fn tree_options_argument(args: &Value) -> Result<TreeOptions> {
+    let mut options = TreeOptions {
+        include_patterns: list_argument(args, "extensions")
+            .iter()
+            .map(|extension| extension_pattern(extension))
+            .collect(),
+        case_insensitive: bool_argument(args, "case_insensitive").unwrap_or(false),
+        ..Default::default()
+    };
+
+    for name in list_argument(args, "preset") {
+        let preset = find_preset(&name)
+            .ok_or_else(|| anyhow!(Message::UnknownPreset.text(&[&name, &preset_names()])))?;
+        options.include_patterns.extend(preset.include_patterns());
+        options.exclude_patterns.extend(preset.exclude_patterns());
+    }
+
+    // Given last, so the caller's `!pattern` entries win over extensions and presets
+    options
+        .include_patterns
+        .extend(list_argument(args, "include_patterns"));
+    options
+        .exclude_patterns
+        .extend(list_argument(args, "exclude_patterns"));
+
+    Ok(options)
+}
+
+/// The glob matching files with an extension, given as `rs`, `.rs` or `*.rs`
+fn extension_pattern(extension: &str) -> String {
+    format!(
+        "*.{}",
+        extension.trim_start_matches('*').trim_start_matches('.')
+    )
+}
+
+/// The names of the pattern presets, for argument descriptions and errors
+pub(crate) fn preset_names() -> String {
+    PATTERN_PRESETS
+        .iter()
+        .map(|preset| preset.name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Formats the UTC instant `days` days ago as an ISO 8601 timestamp
+pub(crate) fn iso_timestamp_days_ago(days: u64) -> String {
+    let instant = SystemTime::now()
+        .checked_sub(Duration::from_secs(days * 24 * 60 * 60))
+        .unwrap_or(UNIX_EPOCH);
+    let seconds = instant
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    // Convert days since the epoch to a civil date (proleptic Gregorian calendar)
+    let days_since_epoch = (seconds / 86_400) as i64 + 719_468;
+    let era = days_since_epoch.div_euclid(146_097);
+    let day_of_era = days_since_epoch.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    let time_of_day = seconds % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time_of_day / 3_600,
+        time_of_day % 3_600 / 60,
+        time_of_day % 60
+    )
+}
+
+/// Repositories larger than this need `force` before being walked or ingested
+const DEFAULT_MAX_REPO_SIZE_MB: u64 = 1024;
+
+/// Checks the repository's size from its metadata before a full walk or ingest, failing above
+/// GITINGEST_MAX_REPO_SIZE_MB unless `force` is set, in which case the warning is returned to
+/// be shown with the output. Unknown sizes pass unchecked.
+pub(crate) async fn check_repository_size(
+    provider: &dyn GitProvider,
+    repo_path: &str,
+    force: bool,
+) -> Result<Option<String>> {
+    let max_size_mb = std::env::var("GITINGEST_MAX_REPO_SIZE_MB")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_MAX_REPO_SIZE_MB);
+    if max_size_mb == 0 {
+        return Ok(None);
+    }
+
+    let Some(size_kb) = provider
+        .get_repository_info(repo_path)
+        .await
+        .ok()
+        .and_then(|info| info.size_kb)
+    else {
+        return Ok(None);
+    };
+
+    let size_mb = size_kb / 1024;
+    if size_mb <= max_size_mb {
+        return Ok(None);
+    }
+
+    if !force {
+        return Err(anyhow!(Message::RepositoryTooLarge.text(&[
+            &repo_path,
+            &size_mb,
+            &max_size_mb
+        ])));
+    }
+
+    Ok(Some(format!(
+        "{}\n\n",
+        Message::RepositoryTooLargeWarning.text(&[&repo_path, &size_mb, &max_size_mb])
+    )))
+}
@@ -0,0 +1,434 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{GitProvider, RepoItem, RepositoryInfo, TreeOptions};
+use serde_json::{Value, json};
+
+use crate::common::{
+    find_provider, iso_timestamp_days_ago, language_for_path, parse_repo_identifier,
+    required_arguments, supported_providers, usize_argument,
+};
+
+const DEFAULT_ACTIVITY_DAYS: usize = 90;
+/// Commits counted per repository, more are reported as "N+"
+const MAX_COMMITS: usize = 100;
+/// Languages listed per repository, by share
+const TOP_LANGUAGES: usize = 4;
+
+/// Dependency manifests read at the repository root
+const MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "go.mod",
+    "requirements.txt",
+    "pyproject.toml",
+    "Gemfile",
+];
+
+/// Counts the dependencies declared by a manifest, development ones included
+fn count_dependencies(manifest: &str, content: &str) -> Option<usize> {
+    let table_len = |value: Option<&toml::Value>| {
+        value
+            .and_then(|v| v.as_table())
+            .map_or(0, |table| table.len())
+    };
+
+    match manifest {
+        "Cargo.toml" => {
+            let value: toml::Value = toml::from_str(content).ok()?;
+            let workspace = value.get("workspace");
+            Some(
+                ["dependencies", "dev-dependencies", "build-dependencies"]
+                    .iter()
+                    .map(|key| table_len(value.get(key)))
+                    .sum::<usize>()
+                    + table_len(workspace.and_then(|w| w.get("dependencies"))),
+            )
+        }
+        "package.json" => {
+            let value: Value = serde_json::from_str(content).ok()?;
+            Some(
+                [
+                    "dependencies",
+                    "devDependencies",
+                    "peerDependencies",
+                    "optionalDependencies",
+                ]
+                .iter()
+                .filter_map(|key| value.get(key).and_then(|v| v.as_object()))
+                .map(|dependencies| dependencies.len())
+                .sum(),
+            )
+        }
+        "go.mod" => {
+            let mut count = 0;
+            let mut in_block = false;
+            for line in content.lines().map(str::trim) {
+                if in_block {
+                    if line == ")" {
+                        in_block = false;
+                    } else if !line.is_empty() && !line.starts_with("//") {
+                        count += 1;
+                    }
+                } else if line == "require (" {
+                    in_block = true;
+                } else if line.starts_with("require ") {
+                    count += 1;
+                }
+            }
+            Some(count)
+        }
+        "requirements.txt" => Some(
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('-'))
+                .count(),
+        ),
+        "pyproject.toml" => {
+            let value: toml::Value = toml::from_str(content).ok()?;
+            let project = value.get("project");
+            let array_len = |value: Option<&toml::Value>| {
+                value
+                    .and_then(|v| v.as_array())
+                    .map_or(0, |array| array.len())
+            };
+            let optional: usize = project
+                .and_then(|p| p.get("optional-dependencies"))
+                .and_then(|v| v.as_table())
+                .map_or(0, |groups| {
+                    groups.values().map(|v| array_len(Some(v))).sum()
+                });
+            let poetry = value.get("tool").and_then(|t| t.get("poetry"));
+            // Poetry lists the Python version among the dependencies
+            let poetry_dependencies = poetry
+                .and_then(|p| p.get("dependencies"))
+                .and_then(|v| v.as_table())
+                .map_or(0, |table| table.keys().filter(|k| *k != "python").count());
+
+            Some(
+                array_len(project.and_then(|p| p.get("dependencies")))
+                    + optional
+                    + poetry_dependencies
+                    + table_len(poetry.and_then(|p| p.get("dev-dependencies"))),
+            )
+        }
+        "Gemfile" => Some(
+            content
+                .lines()
+                .filter(|line| line.trim_start().starts_with("gem "))
+                .count(),
+        ),
+        _ => None,
+    }
+}
+
+/// Whether a path looks like a test by its directory or naming convention
+fn is_test_path(path: &str) -> bool {
+    let (directories, name) = match path.rsplit_once('/') {
+        Some((directories, name)) => (directories, name),
+        None => ("", path),
+    };
+    if directories
+        .split('/')
+        .any(|d| matches!(d, "test" | "tests" | "__tests__" | "spec" | "specs"))
+    {
+        return true;
+    }
+
+    let stem = name.split('.').next().unwrap_or(name);
+    stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with("_spec")
+        || stem.ends_with("Test")
+        || stem.ends_with("Tests")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+}
+
+/// The largest languages of a repository with their share of bytes, or of files when the
+/// host reports no sizes
+fn language_mix(files: &[RepoItem]) -> String {
+    let by_size = files.iter().all(|file| file.size.is_some());
+
+    let mut weights: BTreeMap<&str, u64> = BTreeMap::new();
+    for file in files {
+        if let Some(language) = language_for_path(&file.path) {
+            let weight = if by_size { file.size.unwrap_or(0) } else { 1 };
+            *weights.entry(language).or_default() += weight;
+        }
+    }
+
+    let total: u64 = weights.values().sum();
+    if total == 0 {
+        return "-".to_string();
+    }
+
+    let mut languages: Vec<(&str, u64)> = weights.into_iter().collect();
+    languages.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    languages
+        .iter()
+        .take(TOP_LANGUAGES)
+        .map(|(language, weight)| format!("{} {}%", language, weight * 100 / total))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Everything gathered about one side of the comparison, `None` where it could not be fetched
+struct Profile {
+    info: Option<RepositoryInfo>,
+    recent_commits: Option<usize>,
+    files: Option<Vec<RepoItem>>,
+    /// Dependency count per manifest found at the root
+    dependencies: Vec<(String, usize)>,
+    errors: Vec<String>,
+}
+
+/// The value of a result, noting its error under `what` otherwise
+fn record<T>(result: Result<T>, what: &str, errors: &mut Vec<String>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            errors.push(format!("{}: {}", what, e));
+            None
+        }
+    }
+}
+
+async fn profile(provider: &dyn GitProvider, repo_path: &str, days: usize) -> Profile {
+    let since = iso_timestamp_days_ago(days as u64);
+    let options = TreeOptions::default();
+    let (info, commits, files) = futures::join!(
+        provider.get_repository_info(repo_path),
+        provider.list_commits_since(repo_path, None, &since, MAX_COMMITS),
+        provider.list_files(repo_path, None, &options),
+    );
+
+    let mut errors = Vec::new();
+    let info = record(info, "metadata", &mut errors);
+    let recent_commits = record(commits, "commits", &mut errors).map(|commits| commits.len());
+    let files = record(files, "files", &mut errors);
+
+    let manifests: Vec<&str> = files
+        .iter()
+        .flatten()
+        .map(|file| file.path.as_str())
+        .filter(|path| MANIFESTS.contains(path))
+        .collect();
+    let contents = join_all(
+        manifests
+            .iter()
+            .map(|path| provider.get_file_content(repo_path, path, None)),
+    )
+    .await;
+
+    let mut dependencies = Vec::new();
+    for (manifest, content) in manifests.iter().zip(contents) {
+        match content {
+            Ok(content) => match count_dependencies(manifest, &content) {
+                Some(count) => dependencies.push((manifest.to_string(), count)),
+                None => errors.push(format!("{}: could not be parsed", manifest)),
+            },
+            Err(e) => errors.push(format!("{}: {}", manifest, e)),
+        }
+    }
+    dependencies.sort();
+
+    Profile {
+        info,
+        recent_commits,
+        files,
+        dependencies,
+        errors,
+    }
+}
+
+/// The labelled values of one column of the report, "-" standing for missing data
+fn report_rows(profile: &Profile, days: usize) -> Vec<(String, String)> {
+    let missing = || "-".to_string();
+    let info = profile.info.as_ref();
+
+    let dependencies = if profile.dependencies.is_empty() {
+        missing()
+    } else {
+        let total: usize = profile.dependencies.iter().map(|(_, count)| count).sum();
+        let manifests = profile
+            .dependencies
+            .iter()
+            .map(|(manifest, count)| format!("{} {}", manifest, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{} ({})", total, manifests)
+    };
+
+    vec![
+        (
+            "Description".to_string(),
+            info.and_then(|info| info.description.clone())
+                .unwrap_or_else(missing),
+        ),
+        (
+            "Stars".to_string(),
+            info.map_or_else(missing, |info| info.stars.to_string()),
+        ),
+        (
+            "Forks".to_string(),
+            info.map_or_else(missing, |info| info.forks.to_string()),
+        ),
+        (
+            "Open issues".to_string(),
+            info.and_then(|info| info.open_issues)
+                .map_or_else(missing, |count| count.to_string()),
+        ),
+        (
+            "License".to_string(),
+            info.and_then(|info| info.license.clone())
+                .unwrap_or_else(missing),
+        ),
+        (
+            "Created".to_string(),
+            info.map_or_else(missing, |info| info.created_at.clone()),
+        ),
+        (
+            "Last activity".to_string(),
+            info.and_then(|info| info.last_activity_at.clone())
+                .unwrap_or_else(missing),
+        ),
+        (
+            "Archived".to_string(),
+            info.map_or_else(missing, |info| {
+                if info.archived { "yes" } else { "no" }.to_string()
+            }),
+        ),
+        (
+            format!("Commits (last {} days)", days),
+            match profile.recent_commits {
+                Some(count) if count >= MAX_COMMITS => format!("{}+", MAX_COMMITS),
+                Some(count) => count.to_string(),
+                None => missing(),
+            },
+        ),
+        (
+            "Files".to_string(),
+            profile
+                .files
+                .as_ref()
+                .map_or_else(missing, |files| files.len().to_string()),
+        ),
+        (
+            "Languages".to_string(),
+            profile.files.as_deref().map_or_else(missing, language_mix),
+        ),
+        ("Dependencies".to_string(), dependencies),
+        (
+            "Test files".to_string(),
+            profile.files.as_ref().map_or_else(missing, |files| {
+                match files.iter().filter(|file| is_test_path(&file.path)).count() {
+                    0 => "none".to_string(),
+                    count => count.to_string(),
+                }
+            }),
+        ),
+    ]
+}
+
+/// Keeps a value on a single table cell
+fn cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+pub struct CompareRepositories {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl CompareRepositories {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for CompareRepositories {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let days = usize_argument(&args, "days").unwrap_or(DEFAULT_ACTIVITY_DAYS);
+        let mut repositories = Vec::new();
+        for key in ["repo_a", "repo_b"] {
+            let identifier = args
+                .get(key)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing or invalid {}", key.replace('_', " ")))?;
+            let (provider_name, repo_path) = parse_repo_identifier(identifier)?;
+            let provider = find_provider(&self.providers, provider_name)?;
+            repositories.push((identifier, provider, repo_path));
+        }
+
+        let profiles = join_all(
+            repositories
+                .iter()
+                .map(|(_, provider, repo_path)| profile(*provider, repo_path, days)),
+        )
+        .await;
+
+        let rows: Vec<Vec<(String, String)>> = profiles
+            .iter()
+            .map(|profile| report_rows(profile, days))
+            .collect();
+
+        let (repo_a, repo_b) = (repositories[0].0, repositories[1].0);
+        let mut formatted_output = format!("# {} vs {}\n\n", repo_a, repo_b);
+        formatted_output.push_str(&format!("| | {} | {} |\n|---|---|---|\n", repo_a, repo_b));
+        for ((label, a), (_, b)) in rows[0].iter().zip(&rows[1]) {
+            formatted_output.push_str(&format!("| {} | {} | {} |\n", label, cell(a), cell(b)));
+        }
+
+        for ((identifier, _, _), profile) in repositories.iter().zip(&profiles) {
+            if profile.errors.is_empty() {
+                continue;
+            }
+
+            formatted_output.push_str(&format!("\nMissing data for {}:\n", identifier));
+            for error in &profile.errors {
+                formatted_output.push_str(&format!("- {}\n", error));
+            }
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "compare_repositories".into(),
+            description: Some(format!(
+                "Compare two repositories side by side: popularity, recent activity, language mix, dependency count, license and tests, to choose between libraries. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo_a": {
+                        "type": "string",
+                        "description": "First repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:serde-rs/json')"
+                    },
+                    "repo_b": {
+                        "type": "string",
+                        "description": "Second repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:simd-lite/simd-json')"
+                    },
+                    "days": {
+                        "type": "integer",
+                        "description": format!("Optional window in days commits are counted over. Default: {}", DEFAULT_ACTIVITY_DAYS)
+                    }
+                },
+                "required": ["repo_a", "repo_b"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Result;
+use git_provider::{GitProvider, TreeOptions};
+use parking_lot::Mutex;
+use serde_json::Value;
+
+use crate::{
+    common::{find_provider, parse_repo_identifier},
+    history::AccessHistory,
+};
+
+/// Most values returned for a single completion request, as allowed by MCP
+pub const MAX_COMPLETIONS: usize = 100;
+
+/// Shortest query sent to the hosts' repository search
+const MIN_SEARCH_LENGTH: usize = 2;
+
+/// Suggests values for tool and resource template arguments while they are typed
+pub struct Completions {
+    providers: Vec<Arc<dyn GitProvider>>,
+    history: Arc<AccessHistory>,
+    /// File paths of the repositories completed so far, keyed by "provider:path"
+    files: Mutex<HashMap<String, Arc<Vec<String>>>>,
+}
+
+impl Completions {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>, history: Arc<AccessHistory>) -> Self {
+        Self {
+            providers,
+            history,
+            files: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Completes `value` for the argument `name`, reading the repository from the other
+    /// `arguments` already filled in: either `repo` or `provider` and `repo`
+    pub async fn complete(
+        &self,
+        name: &str,
+        value: &str,
+        arguments: &Value,
+    ) -> Result<Vec<String>> {
+        let mut values = match name {
+            "provider" => self
+                .providers
+                .iter()
+                .map(|p| p.name().to_string())
+                .filter(|p| p.starts_with(value))
+                .collect(),
+            "repo" if arguments.get("provider").is_some() => {
+                let provider = arguments["provider"].as_str().unwrap_or_default();
+                self.complete_repo(&format!("{}:{}", provider, value))
+                    .await?
+                    .into_iter()
+                    .filter_map(|repo| repo.split_once(':').map(|(_, path)| path.to_string()))
+                    .collect()
+            }
+            "repo" => self.complete_repo(value).await?,
+            "git_ref" | "base" | "head" | "from" | "to" => match self.repository(arguments) {
+                Some((provider, repo_path)) => provider.list_refs(&repo_path, value).await?,
+                None => Vec::new(),
+            },
+            "file_path" | "path" => match self.repository(arguments) {
+                Some((provider, repo_path)) => {
+                    let files = self.files(provider, &repo_path).await?;
+                    files
+                        .iter()
+                        .filter(|path| path.starts_with(value))
+                        .take(MAX_COMPLETIONS + 1)
+                        .cloned()
+                        .collect()
+                }
+                None => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+
+        values.sort();
+        values.dedup();
+        Ok(values)
+    }
+
+    /// Recently accessed repositories, and provider names until one is chosen, then the
+    /// repositories found by the host's search
+    async fn complete_repo(&self, value: &str) -> Result<Vec<String>> {
+        let mut values: Vec<String> = self
+            .history
+            .recent()
+            .into_iter()
+            .map(|access| access.repo)
+            .filter(|repo| repo.starts_with(value))
+            .collect();
+
+        let Some((provider_name, query)) = value.split_once(':') else {
+            values.extend(
+                self.providers
+                    .iter()
+                    .map(|p| format!("{}:", p.name()))
+                    .filter(|p| p.starts_with(value)),
+            );
+            return Ok(values);
+        };
+
+        if query.len() < MIN_SEARCH_LENGTH {
+            return Ok(values);
+        }
+        let provider = find_provider(&self.providers, provider_name)?;
+
+        // Searching "owner/na" finds nothing, search for the name and filter on the owner
+        let (owner, name) = match query.split_once('/') {
+            Some((owner, name)) => (Some(owner), name),
+            None => (None, query),
+        };
+        let results = provider
+            .find_repositories(if name.is_empty() { query } else { name }, Some(20))
+            .await?;
+
+        values.extend(
+            results
+                .into_iter()
+                .filter(|result| {
+                    owner.is_none_or(|owner| {
+                        result
+                            .full_name
+                            .to_lowercase()
+                            .starts_with(&format!("{}/", owner.to_lowercase()))
+                    })
+                })
+                .map(|result| format!("{}:{}", provider_name, result.full_name)),
+        );
+
+        Ok(values)
+    }
+
+    fn repository<'a>(&'a self, arguments: &Value) -> Option<(&'a dyn GitProvider, String)> {
+        let repo = arguments.get("repo")?.as_str()?;
+
+        let (provider_name, repo_path) = match arguments.get("provider").and_then(|p| p.as_str()) {
+            Some(provider) => (provider, repo),
+            None => parse_repo_identifier(repo).ok()?,
+        };
+
+        Some((
+            find_provider(&self.providers, provider_name).ok()?,
+            repo_path.to_string(),
+        ))
+    }
+
+    async fn files(&self, provider: &dyn GitProvider, repo_path: &str) -> Result<Arc<Vec<String>>> {
+        let key = format!("{}:{}", provider.name(), repo_path);
+        if let Some(files) = self.files.lock().get(&key) {
+            return Ok(files.clone());
+        }
+
+        let files: Arc<Vec<String>> = Arc::new(
+            provider
+                .list_files(repo_path, None, &TreeOptions::default())
+                .await?
+                .into_iter()
+                .map(|file| file.path)
+                .collect(),
+        );
+        self.files.lock().insert(key, files.clone());
+
+        Ok(files)
+    }
+}
@@ -0,0 +1,457 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{GitProvider, TreeOptions};
+use regex::Regex;
+use serde_json::{Value, json};
+
+use crate::{
+    common::{
+        git_ref_argument, language_for_path, repo_argument, required_arguments,
+        supported_providers, usize_argument,
+    },
+    semantic::StructuredFormat,
+};
+
+pub(crate) const DEFAULT_MAX_FILES: usize = 300;
+/// Generated or vendored sources this large are not worth scanning
+const MAX_FILE_SIZE: u64 = 256 * 1024;
+/// Locations listed per variable before the rest is only counted
+const MAX_LOCATIONS: usize = 5;
+/// Example values are cut past this length
+const MAX_VALUE_CHARS: usize = 60;
+
+/// Languages whose sources are scanned for environment variable reads
+const SCANNED_LANGUAGES: &[&str] = &[
+    "Rust",
+    "Go",
+    "Python",
+    "JavaScript",
+    "JavaScript (JSX)",
+    "TypeScript",
+    "TypeScript (TSX)",
+    "Java",
+    "Kotlin",
+    "C#",
+    "Ruby",
+    "PHP",
+    "Dockerfile",
+];
+
+/// Reads of an environment variable across languages, capturing the variable name
+const ENV_PATTERNS: &[&str] = &[
+    // Rust, including clap's `#[arg(env = "...")]`
+    r#"\benv::var(?:_os)?\(\s*"([A-Za-z_][A-Za-z0-9_]*)""#,
+    r#"\b(?:option_)?env!\(\s*"([A-Za-z_][A-Za-z0-9_]*)""#,
+    r#"\benv\s*=\s*"([A-Za-z_][A-Za-z0-9_]*)""#,
+    // JavaScript and TypeScript
+    r#"\bprocess\.env\.([A-Za-z_][A-Za-z0-9_]*)"#,
+    r#"\bprocess\.env\[\s*['"]([A-Za-z_][A-Za-z0-9_]*)['"]"#,
+    r#"\bimport\.meta\.env\.([A-Za-z_][A-Za-z0-9_]*)"#,
+    // Python
+    r#"\bos\.environ(?:\.get\(|\[)\s*['"]([A-Za-z_][A-Za-z0-9_]*)['"]"#,
+    r#"\bos\.getenv\(\s*['"]([A-Za-z_][A-Za-z0-9_]*)['"]"#,
+    // Go
+    r#"\bos\.(?:Getenv|LookupEnv)\(\s*"([A-Za-z_][A-Za-z0-9_]*)""#,
+    // Java, Kotlin and C#
+    r#"\bSystem\.getenv\(\s*"([A-Za-z_][A-Za-z0-9_]*)""#,
+    r#"\bGetEnvironmentVariable\(\s*"([A-Za-z_][A-Za-z0-9_]*)""#,
+    // Ruby and PHP
+    r#"\bENV(?:\.fetch\(|\[)\s*['"]([A-Za-z_][A-Za-z0-9_]*)['"]"#,
+    r#"(?:\bgetenv\(|\$_ENV\[)\s*['"]([A-Za-z_][A-Za-z0-9_]*)['"]"#,
+    // Dockerfile
+    r"^\s*(?:ENV|ARG)\s+([A-Za-z_][A-Za-z0-9_]*)",
+];
+
+/// Marks in file names of configuration examples meant to be copied
+const EXAMPLE_MARKS: &[&str] = &["example", "sample", "template", "dist", "defaults"];
+
+/// How an example configuration file lists its keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExampleFormat {
+    /// `KEY=value` lines, as read by dotenv
+    Dotenv,
+    Structured(StructuredFormat),
+}
+
+impl ExampleFormat {
+    fn from_path(path: &str) -> Option<Self> {
+        let name = path.rsplit('/').next().unwrap_or(path).to_ascii_lowercase();
+        let parts: Vec<&str> = name.split('.').collect();
+        if !parts
+            .iter()
+            .any(|part| EXAMPLE_MARKS.iter().any(|mark| part.contains(mark)))
+        {
+            return None;
+        }
+
+        // ".env.example", "example.env", "config.example.yaml" or "config.yaml.dist"
+        if parts.contains(&"env") {
+            return Some(Self::Dotenv);
+        }
+        parts
+            .iter()
+            .rev()
+            .find_map(|part| StructuredFormat::from_path(&format!(".{}", part)))
+            .map(Self::Structured)
+    }
+}
+
+/// Shortens an example value to fit on a line
+fn shorten(value: &str) -> String {
+    let value = value.trim();
+    if value.chars().count() > MAX_VALUE_CHARS {
+        format!(
+            "{}…",
+            value.chars().take(MAX_VALUE_CHARS).collect::<String>()
+        )
+    } else {
+        value.to_string()
+    }
+}
+
+/// The keys of a dotenv file with their example values
+fn dotenv_keys(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            Some((key.trim().to_string(), shorten(value)))
+        })
+        .collect()
+}
+
+/// The leaf keys of a structured document as dotted paths, with their scalar values
+fn structured_keys(prefix: &str, value: &Value, keys: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(object) => {
+            for (key, child) in object {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                structured_keys(&path, child, keys);
+            }
+        }
+        Value::Array(_) => keys.push((prefix.to_string(), "[…]".to_string())),
+        Value::String(text) => keys.push((prefix.to_string(), shorten(text))),
+        Value::Null => keys.push((prefix.to_string(), String::new())),
+        scalar => keys.push((prefix.to_string(), scalar.to_string())),
+    }
+}
+
+pub struct ConfigInventory {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ConfigInventory {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ConfigInventory {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let max_files = usize_argument(&args, "max_files").unwrap_or(DEFAULT_MAX_FILES);
+
+        // Example files are usually hidden, so the default ignore patterns are bypassed
+        let mut files = provider
+            .list_files(
+                repo_path,
+                git_ref.clone(),
+                &TreeOptions {
+                    ignore_patterns: Some(vec![]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| anyhow!("Error listing files: {}", e))?;
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let examples: Vec<(&str, ExampleFormat)> = files
+            .iter()
+            .filter_map(|file| {
+                ExampleFormat::from_path(&file.path).map(|format| (file.path.as_str(), format))
+            })
+            .collect();
+        // Dependencies would drown the project's own variables
+        let sources: Vec<&str> = files
+            .iter()
+            .filter(|file| file.size.is_none_or(|size| size <= MAX_FILE_SIZE))
+            .filter(|file| {
+                !file
+                    .path
+                    .split('/')
+                    .any(|segment| matches!(segment, "node_modules" | "vendor" | "third_party"))
+            })
+            .filter(|file| {
+                language_for_path(&file.path)
+                    .is_some_and(|language| SCANNED_LANGUAGES.contains(&language))
+            })
+            .map(|file| file.path.as_str())
+            .collect();
+        let scanned = &sources[..sources.len().min(max_files)];
+
+        let patterns = ENV_PATTERNS
+            .iter()
+            .map(|pattern| Regex::new(&format!("(?m){}", pattern)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let contents = join_all(
+            scanned
+                .iter()
+                .chain(examples.iter().map(|(path, _)| path))
+                .map(|path| provider.get_file_content(repo_path, path, git_ref.clone())),
+        )
+        .await;
+        let (source_contents, example_contents) = contents.split_at(scanned.len());
+
+        // Locations by variable, deduplicated by line
+        let mut variables: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (path, content) in scanned.iter().zip(source_contents) {
+            let Ok(content) = content else {
+                continue;
+            };
+            for (index, line) in content.lines().enumerate() {
+                for pattern in &patterns {
+                    for captures in pattern.captures_iter(line) {
+                        let location = format!("{}:{}", path, index + 1);
+                        let locations = variables.entry(captures[1].to_string()).or_default();
+                        if !locations.contains(&location) {
+                            locations.push(location);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut example_keys: Vec<(&str, Result<Vec<(String, String)>>)> = Vec::new();
+        for ((path, format), content) in examples.iter().zip(example_contents) {
+            let keys = match content {
+                Ok(content) => match format {
+                    ExampleFormat::Dotenv => Ok(dotenv_keys(content)),
+                    ExampleFormat::Structured(format) => format.parse(content).map(|document| {
+                        let mut keys = Vec::new();
+                        structured_keys("", &document, &mut keys);
+                        keys
+                    }),
+                },
+                Err(e) => Err(anyhow!("{}", e)),
+            };
+            example_keys.push((*path, keys));
+        }
+
+        // Values documented by dotenv examples, shown next to the variables that read them
+        let documented: BTreeMap<&str, &str> = example_keys
+            .iter()
+            .zip(&examples)
+            .filter(|(_, (_, format))| *format == ExampleFormat::Dotenv)
+            .filter_map(|((_, keys), _)| keys.as_ref().ok())
+            .flatten()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+
+        if variables.is_empty() && example_keys.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!(
+                    "No environment variable reads or example configuration found in {}",
+                    repo_path
+                ),
+            }]);
+        }
+
+        let mut formatted_output = format!(
+            "# Configuration of {}\n\n{} environment variables read in {} scanned files, {} example configuration files\n",
+            repo_path,
+            variables.len(),
+            scanned.len(),
+            example_keys.len()
+        );
+        if sources.len() > scanned.len() {
+            formatted_output.push_str(&format!(
+                "({} more source files not scanned, raise max_files to include them)\n",
+                sources.len() - scanned.len()
+            ));
+        }
+
+        if !variables.is_empty() {
+            formatted_output.push_str("\n## Environment variables\n\n");
+            for (name, locations) in &variables {
+                formatted_output.push_str(&format!(
+                    "- {}: {}",
+                    name,
+                    locations
+                        .iter()
+                        .take(MAX_LOCATIONS)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+                if locations.len() > MAX_LOCATIONS {
+                    formatted_output
+                        .push_str(&format!(" and {} more", locations.len() - MAX_LOCATIONS));
+                }
+                match documented.get(name.as_str()) {
+                    Some(value) if !value.is_empty() => {
+                        formatted_output.push_str(&format!(" (example: {})", value))
+                    }
+                    Some(_) => formatted_output.push_str(" (documented)"),
+                    None => {}
+                }
+                formatted_output.push('\n');
+            }
+        }
+
+        if !example_keys.is_empty() {
+            formatted_output.push_str("\n## Example configuration\n");
+            for (path, keys) in example_keys {
+                formatted_output.push_str(&format!("\n### {}\n\n", path));
+                match keys {
+                    Ok(keys) if keys.is_empty() => formatted_output.push_str("No keys\n"),
+                    Ok(keys) => {
+                        for (key, value) in keys {
+                            if value.is_empty() {
+                                formatted_output.push_str(&format!("- {}\n", key));
+                            } else {
+                                formatted_output.push_str(&format!("- {} = {}\n", key, value));
+                            }
+                        }
+                    }
+                    Err(e) => formatted_output.push_str(&format!("Unreadable: {}\n", e)),
+                }
+            }
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "config_inventory".into(),
+            description: Some(format!(
+                "List the environment variables a repository's code reads (Rust, Go, Python, JavaScript/TypeScript, Java/Kotlin, C#, Ruby, PHP and Dockerfiles) with their locations, and the keys of its example configuration files (.env.example, config.example.yaml, ...), to answer how a service is configured. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "description": format!("Optional maximum number of source files scanned. Default: {}", DEFAULT_MAX_FILES)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The variables the patterns find in one line of source
+    fn reads(line: &str) -> Vec<String> {
+        ENV_PATTERNS
+            .iter()
+            .map(|pattern| Regex::new(&format!("(?m){}", pattern)).unwrap())
+            .flat_map(|pattern| {
+                pattern
+                    .captures_iter(line)
+                    .map(|captures| captures[1].to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reads_are_found_across_languages() {
+        assert_eq!(
+            reads(r#"let url = env::var("DATABASE_URL")?;"#),
+            ["DATABASE_URL"]
+        );
+        assert_eq!(
+            reads("const key = process.env.API_KEY ?? process.env['FALLBACK_KEY'];"),
+            ["API_KEY", "FALLBACK_KEY"]
+        );
+        assert_eq!(reads(r#"debug = os.environ.get("DEBUG", "0")"#), ["DEBUG"]);
+        assert_eq!(reads(r#"port := os.Getenv("PORT")"#), ["PORT"]);
+        assert_eq!(reads("ENV['REDIS_URL']"), ["REDIS_URL"]);
+        assert_eq!(reads("ARG NODE_VERSION=20"), ["NODE_VERSION"]);
+        assert!(reads("environment = production").is_empty());
+    }
+
+    #[test]
+    fn example_files_are_recognized() {
+        assert_eq!(
+            ExampleFormat::from_path(".env.example"),
+            Some(ExampleFormat::Dotenv)
+        );
+        assert_eq!(
+            ExampleFormat::from_path("config/settings.sample.yml"),
+            Some(ExampleFormat::Structured(StructuredFormat::Yaml))
+        );
+        assert_eq!(
+            ExampleFormat::from_path("app.toml.dist"),
+            Some(ExampleFormat::Structured(StructuredFormat::Toml))
+        );
+        assert_eq!(ExampleFormat::from_path(".env"), None);
+        assert_eq!(ExampleFormat::from_path("config.yaml"), None);
+    }
+
+    #[test]
+    fn example_keys_are_listed() {
+        assert_eq!(
+            dotenv_keys("# Database\nexport DB_HOST=\"localhost\"\nDB_PORT = 5432\nNOT A KEY\n"),
+            [
+                ("DB_HOST".to_string(), "localhost".to_string()),
+                ("DB_PORT".to_string(), "5432".to_string()),
+            ]
+        );
+
+        let document = StructuredFormat::Yaml
+            .parse("server:\n  port: 8080\n  hosts: [a, b]\nlog: ~\n")
+            .unwrap();
+        let mut keys = Vec::new();
+        structured_keys("", &document, &mut keys);
+        assert_eq!(
+            keys,
+            [
+                ("log".to_string(), String::new()),
+                ("server.hosts".to_string(), "[…]".to_string()),
+                ("server.port".to_string(), "8080".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            shorten(&"x".repeat(MAX_VALUE_CHARS + 1)).chars().count(),
+            MAX_VALUE_CHARS + 1
+        );
+        assert!(shorten(&"x".repeat(MAX_VALUE_CHARS + 1)).ends_with('…'));
+    }
+}
@@ -0,0 +1,575 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{GitProvider, TreeOptions};
+use regex::{Captures, Regex};
+use serde_json::{Value, json};
+
+use crate::common::{git_ref_argument, repo_argument, required_arguments, supported_providers};
+
+/// Directories of dependencies, whose container files are not the project's
+const SKIPPED_DIRECTORIES: &[&str] = &["node_modules", "vendor", "third_party"];
+
+/// What a file describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContainerFile {
+    Dockerfile,
+    Compose,
+}
+
+impl ContainerFile {
+    fn from_path(path: &str) -> Option<Self> {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let lower = name.to_ascii_lowercase();
+
+        if lower == "dockerfile"
+            || lower == "containerfile"
+            || lower.starts_with("dockerfile.")
+            || lower.ends_with(".dockerfile")
+        {
+            Some(Self::Dockerfile)
+        } else if (lower.starts_with("compose.") || lower.starts_with("docker-compose."))
+            && (lower.ends_with(".yml") || lower.ends_with(".yaml"))
+        {
+            Some(Self::Compose)
+        } else {
+            None
+        }
+    }
+}
+
+/// A COPY or ADD instruction
+struct CopyInstruction {
+    instruction: String,
+    /// The stage or image the files come from, for `--from`
+    from: Option<String>,
+    sources: Vec<String>,
+    destination: String,
+    line: usize,
+}
+
+/// A build stage, started by a FROM instruction
+#[derive(Default)]
+struct Stage {
+    name: Option<String>,
+    base: String,
+    /// Whether the base is an earlier stage rather than an image
+    base_is_stage: bool,
+    platform: Option<String>,
+    line: usize,
+    workdir: Option<String>,
+    user: Option<String>,
+    exposes: Vec<String>,
+    copies: Vec<CopyInstruction>,
+    entrypoint: Option<String>,
+    cmd: Option<String>,
+}
+
+/// The instructions of a Dockerfile with the line each starts on, continuations joined and
+/// comments dropped
+fn instructions(content: &str) -> Vec<(usize, String, String)> {
+    let mut instructions = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') || trimmed.is_empty() {
+            continue;
+        }
+
+        let (start, mut text) = current.take().unwrap_or((index + 1, String::new()));
+        match trimmed.strip_suffix('\\') {
+            Some(continued) => {
+                text.push_str(continued.trim());
+                text.push(' ');
+                current = Some((start, text));
+            }
+            None => {
+                text.push_str(trimmed);
+                instructions.push((start, text));
+            }
+        }
+    }
+    instructions.extend(current);
+
+    instructions
+        .into_iter()
+        .filter_map(|(line, text)| {
+            let (keyword, arguments) = text.split_once(char::is_whitespace)?;
+            Some((line, keyword.to_uppercase(), arguments.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Splits the arguments of an instruction into its `--flag=value` options and the rest,
+/// reading the JSON array form of COPY, ADD, ENTRYPOINT and CMD
+fn split_arguments(arguments: &str) -> (BTreeMap<String, String>, Vec<String>) {
+    let mut flags = BTreeMap::new();
+    let mut rest = arguments.trim();
+    while let Some(flag) = rest.strip_prefix("--") {
+        let (flag, remainder) = flag.split_once(char::is_whitespace).unwrap_or((flag, ""));
+        let (name, value) = flag.split_once('=').unwrap_or((flag, ""));
+        flags.insert(name.to_string(), value.to_string());
+        rest = remainder.trim_start();
+    }
+
+    let words = match serde_json::from_str::<Vec<String>>(rest) {
+        Ok(words) => words,
+        Err(_) => rest.split_whitespace().map(String::from).collect(),
+    };
+    (flags, words)
+}
+
+/// Replaces `$NAME`, `${NAME}` and `${NAME:-default}` with the value of a build argument,
+/// keeping references to arguments without a default
+fn substitute(text: &str, variables: &BTreeMap<String, String>) -> Result<String> {
+    let reference = Regex::new(r"\$\{(\w+)(?::-([^}]*))?\}|\$(\w+)")?;
+
+    Ok(reference
+        .replace_all(text, |captures: &Captures| {
+            let name = captures
+                .get(1)
+                .or_else(|| captures.get(3))
+                .map_or("", |m| m.as_str());
+            variables
+                .get(name)
+                .map(String::as_str)
+                .or_else(|| captures.get(2).map(|m| m.as_str()))
+                .unwrap_or(&captures[0])
+                .to_string()
+        })
+        .into_owned())
+}
+
+/// The build stages of a Dockerfile
+fn parse_dockerfile(content: &str) -> Result<Vec<Stage>> {
+    let mut variables = BTreeMap::new();
+    let mut stages: Vec<Stage> = Vec::new();
+
+    for (line, keyword, arguments) in instructions(content) {
+        if keyword == "FROM" {
+            let (flags, words) = split_arguments(&arguments);
+            let base = substitute(words.first().map_or("", String::as_str), &variables)?;
+            let name = match &words[..] {
+                [_, as_keyword, name, ..] if as_keyword.eq_ignore_ascii_case("as") => {
+                    Some(name.clone())
+                }
+                _ => None,
+            };
+            let base_is_stage = stages
+                .iter()
+                .any(|stage| stage.name.as_deref() == Some(base.as_str()));
+
+            stages.push(Stage {
+                name,
+                base,
+                base_is_stage,
+                platform: flags.get("platform").cloned(),
+                line,
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let Some(stage) = stages.last_mut() else {
+            // Arguments declared before the first stage parameterize the FROM lines
+            if keyword == "ARG" {
+                let (name, default) = arguments.split_once('=').unwrap_or((&arguments, ""));
+                let default = default.trim_matches('"');
+                if !default.is_empty() {
+                    variables.insert(name.trim().to_string(), default.to_string());
+                }
+            }
+            continue;
+        };
+
+        match keyword.as_str() {
+            "WORKDIR" => stage.workdir = Some(arguments),
+            "USER" => stage.user = Some(arguments),
+            "EXPOSE" => stage
+                .exposes
+                .extend(arguments.split_whitespace().map(String::from)),
+            "ENTRYPOINT" => stage.entrypoint = Some(arguments),
+            "CMD" => stage.cmd = Some(arguments),
+            "COPY" | "ADD" => {
+                let (flags, mut words) = split_arguments(&arguments);
+                let Some(destination) = words.pop() else {
+                    continue;
+                };
+                stage.copies.push(CopyInstruction {
+                    instruction: keyword.clone(),
+                    from: flags.get("from").cloned(),
+                    sources: words,
+                    destination,
+                    line,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(stages)
+}
+
+/// A string, or the entries of a list or the keys of a mapping, of a compose field
+fn compose_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(text)) => vec![text.clone()],
+        Some(Value::Number(number)) => vec![number.to_string()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::String(text) => Some(text.clone()),
+                Value::Number(number) => Some(number.to_string()),
+                // The long syntax of ports and volumes
+                Value::Object(fields) => {
+                    let target = fields.get("target").map(|v| match v {
+                        Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    })?;
+                    Some(
+                        match fields.get("published").or_else(|| fields.get("source")) {
+                            Some(Value::String(source)) => format!("{}:{}", source, target),
+                            Some(source) => format!("{}:{}", source, target),
+                            None => target,
+                        },
+                    )
+                }
+                _ => None,
+            })
+            .collect(),
+        Some(Value::Object(fields)) => fields.keys().cloned().collect(),
+        _ => vec![],
+    }
+}
+
+/// The services of a compose file, formatted as a list
+fn format_compose(content: &str) -> Option<String> {
+    let document: Value = serde_yaml::from_str(content).ok()?;
+    let services = document.get("services")?.as_object()?;
+
+    let mut output = String::new();
+    for (name, service) in services {
+        output.push_str(&format!("- `{}`", name));
+
+        if let Some(image) = service.get("image").and_then(|v| v.as_str()) {
+            output.push_str(&format!(": image `{}`", image));
+        }
+        match service.get("build") {
+            Some(Value::String(context)) => output.push_str(&format!(", built from {}", context)),
+            Some(Value::Object(build)) => {
+                let field = |key: &str| build.get(key).and_then(|v| v.as_str());
+                output.push_str(&format!(", built from {}", field("context").unwrap_or(".")));
+                if let Some(dockerfile) = field("dockerfile") {
+                    output.push_str(&format!(" with {}", dockerfile));
+                }
+                if let Some(target) = field("target") {
+                    output.push_str(&format!(" (stage {})", target));
+                }
+            }
+            _ => {}
+        }
+        output.push('\n');
+
+        for (label, key) in [
+            ("Ports", "ports"),
+            ("Exposes", "expose"),
+            ("Depends on", "depends_on"),
+            ("Volumes", "volumes"),
+        ] {
+            let values = compose_list(service.get(key));
+            if !values.is_empty() {
+                output.push_str(&format!("  - {}: {}\n", label, values.join(", ")));
+            }
+        }
+    }
+
+    Some(output)
+}
+
+pub struct ContainerAnalysis {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ContainerAnalysis {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ContainerAnalysis {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+
+        let mut files = provider
+            .list_files(repo_path, git_ref.clone(), &TreeOptions::default())
+            .await
+            .map_err(|e| anyhow!("Error listing files: {}", e))?;
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let found: Vec<(&str, ContainerFile)> = files
+            .iter()
+            .filter(|file| {
+                !file
+                    .path
+                    .split('/')
+                    .any(|segment| SKIPPED_DIRECTORIES.contains(&segment))
+            })
+            .filter_map(|file| {
+                ContainerFile::from_path(&file.path).map(|kind| (file.path.as_str(), kind))
+            })
+            .collect();
+
+        if found.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("No Dockerfiles or compose files found in {}", repo_path),
+            }]);
+        }
+
+        let contents = join_all(
+            found
+                .iter()
+                .map(|(path, _)| provider.get_file_content(repo_path, path, git_ref.clone())),
+        )
+        .await;
+
+        // Files using each base image, in the order they are found
+        let mut base_images: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut sections = String::new();
+        for ((path, kind), content) in found.iter().zip(contents) {
+            let content = match content {
+                Ok(content) => content,
+                Err(e) => {
+                    sections.push_str(&format!("\n## {}\n\nError reading file: {}\n", path, e));
+                    continue;
+                }
+            };
+
+            match kind {
+                ContainerFile::Dockerfile => {
+                    let stages = parse_dockerfile(&content)?;
+                    sections.push_str(&format!(
+                        "\n## {} ({} stage{})\n",
+                        path,
+                        stages.len(),
+                        if stages.len() == 1 { "" } else { "s" }
+                    ));
+
+                    for (index, stage) in stages.iter().enumerate() {
+                        if !stage.base_is_stage && stage.base != "scratch" {
+                            let users = base_images.entry(stage.base.clone()).or_default();
+                            if !users.iter().any(|user| user == path) {
+                                users.push(path.to_string());
+                            }
+                        }
+
+                        sections.push_str(&format!("\n### Stage {}", index + 1));
+                        if let Some(name) = &stage.name {
+                            sections.push_str(&format!(" `{}`", name));
+                        }
+                        sections.push_str(&format!(
+                            " from {} `{}`",
+                            if stage.base_is_stage {
+                                "stage"
+                            } else {
+                                "image"
+                            },
+                            stage.base
+                        ));
+                        if let Some(platform) = &stage.platform {
+                            sections.push_str(&format!(" for {}", platform));
+                        }
+                        sections.push_str(&format!(" ({}:{})\n\n", path, stage.line));
+
+                        if let Some(workdir) = &stage.workdir {
+                            sections.push_str(&format!("- Workdir: {}\n", workdir));
+                        }
+                        for copy in &stage.copies {
+                            sections.push_str(&format!(
+                                "- {}{}: {} → {} (line {})\n",
+                                copy.instruction,
+                                copy.from
+                                    .as_ref()
+                                    .map(|from| format!(" from `{}`", from))
+                                    .unwrap_or_default(),
+                                copy.sources.join(", "),
+                                copy.destination,
+                                copy.line
+                            ));
+                        }
+                        if !stage.exposes.is_empty() {
+                            sections
+                                .push_str(&format!("- Exposes: {}\n", stage.exposes.join(", ")));
+                        }
+                        if let Some(user) = &stage.user {
+                            sections.push_str(&format!("- User: {}\n", user));
+                        }
+                        if let Some(entrypoint) = &stage.entrypoint {
+                            sections.push_str(&format!("- Entrypoint: `{}`\n", entrypoint));
+                        }
+                        if let Some(cmd) = &stage.cmd {
+                            sections.push_str(&format!("- Cmd: `{}`\n", cmd));
+                        }
+                    }
+                }
+                ContainerFile::Compose => {
+                    sections.push_str(&format!("\n## {}\n\n", path));
+                    match format_compose(&content) {
+                        Some(services) if !services.is_empty() => sections.push_str(&services),
+                        _ => sections.push_str("No services defined\n"),
+                    }
+                }
+            }
+        }
+
+        let mut formatted_output = format!("# Containers of {}\n", repo_path);
+        if !base_images.is_empty() {
+            formatted_output.push_str("\n## Base images\n\n");
+            for (image, users) in &base_images {
+                formatted_output.push_str(&format!("- `{}` ({})\n", image, users.join(", ")));
+            }
+        }
+        formatted_output.push_str(&sections);
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "container_analysis".into(),
+            description: Some(format!(
+                "Analyze the Dockerfiles and compose files of a repository: base images, build stages, copied paths, exposed ports, users and entrypoints of each stage, and the images, builds, ports, dependencies and volumes of each compose service. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCKERFILE: &str = r#"# syntax=docker/dockerfile:1
+ARG RUST_VERSION=1.82
+FROM --platform=$BUILDPLATFORM rust:${RUST_VERSION}-slim AS builder
+WORKDIR /src
+COPY Cargo.toml Cargo.lock ./
+RUN cargo build --release \
+    --locked
+FROM builder AS tester
+FROM gcr.io/distroless/cc:${TAG:-nonroot}
+COPY --from=builder ["/src/target/release/app", "/usr/local/bin/app"]
+USER 65532
+EXPOSE 8080 9090/udp
+ENTRYPOINT ["/usr/local/bin/app"]
+"#;
+
+    #[test]
+    fn file_kinds_follow_their_names() {
+        assert_eq!(
+            ContainerFile::from_path("deploy/Dockerfile.prod"),
+            Some(ContainerFile::Dockerfile)
+        );
+        assert_eq!(
+            ContainerFile::from_path("api.dockerfile"),
+            Some(ContainerFile::Dockerfile)
+        );
+        assert_eq!(
+            ContainerFile::from_path("docker-compose.override.yaml"),
+            Some(ContainerFile::Compose)
+        );
+        assert_eq!(ContainerFile::from_path("compose.json"), None);
+    }
+
+    #[test]
+    fn continued_lines_are_joined() {
+        let parsed = instructions(
+            "# comment\nrun apt-get update \\\n  && apt-get install -y git\n\nCMD [\"sh\"]\n",
+        );
+        assert_eq!(
+            parsed,
+            [
+                (
+                    2,
+                    "RUN".to_string(),
+                    "apt-get update && apt-get install -y git".to_string()
+                ),
+                (5, "CMD".to_string(), "[\"sh\"]".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn stages_are_parsed_with_their_arguments() {
+        let stages = parse_dockerfile(DOCKERFILE).unwrap();
+        assert_eq!(stages.len(), 3);
+
+        assert_eq!(stages[0].name.as_deref(), Some("builder"));
+        assert_eq!(stages[0].base, "rust:1.82-slim");
+        assert_eq!(stages[0].platform.as_deref(), Some("$BUILDPLATFORM"));
+        assert_eq!(stages[0].workdir.as_deref(), Some("/src"));
+        assert_eq!(stages[0].copies[0].sources, ["Cargo.toml", "Cargo.lock"]);
+        assert_eq!(stages[0].copies[0].destination, "./");
+
+        assert!(stages[1].base_is_stage);
+
+        assert_eq!(stages[2].base, "gcr.io/distroless/cc:nonroot");
+        assert!(!stages[2].base_is_stage);
+        assert_eq!(stages[2].copies[0].from.as_deref(), Some("builder"));
+        assert_eq!(stages[2].copies[0].sources, ["/src/target/release/app"]);
+        assert_eq!(stages[2].copies[0].line, 10);
+        assert_eq!(stages[2].user.as_deref(), Some("65532"));
+        assert_eq!(stages[2].exposes, ["8080", "9090/udp"]);
+    }
+
+    #[test]
+    fn compose_services_are_listed() {
+        let compose = r#"services:
+  db:
+    image: postgres:16
+    volumes:
+      - type: volume
+        source: data
+        target: /var/lib/postgresql/data
+  web:
+    build:
+      context: ./web
+      target: runtime
+    ports: ["8080:80", 9000]
+    depends_on:
+      db:
+        condition: service_healthy
+"#;
+
+        assert_eq!(
+            format_compose(compose).unwrap(),
+            "- `db`: image `postgres:16`\n  - Volumes: data:/var/lib/postgresql/data\n- `web`, built from ./web (stage runtime)\n  - Ports: 8080:80, 9000\n  - Depends on: db\n"
+        );
+        assert!(format_compose("not: [valid").is_none());
+    }
+}
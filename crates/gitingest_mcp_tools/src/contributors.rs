@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::common::{repo_argument, required_arguments, supported_providers, usize_argument};
+
+const DEFAULT_LIMIT: usize = 20;
+
+pub struct ListContributors {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ListContributors {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ListContributors {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let limit = usize_argument(&args, "limit").unwrap_or(DEFAULT_LIMIT);
+
+        let contributors = provider
+            .list_contributors(repo_path, limit)
+            .await
+            .map_err(|e| anyhow!("Error listing contributors: {}", e))?;
+
+        if contributors.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("No contributors found for {}", repo_path),
+            }]);
+        }
+
+        let mut formatted_output = format!(
+            "Top {} contributors of {}\n\n",
+            contributors.len(),
+            repo_path
+        );
+        for (index, contributor) in contributors.iter().enumerate() {
+            let mut line = format!("{}. {}", index + 1, contributor.login);
+            if let Some(email) = &contributor.email {
+                line.push_str(&format!(" <{}>", email));
+            }
+            line.push_str(&format!(": {} commits", contributor.contributions));
+            if contributor.bot {
+                line.push_str(" (bot)");
+            }
+            formatted_output.push_str(&line);
+            formatted_output.push('\n');
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output.trim_end().to_string(),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "list_contributors".into(),
+            description: Some(format!(
+                "List the top contributors of a repository with their number of commits to the default branch, most active first. GitHub names accounts, GitLab commit authors with their email. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": format!("Optional number of contributors listed, at most 100. Default: {}", DEFAULT_LIMIT)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{GitProvider, RepositoryInfo};
+use regex::Regex;
+use serde_json::{Value, json};
+
+use crate::{
+    common::{
+        bool_argument, find_provider, git_ref_argument, repo_argument, required_arguments,
+        supported_providers, usize_argument,
+    },
+    links::parse_link,
+};
+
+const DEFAULT_PATH: &str = "README.md";
+pub(crate) const DEFAULT_MAX_REPOSITORIES: usize = 50;
+
+/// A repository linked from a curated list
+struct ListedRepository {
+    provider: &'static str,
+    repo_path: String,
+    /// Heading the link appears under, empty before the first one
+    section: String,
+    title: String,
+    description: Option<String>,
+}
+
+/// Extracts the repositories linked from a markdown list, in order and without duplicates,
+/// returning them with the number of other web links skipped
+fn extract_repositories(markdown: &str) -> Result<(Vec<ListedRepository>, usize)> {
+    let link = Regex::new(r"\[([^\]]*)\]\((https?://[^)\s]+)\)")?;
+
+    let mut section = String::new();
+    let mut seen = BTreeSet::new();
+    let mut repositories = Vec::new();
+    let mut skipped = 0;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('#') {
+            section = trimmed.trim_start_matches('#').trim().to_string();
+            continue;
+        }
+
+        for captures in link.captures_iter(line) {
+            let Ok(parsed) = parse_link(&captures[2]) else {
+                skipped += 1;
+                continue;
+            };
+            if !seen.insert((parsed.provider, parsed.repo_path.clone())) {
+                continue;
+            }
+
+            // Entries read "[name](url) - what it does"
+            let end = captures.get(0).map_or(line.len(), |m| m.end());
+            let description = line[end..]
+                .trim_start_matches(|c: char| {
+                    c.is_whitespace() || matches!(c, '-' | '–' | '—' | ':')
+                })
+                .trim();
+
+            repositories.push(ListedRepository {
+                provider: parsed.provider,
+                repo_path: parsed.repo_path,
+                section: section.clone(),
+                title: captures[1].trim().to_string(),
+                description: (!description.is_empty()).then(|| description.to_string()),
+            });
+        }
+    }
+
+    Ok((repositories, skipped))
+}
+
+/// One line summing up the metadata of a repository
+fn metadata_line(info: &RepositoryInfo) -> String {
+    let mut parts = vec![format!("⭐️{}", info.stars)];
+    if let Some(language) = &info.language {
+        parts.push(language.clone());
+    }
+    if let Some(license) = &info.license {
+        parts.push(license.clone());
+    }
+    if let Some(last_activity_at) = &info.last_activity_at {
+        parts.push(format!(
+            "last activity {}",
+            last_activity_at.get(..10).unwrap_or(last_activity_at)
+        ));
+    }
+    if info.archived {
+        parts.push("archived".to_string());
+    }
+
+    parts.join(" · ")
+}
+
+pub struct ExpandCuratedList {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ExpandCuratedList {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ExpandCuratedList {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .unwrap_or(DEFAULT_PATH);
+        let fetch_metadata = bool_argument(&args, "fetch_metadata").unwrap_or(false);
+        let max_repositories =
+            usize_argument(&args, "max_repositories").unwrap_or(DEFAULT_MAX_REPOSITORIES);
+
+        let markdown = provider
+            .get_file_content(repo_path, path, git_ref)
+            .await
+            .map_err(|e| anyhow!("Error reading file content: {}", e))?;
+
+        let (mut repositories, skipped) = extract_repositories(&markdown)?;
+        // The list usually links back to itself
+        repositories.retain(|repository| {
+            repository.provider != provider.name() || repository.repo_path != repo_path
+        });
+
+        if repositories.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("No repository links found in {} of {}", path, repo_path),
+            }]);
+        }
+
+        let total = repositories.len();
+        let sections = repositories
+            .iter()
+            .map(|repository| repository.section.as_str())
+            .collect::<BTreeSet<_>>()
+            .len();
+        repositories.truncate(max_repositories);
+
+        // Links to hosts without a provider are listed, only their metadata is missing
+        let metadata: Vec<Option<Result<RepositoryInfo>>> = if fetch_metadata {
+            join_all(repositories.iter().map(|repository| async move {
+                match find_provider(&self.providers, repository.provider) {
+                    Ok(provider) => provider.get_repository_info(&repository.repo_path).await,
+                    Err(e) => Err(e),
+                }
+            }))
+            .await
+            .into_iter()
+            .map(Some)
+            .collect()
+        } else {
+            repositories.iter().map(|_| None).collect()
+        };
+
+        let mut formatted_output = format!(
+            "# Repositories listed in {} of {}\n\n{} repositories in {} sections",
+            path, repo_path, total, sections
+        );
+        if skipped > 0 {
+            formatted_output.push_str(&format!(", {} other links skipped", skipped));
+        }
+        formatted_output.push('\n');
+
+        let mut current_section = None;
+        for (repository, info) in repositories.iter().zip(metadata) {
+            if current_section != Some(repository.section.as_str()) {
+                current_section = Some(repository.section.as_str());
+                if !repository.section.is_empty() {
+                    formatted_output.push_str(&format!("\n## {}\n", repository.section));
+                }
+                formatted_output.push('\n');
+            }
+
+            formatted_output.push_str(&format!(
+                "- {}:{} ({})",
+                repository.provider, repository.repo_path, repository.title
+            ));
+            if let Some(description) = &repository.description {
+                formatted_output.push_str(&format!(": {}", description));
+            }
+            formatted_output.push('\n');
+
+            match info {
+                Some(Ok(info)) => {
+                    formatted_output.push_str(&format!("  {}\n", metadata_line(&info)));
+                }
+                Some(Err(e)) => {
+                    formatted_output.push_str(&format!("  (metadata unavailable: {})\n", e));
+                }
+                None => {}
+            }
+        }
+
+        if total > repositories.len() {
+            formatted_output.push_str(&format!(
+                "\n({} more repositories not listed, raise max_repositories to see them)\n",
+                total - repositories.len()
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "expand_curated_list".into(),
+            description: Some(format!(
+                "Extract the repositories linked from an 'awesome-*' style markdown list, grouped by section with their descriptions, optionally fetching their stars, language, license and activity. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier of the list in format 'gitprovider:username/reponame' (e.g., 'github:rust-unofficial/awesome-rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": format!("Optional path of the markdown list. Default: {}", DEFAULT_PATH)
+                    },
+                    "fetch_metadata": {
+                        "type": "boolean",
+                        "description": "Optional flag to fetch the metadata of every listed repository, one request each. Default: false"
+                    },
+                    "max_repositories": {
+                        "type": "integer",
+                        "description": format!("Optional maximum number of repositories listed. Default: {}", DEFAULT_MAX_REPOSITORIES)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,607 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{ChangedFile, CompareMode, FileStatus, GitProvider, GitRef, RefComparison};
+use serde_json::{Value, json};
+
+use crate::{
+    common::{
+        bool_argument, git_ref_argument, parse_git_ref, repo_argument, required_arguments,
+        string_argument, supported_providers,
+    },
+    semantic::{StructuredFormat, render_changes, structural_changes},
+};
+
+fn status_label(status: FileStatus) -> &'static str {
+    match status {
+        FileStatus::Added => "added",
+        FileStatus::Modified => "modified",
+        FileStatus::Removed => "removed",
+        FileStatus::Renamed => "renamed",
+    }
+}
+
+/// Minimum similarity for a removed and an added file to be reported as a rename, as in git
+const RENAME_THRESHOLD: u8 = 50;
+
+/// Content lines of a whole-file patch, without the diff markers
+fn patch_content(patch: &str, marker: char) -> Vec<&str> {
+    patch
+        .lines()
+        .filter(|line| !line.starts_with("@@"))
+        .filter_map(|line| line.strip_prefix(marker))
+        .collect()
+}
+
+/// Share of lines two files have in common, as a percentage of their combined size
+fn similarity(removed: &[&str], added: &[&str]) -> u8 {
+    if removed.is_empty() && added.is_empty() {
+        return 100;
+    }
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for line in removed {
+        *counts.entry(line).or_default() += 1;
+    }
+    let common = added
+        .iter()
+        .filter(|line| match counts.get_mut(*line) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        })
+        .count();
+
+    (common * 200 / (removed.len() + added.len())) as u8
+}
+
+/// Pairs removed and added files with similar contents into renames, so a moved file is not
+/// reported as a full deletion and a full addition
+pub(crate) fn detect_renames(files: Vec<ChangedFile>) -> Vec<ChangedFile> {
+    let removed: Vec<usize> = (0..files.len())
+        .filter(|&i| files[i].status == FileStatus::Removed && files[i].patch.is_some())
+        .collect();
+    let added: Vec<usize> = (0..files.len())
+        .filter(|&i| files[i].status == FileStatus::Added && files[i].patch.is_some())
+        .collect();
+
+    let mut candidates: Vec<(u8, usize, usize)> = Vec::new();
+    for &r in &removed {
+        let old = patch_content(files[r].patch.as_deref().unwrap_or_default(), '-');
+        for &a in &added {
+            let new = patch_content(files[a].patch.as_deref().unwrap_or_default(), '+');
+            let score = similarity(&old, &new);
+            if score >= RENAME_THRESHOLD {
+                candidates.push((score, r, a));
+            }
+        }
+    }
+    // Best matches first, each file taking part in at most one rename
+    candidates.sort_by(|x, y| y.0.cmp(&x.0).then_with(|| (x.1, x.2).cmp(&(y.1, y.2))));
+
+    let mut paired: HashMap<usize, (usize, u8)> = HashMap::new();
+    let mut used_removed: Vec<usize> = Vec::new();
+    for (score, r, a) in candidates {
+        if used_removed.contains(&r) || paired.contains_key(&a) {
+            continue;
+        }
+        used_removed.push(r);
+        paired.insert(a, (r, score));
+    }
+
+    let previous: HashMap<usize, (String, u8)> = paired
+        .into_iter()
+        .map(|(a, (r, score))| (a, (files[r].path.clone(), score)))
+        .collect();
+
+    files
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| !used_removed.contains(index))
+        .map(|(index, mut file)| {
+            if let Some((previous_path, score)) = previous.get(&index) {
+                file.status = FileStatus::Renamed;
+                file.previous_path = Some(previous_path.clone());
+                file.similarity = Some(*score);
+            }
+            file
+        })
+        .collect()
+}
+
+/// Whether a changed file is `path` itself or below it, under its current or previous name
+pub(crate) fn touches(file: &ChangedFile, path: &str) -> bool {
+    let matches = |candidate: &str| {
+        candidate == path
+            || candidate
+                .strip_prefix(path)
+                .is_some_and(|rest| rest.starts_with('/'))
+    };
+
+    matches(&file.path) || file.previous_path.as_deref().is_some_and(matches)
+}
+
+/// How much of each file diff is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiffRender {
+    /// The complete unified patch, optionally with word-level markers on replaced lines
+    Unified { intraline: bool },
+    /// One line per hunk header, with the hunk bodies collapsed
+    Summary,
+    /// Only the per-file and total line counts
+    Stats,
+}
+
+pub(crate) fn diff_render_argument(args: &Value) -> Result<DiffRender> {
+    match args.get("render").and_then(|v| v.as_str()) {
+        None | Some("unified") => Ok(DiffRender::Unified {
+            intraline: bool_argument(args, "intraline").unwrap_or(false),
+        }),
+        Some("summary") => Ok(DiffRender::Summary),
+        Some("stats") => Ok(DiffRender::Stats),
+        Some(other) => Err(anyhow!(
+            "Invalid render '{}': expected 'unified', 'summary' or 'stats'",
+            other
+        )),
+    }
+}
+
+/// Collapses every hunk of a patch into its header and the number of lines it spans
+fn collapse_hunks(patch: &str) -> String {
+    let mut hunks: Vec<(String, usize)> = Vec::new();
+    for line in patch.lines() {
+        if line.starts_with("@@") {
+            hunks.push((line.to_string(), 0));
+        } else if let Some((_, lines)) = hunks.last_mut() {
+            *lines += 1;
+        }
+    }
+
+    hunks
+        .into_iter()
+        .map(|(header, lines)| format!("{} ({} lines)\n", header, lines))
+        .collect()
+}
+
+/// Lines longer than this many tokens are left without intraline markers
+const MAX_INTRALINE_TOKENS: usize = 512;
+
+/// Splits a line into words and single punctuation or whitespace characters
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (index, c) in line.char_indices() {
+        if c.is_alphanumeric() || c == '_' {
+            start.get_or_insert(index);
+            continue;
+        }
+        if let Some(word_start) = start.take() {
+            tokens.push(&line[word_start..index]);
+        }
+        tokens.push(&line[index..index + c.len_utf8()]);
+    }
+    if let Some(word_start) = start {
+        tokens.push(&line[word_start..]);
+    }
+
+    tokens
+}
+
+/// Merges a removed and an added line into one, marking changed words as `[-old-]{+new+}`
+fn intraline(old: &str, new: &str) -> Option<String> {
+    let old = tokenize(old);
+    let new = tokenize(new);
+    if old.len() > MAX_INTRALINE_TOKENS || new.len() > MAX_INTRALINE_TOKENS {
+        return None;
+    }
+
+    // Longest common subsequence of tokens
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut merged = String::new();
+    let (mut removed, mut added) = (String::new(), String::new());
+    let flush = |merged: &mut String, removed: &mut String, added: &mut String| {
+        if !removed.is_empty() {
+            merged.push_str(&format!("[-{}-]", removed));
+            removed.clear();
+        }
+        if !added.is_empty() {
+            merged.push_str(&format!("{{+{}+}}", added));
+            added.clear();
+        }
+    };
+
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            flush(&mut merged, &mut removed, &mut added);
+            merged.push_str(old[i]);
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            removed.push_str(old[i]);
+            i += 1;
+        } else {
+            added.push_str(new[j]);
+            j += 1;
+        }
+    }
+    flush(&mut merged, &mut removed, &mut added);
+
+    Some(merged)
+}
+
+/// Rewrites a patch so that runs of removed lines directly replaced by as many added lines
+/// become single '~' lines with word-level markers
+fn intraline_patch(patch: &str) -> String {
+    let lines: Vec<&str> = patch.lines().collect();
+    let mut output = Vec::with_capacity(lines.len());
+    let mut index = 0;
+
+    while index < lines.len() {
+        let is_removed = |line: &str| line.starts_with('-') && !line.starts_with("---");
+        let is_added = |line: &str| line.starts_with('+') && !line.starts_with("+++");
+
+        let removed = lines[index..].iter().take_while(|l| is_removed(l)).count();
+        let added = lines[index + removed..]
+            .iter()
+            .take_while(|l| is_added(l))
+            .count();
+
+        if removed == 0 || removed != added {
+            let run = (removed + added).max(1);
+            output.extend(lines[index..index + run].iter().map(|l| l.to_string()));
+            index += run;
+            continue;
+        }
+
+        for offset in 0..removed {
+            let old = lines[index + offset];
+            let new = lines[index + removed + offset];
+            match intraline(&old[1..], &new[1..]) {
+                Some(merged) => output.push(format!("~{}", merged)),
+                None => {
+                    output.push(old.to_string());
+                    output.push(new.to_string());
+                }
+            }
+        }
+        index += removed * 2;
+    }
+
+    output.join("\n")
+}
+
+/// Renders one changed file as a markdown section with its unified diff
+pub(crate) fn render_file_diff(
+    file: &ChangedFile,
+    render: DiffRender,
+    semantic: Option<&str>,
+) -> String {
+    let path = match &file.previous_path {
+        Some(previous) if previous != &file.path => format!("{} -> {}", previous, file.path),
+        _ => file.path.clone(),
+    };
+    let status = match file.similarity {
+        Some(similarity) => format!("{}, {}% similar", status_label(file.status), similarity),
+        None => status_label(file.status).to_string(),
+    };
+
+    if render == DiffRender::Stats {
+        return format!(
+            "{} ({}, +{} -{})\n",
+            path, status, file.additions, file.deletions
+        );
+    }
+
+    let mut section = format!(
+        "## {} ({}, +{} -{})\n",
+        path, status, file.additions, file.deletions
+    );
+    if let Some(report) = semantic {
+        section.push_str(&format!("\n```\n{}```\n", report));
+        return section;
+    }
+    match (&file.patch, render) {
+        (Some(patch), DiffRender::Summary) => {
+            section.push_str(&format!("\n```\n{}```\n", collapse_hunks(patch)))
+        }
+        (Some(patch), DiffRender::Unified { intraline: true }) => section.push_str(&format!(
+            "\n```diff\n{}\n```\n",
+            intraline_patch(patch.trim_end())
+        )),
+        (Some(patch), _) => section.push_str(&format!("\n```diff\n{}\n```\n", patch.trim_end())),
+        (None, _) => section.push_str("\n(no textual diff available)\n"),
+    }
+
+    section
+}
+
+/// Renders a comparison as a summary header followed by every file diff
+pub(crate) fn render_diff(
+    comparison: &RefComparison,
+    mode: CompareMode,
+    render: DiffRender,
+    semantic: &HashMap<String, String>,
+) -> String {
+    let (additions, deletions) = comparison.files.iter().fold((0, 0), |(a, d), file| {
+        (a + file.additions, d + file.deletions)
+    });
+
+    let mut output = format!(
+        "Diff {}{}{} ({})\n{} commits, {} files changed, +{} -{}\n",
+        comparison.base,
+        match mode {
+            CompareMode::MergeBase => "...",
+            CompareMode::Direct => "..",
+        },
+        comparison.head,
+        match mode {
+            CompareMode::MergeBase => "against the merge base",
+            CompareMode::Direct => "direct comparison",
+        },
+        comparison.commits.len(),
+        comparison.files.len(),
+        additions,
+        deletions
+    );
+
+    if render == DiffRender::Stats && !comparison.files.is_empty() {
+        output.push('\n');
+    }
+    for file in &comparison.files {
+        if render != DiffRender::Stats {
+            output.push('\n');
+        }
+        output.push_str(&render_file_diff(
+            file,
+            render,
+            semantic.get(&file.path).map(String::as_str),
+        ));
+    }
+
+    output
+}
+
+pub(crate) fn compare_mode_argument(args: &Value) -> Result<CompareMode> {
+    match args.get("mode").and_then(|v| v.as_str()) {
+        None | Some("merge_base") => Ok(CompareMode::MergeBase),
+        Some("direct") => Ok(CompareMode::Direct),
+        Some(other) => Err(anyhow!(
+            "Invalid mode '{}': expected 'merge_base' or 'direct'",
+            other
+        )),
+    }
+}
+
+/// Compares the structured files changed on both sides key by key, leaving out the files
+/// that cannot be fetched or parsed so they fall back to their textual diff
+async fn semantic_reports(
+    provider: &dyn GitProvider,
+    repo_path: &str,
+    comparison: &RefComparison,
+) -> HashMap<String, String> {
+    let old_ref = match &comparison.merge_base {
+        Some(sha) => GitRef::Commit(sha.clone()),
+        None => GitRef::Branch(comparison.base.clone()),
+    };
+    let new_ref = GitRef::Branch(comparison.head.clone());
+
+    let reports = join_all(
+        comparison
+            .files
+            .iter()
+            .filter(|file| matches!(file.status, FileStatus::Modified | FileStatus::Renamed))
+            .filter_map(|file| Some((file, StructuredFormat::from_path(&file.path)?)))
+            .map(|(file, format)| {
+                let old_path = file.previous_path.as_deref().unwrap_or(&file.path);
+                let (old_ref, new_ref) = (old_ref.clone(), new_ref.clone());
+                async move {
+                    let (old, new) = futures::try_join!(
+                        provider.get_file_content(repo_path, old_path, Some(old_ref)),
+                        provider.get_file_content(repo_path, &file.path, Some(new_ref))
+                    )?;
+                    let changes = structural_changes(&format.parse(&old)?, &format.parse(&new)?);
+
+                    Ok::<_, anyhow::Error>((file.path.clone(), render_changes(&changes)))
+                }
+            }),
+    )
+    .await;
+
+    reports.into_iter().flatten().collect()
+}
+
+pub struct RepositoryDiff {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositoryDiff {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositoryDiff {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let base = parse_git_ref(string_argument(&args, "base")?);
+        let head = git_ref_argument(&args, "head");
+        let mode = compare_mode_argument(&args)?;
+        let render = diff_render_argument(&args)?;
+        let renames = bool_argument(&args, "detect_renames").unwrap_or(true);
+        let semantic = bool_argument(&args, "semantic").unwrap_or(false);
+
+        let mut comparison = provider
+            .compare_refs(repo_path, Some(base), head, mode)
+            .await
+            .map_err(|e| anyhow!("Error comparing references: {}", e))?;
+
+        if renames {
+            comparison.files = detect_renames(comparison.files);
+        }
+
+        let reports = if semantic && render != DiffRender::Stats {
+            semantic_reports(provider, repo_path, &comparison).await
+        } else {
+            HashMap::new()
+        };
+
+        Ok(vec![ToolContent::Text {
+            text: render_diff(&comparison, mode, render, &reports),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_diff".into(),
+            description: Some(format!(
+                "Show the file diffs between two git references. By default head is compared against its merge base with base (triple-dot), matching what a pull request shows; 'direct' compares the two trees as they are. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "base": {
+                        "type": "string",
+                        "description": "Base git reference: branch name, 'tag:name', or 'commit:sha'"
+                    },
+                    "head": {
+                        "type": "string",
+                        "description": "Optional head git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["merge_base", "direct"],
+                        "description": "Optional comparison mode: against the merge base of both references, or directly against base. Default: merge_base"
+                    },
+                    "render": {
+                        "type": "string",
+                        "enum": ["unified", "summary", "stats"],
+                        "description": "Optional output detail: full unified patches, per-file hunk headers with the hunks collapsed, or only files changed with insertions and deletions. Default: unified"
+                    },
+                    "intraline": {
+                        "type": "boolean",
+                        "description": "Optional word-level markers in unified output: a line replaced by another is shown once as '~' with '[-removed-]' and '{+added+}' spans. Default: false"
+                    },
+                    "semantic": {
+                        "type": "boolean",
+                        "description": "Optional key-level report for changed JSON, YAML and TOML files instead of their textual hunks. Default: false"
+                    },
+                    "detect_renames": {
+                        "type": "boolean",
+                        "description": "Optional pairing of removed and added files with similar contents into renames. Default: true"
+                    }
+                },
+                "required": ["repo", "base"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changed(path: &str, status: FileStatus, patch: &str) -> ChangedFile {
+        ChangedFile {
+            path: path.to_string(),
+            previous_path: None,
+            similarity: None,
+            status,
+            additions: 0,
+            deletions: 0,
+            patch: Some(patch.to_string()),
+        }
+    }
+
+    #[test]
+    fn words_and_punctuation_are_separate_tokens() {
+        assert_eq!(
+            tokenize("let total_2 = a+b;"),
+            ["let", " ", "total_2", " ", "=", " ", "a", "+", "b", ";"]
+        );
+        assert_eq!(tokenize("héllo wörld"), ["héllo", " ", "wörld"]);
+        assert!(tokenize("").is_empty());
+    }
+
+    #[test]
+    fn changed_words_are_marked() {
+        assert_eq!(
+            intraline("let x = 1;", "let y = 1;").unwrap(),
+            "let [-x-]{+y+} = 1;"
+        );
+        assert_eq!(intraline("a b", "a b c").unwrap(), "a b{+ c+}");
+        assert!(intraline(&"x ".repeat(MAX_INTRALINE_TOKENS), "x").is_none());
+    }
+
+    #[test]
+    fn only_replaced_runs_are_merged() {
+        let patch = "@@ -1,4 +1,4 @@\n-let x = 1;\n+let y = 1;\n context\n-gone\n-also gone\n+kept";
+        assert_eq!(
+            intraline_patch(patch),
+            "@@ -1,4 +1,4 @@\n~let [-x-]{+y+} = 1;\n context\n-gone\n-also gone\n+kept"
+        );
+    }
+
+    #[test]
+    fn hunks_collapse_to_their_headers() {
+        let patch = "@@ -1,2 +1,2 @@ fn main()\n-a\n+b\n c\n@@ -10 +10 @@\n-x\n+y";
+        assert_eq!(
+            collapse_hunks(patch),
+            "@@ -1,2 +1,2 @@ fn main() (3 lines)\n@@ -10 +10 @@ (2 lines)\n"
+        );
+    }
+
+    #[test]
+    fn similar_removed_and_added_files_are_renames() {
+        let files = vec![
+            changed("old.rs", FileStatus::Removed, "@@ -1,3 +0,0 @@\n-a\n-b\n-c"),
+            changed("new.rs", FileStatus::Added, "@@ -0,0 +1,3 @@\n+a\n+b\n+d"),
+            changed("other.rs", FileStatus::Added, "@@ -0,0 +1,2 @@\n+x\n+y"),
+        ];
+
+        let files = detect_renames(files);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "new.rs");
+        assert_eq!(files[0].status, FileStatus::Renamed);
+        assert_eq!(files[0].previous_path.as_deref(), Some("old.rs"));
+        assert_eq!(files[0].similarity, Some(66));
+        assert_eq!(files[1].status, FileStatus::Added);
+    }
+
+    #[test]
+    fn paths_below_a_directory_are_touched() {
+        let mut file = changed("src/lib.rs", FileStatus::Renamed, "");
+        file.previous_path = Some("lib/old.rs".to_string());
+
+        assert!(touches(&file, "src"));
+        assert!(touches(&file, "lib"));
+        assert!(touches(&file, "src/lib.rs"));
+        assert!(!touches(&file, "sr"));
+    }
+}
@@ -0,0 +1,630 @@
+use std::{cmp::Ordering, collections::BTreeSet, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::{StreamExt, future::join_all, stream};
+use git_provider::{
+    CommitInfo, GitProvider, GitRef, RepoItem, RepoItemType, RepoNode, TreeOptions,
+    create_tree_structure, is_readme,
+};
+use serde_json::{Value, json};
+
+use crate::{
+    common::{
+        ANONYMOUS_MAX_FILES, FETCH_CONCURRENCY, anonymous_limit_note, bool_argument,
+        check_repository_size, git_ref_argument, language_for_path, max_files_argument,
+        preset_names, repo_argument, required_arguments, supported_providers,
+        tree_options_argument, usize_argument, warn_skipped, warn_truncated,
+    },
+    i18n::Message,
+    key_files::{CONFIG_FILES, ENTRY_POINTS},
+    licenses::{Attribution, is_license_file},
+    manifest::{Change, compare_manifests, render_manifest},
+};
+
+pub(crate) const DEFAULT_MAX_FILES: usize = 100;
+const DEFAULT_SAMPLE_LINES: usize = 20;
+
+/// How much of each file goes into the digest
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DigestMode {
+    /// Complete file contents
+    Full,
+    /// Only the first lines of each file, for a quick overview
+    Sample(usize),
+}
+
+/// Order in which files appear in the digest
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FileOrder {
+    Alphabetical,
+    /// At every level, the contents of subdirectories come before the files next to them
+    DirectoryFirst,
+    /// Smallest files first, files of unknown size last
+    SizeAscending,
+    /// READMEs, entry points and manifests first, the rest alphabetically
+    Priority,
+}
+
+impl FileOrder {
+    fn parse(order: &str) -> Result<Self> {
+        match order {
+            "alphabetical" => Ok(Self::Alphabetical),
+            "directory_first" => Ok(Self::DirectoryFirst),
+            "size" => Ok(Self::SizeAscending),
+            "priority" => Ok(Self::Priority),
+            other => Err(anyhow!(
+                "Invalid order '{}': expected 'alphabetical', 'directory_first', 'size' or 'priority'",
+                other
+            )),
+        }
+    }
+
+    fn sort(self, files: &mut [RepoItem]) {
+        match self {
+            Self::Alphabetical => files.sort_by(|a, b| a.path.cmp(&b.path)),
+            Self::DirectoryFirst => files.sort_by(|a, b| directory_first(&a.path, &b.path)),
+            Self::SizeAscending => files.sort_by(|a, b| {
+                a.size
+                    .unwrap_or(u64::MAX)
+                    .cmp(&b.size.unwrap_or(u64::MAX))
+                    .then_with(|| a.path.cmp(&b.path))
+            }),
+            Self::Priority => files.sort_by(|a, b| {
+                priority(a)
+                    .cmp(&priority(b))
+                    .then_with(|| {
+                        a.path
+                            .matches('/')
+                            .count()
+                            .cmp(&b.path.matches('/').count())
+                    })
+                    .then_with(|| a.path.cmp(&b.path))
+            }),
+        }
+    }
+}
+
+fn directory_first(a: &str, b: &str) -> Ordering {
+    let mut a_parts = a.split('/').peekable();
+    let mut b_parts = b.split('/').peekable();
+
+    while let (Some(a_part), Some(b_part)) = (a_parts.next(), b_parts.next()) {
+        let a_is_dir = a_parts.peek().is_some();
+        let b_is_dir = b_parts.peek().is_some();
+
+        let ordering = b_is_dir.cmp(&a_is_dir).then_with(|| a_part.cmp(b_part));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+fn priority(file: &RepoItem) -> u8 {
+    let name = file.name.as_str();
+
+    if is_readme(name) {
+        0
+    } else if ENTRY_POINTS.contains(&name) {
+        1
+    } else if CONFIG_FILES.contains(&name) {
+        2
+    } else {
+        3
+    }
+}
+
+/// Renders the metadata block preceding a file, one "key: value" per line between "---"
+/// markers, leaving out what is unknown
+fn front_matter(
+    file: &RepoItem,
+    content: Option<&str>,
+    last_commit: Option<&CommitInfo>,
+) -> String {
+    let mut block = format!("---\npath: {}\n", file.path);
+
+    if let Some(size) = file.size.or(content.map(|c| c.len() as u64)) {
+        block.push_str(&format!("size: {}\n", size));
+    }
+    if let Some(language) = language_for_path(&file.path) {
+        block.push_str(&format!("language: {}\n", language));
+    }
+    if let Some(sha) = &file.sha {
+        block.push_str(&format!("sha: {}\n", sha));
+    }
+    if let Some(commit) = last_commit {
+        block.push_str(&format!("last_modified: {}\n", commit.date));
+        block.push_str(&format!("last_commit: {}\n", commit.sha));
+    }
+    block.push_str("---\n");
+
+    block
+}
+
+/// Keeps the first `lines` lines of a file, noting how many were left out
+fn sample_content(content: &str, lines: usize) -> String {
+    let total = content.lines().count();
+    let mut sample: String = content.lines().take(lines).collect::<Vec<_>>().join("\n");
+
+    if total > lines {
+        sample.push_str(&format!("\n... ({} more lines)", total - lines));
+    }
+
+    sample
+}
+
+/// A rough token count, about four characters per token for code and English prose
+fn estimate_tokens(content: &str) -> usize {
+    content.chars().count().div_ceil(4)
+}
+
+/// Adds a file to the tree below `node`, creating the directories on its path
+fn insert_path(node: &mut RepoNode, path: &str) {
+    node.file_count += 1;
+
+    let Some((directory, rest)) = path.split_once('/') else {
+        node.children.push(RepoNode {
+            name: path.to_string(),
+            node_type: RepoItemType::File,
+            size: 0,
+            children: vec![],
+            file_count: 1,
+            dir_count: 0,
+            description: None,
+        });
+        return;
+    };
+
+    let name = format!("{}/", directory);
+    let index = match node.children.iter().position(|child| child.name == name) {
+        Some(index) => index,
+        None => {
+            node.dir_count += 1;
+            node.children.push(RepoNode {
+                name,
+                node_type: RepoItemType::Directory,
+                size: 0,
+                children: vec![],
+                file_count: 0,
+                dir_count: 0,
+                description: None,
+            });
+            node.children.len() - 1
+        }
+    };
+    insert_path(&mut node.children[index], rest);
+}
+
+/// Directories first, then files, both alphabetically, at every level
+fn sort_tree(node: &mut RepoNode) {
+    node.children
+        .sort_by(|a, b| match (a.node_type, b.node_type) {
+            (RepoItemType::Directory, RepoItemType::File) => Ordering::Less,
+            (RepoItemType::File, RepoItemType::Directory) => Ordering::Greater,
+            _ => a.name.cmp(&b.name),
+        });
+    node.children.iter_mut().for_each(sort_tree);
+}
+
+/// The gitingest summary header: what was ingested, its estimated size and the tree of the
+/// included files
+fn summary_header(repo_path: &str, files: &[&RepoItem], tokens: usize) -> String {
+    let name = repo_path
+        .trim_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(repo_path);
+    let mut root = RepoNode {
+        name: format!("{}/", name),
+        node_type: RepoItemType::Directory,
+        size: 0,
+        children: vec![],
+        file_count: 0,
+        dir_count: 0,
+        description: None,
+    };
+    for file in files {
+        insert_path(&mut root, &file.path);
+    }
+    sort_tree(&mut root);
+
+    format!(
+        "Repository: {}\nFiles analyzed: {}\nEstimated tokens: {}\n\nDirectory structure:\n{}\n",
+        repo_path,
+        files.len(),
+        tokens,
+        create_tree_structure(&root, "", true)
+    )
+}
+
+/// Collects the licenses covering the included files: the license files of the directories
+/// holding them, even those the patterns leave out, and the license headers of the files
+async fn collect_attribution(
+    provider: &dyn GitProvider,
+    repo_path: &str,
+    git_ref: Option<GitRef>,
+    files: &[RepoItem],
+    contents: &[Result<String>],
+) -> Result<Attribution> {
+    let listed = provider
+        .list_files(repo_path, git_ref.clone(), &TreeOptions::default())
+        .await
+        .map_err(|e| anyhow!("Error listing license files: {}", e))?;
+    let license_files: Vec<&RepoItem> = listed
+        .iter()
+        .filter(|file| is_license_file(&file.path))
+        .filter(|license| {
+            let directory = license.path.rsplit_once('/').map(|(dir, _)| dir);
+            files.iter().any(|file| match directory {
+                Some(directory) => file
+                    .path
+                    .strip_prefix(directory)
+                    .is_some_and(|rest| rest.starts_with('/')),
+                None => true,
+            })
+        })
+        .collect();
+
+    let texts = join_all(license_files.iter().map(|license| {
+        let git_ref = git_ref.clone();
+        async move {
+            provider
+                .get_file_content(repo_path, &license.path, git_ref)
+                .await
+        }
+    }))
+    .await;
+
+    let mut attribution = Attribution::default();
+    for (license, text) in license_files.iter().zip(texts) {
+        match text {
+            Ok(text) => attribution.add_license_file(&license.path, &text),
+            Err(e) => warn_skipped(&license.path, &e),
+        }
+    }
+    for content in contents
+        .iter()
+        .filter_map(|content| content.as_deref().ok())
+    {
+        attribution.add_file(content);
+    }
+
+    Ok(attribution)
+}
+
+pub struct RepositoryDigest {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositoryDigest {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositoryDigest {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let max_files = max_files_argument(provider, &args, DEFAULT_MAX_FILES);
+
+        let mode = match args.get("mode").and_then(|v| v.as_str()).unwrap_or("full") {
+            "full" => DigestMode::Full,
+            "sample" => DigestMode::Sample(
+                usize_argument(&args, "sample_lines").unwrap_or(DEFAULT_SAMPLE_LINES),
+            ),
+            other => {
+                return Err(anyhow!(
+                    "Invalid mode '{}': expected 'full' or 'sample'",
+                    other
+                ));
+            }
+        };
+
+        let order = FileOrder::parse(
+            args.get("order")
+                .and_then(|v| v.as_str())
+                .unwrap_or("alphabetical"),
+        )?;
+
+        let with_front_matter = bool_argument(&args, "front_matter").unwrap_or(true);
+        let last_modified = bool_argument(&args, "last_modified").unwrap_or(false);
+        let previous_manifest = args
+            .get("previous_manifest")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty());
+        let include_manifest =
+            bool_argument(&args, "include_manifest").unwrap_or(previous_manifest.is_some());
+        let with_summary = bool_argument(&args, "summary").unwrap_or(true);
+        let with_attribution = bool_argument(&args, "attribution").unwrap_or(false);
+        let max_tokens = usize_argument(&args, "max_tokens");
+
+        let options = tree_options_argument(&args)?;
+
+        let force = bool_argument(&args, "force").unwrap_or(false);
+        let size_warning = check_repository_size(provider, repo_path, force).await?;
+
+        let listed = provider
+            .list_files(repo_path, git_ref.clone(), &options)
+            .await
+            .map_err(|e| anyhow!("Error listing repository files: {}", e))?;
+        let manifest = render_manifest(&listed);
+        let mut files = listed.clone();
+
+        // Only files added or changed since the previous manifest, deletions are listed apart
+        let mut deleted = Vec::new();
+        if let Some(previous) = previous_manifest {
+            let changes = compare_manifests(previous, &manifest);
+            let updated: BTreeSet<&str> = changes
+                .iter()
+                .filter(|(_, change)| *change != Change::Removed)
+                .map(|(path, _)| *path)
+                .collect();
+            deleted = changes
+                .iter()
+                .filter(|(_, change)| *change == Change::Removed)
+                .map(|(path, _)| path.to_string())
+                .collect();
+            files.retain(|file| updated.contains(file.path.as_str()));
+        }
+
+        let total_files = files.len();
+        order.sort(&mut files);
+        let mut left_out: BTreeSet<String> = files
+            .split_off(max_files.min(files.len()))
+            .into_iter()
+            .map(|file| file.path)
+            .collect();
+
+        // Fetched in order and a few at a time, so files past the token budget are never
+        // requested, beyond the ones already in flight
+        let mut contents: Vec<Result<String>> = Vec::with_capacity(files.len());
+        let mut over_budget = false;
+        {
+            // Owned paths, so the stream stays `Send` without borrowing `files`
+            let paths: Vec<String> = files.iter().map(|file| file.path.clone()).collect();
+            let mut fetched = stream::iter(paths)
+                .map(|path| {
+                    let git_ref = git_ref.clone();
+                    async move {
+                        provider
+                            .get_file_content(repo_path, &path, git_ref)
+                            .await
+                            .map(|content| match mode {
+                                DigestMode::Full => content,
+                                DigestMode::Sample(lines) => sample_content(&content, lines),
+                            })
+                    }
+                })
+                .buffered(FETCH_CONCURRENCY);
+
+            // Files past the token budget are left out whole, keeping the order
+            let mut used = 0;
+            while let Some(content) = fetched.next().await {
+                used += content.as_deref().map_or(0, estimate_tokens);
+                if max_tokens.is_some_and(|max_tokens| used > max_tokens) {
+                    over_budget = true;
+                    break;
+                }
+                contents.push(content);
+            }
+        }
+        left_out.extend(
+            files
+                .split_off(contents.len())
+                .into_iter()
+                .map(|file| file.path),
+        );
+
+        // One extra request per file, only made when asked for
+        let last_commits = if with_front_matter && last_modified {
+            join_all(files.iter().map(|file| {
+                let git_ref = git_ref.clone();
+                async move {
+                    provider
+                        .list_commits(repo_path, git_ref, Some(&file.path), 1)
+                        .await
+                        .ok()
+                        .and_then(|commits| commits.into_iter().next())
+                }
+            }))
+            .await
+        } else {
+            vec![None; files.len()]
+        };
+
+        let mut digest = size_warning.unwrap_or_default();
+        if with_attribution {
+            let attribution =
+                collect_attribution(provider, repo_path, git_ref.clone(), &files, &contents)
+                    .await?;
+            digest.push_str(&attribution.render());
+        }
+        if with_summary {
+            let included: Vec<&RepoItem> = files
+                .iter()
+                .zip(&contents)
+                .filter(|(_, content)| content.is_ok())
+                .map(|(file, _)| file)
+                .collect();
+            let tokens = contents
+                .iter()
+                .filter_map(|content| content.as_deref().ok())
+                .map(estimate_tokens)
+                .sum();
+            digest.push_str(&summary_header(repo_path, &included, tokens));
+        }
+        if previous_manifest.is_some() {
+            digest.push_str(&format!(
+                "Changes since the previous manifest: {} added or changed files, {} deleted\n\n",
+                total_files,
+                deleted.len()
+            ));
+        }
+        for ((file, content), last_commit) in files.iter().zip(contents).zip(last_commits) {
+            digest.push_str(&format!("=== {} ===\n", file.path));
+            if with_front_matter {
+                digest.push_str(&front_matter(
+                    file,
+                    content.as_deref().ok(),
+                    last_commit.as_ref(),
+                ));
+            }
+
+            match content {
+                Ok(content) => digest.push_str(content.trim_end_matches('\n')),
+                Err(e) => {
+                    warn_skipped(&file.path, &e);
+                    digest.push_str(&format!("(skipped: {})", e));
+                    left_out.insert(file.path.clone());
+                }
+            }
+            digest.push_str("\n\n");
+        }
+
+        let anonymous_note = (!over_budget)
+            .then(|| anonymous_limit_note(provider, &args, files.len(), total_files))
+            .flatten();
+        if let Some(note) = anonymous_note {
+            digest.push_str(&format!("({})\n", note));
+        } else if total_files > files.len() {
+            let limit = if over_budget {
+                "max_tokens"
+            } else {
+                "max_files"
+            };
+            warn_truncated(files.len(), total_files, Message::Files, limit);
+            digest.push_str(&format!(
+                "({} more files not included, raise {} or narrow the patterns)\n",
+                total_files - files.len(),
+                limit
+            ));
+        }
+
+        if !deleted.is_empty() {
+            digest.push_str("Deleted files:\n");
+            for path in &deleted {
+                digest.push_str(&format!("- {}\n", path));
+            }
+            digest.push('\n');
+        }
+
+        if include_manifest {
+            // Files left out are missing from the manifest, so the next incremental digest
+            // includes them
+            let delivered: Vec<RepoItem> = listed
+                .into_iter()
+                .filter(|file| !left_out.contains(&file.path))
+                .collect();
+            digest.push_str(&format!(
+                "Manifest of {}, to pass as previous_manifest for an incremental digest:\n\n{}\n",
+                repo_path,
+                render_manifest(&delivered)
+            ));
+        }
+
+        Ok(vec![ToolContent::Text { text: digest }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_digest".into(),
+            description: Some(format!(
+                "Concatenate the files of a Git repository into a single document with '=== path ===' separators, headed by a summary and the directory tree as gitingest does. The 'sample' mode only keeps the first lines of each file for a quick overview. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["full", "sample"],
+                        "description": "Optional digest mode: complete contents or the first lines of every file. Default: full"
+                    },
+                    "sample_lines": {
+                        "type": "integer",
+                        "description": format!("Optional number of lines per file in sample mode. Default: {}", DEFAULT_SAMPLE_LINES)
+                    },
+                    "order": {
+                        "type": "string",
+                        "enum": ["alphabetical", "directory_first", "size", "priority"],
+                        "description": "Optional file order: by path, subdirectories before files at every level, smallest files first, or READMEs, entry points and manifests first. Default: alphabetical"
+                    },
+                    "front_matter": {
+                        "type": "boolean",
+                        "description": "Optional metadata block before each file with its path, size, language and blob SHA, between '---' lines. Default: true"
+                    },
+                    "last_modified": {
+                        "type": "boolean",
+                        "description": "Optional date and SHA of the last commit touching each file in its metadata block, at the cost of one request per file. Default: false"
+                    },
+                    "exclude_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to exclude"
+                    },
+                    "include_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to include"
+                    },
+                    "preset": {
+                        "type": "string",
+                        "description": format!("Optional comma-separated list of ecosystem presets adding their source and exclude patterns: {}", preset_names())
+                    },
+                    "extensions": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of file extensions to include, e.g. 'rs,toml,md'"
+                    },
+                    "case_insensitive": {
+                        "type": "boolean",
+                        "description": "Optional flag to match every pattern regardless of case. Default: false"
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "description": format!("Optional maximum number of files to include. Default: {}, or {} for providers called without a token", DEFAULT_MAX_FILES, ANONYMOUS_MAX_FILES)
+                    },
+                    "max_tokens": {
+                        "type": "integer",
+                        "description": "Optional budget of estimated tokens for the file contents, files past it are left out whole. Default: no limit"
+                    },
+                    "attribution": {
+                        "type": "boolean",
+                        "description": "Optional section before the files gathering the license files covering them with their full text, their SPDX license headers and their copyright notices, for the attribution their licenses require. Default: false"
+                    },
+                    "summary": {
+                        "type": "boolean",
+                        "description": "Optional header with the repository, the number of files, the estimated token count and the directory tree of the included files. Default: true"
+                    },
+                    "force": {
+                        "type": "boolean",
+                        "description": "Optional flag to ingest repositories above the server's size limit. Default: false"
+                    },
+                    "previous_manifest": {
+                        "type": "string",
+                        "description": "Optional manifest returned by an earlier digest or by repository_manifest, to only include files added or changed since and list the deleted ones"
+                    },
+                    "include_manifest": {
+                        "type": "boolean",
+                        "description": "Optional flag to end the digest with the manifest of the listed files, for a later incremental digest. Default: true with previous_manifest, false otherwise"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,689 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    sync::Arc,
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{GitProvider, TreeOptions};
+use serde_json::{Value, json};
+
+use crate::common::{
+    git_ref_argument, repo_argument, required_arguments, supported_providers, usize_argument,
+};
+
+pub(crate) const DEFAULT_MAX_FILES: usize = 200;
+
+const PAGE_EXTENSIONS: &[&str] = &[".md", ".mdx", ".markdown"];
+
+/// File stems of the pages standing for the directory they are in
+const INDEX_STEMS: &[&str] = &["_index", "index", "readme"];
+
+/// Files describing a Docusaurus sidebar category, for the directory they are in
+const CATEGORY_FILES: &[&str] = &["_category_.json", "_category_.yml", "_category_.yaml"];
+
+/// Static site generators whose layout and ordering conventions are understood
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Generator {
+    MkDocs,
+    Docusaurus,
+    Hugo,
+}
+
+impl Generator {
+    /// Recognizes a generator from the configuration files at the repository root
+    fn detect(paths: &BTreeSet<&str>) -> Option<Self> {
+        let has = |candidates: &[&str]| candidates.iter().any(|path| paths.contains(path));
+
+        if has(&["mkdocs.yml", "mkdocs.yaml"]) {
+            Some(Self::MkDocs)
+        } else if has(&[
+            "docusaurus.config.js",
+            "docusaurus.config.ts",
+            "docusaurus.config.mjs",
+        ]) {
+            Some(Self::Docusaurus)
+        } else if has(&["hugo.toml", "hugo.yaml", "hugo.json"])
+            || (has(&["config.toml"]) && paths.iter().any(|path| path.starts_with("content/")))
+        {
+            Some(Self::Hugo)
+        } else {
+            None
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::MkDocs => "MkDocs",
+            Self::Docusaurus => "Docusaurus",
+            Self::Hugo => "Hugo",
+        }
+    }
+
+    /// The directory pages are read from unless configured otherwise
+    fn default_dir(&self) -> &'static str {
+        match self {
+            Self::MkDocs | Self::Docusaurus => "docs",
+            Self::Hugo => "content",
+        }
+    }
+}
+
+/// A documentation page and the metadata of its frontmatter
+struct Page {
+    path: String,
+    title: String,
+    tags: Vec<String>,
+    /// Position among its siblings, lower first
+    weight: Option<i64>,
+    description: Option<String>,
+    draft: bool,
+}
+
+fn is_page(path: &str) -> bool {
+    let path = path.to_ascii_lowercase();
+    PAGE_EXTENSIONS
+        .iter()
+        .any(|extension| path.ends_with(extension))
+}
+
+/// The file name of a path without its extension
+fn file_stem(path: &str) -> &str {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.rsplit_once('.').map_or(name, |(stem, _)| stem)
+}
+
+fn is_index(path: &str) -> bool {
+    INDEX_STEMS.contains(&file_stem(path).to_ascii_lowercase().as_str())
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(parent, _)| parent)
+}
+
+/// Splits a document into its YAML (`---`) or TOML (`+++`) frontmatter and its body. Documents
+/// without frontmatter, or with frontmatter that does not parse, get `Value::Null`.
+fn split_frontmatter(content: &str) -> (Value, &str) {
+    let content = content.trim_start_matches('\u{feff}');
+    let Some(delimiter) = ["---", "+++"]
+        .into_iter()
+        .find(|delimiter| content.lines().next().map(str::trim_end) == Some(*delimiter))
+    else {
+        return (Value::Null, content);
+    };
+
+    let start = content.find('\n').map_or(content.len(), |i| i + 1);
+    let mut offset = start;
+    for line in content[start..].split_inclusive('\n') {
+        if line.trim_end() == delimiter {
+            let source = &content[start..offset];
+            let frontmatter = match delimiter {
+                "---" => serde_yaml::from_str(source).ok(),
+                _ => toml::from_str(source).ok(),
+            };
+            return (
+                frontmatter.unwrap_or(Value::Null),
+                &content[offset + line.len()..],
+            );
+        }
+        offset += line.len();
+    }
+
+    (Value::Null, content)
+}
+
+/// The first of `keys` holding a non-empty string
+fn string_field(frontmatter: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| frontmatter.get(key)?.as_str())
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+}
+
+/// The first of `keys` holding a number, generators accept both integers and floats
+fn weight_field(frontmatter: &Value, keys: &[&str]) -> Option<i64> {
+    keys.iter()
+        .filter_map(|key| frontmatter.get(key))
+        .find_map(|value| {
+            value
+                .as_i64()
+                .or_else(|| value.as_f64().map(|f| f.round() as i64))
+        })
+}
+
+/// Tags given as a list, or as a comma-separated string
+fn tags_field(frontmatter: &Value) -> Vec<String> {
+    match frontmatter.get("tags") {
+        Some(Value::Array(tags)) => tags
+            .iter()
+            .filter_map(|tag| match tag {
+                Value::String(tag) => Some(tag.clone()),
+                // Docusaurus also accepts objects with a label
+                tag => tag.get("label")?.as_str().map(str::to_string),
+            })
+            .collect(),
+        Some(Value::String(tags)) => tags
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_page(path: &str, content: &str) -> Page {
+    let (frontmatter, body) = split_frontmatter(content);
+
+    // The first heading stands in for a missing title, as every generator does
+    let title = string_field(&frontmatter, &["title", "sidebar_label", "linkTitle"])
+        .or_else(|| {
+            body.lines()
+                .find_map(|line| line.strip_prefix("# "))
+                .map(|heading| heading.trim().to_string())
+        })
+        .unwrap_or_else(|| file_stem(path).to_string());
+
+    Page {
+        path: path.to_string(),
+        title,
+        tags: tags_field(&frontmatter),
+        weight: weight_field(&frontmatter, &["weight", "sidebar_position", "nav_order"]),
+        description: string_field(&frontmatter, &["description", "summary"]),
+        draft: frontmatter
+            .get("draft")
+            .and_then(|draft| draft.as_bool())
+            .unwrap_or(false),
+    }
+}
+
+fn page_line(page: &Page, depth: usize) -> String {
+    let indent = "  ".repeat(depth);
+    let mut line = format!("{}- {} ({})", indent, page.title, page.path);
+    if page.draft {
+        line.push_str(" [draft]");
+    }
+    if !page.tags.is_empty() {
+        line.push_str(&format!(" · tags: {}", page.tags.join(", ")));
+    }
+    line.push('\n');
+    if let Some(description) = &page.description {
+        line.push_str(&format!("{}  {}\n", indent, description));
+    }
+    line
+}
+
+/// How a directory shows up in the site navigation
+struct Section<'a> {
+    label: String,
+    weight: Option<i64>,
+    index: Option<&'a Page>,
+}
+
+/// A page or a subdirectory, as listed within a directory
+enum Entry<'a> {
+    Page(&'a Page),
+    Section(&'a str, Section<'a>),
+}
+
+/// Pages grouped by directory, ordered the way the generators order their sidebars: by
+/// weight, then by title
+struct DirectoryLayout<'a> {
+    pages_by_dir: BTreeMap<&'a str, Vec<&'a Page>>,
+    /// Every directory holding a page, its ancestors included
+    dirs: BTreeSet<&'a str>,
+    /// Labels and positions of Docusaurus categories, by directory
+    categories: HashMap<&'a str, (Option<String>, Option<i64>)>,
+}
+
+impl<'a> DirectoryLayout<'a> {
+    fn new(
+        root: &'a str,
+        pages: &'a [Page],
+        categories: HashMap<&'a str, (Option<String>, Option<i64>)>,
+    ) -> Self {
+        let mut pages_by_dir: BTreeMap<&str, Vec<&Page>> = BTreeMap::new();
+        let mut dirs = BTreeSet::new();
+        for page in pages {
+            let mut dir = parent_dir(&page.path);
+            pages_by_dir.entry(dir).or_default().push(page);
+            while dir.len() > root.len() && dirs.insert(dir) {
+                dir = parent_dir(dir);
+            }
+        }
+
+        Self {
+            pages_by_dir,
+            dirs,
+            categories,
+        }
+    }
+
+    fn section(&self, dir: &'a str) -> Section<'a> {
+        let index = self
+            .pages_by_dir
+            .get(dir)
+            .and_then(|pages| pages.iter().find(|page| is_index(&page.path)).copied());
+        let (label, position) = self.categories.get(dir).cloned().unwrap_or_default();
+
+        Section {
+            label: label
+                .or_else(|| index.map(|page| page.title.clone()))
+                .unwrap_or_else(|| dir.rsplit('/').next().unwrap_or(dir).to_string()),
+            weight: position.or_else(|| index.and_then(|page| page.weight)),
+            index,
+        }
+    }
+
+    fn render(&self, dir: &'a str, is_root: bool, depth: usize, output: &mut String) {
+        // The index of a section is listed on the section's own line
+        let mut entries: Vec<Entry> = self
+            .pages_by_dir
+            .get(dir)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|page| is_root || !is_index(&page.path))
+            .map(Entry::Page)
+            .chain(
+                self.dirs
+                    .iter()
+                    .copied()
+                    .filter(|child| parent_dir(child) == dir)
+                    .map(|child| Entry::Section(child, self.section(child))),
+            )
+            .collect();
+
+        entries.sort_by_cached_key(|entry| match entry {
+            Entry::Page(page) => (page.weight.unwrap_or(i64::MAX), page.title.to_lowercase()),
+            Entry::Section(_, section) => (
+                section.weight.unwrap_or(i64::MAX),
+                section.label.to_lowercase(),
+            ),
+        });
+
+        for entry in entries {
+            match entry {
+                Entry::Page(page) => output.push_str(&page_line(page, depth)),
+                Entry::Section(child, section) => {
+                    output.push_str(&format!("{}- {}/", "  ".repeat(depth), section.label));
+                    if let Some(index) = section.index {
+                        output.push_str(&format!(" ({})", index.path));
+                    }
+                    output.push('\n');
+                    self.render(child, false, depth + 1, output);
+                }
+            }
+        }
+    }
+}
+
+/// Renders an MkDocs `nav` entry, remembering the pages it lists
+fn render_nav(
+    items: &[serde_yaml::Value],
+    docs_dir: &str,
+    pages: &HashMap<&str, &Page>,
+    depth: usize,
+    listed: &mut HashSet<String>,
+    output: &mut String,
+) {
+    let indent = "  ".repeat(depth);
+    for item in items {
+        // Entries are either a bare path, or a single title mapped to a path or to children
+        let (title, target) = match item {
+            serde_yaml::Value::Mapping(mapping) => match mapping.iter().next() {
+                Some((title, target)) => (title.as_str(), target),
+                None => continue,
+            },
+            target => (None, target),
+        };
+
+        match target {
+            serde_yaml::Value::String(target) if target.contains("://") => {
+                output.push_str(&format!(
+                    "{}- {} ({})\n",
+                    indent,
+                    title.unwrap_or(target.as_str()),
+                    target
+                ));
+            }
+            serde_yaml::Value::String(target) => {
+                let path = format!("{}/{}", docs_dir, target.trim_start_matches("./"));
+                match pages.get(path.as_str()) {
+                    Some(page) => {
+                        let mut line = page_line(page, depth);
+                        if let Some(title) = title {
+                            // The navigation title wins over the page's own
+                            line = line.replacen(&page.title, title, 1);
+                        }
+                        output.push_str(&line);
+                    }
+                    None => output.push_str(&format!(
+                        "{}- {} ({}) [missing]\n",
+                        indent,
+                        title.unwrap_or(target.as_str()),
+                        path
+                    )),
+                }
+                listed.insert(path);
+            }
+            serde_yaml::Value::Sequence(children) => {
+                output.push_str(&format!("{}- {}/\n", indent, title.unwrap_or("Untitled")));
+                render_nav(children, docs_dir, pages, depth + 1, listed, output);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub struct DocsSiteMap {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl DocsSiteMap {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for DocsSiteMap {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let max_files = usize_argument(&args, "max_files").unwrap_or(DEFAULT_MAX_FILES);
+
+        let files = provider
+            .list_files(repo_path, git_ref.clone(), &TreeOptions::default())
+            .await
+            .map_err(|e| anyhow!("Error listing files: {}", e))?;
+        let paths: BTreeSet<&str> = files.iter().map(|file| file.path.as_str()).collect();
+        let generator = Generator::detect(&paths);
+
+        // MkDocs configures both the directory and the navigation
+        let mkdocs: Option<serde_yaml::Value> = match generator {
+            Some(Generator::MkDocs) => {
+                let config = ["mkdocs.yml", "mkdocs.yaml"]
+                    .into_iter()
+                    .find(|path| paths.contains(path))
+                    .unwrap_or("mkdocs.yml");
+                provider
+                    .get_file_content(repo_path, config, git_ref.clone())
+                    .await
+                    .ok()
+                    .and_then(|content| serde_yaml::from_str(&content).ok())
+            }
+            _ => None,
+        };
+
+        let dir = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .map(|path| path.trim_matches('/').to_string())
+            .filter(|path| !path.is_empty())
+            .or_else(|| {
+                mkdocs
+                    .as_ref()
+                    .and_then(|config| config.get("docs_dir")?.as_str())
+                    .map(|dir| dir.trim_matches('/').to_string())
+            })
+            .unwrap_or_else(|| {
+                generator
+                    .map_or("docs", |generator| generator.default_dir())
+                    .to_string()
+            });
+        let prefix = format!("{}/", dir);
+
+        let page_paths: Vec<&str> = paths
+            .iter()
+            .copied()
+            .filter(|path| path.starts_with(&prefix) && is_page(path))
+            .collect();
+        if page_paths.is_empty() {
+            return Err(anyhow!(
+                "No markdown pages found under {}/ of {}",
+                dir,
+                repo_path
+            ));
+        }
+        let total = page_paths.len();
+        let page_paths = &page_paths[..total.min(max_files)];
+
+        let contents = join_all(
+            page_paths
+                .iter()
+                .map(|path| provider.get_file_content(repo_path, path, git_ref.clone())),
+        )
+        .await;
+        // Unreadable pages are still listed, under their file name
+        let pages: Vec<Page> = page_paths
+            .iter()
+            .zip(contents)
+            .map(|(path, content)| parse_page(path, content.as_deref().unwrap_or_default()))
+            .collect();
+
+        let mut formatted_output = format!(
+            "# Documentation site map of {}\n\n{}/ · {} · {} pages\n\n",
+            repo_path,
+            dir,
+            generator.map_or("no site generator detected", |generator| generator.name()),
+            total
+        );
+
+        let nav = mkdocs
+            .as_ref()
+            .and_then(|config| config.get("nav")?.as_sequence());
+        match nav {
+            Some(nav) => {
+                let pages_by_path: HashMap<&str, &Page> = pages
+                    .iter()
+                    .map(|page| (page.path.as_str(), page))
+                    .collect();
+                let mut listed = HashSet::new();
+                render_nav(
+                    nav,
+                    &dir,
+                    &pages_by_path,
+                    0,
+                    &mut listed,
+                    &mut formatted_output,
+                );
+
+                let unlisted: Vec<&Page> = pages
+                    .iter()
+                    .filter(|page| !listed.contains(&page.path))
+                    .collect();
+                if !unlisted.is_empty() {
+                    formatted_output.push_str("\n## Not in nav\n\n");
+                    for page in unlisted {
+                        formatted_output.push_str(&page_line(page, 0));
+                    }
+                }
+            }
+            None => {
+                let category_paths: Vec<&str> = paths
+                    .iter()
+                    .copied()
+                    .filter(|path| {
+                        path.starts_with(&prefix)
+                            && CATEGORY_FILES.iter().any(|name| path.ends_with(name))
+                    })
+                    .collect();
+                let category_contents = join_all(
+                    category_paths
+                        .iter()
+                        .map(|path| provider.get_file_content(repo_path, path, git_ref.clone())),
+                )
+                .await;
+                // JSON is valid YAML, both spellings parse alike
+                let categories = category_paths
+                    .iter()
+                    .zip(category_contents)
+                    .filter_map(|(path, content)| {
+                        let category: Value = serde_yaml::from_str(&content.ok()?).ok()?;
+                        Some((
+                            parent_dir(path),
+                            (
+                                string_field(&category, &["label"]),
+                                weight_field(&category, &["position"]),
+                            ),
+                        ))
+                    })
+                    .collect();
+
+                DirectoryLayout::new(&dir, &pages, categories).render(
+                    &dir,
+                    true,
+                    0,
+                    &mut formatted_output,
+                );
+            }
+        }
+
+        if total > pages.len() {
+            formatted_output.push_str(&format!(
+                "\n({} more pages not read, raise max_files to include them)\n",
+                total - pages.len()
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "docs_site_map".into(),
+            description: Some(format!(
+                "Map the documentation pages of a repository with the title, tags, description and ordering of their frontmatter, following the navigation of MkDocs, Docusaurus and Hugo sites. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Optional directory of the documentation pages. Default: the site generator's, else 'docs'"
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "description": format!("Optional maximum number of pages read. Default: {}", DEFAULT_MAX_FILES)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frontmatter_gives_the_page_metadata() {
+        let page = parse_page(
+            "docs/intro.md",
+            "---\ntitle: Introduction\ntags: [start, basics]\nsidebar_position: 1\ndescription: Where to begin\n---\n# Ignored\n",
+        );
+        assert_eq!(page.title, "Introduction");
+        assert_eq!(page.tags, ["start", "basics"]);
+        assert_eq!(page.weight, Some(1));
+        assert_eq!(page.description.as_deref(), Some("Where to begin"));
+        assert!(!page.draft);
+
+        let page = parse_page(
+            "content/post.md",
+            "+++\nweight = 2.6\ndraft = true\ntags = \"a, b\"\n+++\n# Heading title\n",
+        );
+        assert_eq!(page.title, "Heading title");
+        assert_eq!(page.tags, ["a", "b"]);
+        assert_eq!(page.weight, Some(3));
+        assert!(page.draft);
+
+        let page = parse_page("docs/broken.md", "---\ntitle: [unclosed\n---\nText\n");
+        assert_eq!(page.title, "broken");
+        assert_eq!(split_frontmatter("---\nno end").1, "---\nno end");
+    }
+
+    #[test]
+    fn generators_are_recognized_by_their_configuration() {
+        let detect = |paths: &[&str]| Generator::detect(&paths.iter().copied().collect());
+
+        assert_eq!(
+            detect(&["mkdocs.yml", "docs/index.md"]),
+            Some(Generator::MkDocs)
+        );
+        assert_eq!(
+            detect(&["docusaurus.config.ts"]),
+            Some(Generator::Docusaurus)
+        );
+        assert_eq!(
+            detect(&["config.toml", "content/_index.md"]),
+            Some(Generator::Hugo)
+        );
+        assert_eq!(detect(&["config.toml", "docs/index.md"]), None);
+    }
+
+    #[test]
+    fn directories_are_ordered_by_weight_then_title() {
+        let pages = [
+            parse_page("docs/index.md", "# Home\n"),
+            parse_page(
+                "docs/intro.md",
+                "---\ntitle: Intro\nweight: 1\ntags: [start]\ndescription: Getting started\n---\n",
+            ),
+            parse_page("docs/guide/index.md", "---\ntitle: Guide\nweight: 2\n---\n"),
+            parse_page("docs/guide/setup.md", "# Setup\n"),
+            parse_page("docs/api/reference.md", "# Reference\n"),
+        ];
+        let categories = HashMap::from([("docs/api", (Some("API".to_string()), Some(3)))]);
+
+        let mut output = String::new();
+        DirectoryLayout::new("docs", &pages, categories).render("docs", true, 0, &mut output);
+        assert_eq!(
+            output,
+            "- Intro (docs/intro.md) · tags: start\n  Getting started\n- Guide/ (docs/guide/index.md)\n  - Setup (docs/guide/setup.md)\n- API/\n  - Reference (docs/api/reference.md)\n- Home (docs/index.md)\n"
+        );
+    }
+
+    #[test]
+    fn mkdocs_navigation_marks_missing_pages() {
+        let pages = [
+            parse_page("docs/index.md", "# Home\n"),
+            parse_page("docs/guide/setup.md", "# Setup\n"),
+        ];
+        let by_path = pages
+            .iter()
+            .map(|page| (page.path.as_str(), page))
+            .collect();
+        let nav: Vec<serde_yaml::Value> = serde_yaml::from_str(
+            "- index.md\n- Guide:\n  - Install: guide/setup.md\n  - guide/gone.md\n- Source: https://example.com\n",
+        )
+        .unwrap();
+
+        let mut listed = HashSet::new();
+        let mut output = String::new();
+        render_nav(&nav, "docs", &by_path, 0, &mut listed, &mut output);
+        assert_eq!(
+            output,
+            "- Home (docs/index.md)\n- Guide/\n  - Install (docs/guide/setup.md)\n  - guide/gone.md (docs/guide/gone.md) [missing]\n- Source (https://example.com)\n"
+        );
+        assert!(listed.contains("docs/guide/setup.md"));
+        assert!(!listed.contains("https://example.com"));
+    }
+}
@@ -0,0 +1,260 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::{StreamExt, stream};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::{
+    common::{
+        ANONYMOUS_MAX_FILES, FETCH_CONCURRENCY, anonymous_limit_note, bool_argument,
+        check_repository_size, git_ref_argument, max_files_argument, preset_names, repo_argument,
+        required_arguments, string_argument, supported_providers, tree_options_argument,
+        warn_skipped, warn_truncated,
+    },
+    i18n::Message,
+    write_roots::WriteRoots,
+};
+
+pub(crate) const DEFAULT_MAX_FILES: usize = 500;
+
+/// The path of `file` relative to `directory`, or `None` when the file lies outside of it or
+/// its path would escape the target directory
+fn relative_path(file: &str, directory: &str) -> Option<PathBuf> {
+    let relative = if directory.is_empty() {
+        file
+    } else {
+        file.strip_prefix(directory)?.strip_prefix('/')?
+    };
+
+    let path = Path::new(relative);
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_)))
+        .then(|| path.to_path_buf())
+}
+
+pub struct DownloadDirectory {
+    providers: Vec<Arc<dyn GitProvider>>,
+    write_roots: WriteRoots,
+}
+
+impl DownloadDirectory {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>, write_roots: WriteRoots) -> Self {
+        Self {
+            providers,
+            write_roots,
+        }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for DownloadDirectory {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let target = self
+            .write_roots
+            .resolve(Path::new(&string_argument(&args, "target_path")?))?;
+        let directory = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .trim_matches('/');
+        let max_files = max_files_argument(provider, &args, DEFAULT_MAX_FILES);
+        let overwrite = bool_argument(&args, "overwrite").unwrap_or(false);
+
+        let options = tree_options_argument(&args)?;
+
+        let force = bool_argument(&args, "force").unwrap_or(false);
+        let size_warning = check_repository_size(provider, repo_path, force).await?;
+
+        let mut files: Vec<(String, PathBuf)> = provider
+            .list_files(repo_path, git_ref.clone(), &options)
+            .await
+            .map_err(|e| anyhow!("Error listing repository files: {}", e))?
+            .into_iter()
+            .filter_map(|file| {
+                let relative = relative_path(&file.path, directory)?;
+                Some((file.path, relative))
+            })
+            .collect();
+
+        if files.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!(
+                    "No files of {} under '{}' match the patterns",
+                    repo_path, directory
+                ),
+            }]);
+        }
+
+        let total_files = files.len();
+        files.truncate(max_files);
+
+        // Files kept in place are not fetched at all
+        let mut existing = Vec::new();
+        let mut pending = Vec::new();
+        for (path, relative) in &files {
+            let destination = target.join(relative);
+            if destination.exists() && !overwrite {
+                existing.push(path.as_str());
+            } else {
+                // Owned, so the stream stays `Send` without borrowing `files`
+                pending.push((path.clone(), destination));
+            }
+        }
+
+        let mut fetched = stream::iter(pending)
+            .map(|(path, destination)| {
+                let git_ref = git_ref.clone();
+                async move {
+                    let content = provider.get_file_content(repo_path, &path, git_ref).await;
+                    (path, destination, content)
+                }
+            })
+            .buffer_unordered(FETCH_CONCURRENCY);
+
+        let mut written = 0;
+        let mut bytes = 0;
+        let mut failed = Vec::new();
+        while let Some((path, destination, content)) = fetched.next().await {
+            let result = content.and_then(|content| {
+                // Checked again, as a directory already in the target can link out of the roots
+                let destination = self.write_roots.resolve(&destination)?;
+                if let Some(parent) = destination.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&destination, &content)?;
+                Ok(content.len())
+            });
+
+            match result {
+                Ok(size) => {
+                    written += 1;
+                    bytes += size;
+                }
+                Err(e) => {
+                    warn_skipped(&path, &e);
+                    failed.push(format!("{}: {}", path, e));
+                }
+            }
+        }
+
+        let mut formatted_output = size_warning.unwrap_or_default();
+        formatted_output.push_str(&format!(
+            "Downloaded {} files ({} bytes) of {} under '{}' to {}\n",
+            written,
+            bytes,
+            repo_path,
+            directory,
+            target.display()
+        ));
+
+        if !existing.is_empty() {
+            formatted_output.push_str(&format!(
+                "\nKept {} existing files, pass overwrite: true to replace them:\n",
+                existing.len()
+            ));
+            for path in existing {
+                formatted_output.push_str(&format!("- {}\n", path));
+            }
+        }
+
+        if !failed.is_empty() {
+            // Downloads finish in any order
+            failed.sort();
+            formatted_output.push_str(&format!("\nFailed to download {} files:\n", failed.len()));
+            for failure in failed {
+                formatted_output.push_str(&format!("- {}\n", failure));
+            }
+        }
+
+        if let Some(note) = anonymous_limit_note(provider, &args, files.len(), total_files) {
+            formatted_output.push_str(&format!("\n({})\n", note));
+        } else if total_files > files.len() {
+            warn_truncated(files.len(), total_files, Message::Files, "max_files");
+            formatted_output.push_str(&format!(
+                "\n({} more files not downloaded, raise max_files or narrow the patterns)\n",
+                total_files - files.len()
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "download_directory".into(),
+            description: Some(format!(
+                "Download the files of a repository directory matching the patterns into a local directory, preserving their structure, for tools that need real files such as compilers and linters. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Optional directory of the repository to download, its files are written relative to it. Default: the repository root"
+                    },
+                    "target_path": {
+                        "type": "string",
+                        "description": "Local directory the files are written into, created if missing. Relative paths start from the first directory of GITINGEST_WRITE_ROOTS, and paths outside of them are refused"
+                    },
+                    "exclude_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to exclude"
+                    },
+                    "include_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to include"
+                    },
+                    "preset": {
+                        "type": "string",
+                        "description": format!("Optional comma-separated list of ecosystem presets adding their source and exclude patterns: {}", preset_names())
+                    },
+                    "extensions": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of file extensions to include, e.g. 'rs,toml,md'"
+                    },
+                    "case_insensitive": {
+                        "type": "boolean",
+                        "description": "Optional flag to match every pattern regardless of case. Default: false"
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "description": format!("Optional maximum number of files to download. Default: {}, or {} for providers called without a token", DEFAULT_MAX_FILES, ANONYMOUS_MAX_FILES)
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "Optional flag to replace files already present in the target directory. Default: false"
+                    },
+                    "force": {
+                        "type": "boolean",
+                        "description": "Optional flag to download from repositories above the server's size limit. Default: false"
+                    }
+                },
+                "required": ["repo", "target_path"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use git_provider::GitProvider;
+use serde_json::Value;
+
+use crate::{
+    archive, bisect,
+    common::{max_files_argument, repo_argument, usize_argument},
+    config_inventory, curated, digest, docs_site, download, grep, hotspots, kubernetes, link_check,
+    schemas, terraform,
+};
+
+/// The calls a tool makes once its first listing is answered, one per item it reads, which a
+/// dry run cannot see since it answers nothing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FanOut {
+    /// Calls at most, fewer when the listing holds fewer items
+    pub calls: usize,
+    /// What each call reads (e.g., "file")
+    pub item: &'static str,
+}
+
+/// Estimates the fan-out of `tool` from its arguments and defaults, `None` for tools making a
+/// fixed number of calls
+pub fn estimate_fan_out(
+    providers: &[Arc<dyn GitProvider>],
+    tool: &str,
+    arguments: &Value,
+) -> Option<FanOut> {
+    // Lowered for providers called without a token, as the tools themselves do
+    let files = |default| match repo_argument(providers, arguments) {
+        Ok((provider, _)) => max_files_argument(provider, arguments, default),
+        Err(_) => usize_argument(arguments, "max_files").unwrap_or(default),
+    };
+    let scanned = |default| usize_argument(arguments, "max_files").unwrap_or(default);
+
+    let (calls, item) = match tool {
+        "repository_digest" => (files(digest::DEFAULT_MAX_FILES), "file"),
+        "repository_grep" => (files(grep::DEFAULT_MAX_FILES), "file"),
+        "repository_archive" => (files(archive::DEFAULT_MAX_FILES), "file"),
+        "download_directory" => (files(download::DEFAULT_MAX_FILES), "file"),
+        "check_links" => (scanned(link_check::DEFAULT_MAX_FILES), "file"),
+        "config_inventory" => (scanned(config_inventory::DEFAULT_MAX_FILES), "file"),
+        "repository_schemas" => (scanned(schemas::DEFAULT_MAX_FILES), "file"),
+        "terraform_inventory" => (scanned(terraform::DEFAULT_MAX_FILES), "file"),
+        "docs_site_map" => (scanned(docs_site::DEFAULT_MAX_FILES), "file"),
+        "kubernetes_inventory" => (scanned(kubernetes::DEFAULT_MAX_FILES), "file"),
+        "expand_curated_list" => (
+            usize_argument(arguments, "max_repositories")
+                .unwrap_or(curated::DEFAULT_MAX_REPOSITORIES),
+            "repository",
+        ),
+        "bisect_helper" => (
+            usize_argument(arguments, "max_commits")
+                .unwrap_or(bisect::DEFAULT_MAX_COMMITS)
+                .clamp(1, bisect::MAX_COMMITS),
+            "commit",
+        ),
+        "repository_hotspots" => (
+            usize_argument(arguments, "commits")
+                .unwrap_or(hotspots::DEFAULT_COMMITS)
+                .clamp(1, hotspots::MAX_COMMITS),
+            "commit",
+        ),
+        _ => return None,
+    };
+
+    Some(FanOut { calls, item })
+}
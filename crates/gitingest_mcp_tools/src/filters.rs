@@ -0,0 +1,234 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::{
+    GitProvider, TreeOptions,
+    ignore_patterns::DEFAULT_IGNORE_PATTERNS,
+    patterns::{FilterDecision, PathFilter, PatternSource},
+};
+use serde_json::{Value, json};
+
+use crate::common::{
+    git_ref_argument, list_argument, preset_names, repo_argument, required_arguments,
+    supported_providers, tree_options_argument, usize_argument,
+};
+
+const DEFAULT_SAMPLE: usize = 40;
+
+/// Why a path was kept or dropped, in words
+fn reason(decision: FilterDecision<'_>, custom_ignores: bool) -> &'static str {
+    let Some(rule) = decision.rule else {
+        return if decision.keep {
+            "no pattern matched"
+        } else {
+            "matched none of the include patterns"
+        };
+    };
+
+    match (rule.source, rule.negated) {
+        (PatternSource::Include, false) => "matched an include pattern",
+        (PatternSource::Include, true) => "matched a negated include pattern",
+        (PatternSource::Exclude, false) => "matched an exclude pattern",
+        (PatternSource::Exclude, true) => "re-included by a negated exclude pattern",
+        (PatternSource::Ignore, _) if custom_ignores => "contains an ignore pattern",
+        (PatternSource::Ignore, _) => "contains a default ignore pattern",
+    }
+}
+
+/// Picks up to `count` items spread evenly over `items`, keeping their order
+fn spread<T>(items: &[T], count: usize) -> Vec<&T> {
+    if items.len() <= count {
+        return items.iter().collect();
+    }
+
+    (0..count)
+        .map(|i| &items[i * items.len() / count])
+        .collect()
+}
+
+pub struct ExplainFilters {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ExplainFilters {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ExplainFilters {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let sample = usize_argument(&args, "sample").unwrap_or(DEFAULT_SAMPLE);
+        let requested_paths = list_argument(&args, "paths");
+
+        let options = tree_options_argument(&args)?;
+        let custom_ignores = args.get("ignore_patterns").is_some();
+        let ignore_patterns = if custom_ignores {
+            list_argument(&args, "ignore_patterns")
+        } else {
+            DEFAULT_IGNORE_PATTERNS
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect()
+        };
+        let filter = PathFilter::from_options(&options, &ignore_patterns);
+
+        // Every file of the repository, so dropped paths can be explained too
+        let unfiltered = TreeOptions {
+            ignore_patterns: Some(Vec::new()),
+            ..Default::default()
+        };
+        let files = provider
+            .list_files(repo_path, git_ref, &unfiltered)
+            .await
+            .map_err(|e| anyhow!("Error listing repository files: {}", e))?;
+
+        let mut rules: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+        let mut kept = Vec::new();
+        let mut dropped = Vec::new();
+        for file in &files {
+            let decision = filter.decide(&file.path);
+            let rule = decision
+                .rule
+                .map(|rule| rule.to_string())
+                .unwrap_or_else(|| "(no rule)".to_string());
+
+            let counts = rules.entry(rule).or_default();
+            if decision.keep {
+                counts.0 += 1;
+                kept.push(file.path.as_str());
+            } else {
+                counts.1 += 1;
+                dropped.push(file.path.as_str());
+            }
+        }
+
+        let mut formatted_output = format!(
+            "Filters for {} ({} files: {} kept, {} dropped)\n\n",
+            repo_path,
+            files.len(),
+            kept.len(),
+            dropped.len()
+        );
+
+        formatted_output.push_str("Include patterns narrow, exclude patterns override them and later patterns win. `!pattern` inverts an include or exclude pattern.\n\n");
+
+        formatted_output.push_str("## Rules\n\n| Rule | Kept | Dropped |\n|---|---|---|\n");
+        for (rule, (kept_count, dropped_count)) in &rules {
+            formatted_output.push_str(&format!(
+                "| {} | {} | {} |\n",
+                rule, kept_count, dropped_count
+            ));
+        }
+
+        // Explicitly requested paths, otherwise a sample split between both outcomes
+        let existing: BTreeSet<&str> = files.iter().map(|file| file.path.as_str()).collect();
+        let paths: Vec<&str> = if requested_paths.is_empty() {
+            let dropped_count = dropped
+                .len()
+                .min((sample / 2).max(sample.saturating_sub(kept.len())));
+            let mut paths: Vec<&str> = spread(&dropped, dropped_count)
+                .into_iter()
+                .chain(spread(&kept, sample - dropped_count))
+                .copied()
+                .collect();
+            paths.sort();
+            paths
+        } else {
+            requested_paths.iter().map(String::as_str).collect()
+        };
+
+        formatted_output
+            .push_str("\n## Paths\n\n| Path | Result | Rule | Reason |\n|---|---|---|---|\n");
+        for path in paths {
+            let decision = filter.decide(path);
+            let mut explanation = reason(decision, custom_ignores).to_string();
+            if !existing.contains(path) {
+                explanation.push_str(" (not in repository)");
+            }
+
+            formatted_output.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                path,
+                if decision.keep { "kept" } else { "dropped" },
+                decision
+                    .rule
+                    .map(|rule| format!("`{}`", rule))
+                    .unwrap_or_else(|| "-".to_string()),
+                explanation
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "explain_filters".into(),
+            description: Some(format!(
+                "Explain which include, exclude or ignore pattern keeps or drops each path of a repository, to debug files missing from tree and digest output. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "exclude_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to exclude, '!pattern' re-includes"
+                    },
+                    "include_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to include, '!pattern' drops"
+                    },
+                    "ignore_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of substrings replacing the default ignore patterns"
+                    },
+                    "preset": {
+                        "type": "string",
+                        "description": format!("Optional comma-separated list of ecosystem presets adding their source and exclude patterns: {}", preset_names())
+                    },
+                    "extensions": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of file extensions to include, e.g. 'rs,toml,md'"
+                    },
+                    "case_insensitive": {
+                        "type": "boolean",
+                        "description": "Optional flag to match every pattern regardless of case. Default: false"
+                    },
+                    "paths": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of paths to explain instead of a sample"
+                    },
+                    "sample": {
+                        "type": "integer",
+                        "description": format!("Optional number of paths explained, split between kept and dropped ones. Default: {}", DEFAULT_SAMPLE)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
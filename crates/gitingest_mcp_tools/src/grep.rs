@@ -0,0 +1,305 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{
+    GitProvider,
+    ignore_patterns::DEFAULT_IGNORE_PATTERNS,
+    patterns::PathFilter,
+    warnings::{self, WarningKind},
+};
+use regex::RegexBuilder;
+use serde_json::{Value, json};
+
+use crate::{
+    common::{
+        ANONYMOUS_MAX_FILES, anonymous_limit_note, bool_argument, git_ref_argument,
+        max_files_argument, preset_names, repo_argument, required_arguments, string_argument,
+        supported_providers, tree_options_argument, usize_argument, warn_truncated,
+    },
+    i18n::Message,
+    links::DEFAULT_CONTEXT_LINES,
+};
+
+const DEFAULT_MAX_MATCHES: usize = 100;
+pub(crate) const DEFAULT_MAX_FILES: usize = 200;
+/// Files fetched at once, later batches are skipped once enough matches are found
+const BATCH_SIZE: usize = 20;
+/// Larger files are most likely generated or binary, and not worth a request
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Renders the matching lines of a file with their context, numbering every line and marking
+/// the matches with '>'. Distant groups of lines are separated by "--".
+fn render_matches(lines: &[&str], matches: &[usize], context: usize) -> String {
+    // Line ranges to show, merged when they touch
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &index in matches {
+        let first = index.saturating_sub(context);
+        let last = (index + context).min(lines.len() - 1);
+        match ranges.last_mut() {
+            Some((_, previous_last)) if first <= *previous_last + 1 => *previous_last = last,
+            _ => ranges.push((first, last)),
+        }
+    }
+
+    let width = ranges
+        .last()
+        .map_or(1, |(_, last)| (last + 1).to_string().len());
+    let mut output = String::new();
+    for (position, (first, last)) in ranges.into_iter().enumerate() {
+        if position > 0 {
+            output.push_str("--\n");
+        }
+        for (index, line) in lines.iter().enumerate().take(last + 1).skip(first) {
+            let marker = if matches.contains(&index) { '>' } else { ' ' };
+            output.push_str(&format!(
+                "{} {:>width$} | {}\n",
+                marker,
+                index + 1,
+                line,
+                width = width
+            ));
+        }
+    }
+
+    output
+}
+
+pub struct RepositoryGrep {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositoryGrep {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositoryGrep {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let pattern = string_argument(&args, "pattern")?;
+        let is_regex = bool_argument(&args, "regex").unwrap_or(false);
+        let ignore_case = bool_argument(&args, "ignore_case").unwrap_or(false);
+        let context = usize_argument(&args, "context_lines").unwrap_or(DEFAULT_CONTEXT_LINES);
+        let max_matches = usize_argument(&args, "max_matches")
+            .unwrap_or(DEFAULT_MAX_MATCHES)
+            .max(1);
+        let max_files = max_files_argument(provider, &args, DEFAULT_MAX_FILES);
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let options = tree_options_argument(&args)?;
+
+        let expression = if is_regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        };
+        let matcher = RegexBuilder::new(&expression)
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|e| anyhow!("Invalid pattern '{}': {}", pattern, e))?;
+
+        // Code search only indexes the default branch and finds literal strings, and finding
+        // nothing may only mean the file is not indexed, so the tree is scanned instead
+        let searched = if git_ref.is_none() && !is_regex {
+            provider
+                .search_code(repo_path, pattern, max_files)
+                .await
+                .ok()
+                .filter(|paths| !paths.is_empty())
+        } else {
+            None
+        };
+
+        let (mut candidates, strategy) = match searched {
+            Some(paths) => {
+                let ignore_patterns: Vec<String> = DEFAULT_IGNORE_PATTERNS
+                    .iter()
+                    .map(|pattern| pattern.to_string())
+                    .collect();
+                let filter = PathFilter::from_options(&options, &ignore_patterns);
+                let paths: Vec<String> = paths.into_iter().filter(|p| filter.keeps(p)).collect();
+                (paths, Message::CodeSearch)
+            }
+            None => {
+                let files = provider
+                    .list_files(repo_path, git_ref.clone(), &options)
+                    .await
+                    .map_err(|e| anyhow!("Error listing repository files: {}", e))?;
+                let paths = files
+                    .into_iter()
+                    .filter(|file| file.size.is_none_or(|size| size <= MAX_FILE_SIZE))
+                    .map(|file| file.path)
+                    .collect();
+                (paths, Message::TreeScan)
+            }
+        };
+        candidates.sort();
+        let total_candidates = candidates.len();
+        candidates.truncate(max_files);
+
+        let mut sections = Vec::new();
+        let mut total_matches = 0;
+        let mut searched_files = 0;
+        let mut unreadable = 0;
+        'batches: for batch in candidates.chunks(BATCH_SIZE) {
+            let contents = join_all(batch.iter().map(|path| {
+                let git_ref = git_ref.clone();
+                async move { provider.get_file_content(repo_path, path, git_ref).await }
+            }))
+            .await;
+
+            for (path, content) in batch.iter().zip(contents) {
+                let Ok(content) = content else {
+                    unreadable += 1;
+                    continue;
+                };
+                searched_files += 1;
+
+                let lines: Vec<&str> = content.lines().collect();
+                let matches: Vec<usize> = lines
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, line)| matcher.is_match(line))
+                    .map(|(index, _)| index)
+                    .take(max_matches - total_matches)
+                    .collect();
+                if matches.is_empty() {
+                    continue;
+                }
+
+                total_matches += matches.len();
+                sections.push(format!(
+                    "## {} ({})\n\n```\n{}```\n",
+                    path,
+                    Message::FileMatches.text(&[&matches.len()]),
+                    render_matches(&lines, &matches, context)
+                ));
+
+                if total_matches >= max_matches {
+                    break 'batches;
+                }
+            }
+        }
+
+        let mut formatted_output = Message::GrepSummary.text(&[
+            &total_matches,
+            &pattern,
+            &sections.len(),
+            &repo_path,
+            &searched_files,
+            &strategy.text(&[]),
+        ]);
+        formatted_output.push('\n');
+        if total_matches >= max_matches {
+            warnings::warn(
+                WarningKind::Truncated,
+                Message::MaxMatchesReached.text(&[&max_matches]),
+            );
+            formatted_output.push_str(&Message::StoppedAtMaxMatches.text(&[]));
+            formatted_output.push('\n');
+        } else if let Some(note) =
+            anonymous_limit_note(provider, &args, candidates.len(), total_candidates)
+        {
+            formatted_output.push_str(&note);
+            formatted_output.push('\n');
+        } else if total_candidates > candidates.len() {
+            warn_truncated(
+                candidates.len(),
+                total_candidates,
+                Message::Files,
+                "max_files",
+            );
+            formatted_output
+                .push_str(&Message::FilesNotSearched.text(&[&candidates.len(), &total_candidates]));
+            formatted_output.push('\n');
+        }
+        if unreadable > 0 {
+            formatted_output.push_str(&Message::UnreadableFiles.text(&[&unreadable]));
+            formatted_output.push('\n');
+        }
+        for section in sections {
+            formatted_output.push('\n');
+            formatted_output.push_str(&section);
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_grep".into(),
+            description: Some(format!(
+                "Search the files of a Git repository for a string or regular expression, returning the matching lines with their numbers and surrounding context. Literal searches on the default branch narrow the files down with the host's code search when available, otherwise the tree is scanned. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "pattern": {
+                        "type": "string",
+                        "description": "String to search for, or a regular expression with 'regex'"
+                    },
+                    "regex": {
+                        "type": "boolean",
+                        "description": "Optional flag to read the pattern as a regular expression matched against each line. Default: false"
+                    },
+                    "ignore_case": {
+                        "type": "boolean",
+                        "description": "Optional flag to match the pattern regardless of case. Default: false"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "context_lines": {
+                        "type": "integer",
+                        "description": format!("Optional number of lines shown around each match. Default: {}", DEFAULT_CONTEXT_LINES)
+                    },
+                    "max_matches": {
+                        "type": "integer",
+                        "description": format!("Optional maximum number of matching lines returned. Default: {}", DEFAULT_MAX_MATCHES)
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "description": format!("Optional maximum number of files searched, each costing one request. Default: {}, or {} for providers called without a token", DEFAULT_MAX_FILES, ANONYMOUS_MAX_FILES)
+                    },
+                    "exclude_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to exclude"
+                    },
+                    "include_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to include"
+                    },
+                    "preset": {
+                        "type": "string",
+                        "description": format!("Optional comma-separated list of ecosystem presets adding their source and exclude patterns: {}", preset_names())
+                    },
+                    "extensions": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of file extensions to include, e.g. 'rs,toml,md'"
+                    },
+                    "case_insensitive": {
+                        "type": "boolean",
+                        "description": "Optional flag to match the include and exclude patterns regardless of case. Default: false"
+                    }
+                },
+                "required": ["repo", "pattern"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::{collections::BTreeMap, fs, path::PathBuf, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::common::{iso_timestamp_days_ago, usize_argument};
+
+const DEFAULT_LIMIT: usize = 20;
+
+/// Entries kept, the least recently accessed ones are forgotten first
+const MAX_ENTRIES: usize = 500;
+
+/// A repository, or a file within it, looked at through the tools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Access {
+    /// Repository identifier, e.g. "github:rust-lang/rust"
+    pub repo: String,
+    pub file: Option<String>,
+    pub last_tool: String,
+    pub last_accessed: String,
+    pub count: usize,
+}
+
+/// Repositories and files accessed during the server's lifetime, optionally persisted to a
+/// JSON file so they survive restarts
+#[derive(Default)]
+pub struct AccessHistory {
+    entries: Mutex<BTreeMap<(String, Option<String>), Access>>,
+    path: Option<PathBuf>,
+}
+
+impl AccessHistory {
+    /// Loads the history persisted at `path`, starting empty when it does not exist yet
+    pub fn persisted(path: PathBuf) -> Result<Self> {
+        let entries: Vec<Access> = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            entries: Mutex::new(
+                entries
+                    .into_iter()
+                    .map(|access| ((access.repo.clone(), access.file.clone()), access))
+                    .collect(),
+            ),
+            path: Some(path),
+        })
+    }
+
+    /// Records a tool call, reading the repository and file from its arguments
+    pub fn record(&self, tool: &str, arguments: &Value) {
+        let Some(repo) = arguments.get("repo").and_then(|v| v.as_str()) else {
+            return;
+        };
+        let file = ["file_path", "path"]
+            .iter()
+            .find_map(|key| arguments.get(*key).and_then(|v| v.as_str()))
+            // Line fragments point into the same file
+            .map(|file| file.split('#').next().unwrap_or(file).to_string());
+
+        let now = iso_timestamp_days_ago(0);
+        let mut entries = self.entries.lock();
+
+        // A file access is also an access to its repository
+        for file in std::iter::once(None).chain(file.map(Some)) {
+            let access = entries
+                .entry((repo.to_string(), file.clone()))
+                .or_insert_with(|| Access {
+                    repo: repo.to_string(),
+                    file,
+                    last_tool: String::new(),
+                    last_accessed: String::new(),
+                    count: 0,
+                });
+            access.last_tool = tool.to_string();
+            access.last_accessed = now.clone();
+            access.count += 1;
+        }
+
+        while entries.len() > MAX_ENTRIES {
+            let Some(oldest) = entries
+                .iter()
+                .min_by(|a, b| a.1.last_accessed.cmp(&b.1.last_accessed))
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            entries.remove(&oldest);
+        }
+
+        if let Some(path) = &self.path {
+            let snapshot: Vec<&Access> = entries.values().collect();
+            if let Err(e) = serde_json::to_string(&snapshot)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| Ok(fs::write(path, json)?))
+            {
+                eprintln!("Error saving access history to {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Accesses, most recent first
+    pub fn recent(&self) -> Vec<Access> {
+        let mut accesses: Vec<Access> = self.entries.lock().values().cloned().collect();
+        accesses.sort_by(|a, b| {
+            b.last_accessed
+                .cmp(&a.last_accessed)
+                .then_with(|| a.repo.cmp(&b.repo))
+                .then_with(|| a.file.cmp(&b.file))
+        });
+        accesses
+    }
+}
+
+pub struct RecentActivity {
+    history: Arc<AccessHistory>,
+}
+
+impl RecentActivity {
+    pub fn new(history: Arc<AccessHistory>) -> Self {
+        Self { history }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RecentActivity {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = arguments.unwrap_or_default();
+
+        let limit = usize_argument(&args, "limit").unwrap_or(DEFAULT_LIMIT);
+        let repo = args.get("repo").and_then(|v| v.as_str());
+        let kind = args.get("kind").and_then(|v| v.as_str()).unwrap_or("all");
+
+        let (repositories, files) = match kind {
+            "all" => (true, true),
+            "repositories" => (true, false),
+            "files" => (false, true),
+            other => {
+                return Err(anyhow!(
+                    "Invalid kind '{}': expected 'all', 'repositories' or 'files'",
+                    other
+                ));
+            }
+        };
+
+        let accesses: Vec<Access> = self
+            .history
+            .recent()
+            .into_iter()
+            .filter(|access| repo.is_none_or(|repo| access.repo == repo))
+            .filter(|access| match access.file {
+                Some(_) => files,
+                None => repositories,
+            })
+            .take(limit)
+            .collect();
+
+        if accesses.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: "No repositories or files accessed yet".to_string(),
+            }]);
+        }
+
+        let mut formatted_output = String::from("Recently accessed\n\n");
+        for access in accesses {
+            let target = match &access.file {
+                Some(file) => format!("{} {}", access.repo, file),
+                None => access.repo.clone(),
+            };
+            formatted_output.push_str(&format!(
+                "- {} — {} time(s), last with {} at {}\n",
+                target, access.count, access.last_tool, access.last_accessed
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        Tool {
+            name: "recent_activity".into(),
+            description: Some(
+                "List the repositories and files accessed through the other tools, most recent first, to return to something looked at earlier".into(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Optional repository identifier to restrict the list to (e.g., 'github:rust-lang/rust')"
+                    },
+                    "kind": {
+                        "type": "string",
+                        "enum": ["all", "repositories", "files"],
+                        "description": "Optional kind of entries listed. Default: all"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": format!("Optional number of entries to list. Default: {}", DEFAULT_LIMIT)
+                    }
+                }
+            }),
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::common::{
+    git_ref_argument, iso_timestamp_days_ago, repo_argument, required_arguments,
+    supported_providers, usize_argument,
+};
+
+const DEFAULT_LIMIT: usize = 20;
+pub(crate) const DEFAULT_COMMITS: usize = 50;
+/// Each analyzed commit costs one request, the hosts list at most 100 commits per page
+pub(crate) const MAX_COMMITS: usize = 100;
+const DEFAULT_RECENT_DAYS: u64 = 30;
+
+/// How often, and how heavily, a file changed over the analyzed commits
+#[derive(Default)]
+struct Hotspot {
+    commits: usize,
+    churn: usize,
+    recent_commits: usize,
+    recent_churn: usize,
+    last_changed: String,
+}
+
+pub struct RepositoryHotspots {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositoryHotspots {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositoryHotspots {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty());
+        let limit = usize_argument(&args, "limit").unwrap_or(DEFAULT_LIMIT);
+        let commit_count = usize_argument(&args, "commits")
+            .unwrap_or(DEFAULT_COMMITS)
+            .clamp(1, MAX_COMMITS);
+        let recent_days = usize_argument(&args, "recent_days")
+            .map(|days| days as u64)
+            .unwrap_or(DEFAULT_RECENT_DAYS);
+
+        let commits = provider
+            .list_commits(repo_path, git_ref, path, commit_count)
+            .await
+            .map_err(|e| anyhow!("Error listing commits: {}", e))?;
+
+        if commits.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("No commits found for {}", repo_path),
+            }]);
+        }
+
+        let details = join_all(
+            commits
+                .iter()
+                .map(|commit| provider.get_commit(repo_path, &commit.sha)),
+        )
+        .await;
+
+        // Dates are compared on their day, hosts disagree on offsets and fractional seconds
+        let recent_since = iso_timestamp_days_ago(recent_days);
+        let recent_since = &recent_since[..10];
+
+        let mut hotspots: BTreeMap<String, Hotspot> = BTreeMap::new();
+        let mut analyzed = 0;
+        for commit in details.into_iter().flatten() {
+            analyzed += 1;
+            let day = commit.commit.date.get(..10).unwrap_or(&commit.commit.date);
+            let recent = day >= recent_since;
+
+            for file in commit.files {
+                if path.is_some_and(|path| !file.path.starts_with(path)) {
+                    continue;
+                }
+
+                let churn = file.additions + file.deletions;
+                let hotspot = hotspots.entry(file.path).or_default();
+                hotspot.commits += 1;
+                hotspot.churn += churn;
+                if recent {
+                    hotspot.recent_commits += 1;
+                    hotspot.recent_churn += churn;
+                }
+                if hotspot.last_changed.as_str() < day {
+                    hotspot.last_changed = day.to_string();
+                }
+            }
+        }
+
+        if hotspots.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("No changed files found in the last {} commits", analyzed),
+            }]);
+        }
+
+        // Recent changes weigh more, a file rewritten last week says more than one churned a year ago
+        let mut ranked: Vec<(String, Hotspot)> = hotspots.into_iter().collect();
+        ranked.sort_by(|a, b| {
+            (b.1.commits + b.1.recent_commits)
+                .cmp(&(a.1.commits + a.1.recent_commits))
+                .then_with(|| b.1.churn.cmp(&a.1.churn))
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        let total = ranked.len();
+        ranked.truncate(limit);
+
+        let mut formatted_output = format!(
+            "Hotspots of {} over the last {} commits ({} files changed, recent = last {} days)\n\n",
+            repo_path, analyzed, total, recent_days
+        );
+        if analyzed < commits.len() {
+            formatted_output.push_str(&format!(
+                "Note: {} of {} commits could not be read\n\n",
+                commits.len() - analyzed,
+                commits.len()
+            ));
+        }
+
+        formatted_output.push_str(
+            "| File | Commits | Churn | Recent commits | Recent churn | Last changed |\n",
+        );
+        formatted_output.push_str("|---|---|---|---|---|---|\n");
+        for (path, hotspot) in ranked {
+            formatted_output.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                path,
+                hotspot.commits,
+                hotspot.churn,
+                hotspot.recent_commits,
+                hotspot.recent_churn,
+                hotspot.last_changed
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_hotspots".into(),
+            description: Some(format!(
+                "Rank files by how often and how heavily they changed in recent commits, pointing at the most volatile parts of a codebase. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Optional directory or file path to restrict the analysis to (e.g., 'src/')"
+                    },
+                    "commits": {
+                        "type": "integer",
+                        "description": format!("Optional number of most recent commits analyzed, at most {}. Default: {}", MAX_COMMITS, DEFAULT_COMMITS)
+                    },
+                    "recent_days": {
+                        "type": "integer",
+                        "description": format!("Optional number of days counted as recent churn. Default: {}", DEFAULT_RECENT_DAYS)
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": format!("Optional number of files listed. Default: {}", DEFAULT_LIMIT)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
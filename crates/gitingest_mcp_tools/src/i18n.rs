@@ -0,0 +1,457 @@
+//! Translations of the human-readable text the tools emit
+//!
+//! The language is chosen once for the whole server, from GITINGEST_LANGUAGE, since the
+//! output is read by models answering in that language. Repository content, identifiers and
+//! the tool descriptions advertised to clients are never translated. Errors and warnings
+//! raised by the providers and the HTTP middleware, below the tools, stay in English too.
+
+use std::{env, fmt::Display, sync::OnceLock};
+
+use anyhow::{Result, anyhow};
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+    German,
+    Spanish,
+}
+
+impl Language {
+    const ALL: [Language; 4] = [
+        Language::English,
+        Language::French,
+        Language::German,
+        Language::Spanish,
+    ];
+
+    /// ISO 639-1 code of the language
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::French => "fr",
+            Language::German => "de",
+            Language::Spanish => "es",
+        }
+    }
+
+    /// Parses a language code, ignoring any region and encoding (e.g., "fr", "de-AT",
+    /// "es_ES.UTF-8")
+    pub fn parse(value: &str) -> Result<Self> {
+        let code = value
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|language| language.code() == code)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unsupported language '{}'. Supported languages: {}",
+                    value,
+                    Self::ALL.map(Language::code).join(", ")
+                )
+            })
+    }
+
+    /// Reads `GITINGEST_LANGUAGE`, English unless set
+    pub fn from_env() -> Result<Self> {
+        match env::var("GITINGEST_LANGUAGE") {
+            Ok(value) if !value.trim().is_empty() => Self::parse(value.trim()),
+            _ => Ok(Self::default()),
+        }
+    }
+}
+
+/// Sets the language of the tools' output, which can only be chosen once per process
+pub fn set_language(language: Language) -> Result<()> {
+    LANGUAGE
+        .set(language)
+        .map_err(|_| anyhow!("The output language is already set"))
+}
+
+/// The language of the tools' output, English unless set
+pub(crate) fn language() -> Language {
+    LANGUAGE.get().copied().unwrap_or_default()
+}
+
+/// A translatable piece of output. Templates refer to their arguments as `{0}`, `{1}`, ...
+/// so translations can reorder them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Message {
+    // Argument errors shared by the tools
+    MissingArguments,
+    MissingRepository,
+    InvalidRepositoryFormat,
+    UnsupportedProvider,
+    MissingArgument,
+    UnknownPreset,
+
+    // Tools walking a whole repository
+    RepositoryTooLarge,
+    RepositoryTooLargeWarning,
+
+    // Tools reading many files
+    AnonymousLimit,
+
+    // Warnings handed to the client with the result
+    AnonymousLimitWarning,
+    ItemsLeftOut,
+    Files,
+    FileDiffs,
+    MaxMatchesReached,
+
+    // repository_read and repository_tree_view
+    ReadFileFailed,
+    NoWebInterface,
+    ReadTreeFailed,
+
+    // find_repositories
+    SearchResults,
+    NoRepositoriesFound,
+
+    // repository_info
+    Url,
+    DefaultBranch,
+    ProgrammingLanguage,
+    License,
+    Topics,
+    Stars,
+    Forks,
+    OpenIssues,
+    Size,
+    Created,
+    LastActivity,
+    Archived,
+    Template,
+    GeneratedFrom,
+    ForkedFrom,
+    Provenance,
+    Yes,
+    No,
+
+    // repository_tags
+    TagsOf,
+    NoTagsFound,
+    Annotated,
+    Lightweight,
+    Commit,
+    Tagger,
+    Date,
+    Signature,
+    SignatureNotReported,
+
+    // repository_grep
+    GrepSummary,
+    FileMatches,
+    CodeSearch,
+    TreeScan,
+    StoppedAtMaxMatches,
+    FilesNotSearched,
+    UnreadableFiles,
+}
+
+impl Message {
+    /// The templates of the message, in the order of [`Language::ALL`]
+    fn templates(self) -> [&'static str; 4] {
+        match self {
+            Message::MissingArguments => [
+                "Missing arguments",
+                "Arguments manquants",
+                "Fehlende Argumente",
+                "Faltan los argumentos",
+            ],
+            Message::MissingRepository => [
+                "Missing or invalid repository identifier",
+                "Identifiant de dépôt manquant ou invalide",
+                "Fehlende oder ungültige Repository-Kennung",
+                "Identificador de repositorio ausente o no válido",
+            ],
+            Message::InvalidRepositoryFormat => [
+                "Invalid repository format. Expected 'gitprovider:username/reponame'",
+                "Format de dépôt invalide. Format attendu : 'gitprovider:username/reponame'",
+                "Ungültiges Repository-Format. Erwartet: 'gitprovider:username/reponame'",
+                "Formato de repositorio no válido. Se esperaba 'gitprovider:username/reponame'",
+            ],
+            Message::UnsupportedProvider => [
+                "Git provider '{0}' is not supported. Supported providers: {1}",
+                "Le fournisseur Git '{0}' n'est pas pris en charge. Fournisseurs pris en charge : {1}",
+                "Der Git-Anbieter '{0}' wird nicht unterstützt. Unterstützte Anbieter: {1}",
+                "El proveedor Git '{0}' no es compatible. Proveedores compatibles: {1}",
+            ],
+            Message::MissingArgument => [
+                "Missing or invalid {0}",
+                "Argument {0} manquant ou invalide",
+                "Fehlendes oder ungültiges Argument {0}",
+                "Argumento {0} ausente o no válido",
+            ],
+            Message::UnknownPreset => [
+                "Unknown preset '{0}'. Available presets: {1}",
+                "Préréglage '{0}' inconnu. Préréglages disponibles : {1}",
+                "Unbekannte Vorgabe '{0}'. Verfügbare Vorgaben: {1}",
+                "Preajuste '{0}' desconocido. Preajustes disponibles: {1}",
+            ],
+            Message::RepositoryTooLarge => [
+                "{0} is {1} MB, above the {2} MB limit, so walking it may take very long. Narrow the patterns and pass force: true to continue anyway",
+                "{0} fait {1} Mo, au-delà de la limite de {2} Mo, son parcours peut donc être très long. Restreignez les motifs et passez force: true pour continuer malgré tout",
+                "{0} ist {1} MB groß und liegt damit über dem Limit von {2} MB, das Durchlaufen kann sehr lange dauern. Schränken Sie die Muster ein und übergeben Sie force: true, um trotzdem fortzufahren",
+                "{0} ocupa {1} MB, por encima del límite de {2} MB, así que recorrerlo puede tardar mucho. Restrinja los patrones y pase force: true para continuar de todos modos",
+            ],
+            Message::RepositoryTooLargeWarning => [
+                "Warning: {0} is {1} MB, above the {2} MB limit, the output may be slow and incomplete",
+                "Attention : {0} fait {1} Mo, au-delà de la limite de {2} Mo, le résultat peut être lent et incomplet",
+                "Warnung: {0} ist {1} MB groß und liegt damit über dem Limit von {2} MB, die Ausgabe kann langsam und unvollständig sein",
+                "Advertencia: {0} ocupa {1} MB, por encima del límite de {2} MB, el resultado puede ser lento e incompleto",
+            ],
+            Message::AnonymousLimit => [
+                "Only {0} of {1} files were read: {2} is called without a token, and its unauthenticated rate limit allows few calls. Configure a token, or raise max_files to read more",
+                "Seuls {0} des {1} fichiers ont été lus : {2} est appelé sans jeton, et sa limite de requêtes non authentifiées en autorise peu. Configurez un jeton, ou augmentez max_files pour en lire davantage",
+                "Nur {0} von {1} Dateien wurden gelesen: {2} wird ohne Token aufgerufen, und sein Limit für nicht authentifizierte Anfragen erlaubt nur wenige. Konfigurieren Sie ein Token oder erhöhen Sie max_files, um mehr zu lesen",
+                "Solo se leyeron {0} de {1} archivos: {2} se usa sin token, y su límite de solicitudes no autenticadas permite pocas. Configure un token o aumente max_files para leer más",
+            ],
+            Message::AnonymousLimitWarning => [
+                "Read {0} of {1} files, the default for {2} without a token",
+                "{0} fichiers lus sur {1}, la valeur par défaut pour {2} sans jeton",
+                "{0} von {1} Dateien gelesen, die Vorgabe für {2} ohne Token",
+                "Se leyeron {0} de {1} archivos, el valor predeterminado para {2} sin token",
+            ],
+            Message::ItemsLeftOut => [
+                "{0} of {1} {2} left out, raise {3} to include them",
+                "{0} {2} sur {1} laissés de côté, augmentez {3} pour les inclure",
+                "{0} von {1} {2} ausgelassen, erhöhen Sie {3}, um sie einzuschließen",
+                "{0} de {1} {2} omitidos, aumente {3} para incluirlos",
+            ],
+            Message::Files => ["files", "fichiers", "Dateien", "archivos"],
+            Message::FileDiffs => [
+                "file diffs",
+                "différences de fichiers",
+                "Dateiänderungen",
+                "diferencias de archivos",
+            ],
+            Message::MaxMatchesReached => [
+                "Stopped at {0} matches, raise max_matches to find more",
+                "Arrêté à {0} occurrences, augmentez max_matches pour en trouver davantage",
+                "Bei {0} Treffern abgebrochen, erhöhen Sie max_matches, um mehr zu finden",
+                "Detenido en {0} coincidencias, aumente max_matches para encontrar más",
+            ],
+            Message::ReadFileFailed => [
+                "Error getting file content: {0}",
+                "Erreur lors de la lecture du fichier : {0}",
+                "Fehler beim Lesen der Datei: {0}",
+                "Error al leer el archivo: {0}",
+            ],
+            Message::NoWebInterface => [
+                "Provider '{0}' has no web interface",
+                "Le fournisseur '{0}' n'a pas d'interface web",
+                "Der Anbieter '{0}' hat keine Weboberfläche",
+                "El proveedor '{0}' no tiene interfaz web",
+            ],
+            Message::ReadTreeFailed => [
+                "Error getting repository tree structure: {0}",
+                "Erreur lors de la lecture de l'arborescence du dépôt : {0}",
+                "Fehler beim Lesen der Verzeichnisstruktur des Repositorys: {0}",
+                "Error al leer la estructura del repositorio: {0}",
+            ],
+            Message::SearchResults => [
+                "Search results for: \"{0}\"",
+                "Résultats de recherche pour : \"{0}\"",
+                "Suchergebnisse für: \"{0}\"",
+                "Resultados de búsqueda para: \"{0}\"",
+            ],
+            Message::NoRepositoriesFound => [
+                "No repositories found matching query: \"{0}\"",
+                "Aucun dépôt ne correspond à la requête : \"{0}\"",
+                "Keine Repositories zur Suchanfrage gefunden: \"{0}\"",
+                "No se encontraron repositorios para la consulta: \"{0}\"",
+            ],
+            Message::Url => ["URL", "URL", "URL", "URL"],
+            Message::DefaultBranch => [
+                "Default branch",
+                "Branche par défaut",
+                "Standard-Branch",
+                "Rama predeterminada",
+            ],
+            Message::ProgrammingLanguage => ["Language", "Langage", "Sprache", "Lenguaje"],
+            Message::License => ["License", "Licence", "Lizenz", "Licencia"],
+            Message::Topics => ["Topics", "Sujets", "Themen", "Temas"],
+            Message::Stars => ["Stars", "Étoiles", "Sterne", "Estrellas"],
+            Message::Forks => ["Forks", "Forks", "Forks", "Forks"],
+            Message::OpenIssues => [
+                "Open issues",
+                "Tickets ouverts",
+                "Offene Issues",
+                "Incidencias abiertas",
+            ],
+            Message::Size => ["Size", "Taille", "Größe", "Tamaño"],
+            Message::Created => ["Created", "Créé le", "Erstellt", "Creado"],
+            Message::LastActivity => [
+                "Last activity",
+                "Dernière activité",
+                "Letzte Aktivität",
+                "Última actividad",
+            ],
+            Message::Archived => ["Archived", "Archivé", "Archiviert", "Archivado"],
+            Message::Template => ["Template", "Modèle", "Vorlage", "Plantilla"],
+            Message::GeneratedFrom => [
+                "Generated from template",
+                "Généré à partir du modèle",
+                "Aus Vorlage erstellt",
+                "Generado a partir de la plantilla",
+            ],
+            Message::ForkedFrom => ["Forked from", "Fork de", "Fork von", "Bifurcado de"],
+            Message::Provenance => ["Provenance", "Provenance", "Herkunft", "Procedencia"],
+            Message::Yes => ["yes", "oui", "ja", "sí"],
+            Message::No => ["no", "non", "nein", "no"],
+            Message::TagsOf => [
+                "Tags of {0} ({1})",
+                "Tags de {0} ({1})",
+                "Tags von {0} ({1})",
+                "Etiquetas de {0} ({1})",
+            ],
+            Message::NoTagsFound => [
+                "No tags found for {0}",
+                "Aucun tag trouvé pour {0}",
+                "Keine Tags für {0} gefunden",
+                "No se encontraron etiquetas para {0}",
+            ],
+            Message::Annotated => ["annotated", "annoté", "annotiert", "anotada"],
+            Message::Lightweight => ["lightweight", "léger", "leichtgewichtig", "ligera"],
+            Message::Commit => ["commit", "commit", "Commit", "commit"],
+            Message::Tagger => ["Tagger", "Auteur du tag", "Tagger", "Autor de la etiqueta"],
+            Message::Date => ["Date", "Date", "Datum", "Fecha"],
+            Message::Signature => ["Signature", "Signature", "Signatur", "Firma"],
+            Message::SignatureNotReported => [
+                "not reported by the host",
+                "non communiquée par l'hébergeur",
+                "vom Host nicht gemeldet",
+                "no informada por el servidor",
+            ],
+            Message::GrepSummary => [
+                "{0} matches of '{1}' in {2} files of {3} ({4} files searched by {5})",
+                "{0} occurrences de '{1}' dans {2} fichiers de {3} ({4} fichiers parcourus par {5})",
+                "{0} Treffer für '{1}' in {2} Dateien von {3} ({4} Dateien durchsucht per {5})",
+                "{0} coincidencias de '{1}' en {2} archivos de {3} ({4} archivos buscados mediante {5})",
+            ],
+            Message::FileMatches => [
+                "{0} matches",
+                "{0} occurrences",
+                "{0} Treffer",
+                "{0} coincidencias",
+            ],
+            Message::CodeSearch => [
+                "code search",
+                "recherche de code",
+                "Codesuche",
+                "búsqueda de código",
+            ],
+            Message::TreeScan => [
+                "tree scan",
+                "parcours de l'arborescence",
+                "Durchlauf des Verzeichnisbaums",
+                "recorrido del árbol",
+            ],
+            Message::StoppedAtMaxMatches => [
+                "Stopped at max_matches, raise it or narrow the patterns",
+                "Arrêté à max_matches, augmentez-le ou restreignez les motifs",
+                "Bei max_matches abgebrochen, erhöhen Sie den Wert oder schränken Sie die Muster ein",
+                "Detenido en max_matches, auméntelo o restrinja los patrones",
+            ],
+            Message::FilesNotSearched => [
+                "Only {0} of {1} files were searched, raise max_files or narrow the patterns",
+                "Seuls {0} des {1} fichiers ont été parcourus, augmentez max_files ou restreignez les motifs",
+                "Nur {0} von {1} Dateien wurden durchsucht, erhöhen Sie max_files oder schränken Sie die Muster ein",
+                "Solo se buscaron {0} de {1} archivos, aumente max_files o restrinja los patrones",
+            ],
+            Message::UnreadableFiles => [
+                "{0} files could not be read",
+                "{0} fichiers n'ont pas pu être lus",
+                "{0} Dateien konnten nicht gelesen werden",
+                "No se pudieron leer {0} archivos",
+            ],
+        }
+    }
+
+    /// The message in the configured language, with its arguments filled in
+    pub(crate) fn text(self, args: &[&dyn Display]) -> String {
+        self.text_in(language(), args)
+    }
+
+    /// The message in `language`, with its arguments filled in
+    fn text_in(self, language: Language, args: &[&dyn Display]) -> String {
+        let index = Language::ALL
+            .iter()
+            .position(|candidate| *candidate == language)
+            .unwrap_or_default();
+        fill(self.templates()[index], args)
+    }
+}
+
+/// Replaces the `{n}` placeholders of a template with the matching arguments, in one pass so
+/// arguments containing braces are left alone
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let placeholder = rest[start + 1..].split_once('}').and_then(|(index, _)| {
+            index
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| args.get(i))
+                .map(|arg| (index.len(), arg))
+        });
+        match placeholder {
+            Some((length, arg)) => {
+                output.push_str(&arg.to_string());
+                rest = &rest[start + length + 2..];
+            }
+            None => {
+                output.push('{');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_filled_in_any_order() {
+        assert_eq!(fill("{1} before {0}", &[&"a", &2]), "2 before a");
+        assert_eq!(fill("{0} and {0}", &[&"twice"]), "twice and twice");
+        // Braces in arguments, unknown placeholders and stray braces are kept as they are
+        assert_eq!(fill("{0}", &[&"{1}"]), "{1}");
+        assert_eq!(fill("{2} {x} {", &[&"a"]), "{2} {x} {");
+    }
+
+    #[test]
+    fn languages_are_parsed_from_locale_codes() {
+        assert_eq!(Language::parse("fr").unwrap(), Language::French);
+        assert_eq!(Language::parse("de-AT").unwrap(), Language::German);
+        assert_eq!(Language::parse("es_ES.UTF-8").unwrap(), Language::Spanish);
+        assert_eq!(Language::parse("EN").unwrap(), Language::English);
+        assert!(Language::parse("it").is_err());
+    }
+
+    #[test]
+    fn messages_are_written_in_the_chosen_language() {
+        let args: [&dyn Display; 2] = [&"gitee", &"github"];
+        assert_eq!(
+            Message::UnsupportedProvider.text_in(Language::English, &args),
+            "Git provider 'gitee' is not supported. Supported providers: github"
+        );
+        assert_eq!(
+            Message::UnsupportedProvider.text_in(Language::German, &args),
+            "Der Git-Anbieter 'gitee' wird nicht unterstützt. Unterstützte Anbieter: github"
+        );
+        assert_eq!(Message::Yes.text_in(Language::French, &[]), "oui");
+    }
+}
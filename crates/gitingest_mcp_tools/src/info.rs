@@ -0,0 +1,191 @@
+use std::{fmt::Display, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::{
+    common::{repo_argument, required_arguments, supported_providers},
+    i18n::Message,
+};
+
+pub struct RepositoryInfo {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositoryInfo {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositoryInfo {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+
+        let info = provider
+            .get_repository_info(repo_path)
+            .await
+            .map_err(|e| anyhow!("Error getting repository info: {}", e))?;
+
+        let mut formatted_output = format!("# {}\n\n", info.full_name);
+        if let Some(description) = &info.description {
+            formatted_output.push_str(&format!("{}\n\n", description.trim()));
+        }
+
+        let line =
+            |label: Message, value: &dyn Display| format!("- {}: {}\n", label.text(&[]), value);
+        formatted_output.push_str(&line(Message::Url, &info.url));
+        if let Some(default_branch) = &info.default_branch {
+            formatted_output.push_str(&line(Message::DefaultBranch, default_branch));
+        }
+        if let Some(language) = &info.language {
+            formatted_output.push_str(&line(Message::ProgrammingLanguage, language));
+        }
+        if let Some(license) = &info.license {
+            formatted_output.push_str(&line(Message::License, license));
+        }
+        if !info.topics.is_empty() {
+            formatted_output.push_str(&line(Message::Topics, &info.topics.join(", ")));
+        }
+        formatted_output.push_str(&line(Message::Stars, &info.stars));
+        formatted_output.push_str(&line(Message::Forks, &info.forks));
+        if let Some(open_issues) = info.open_issues {
+            formatted_output.push_str(&line(Message::OpenIssues, &open_issues));
+        }
+        if let Some(size_kb) = info.size_kb {
+            formatted_output.push_str(&line(Message::Size, &format!("{} KB", size_kb)));
+        }
+        formatted_output.push_str(&line(Message::Created, &info.created_at));
+        if let Some(last_activity_at) = &info.last_activity_at {
+            formatted_output.push_str(&line(Message::LastActivity, last_activity_at));
+        }
+        if info.archived {
+            formatted_output.push_str(&line(Message::Archived, &Message::Yes.text(&[])));
+        }
+
+        // Template and fork origins tell whether the code is boilerplate or someone's own work
+        let is_template = if info.is_template {
+            Message::Yes
+        } else {
+            Message::No
+        };
+        formatted_output.push_str(&line(Message::Template, &is_template.text(&[])));
+        if let Some(generated_from) = &info.generated_from {
+            formatted_output.push_str(&line(
+                Message::GeneratedFrom,
+                &format!("{}:{}", provider.name(), generated_from),
+            ));
+        }
+        if let Some(forked_from) = &info.forked_from {
+            formatted_output.push_str(&line(
+                Message::ForkedFrom,
+                &format!("{}:{}", provider.name(), forked_from),
+            ));
+        }
+        // Issues and contributions belong upstream when the repository is only a copy
+        if let Some(mirror) = &info.mirror {
+            formatted_output.push_str(&line(Message::Provenance, &mirror.describe()));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_info".into(),
+            description: Some(format!(
+                "Get a repository's metadata: description, license, popularity, and whether it is a template, was generated from one, is a fork, or an automated mirror along with its likely canonical origin. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+/// The same metadata as [`RepositoryInfo`] as a JSON object, for clients that read fields
+/// rather than prose
+pub struct RepositorySummary {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositorySummary {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositorySummary {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+
+        let info = provider
+            .get_repository_info(repo_path)
+            .await
+            .map_err(|e| anyhow!("Error getting repository info: {}", e))?;
+
+        // Hosts without a notion of pushes report their last activity of any kind instead
+        let summary = json!({
+            "repo": format!("{}:{}", provider.name(), info.full_name),
+            "description": info.description.as_deref().map(str::trim),
+            "url": info.url,
+            "default_branch": info.default_branch,
+            "stars": info.stars,
+            "forks": info.forks,
+            "open_issues": info.open_issues,
+            "language": info.language,
+            "license": info.license,
+            "topics": info.topics,
+            "archived": info.archived,
+            "created_at": info.created_at,
+            "last_push_at": info.last_activity_at,
+        });
+
+        Ok(vec![ToolContent::Text {
+            text: serde_json::to_string_pretty(&summary)?,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_summary".into(),
+            description: Some(format!(
+                "Get a repository's metadata as a JSON object: description, default branch, stars, forks, open issues, language, license, topics, archived flag, and creation and last push dates. Fields a host does not disclose are null. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::{GitProvider, IssueFilter};
+use serde_json::{Value, json};
+
+use crate::common::{
+    list_argument, repo_argument, required_arguments, string_argument, supported_providers,
+    usize_argument,
+};
+
+const DEFAULT_PER_PAGE: usize = 30;
+
+const STATES: &[&str] = &["open", "closed", "all"];
+
+/// Longest part of an issue description shown in listings, in characters
+const EXCERPT_LENGTH: usize = 160;
+
+/// The start of an issue description, on one line
+fn excerpt(body: &str) -> String {
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    match body.char_indices().nth(EXCERPT_LENGTH) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body,
+    }
+}
+
+pub struct ListIssues {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ListIssues {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ListIssues {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let state = string_argument(&args, "state").unwrap_or("open");
+        if !STATES.contains(&state) {
+            return Err(anyhow!(
+                "Invalid state '{}', expected one of: {}",
+                state,
+                STATES.join(", ")
+            ));
+        }
+        let filter = IssueFilter {
+            state: state.to_string(),
+            labels: list_argument(&args, "labels"),
+            assignee: string_argument(&args, "assignee").ok().map(String::from),
+        };
+        let page = usize_argument(&args, "page").unwrap_or(1).max(1);
+        let per_page = usize_argument(&args, "per_page")
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, 100);
+
+        let issues = provider
+            .list_issues(repo_path, &filter, page, per_page)
+            .await
+            .map_err(|e| anyhow!("Error listing issues: {}", e))?;
+
+        if issues.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!(
+                    "No {} issues found for {} on page {}",
+                    state, repo_path, page
+                ),
+            }]);
+        }
+
+        let mut formatted_output = format!(
+            "{} {} issues of {}, page {}, most recently updated first\n\n",
+            issues.len(),
+            state,
+            repo_path,
+            page
+        );
+        for issue in &issues {
+            formatted_output.push_str(&format!("- #{} {}", issue.number, issue.title));
+            if state != "open" {
+                formatted_output.push_str(&format!(" [{}]", issue.state));
+            }
+            formatted_output.push('\n');
+            if !issue.labels.is_empty() {
+                formatted_output.push_str(&format!("  labels: {}\n", issue.labels.join(", ")));
+            }
+            if let Some(body) = &issue.body {
+                formatted_output.push_str(&format!("  {}\n", excerpt(body)));
+            }
+        }
+
+        // Pages can come back short before the last one, GitHub leaving pull requests out
+        formatted_output.push_str(&format!("\nNext issues: page {}", page + 1));
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output.trim_end().to_string(),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "list_issues".into(),
+            description: Some(format!(
+                "List the issues of a repository with their number, title, labels and the start of their description, filtered by state, labels and assignee, one page at a time. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "state": {
+                        "type": "string",
+                        "enum": STATES,
+                        "description": "Optional state of the issues listed. Default: open"
+                    },
+                    "labels": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of labels every issue listed carries"
+                    },
+                    "assignee": {
+                        "type": "string",
+                        "description": "Optional username every issue listed is assigned to"
+                    },
+                    "page": {
+                        "type": "integer",
+                        "description": "Optional page of results, starting at 1. Default: 1"
+                    },
+                    "per_page": {
+                        "type": "integer",
+                        "description": format!("Optional number of issues per page, at most 100. Default: {}", DEFAULT_PER_PAGE)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+pub struct ReadIssue {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ReadIssue {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ReadIssue {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let number = usize_argument(&args, "number")
+            .ok_or_else(|| anyhow!("Missing or invalid number"))? as u64;
+
+        let (issue, comments) = futures::try_join!(
+            provider.get_issue(repo_path, number),
+            provider.list_issue_comments(repo_path, number),
+        )
+        .map_err(|e| anyhow!("Error reading issue #{}: {}", number, e))?;
+
+        let mut formatted_output = format!("# #{} {}\n\n", issue.number, issue.title);
+        formatted_output.push_str(&format!(
+            "State: {}\nAuthor: {}, opened {}\n",
+            issue.state, issue.author, issue.created_at
+        ));
+        if !issue.labels.is_empty() {
+            formatted_output.push_str(&format!("Labels: {}\n", issue.labels.join(", ")));
+        }
+        formatted_output.push_str(&format!("URL: {}\n", issue.url));
+
+        formatted_output.push_str("\n## Description\n\n");
+        match issue.body.as_deref().map(str::trim) {
+            Some(body) if !body.is_empty() => formatted_output.push_str(body),
+            _ => formatted_output.push_str("(no description)"),
+        }
+        formatted_output.push('\n');
+
+        formatted_output.push_str(&format!("\n## Comments ({})\n", comments.len()));
+        for comment in &comments {
+            let edited = match &comment.updated_at {
+                Some(updated_at) => format!(", edited {}", updated_at),
+                None => String::new(),
+            };
+            formatted_output.push_str(&format!(
+                "\n**{}**, {}{}\n\n{}\n",
+                comment.author,
+                comment.created_at,
+                edited,
+                comment.body.trim()
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output.trim_end().to_string(),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "read_issue".into(),
+            description: Some(format!(
+                "Read an issue in one call: its description, labels and state, followed by its comment thread with the author and date of every comment. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "number": {
+                        "type": "integer",
+                        "description": "Number of the issue (IID on GitLab)"
+                    }
+                },
+                "required": ["repo", "number"]
+            }),
+        }
+    }
+}
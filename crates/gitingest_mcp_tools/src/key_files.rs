@@ -0,0 +1,343 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{GitProvider, RepoItem, is_readme};
+use serde_json::{Value, json};
+
+use crate::common::{
+    git_ref_argument, preset_names, repo_argument, required_arguments, supported_providers,
+    tree_options_argument, usize_argument,
+};
+
+const DEFAULT_LIMIT: usize = 20;
+const DEFAULT_RECENT_COMMITS: usize = 10;
+const LARGEST_MODULES: usize = 10;
+/// Source files smaller than this are not called large, whatever their rank
+const LARGE_MODULE_BYTES: u64 = 8 * 1024;
+
+/// File names that usually start a program or a library
+pub(crate) const ENTRY_POINTS: &[&str] = &[
+    "main.rs",
+    "lib.rs",
+    "main.go",
+    "main.py",
+    "__main__.py",
+    "app.py",
+    "manage.py",
+    "index.js",
+    "index.ts",
+    "main.js",
+    "main.ts",
+    "server.js",
+    "server.ts",
+    "app.js",
+    "app.ts",
+    "main.c",
+    "main.cpp",
+    "Main.java",
+    "Program.cs",
+    "main.swift",
+    "main.kt",
+    "main.dart",
+];
+
+/// Manifests and build configuration describing how a project is put together
+pub(crate) const CONFIG_FILES: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "setup.py",
+    "setup.cfg",
+    "requirements.txt",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "CMakeLists.txt",
+    "Makefile",
+    "justfile",
+    "Dockerfile",
+    "docker-compose.yml",
+    "docker-compose.yaml",
+    "tsconfig.json",
+    "Gemfile",
+    "composer.json",
+    "mix.exs",
+    "deno.json",
+    "flake.nix",
+];
+
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "go", "py", "js", "jsx", "ts", "tsx", "java", "kt", "c", "h", "cc", "cpp", "hpp", "cs",
+    "rb", "php", "swift", "scala", "ex", "exs", "dart", "zig", "lua",
+];
+
+fn depth(path: &str) -> usize {
+    path.matches('/').count()
+}
+
+fn is_source(path: &str) -> bool {
+    path.rsplit_once('.')
+        .is_some_and(|(_, extension)| SOURCE_EXTENSIONS.contains(&extension))
+}
+
+/// The largest source files, biggest first, among the ones past `LARGE_MODULE_BYTES` when the
+/// provider reports sizes
+fn large_modules(files: &[RepoItem]) -> Vec<&RepoItem> {
+    let mut sources: Vec<&RepoItem> = files
+        .iter()
+        .filter(|file| {
+            file.size.is_some_and(|size| size >= LARGE_MODULE_BYTES) && is_source(&file.path)
+        })
+        .collect();
+    sources.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    sources.truncate(LARGEST_MODULES);
+    sources
+}
+
+/// Accumulated evidence that a file matters
+#[derive(Default)]
+struct Candidate {
+    score: i64,
+    reasons: Vec<String>,
+}
+
+impl Candidate {
+    fn add(&mut self, score: i64, reason: impl Into<String>) {
+        self.score += score;
+        self.reasons.push(reason.into());
+    }
+}
+
+pub struct RepositoryKeyFiles {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositoryKeyFiles {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositoryKeyFiles {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let limit = usize_argument(&args, "limit").unwrap_or(DEFAULT_LIMIT);
+        let max_depth = usize_argument(&args, "max_depth");
+        let recent_commits =
+            usize_argument(&args, "recent_commits").unwrap_or(DEFAULT_RECENT_COMMITS);
+
+        let options = tree_options_argument(&args)?;
+
+        let files: Vec<RepoItem> = provider
+            .list_files(repo_path, git_ref.clone(), &options)
+            .await
+            .map_err(|e| anyhow!("Error listing repository files: {}", e))?
+            .into_iter()
+            .filter(|file| max_depth.is_none_or(|max_depth| depth(&file.path) <= max_depth))
+            .collect();
+
+        let mut candidates: BTreeMap<&str, Candidate> = BTreeMap::new();
+
+        for file in &files {
+            let name = file.name.as_str();
+
+            if ENTRY_POINTS.contains(&name) {
+                candidates
+                    .entry(&file.path)
+                    .or_default()
+                    .add(50, "entry point");
+            }
+            if CONFIG_FILES.contains(&name) {
+                candidates
+                    .entry(&file.path)
+                    .or_default()
+                    .add(40, "project configuration");
+            }
+            if is_readme(name) && depth(&file.path) == 0 {
+                candidates
+                    .entry(&file.path)
+                    .or_default()
+                    .add(30, "project README");
+            }
+        }
+
+        for (rank, file) in large_modules(&files).into_iter().enumerate() {
+            candidates.entry(&file.path).or_default().add(
+                20 - rank as i64,
+                format!("large module ({} bytes)", file.size.unwrap_or_default()),
+            );
+        }
+
+        // Files touched by the most recent commits
+        let mut change_counts: HashMap<String, usize> = HashMap::new();
+        if recent_commits > 0 {
+            let commits = provider
+                .list_commits(repo_path, git_ref, None, recent_commits)
+                .await
+                .unwrap_or_default();
+
+            let details = join_all(
+                commits
+                    .iter()
+                    .map(|commit| provider.get_commit(repo_path, &commit.sha)),
+            )
+            .await;
+
+            for commit in details.into_iter().flatten() {
+                for file in commit.files {
+                    *change_counts.entry(file.path).or_default() += 1;
+                }
+            }
+        }
+
+        for file in &files {
+            if let Some(count) = change_counts.get(&file.path) {
+                candidates.entry(&file.path).or_default().add(
+                    (*count as i64 * 10).min(30),
+                    format!(
+                        "changed in {} of the last {} commits",
+                        count, recent_commits
+                    ),
+                );
+            }
+        }
+
+        // Shallow files are easier to build a mental model from
+        let mut ranked: Vec<(&str, Candidate)> = candidates
+            .into_iter()
+            .map(|(path, mut candidate)| {
+                candidate.score -= 5 * depth(path) as i64;
+                (path, candidate)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.score.cmp(&a.1.score).then_with(|| a.0.cmp(b.0)));
+        ranked.truncate(limit);
+
+        if ranked.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("No key files found for {}", repo_path),
+            }]);
+        }
+
+        let mut formatted_output = format!(
+            "Key files of {} ({} files considered)\n\n",
+            repo_path,
+            files.len()
+        );
+        for (index, (path, candidate)) in ranked.iter().enumerate() {
+            formatted_output.push_str(&format!(
+                "{}. {} — {}\n",
+                index + 1,
+                path,
+                candidate.reasons.join(", ")
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_key_files".into(),
+            description: Some(format!(
+                "Rank the files of a Git repository most worth reading first: entry points, main configuration, largest modules and recently changed files. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": format!("Optional number of files to return. Default: {}", DEFAULT_LIMIT)
+                    },
+                    "max_depth": {
+                        "type": "integer",
+                        "description": "Optional maximum directory depth of the files considered, 0 being the repository root. Default: unlimited"
+                    },
+                    "recent_commits": {
+                        "type": "integer",
+                        "description": format!("Optional number of recent commits inspected for changed files, 0 to skip. Default: {}", DEFAULT_RECENT_COMMITS)
+                    },
+                    "exclude_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to exclude"
+                    },
+                    "include_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to include"
+                    },
+                    "preset": {
+                        "type": "string",
+                        "description": format!("Optional comma-separated list of ecosystem presets adding their source and exclude patterns: {}", preset_names())
+                    },
+                    "extensions": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of file extensions to include, e.g. 'rs,toml,md'"
+                    },
+                    "case_insensitive": {
+                        "type": "boolean",
+                        "description": "Optional flag to match every pattern regardless of case. Default: false"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git_provider::RepoItemType;
+
+    use super::*;
+
+    fn file(path: &str, size: Option<u64>) -> RepoItem {
+        RepoItem {
+            name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            path: path.to_string(),
+            item_type: RepoItemType::File,
+            size,
+            sha: None,
+        }
+    }
+
+    #[test]
+    fn only_sources_past_the_threshold_are_large() {
+        let files = [
+            file("src/small.rs", Some(LARGE_MODULE_BYTES - 1)),
+            file("src/parser.rs", Some(LARGE_MODULE_BYTES)),
+            file("src/engine.rs", Some(LARGE_MODULE_BYTES * 4)),
+            file("assets/logo.png", Some(LARGE_MODULE_BYTES * 8)),
+            file("src/unknown.rs", None),
+        ];
+
+        let paths: Vec<&str> = large_modules(&files)
+            .into_iter()
+            .map(|file| file.path.as_str())
+            .collect();
+        assert_eq!(paths, ["src/engine.rs", "src/parser.rs"]);
+    }
+}
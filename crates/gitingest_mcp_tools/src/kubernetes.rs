@@ -0,0 +1,734 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{GitProvider, TreeOptions};
+use regex::Regex;
+use serde_json::{Value, json};
+
+use crate::common::{
+    git_ref_argument, repo_argument, required_arguments, supported_providers, usize_argument,
+};
+
+pub(crate) const DEFAULT_MAX_FILES: usize = 200;
+
+/// Directories whose YAML files are not deployed, or are not the project's
+const SKIPPED_DIRECTORIES: &[&str] = &[".github", "node_modules", "vendor", "third_party"];
+
+/// The files of a Helm chart, found next to its Chart.yaml
+#[derive(Default)]
+struct Chart<'a> {
+    directory: &'a str,
+    metadata: Option<Value>,
+    values: Option<Value>,
+    schema: Option<Value>,
+    /// Template file names with the kinds they declare
+    templates: Vec<(&'a str, Vec<String>)>,
+}
+
+/// A resource declared by a manifest
+struct Resource {
+    kind: String,
+    name: String,
+    namespace: Option<String>,
+    images: Vec<String>,
+    /// The chart a HelmRelease or Argo CD Application deploys
+    release: Option<String>,
+}
+
+/// Image references below a value, with the dotted path of their key. An `image` key holds
+/// either a reference or, in Helm values, its `registry`, `repository` and `tag` parts.
+fn collect_images(value: &Value, path: &str, images: &mut Vec<(String, String)>) {
+    let child_path = |key: &str| match path {
+        "" => key.to_string(),
+        path => format!("{}.{}", path, key),
+    };
+
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                if key == "image" {
+                    match field {
+                        Value::String(image) if !image.is_empty() => {
+                            images.push((child_path(key), image.clone()));
+                            continue;
+                        }
+                        Value::Object(parts) => {
+                            let part = |name: &str| parts.get(name).and_then(|v| v.as_str());
+                            if let Some(repository) = part("repository") {
+                                let mut image = match part("registry") {
+                                    Some(registry) if !registry.is_empty() => {
+                                        format!("{}/{}", registry, repository)
+                                    }
+                                    _ => repository.to_string(),
+                                };
+                                match parts.get("tag") {
+                                    Some(Value::String(tag)) if !tag.is_empty() => {
+                                        image.push_str(&format!(":{}", tag))
+                                    }
+                                    Some(Value::Number(tag)) => {
+                                        image.push_str(&format!(":{}", tag))
+                                    }
+                                    _ => {}
+                                }
+                                images.push((child_path(key), image));
+                                continue;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                collect_images(field, &child_path(key), images);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                collect_images(item, &format!("{}[{}]", path, index), images);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The documents of a YAML stream, skipping those that do not parse, such as templates. Each
+/// is parsed on its own, as a stream stops at its first syntax error
+fn yaml_documents(content: &str) -> Vec<Value> {
+    let mut documents = vec![String::new()];
+    for line in content.lines() {
+        if line.trim_end() == "---" || line.starts_with("--- ") {
+            documents.push(String::new());
+        } else if let Some(document) = documents.last_mut() {
+            document.push_str(line);
+            document.push('\n');
+        }
+    }
+
+    documents
+        .iter()
+        .filter_map(|document| serde_yaml::from_str::<Value>(document).ok())
+        .filter(|document| !document.is_null())
+        .collect()
+}
+
+/// The resources of a manifest, expanding `List` documents
+fn manifest_resources(documents: &[Value]) -> Vec<Resource> {
+    let mut resources = Vec::new();
+    for document in documents {
+        if document.get("apiVersion").is_none() {
+            continue;
+        }
+        let Some(kind) = document.get("kind").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        if kind == "List" {
+            if let Some(items) = document.get("items").and_then(|v| v.as_array()) {
+                resources.extend(manifest_resources(items));
+            }
+            continue;
+        }
+
+        let metadata = |key: &str| {
+            document
+                .pointer(&format!("/metadata/{}", key))
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        };
+
+        let mut images = Vec::new();
+        collect_images(
+            document.get("spec").unwrap_or(&Value::Null),
+            "",
+            &mut images,
+        );
+        let images = images
+            .into_iter()
+            .fold(Vec::new(), |mut unique, (_, image)| {
+                if !unique.contains(&image) {
+                    unique.push(image);
+                }
+                unique
+            });
+
+        let field = |pointer: &str| document.pointer(pointer).and_then(|v| v.as_str());
+        let release = match kind {
+            // Flux
+            "HelmRelease" => field("/spec/chart/spec/chart").map(|chart| {
+                match field("/spec/chart/spec/version") {
+                    Some(version) => format!("{} {}", chart, version),
+                    None => chart.to_string(),
+                }
+            }),
+            // Argo CD
+            "Application" => {
+                let source = |key: &str| field(&format!("/spec/source/{}", key));
+                match (
+                    source("repoURL"),
+                    source("chart").or_else(|| source("path")),
+                ) {
+                    (Some(repository), Some(chart)) => {
+                        Some(format!("{} from {}", chart, repository))
+                    }
+                    (Some(repository), None) => Some(repository.to_string()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        resources.push(Resource {
+            kind: kind.to_string(),
+            name: metadata("name")
+                .or_else(|| metadata("generateName"))
+                .unwrap_or_else(|| "(unnamed)".to_string()),
+            namespace: metadata("namespace"),
+            images,
+            release,
+        });
+    }
+    resources
+}
+
+/// The leaves of a JSON schema's properties, with their type and whether they are required
+fn schema_properties(schema: &Value, path: &str, properties: &mut Vec<String>) {
+    let required: BTreeSet<&str> = schema
+        .get("required")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .collect();
+
+    let Some(fields) = schema.get("properties").and_then(|v| v.as_object()) else {
+        return;
+    };
+    for (name, field) in fields {
+        let field_path = match path {
+            "" => name.clone(),
+            path => format!("{}.{}", path, name),
+        };
+        if field.get("properties").is_some() {
+            schema_properties(field, &field_path, properties);
+            continue;
+        }
+
+        let field_type = match field.get("type") {
+            Some(Value::String(field_type)) => field_type.clone(),
+            Some(Value::Array(types)) => types
+                .iter()
+                .filter_map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(" | "),
+            _ => "any".to_string(),
+        };
+        properties.push(format!(
+            "{}: {}{}",
+            field_path,
+            field_type,
+            if required.contains(name.as_str()) {
+                " (required)"
+            } else {
+                ""
+            }
+        ));
+    }
+}
+
+/// How a YAML or JSON file is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileRole {
+    ChartMetadata,
+    ChartValues,
+    ChartSchema,
+    ChartTemplate,
+    Helmfile,
+    Manifest,
+}
+
+pub struct KubernetesInventory {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl KubernetesInventory {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for KubernetesInventory {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let max_files = usize_argument(&args, "max_files").unwrap_or(DEFAULT_MAX_FILES);
+
+        let mut files = provider
+            .list_files(repo_path, git_ref.clone(), &TreeOptions::default())
+            .await
+            .map_err(|e| anyhow!("Error listing files: {}", e))?;
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let chart_directories: Vec<&str> = files
+            .iter()
+            .filter_map(|file| match file.path.rsplit_once('/') {
+                Some((directory, "Chart.yaml")) => Some(directory),
+                None if file.path == "Chart.yaml" => Some(""),
+                _ => None,
+            })
+            .collect();
+        // The innermost chart owns a file, subcharts being nested in their parent's charts/
+        let chart_of = |path: &str| {
+            chart_directories
+                .iter()
+                .filter(|directory| {
+                    directory.is_empty() || path.starts_with(&format!("{}/", directory))
+                })
+                .max_by_key(|directory| directory.len())
+                .copied()
+        };
+
+        let mut roles: Vec<(&str, FileRole)> = Vec::new();
+        let mut manifests = 0;
+        for file in &files {
+            let path = file.path.as_str();
+            if path
+                .split('/')
+                .any(|segment| SKIPPED_DIRECTORIES.contains(&segment))
+            {
+                continue;
+            }
+            let name = path.rsplit('/').next().unwrap_or(path);
+            let is_yaml = name.ends_with(".yaml") || name.ends_with(".yml");
+
+            let role = match chart_of(path) {
+                Some(chart) => {
+                    let relative = path[chart.len()..].trim_start_matches('/');
+                    match relative {
+                        "Chart.yaml" => Some(FileRole::ChartMetadata),
+                        "values.yaml" => Some(FileRole::ChartValues),
+                        "values.schema.json" => Some(FileRole::ChartSchema),
+                        _ if relative.starts_with("templates/") && is_yaml => {
+                            Some(FileRole::ChartTemplate)
+                        }
+                        _ => None,
+                    }
+                }
+                None if matches!(name, "helmfile.yaml" | "helmfile.yaml.gotmpl") => {
+                    Some(FileRole::Helmfile)
+                }
+                None if is_yaml && manifests < max_files => {
+                    manifests += 1;
+                    Some(FileRole::Manifest)
+                }
+                None => None,
+            };
+            if let Some(role) = role {
+                roles.push((path, role));
+            }
+        }
+
+        let contents = join_all(
+            roles
+                .iter()
+                .map(|(path, _)| provider.get_file_content(repo_path, path, git_ref.clone())),
+        )
+        .await;
+
+        // Templates do not parse as YAML, their kinds are read from the lines declaring them
+        let kind_line = Regex::new(r"(?m)^kind:\s*([A-Za-z]+)\s*$")?;
+
+        let mut charts: BTreeMap<&str, Chart> = chart_directories
+            .iter()
+            .map(|&directory| {
+                (
+                    directory,
+                    Chart {
+                        directory,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+        let mut releases: Vec<(String, &str)> = Vec::new();
+        let mut resources: Vec<(&str, Vec<Resource>)> = Vec::new();
+        // Files referencing each image
+        let mut images: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+        for (&(path, role), content) in roles.iter().zip(contents) {
+            let Ok(content) = content else {
+                continue;
+            };
+
+            let chart = chart_of(path).and_then(|directory| charts.get_mut(directory));
+            match (role, chart) {
+                (FileRole::ChartMetadata, Some(chart)) => {
+                    chart.metadata = serde_yaml::from_str(&content).ok();
+                }
+                (FileRole::ChartValues, Some(chart)) => {
+                    chart.values = serde_yaml::from_str(&content).ok();
+                }
+                (FileRole::ChartSchema, Some(chart)) => {
+                    chart.schema = serde_json::from_str(&content).ok();
+                }
+                (FileRole::ChartTemplate, Some(chart)) => {
+                    let kinds = kind_line
+                        .captures_iter(&content)
+                        .map(|captures| captures[1].to_string())
+                        .collect();
+                    let name = &path[chart.directory.len()..];
+                    chart.templates.push((
+                        name.trim_start_matches('/')
+                            .trim_start_matches("templates/"),
+                        kinds,
+                    ));
+                }
+                (FileRole::Helmfile, _) => {
+                    for document in yaml_documents(&content) {
+                        let entries = document.get("releases").and_then(|v| v.as_array());
+                        for release in entries.into_iter().flatten() {
+                            let field = |key: &str| release.get(key).and_then(|v| v.as_str());
+                            let Some(name) = field("name") else {
+                                continue;
+                            };
+                            let mut line = format!("`{}`", name);
+                            if let Some(chart) = field("chart") {
+                                line.push_str(&format!(" chart `{}`", chart));
+                            }
+                            if let Some(version) = field("version") {
+                                line.push_str(&format!(" {}", version));
+                            }
+                            if let Some(namespace) = field("namespace") {
+                                line.push_str(&format!(" in namespace {}", namespace));
+                            }
+                            releases.push((line, path));
+                        }
+                    }
+                }
+                (FileRole::Manifest, _) => {
+                    let declared = manifest_resources(&yaml_documents(&content));
+                    if declared.is_empty() {
+                        continue;
+                    }
+                    for resource in &declared {
+                        for image in &resource.images {
+                            images
+                                .entry(image.clone())
+                                .or_default()
+                                .insert(path.to_string());
+                        }
+                        if let Some(release) = &resource.release {
+                            releases.push((
+                                format!(
+                                    "`{}` ({}) deploys {}",
+                                    resource.name, resource.kind, release
+                                ),
+                                path,
+                            ));
+                        }
+                    }
+                    resources.push((path, declared));
+                }
+                _ => {}
+            }
+        }
+
+        if charts.is_empty() && releases.is_empty() && resources.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!(
+                    "No Helm charts or Kubernetes manifests found in {}",
+                    repo_path
+                ),
+            }]);
+        }
+
+        let mut formatted_output = format!("# Kubernetes inventory of {}\n", repo_path);
+
+        if !charts.is_empty() {
+            formatted_output.push_str("\n## Helm charts\n");
+        }
+        for (directory, chart) in &charts {
+            let metadata = |key: &str| {
+                chart
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.get(key))
+                    .and_then(|v| v.as_str().map(String::from).or_else(|| Some(v.to_string())))
+            };
+            let location = match *directory {
+                "" => "Chart.yaml".to_string(),
+                directory => format!("{}/Chart.yaml", directory),
+            };
+
+            formatted_output.push_str(&format!(
+                "\n### {} {}",
+                metadata("name").unwrap_or_else(|| "(unnamed)".to_string()),
+                metadata("version").unwrap_or_default()
+            ));
+            if let Some(app_version) = metadata("appVersion") {
+                formatted_output.push_str(&format!(" (app {})", app_version));
+            }
+            formatted_output.push_str(&format!(" · {}\n\n", location));
+            if let Some(description) = metadata("description") {
+                formatted_output.push_str(&format!("{}\n\n", description));
+            }
+
+            let dependencies = chart
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get("dependencies"))
+                .and_then(|v| v.as_array());
+            for dependency in dependencies.into_iter().flatten() {
+                let field = |key: &str| dependency.get(key).and_then(|v| v.as_str());
+                formatted_output.push_str(&format!(
+                    "- Dependency: {} {}{}\n",
+                    field("name").unwrap_or("(unnamed)"),
+                    field("version").unwrap_or("*"),
+                    field("repository")
+                        .map(|repository| format!(" from {}", repository))
+                        .unwrap_or_default()
+                ));
+            }
+
+            if !chart.templates.is_empty() {
+                let templates: Vec<String> = chart
+                    .templates
+                    .iter()
+                    .map(|(name, kinds)| match &kinds[..] {
+                        [] => name.to_string(),
+                        kinds => format!("{} ({})", name, kinds.join(", ")),
+                    })
+                    .collect();
+                formatted_output.push_str(&format!("- Templates: {}\n", templates.join(", ")));
+            }
+
+            if let Some(values) = &chart.values {
+                let values_path = match *directory {
+                    "" => "values.yaml".to_string(),
+                    directory => format!("{}/values.yaml", directory),
+                };
+                let mut value_images = Vec::new();
+                collect_images(values, "", &mut value_images);
+                for (key, image) in value_images {
+                    formatted_output.push_str(&format!("- Image `{}` at {}\n", image, key));
+                    images.entry(image).or_default().insert(values_path.clone());
+                }
+                if let Some(keys) = values.as_object().filter(|keys| !keys.is_empty()) {
+                    formatted_output.push_str(&format!(
+                        "- Values: {}\n",
+                        keys.keys().cloned().collect::<Vec<_>>().join(", ")
+                    ));
+                }
+            }
+
+            if let Some(schema) = &chart.schema {
+                let mut properties = Vec::new();
+                schema_properties(schema, "", &mut properties);
+                if !properties.is_empty() {
+                    formatted_output.push_str("- Values schema:\n");
+                    for property in properties {
+                        formatted_output.push_str(&format!("  - {}\n", property));
+                    }
+                }
+            }
+        }
+
+        if !releases.is_empty() {
+            formatted_output.push_str("\n## Releases\n\n");
+            for (release, path) in &releases {
+                formatted_output.push_str(&format!("- {} ({})\n", release, path));
+            }
+        }
+
+        if !resources.is_empty() {
+            formatted_output.push_str("\n## Manifests\n");
+            for (path, declared) in &resources {
+                formatted_output.push_str(&format!("\n### {}\n\n", path));
+                for resource in declared {
+                    formatted_output.push_str(&format!("- {} `{}`", resource.kind, resource.name));
+                    if let Some(namespace) = &resource.namespace {
+                        formatted_output.push_str(&format!(" in namespace {}", namespace));
+                    }
+                    if !resource.images.is_empty() {
+                        formatted_output.push_str(&format!(": {}", resource.images.join(", ")));
+                    }
+                    formatted_output.push('\n');
+                }
+            }
+        }
+
+        if !images.is_empty() {
+            formatted_output.push_str("\n## Images\n\n");
+            for (image, sources) in &images {
+                formatted_output.push_str(&format!(
+                    "- `{}` ({})\n",
+                    image,
+                    sources.iter().cloned().collect::<Vec<_>>().join(", ")
+                ));
+            }
+        }
+
+        if manifests >= max_files {
+            formatted_output.push_str(&format!(
+                "\nOnly the first {} YAML files outside charts were read, raise max_files to read more\n",
+                max_files
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "kubernetes_inventory".into(),
+            description: Some(format!(
+                "Inventory the Helm charts and Kubernetes manifests of a repository: chart versions, dependencies, templates, values and their schema, releases declared by helmfiles, Flux HelmReleases and Argo CD Applications, the resources of each manifest, and every container image referenced. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "description": "Maximum number of YAML files outside Helm charts to read as manifests. Default: 200"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFESTS: &str = r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  namespace: shop
+spec:
+  template:
+    spec:
+      initContainers:
+        - name: migrate
+          image: ghcr.io/acme/web:1.4.0
+      containers:
+        - name: web
+          image: ghcr.io/acme/web:1.4.0
+        - name: proxy
+          image: envoyproxy/envoy:v1.30
+---
+# Not a resource, a template helpers file would look like this
+{{- include "chart.labels" . }}
+---
+apiVersion: v1
+kind: List
+items:
+  - apiVersion: v1
+    kind: Service
+    metadata:
+      generateName: web-
+---
+apiVersion: argoproj.io/v1alpha1
+kind: Application
+metadata:
+  name: payments
+spec:
+  source:
+    repoURL: https://charts.example.com
+    chart: payments
+"#;
+
+    #[test]
+    fn manifests_list_their_resources_and_images() {
+        let resources = manifest_resources(&yaml_documents(MANIFESTS));
+        assert_eq!(resources.len(), 3);
+
+        assert_eq!(resources[0].kind, "Deployment");
+        assert_eq!(resources[0].name, "web");
+        assert_eq!(resources[0].namespace.as_deref(), Some("shop"));
+        assert_eq!(
+            resources[0].images,
+            ["ghcr.io/acme/web:1.4.0", "envoyproxy/envoy:v1.30"]
+        );
+
+        assert_eq!(resources[1].kind, "Service");
+        assert_eq!(resources[1].name, "web-");
+        assert!(resources[1].images.is_empty());
+
+        assert_eq!(
+            resources[2].release.as_deref(),
+            Some("payments from https://charts.example.com")
+        );
+    }
+
+    #[test]
+    fn helm_values_join_image_parts() {
+        let values = yaml_documents(
+            "image:\n  registry: docker.io\n  repository: bitnami/redis\n  tag: 7.2\nsidecar:\n  image: busybox\nworker:\n  image:\n    repository: acme/worker\n",
+        );
+
+        let mut images = Vec::new();
+        collect_images(&values[0], "", &mut images);
+        assert_eq!(
+            images,
+            [
+                (
+                    "image".to_string(),
+                    "docker.io/bitnami/redis:7.2".to_string()
+                ),
+                ("sidecar.image".to_string(), "busybox".to_string()),
+                ("worker.image".to_string(), "acme/worker".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn schemas_list_their_leaves() {
+        let schema = json!({
+            "required": ["replicas"],
+            "properties": {
+                "replicas": {"type": "integer"},
+                "ingress": {
+                    "required": ["host"],
+                    "properties": {
+                        "host": {"type": "string"},
+                        "tls": {"type": ["boolean", "null"]}
+                    }
+                },
+                "extra": {}
+            }
+        });
+
+        let mut properties = Vec::new();
+        schema_properties(&schema, "", &mut properties);
+        assert_eq!(
+            properties,
+            [
+                "extra: any",
+                "ingress.host: string (required)",
+                "ingress.tls: boolean | null",
+                "replicas: integer (required)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::common::{LANGUAGE_EXTENSIONS, repo_argument, required_arguments, supported_providers};
+
+/// Languages below this share are too marginal to suggest including
+const MIN_SUGGESTED_PERCENT: f64 = 1.0;
+
+/// The extensions of a language as hosts name it, covering its variants (e.g., "TypeScript"
+/// also gives the extension of "TypeScript (TSX)")
+fn extensions_of(language: &str) -> impl Iterator<Item = &'static str> + '_ {
+    LANGUAGE_EXTENSIONS
+        .iter()
+        .filter(move |(name, _)| {
+            name.eq_ignore_ascii_case(language)
+                || name
+                    .strip_prefix(language)
+                    .is_some_and(|rest| rest.starts_with(" ("))
+        })
+        .flat_map(|(_, extensions)| extensions.iter().copied())
+}
+
+pub struct RepositoryLanguages {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositoryLanguages {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositoryLanguages {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+
+        let languages = provider
+            .list_languages(repo_path)
+            .await
+            .map_err(|e| anyhow!("Error listing languages: {}", e))?;
+
+        if languages.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("No languages detected in {}", repo_path),
+            }]);
+        }
+
+        let mut formatted_output = format!("Languages of {}\n\n", repo_path);
+        for language in &languages {
+            match language.bytes {
+                Some(bytes) => formatted_output.push_str(&format!(
+                    "- {}: {:.1}% ({} bytes)\n",
+                    language.name, language.percent, bytes
+                )),
+                None => formatted_output
+                    .push_str(&format!("- {}: {:.1}%\n", language.name, language.percent)),
+            }
+        }
+
+        let mut suggested: Vec<&str> = Vec::new();
+        for language in &languages {
+            if language.percent < MIN_SUGGESTED_PERCENT {
+                continue;
+            }
+            for extension in extensions_of(&language.name) {
+                if !suggested.contains(&extension) {
+                    suggested.push(extension);
+                }
+            }
+        }
+        if !suggested.is_empty() {
+            formatted_output.push_str(&format!(
+                "\nSuggested extensions for a digest of the code: {}\n",
+                suggested.join(",")
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output.trim_end().to_string(),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_languages".into(),
+            description: Some(format!(
+                "Get the language breakdown of a repository as percentages of its code, with the file extensions of its main languages ready to pass as `extensions` to a digest. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
@@ -1,12 +1,135 @@
+mod activity;
+mod affiliation;
+mod apply_patch_preview;
+mod archive;
+mod bisect;
+mod branches;
+mod build_instructions;
+mod changelog;
+mod ci;
+mod commit_diff;
+mod common;
+mod compare;
+mod completion;
+mod config_inventory;
+mod containers;
+mod contributors;
+mod curated;
+mod diff;
+mod digest;
+mod docs_site;
+mod download;
+mod fan_out;
+mod filters;
+mod grep;
+mod history;
+mod hotspots;
+mod i18n;
+mod info;
+mod issues;
+mod key_files;
+mod kubernetes;
+mod languages;
+mod licenses;
+mod link_check;
+mod links;
+mod manifest;
+mod mirror;
+mod ownership;
+mod patch;
+mod permalink;
+mod planning;
+mod pull_requests;
+mod readme;
+mod releases;
+mod resources;
+mod review_requests;
+mod schemas;
+mod semantic;
+mod tags;
+mod terraform;
+mod write;
+mod write_policy;
+mod write_roots;
+
 use std::sync::Arc;
 
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use context_server::{Tool, ToolContent, ToolExecutor};
 use futures::future::join_all;
-use git_provider::{GitProvider, GitRef};
+use git_provider::{
+    DEFAULT_MAX_DEPTH, DEPTH_LIMITED, GitProvider, GitRef, MAX_TREE_DEPTH, TreeOptions,
+    provenance::MirrorOrigin,
+};
 use serde_json::{Value, json};
 
+use crate::{
+    common::{
+        bool_argument, git_ref_argument, preset_names, repo_argument, required_arguments,
+        string_argument, supported_providers, usize_argument,
+    },
+    i18n::Message,
+    link_check::{is_markdown, rewrite_relative_links},
+    links::{DEFAULT_CONTEXT_LINES, excerpt, parse_line_fragment},
+    permalink::{default_branch_ref, raw_url},
+};
+
+pub use crate::{
+    activity::RepositoryActivity,
+    affiliation::ContributorAffiliation,
+    apply_patch_preview::ApplyPatchPreview,
+    archive::RepositoryArchive,
+    bisect::BisectHelper,
+    branches::BranchDivergence,
+    build_instructions::BuildInstructions,
+    changelog::SynthesizeChangelog,
+    ci::{CheckAnnotations, CiArtifacts, CiJobLog},
+    commit_diff::CommitDiff,
+    compare::CompareRepositories,
+    completion::{Completions, MAX_COMPLETIONS},
+    config_inventory::ConfigInventory,
+    containers::ContainerAnalysis,
+    contributors::ListContributors,
+    curated::ExpandCuratedList,
+    diff::RepositoryDiff,
+    digest::RepositoryDigest,
+    docs_site::DocsSiteMap,
+    download::DownloadDirectory,
+    fan_out::{FanOut, estimate_fan_out},
+    filters::ExplainFilters,
+    grep::RepositoryGrep,
+    history::{Access, AccessHistory, RecentActivity},
+    hotspots::RepositoryHotspots,
+    i18n::{Language, set_language},
+    info::{RepositoryInfo, RepositorySummary},
+    issues::{ListIssues, ReadIssue},
+    key_files::RepositoryKeyFiles,
+    kubernetes::KubernetesInventory,
+    languages::RepositoryLanguages,
+    licenses::DetectLicense,
+    link_check::CheckLinks,
+    links::ResolveLink,
+    manifest::RepositoryManifest,
+    mirror::CloneToCache,
+    ownership::WhoOwnsPath,
+    permalink::Permalink,
+    planning::{ProjectBoards, RepositoryLabels, RepositoryMilestones},
+    pull_requests::{ListPullRequests, ReadPullRequest},
+    readme::ReadReadme,
+    releases::ListReleases,
+    resources::{ChangelogResource, DigestResource, ResourceTemplate},
+    review_requests::MyReviewRequests,
+    schemas::RepositorySchemas,
+    tags::RepositoryTags,
+    terraform::TerraformInventory,
+    write::{
+        CommitFiles, CreateBranch, CreateComment, CreateIssue, CreatePullRequest, ForkRepository,
+    },
+    write_policy::WritePolicy,
+    write_roots::WriteRoots,
+};
+
 pub struct RepositoryRead {
     providers: Vec<Arc<dyn GitProvider>>,
 }
@@ -15,90 +138,65 @@ impl RepositoryRead {
     pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
         Self { providers }
     }
-
-    fn get_provider(&self, provider_name: &str) -> Option<&dyn GitProvider> {
-        self.providers
-            .iter()
-            .find(|p| p.name() == provider_name)
-            .map(|p| p.as_ref())
-    }
-
-    fn get_supported_providers(&self) -> Vec<String> {
-        self.providers
-            .iter()
-            .map(|p| p.name().to_string())
-            .collect()
-    }
-
-    fn parse_git_ref(&self, ref_str: &str) -> GitRef {
-        if ref_str.is_empty() {
-            return GitRef::Default;
-        }
-
-        let parts: Vec<&str> = ref_str.split(':').collect();
-        if parts.len() != 2 {
-            return GitRef::Branch(ref_str.to_string());
-        }
-
-        match parts[0] {
-            "tag" => GitRef::Tag(parts[1].to_string()),
-            "commit" => GitRef::Commit(parts[1].to_string()),
-            "branch" => GitRef::Branch(parts[1].to_string()),
-            _ => GitRef::Branch(ref_str.to_string()),
-        }
-    }
 }
 
 #[async_trait]
 impl ToolExecutor for RepositoryRead {
     async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
-        let args = arguments.ok_or_else(|| anyhow!("Missing arguments"))?;
-
-        // Extract the repository identifier (e.g., "github:owner/repo")
-        let repo_identifier = args
-            .get("repo")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow!("Missing or invalid repository identifier"))?;
-
-        // Parse the "gitprovider:username/reponame" format
-        let parts: Vec<&str> = repo_identifier.split(':').collect();
-        if parts.len() != 2 || !parts[1].contains('/') {
-            return Err(anyhow!(
-                "Invalid repository format. Expected 'gitprovider:username/reponame'"
-            ));
-        }
-
-        let git_provider = parts[0];
-        let repo_path = parts[1];
-
-        // Get the file path
-        let file_path = args
-            .get("file_path")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow!("Missing or invalid file path"))?;
-
-        // Get the provider implementation
-        let provider = self.get_provider(git_provider).ok_or_else(|| {
-            let supported = self.get_supported_providers().join(", ");
-            anyhow!(
-                "Git provider '{}' is not supported. Supported providers: {}",
-                git_provider,
-                supported
-            )
-        })?;
-
-        // Parse git reference (branch, tag, commit)
-        let git_ref = args
-            .get("git_ref")
-            .and_then(|v| v.as_str())
-            .map(|s| self.parse_git_ref(s));
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let file_path = string_argument(&args, "file_path")?;
+
+        // A "#L10-L25" suffix or the lines argument restricts the output to a range
+        let (file_path, fragment) = match file_path.split_once("#L") {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (file_path, None),
+        };
+        let lines = fragment
+            .or_else(|| args.get("lines").and_then(|v| v.as_str()))
+            .and_then(parse_line_fragment);
+        let context_lines = usize_argument(&args, "context_lines").unwrap_or(DEFAULT_CONTEXT_LINES);
+        let absolute_links = bool_argument(&args, "absolute_links").unwrap_or(false);
 
         // Get file content
         match provider
-            .get_file_content(repo_path, file_path, git_ref)
+            .get_file_content(repo_path, file_path, git_ref.clone())
             .await
         {
             Ok(content) => {
+                let content = if absolute_links && lines.is_none() && is_markdown(file_path) {
+                    let base_url = provider.web_url().ok_or_else(|| {
+                        anyhow!(Message::NoWebInterface.text(&[&provider.name()]))
+                    })?;
+                    // Links keep working only if they name a reference
+                    let git_ref = match git_ref.unwrap_or_default() {
+                        GitRef::Default => default_branch_ref(provider, repo_path).await,
+                        git_ref => git_ref,
+                    };
+
+                    rewrite_relative_links(&content, file_path, |path| {
+                        raw_url(provider.kind(), &base_url, repo_path, &git_ref, path)
+                    })?
+                } else {
+                    content
+                };
+
+                if let Some((start, end)) = lines {
+                    let extension = file_path.rsplit('.').next().unwrap_or("");
+                    return Ok(vec![ToolContent::Text {
+                        text: format!(
+                            "{} (lines {}-{})\n\n```{}\n{}```",
+                            file_path,
+                            start,
+                            end,
+                            extension,
+                            excerpt(&content, (start, end), context_lines)
+                        ),
+                    }]);
+                }
+
                 // Determine if we need to wrap the content in a code block
                 let is_code = file_path.ends_with(".rs")
                     || file_path.ends_with(".js")
@@ -118,7 +216,7 @@ impl ToolExecutor for RepositoryRead {
 
                 let formatted_content = if is_code {
                     // Get file extension for syntax highlighting
-                    let extension = file_path.split('.').last().unwrap_or("");
+                    let extension = file_path.split('.').next_back().unwrap_or("");
                     format!("```{}\n{}\n```", extension, content)
                 } else {
                     content
@@ -128,12 +226,12 @@ impl ToolExecutor for RepositoryRead {
                     text: formatted_content,
                 }])
             }
-            Err(e) => Err(anyhow!("Error getting file content: {}", e)),
+            Err(e) => Err(anyhow!(Message::ReadFileFailed.text(&[&e]))),
         }
     }
 
     fn to_tool(&self) -> Tool {
-        let providers = self.get_supported_providers().join(", ");
+        let providers = supported_providers(&self.providers).join(", ");
 
         Tool {
             name: "repository_read".into(),
@@ -150,11 +248,23 @@ impl ToolExecutor for RepositoryRead {
                     },
                     "file_path": {
                         "type": "string",
-                        "description": "Path to the file within the repository to read"
+                        "description": "Path to the file within the repository to read, optionally followed by a line range (e.g., 'src/main.rs#L10-L25')"
                     },
                     "git_ref": {
                         "type": "string",
                         "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "lines": {
+                        "type": "string",
+                        "description": "Optional line range to return, e.g. '10-25' or 'L10-L25'. Default: whole file"
+                    },
+                    "context_lines": {
+                        "type": "integer",
+                        "description": format!("Optional number of lines shown around the line range. Default: {}", DEFAULT_CONTEXT_LINES)
+                    },
+                    "absolute_links": {
+                        "type": "boolean",
+                        "description": "Optional flag to rewrite the relative links and image sources of a markdown file into absolute raw file URLs, so the document stands on its own. Default: false"
                     }
                 },
                 "required": ["repo", "file_path"]
@@ -163,125 +273,125 @@ impl ToolExecutor for RepositoryRead {
     }
 }
 
-pub struct RepositoryTreeView {
-    providers: Vec<Arc<dyn GitProvider>>,
+/// Arguments of `repository_tree_view` carried by its cursor to the walks of subtrees
+const TREE_CURSOR_ARGUMENTS: &[&str] = &[
+    "repo",
+    "git_ref",
+    "exclude_patterns",
+    "include_patterns",
+    "preset",
+    "extensions",
+    "case_insensitive",
+    "describe_directories",
+    "max_depth",
+];
+
+/// The cursor continuing a tree walk, its arguments in an opaque token
+fn tree_cursor(args: &Value) -> Result<String> {
+    let carried: serde_json::Map<String, Value> = TREE_CURSOR_ARGUMENTS
+        .iter()
+        .filter_map(|key| args.get(*key).map(|value| (key.to_string(), value.clone())))
+        .collect();
+
+    Ok(base64::encode_config(
+        serde_json::to_vec(&carried)?,
+        base64::URL_SAFE_NO_PAD,
+    ))
 }
 
-impl RepositoryTreeView {
-    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
-        Self { providers }
-    }
-
-    fn get_provider(&self, provider_name: &str) -> Option<&dyn GitProvider> {
-        self.providers
-            .iter()
-            .find(|p| p.name() == provider_name)
-            .map(|p| p.as_ref())
+/// The arguments of a walk continuing from a cursor, the ones given again taking precedence
+fn resume_tree_cursor(args: Value) -> Result<Value> {
+    let Some(cursor) = args.get("cursor").and_then(|v| v.as_str()) else {
+        return Ok(args);
+    };
+    let mut resumed: serde_json::Map<String, Value> =
+        base64::decode_config(cursor, base64::URL_SAFE_NO_PAD)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or_else(|| anyhow!("Invalid cursor, pass the one ending a previous tree"))?;
+
+    if let (Some(repo), Some(cursor_repo)) = (args.get("repo"), resumed.get("repo"))
+        && repo != cursor_repo
+    {
+        return Err(anyhow!(
+            "Cursor was issued for {} and not {}",
+            cursor_repo,
+            repo
+        ));
     }
-
-    fn get_supported_providers(&self) -> Vec<String> {
-        self.providers
-            .iter()
-            .map(|p| p.name().to_string())
-            .collect()
+    if let Value::Object(given) = args {
+        resumed.extend(given);
     }
 
-    fn parse_git_ref(&self, ref_str: &str) -> GitRef {
-        if ref_str.is_empty() {
-            return GitRef::Default;
-        }
+    Ok(Value::Object(resumed))
+}
 
-        let parts: Vec<&str> = ref_str.split(':').collect();
-        if parts.len() != 2 {
-            return GitRef::Branch(ref_str.to_string());
-        }
+pub struct RepositoryTreeView {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
 
-        match parts[0] {
-            "tag" => GitRef::Tag(parts[1].to_string()),
-            "commit" => GitRef::Commit(parts[1].to_string()),
-            "branch" => GitRef::Branch(parts[1].to_string()),
-            _ => GitRef::Branch(ref_str.to_string()),
-        }
+impl RepositoryTreeView {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
     }
 }
 
 #[async_trait]
 impl ToolExecutor for RepositoryTreeView {
     async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
-        let args = arguments.ok_or_else(|| anyhow!("Missing arguments"))?;
+        let args = resume_tree_cursor(required_arguments(arguments)?)?;
 
-        let repo_identifier = args
-            .get("repo")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow!("Missing or invalid repository identifier"))?;
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
 
-        // Parse the "gitprovider:username/reponame" format
-        let parts: Vec<&str> = repo_identifier.split(':').collect();
-        if parts.len() != 2 || !parts[1].contains('/') {
-            return Err(anyhow!(
-                "Invalid repository format. Expected 'gitprovider:username/reponame'"
-            ));
-        }
+        let describe_directories = args
+            .get("describe_directories")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
 
-        let git_provider = parts[0];
-        let repo_path = parts[1];
-
-        // Get the provider implementation
-        let provider = self.get_provider(git_provider).ok_or_else(|| {
-            let supported = self.get_supported_providers().join(", ");
-            anyhow!(
-                "Git provider '{}' is not supported. Supported providers: {}",
-                git_provider,
-                supported
-            )
-        })?;
-
-        // Parse git reference (branch, tag, commit)
-        let git_ref = args
-            .get("git_ref")
-            .and_then(|v| v.as_str())
-            .map(|s| self.parse_git_ref(s));
+        let force = args.get("force").and_then(|v| v.as_bool()).unwrap_or(false);
+        let size_warning = common::check_repository_size(provider, repo_path, force).await?;
 
-        let exclude_patterns = args
-            .get("exclude_patterns")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        let include_patterns = args
-            .get("include_patterns")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
+        let options = TreeOptions {
+            describe_directories,
+            max_depth: usize_argument(&args, "max_depth"),
+            subtree: args.get("path").and_then(|v| v.as_str()).map(String::from),
+            ..common::tree_options_argument(&args)?
+        };
 
         // Get tree structure directly from the provider
         match provider
-            .get_tree_structure(repo_path, git_ref, exclude_patterns, include_patterns)
+            .get_tree_structure(repo_path, git_ref, options)
             .await
         {
             Ok(tree_structure) => {
                 // Return the tree structure as text wrapped in code block for better formatting
-                Ok(vec![ToolContent::Text {
-                    text: format!("```\n{}\n```", tree_structure),
-                }])
+                let mut text = format!(
+                    "{}```\n{}\n```",
+                    size_warning.unwrap_or_default(),
+                    tree_structure
+                );
+                // Unexpanded directories are walked on demand, with the same arguments
+                if tree_structure.contains(DEPTH_LIMITED) {
+                    text.push_str(&format!(
+                        "\n\nTo expand a directory, call again with its path and cursor: {}",
+                        tree_cursor(&args)?
+                    ));
+                }
+
+                Ok(vec![ToolContent::Text { text }])
             }
-            Err(e) => Err(anyhow!("Error getting repository tree structure: {}", e)),
+            Err(e) => Err(anyhow!(Message::ReadTreeFailed.text(&[&e]))),
         }
     }
 
     fn to_tool(&self) -> Tool {
-        let providers = self.get_supported_providers().join(", ");
+        let providers = supported_providers(&self.providers).join(", ");
 
         Tool {
             name: "repository_tree_view".into(),
             description: Some(format!(
-                "View the file structure of a Git repository recursively. Supported providers: {}",
+                "View the file structure of a Git repository recursively, down to a depth limit. Directories left unexpanded can be walked afterwards as subtrees, passing their path and the cursor ending the tree. Supported providers: {}",
                 providers
             )),
             input_schema: json!({
@@ -302,6 +412,38 @@ impl ToolExecutor for RepositoryTreeView {
                     "include_patterns": {
                         "type": "string",
                         "description": "Optional comma-separated list of patterns to include"
+                    },
+                    "preset": {
+                        "type": "string",
+                        "description": format!("Optional comma-separated list of ecosystem presets adding their source and exclude patterns: {}", preset_names())
+                    },
+                    "extensions": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of file extensions to include, e.g. 'rs,toml,md'"
+                    },
+                    "case_insensitive": {
+                        "type": "boolean",
+                        "description": "Optional flag to match every pattern regardless of case. Default: false"
+                    },
+                    "describe_directories": {
+                        "type": "boolean",
+                        "description": "Optional flag to annotate directories with the first paragraph of their README. Default: false"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Optional directory to walk instead of the repository root, e.g. one left unexpanded in a previous tree"
+                    },
+                    "cursor": {
+                        "type": "string",
+                        "description": "Optional cursor ending a previous tree, walking the path with that tree's arguments. Arguments given again take precedence"
+                    },
+                    "max_depth": {
+                        "type": "integer",
+                        "description": format!("Optional deepest level of entries listed, the root's entries being at level 0, at most {}. Default: {}", MAX_TREE_DEPTH, DEFAULT_MAX_DEPTH)
+                    },
+                    "force": {
+                        "type": "boolean",
+                        "description": "Optional flag to walk repositories above the server's size limit. Default: false"
                     }
                 },
                 "required": ["repo"]
@@ -318,19 +460,12 @@ impl FindRepositories {
     pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
         Self { providers }
     }
-
-    fn get_supported_providers(&self) -> Vec<String> {
-        self.providers
-            .iter()
-            .map(|p| p.name().to_string())
-            .collect()
-    }
 }
 
 #[async_trait]
 impl ToolExecutor for FindRepositories {
     async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
-        let args = arguments.ok_or_else(|| anyhow!("Missing arguments"))?;
+        let args = required_arguments(arguments)?;
 
         // Extract the query for repository search
         let query = args
@@ -348,11 +483,15 @@ impl ToolExecutor for FindRepositories {
             }
         });
 
-        let mut results = join_all(
-            self.providers
-                .iter()
-                .map(|p| p.find_repositories(query, limit)),
-        )
+        let templates_only = bool_argument(&args, "templates_only").unwrap_or(false);
+
+        let mut results = join_all(self.providers.iter().map(|p| async move {
+            if templates_only {
+                p.find_template_repositories(query, limit).await
+            } else {
+                p.find_repositories(query, limit).await
+            }
+        }))
         .await
         .into_iter()
         .filter_map(|result| result.ok())
@@ -362,24 +501,38 @@ impl ToolExecutor for FindRepositories {
         // If no results were found
         if results.is_empty() {
             return Ok(vec![ToolContent::Text {
-                text: format!("No repositories found matching query: \"{}\"", query),
+                text: Message::NoRepositoriesFound.text(&[&query]),
             }]);
         }
 
-        // Sort results by star count (most popular first)
-        results.sort_by(|a, b| b.stargazers_count.cmp(&a.stargazers_count));
+        // Sort results by star count (most popular first), then by name for a stable order
+        results.sort_by(|a, b| {
+            b.stargazers_count
+                .cmp(&a.stargazers_count)
+                .then_with(|| a.provider.cmp(&b.provider))
+                .then_with(|| a.full_name.cmp(&b.full_name))
+        });
 
         // Format results in a simpler format
         let mut formatted_output = String::new();
-        formatted_output.push_str(&format!("Search results for: \"{}\"\n\n", query));
+        formatted_output.push_str(&format!("{}\n\n", Message::SearchResults.text(&[&query])));
 
         for repo in results.iter() {
             let description = repo.description.as_deref().unwrap_or("").trim();
 
             formatted_output.push_str(&format!(
-                "- {}:{} ⭐️{}\n  {}\n\n",
+                "- {}:{} ⭐️{}\n  {}\n",
                 repo.provider, repo.full_name, repo.stargazers_count, description
             ));
+            // Search results only carry the description, so mirrors are told by it alone
+            if let Some(mirror) = MirrorOrigin::from_description(description) {
+                formatted_output.push_str(&format!(
+                    "  {}: {}\n",
+                    Message::Provenance.text(&[]),
+                    mirror.describe()
+                ));
+            }
+            formatted_output.push('\n');
         }
 
         Ok(vec![ToolContent::Text {
@@ -388,12 +541,12 @@ impl ToolExecutor for FindRepositories {
     }
 
     fn to_tool(&self) -> Tool {
-        let providers = self.get_supported_providers().join(", ");
+        let providers = supported_providers(&self.providers).join(", ");
 
         Tool {
             name: "find_repositories".into(),
             description: Some(format!(
-                "Find code repositories matching a search query. Supported providers: {}",
+                "Find code repositories matching a search query, flagging the ones whose description marks them as mirrors of another repository. Supported providers: {}",
                 providers
             )),
             input_schema: json!({
@@ -406,6 +559,10 @@ impl ToolExecutor for FindRepositories {
                     "limit": {
                         "type": "string",
                         "description": "Optional maximum number of results to return per each provider"
+                    },
+                    "templates_only": {
+                        "type": "boolean",
+                        "description": "Optional flag to only return repositories marked as templates, for providers that support them. Default: false"
                     }
                 },
                 "required": ["query"]
@@ -0,0 +1,307 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::common::{
+    git_ref_argument, language_for_path, repo_argument, required_arguments, supported_providers,
+};
+
+/// Names of the files holding a license or the notices it requires, compared case-insensitively
+/// and without extension (e.g., "LICENSE.md", "license-mit", "COPYING.LESSER")
+const LICENSE_FILE_STEMS: &[&str] = &["license", "licence", "copying", "notice", "unlicense"];
+
+/// How far into a file license headers are looked for
+const HEADER_LINES: usize = 30;
+
+/// How far into a license text its first phrase is looked for, since texts name the licenses
+/// they are not (e.g., the GPL recommending the LGPL for libraries)
+const OPENING_LENGTH: usize = 400;
+
+/// Phrases telling the common licenses apart, most specific first, with their SPDX identifier.
+/// The first phrase opens the text, the others can appear anywhere in it
+const LICENSE_PHRASES: &[(&str, &[&str])] = &[
+    (
+        "AGPL-3.0",
+        &["gnu affero general public license", "version 3"],
+    ),
+    (
+        "LGPL-3.0",
+        &["gnu lesser general public license", "version 3"],
+    ),
+    (
+        "LGPL-2.1",
+        &["gnu lesser general public license", "version 2.1"],
+    ),
+    ("GPL-3.0", &["gnu general public license", "version 3"]),
+    ("GPL-2.0", &["gnu general public license", "version 2"]),
+    ("Apache-2.0", &["apache license", "version 2.0"]),
+    ("MPL-2.0", &["mozilla public license", "2.0"]),
+    ("EPL-2.0", &["eclipse public license", "2.0"]),
+    ("BSL-1.0", &["boost software license", "version 1.0"]),
+    (
+        "BSD-3-Clause",
+        &[
+            "redistribution and use in source and binary forms",
+            "neither the name",
+        ],
+    ),
+    (
+        "BSD-2-Clause",
+        &["redistribution and use in source and binary forms"],
+    ),
+    ("MIT", &["permission is hereby granted, free of charge"]),
+    (
+        "ISC",
+        &["permission to use, copy, modify, and/or distribute this software"],
+    ),
+    ("Unlicense", &["this is free and unencumbered software"]),
+    ("CC0-1.0", &["cc0 1.0 universal"]),
+    (
+        "Zlib",
+        &[
+            "this software is provided 'as-is'",
+            "altered source versions",
+        ],
+    ),
+];
+
+/// Whether a file holds a license or its notices, at the root or in a vendored directory.
+/// Source files named after licenses (e.g., "license.rs") are not license files
+pub(crate) fn is_license_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path).to_ascii_lowercase();
+    let stem = name.split(['.', '-', '_']).next().unwrap_or(&name);
+    LICENSE_FILE_STEMS.contains(&stem)
+        && language_for_path(path).is_none_or(|language| language == "Markdown")
+}
+
+/// The SPDX identifier of a license text, by the phrases its usual wording contains
+pub(crate) fn identify_license(text: &str) -> Option<&'static str> {
+    let text = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    let opening = match text.char_indices().nth(OPENING_LENGTH) {
+        Some((end, _)) => &text[..end],
+        None => text.as_str(),
+    };
+    LICENSE_PHRASES
+        .iter()
+        .find(|(_, phrases)| {
+            phrases
+                .first()
+                .is_some_and(|phrase| opening.contains(phrase))
+                && phrases.iter().all(|phrase| text.contains(phrase))
+        })
+        .map(|(id, _)| *id)
+}
+
+/// The copyright lines of a text, without comment markers
+fn copyright_lines(lines: impl Iterator<Item = impl AsRef<str>>) -> Vec<String> {
+    lines
+        .map(|line| {
+            line.as_ref()
+                .trim()
+                .trim_start_matches(['/', '*', '#', '-', ';', '!', '<', '>', '%'])
+                .trim_end_matches(['*', '/', '-', '>'])
+                .trim()
+                .to_string()
+        })
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            (lower.starts_with("copyright ") || lower.starts_with("(c) ") || line.starts_with('©'))
+                // Skip the clauses of license texts speaking of "copyright notices"
+                && line.chars().any(|c| c.is_ascii_digit())
+        })
+        .collect()
+}
+
+/// The license files, SPDX headers and copyright notices found in ingested content, for
+/// the attribution the licenses of redistributed code require
+#[derive(Default)]
+pub(crate) struct Attribution {
+    /// Path, license when recognized and text of each license file
+    license_files: Vec<(String, Option<&'static str>, String)>,
+    /// Files declaring each SPDX expression in their header
+    spdx_headers: BTreeMap<String, usize>,
+    /// Files carrying each copyright notice in their header or license file
+    copyrights: BTreeMap<String, usize>,
+}
+
+impl Attribution {
+    /// Records a license file and its full text
+    pub(crate) fn add_license_file(&mut self, path: &str, text: &str) {
+        for notice in copyright_lines(text.lines())
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+        {
+            *self.copyrights.entry(notice).or_default() += 1;
+        }
+        self.license_files
+            .push((path.to_string(), identify_license(text), text.to_string()));
+    }
+
+    /// Records the license header of a source file
+    pub(crate) fn add_file(&mut self, content: &str) {
+        let header: Vec<&str> = content.lines().take(HEADER_LINES).collect();
+
+        if let Some(expression) = header.iter().find_map(|line| {
+            line.split_once("SPDX-License-Identifier:")
+                .map(|(_, expression)| expression.trim().trim_end_matches("*/").trim())
+        }) && !expression.is_empty()
+        {
+            *self.spdx_headers.entry(expression.to_string()).or_default() += 1;
+        }
+        for notice in copyright_lines(header.iter())
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+        {
+            *self.copyrights.entry(notice).or_default() += 1;
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.license_files.is_empty() && self.spdx_headers.is_empty() && self.copyrights.is_empty()
+    }
+
+    /// Renders the attribution section, the license texts last
+    pub(crate) fn render(&self) -> String {
+        let mut section = String::from("Attribution:\n");
+        if self.is_empty() {
+            section.push_str("No license files, SPDX headers or copyright notices found.\n\n");
+            return section;
+        }
+
+        if !self.license_files.is_empty() {
+            section.push_str("\nLicense files:\n");
+            for (path, license, _) in &self.license_files {
+                section.push_str(&format!(
+                    "- {}: {}\n",
+                    path,
+                    license.unwrap_or("unrecognized license")
+                ));
+            }
+        }
+        if !self.spdx_headers.is_empty() {
+            section.push_str("\nSPDX headers:\n");
+            for (expression, files) in &self.spdx_headers {
+                section.push_str(&format!("- {} ({} files)\n", expression, files));
+            }
+        }
+        if !self.copyrights.is_empty() {
+            section.push_str("\nCopyright notices:\n");
+            for (notice, files) in &self.copyrights {
+                section.push_str(&format!("- {} ({} files)\n", notice, files));
+            }
+        }
+        for (path, _, text) in &self.license_files {
+            section.push_str(&format!("\n--- {} ---\n{}\n", path, text.trim_end()));
+        }
+        section.push('\n');
+
+        section
+    }
+}
+
+pub struct DetectLicense {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl DetectLicense {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for DetectLicense {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+
+        let license = provider
+            .get_license(repo_path, git_ref)
+            .await
+            .map_err(|e| anyhow!("Error detecting license: {}", e))?;
+
+        // Hosts without license detection leave the identification to the text's wording
+        let spdx_id = match license.spdx_id {
+            Some(spdx_id) => format!("{} (detected by {})", spdx_id, provider.kind()),
+            None => match identify_license(&license.content) {
+                Some(spdx_id) => format!("{} (identified from the text)", spdx_id),
+                None => "unrecognized".to_string(),
+            },
+        };
+
+        Ok(vec![ToolContent::Text {
+            text: format!(
+                "License of {}: {}\nFile: {}\n\n{}",
+                repo_path,
+                spdx_id,
+                license.path,
+                license.content.trim_end()
+            ),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "detect_license".into(),
+            description: Some(format!(
+                "Detect the license of a repository: its SPDX identifier and the full text of its license file (LICENSE, COPYING and their variants). Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn licenses_are_told_apart_by_their_opening() {
+        // The LGPL is only named past the opening, as in the full text
+        let gpl = format!(
+            "GNU GENERAL PUBLIC LICENSE\nVersion 3, 29 June 2007\n\n{}\nuse the GNU Lesser General Public License instead of this License.",
+            "The GNU General Public License is a free, copyleft license.\n".repeat(10)
+        );
+        assert_eq!(identify_license(&gpl), Some("GPL-3.0"));
+
+        let mit = "MIT License\n\nCopyright (c) 2024 Jane Doe\n\nPermission is hereby granted, free of charge, to any person";
+        assert_eq!(identify_license(mit), Some("MIT"));
+        assert_eq!(
+            copyright_lines(mit.lines()),
+            ["Copyright (c) 2024 Jane Doe"]
+        );
+
+        assert!(is_license_file("third_party/zlib/LICENSE.md"));
+        assert!(is_license_file("COPYING.LESSER"));
+        assert!(!is_license_file("src/license.rs"));
+    }
+}
@@ -0,0 +1,550 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{GitProvider, GitRef, TreeOptions};
+use regex::Regex;
+use serde_json::{Value, json};
+
+use crate::common::{
+    bool_argument, git_ref_argument, repo_argument, required_arguments, supported_providers,
+    usize_argument,
+};
+
+pub(crate) const DEFAULT_MAX_FILES: usize = 200;
+
+pub(crate) fn is_markdown(path: &str) -> bool {
+    let path = path.to_ascii_lowercase();
+    path.ends_with(".md") || path.ends_with(".markdown")
+}
+
+/// A link found in a markdown file
+struct Link {
+    line: usize,
+    target: String,
+}
+
+/// Patterns of inline, reference-style and HTML links, capturing the link target
+fn link_patterns() -> Result<[Regex; 3]> {
+    Ok([
+        Regex::new(r#"\]\(\s*<?([^)\s>]+)>?(?:\s+"[^"]*")?\s*\)"#)?,
+        Regex::new(r"^\s*\[[^\]]+\]:\s*<?([^\s>]+)>?")?,
+        Regex::new(r#"(?:src|href)\s*=\s*"([^"]+)""#)?,
+    ])
+}
+
+/// Extracts the inline, reference-style and HTML link targets of a markdown document,
+/// skipping fenced code blocks
+fn extract_links(markdown: &str) -> Result<Vec<Link>> {
+    let patterns = link_patterns()?;
+
+    let mut links = Vec::new();
+    let mut in_fence = false;
+    for (index, line) in markdown.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        for re in &patterns {
+            for captures in re.captures_iter(line) {
+                links.push(Link {
+                    line: index + 1,
+                    target: captures[1].to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(links)
+}
+
+/// Rewrites the relative link and image targets of the markdown file at `source` with
+/// `to_url`, called with the repository path they resolve to. Fragment-only links, links
+/// leaving the repository and fenced code blocks are kept as they are.
+pub(crate) fn rewrite_relative_links(
+    markdown: &str,
+    source: &str,
+    to_url: impl Fn(&str) -> Result<String>,
+) -> Result<String> {
+    let patterns = link_patterns()?;
+
+    let mut rewritten = String::with_capacity(markdown.len());
+    let mut in_fence = false;
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            rewritten.push_str(line);
+            continue;
+        }
+        if in_fence {
+            rewritten.push_str(line);
+            continue;
+        }
+
+        let mut line = line.to_string();
+        for re in &patterns {
+            // Replaced from the end so earlier offsets stay valid
+            let targets: Vec<_> = re
+                .captures_iter(&line)
+                .filter_map(|captures| captures.get(1))
+                .map(|target| (target.range(), target.as_str().to_string()))
+                .collect();
+            for (range, target) in targets.into_iter().rev() {
+                if target.starts_with('#') || is_external(&target) {
+                    continue;
+                }
+                let Some((path, fragment)) = resolve(source, &target) else {
+                    continue;
+                };
+
+                let mut url = to_url(&path)?;
+                if let Some(fragment) = fragment {
+                    url = format!("{}#{}", url, fragment);
+                }
+                line.replace_range(range, &url);
+            }
+        }
+        rewritten.push_str(&line);
+    }
+
+    Ok(rewritten)
+}
+
+/// The anchor GitHub generates for a heading: lowercase, punctuation dropped and spaces
+/// turned into dashes
+fn heading_slug(heading: &str) -> String {
+    // Keep the text of links, other markup is dropped with the punctuation
+    let text = Regex::new(r"\[([^\]]*)\]\([^)]*\)")
+        .map(|re| re.replace_all(heading, "$1").into_owned())
+        .unwrap_or_else(|_| heading.to_string());
+
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The anchors a markdown document defines: its heading slugs, numbered like GitHub does for
+/// duplicates, and explicit `id` or `name` attributes
+fn anchors(markdown: &str) -> Result<BTreeSet<String>> {
+    let explicit = Regex::new(r#"(?:id|name)\s*=\s*"([^"]+)""#)?;
+
+    let mut anchors = BTreeSet::new();
+    let mut occurrences: BTreeMap<String, usize> = BTreeMap::new();
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        for captures in explicit.captures_iter(line) {
+            anchors.insert(captures[1].to_lowercase());
+        }
+
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        if !(1..=6).contains(&level) || !trimmed[level..].starts_with([' ', '\t']) {
+            continue;
+        }
+
+        let slug = heading_slug(trimmed[level..].trim().trim_end_matches('#'));
+        let count = occurrences.entry(slug.clone()).or_default();
+        anchors.insert(match *count {
+            0 => slug,
+            n => format!("{}-{}", slug, n),
+        });
+        *count += 1;
+    }
+
+    Ok(anchors)
+}
+
+/// Resolves a relative link of the file at `source` to a repository path and an optional
+/// fragment, `None` for links leaving the repository
+fn resolve(source: &str, target: &str) -> Option<(String, Option<String>)> {
+    let (location, fragment) = match target.split_once('#') {
+        Some((location, fragment)) => (location, Some(fragment.to_string())),
+        None => (target, None),
+    };
+    let location = location.split('?').next().unwrap_or(location);
+    let location = urlencoding::decode(location)
+        .map(|s| s.into_owned())
+        .unwrap_or_else(|_| location.to_string());
+
+    // Fragment-only links point into the file itself
+    if location.is_empty() {
+        return Some((source.to_string(), fragment));
+    }
+
+    let mut segments: Vec<&str> = if location.starts_with('/') {
+        Vec::new()
+    } else {
+        source
+            .split('/')
+            .collect::<Vec<_>>()
+            .split_last()?
+            .1
+            .to_vec()
+    };
+    for segment in location.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    Some((segments.join("/"), fragment))
+}
+
+/// Whether a link target is absolute, such as a web URL or an email address
+fn is_external(target: &str) -> bool {
+    target.starts_with("//")
+        || target.split_once(':').is_some_and(|(scheme, _)| {
+            !scheme.is_empty()
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '.' | '-'))
+        })
+}
+
+/// Fetches markdown files concurrently, keyed by path
+async fn fetch_markdown(
+    provider: &dyn GitProvider,
+    repo_path: &str,
+    git_ref: Option<&GitRef>,
+    paths: &[&str],
+) -> BTreeMap<String, Result<String>> {
+    let contents = join_all(
+        paths
+            .iter()
+            .map(|path| provider.get_file_content(repo_path, path, git_ref.cloned())),
+    )
+    .await;
+
+    paths
+        .iter()
+        .map(|path| path.to_string())
+        .zip(contents)
+        .collect()
+}
+
+pub struct CheckLinks {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl CheckLinks {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for CheckLinks {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let directory = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .trim_matches('/');
+        let max_files = usize_argument(&args, "max_files").unwrap_or(DEFAULT_MAX_FILES);
+        let check_anchors = bool_argument(&args, "check_anchors").unwrap_or(true);
+
+        // Every file, links to ignored ones such as images are valid too
+        let unfiltered = TreeOptions {
+            ignore_patterns: Some(Vec::new()),
+            ..Default::default()
+        };
+        let files = provider
+            .list_files(repo_path, git_ref.clone(), &unfiltered)
+            .await
+            .map_err(|e| anyhow!("Error listing repository files: {}", e))?;
+
+        let existing: BTreeSet<&str> = files.iter().map(|file| file.path.as_str()).collect();
+        let directories: BTreeSet<&str> = files
+            .iter()
+            .flat_map(|file| {
+                file.path
+                    .match_indices('/')
+                    .map(|(index, _)| &file.path[..index])
+            })
+            .collect();
+
+        let mut scanned: Vec<&str> = existing
+            .iter()
+            .copied()
+            .filter(|path| is_markdown(path))
+            .filter(|path| {
+                directory.is_empty()
+                    || path
+                        .strip_prefix(directory)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .collect();
+        let total_files = scanned.len();
+        scanned.truncate(max_files);
+
+        if scanned.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!(
+                    "No markdown files of {} under '{}' to check",
+                    repo_path, directory
+                ),
+            }]);
+        }
+
+        let mut contents = fetch_markdown(provider, repo_path, git_ref.as_ref(), &scanned).await;
+
+        let mut links_by_file = Vec::new();
+        let mut unreadable = Vec::new();
+        for path in &scanned {
+            match &contents[*path] {
+                Ok(content) => links_by_file.push((*path, extract_links(content)?)),
+                Err(e) => unreadable.push(format!("{}: {}", path, e)),
+            }
+        }
+
+        // Anchors may point into markdown files outside of the scanned ones
+        if check_anchors {
+            let targets: BTreeSet<String> = links_by_file
+                .iter()
+                .flat_map(|(source, links)| {
+                    links
+                        .iter()
+                        .filter(|link| !is_external(&link.target))
+                        .filter_map(|link| resolve(source, &link.target))
+                })
+                .filter(|(path, fragment)| {
+                    fragment.is_some() && is_markdown(path) && existing.contains(path.as_str())
+                })
+                .map(|(path, _)| path)
+                .collect();
+            let missing: Vec<&str> = targets
+                .iter()
+                .map(String::as_str)
+                .filter(|path| !contents.contains_key(*path))
+                .collect();
+            contents.extend(fetch_markdown(provider, repo_path, git_ref.as_ref(), &missing).await);
+        }
+
+        let mut anchors_by_file: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+        for (path, content) in &contents {
+            if let Ok(content) = content {
+                anchors_by_file.insert(path.as_str(), anchors(content)?);
+            }
+        }
+
+        let mut checked = 0;
+        let mut broken: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for (source, links) in &links_by_file {
+            for link in links.iter().filter(|link| !is_external(&link.target)) {
+                checked += 1;
+
+                let problem = match resolve(source, &link.target) {
+                    None => Some("points outside of the repository".to_string()),
+                    Some((path, fragment)) => {
+                        let path = path.trim_end_matches('/');
+                        if !path.is_empty()
+                            && !existing.contains(path)
+                            && !directories.contains(path)
+                        {
+                            Some(format!("missing file {}", path))
+                        } else {
+                            match (fragment, anchors_by_file.get(path)) {
+                                (Some(fragment), Some(anchors))
+                                    if check_anchors
+                                        && !fragment.is_empty()
+                                        && !anchors.contains(&fragment.to_lowercase()) =>
+                                {
+                                    Some(format!("missing anchor #{} in {}", fragment, path))
+                                }
+                                _ => None,
+                            }
+                        }
+                    }
+                };
+
+                if let Some(problem) = problem {
+                    broken.entry(source).or_default().push(format!(
+                        "- line {}: `{}`: {}",
+                        link.line, link.target, problem
+                    ));
+                }
+            }
+        }
+
+        let broken_count: usize = broken.values().map(Vec::len).sum();
+        let mut formatted_output = format!(
+            "Checked {} relative links in {} markdown files of {}: {} broken\n",
+            checked,
+            links_by_file.len(),
+            repo_path,
+            broken_count
+        );
+
+        for (source, problems) in &broken {
+            formatted_output.push_str(&format!("\n## {}\n\n", source));
+            for problem in problems {
+                formatted_output.push_str(&format!("{}\n", problem));
+            }
+        }
+
+        if !unreadable.is_empty() {
+            formatted_output.push_str(&format!(
+                "\nCould not read {} markdown files:\n",
+                unreadable.len()
+            ));
+            for failure in unreadable {
+                formatted_output.push_str(&format!("- {}\n", failure));
+            }
+        }
+
+        if total_files > scanned.len() {
+            formatted_output.push_str(&format!(
+                "\n({} more markdown files not checked, raise max_files or narrow the path)\n",
+                total_files - scanned.len()
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "check_links".into(),
+            description: Some(format!(
+                "Scan the markdown files of a repository for relative links and images pointing at missing files or heading anchors, to maintain documentation. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Optional directory whose markdown files are checked. Default: the whole repository"
+                    },
+                    "check_anchors": {
+                        "type": "boolean",
+                        "description": "Optional flag to check that '#anchor' fragments match a heading of the linked markdown file. Default: true"
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "description": format!("Optional maximum number of markdown files checked. Default: {}", DEFAULT_MAX_FILES)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_outside_code_fences_are_extracted() {
+        let markdown = "See [the guide](docs/guide.md \"Guide\") and ![logo](<img/logo.png>).\n\n```\n[not a link](nowhere.md)\n```\n\n[ref]: ../CONTRIBUTING.md\n<a href=\"LICENSE\">license</a>\n";
+
+        let links: Vec<(usize, String)> = extract_links(markdown)
+            .unwrap()
+            .into_iter()
+            .map(|link| (link.line, link.target))
+            .collect();
+        assert_eq!(
+            links,
+            [
+                (1, "docs/guide.md".to_string()),
+                (1, "img/logo.png".to_string()),
+                (7, "../CONTRIBUTING.md".to_string()),
+                (8, "LICENSE".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn targets_resolve_against_their_file() {
+        assert_eq!(
+            resolve("docs/api/index.md", "../guide%20one.md#setup"),
+            Some(("docs/guide one.md".to_string(), Some("setup".to_string())))
+        );
+        assert_eq!(
+            resolve("docs/index.md", "/README.md?plain=1"),
+            Some(("README.md".to_string(), None))
+        );
+        assert_eq!(
+            resolve("docs/index.md", "#usage"),
+            Some(("docs/index.md".to_string(), Some("usage".to_string())))
+        );
+        assert_eq!(resolve("README.md", "../outside.md"), None);
+
+        assert!(is_external("https://example.com"));
+        assert!(is_external("mailto:someone@example.com"));
+        assert!(is_external("//cdn.example.com/logo.png"));
+        assert!(!is_external("docs/guide.md"));
+    }
+
+    #[test]
+    fn anchors_follow_github_slugs() {
+        let markdown = "# Getting Started!\n## Usage\n## Usage\n### [API](api.md) & CLI\n<a name=\"Custom\"></a>\n```\n# not a heading\n```\n#hashtag\n";
+
+        let anchors: Vec<String> = anchors(markdown).unwrap().into_iter().collect();
+        assert_eq!(
+            anchors,
+            ["api--cli", "custom", "getting-started", "usage", "usage-1"]
+        );
+    }
+
+    #[test]
+    fn relative_links_are_rewritten() {
+        let markdown = "[guide](guide.md#intro) [site](https://example.com) [top](#top)\n```\n[code](guide.md)\n```\n";
+
+        let rewritten = rewrite_relative_links(markdown, "docs/README.md", |path| {
+            Ok(format!("https://host/blob/main/{}", path))
+        })
+        .unwrap();
+        assert_eq!(
+            rewritten,
+            "[guide](https://host/blob/main/docs/guide.md#intro) [site](https://example.com) [top](#top)\n```\n[code](guide.md)\n```\n"
+        );
+    }
+}
@@ -0,0 +1,388 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::{GitProvider, GitRef};
+use serde_json::{Value, json};
+
+use crate::common::{
+    find_provider, required_arguments, string_argument, supported_providers, usize_argument,
+};
+
+/// What a hosting web URL points at
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum LinkTarget {
+    Repository,
+    /// A file at a reference, with an optional 1-based inclusive line range
+    File {
+        git_ref: GitRef,
+        path: String,
+        lines: Option<(usize, usize)>,
+    },
+    Commit(String),
+    PullRequest(u64),
+    Issue(u64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RepoLink {
+    pub provider: &'static str,
+    pub repo_path: String,
+    pub target: LinkTarget,
+}
+
+/// Lines shown around a referenced range unless asked otherwise
+pub(crate) const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// Parses "L10", "L10-L20" and "L10-20" fragments, as well as plain "10-20" ranges
+pub(crate) fn parse_line_fragment(fragment: &str) -> Option<(usize, usize)> {
+    let (start, end) = match fragment.split_once('-') {
+        Some((start, end)) => (start, Some(end)),
+        None => (fragment, None),
+    };
+
+    let start = start.trim_start_matches('L').parse::<usize>().ok()?;
+    let end = match end {
+        Some(end) => end.trim_start_matches('L').parse::<usize>().ok()?,
+        None => start,
+    };
+
+    Some((start.min(end), start.max(end)))
+}
+
+/// Renders the referenced lines of a file with surrounding context, numbering every line
+/// and marking the referenced ones with '>'
+pub(crate) fn excerpt(content: &str, (start, end): (usize, usize), context: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    let start = start.clamp(1, lines.len());
+    let end = end.clamp(start, lines.len());
+    let first = start.saturating_sub(context).max(1);
+    let last = (end + context).min(lines.len());
+    let width = last.to_string().len();
+
+    let mut output = String::new();
+    for (index, line) in lines.iter().enumerate().take(last).skip(first - 1) {
+        let number = index + 1;
+        let marker = if (start..=end).contains(&number) {
+            '>'
+        } else {
+            ' '
+        };
+        output.push_str(&format!(
+            "{} {:>width$} | {}\n",
+            marker,
+            number,
+            line,
+            width = width
+        ));
+    }
+
+    output
+}
+
+fn parse_number(segment: Option<&&str>) -> Result<u64> {
+    segment
+        .and_then(|s| s.parse::<u64>().ok())
+        .ok_or_else(|| anyhow!("Missing or invalid number in link"))
+}
+
+/// Interprets the segments after the repository path, shared by both hosts
+fn parse_target(segments: &[&str], lines: Option<(usize, usize)>) -> Result<LinkTarget> {
+    let decode = |s: &str| -> String {
+        urlencoding::decode(s)
+            .map(|s| s.into_owned())
+            .unwrap_or_else(|_| s.to_string())
+    };
+
+    match segments {
+        [] => Ok(LinkTarget::Repository),
+        // Branch names containing '/' cannot be told apart from the path, assume a single segment
+        ["blob" | "raw" | "resolve", git_ref, path @ ..] if !path.is_empty() => {
+            Ok(LinkTarget::File {
+                git_ref: GitRef::Branch(decode(git_ref)),
+                path: path.iter().map(|s| decode(s)).collect::<Vec<_>>().join("/"),
+                lines,
+            })
+        }
+        ["commit" | "commits", sha, ..] => Ok(LinkTarget::Commit(sha.to_string())),
+        ["pull" | "merge_requests", rest @ ..] => {
+            Ok(LinkTarget::PullRequest(parse_number(rest.first())?))
+        }
+        ["issues", rest @ ..] => Ok(LinkTarget::Issue(parse_number(rest.first())?)),
+        _ => Err(anyhow!(
+            "Unsupported link type: '{}'",
+            segments.first().unwrap_or(&"")
+        )),
+    }
+}
+
+/// Parses a GitHub, Gist, GitLab or Hugging Face web URL into the repository and the object it points at
+pub(crate) fn parse_link(url: &str) -> Result<RepoLink> {
+    let without_scheme = url
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://");
+
+    let (location, fragment) = match without_scheme.split_once('#') {
+        Some((location, fragment)) => (location, Some(fragment)),
+        None => (without_scheme, None),
+    };
+    let location = location.split('?').next().unwrap_or(location);
+    let lines = fragment.and_then(parse_line_fragment);
+
+    let (host, path) = location
+        .split_once('/')
+        .ok_or_else(|| anyhow!("Link has no repository path: {}", url))?;
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    match host {
+        "github.com" | "www.github.com" => {
+            if segments.len() < 2 {
+                return Err(anyhow!("Link has no repository path: {}", url));
+            }
+
+            Ok(RepoLink {
+                provider: "github",
+                repo_path: format!("{}/{}", segments[0], segments[1]),
+                target: parse_target(&segments[2..], lines)?,
+            })
+        }
+        "raw.githubusercontent.com" => {
+            if segments.len() < 4 {
+                return Err(anyhow!("Incomplete raw file link: {}", url));
+            }
+
+            let mut target_segments = vec!["raw"];
+            target_segments.extend_from_slice(&segments[2..]);
+
+            Ok(RepoLink {
+                provider: "github",
+                repo_path: format!("{}/{}", segments[0], segments[1]),
+                target: parse_target(&target_segments, lines)?,
+            })
+        }
+        "gist.github.com" => {
+            if segments.len() < 2 {
+                return Err(anyhow!("Link has no gist path: {}", url));
+            }
+
+            // Files of a gist are anchors of a single page, the whole gist is the target
+            Ok(RepoLink {
+                provider: "github-gist",
+                repo_path: format!("{}/{}", segments[0], segments[1]),
+                target: LinkTarget::Repository,
+            })
+        }
+        "gitlab.com" | "www.gitlab.com" => {
+            // Everything before "/-/" is the (possibly nested) project path
+            let separator = segments.iter().position(|s| *s == "-");
+            let (project, rest) = match separator {
+                Some(index) => (&segments[..index], &segments[index + 1..]),
+                None => (&segments[..], &[][..]),
+            };
+
+            // Personal snippets have no project, project snippets are addressed within theirs
+            if let ["snippets", id, ..] = rest {
+                let mut snippet_path = project.to_vec();
+                snippet_path.push(*id);
+                return Ok(RepoLink {
+                    provider: "gitlab-snippet",
+                    repo_path: snippet_path.join("/"),
+                    target: LinkTarget::Repository,
+                });
+            }
+
+            if project.len() < 2 {
+                return Err(anyhow!("Link has no repository path: {}", url));
+            }
+
+            Ok(RepoLink {
+                provider: "gitlab",
+                repo_path: project.join("/"),
+                target: parse_target(rest, lines)?,
+            })
+        }
+        "huggingface.co" | "www.huggingface.co" => {
+            // Datasets and Spaces are prefixed, models are not; ids of old models have no org
+            let id_start = match segments.first() {
+                Some(&"datasets") | Some(&"spaces") => 1,
+                _ => 0,
+            };
+            let id_len = match segments.get(id_start + 1) {
+                Some(&"tree") | Some(&"blob") | Some(&"resolve") | None => 1,
+                Some(_) => 2,
+            };
+            if segments.len() < id_start + id_len {
+                return Err(anyhow!("Link has no repository path: {}", url));
+            }
+
+            Ok(RepoLink {
+                provider: "huggingface",
+                repo_path: segments[..id_start + id_len].join("/"),
+                target: parse_target(&segments[id_start + id_len..], lines)?,
+            })
+        }
+        _ => Err(anyhow!("Unsupported hosting service: {}", host)),
+    }
+}
+
+pub struct ResolveLink {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ResolveLink {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ResolveLink {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let url = string_argument(&args, "url")?;
+        let context_lines = usize_argument(&args, "context_lines").unwrap_or(DEFAULT_CONTEXT_LINES);
+        let link = parse_link(url)?;
+        let provider = find_provider(&self.providers, link.provider)?;
+        let repo_path = link.repo_path.as_str();
+
+        let text = match link.target {
+            LinkTarget::Repository => {
+                let tree = provider
+                    .get_tree_structure(repo_path, None, Default::default())
+                    .await
+                    .map_err(|e| anyhow!("Error getting repository tree structure: {}", e))?;
+
+                format!("```\n{}\n```", tree)
+            }
+            LinkTarget::File {
+                git_ref,
+                path,
+                lines,
+            } => {
+                let content = provider
+                    .get_file_content(repo_path, &path, Some(git_ref))
+                    .await
+                    .map_err(|e| anyhow!("Error reading file content: {}", e))?;
+
+                let extension = path.rsplit('.').next().unwrap_or("");
+
+                match lines {
+                    Some(range) => format!(
+                        "{} (lines {}-{})\n\n```{}\n{}```",
+                        path,
+                        range.0,
+                        range.1,
+                        extension,
+                        excerpt(&content, range, context_lines)
+                    ),
+                    None => format!(
+                        "{}\n\n```{}\n{}\n```",
+                        path,
+                        extension,
+                        content.trim_end_matches('\n')
+                    ),
+                }
+            }
+            LinkTarget::Commit(sha) => {
+                let details = provider
+                    .get_commit(repo_path, &sha)
+                    .await
+                    .map_err(|e| anyhow!("Error getting commit: {}", e))?;
+
+                let mut output = format!(
+                    "Commit {}\nAuthor: {}\nDate: {}\n",
+                    details.commit.sha, details.commit.author, details.commit.date
+                );
+                if let Some(verification) = &details.commit.verification {
+                    output.push_str(&format!("Signature: {}\n", verification.describe()));
+                }
+                output.push_str(&format!("\n{}\n", details.commit.message.trim_end()));
+                for file in details.files {
+                    output.push_str(&format!(
+                        "\n## {} (+{} -{})\n",
+                        file.path, file.additions, file.deletions
+                    ));
+                    if let Some(patch) = file.patch {
+                        output.push_str(&format!("\n```diff\n{}\n```\n", patch.trim_end()));
+                    }
+                }
+
+                output
+            }
+            LinkTarget::PullRequest(number) => {
+                let pull = provider
+                    .get_pull_request(repo_path, number)
+                    .await
+                    .map_err(|e| anyhow!("Error getting pull request: {}", e))?;
+                let summary = &pull.summary;
+
+                format!(
+                    "#{} {}\nState: {}{}\nAuthor: {}\nBranches: {} -> {}\nLabels: {}\nURL: {}\n\n{}",
+                    summary.number,
+                    summary.title,
+                    summary.state,
+                    if summary.draft { " (draft)" } else { "" },
+                    summary.author,
+                    summary.source_branch.as_deref().unwrap_or("?"),
+                    summary.target_branch.as_deref().unwrap_or("?"),
+                    summary.labels.join(", "),
+                    summary.url,
+                    pull.body.as_deref().unwrap_or("(no description)")
+                )
+            }
+            LinkTarget::Issue(number) => {
+                let issue = provider
+                    .get_issue(repo_path, number)
+                    .await
+                    .map_err(|e| anyhow!("Error getting issue: {}", e))?;
+
+                format!(
+                    "#{} {}\nState: {}\nAuthor: {}\nCreated: {}\nLabels: {}\nURL: {}\n\n{}",
+                    issue.number,
+                    issue.title,
+                    issue.state,
+                    issue.author,
+                    issue.created_at,
+                    issue.labels.join(", "),
+                    issue.url,
+                    issue.body.as_deref().unwrap_or("(no description)")
+                )
+            }
+        };
+
+        Ok(vec![ToolContent::Text { text }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "resolve_link".into(),
+            description: Some(format!(
+                "Fetch what a GitHub or GitLab web link points at: a file (with '#L10-L20' line fragments), a commit, a pull/merge request, an issue, or a repository. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "Web URL to resolve (e.g., 'https://github.com/rust-lang/rust/blob/master/README.md#L1-L10')"
+                    },
+                    "context_lines": {
+                        "type": "integer",
+                        "description": format!("Optional number of lines shown around a referenced line range. Default: {}", DEFAULT_CONTEXT_LINES)
+                    }
+                },
+                "required": ["url"]
+            }),
+        }
+    }
+}
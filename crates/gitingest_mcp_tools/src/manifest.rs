@@ -0,0 +1,211 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::{GitProvider, RepoItem};
+use serde_json::{Value, json};
+
+use crate::common::{
+    git_ref_argument, preset_names, repo_argument, required_arguments, supported_providers,
+    tree_options_argument,
+};
+
+/// Blob SHA and size of a manifest line, `-` standing for values the provider did not report
+struct Entry<'a> {
+    sha: &'a str,
+    size: &'a str,
+}
+
+impl Entry<'_> {
+    /// Whether the file changed between two manifests, comparing SHAs when both are known
+    fn differs(&self, other: &Entry) -> bool {
+        if self.sha != "-" && other.sha != "-" {
+            self.sha != other.sha
+        } else {
+            self.size != other.size
+        }
+    }
+}
+
+/// How a file differs between two manifests
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Change {
+    Added,
+    Changed,
+    Removed,
+}
+
+impl Change {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Changed => "changed",
+            Change::Removed => "removed",
+        }
+    }
+}
+
+/// Renders files as tab-separated `sha size path` manifest lines, sorted by path
+pub(crate) fn render_manifest(files: &[RepoItem]) -> String {
+    let mut files: Vec<&RepoItem> = files.iter().collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    files
+        .iter()
+        .map(|file| {
+            format!(
+                "{}\t{}\t{}",
+                file.sha.as_deref().unwrap_or("-"),
+                file.size
+                    .map(|size| size.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                file.path
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Reads the entries of a manifest by path, skipping lines that are not entries
+fn parse_manifest(manifest: &str) -> BTreeMap<&str, Entry<'_>> {
+    manifest
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let sha = fields.next()?;
+            let size = fields.next()?;
+            let path = fields.next()?;
+            Some((path, Entry { sha, size }))
+        })
+        .collect()
+}
+
+/// The files added, changed or removed between two manifests, sorted by path
+pub(crate) fn compare_manifests<'a>(before: &'a str, after: &'a str) -> Vec<(&'a str, Change)> {
+    let before = parse_manifest(before);
+    let after = parse_manifest(after);
+
+    let mut changes: Vec<(&str, Change)> = after
+        .iter()
+        .filter_map(|(path, entry)| match before.get(path) {
+            None => Some((*path, Change::Added)),
+            Some(old) if old.differs(entry) => Some((*path, Change::Changed)),
+            Some(_) => None,
+        })
+        .chain(
+            before
+                .keys()
+                .filter(|path| !after.contains_key(*path))
+                .map(|path| (*path, Change::Removed)),
+        )
+        .collect();
+    changes.sort_by(|a, b| a.0.cmp(b.0));
+
+    changes
+}
+
+pub struct RepositoryManifest {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositoryManifest {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositoryManifest {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let previous = args.get("previous").and_then(|v| v.as_str());
+
+        let options = tree_options_argument(&args)?;
+
+        let files = provider
+            .list_files(repo_path, git_ref, &options)
+            .await
+            .map_err(|e| anyhow!("Error listing repository files: {}", e))?;
+        let manifest = render_manifest(&files);
+
+        let mut formatted_output = String::new();
+        if let Some(previous) = previous {
+            let changes = compare_manifests(previous, &manifest);
+
+            formatted_output.push_str(&format!(
+                "{} changed files since the previous manifest\n\n",
+                changes.len()
+            ));
+            for (path, change) in changes {
+                formatted_output.push_str(&format!("- {}: {}\n", change.as_str(), path));
+            }
+            formatted_output.push('\n');
+        }
+
+        formatted_output.push_str(&format!(
+            "Manifest of {} ({} files, blob SHA, size in bytes and path per line)\n\n",
+            repo_path,
+            files.len()
+        ));
+        formatted_output.push_str(&manifest);
+        formatted_output.push('\n');
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_manifest".into(),
+            description: Some(format!(
+                "List the blob SHA and size of every file of a repository matching the patterns, and the files changed since a previous manifest, to detect changes between sessions without ingesting contents again. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "previous": {
+                        "type": "string",
+                        "description": "Optional manifest returned by an earlier call, to list the files added, removed or changed since"
+                    },
+                    "exclude_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to exclude"
+                    },
+                    "include_patterns": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of patterns to include"
+                    },
+                    "preset": {
+                        "type": "string",
+                        "description": format!("Optional comma-separated list of ecosystem presets adding their source and exclude patterns: {}", preset_names())
+                    },
+                    "extensions": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of file extensions to include, e.g. 'rs,toml,md'"
+                    },
+                    "case_insensitive": {
+                        "type": "boolean",
+                        "description": "Optional flag to match every pattern regardless of case. Default: false"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_mirror::{CloneOptions, MirrorCache};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::common::{repo_argument, required_arguments, supported_providers, usize_argument};
+
+pub struct CloneToCache {
+    providers: Vec<Arc<dyn GitProvider>>,
+    cache: Arc<MirrorCache>,
+}
+
+impl CloneToCache {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>, cache: Arc<MirrorCache>) -> Self {
+        Self { providers, cache }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for CloneToCache {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+
+        // Arguments override the server's defaults, a depth of 0 asks for the full history
+        let defaults = self.cache.clone_options();
+        let options = CloneOptions {
+            depth: match usize_argument(&args, "depth") {
+                Some(0) => None,
+                Some(depth) => Some(u32::try_from(depth)?),
+                None => defaults.depth,
+            },
+            filter: match args.get("filter").and_then(|v| v.as_str()) {
+                Some("") | Some("none") => None,
+                Some(filter) => Some(filter.to_string()),
+                None => defaults.filter.clone(),
+            },
+        }
+        .validated()?;
+
+        let remote = provider
+            .clone_remote(repo_path)
+            .await
+            .map_err(|e| anyhow!("Error preparing the clone: {}", e))?;
+        let status = self
+            .cache
+            .sync(provider.name(), repo_path, &remote, &options)
+            .await
+            .map_err(|e| anyhow!("Error updating the mirror of {}: {}", repo_path, e))?;
+
+        let mut formatted_output = format!(
+            "{} {} at {}\n\n",
+            if status.cloned {
+                "Cloned a mirror of"
+            } else {
+                "Fetched new commits into the mirror of"
+            },
+            repo_path,
+            status.path.display()
+        );
+        formatted_output.push_str(&format!("- Default branch: {}\n", status.default_branch));
+        formatted_output.push_str(&format!("- Branches: {}\n", status.branches));
+        formatted_output.push_str(&format!("- Tags: {}\n", status.tags));
+        if status.shallow {
+            formatted_output.push_str(&format!(
+                "- History: shallow, {} commits per reference, older commits and merge bases may be missing\n",
+                options
+                    .depth
+                    .map_or_else(|| "limited".to_string(), |depth| depth.to_string())
+            ));
+        }
+        if let Some(filter) = &status.filter {
+            formatted_output.push_str(&format!(
+                "- Partial clone: {}, file contents are fetched when first needed\n",
+                filter
+            ));
+        }
+        formatted_output.push_str(
+            "\nCommit history, commit details and comparisons of this repository are now read from the mirror. Call this tool again to fetch new commits.\n",
+        );
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "clone_to_cache".into(),
+            description: Some(format!(
+                "Clone a bare mirror of a repository into the local cache, or fetch its new commits, so history and diff analysis runs on local git instead of the rate-limited API. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "depth": {
+                        "type": "integer",
+                        "description": "Optional number of commits kept per reference, 0 for the full history. Default: the server's GITINGEST_CLONE_DEPTH, else the full history"
+                    },
+                    "filter": {
+                        "type": "string",
+                        "description": "Optional partial clone filter, e.g. 'blob:none' to fetch file contents only when needed, or 'none' for a full clone. Default: the server's GITINGEST_CLONE_FILTER, else none"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
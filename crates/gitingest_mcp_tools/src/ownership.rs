@@ -0,0 +1,388 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::GitProvider;
+use regex::Regex;
+use serde_json::{Value, json};
+
+use crate::common::{
+    git_ref_argument, list_argument, repo_argument, required_arguments, string_argument,
+    supported_providers,
+};
+
+/// Where GitHub looks for a CODEOWNERS file, first found wins
+const GITHUB_LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+/// Where GitLab looks for a CODEOWNERS file, first found wins
+const GITLAB_LOCATIONS: &[&str] = &["CODEOWNERS", "docs/CODEOWNERS", ".gitlab/CODEOWNERS"];
+
+/// One `pattern owner...` line of a CODEOWNERS file
+#[derive(Debug)]
+struct Rule {
+    pattern: String,
+    owners: Vec<String>,
+    line: usize,
+    regex: Regex,
+}
+
+/// A GitLab section, whose last matching rule applies independently of other sections. GitHub
+/// files are a single unnamed section.
+#[derive(Debug, Default)]
+struct Section {
+    name: Option<String>,
+    optional: bool,
+    approvals: Option<usize>,
+    /// Owners of the rules of the section that list none
+    default_owners: Vec<String>,
+    rules: Vec<Rule>,
+}
+
+/// Translates a CODEOWNERS pattern, which follows gitignore rules, into a regex over paths
+///
+/// - A pattern starting with or containing a `/` (other than a trailing one) is anchored to
+///   the root, others match at any depth
+/// - A trailing `/` matches the contents of directories only
+/// - `*` and `?` stay within a path segment, `**` crosses segments
+/// - A pattern matching a directory matches everything below it, except one ending in a single
+///   `*`, which only matches the files directly inside
+fn pattern_regex(pattern: &str) -> Result<Regex> {
+    let anchored = pattern.starts_with('/') || pattern.trim_end_matches('/').contains('/');
+    let directory_only = pattern.ends_with('/');
+    let body = pattern.trim_start_matches('/').trim_end_matches('/');
+
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    regex.push_str(&regex::escape(&escaped.to_string()));
+                }
+            }
+            '[' => {
+                // Character classes are kept, unless never closed
+                let class: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                regex.push('[');
+                regex.push_str(&class.replace('\\', "\\\\").replacen('!', "^", 1));
+                regex.push(']');
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push_str(if directory_only {
+        "/.*$"
+    } else if body.ends_with('*') && !body.ends_with("**") {
+        "$"
+    } else {
+        "(?:/.*)?$"
+    });
+
+    Ok(Regex::new(&regex)?)
+}
+
+/// Parses a CODEOWNERS file, reading `[Section]` headers when `sections` is set
+fn parse_codeowners(content: &str, sections: bool) -> Result<Vec<Section>> {
+    let header = Regex::new(r"^(\^)?\[([^\]]+)\](?:\[(\d+)\])?\s*(.*)$")?;
+
+    let mut parsed = vec![Section::default()];
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if sections && let Some(captures) = header.captures(line) {
+            parsed.push(Section {
+                name: Some(captures[2].to_string()),
+                optional: captures.get(1).is_some(),
+                approvals: captures.get(3).and_then(|m| m.as_str().parse().ok()),
+                default_owners: captures[4].split_whitespace().map(String::from).collect(),
+                rules: vec![],
+            });
+            continue;
+        }
+
+        // Spaces in patterns are escaped, owners end at an inline comment
+        let mut pattern = String::new();
+        let mut rest = line;
+        while let Some((head, tail)) = rest.split_once(char::is_whitespace) {
+            if let Some(escaped) = head.strip_suffix('\\') {
+                pattern.push_str(escaped);
+                pattern.push(' ');
+                rest = tail;
+            } else {
+                pattern.push_str(head);
+                rest = tail;
+                break;
+            }
+        }
+        if pattern.is_empty() {
+            pattern.push_str(rest);
+            rest = "";
+        }
+        let owners: Vec<String> = rest
+            .split_whitespace()
+            .take_while(|owner| !owner.starts_with('#'))
+            .map(String::from)
+            .collect();
+
+        let Ok(regex) = pattern_regex(&pattern) else {
+            continue;
+        };
+        let section = parsed.last_mut().expect("a section is always open");
+        section.rules.push(Rule {
+            pattern,
+            owners,
+            line: index + 1,
+            regex,
+        });
+    }
+
+    // GitLab merges sections sharing a name
+    let mut merged: Vec<Section> = Vec::new();
+    for section in parsed {
+        match merged.iter_mut().find(|existing| {
+            existing.name.is_some()
+                && existing.name.as_deref().map(str::to_lowercase)
+                    == section.name.as_deref().map(str::to_lowercase)
+        }) {
+            Some(existing) => existing.rules.extend(section.rules),
+            None => merged.push(section),
+        }
+    }
+
+    Ok(merged)
+}
+
+/// The rules of a section matching a path, the last one deciding
+fn matching_rules<'a>(section: &'a Section, path: &str) -> Vec<&'a Rule> {
+    section
+        .rules
+        .iter()
+        .filter(|rule| rule.regex.is_match(path))
+        .collect()
+}
+
+pub struct WhoOwnsPath {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl WhoOwnsPath {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for WhoOwnsPath {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let mut paths = list_argument(&args, "paths");
+        if let Ok(path) = string_argument(&args, "path") {
+            paths.insert(0, path.to_string());
+        }
+        if paths.is_empty() {
+            return Err(anyhow!("Missing path or paths"));
+        }
+
+        let gitlab = provider.kind() == "gitlab";
+        let locations = if gitlab {
+            GITLAB_LOCATIONS
+        } else {
+            GITHUB_LOCATIONS
+        };
+
+        let mut codeowners = None;
+        for location in locations {
+            if let Ok(content) = provider
+                .get_file_content(repo_path, location, git_ref.clone())
+                .await
+            {
+                codeowners = Some((*location, content));
+                break;
+            }
+        }
+        let Some((location, content)) = codeowners else {
+            return Ok(vec![ToolContent::Text {
+                text: format!(
+                    "No CODEOWNERS file in {}, looked for {}",
+                    repo_path,
+                    locations.join(", ")
+                ),
+            }]);
+        };
+        let sections = parse_codeowners(&content, gitlab)?;
+
+        let mut formatted_output =
+            format!("# Path owners in {}\n\nRules: {}\n", repo_path, location);
+        for path in &paths {
+            let path = path.trim_matches('/');
+            formatted_output.push_str(&format!("\n## {}\n\n", path));
+
+            let mut owned = false;
+            for section in &sections {
+                let rules = matching_rules(section, path);
+                let Some((rule, overridden)) = rules.split_last() else {
+                    continue;
+                };
+                owned = true;
+
+                let owners = if rule.owners.is_empty() {
+                    &section.default_owners
+                } else {
+                    &rule.owners
+                };
+                if let Some(name) = &section.name {
+                    formatted_output.push_str(&format!("### Section {}", name));
+                    if section.optional {
+                        formatted_output.push_str(" (optional)");
+                    }
+                    if let Some(approvals) = section.approvals {
+                        formatted_output.push_str(&format!(" ({} approvals)", approvals));
+                    }
+                    formatted_output.push_str("\n\n");
+                }
+
+                // A matching rule without owners removes the ownership of earlier rules
+                if owners.is_empty() {
+                    formatted_output.push_str("Owners: none, the path is explicitly unowned\n");
+                } else {
+                    formatted_output.push_str(&format!("Owners: {}\n", owners.join(" ")));
+                }
+                formatted_output.push_str(&format!(
+                    "Rule: `{}` ({}:{})\n",
+                    rule.pattern, location, rule.line
+                ));
+                if !overridden.is_empty() {
+                    let overridden: Vec<String> = overridden
+                        .iter()
+                        .rev()
+                        .map(|rule| format!("`{}` (line {})", rule.pattern, rule.line))
+                        .collect();
+                    formatted_output.push_str(&format!(
+                        "Overrides earlier matches: {}\n",
+                        overridden.join(", ")
+                    ));
+                }
+                formatted_output.push('\n');
+            }
+
+            if !owned {
+                formatted_output.push_str("No rule matches, the path has no owners\n\n");
+            }
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output.trim_end().to_string() + "\n",
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "who_owns_path".into(),
+            description: Some(format!(
+                "Tell which users and teams own paths of a repository according to its CODEOWNERS file, applying CODEOWNERS precedence (the last matching rule wins) and GitLab sections, and naming the deciding rule and the earlier rules it overrides. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Path of the file or directory to look up, e.g. 'src/api/handler.rs'"
+                    },
+                    "paths": {
+                        "type": "string",
+                        "description": "Optional comma-separated further paths to look up in the same call"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owners_of(content: &str, path: &str) -> Option<Vec<String>> {
+        let sections = parse_codeowners(content, false).unwrap();
+        matching_rules(&sections[0], path)
+            .last()
+            .map(|rule| rule.owners.clone())
+    }
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let content = "*.rs @rust\n/src/api/ @api-team\n";
+        assert_eq!(owners_of(content, "src/main.rs").unwrap(), ["@rust"]);
+        assert_eq!(
+            owners_of(content, "src/api/handler.rs").unwrap(),
+            ["@api-team"]
+        );
+    }
+
+    #[test]
+    fn unanchored_patterns_match_at_any_depth() {
+        let content = "docs/ @writers\nMakefile @build\n";
+        assert_eq!(owners_of(content, "docs/guide.md").unwrap(), ["@writers"]);
+        assert_eq!(owners_of(content, "crates/a/Makefile").unwrap(), ["@build"]);
+        assert!(owners_of(content, "docs").is_none());
+    }
+
+    #[test]
+    fn single_star_stays_within_a_segment() {
+        let content = "/docs/* @writers\n/apps/**/tests @qa\n";
+        assert!(owners_of(content, "docs/guide.md").is_some());
+        assert!(owners_of(content, "docs/api/guide.md").is_none());
+        assert!(owners_of(content, "apps/web/unit/tests/a.rs").is_some());
+    }
+
+    #[test]
+    fn rules_without_owners_unown_paths() {
+        let content = "* @everyone\n/vendor/\n";
+        assert!(owners_of(content, "vendor/lib.rs").unwrap().is_empty());
+    }
+
+    #[test]
+    fn gitlab_sections_apply_independently() {
+        let content = "[Backend][2] @backend\n*.rs\n^[Docs]\n*.md @writers\n*.rs @reviewers\n";
+        let sections = parse_codeowners(content, true).unwrap();
+        let deciding: Vec<_> = sections
+            .iter()
+            .filter_map(|section| matching_rules(section, "src/lib.rs").last().copied())
+            .collect();
+        assert_eq!(deciding.len(), 2);
+        assert!(deciding[0].owners.is_empty());
+        assert_eq!(sections[1].default_owners, ["@backend"]);
+        assert_eq!(sections[1].approvals, Some(2));
+        assert!(sections[2].optional);
+        assert_eq!(deciding[1].owners, ["@reviewers"]);
+    }
+}
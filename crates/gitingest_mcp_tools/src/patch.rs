@@ -0,0 +1,397 @@
+use anyhow::{Result, anyhow};
+
+/// A single file section of a unified diff
+#[derive(Debug, Clone)]
+pub(crate) struct FilePatch {
+    /// Path before the change, `None` when the file is created
+    pub old_path: Option<String>,
+    /// Path after the change, `None` when the file is deleted
+    pub new_path: Option<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    /// Returns the path the patch refers to, preferring the new path
+    pub fn path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Hunk {
+    pub header: String,
+    pub old_start: usize,
+    pub lines: Vec<HunkLine>,
+    /// The hunk ends the original file, which has no final newline
+    pub old_missing_newline: bool,
+    /// The hunk ends the resulting file, which has no final newline
+    pub new_missing_newline: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum HunkLine {
+    Context(String),
+    Removed(String),
+    Added(String),
+}
+
+impl Hunk {
+    /// Lines the hunk expects to find in the original file
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(s) | HunkLine::Removed(s) => Some(s.as_str()),
+                HunkLine::Added(_) => None,
+            })
+            .collect()
+    }
+
+    /// Lines the hunk leaves in the resulting file
+    fn new_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(s) | HunkLine::Added(s) => Some(s.as_str()),
+                HunkLine::Removed(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Outcome of applying a file patch to its original content
+#[derive(Debug, Clone)]
+pub(crate) enum ApplyOutcome {
+    /// All hunks applied; holds the resulting content (`None` if the file was deleted)
+    Applied(Option<String>),
+    /// Some hunks did not match the original content
+    Conflict(Vec<HunkConflict>),
+    /// The patch creates a file that already exists
+    AlreadyExists,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct HunkConflict {
+    pub header: String,
+    pub expected: Vec<String>,
+}
+
+fn strip_path_prefix(raw: &str) -> Option<String> {
+    // Drop trailing timestamps emitted by `diff -u`
+    let path = raw.split('\t').next().unwrap_or(raw).trim();
+
+    if path == "/dev/null" {
+        return None;
+    }
+
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+
+    Some(path.to_string())
+}
+
+fn parse_range(range: &str) -> Result<(usize, usize)> {
+    let mut parts = range.splitn(2, ',');
+    let start = parts
+        .next()
+        .unwrap_or("")
+        .parse::<usize>()
+        .map_err(|_| anyhow!("Invalid hunk range: {}", range))?;
+    let count = match parts.next() {
+        Some(count) => count
+            .parse::<usize>()
+            .map_err(|_| anyhow!("Invalid hunk range: {}", range))?,
+        None => 1,
+    };
+
+    Ok((start, count))
+}
+
+fn parse_hunk_header(line: &str) -> Result<(usize, usize, usize)> {
+    // @@ -old_start,old_count +new_start,new_count @@ optional section
+    let body = line
+        .strip_prefix("@@ ")
+        .and_then(|rest| rest.split(" @@").next())
+        .ok_or_else(|| anyhow!("Invalid hunk header: {}", line))?;
+
+    let mut ranges = body.split_whitespace();
+    let old = ranges
+        .next()
+        .and_then(|r| r.strip_prefix('-'))
+        .ok_or_else(|| anyhow!("Invalid hunk header: {}", line))?;
+    let new = ranges
+        .next()
+        .and_then(|r| r.strip_prefix('+'))
+        .ok_or_else(|| anyhow!("Invalid hunk header: {}", line))?;
+
+    let (old_start, old_count) = parse_range(old)?;
+    let (_, new_count) = parse_range(new)?;
+
+    Ok((old_start, old_count, new_count))
+}
+
+/// Parses a unified diff (as produced by `git diff` or `diff -u`) into per-file patches
+pub(crate) fn parse_unified_diff(diff: &str) -> Result<Vec<FilePatch>> {
+    let mut patches: Vec<FilePatch> = Vec::new();
+    let lines: Vec<&str> = diff.lines().collect();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+
+        if let Some(old) = line.strip_prefix("--- ") {
+            let new = lines
+                .get(i + 1)
+                .and_then(|l| l.strip_prefix("+++ "))
+                .ok_or_else(|| anyhow!("Missing '+++' line after '{}'", line))?;
+
+            patches.push(FilePatch {
+                old_path: strip_path_prefix(old),
+                new_path: strip_path_prefix(new),
+                hunks: Vec::new(),
+            });
+            i += 2;
+            continue;
+        }
+
+        if line.starts_with("@@ ") {
+            let patch = patches
+                .last_mut()
+                .ok_or_else(|| anyhow!("Hunk found before any file header"))?;
+            let (old_start, mut old_remaining, mut new_remaining) = parse_hunk_header(line)?;
+
+            let mut hunk = Hunk {
+                header: line.to_string(),
+                old_start,
+                lines: Vec::new(),
+                old_missing_newline: false,
+                new_missing_newline: false,
+            };
+            i += 1;
+
+            // The marker may also follow the last line of the hunk
+            while i < lines.len()
+                && (old_remaining > 0 || new_remaining > 0 || lines[i].starts_with('\\'))
+            {
+                let hunk_line = lines[i];
+
+                if let Some(text) = hunk_line.strip_prefix('-') {
+                    hunk.lines.push(HunkLine::Removed(text.to_string()));
+                    old_remaining = old_remaining.saturating_sub(1);
+                } else if let Some(text) = hunk_line.strip_prefix('+') {
+                    hunk.lines.push(HunkLine::Added(text.to_string()));
+                    new_remaining = new_remaining.saturating_sub(1);
+                } else if hunk_line.starts_with('\\') {
+                    // "\ No newline at end of file", about the line before it
+                    match hunk.lines.last() {
+                        Some(HunkLine::Removed(_)) => hunk.old_missing_newline = true,
+                        Some(HunkLine::Added(_)) => hunk.new_missing_newline = true,
+                        Some(HunkLine::Context(_)) => {
+                            hunk.old_missing_newline = true;
+                            hunk.new_missing_newline = true;
+                        }
+                        None => {}
+                    }
+                } else {
+                    // Some tools strip the leading space from empty context lines
+                    let text = hunk_line.strip_prefix(' ').unwrap_or(hunk_line);
+                    hunk.lines.push(HunkLine::Context(text.to_string()));
+                    old_remaining = old_remaining.saturating_sub(1);
+                    new_remaining = new_remaining.saturating_sub(1);
+                }
+                i += 1;
+            }
+
+            patch.hunks.push(hunk);
+            continue;
+        }
+
+        i += 1;
+    }
+
+    if patches.is_empty() {
+        return Err(anyhow!("No file changes found in the provided diff"));
+    }
+
+    Ok(patches)
+}
+
+fn matches_at(lines: &[String], expected: &[&str], position: usize) -> bool {
+    position + expected.len() <= lines.len()
+        && expected
+            .iter()
+            .zip(&lines[position..])
+            .all(|(e, l)| e.trim_end() == l.trim_end())
+}
+
+/// Finds where a hunk applies, starting at its declared position and searching outwards
+fn locate_hunk(lines: &[String], expected: &[&str], hint: usize, floor: usize) -> Option<usize> {
+    if expected.is_empty() {
+        return Some(hint.clamp(floor, lines.len()));
+    }
+
+    let upper = lines.len().saturating_sub(expected.len());
+    for offset in 0..=lines.len() {
+        let after = hint + offset;
+        if after >= floor && after <= upper && matches_at(lines, expected, after) {
+            return Some(after);
+        }
+
+        if offset > 0
+            && let Some(before) = hint.checked_sub(offset)
+            && before >= floor
+            && before <= upper
+            && matches_at(lines, expected, before)
+        {
+            return Some(before);
+        }
+    }
+
+    None
+}
+
+/// Applies a file patch to the original content (`None` when the file does not exist)
+pub(crate) fn apply_file_patch(original: Option<&str>, patch: &FilePatch) -> ApplyOutcome {
+    if patch.old_path.is_none() && original.is_some() {
+        return ApplyOutcome::AlreadyExists;
+    }
+
+    let original = original.unwrap_or("");
+    let mut trailing_newline = original.is_empty() || original.ends_with('\n');
+    let mut lines: Vec<String> = original.lines().map(|l| l.to_string()).collect();
+    let mut conflicts = Vec::new();
+
+    // Offset between the declared hunk positions and the current buffer
+    let mut shift: isize = 0;
+    let mut floor = 0;
+
+    for hunk in &patch.hunks {
+        let expected = hunk.old_lines();
+        let replacement = hunk.new_lines();
+
+        // Hunks for empty ranges declare the line *before* the insertion point
+        let declared = if expected.is_empty() {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let hint = (declared as isize + shift).max(0) as usize;
+
+        match locate_hunk(&lines, &expected, hint, floor) {
+            Some(position) => {
+                lines.splice(
+                    position..position + expected.len(),
+                    replacement.iter().map(|l| l.to_string()),
+                );
+                shift += replacement.len() as isize - expected.len() as isize;
+                floor = position + replacement.len();
+
+                if hunk.new_missing_newline {
+                    trailing_newline = false;
+                } else if hunk.old_missing_newline {
+                    trailing_newline = true;
+                }
+            }
+            None => conflicts.push(HunkConflict {
+                header: hunk.header.clone(),
+                expected: expected.iter().map(|l| l.to_string()).collect(),
+            }),
+        }
+    }
+
+    if !conflicts.is_empty() {
+        return ApplyOutcome::Conflict(conflicts);
+    }
+
+    if patch.new_path.is_none() {
+        return ApplyOutcome::Applied(None);
+    }
+
+    let mut content = lines.join("\n");
+    if trailing_newline && !content.is_empty() {
+        content.push('\n');
+    }
+
+    ApplyOutcome::Applied(Some(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(original: Option<&str>, diff: &str) -> ApplyOutcome {
+        let patches = parse_unified_diff(diff).unwrap();
+        assert_eq!(patches.len(), 1);
+        apply_file_patch(original, &patches[0])
+    }
+
+    fn applied(original: Option<&str>, diff: &str) -> Option<String> {
+        match apply(original, diff) {
+            ApplyOutcome::Applied(content) => content,
+            outcome => panic!("patch did not apply: {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn applies_every_hunk_of_a_file() {
+        let original = "a\nb\nc\nd\ne\nf\ng\nh\n";
+        let diff = "--- a/letters.txt\n+++ b/letters.txt\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n@@ -6,3 +6,4 @@\n f\n g\n+g2\n h\n";
+
+        assert_eq!(
+            applied(Some(original), diff).unwrap(),
+            "a\nB\nc\nd\ne\nf\ng\ng2\nh\n"
+        );
+    }
+
+    #[test]
+    fn finds_hunks_away_from_their_declared_lines() {
+        // Two lines were added at the top since the diff was made, and a trailing space dropped
+        let original = "new\nnew\na\nb \nc\n";
+        let diff = "--- a/f\n+++ b/f\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n";
+
+        assert_eq!(
+            applied(Some(original), diff).unwrap(),
+            "new\nnew\na\nB\nc\n"
+        );
+
+        match apply(Some("x\ny\nz\n"), diff) {
+            ApplyOutcome::Conflict(conflicts) => {
+                assert_eq!(conflicts.len(), 1);
+                assert_eq!(conflicts[0].expected, ["a", "b", "c"]);
+            }
+            outcome => panic!("expected a conflict: {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn creates_and_deletes_files() {
+        let create = "--- /dev/null\n+++ b/src/new.rs\n@@ -0,0 +1,2 @@\n+fn new() {}\n+\n";
+        assert_eq!(applied(None, create).unwrap(), "fn new() {}\n\n");
+        assert!(matches!(
+            apply(Some("fn old() {}\n"), create),
+            ApplyOutcome::AlreadyExists
+        ));
+
+        let delete = "--- a/src/old.rs\n+++ /dev/null\n@@ -1 +0,0 @@\n-fn old() {}\n";
+        assert_eq!(applied(Some("fn old() {}\n"), delete), None);
+    }
+
+    #[test]
+    fn follows_missing_final_newlines() {
+        let add_newline =
+            "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+b\n";
+        assert_eq!(applied(Some("a\nb"), add_newline).unwrap(), "a\nb\n");
+
+        let drop_newline =
+            "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n+b\n\\ No newline at end of file\n";
+        assert_eq!(applied(Some("a\nb\n"), drop_newline).unwrap(), "a\nb");
+
+        let create = "--- /dev/null\n+++ b/f\n@@ -0,0 +1 @@\n+only\n\\ No newline at end of file\n";
+        assert_eq!(applied(None, create).unwrap(), "only");
+    }
+}
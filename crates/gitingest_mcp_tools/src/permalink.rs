@@ -0,0 +1,273 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::{GitProvider, GitRef, encoding::encode_path};
+use serde_json::{Value, json};
+
+use crate::common::{
+    bool_argument, find_provider, git_ref_argument, parse_repo_identifier, required_arguments,
+    supported_providers, usize_argument,
+};
+
+/// Bitbucket has no provider, its links are built without resolving anything
+const BITBUCKET_URL: &str = "https://bitbucket.org";
+
+/// The fragment highlighting a line range, as each hosting service spells it
+fn line_fragment(provider: &str, (start, end): (usize, usize)) -> String {
+    match provider {
+        "bitbucket" if start == end => format!("lines-{}", start),
+        "bitbucket" => format!("lines-{}:{}", start, end),
+        _ if start == end => format!("L{}", start),
+        "gitlab" => format!("L{}-{}", start, end),
+        _ => format!("L{}-L{}", start, end),
+    }
+}
+
+/// The encoded name of a resolved reference
+fn ref_name(git_ref: &GitRef) -> Result<String> {
+    match git_ref {
+        GitRef::Branch(name) | GitRef::Tag(name) | GitRef::Commit(name) => Ok(encode_path(name)),
+        GitRef::Default => Err(anyhow!("The reference must be resolved first")),
+    }
+}
+
+/// The kind of reference Gitea spells out in its URLs
+fn gitea_ref_kind(git_ref: &GitRef) -> &'static str {
+    match git_ref {
+        GitRef::Tag(_) => "tag",
+        GitRef::Commit(_) => "commit",
+        _ => "branch",
+    }
+}
+
+/// The web URL of a file or directory at a reference, in the layout of the hosting service
+/// named `provider`. `git_ref` must not be `GitRef::Default`.
+fn permalink_url(
+    provider: &str,
+    base_url: &str,
+    repo_path: &str,
+    git_ref: &GitRef,
+    path: &str,
+    lines: Option<(usize, usize)>,
+) -> Result<String> {
+    let ref_name = ref_name(git_ref)?;
+    let path = encode_path(path.trim_matches('/'));
+    let base = format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        repo_path.trim_matches('/')
+    );
+    // Directories are browsed under "tree" rather than "blob" on GitHub and GitLab
+    let blob = if path.is_empty() { "tree" } else { "blob" };
+
+    let url = match provider {
+        "github" => format!("{}/{}/{}/{}", base, blob, ref_name, path),
+        "gitlab" => format!("{}/-/{}/{}/{}", base, blob, ref_name, path),
+        "gitea" => format!(
+            "{}/src/{}/{}/{}",
+            base,
+            gitea_ref_kind(git_ref),
+            ref_name,
+            path
+        ),
+        "bitbucket" => format!("{}/src/{}/{}", base, ref_name, path),
+        _ => {
+            return Err(anyhow!(
+                "Permalinks are not supported for provider '{}'",
+                provider
+            ));
+        }
+    };
+
+    let url = url.trim_end_matches('/').to_string();
+    Ok(match lines {
+        Some(lines) => format!("{}#{}", url, line_fragment(provider, lines)),
+        None => url,
+    })
+}
+
+/// The URL serving the raw content of a file at a reference, in the layout of the hosting
+/// service named `provider`. `git_ref` must not be `GitRef::Default`.
+pub(crate) fn raw_url(
+    provider: &str,
+    base_url: &str,
+    repo_path: &str,
+    git_ref: &GitRef,
+    path: &str,
+) -> Result<String> {
+    let ref_name = ref_name(git_ref)?;
+    let path = encode_path(path.trim_matches('/'));
+    let base = format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        repo_path.trim_matches('/')
+    );
+
+    match provider {
+        "github" | "bitbucket" => Ok(format!("{}/raw/{}/{}", base, ref_name, path)),
+        "gitlab" => Ok(format!("{}/-/raw/{}/{}", base, ref_name, path)),
+        "gitea" => Ok(format!(
+            "{}/raw/{}/{}/{}",
+            base,
+            gitea_ref_kind(git_ref),
+            ref_name,
+            path
+        )),
+        _ => Err(anyhow!(
+            "Raw file URLs are not supported for provider '{}'",
+            provider
+        )),
+    }
+}
+
+/// The default branch of a repository as a reference URLs can name, every host serving it
+/// under HEAD when it cannot be looked up
+pub(crate) async fn default_branch_ref(provider: &dyn GitProvider, repo_path: &str) -> GitRef {
+    let default_branch = provider
+        .get_repository_info(repo_path)
+        .await
+        .ok()
+        .and_then(|info| info.default_branch);
+
+    GitRef::Branch(default_branch.unwrap_or_else(|| "HEAD".to_string()))
+}
+
+pub struct Permalink {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl Permalink {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for Permalink {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let repo_identifier = args
+            .get("repo")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing or invalid repository identifier"))?;
+        let (provider_name, repo_path) = parse_repo_identifier(repo_identifier)?;
+        let git_ref = git_ref_argument(&args, "git_ref").unwrap_or_default();
+        let path = args.get("path").and_then(|v| v.as_str()).unwrap_or("");
+        let pin = bool_argument(&args, "pin").unwrap_or(true);
+
+        let lines = match (
+            usize_argument(&args, "start_line"),
+            usize_argument(&args, "end_line"),
+        ) {
+            (Some(start), end) => Some((start, end.unwrap_or(start).max(start))),
+            (None, Some(_)) => return Err(anyhow!("end_line requires start_line")),
+            (None, None) => None,
+        };
+        if lines.is_some() && path.trim_matches('/').is_empty() {
+            return Err(anyhow!("A line range requires the path of a file"));
+        }
+
+        // Bitbucket links need no API call, other hosts are only known through their provider
+        let provider = if provider_name == "bitbucket" {
+            None
+        } else {
+            Some(find_provider(&self.providers, provider_name)?)
+        };
+        let base_url = match provider {
+            Some(provider) => provider
+                .web_url()
+                .ok_or_else(|| anyhow!("Provider '{}' has no web interface", provider_name))?,
+            None => BITBUCKET_URL.to_string(),
+        };
+
+        // Branches and tags move, so they are pinned to the commit they currently point at
+        let mut note = None;
+        let resolved = match provider {
+            _ if !pin || matches!(git_ref, GitRef::Commit(_)) => git_ref.clone(),
+            None => {
+                note = Some(format!("{} references are not resolved", provider_name));
+                git_ref.clone()
+            }
+            Some(provider) => match provider
+                .list_commits(repo_path, Some(git_ref.clone()), None, 1)
+                .await
+            {
+                Ok(commits) if !commits.is_empty() => GitRef::Commit(commits[0].sha.clone()),
+                Ok(_) => {
+                    note = Some("the reference has no commits".to_string());
+                    git_ref.clone()
+                }
+                Err(e) => {
+                    note = Some(e.to_string());
+                    git_ref.clone()
+                }
+            },
+        };
+
+        let resolved = match (resolved, provider) {
+            (GitRef::Default, Some(provider)) => default_branch_ref(provider, repo_path).await,
+            // Every host serves the default branch under HEAD
+            (GitRef::Default, None) => GitRef::Branch("HEAD".to_string()),
+            (resolved, _) => resolved,
+        };
+
+        let kind = provider.map_or(provider_name, |provider| provider.kind());
+        let url = permalink_url(kind, &base_url, repo_path, &resolved, path, lines)?;
+
+        let mut text = format!("{}\n", url);
+        if let Some(note) = note {
+            text.push_str(&format!(
+                "\nNot pinned to a commit, the link follows the reference: {}\n",
+                note
+            ));
+        }
+
+        Ok(vec![ToolContent::Text { text }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let mut providers = supported_providers(&self.providers);
+        providers.push("bitbucket".to_string());
+
+        Tool {
+            name: "permalink".into(),
+            description: Some(format!(
+                "Build the web URL of a file, an optional line range or a directory of a repository, pinned to the current commit of the reference, to cite sources. Supported providers: {}",
+                providers.join(", ")
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Optional path of a file or directory. Default: the repository root"
+                    },
+                    "start_line": {
+                        "type": "integer",
+                        "description": "Optional first line of the range to highlight, 1-based"
+                    },
+                    "end_line": {
+                        "type": "integer",
+                        "description": "Optional last line of the range to highlight. Default: start_line"
+                    },
+                    "pin": {
+                        "type": "boolean",
+                        "description": "Optional flag to link the commit the reference points at rather than the reference itself. Default: true"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::common::{repo_argument, required_arguments, string_argument, supported_providers};
+
+pub struct RepositoryMilestones {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositoryMilestones {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositoryMilestones {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let state = string_argument(&args, "state").unwrap_or("open");
+
+        if !matches!(state, "open" | "closed" | "all") {
+            return Err(anyhow!(
+                "Invalid state '{}': expected 'open', 'closed' or 'all'",
+                state
+            ));
+        }
+
+        let milestones = provider
+            .list_milestones(repo_path, state)
+            .await
+            .map_err(|e| anyhow!("Error getting milestones: {}", e))?;
+
+        if milestones.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("No {} milestones found for {}", state, repo_path),
+            }]);
+        }
+
+        let mut formatted_output = format!("Milestones for {} ({})\n\n", repo_path, state);
+
+        for milestone in milestones {
+            let total = milestone.open_issues + milestone.closed_issues;
+            let progress = (milestone.closed_issues * 100)
+                .checked_div(total)
+                .unwrap_or_default();
+
+            formatted_output.push_str(&format!(
+                "## {} [{}]\n\nProgress: {}% ({} of {} issues closed)\n",
+                milestone.title, milestone.state, progress, milestone.closed_issues, total
+            ));
+
+            if let Some(due_on) = &milestone.due_on {
+                formatted_output
+                    .push_str(&format!("Due: {}\n", due_on.get(..10).unwrap_or(due_on)));
+            }
+            if let Some(url) = &milestone.url {
+                formatted_output.push_str(&format!("URL: {}\n", url));
+            }
+            if let Some(description) = &milestone.description {
+                formatted_output.push_str(&format!("\n{}\n", description.trim()));
+            }
+            formatted_output.push('\n');
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_milestones".into(),
+            description: Some(format!(
+                "List the milestones of a Git repository with due dates and progress based on closed issues. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "state": {
+                        "type": "string",
+                        "enum": ["open", "closed", "all"],
+                        "description": "Optional milestone state to list. Default: open"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+pub struct ProjectBoards {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ProjectBoards {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ProjectBoards {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+
+        let boards = provider
+            .list_boards(repo_path)
+            .await
+            .map_err(|e| anyhow!("Error getting project boards: {}", e))?;
+
+        if boards.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("No project boards found for {}", repo_path),
+            }]);
+        }
+
+        let mut formatted_output = String::new();
+
+        for board in boards {
+            formatted_output.push_str(&format!("# {}\n", board.name));
+            if let Some(url) = &board.url {
+                formatted_output.push_str(&format!("URL: {}\n", url));
+            }
+
+            if board.columns.is_empty() {
+                formatted_output.push_str("\nThe board is empty\n");
+            }
+
+            for column in board.columns {
+                formatted_output.push_str(&format!(
+                    "\n## {} ({})\n\n",
+                    column.name,
+                    column.items.len()
+                ));
+
+                for item in column.items {
+                    let number = item.number.map(|n| format!("#{} ", n)).unwrap_or_default();
+                    let state = item.state.map(|s| format!(" [{}]", s)).unwrap_or_default();
+                    formatted_output.push_str(&format!("- {}{}{}\n", number, item.title, state));
+                }
+            }
+            formatted_output.push('\n');
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "project_boards".into(),
+            description: Some(format!(
+                "Read the planning boards of a Git repository (GitHub Projects or GitLab issue boards) with the items in each column. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+pub struct RepositoryLabels {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositoryLabels {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositoryLabels {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+
+        let mut labels = provider
+            .list_labels(repo_path)
+            .await
+            .map_err(|e| anyhow!("Error getting labels: {}", e))?;
+
+        if labels.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("No labels found for {}", repo_path),
+            }]);
+        }
+
+        // Most used labels first, then alphabetically
+        labels.sort_by(|a, b| {
+            b.open_issues
+                .cmp(&a.open_issues)
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+
+        let mut formatted_output = format!("Labels for {} ({})\n\n", repo_path, labels.len());
+
+        for label in labels {
+            formatted_output.push_str(&format!(
+                "- {} (#{}) — {} open issues",
+                label.name, label.color, label.open_issues
+            ));
+            if let Some(description) = &label.description {
+                formatted_output.push_str(&format!(": {}", description));
+            }
+            formatted_output.push('\n');
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_labels".into(),
+            description: Some(format!(
+                "List the labels of a Git repository with their colors, descriptions and number of open issues. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::common::{
+    repo_argument, required_arguments, string_argument, supported_providers, usize_argument,
+    warn_truncated,
+};
+use crate::diff::{DiffRender, render_file_diff};
+use crate::i18n::Message;
+
+const DEFAULT_LIMIT: usize = 30;
+
+/// Default cap on the size of the diffs included by `read_pull_request`
+const DEFAULT_MAX_DIFF_BYTES: usize = 100 * 1024;
+
+const STATES: &[&str] = &["open", "closed", "merged", "all"];
+
+pub struct ListPullRequests {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ListPullRequests {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ListPullRequests {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let state = string_argument(&args, "state").unwrap_or("open");
+        if !STATES.contains(&state) {
+            return Err(anyhow!(
+                "Invalid state '{}', expected one of: {}",
+                state,
+                STATES.join(", ")
+            ));
+        }
+        let target_branch = string_argument(&args, "target_branch").ok();
+        let limit = usize_argument(&args, "limit").unwrap_or(DEFAULT_LIMIT);
+
+        let pull_requests = provider
+            .list_pull_requests(repo_path, state, target_branch, limit)
+            .await
+            .map_err(|e| anyhow!("Error listing pull requests: {}", e))?;
+
+        if pull_requests.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!("No {} pull requests found for {}", state, repo_path),
+            }]);
+        }
+
+        let mut formatted_output = format!(
+            "{} {} pull requests of {}, most recently updated first\n\n",
+            pull_requests.len(),
+            state,
+            repo_path
+        );
+        for pull_request in &pull_requests {
+            let mut flags = Vec::new();
+            if state != "open" {
+                flags.push(pull_request.state.as_str());
+            }
+            if pull_request.draft {
+                flags.push("draft");
+            }
+
+            formatted_output.push_str(&format!(
+                "- #{} {}",
+                pull_request.number, pull_request.title
+            ));
+            if !flags.is_empty() {
+                formatted_output.push_str(&format!(" [{}]", flags.join(", ")));
+            }
+            formatted_output.push('\n');
+
+            let branches = match (&pull_request.source_branch, &pull_request.target_branch) {
+                (Some(source), Some(target)) => format!(", {} -> {}", source, target),
+                _ => String::new(),
+            };
+            formatted_output.push_str(&format!(
+                "  by {}{}, updated {}{}\n  {}\n",
+                pull_request.author,
+                branches,
+                pull_request
+                    .updated_at
+                    .get(..10)
+                    .unwrap_or(&pull_request.updated_at),
+                if pull_request.labels.is_empty() {
+                    String::new()
+                } else {
+                    format!(", labels: {}", pull_request.labels.join(", "))
+                },
+                pull_request.url
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output.trim_end().to_string(),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "list_pull_requests".into(),
+            description: Some(format!(
+                "List the pull requests (merge requests) of a repository with their number, title, author, source and target branches and labels, to discover in-flight work before reading the code. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "state": {
+                        "type": "string",
+                        "enum": STATES,
+                        "description": "Optional state of the pull requests listed. Default: open"
+                    },
+                    "target_branch": {
+                        "type": "string",
+                        "description": "Optional branch the pull requests merge into, e.g. 'main'"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": format!("Optional number of pull requests listed, at most 100. Default: {}", DEFAULT_LIMIT)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+pub struct ReadPullRequest {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ReadPullRequest {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ReadPullRequest {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let number = usize_argument(&args, "number")
+            .ok_or_else(|| anyhow!("Missing or invalid number"))? as u64;
+        let max_diff_bytes =
+            usize_argument(&args, "max_diff_bytes").unwrap_or(DEFAULT_MAX_DIFF_BYTES);
+
+        let (details, files, comments) = futures::try_join!(
+            provider.get_pull_request(repo_path, number),
+            provider.list_pull_request_files(repo_path, number),
+            provider.list_pull_request_comments(repo_path, number),
+        )
+        .map_err(|e| anyhow!("Error reading pull request #{}: {}", number, e))?;
+
+        let summary = &details.summary;
+        let mut formatted_output = format!("# #{} {}\n\n", summary.number, summary.title);
+        formatted_output.push_str(&format!(
+            "State: {}{}\nAuthor: {}\n",
+            summary.state,
+            if summary.draft { " (draft)" } else { "" },
+            summary.author
+        ));
+        if let (Some(source), Some(target)) = (&summary.source_branch, &summary.target_branch) {
+            formatted_output.push_str(&format!("Branches: {} -> {}\n", source, target));
+        }
+        if !summary.labels.is_empty() {
+            formatted_output.push_str(&format!("Labels: {}\n", summary.labels.join(", ")));
+        }
+        formatted_output.push_str(&format!("URL: {}\n", summary.url));
+
+        formatted_output.push_str("\n## Description\n\n");
+        match details.body.as_deref().map(str::trim) {
+            Some(body) if !body.is_empty() => formatted_output.push_str(body),
+            _ => formatted_output.push_str("(no description)"),
+        }
+        formatted_output.push('\n');
+
+        let (additions, deletions) = files.iter().fold((0, 0), |(a, d), file| {
+            (a + file.additions, d + file.deletions)
+        });
+        formatted_output.push_str(&format!(
+            "\n## Changes\n\n{} files changed, +{} -{}\n",
+            files.len(),
+            additions,
+            deletions
+        ));
+
+        // Diffs are included in order until the cap, the remaining files as one line each
+        let mut diff_bytes = 0;
+        let mut diffs_kept = 0;
+        let mut remaining = Vec::new();
+        for file in &files {
+            let section = render_file_diff(file, DiffRender::Unified { intraline: false }, None);
+            if remaining.is_empty() && diff_bytes + section.len() <= max_diff_bytes {
+                diff_bytes += section.len();
+                diffs_kept += 1;
+                formatted_output.push('\n');
+                formatted_output.push_str(&section.replacen("## ", "### ", 1));
+            } else {
+                remaining.push(render_file_diff(file, DiffRender::Stats, None));
+            }
+        }
+        if !remaining.is_empty() {
+            warn_truncated(
+                diffs_kept,
+                files.len(),
+                Message::FileDiffs,
+                "max_diff_bytes",
+            );
+            formatted_output.push_str(&format!(
+                "\n{} more files, diffs left out past {} bytes:\n",
+                remaining.len(),
+                max_diff_bytes
+            ));
+            for line in &remaining {
+                formatted_output.push_str(&format!("- {}", line));
+            }
+        }
+
+        formatted_output.push_str(&format!("\n## Comments ({})\n", comments.len()));
+        for comment in &comments {
+            let location = match (&comment.path, comment.line) {
+                (Some(path), Some(line)) => format!(" on {}:{}", path, line),
+                (Some(path), None) => format!(" on {}", path),
+                _ => String::new(),
+            };
+            formatted_output.push_str(&format!(
+                "\n**{}**{}, {}\n\n{}\n",
+                comment.author,
+                location,
+                comment.created_at.get(..10).unwrap_or(&comment.created_at),
+                comment.body.trim()
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output.trim_end().to_string(),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "read_pull_request".into(),
+            description: Some(format!(
+                "Read a pull request (merge request) in one call: its description, changed files, diff and the review discussion, inline comments included. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "number": {
+                        "type": "integer",
+                        "description": "Number of the pull request (merge request IID on GitLab)"
+                    },
+                    "max_diff_bytes": {
+                        "type": "integer",
+                        "description": format!("Optional size of the diffs included, files past it are listed with their line counts only. Default: {}", DEFAULT_MAX_DIFF_BYTES)
+                    }
+                },
+                "required": ["repo", "number"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::common::{git_ref_argument, repo_argument, required_arguments, supported_providers};
+
+pub struct ReadReadme {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ReadReadme {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ReadReadme {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+
+        let readme = provider
+            .get_readme(repo_path, git_ref)
+            .await
+            .map_err(|e| anyhow!("Error reading README: {}", e))?;
+
+        Ok(vec![ToolContent::Text {
+            text: format!(
+                "README of {}: {}\n\n{}",
+                repo_path, readme.path, readme.content
+            ),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "read_readme".into(),
+            description: Some(format!(
+                "Read the README of a repository without knowing its name or place: README.md, readme.rst, README or docs/README.md alike, the one closest to the root first. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::common::{repo_argument, required_arguments, supported_providers, usize_argument};
+
+const DEFAULT_LIMIT: usize = 10;
+
+pub struct ListReleases {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ListReleases {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ListReleases {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let limit = usize_argument(&args, "limit").unwrap_or(DEFAULT_LIMIT);
+
+        let releases = provider
+            .list_releases(repo_path, limit)
+            .await
+            .map_err(|e| anyhow!("Error listing releases: {}", e))?;
+
+        if releases.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!(
+                    "No releases found for {}, its tags may still be listed with repository_tags",
+                    repo_path
+                ),
+            }]);
+        }
+
+        let mut formatted_output = format!("Releases of {} ({})\n\n", repo_path, releases.len());
+        for release in &releases {
+            let mut flags = Vec::new();
+            if release.draft {
+                flags.push("draft");
+            }
+            if release.prerelease {
+                flags.push("pre-release");
+            }
+
+            // The title only adds to the heading when it says more than the tag
+            let heading = match &release.name {
+                Some(name) if *name != release.tag_name => {
+                    format!("{} (tag {})", name, release.tag_name)
+                }
+                _ => release.tag_name.clone(),
+            };
+            if flags.is_empty() {
+                formatted_output.push_str(&format!("## {}\n", heading));
+            } else {
+                formatted_output.push_str(&format!("## {} [{}]\n", heading, flags.join(", ")));
+            }
+
+            match &release.published_at {
+                Some(date) => formatted_output.push_str(&format!("- Published: {}\n", date)),
+                None => formatted_output.push_str("- Published: not yet\n"),
+            }
+            if let Some(url) = &release.url {
+                formatted_output.push_str(&format!("- URL: {}\n", url));
+            }
+            match &release.notes {
+                Some(notes) => formatted_output.push_str(&format!("\n{}\n", notes)),
+                None => formatted_output.push_str("\n(no release notes)\n"),
+            }
+            formatted_output.push('\n');
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output.trim_end().to_string(),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "list_releases".into(),
+            description: Some(format!(
+                "List the most recent releases of a repository, newest first, with their tag, title, publication date and release notes, so a changelog can be read without knowing which file holds it. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": format!("Optional number of releases listed, at most 100. Default: {}", DEFAULT_LIMIT)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
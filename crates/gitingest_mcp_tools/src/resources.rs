@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{ToolContent, ToolExecutor};
+use git_provider::{CompareMode, GitProvider};
+use serde_json::json;
+
+use crate::{
+    changelog::render_changelog,
+    common::{find_provider, parse_git_ref},
+    digest::RepositoryDigest,
+};
+
+/// A family of resources addressed by a URI template
+#[async_trait]
+pub trait ResourceTemplate: Send + Sync {
+    /// RFC 6570 URI template, e.g. "gitingest://changelog/{provider}/{repo}/{from}..{to}"
+    fn uri_template(&self) -> &str;
+
+    fn name(&self) -> &str;
+
+    fn description(&self) -> &str;
+
+    fn mime_type(&self) -> &str;
+
+    /// Returns whether `uri` is addressed by this template
+    fn matches(&self, uri: &str) -> bool;
+
+    /// Render the resource identified by `uri`
+    async fn read(&self, uri: &str) -> Result<String>;
+
+    /// A token that changes whenever the content of `uri` does, used to notify subscribers.
+    /// `None` means the resource cannot be watched.
+    async fn revision(&self, _uri: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// Release notes between two references, rendered as a keep-a-changelog entry
+pub struct ChangelogResource {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl ChangelogResource {
+    const PREFIX: &str = "gitingest://changelog/";
+
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+
+    /// Splits a changelog URI into provider, repository path, and reference range
+    fn parse_uri(uri: &str) -> Result<(String, String, String, String)> {
+        let rest = uri
+            .strip_prefix(Self::PREFIX)
+            .ok_or_else(|| anyhow!("Not a changelog resource: {}", uri))?;
+
+        let (provider, rest) = rest
+            .split_once('/')
+            .ok_or_else(|| anyhow!("Missing repository in changelog resource: {}", uri))?;
+        let (repo_path, range) = rest
+            .rsplit_once('/')
+            .ok_or_else(|| anyhow!("Missing reference range in changelog resource: {}", uri))?;
+
+        // Git forbids ".." in reference names, so the first occurrence is the separator
+        let (from, to) = range
+            .split_once("..")
+            .ok_or_else(|| anyhow!("Expected a '{{from}}..{{to}}' range, got '{}'", range))?;
+
+        let decode = |s: &str| -> Result<String> { Ok(urlencoding::decode(s)?.into_owned()) };
+
+        Ok((
+            decode(provider)?,
+            decode(repo_path)?,
+            decode(from)?,
+            decode(to)?,
+        ))
+    }
+}
+
+#[async_trait]
+impl ResourceTemplate for ChangelogResource {
+    fn uri_template(&self) -> &str {
+        "gitingest://changelog/{provider}/{repo}/{from}..{to}"
+    }
+
+    fn name(&self) -> &str {
+        "changelog"
+    }
+
+    fn description(&self) -> &str {
+        "Release notes synthesized from the conventional commits between two git references. An empty 'to' means the main branch."
+    }
+
+    fn mime_type(&self) -> &str {
+        "text/markdown"
+    }
+
+    fn matches(&self, uri: &str) -> bool {
+        uri.starts_with(Self::PREFIX)
+    }
+
+    async fn read(&self, uri: &str) -> Result<String> {
+        let (provider, repo_path, from, to) = Self::parse_uri(uri)?;
+        let provider = find_provider(&self.providers, &provider)?;
+
+        if from.is_empty() {
+            return Err(anyhow!("Missing starting reference in {}", uri));
+        }
+        let to = (!to.is_empty()).then(|| parse_git_ref(&to));
+
+        let comparison = provider
+            .compare_refs(
+                &repo_path,
+                Some(parse_git_ref(&from)),
+                to,
+                CompareMode::MergeBase,
+            )
+            .await
+            .map_err(|e| anyhow!("Error comparing references: {}", e))?;
+
+        Ok(render_changelog(&comparison, None, false))
+    }
+}
+
+/// The digest of a repository's default branch, refreshed as new commits land
+pub struct DigestResource {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl DigestResource {
+    const PREFIX: &str = "gitingest://digest/";
+
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+
+    /// Splits a digest URI into provider and repository path
+    fn parse_uri(uri: &str) -> Result<(String, String)> {
+        let rest = uri
+            .strip_prefix(Self::PREFIX)
+            .ok_or_else(|| anyhow!("Not a digest resource: {}", uri))?;
+
+        let (provider, repo_path) = rest
+            .split_once('/')
+            .ok_or_else(|| anyhow!("Missing repository in digest resource: {}", uri))?;
+
+        Ok((
+            urlencoding::decode(provider)?.into_owned(),
+            urlencoding::decode(repo_path)?.into_owned(),
+        ))
+    }
+}
+
+#[async_trait]
+impl ResourceTemplate for DigestResource {
+    fn uri_template(&self) -> &str {
+        "gitingest://digest/{provider}/{repo}"
+    }
+
+    fn name(&self) -> &str {
+        "digest"
+    }
+
+    fn description(&self) -> &str {
+        "Digest of every file on a repository's default branch. Subscribe to be notified when new commits change it."
+    }
+
+    fn mime_type(&self) -> &str {
+        "text/plain"
+    }
+
+    fn matches(&self, uri: &str) -> bool {
+        uri.starts_with(Self::PREFIX)
+    }
+
+    async fn read(&self, uri: &str) -> Result<String> {
+        let (provider, repo_path) = Self::parse_uri(uri)?;
+
+        let content = RepositoryDigest::new(self.providers.clone())
+            .execute(Some(
+                json!({ "repo": format!("{}:{}", provider, repo_path) }),
+            ))
+            .await?;
+
+        Ok(content
+            .into_iter()
+            .filter_map(|content| match content {
+                ToolContent::Text { text } => Some(text),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn revision(&self, uri: &str) -> Result<Option<String>> {
+        let (provider, repo_path) = Self::parse_uri(uri)?;
+        let provider = find_provider(&self.providers, &provider)?;
+
+        let commits = provider.list_commits(&repo_path, None, None, 1).await?;
+
+        Ok(commits.into_iter().next().map(|commit| commit.sha))
+    }
+}
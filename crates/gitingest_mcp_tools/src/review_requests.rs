@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::common::{supported_providers, usize_argument};
+
+pub struct MyReviewRequests {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl MyReviewRequests {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for MyReviewRequests {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = arguments.unwrap_or_else(|| json!({}));
+        let limit = usize_argument(&args, "limit");
+
+        let results = join_all(self.providers.iter().map(|p| p.list_review_requests(limit))).await;
+
+        let mut formatted_output = String::new();
+        let mut total = 0;
+
+        for (provider, result) in self.providers.iter().zip(results) {
+            match result {
+                Ok(mut requests) => {
+                    total += requests.len();
+                    requests.sort_by(|a, b| {
+                        b.updated_at
+                            .cmp(&a.updated_at)
+                            .then_with(|| a.url.cmp(&b.url))
+                    });
+
+                    formatted_output.push_str(&format!(
+                        "## {} ({} awaiting review)\n\n",
+                        provider.name(),
+                        requests.len()
+                    ));
+
+                    for request in requests {
+                        formatted_output.push_str(&format!(
+                            "- {}:{}#{} {}{}\n  by {}, updated {}{}\n  {}\n",
+                            provider.name(),
+                            request.repository,
+                            request.number,
+                            request.title,
+                            if request.draft { " [draft]" } else { "" },
+                            request.author,
+                            request.updated_at.get(..10).unwrap_or(&request.updated_at),
+                            if request.labels.is_empty() {
+                                String::new()
+                            } else {
+                                format!(", labels: {}", request.labels.join(", "))
+                            },
+                            request.url
+                        ));
+                    }
+                    formatted_output.push('\n');
+                }
+                Err(e) => {
+                    formatted_output.push_str(&format!(
+                        "## {}\n\nSkipped: {}\n\n",
+                        provider.name(),
+                        e
+                    ));
+                }
+            }
+        }
+
+        if total == 0 && formatted_output.is_empty() {
+            return Err(anyhow!("No providers available to list review requests"));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: format!(
+                "{} pull requests awaiting your review\n\n{}",
+                total, formatted_output
+            ),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "my_review_requests".into(),
+            description: Some(format!(
+                "List open pull requests (merge requests) across repositories awaiting review from the user owning the configured token. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "integer",
+                        "description": "Optional maximum number of results to return per each provider. Default: 50"
+                    }
+                }
+            }),
+        }
+    }
+}
@@ -0,0 +1,560 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{GitProvider, TreeOptions};
+use regex::Regex;
+use serde_json::{Value, json};
+
+use crate::{
+    common::{
+        bool_argument, git_ref_argument, repo_argument, required_arguments, supported_providers,
+        usize_argument,
+    },
+    semantic::StructuredFormat,
+};
+
+pub(crate) const DEFAULT_MAX_FILES: usize = 20;
+/// Operations, messages or types listed per schema before the rest is only counted
+const MAX_LISTED: usize = 50;
+/// Schemas returned in full are cut past this size
+const MAX_CONTENT_BYTES: usize = 100 * 1024;
+
+const HTTP_METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Kinds of API definitions the tool recognizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SchemaKind {
+    OpenApi,
+    Protobuf,
+    GraphQl,
+}
+
+impl SchemaKind {
+    fn from_path(path: &str) -> Option<Self> {
+        let name = path.rsplit('/').next().unwrap_or(path).to_ascii_lowercase();
+        let extension = name.rsplit_once('.').map_or("", |(_, extension)| extension);
+
+        match extension {
+            "proto" => Some(Self::Protobuf),
+            "graphql" | "graphqls" | "gql" => Some(Self::GraphQl),
+            "yaml" | "yml" | "json" if name.contains("openapi") || name.contains("swagger") => {
+                Some(Self::OpenApi)
+            }
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::OpenApi => "OpenAPI",
+            Self::Protobuf => "Protobuf",
+            Self::GraphQl => "GraphQL",
+        }
+    }
+}
+
+/// Lists `items`, counting those past `MAX_LISTED`
+fn listed(items: &[String]) -> String {
+    let mut text = items
+        .iter()
+        .take(MAX_LISTED)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if items.len() > MAX_LISTED {
+        text.push_str(&format!(" and {} more", items.len() - MAX_LISTED));
+    }
+    text
+}
+
+/// Checks that braces pair up, the one structural property shared by Protobuf and GraphQL
+fn check_braces(content: &str) -> Result<()> {
+    let mut depth = 0usize;
+    for (index, line) in content.lines().enumerate() {
+        for c in line.chars() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth = depth
+                        .checked_sub(1)
+                        .ok_or_else(|| anyhow!("Unexpected '}}' on line {}", index + 1))?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    match depth {
+        0 => Ok(()),
+        depth => Err(anyhow!("{} unclosed '{{'", depth)),
+    }
+}
+
+/// The content between the first `{` at or after `start` and its matching `}`
+fn block_after(content: &str, start: usize) -> Option<&str> {
+    let open = start + content[start..].find('{')?;
+    let mut depth = 0;
+    for (offset, c) in content[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&content[open + 1..open + offset]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Describes an OpenAPI or Swagger document: its title, servers, operations and schemas
+fn summarize_openapi(path: &str, content: &str) -> Result<String> {
+    let format =
+        StructuredFormat::from_path(path).ok_or_else(|| anyhow!("Unsupported OpenAPI format"))?;
+    let document = format.parse(content)?;
+
+    let version = match (document.get("openapi"), document.get("swagger")) {
+        (Some(version), _) => format!("OpenAPI {}", version.as_str().unwrap_or("?")),
+        (None, Some(version)) => format!("Swagger {}", version.as_str().unwrap_or("?")),
+        (None, None) => return Err(anyhow!("Missing 'openapi' or 'swagger' version field")),
+    };
+
+    let mut summary = version;
+    if let Some(info) = document.get("info") {
+        let title = info
+            .get("title")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Untitled");
+        summary.push_str(&format!(": {}", title));
+        if let Some(api_version) = info.get("version").and_then(|v| v.as_str()) {
+            summary.push_str(&format!(" {}", api_version));
+        }
+    }
+    summary.push('\n');
+
+    // Swagger 2 has a single host and base path
+    let servers: Vec<String> = match document.get("servers").and_then(|v| v.as_array()) {
+        Some(servers) => servers
+            .iter()
+            .filter_map(|server| server.get("url")?.as_str().map(str::to_string))
+            .collect(),
+        None => document
+            .get("host")
+            .and_then(|v| v.as_str())
+            .map(|host| {
+                let base_path = document
+                    .get("basePath")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("");
+                vec![format!("{}{}", host, base_path)]
+            })
+            .unwrap_or_default(),
+    };
+    if !servers.is_empty() {
+        summary.push_str(&format!("Servers: {}\n", servers.join(", ")));
+    }
+
+    let mut operations = Vec::new();
+    if let Some(paths) = document.get("paths").and_then(|v| v.as_object()) {
+        for (route, item) in paths {
+            for method in HTTP_METHODS {
+                let Some(operation) = item.get(*method) else {
+                    continue;
+                };
+                let mut line = format!("{} {}", method.to_uppercase(), route);
+                if let Some(text) = operation.get("summary").and_then(|v| v.as_str()) {
+                    line.push_str(&format!(": {}", text.trim()));
+                }
+                if let Some(id) = operation.get("operationId").and_then(|v| v.as_str()) {
+                    line.push_str(&format!(" ({})", id));
+                }
+                operations.push(line);
+            }
+        }
+    }
+    summary.push_str(&format!("Operations ({}):\n", operations.len()));
+    for operation in operations.iter().take(MAX_LISTED) {
+        summary.push_str(&format!("- {}\n", operation));
+    }
+    if operations.len() > MAX_LISTED {
+        summary.push_str(&format!("- and {} more\n", operations.len() - MAX_LISTED));
+    }
+
+    let schemas: Vec<String> = document
+        .pointer("/components/schemas")
+        .or_else(|| document.get("definitions"))
+        .and_then(|v| v.as_object())
+        .map(|schemas| schemas.keys().cloned().collect())
+        .unwrap_or_default();
+    if !schemas.is_empty() {
+        summary.push_str(&format!(
+            "Schemas ({}): {}\n",
+            schemas.len(),
+            listed(&schemas)
+        ));
+    }
+
+    Ok(summary)
+}
+
+/// Describes a Protobuf file: its package, services with their RPCs, messages and enums
+fn summarize_protobuf(content: &str) -> Result<String> {
+    let comments = Regex::new(r"(?s)//[^\n]*|/\*.*?\*/")?;
+    let content = comments.replace_all(content, "");
+    check_braces(&content)?;
+
+    let syntax = Regex::new(r#"\bsyntax\s*=\s*"([^"]+)"\s*;"#)?;
+    let package = Regex::new(r"\bpackage\s+([\w.]+)\s*;")?;
+    let service = Regex::new(r"\bservice\s+(\w+)")?;
+    let rpc = Regex::new(
+        r"\brpc\s+(\w+)\s*\(\s*(stream\s+)?([\w.]+)\s*\)\s*returns\s*\(\s*(stream\s+)?([\w.]+)\s*\)",
+    )?;
+    let message = Regex::new(r"\bmessage\s+(\w+)")?;
+    let enumeration = Regex::new(r"\benum\s+(\w+)")?;
+
+    let syntax = match syntax.captures(&content) {
+        Some(captures) if matches!(&captures[1], "proto2" | "proto3") => captures[1].to_string(),
+        Some(captures) => return Err(anyhow!("Unknown syntax '{}'", &captures[1])),
+        // Files without a syntax statement are proto2
+        None => "proto2".to_string(),
+    };
+
+    let mut summary = format!("Protobuf ({})", syntax);
+    if let Some(captures) = package.captures(&content) {
+        summary.push_str(&format!(", package {}", &captures[1]));
+    }
+    summary.push('\n');
+
+    for captures in service.captures_iter(&content) {
+        let start = captures.get(0).map_or(0, |m| m.end());
+        let body = block_after(&content, start).unwrap_or_default();
+        summary.push_str(&format!("service {}:\n", &captures[1]));
+        for rpc in rpc.captures_iter(body) {
+            let stream = |group: usize| {
+                if rpc.get(group).is_some() {
+                    "stream "
+                } else {
+                    ""
+                }
+            };
+            summary.push_str(&format!(
+                "- {}({}{}) returns ({}{})\n",
+                &rpc[1],
+                stream(2),
+                &rpc[3],
+                stream(4),
+                &rpc[5]
+            ));
+        }
+    }
+
+    let messages: Vec<String> = message
+        .captures_iter(&content)
+        .map(|captures| captures[1].to_string())
+        .collect();
+    if !messages.is_empty() {
+        summary.push_str(&format!(
+            "Messages ({}): {}\n",
+            messages.len(),
+            listed(&messages)
+        ));
+    }
+    let enums: Vec<String> = enumeration
+        .captures_iter(&content)
+        .map(|captures| captures[1].to_string())
+        .collect();
+    if !enums.is_empty() {
+        summary.push_str(&format!("Enums ({}): {}\n", enums.len(), listed(&enums)));
+    }
+
+    Ok(summary)
+}
+
+/// Describes a GraphQL schema: the fields of its root operation types and its other types
+fn summarize_graphql(content: &str) -> Result<String> {
+    // Descriptions and comments may hold anything, braces included
+    let descriptions = Regex::new(r#"(?s)""".*?"""|"(?:[^"\\\n]|\\.)*"|#[^\n]*"#)?;
+    let content = descriptions.replace_all(content, "");
+    check_braces(&content)?;
+
+    let definition = Regex::new(
+        r"(?m)^\s*(?:extend\s+)?(type|input|enum|interface|union|scalar|directive)\s+@?(\w+)",
+    )?;
+    let field = Regex::new(r"(?m)^\s*(\w+)\s*[(:]")?;
+
+    let mut summary = String::from("GraphQL\n");
+    let mut types: Vec<String> = Vec::new();
+    for captures in definition.captures_iter(&content) {
+        let (keyword, name) = (&captures[1], &captures[2]);
+        if keyword == "type" && matches!(name, "Query" | "Mutation" | "Subscription") {
+            let start = captures.get(0).map_or(0, |m| m.end());
+            let fields: Vec<String> = block_after(&content, start)
+                .map(|body| {
+                    field
+                        .captures_iter(body)
+                        .map(|captures| captures[1].to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            summary.push_str(&format!(
+                "{} ({}): {}\n",
+                name,
+                fields.len(),
+                listed(&fields)
+            ));
+        } else if keyword == "type" {
+            types.push(name.to_string());
+        } else {
+            types.push(format!("{} {}", keyword, name));
+        }
+    }
+    if !types.is_empty() {
+        summary.push_str(&format!("Types ({}): {}\n", types.len(), listed(&types)));
+    }
+
+    Ok(summary)
+}
+
+fn summarize(kind: SchemaKind, path: &str, content: &str) -> Result<String> {
+    match kind {
+        SchemaKind::OpenApi => summarize_openapi(path, content),
+        SchemaKind::Protobuf => summarize_protobuf(content),
+        SchemaKind::GraphQl => summarize_graphql(content),
+    }
+}
+
+pub struct RepositorySchemas {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositorySchemas {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositorySchemas {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let include_content = bool_argument(&args, "include_content").unwrap_or(false);
+        let max_files = usize_argument(&args, "max_files").unwrap_or(DEFAULT_MAX_FILES);
+
+        let mut schemas: Vec<(String, SchemaKind)> = provider
+            .list_files(repo_path, git_ref.clone(), &TreeOptions::default())
+            .await
+            .map_err(|e| anyhow!("Error listing files: {}", e))?
+            .into_iter()
+            .filter_map(|file| SchemaKind::from_path(&file.path).map(|kind| (file.path, kind)))
+            .collect();
+        schemas.sort_by(|a, b| a.0.cmp(&b.0));
+
+        if schemas.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!(
+                    "No OpenAPI, Protobuf or GraphQL schemas found in {}",
+                    repo_path
+                ),
+            }]);
+        }
+
+        let total = schemas.len();
+        let count = |kind: SchemaKind| schemas.iter().filter(|(_, k)| *k == kind).count();
+        let mut formatted_output = format!(
+            "# API schemas in {}\n\n{} files: {} OpenAPI, {} Protobuf, {} GraphQL\n",
+            repo_path,
+            total,
+            count(SchemaKind::OpenApi),
+            count(SchemaKind::Protobuf),
+            count(SchemaKind::GraphQl)
+        );
+
+        schemas.truncate(max_files);
+        let contents = join_all(
+            schemas
+                .iter()
+                .map(|(path, _)| provider.get_file_content(repo_path, path, git_ref.clone())),
+        )
+        .await;
+
+        for ((path, kind), content) in schemas.iter().zip(contents) {
+            formatted_output.push_str(&format!("\n## {}\n\n", path));
+
+            let content = match content {
+                Ok(content) => content,
+                Err(e) => {
+                    formatted_output.push_str(&format!("Unreadable: {}\n", e));
+                    continue;
+                }
+            };
+            match summarize(*kind, path, &content) {
+                Ok(summary) => formatted_output.push_str(&summary),
+                Err(e) => formatted_output.push_str(&format!("Invalid {}: {}\n", kind.name(), e)),
+            }
+
+            if include_content {
+                let extension = path.rsplit('.').next().unwrap_or("");
+                let end = (0..=MAX_CONTENT_BYTES.min(content.len()))
+                    .rev()
+                    .find(|index| content.is_char_boundary(*index))
+                    .unwrap_or(0);
+                formatted_output.push_str(&format!("\n```{}\n{}", extension, &content[..end]));
+                if end < content.len() {
+                    formatted_output
+                        .push_str(&format!("\n... ({} more bytes)", content.len() - end));
+                }
+                formatted_output.push_str("\n```\n");
+            }
+        }
+
+        if total > schemas.len() {
+            formatted_output.push_str(&format!(
+                "\n({} more schemas not read, raise max_files to include them)\n",
+                total - schemas.len()
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_schemas".into(),
+            description: Some(format!(
+                "Find the API definitions of a repository (OpenAPI/Swagger documents, Protobuf files and GraphQL schemas), check that they parse and summarize their operations, services and types, optionally with their content. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "include_content": {
+                        "type": "boolean",
+                        "description": "Optional flag to return the content of every schema after its summary. Default: false"
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "description": format!("Optional maximum number of schemas read. Default: {}", DEFAULT_MAX_FILES)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_follow_the_file_names() {
+        assert_eq!(
+            SchemaKind::from_path("api/openapi.yaml"),
+            Some(SchemaKind::OpenApi)
+        );
+        assert_eq!(
+            SchemaKind::from_path("docs/Swagger.json"),
+            Some(SchemaKind::OpenApi)
+        );
+        assert_eq!(
+            SchemaKind::from_path("proto/user.proto"),
+            Some(SchemaKind::Protobuf)
+        );
+        assert_eq!(
+            SchemaKind::from_path("schema.graphqls"),
+            Some(SchemaKind::GraphQl)
+        );
+        assert_eq!(SchemaKind::from_path("config.yaml"), None);
+    }
+
+    #[test]
+    fn openapi_documents_list_their_operations() {
+        let document = r#"openapi: "3.0.3"
+info:
+  title: Pets
+  version: "1.2"
+servers:
+  - url: https://api.example.com/v1
+paths:
+  /pets:
+    get:
+      summary: List pets
+      operationId: listPets
+    post:
+      operationId: createPet
+components:
+  schemas:
+    Pet: {}
+    Error: {}
+"#;
+
+        assert_eq!(
+            summarize_openapi("openapi.yaml", document).unwrap(),
+            "OpenAPI 3.0.3: Pets 1.2\nServers: https://api.example.com/v1\nOperations (2):\n- GET /pets: List pets (listPets)\n- POST /pets (createPet)\nSchemas (2): Error, Pet\n"
+        );
+        assert!(summarize_openapi("openapi.yaml", "info: {}").is_err());
+    }
+
+    #[test]
+    fn protobuf_files_list_their_services() {
+        let proto = r#"syntax = "proto3";
+package acme.users.v1;
+
+// service Commented {}
+service Users {
+  rpc Get(GetRequest) returns (User);
+  rpc Watch(WatchRequest) returns (stream User);
+}
+
+message User { string name = 1; }
+enum Role { ROLE_UNSPECIFIED = 0; }
+"#;
+
+        assert_eq!(
+            summarize_protobuf(proto).unwrap(),
+            "Protobuf (proto3), package acme.users.v1\nservice Users:\n- Get(GetRequest) returns (User)\n- Watch(WatchRequest) returns (stream User)\nMessages (1): User\nEnums (1): Role\n"
+        );
+        assert!(summarize_protobuf("message Broken {").is_err());
+        assert!(summarize_protobuf("syntax = \"proto4\";").is_err());
+    }
+
+    #[test]
+    fn graphql_schemas_list_their_root_fields() {
+        let schema = r#"""" The root { query } """
+type Query {
+  user(id: ID!): User # lookup { by id }
+  users: [User!]!
+}
+type User { id: ID! }
+enum Role { ADMIN }
+"#;
+
+        assert_eq!(
+            summarize_graphql(schema).unwrap(),
+            "GraphQL\nQuery (2): user, users\nTypes (2): User, enum Role\n"
+        );
+        assert!(check_braces("type A { }}").is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+/// Structured file formats that can be compared key by key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StructuredFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl StructuredFormat {
+    pub(crate) fn from_path(path: &str) -> Option<Self> {
+        let extension = path.rsplit_once('.')?.1.to_ascii_lowercase();
+
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn parse(self, content: &str) -> Result<Value> {
+        match self {
+            Self::Json => serde_json::from_str(content).map_err(|e| anyhow!("Invalid JSON: {}", e)),
+            Self::Yaml => serde_yaml::from_str(content).map_err(|e| anyhow!("Invalid YAML: {}", e)),
+            Self::Toml => toml::from_str(content).map_err(|e| anyhow!("Invalid TOML: {}", e)),
+        }
+    }
+}
+
+/// A key-level difference between two documents
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Change {
+    Added(String, Value),
+    Removed(String, Value),
+    Changed(String, Value, Value),
+}
+
+fn child_path(parent: &str, key: &str) -> String {
+    let simple = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+
+    match (parent.is_empty(), simple) {
+        (true, true) => key.to_string(),
+        (false, true) => format!("{}.{}", parent, key),
+        (_, false) => format!("{}[{:?}]", parent, key),
+    }
+}
+
+fn collect_changes(path: &str, old: &Value, new: &Value, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old {
+                let child = child_path(path, key);
+                match new.get(key) {
+                    Some(new_value) => collect_changes(&child, old_value, new_value, changes),
+                    None => changes.push(Change::Removed(child, old_value.clone())),
+                }
+            }
+            for (key, new_value) in new {
+                if !old.contains_key(key) {
+                    changes.push(Change::Added(child_path(path, key), new_value.clone()));
+                }
+            }
+        }
+        // Arrays are compared by position, extra elements are additions or removals
+        (Value::Array(old), Value::Array(new)) => {
+            for index in 0..old.len().max(new.len()) {
+                let child = format!("{}[{}]", path, index);
+                match (old.get(index), new.get(index)) {
+                    (Some(old_value), Some(new_value)) => {
+                        collect_changes(&child, old_value, new_value, changes)
+                    }
+                    (Some(old_value), None) => {
+                        changes.push(Change::Removed(child, old_value.clone()))
+                    }
+                    (None, Some(new_value)) => {
+                        changes.push(Change::Added(child, new_value.clone()))
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if old != new => {
+            changes.push(Change::Changed(path.to_string(), old.clone(), new.clone()))
+        }
+        _ => {}
+    }
+}
+
+/// Lists the keys added, removed, or changed between two documents
+pub(crate) fn structural_changes(old: &Value, new: &Value) -> Vec<Change> {
+    let mut changes = Vec::new();
+    collect_changes("", old, new, &mut changes);
+    changes
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() { "(root)" } else { path }
+}
+
+/// Renders changes one per line, '+' for added keys, '-' for removed ones and '~' for changed values
+pub(crate) fn render_changes(changes: &[Change]) -> String {
+    if changes.is_empty() {
+        return "(no structural changes, formatting only)\n".to_string();
+    }
+
+    changes
+        .iter()
+        .map(|change| match change {
+            Change::Added(path, value) => format!("+ {}: {}\n", display_path(path), value),
+            Change::Removed(path, value) => format!("- {}: {}\n", display_path(path), value),
+            Change::Changed(path, old, new) => {
+                format!("~ {}: {} -> {}\n", display_path(path), old, new)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn formats_follow_the_extension() {
+        assert_eq!(
+            StructuredFormat::from_path("deploy/values.YML"),
+            Some(StructuredFormat::Yaml)
+        );
+        assert_eq!(
+            StructuredFormat::from_path("Cargo.toml"),
+            Some(StructuredFormat::Toml)
+        );
+        assert_eq!(StructuredFormat::from_path("Makefile"), None);
+        assert!(StructuredFormat::Json.parse("{").is_err());
+    }
+
+    #[test]
+    fn the_three_formats_compare_alike() {
+        let json = StructuredFormat::Json
+            .parse(r#"{"package": {"name": "a", "version": "1.0"}}"#)
+            .unwrap();
+        let yaml = StructuredFormat::Yaml
+            .parse("package:\n  name: a\n  version: '1.0'\n")
+            .unwrap();
+        let toml = StructuredFormat::Toml
+            .parse("[package]\nname = \"a\"\nversion = \"1.0\"\n")
+            .unwrap();
+
+        assert!(structural_changes(&json, &yaml).is_empty());
+        assert!(structural_changes(&json, &toml).is_empty());
+    }
+
+    #[test]
+    fn keys_and_elements_are_compared_by_path() {
+        let old = json!({
+            "dependencies": {"serde": "1.0", "old-crate": "0.1"},
+            "features": ["a", "b"],
+            "weird key": 1
+        });
+        let new = json!({
+            "dependencies": {"serde": "1.1", "tokio": "1"},
+            "features": ["a"],
+            "weird key": 1
+        });
+
+        assert_eq!(
+            structural_changes(&old, &new),
+            [
+                Change::Removed("dependencies.old-crate".to_string(), json!("0.1")),
+                Change::Changed("dependencies.serde".to_string(), json!("1.0"), json!("1.1")),
+                Change::Added("dependencies.tokio".to_string(), json!("1")),
+                Change::Removed("features[1]".to_string(), json!("b")),
+            ]
+        );
+        assert_eq!(
+            render_changes(&structural_changes(&json!({"a b": 1}), &json!(2))),
+            "~ (root): {\"a b\":1} -> 2\n"
+        );
+        assert_eq!(
+            render_changes(&structural_changes(&json!({"a b": 1}), &json!({"a b": 2}))),
+            "~ [\"a b\"]: 1 -> 2\n"
+        );
+        assert_eq!(
+            render_changes(&[]),
+            "(no structural changes, formatting only)\n"
+        );
+    }
+}
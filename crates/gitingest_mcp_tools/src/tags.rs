@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::GitProvider;
+use serde_json::{Value, json};
+
+use crate::{
+    common::{repo_argument, required_arguments, supported_providers, usize_argument},
+    i18n::Message,
+};
+
+const DEFAULT_LIMIT: usize = 20;
+
+pub struct RepositoryTags {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl RepositoryTags {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for RepositoryTags {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let limit = usize_argument(&args, "limit").unwrap_or(DEFAULT_LIMIT);
+
+        let tags = provider
+            .list_tags(repo_path, limit)
+            .await
+            .map_err(|e| anyhow!("Error listing tags: {}", e))?;
+
+        if tags.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: Message::NoTagsFound.text(&[&repo_path]),
+            }]);
+        }
+
+        let mut formatted_output =
+            format!("{}\n\n", Message::TagsOf.text(&[&repo_path, &tags.len()]));
+        for tag in &tags {
+            let kind = if tag.annotated {
+                Message::Annotated
+            } else {
+                Message::Lightweight
+            };
+            formatted_output.push_str(&format!(
+                "## {} ({}, {} {})\n",
+                tag.name,
+                kind.text(&[]),
+                Message::Commit.text(&[]),
+                &tag.commit_sha[..tag.commit_sha.len().min(7)]
+            ));
+
+            if let Some(tagger) = &tag.tagger {
+                formatted_output.push_str(&format!(
+                    "- {}: {}\n",
+                    Message::Tagger.text(&[]),
+                    tagger
+                ));
+            }
+            if let Some(date) = &tag.date {
+                formatted_output.push_str(&format!("- {}: {}\n", Message::Date.text(&[]), date));
+            }
+            // Lightweight tags have no object to sign
+            let signature = match &tag.verification {
+                Some(verification) => Some(verification.describe()),
+                None if tag.annotated => Some(Message::SignatureNotReported.text(&[])),
+                None => None,
+            };
+            if let Some(signature) = signature {
+                formatted_output.push_str(&format!(
+                    "- {}: {}\n",
+                    Message::Signature.text(&[]),
+                    signature
+                ));
+            }
+            if let Some(message) = &tag.message {
+                formatted_output.push_str(&format!("\n{}\n", message));
+            }
+            formatted_output.push('\n');
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output.trim_end().to_string(),
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "repository_tags".into(),
+            description: Some(format!(
+                "List the most recent tags of a repository, telling annotated tags from lightweight ones, with their tagger, message and whether their GPG, SSH or X.509 signature was verified. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": format!("Optional number of tags listed, each costing up to two requests on GitHub. Default: {}", DEFAULT_LIMIT)
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
@@ -0,0 +1,647 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use futures::future::join_all;
+use git_provider::{GitProvider, TreeOptions};
+use regex::Regex;
+use serde_json::{Value, json};
+
+use crate::common::{
+    git_ref_argument, repo_argument, required_arguments, string_argument, supported_providers,
+    usize_argument,
+};
+
+pub(crate) const DEFAULT_MAX_FILES: usize = 200;
+
+/// A block of HCL, with the line its header is on and its body without comments
+struct Block {
+    kind: String,
+    labels: Vec<String>,
+    line: usize,
+    /// Lines of the body, numbered
+    body: Vec<(usize, String)>,
+}
+
+/// Lines of HCL without comments, heredoc contents replaced by an empty string
+fn code_lines(content: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut in_block_comment = false;
+    let mut heredoc: Option<String> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        if let Some(terminator) = &heredoc {
+            if line.trim() == terminator {
+                heredoc = None;
+            }
+            continue;
+        }
+
+        let mut code = String::new();
+        let mut in_string = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if in_block_comment {
+                if c == '*' && chars.peek() == Some(&'/') {
+                    chars.next();
+                    in_block_comment = false;
+                }
+                continue;
+            }
+            if in_string {
+                code.push(c);
+                match c {
+                    '\\' => code.extend(chars.next()),
+                    '"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match c {
+                '"' => {
+                    in_string = true;
+                    code.push(c);
+                }
+                '#' => break,
+                '/' if chars.peek() == Some(&'/') => break,
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    in_block_comment = true;
+                }
+                '<' if chars.peek() == Some(&'<') => {
+                    chars.next();
+                    let marker: String = chars.by_ref().collect();
+                    let marker = marker.trim_start_matches('-').trim();
+                    heredoc = Some(marker.to_string());
+                    code.push_str("\"\"");
+                    break;
+                }
+                _ => code.push(c),
+            }
+        }
+
+        if !code.trim().is_empty() {
+            lines.push((index + 1, code));
+        }
+    }
+
+    lines
+}
+
+/// Difference between the opening and closing braces and brackets of a line, strings aside
+fn depth_change(line: &str) -> i32 {
+    let mut change = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in line.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' | '(' => change += 1,
+            '}' | ']' | ')' => change -= 1,
+            _ => {}
+        }
+    }
+    change
+}
+
+/// The blocks at the top level of the given lines, such as `resource "type" "name" { ... }`
+fn blocks(lines: &[(usize, String)]) -> Result<Vec<Block>> {
+    let header = Regex::new(r#"^\s*([\w-]+)((?:\s+(?:"[^"]*"|[\w-]+))*)\s*\{(.*)$"#)?;
+    let label = Regex::new(r#""([^"]*)"|([\w-]+)"#)?;
+
+    let mut blocks = Vec::new();
+    let mut depth = 0;
+    let mut current: Option<Block> = None;
+    for (number, line) in lines {
+        if depth == 0 {
+            // Attributes at this level are not blocks
+            if let Some(captures) = header.captures(line) {
+                let rest = &captures[3];
+                let mut block = Block {
+                    kind: captures[1].to_string(),
+                    labels: label
+                        .captures_iter(&captures[2])
+                        .filter_map(|label| label.get(1).or_else(|| label.get(2)))
+                        .map(|label| label.as_str().to_string())
+                        .collect(),
+                    line: *number,
+                    body: vec![],
+                };
+                depth = 1 + depth_change(rest);
+                if depth > 0 {
+                    if !rest.trim().is_empty() {
+                        block.body.push((*number, rest.to_string()));
+                    }
+                    current = Some(block);
+                } else {
+                    // A single-line block, e.g. `variable "name" { type = string }`
+                    let inner = rest.trim().trim_end_matches('}');
+                    block.body.extend(
+                        inner
+                            .split(',')
+                            .filter(|part| !part.trim().is_empty())
+                            .map(|part| (*number, part.to_string())),
+                    );
+                    blocks.push(block);
+                    depth = 0;
+                }
+            } else {
+                depth += depth_change(line).max(0);
+            }
+            continue;
+        }
+
+        depth += depth_change(line);
+        if let Some(block) = &mut current {
+            if depth <= 0 {
+                let closing = line.trim_end().trim_end_matches('}');
+                if !closing.trim().is_empty() {
+                    block.body.push((*number, closing.to_string()));
+                }
+            } else {
+                block.body.push((*number, line.clone()));
+            }
+        }
+        if depth <= 0 {
+            depth = 0;
+            blocks.extend(current.take());
+        }
+    }
+    blocks.extend(current);
+
+    Ok(blocks)
+}
+
+/// The attributes at the top level of a block's body, values kept as written, strings unquoted
+fn attributes(body: &[(usize, String)]) -> Result<BTreeMap<String, String>> {
+    let assignment = Regex::new(r"^\s*([\w-]+)\s*=\s*(.*)$")?;
+
+    let mut attributes = BTreeMap::new();
+    let mut depth = 0;
+    let mut current: Option<(String, String)> = None;
+    for (_, line) in body {
+        if depth == 0 {
+            if let Some(captures) = assignment.captures(line) {
+                let value = captures[2].trim().to_string();
+                depth = depth_change(&value).max(0);
+                current = Some((captures[1].to_string(), value));
+            } else {
+                depth = depth_change(line).max(0);
+                continue;
+            }
+        } else {
+            depth += depth_change(line);
+            if let Some((_, value)) = &mut current {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        }
+
+        if depth <= 0 {
+            depth = 0;
+            if let Some((name, value)) = current.take() {
+                let value = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                    Some(unquoted) if !unquoted.contains('"') => unquoted.to_string(),
+                    _ => value,
+                };
+                attributes.insert(name, value);
+            }
+        }
+    }
+
+    Ok(attributes)
+}
+
+/// The `name = { source = ..., version = ... }` entries of a `required_providers` block
+fn required_providers(body: &[(usize, String)]) -> Result<Vec<(String, String)>> {
+    let entry = Regex::new(r#"(\w+)\s*=\s*"([^"]*)""#)?;
+
+    let mut providers = Vec::new();
+    for (name, value) in attributes(body)? {
+        // The legacy form is a version constraint alone
+        let mut details: BTreeMap<String, String> = entry
+            .captures_iter(&value)
+            .map(|captures| (captures[1].to_string(), captures[2].to_string()))
+            .collect();
+        if details.is_empty() {
+            details.insert("version".into(), value.trim_matches('"').to_string());
+        }
+
+        let description = match (details.get("source"), details.get("version")) {
+            (Some(source), Some(version)) => format!("{} {}", source, version),
+            (Some(source), None) => source.clone(),
+            (None, Some(version)) => version.clone(),
+            (None, None) => String::new(),
+        };
+        providers.push((name, description));
+    }
+
+    Ok(providers)
+}
+
+/// A directory of .tf files, i.e. a Terraform module
+#[derive(Default)]
+struct Module {
+    files: Vec<String>,
+    required_version: Option<String>,
+    backend: Option<String>,
+    required_providers: Vec<(String, String)>,
+    provider_configurations: Vec<String>,
+    modules: Vec<String>,
+    resources: Vec<String>,
+    data_sources: Vec<String>,
+    required_variables: Vec<String>,
+    optional_variables: Vec<String>,
+    outputs: Vec<String>,
+}
+
+impl Module {
+    fn add_file(&mut self, path: &str, content: &str) -> Result<()> {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        self.files.push(name.to_string());
+
+        for block in blocks(&code_lines(content))? {
+            let location = format!("{}:{}", name, block.line);
+            let label = |index: usize| block.labels.get(index).map_or("", String::as_str);
+            let attributes = attributes(&block.body)?;
+            let attribute = |key: &str| attributes.get(key).map(String::as_str);
+
+            match block.kind.as_str() {
+                "terraform" => {
+                    if let Some(version) = attribute("required_version") {
+                        self.required_version = Some(version.to_string());
+                    }
+                    for nested in blocks(&block.body)? {
+                        match nested.kind.as_str() {
+                            "required_providers" => self
+                                .required_providers
+                                .extend(required_providers(&nested.body)?),
+                            "backend" | "cloud" => {
+                                self.backend = Some(match nested.labels.first() {
+                                    Some(backend) => backend.clone(),
+                                    None => nested.kind.clone(),
+                                })
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                "provider" => {
+                    let mut configuration = label(0).to_string();
+                    if let Some(alias) = attribute("alias") {
+                        configuration.push_str(&format!(" (alias {})", alias));
+                    }
+                    self.provider_configurations.push(configuration);
+                }
+                "module" => {
+                    let mut module = format!("`{}`", label(0));
+                    if let Some(source) = attribute("source") {
+                        module.push_str(&format!(" from {}", source));
+                    }
+                    if let Some(version) = attribute("version") {
+                        module.push_str(&format!(" {}", version));
+                    }
+                    self.modules.push(format!("{} ({})", module, location));
+                }
+                "resource" => {
+                    self.resources
+                        .push(format!("{}.{} ({})", label(0), label(1), location))
+                }
+                "data" => {
+                    self.data_sources
+                        .push(format!("{}.{} ({})", label(0), label(1), location))
+                }
+                "variable" => {
+                    let mut variable = format!("`{}`", label(0));
+                    if let Some(variable_type) = attribute("type") {
+                        variable.push_str(&format!(": {}", variable_type));
+                    }
+                    // Variables without a default must be set by the caller
+                    let required = match attribute("default") {
+                        Some(default) => {
+                            variable.push_str(&format!(" = {}", default));
+                            false
+                        }
+                        None => true,
+                    };
+                    if let Some(description) = attribute("description") {
+                        variable.push_str(&format!(" — {}", description));
+                    }
+                    if required {
+                        self.required_variables.push(variable);
+                    } else {
+                        self.optional_variables.push(variable);
+                    }
+                }
+                "output" => {
+                    let mut output = format!("`{}`", label(0));
+                    if let Some(description) = attribute("description") {
+                        output.push_str(&format!(" — {}", description));
+                    }
+                    if attribute("sensitive") == Some("true") {
+                        output.push_str(" (sensitive)");
+                    }
+                    self.outputs.push(output);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn format(&self, directory: &str) -> String {
+        let mut output = format!(
+            "\n## Module {} ({} file{})\n\n",
+            match directory {
+                "" => "(root)",
+                directory => directory,
+            },
+            self.files.len(),
+            if self.files.len() == 1 { "" } else { "s" }
+        );
+
+        if let Some(version) = &self.required_version {
+            output.push_str(&format!("- Terraform: {}\n", version));
+        }
+        if let Some(backend) = &self.backend {
+            output.push_str(&format!("- Backend: {}\n", backend));
+        }
+
+        let lists = [
+            (
+                "Required providers",
+                self.required_providers
+                    .iter()
+                    .map(|(name, details)| match details.as_str() {
+                        "" => name.clone(),
+                        details => format!("{} ({})", name, details),
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                "Provider configurations",
+                self.provider_configurations.clone(),
+            ),
+            ("Modules", self.modules.clone()),
+            ("Resources", self.resources.clone()),
+            ("Data sources", self.data_sources.clone()),
+            ("Required variables", self.required_variables.clone()),
+            ("Optional variables", self.optional_variables.clone()),
+            ("Outputs", self.outputs.clone()),
+        ];
+        for (title, items) in lists {
+            if items.is_empty() {
+                continue;
+            }
+            output.push_str(&format!("- {} ({}):\n", title, items.len()));
+            for item in items {
+                output.push_str(&format!("  - {}\n", item));
+            }
+        }
+
+        output
+    }
+}
+
+pub struct TerraformInventory {
+    providers: Vec<Arc<dyn GitProvider>>,
+}
+
+impl TerraformInventory {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for TerraformInventory {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let git_ref = git_ref_argument(&args, "git_ref");
+        let path = string_argument(&args, "path")
+            .ok()
+            .map(|path| path.trim_matches('/').to_string())
+            .filter(|path| !path.is_empty());
+        let max_files = usize_argument(&args, "max_files").unwrap_or(DEFAULT_MAX_FILES);
+
+        let files = provider
+            .list_files(repo_path, git_ref.clone(), &TreeOptions::default())
+            .await
+            .map_err(|e| anyhow!("Error listing files: {}", e))?;
+
+        // Downloaded modules and providers are not the repository's
+        let mut sources: Vec<&str> = files
+            .iter()
+            .map(|file| file.path.as_str())
+            .filter(|file| file.ends_with(".tf"))
+            .filter(|file| !file.split('/').any(|segment| segment == ".terraform"))
+            .filter(|file| {
+                path.as_ref()
+                    .is_none_or(|path| file.starts_with(&format!("{}/", path)))
+            })
+            .collect();
+        sources.sort_unstable();
+        let total = sources.len();
+        sources.truncate(max_files);
+
+        if sources.is_empty() {
+            return Ok(vec![ToolContent::Text {
+                text: format!(
+                    "No Terraform files found in {}{}",
+                    repo_path,
+                    path.map(|path| format!(" under {}", path))
+                        .unwrap_or_default()
+                ),
+            }]);
+        }
+
+        let contents = join_all(
+            sources
+                .iter()
+                .map(|file| provider.get_file_content(repo_path, file, git_ref.clone())),
+        )
+        .await;
+
+        let mut modules: BTreeMap<&str, Module> = BTreeMap::new();
+        for (file, content) in sources.iter().zip(contents) {
+            let Ok(content) = content else {
+                continue;
+            };
+            let directory = file.rsplit_once('/').map_or("", |(directory, _)| directory);
+            modules
+                .entry(directory)
+                .or_default()
+                .add_file(file, &content)?;
+        }
+
+        let mut formatted_output = format!(
+            "# Terraform inventory of {}\n\n{} module{}, {} file{}\n",
+            repo_path,
+            modules.len(),
+            if modules.len() == 1 { "" } else { "s" },
+            sources.len(),
+            if sources.len() == 1 { "" } else { "s" }
+        );
+        for (directory, module) in &modules {
+            formatted_output.push_str(&module.format(directory));
+        }
+        if total > sources.len() {
+            formatted_output.push_str(&format!(
+                "\nOnly the first {} of {} Terraform files were read, raise max_files or narrow path to read the rest\n",
+                sources.len(),
+                total
+            ));
+        }
+
+        Ok(vec![ToolContent::Text {
+            text: formatted_output,
+        }])
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "terraform_inventory".into(),
+            description: Some(format!(
+                "Inventory the Terraform modules of a repository without ingesting them: for each directory of .tf files, the required Terraform version and providers, backend, provider configurations, called modules, resources, data sources, required and optional variables, and outputs, with their locations. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "git_ref": {
+                        "type": "string",
+                        "description": "Optional git reference: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Optional directory to inventory, e.g. 'infra'. Default: the whole repository"
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "description": "Maximum number of .tf files to read. Default: 200"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN_TF: &str = r#"terraform {
+  required_version = ">= 1.5"
+  backend "s3" {}
+  required_providers {
+    aws = {
+      source  = "hashicorp/aws"
+      version = "~> 5.0"
+    }
+    random = "3.6.0"
+  }
+}
+
+# provider "google" {}
+provider "aws" {
+  region = var.region
+  alias  = "east" // used for replicas
+}
+
+/* resource "aws_instance" "commented" {
+} */
+resource "aws_s3_bucket" "logs" {
+  bucket = "logs-${var.env}"
+  tags = {
+    Name = "logs"
+  }
+  policy = <<-EOT
+    resource "not_a" "block" {}
+  EOT
+}
+
+data "aws_caller_identity" "current" {}
+
+module "vpc" {
+  source  = "terraform-aws-modules/vpc/aws"
+  version = "5.1.0"
+}
+
+variable "region" { type = string, default = "us-east-1" }
+variable "env" {
+  type        = string
+  description = "Deployment environment"
+}
+
+output "bucket_arn" {
+  value     = aws_s3_bucket.logs.arn
+  sensitive = true
+}
+"#;
+
+    #[test]
+    fn comments_and_heredocs_are_dropped() {
+        let lines =
+            code_lines("a = 1 # note\n/* x\ny */ b = \"#not a comment\"\nc = <<EOF\nhidden\nEOF\n");
+        assert_eq!(
+            lines,
+            [
+                (1, "a = 1 ".to_string()),
+                (3, " b = \"#not a comment\"".to_string()),
+                (4, "c = \"\"".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn blocks_and_attributes_are_read() {
+        let mut module = Module::default();
+        module.add_file("infra/main.tf", MAIN_TF).unwrap();
+
+        assert_eq!(module.required_version.as_deref(), Some(">= 1.5"));
+        assert_eq!(module.backend.as_deref(), Some("s3"));
+        assert_eq!(
+            module.required_providers,
+            [
+                ("aws".to_string(), "hashicorp/aws ~> 5.0".to_string()),
+                ("random".to_string(), "3.6.0".to_string()),
+            ]
+        );
+        assert_eq!(module.provider_configurations, ["aws (alias east)"]);
+        assert_eq!(module.resources, ["aws_s3_bucket.logs (main.tf:21)"]);
+        assert_eq!(
+            module.data_sources,
+            ["aws_caller_identity.current (main.tf:31)"]
+        );
+        assert_eq!(
+            module.modules,
+            ["`vpc` from terraform-aws-modules/vpc/aws 5.1.0 (main.tf:33)"]
+        );
+        assert_eq!(module.optional_variables, ["`region`: string = us-east-1"]);
+        assert_eq!(
+            module.required_variables,
+            ["`env`: string — Deployment environment"]
+        );
+        assert_eq!(module.outputs, ["`bucket_arn` (sensitive)"]);
+    }
+}
@@ -0,0 +1,495 @@
+//! Tools that modify remote repositories. They are only registered when the
+//! server runs with write mode explicitly enabled, and only write where the
+//! write policy allows.
+
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{Tool, ToolContent, ToolExecutor};
+use git_provider::{CommentTarget, FileChange, GitProvider, IssueDraft, PullRequestDraft};
+use serde_json::{Value, json};
+
+use crate::{
+    common::{
+        git_ref_argument, list_argument, repo_argument, required_arguments, string_argument,
+        supported_providers, usize_argument,
+    },
+    write_policy::WritePolicy,
+};
+
+pub struct ForkRepository {
+    providers: Vec<Arc<dyn GitProvider>>,
+    policy: WritePolicy,
+}
+
+impl ForkRepository {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>, policy: WritePolicy) -> Self {
+        Self { providers, policy }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ForkRepository {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        self.policy.check(provider.name(), repo_path, None)?;
+
+        match provider.fork_repository(repo_path).await {
+            Ok(fork) => Ok(vec![ToolContent::Text {
+                text: format!("Forked {} to {}:{}", repo_path, provider.name(), fork),
+            }]),
+            Err(e) => Err(anyhow!("Error forking repository: {}", e)),
+        }
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "fork_repository".into(),
+            description: Some(format!(
+                "Fork a Git repository into the authenticated user's namespace. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    }
+                },
+                "required": ["repo"]
+            }),
+        }
+    }
+}
+
+pub struct CreateBranch {
+    providers: Vec<Arc<dyn GitProvider>>,
+    policy: WritePolicy,
+}
+
+impl CreateBranch {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>, policy: WritePolicy) -> Self {
+        Self { providers, policy }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for CreateBranch {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let branch = string_argument(&args, "branch")?;
+        let from = git_ref_argument(&args, "from");
+        self.policy
+            .check(provider.name(), repo_path, Some(branch))?;
+
+        match provider.create_branch(repo_path, branch, from).await {
+            Ok(()) => Ok(vec![ToolContent::Text {
+                text: format!("Created branch '{}' in {}", branch, repo_path),
+            }]),
+            Err(e) => Err(anyhow!("Error creating branch: {}", e)),
+        }
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "create_branch".into(),
+            description: Some(format!(
+                "Create a new branch in a Git repository. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "branch": {
+                        "type": "string",
+                        "description": "Name of the branch to create"
+                    },
+                    "from": {
+                        "type": "string",
+                        "description": "Optional git reference to branch from: branch name, 'tag:name', or 'commit:sha'. Default: main branch"
+                    }
+                },
+                "required": ["repo", "branch"]
+            }),
+        }
+    }
+}
+
+pub struct CommitFiles {
+    providers: Vec<Arc<dyn GitProvider>>,
+    policy: WritePolicy,
+}
+
+impl CommitFiles {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>, policy: WritePolicy) -> Self {
+        Self { providers, policy }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for CommitFiles {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let branch = string_argument(&args, "branch")?;
+        let message = string_argument(&args, "message")?;
+        self.policy
+            .check(provider.name(), repo_path, Some(branch))?;
+
+        let changes = args
+            .get("changes")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| anyhow!("Missing or invalid changes"))?
+            .iter()
+            .map(|change| {
+                let path = string_argument(change, "path")?.to_string();
+                let delete = change
+                    .get("delete")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+
+                let content = if delete {
+                    None
+                } else {
+                    Some(
+                        change
+                            .get("content")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| anyhow!("Missing content for {}", path))?
+                            .to_string(),
+                    )
+                };
+
+                Ok(FileChange { path, content })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if changes.is_empty() {
+            return Err(anyhow!("At least one change is required"));
+        }
+
+        let count = changes.len();
+        match provider
+            .commit_files(repo_path, branch, message, changes)
+            .await
+        {
+            Ok(sha) => Ok(vec![ToolContent::Text {
+                text: format!(
+                    "Committed {} file change(s) to '{}' in {}: {}",
+                    count, branch, repo_path, sha
+                ),
+            }]),
+            Err(e) => Err(anyhow!("Error committing files: {}", e)),
+        }
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "commit_files".into(),
+            description: Some(format!(
+                "Commit file changes to an existing branch of a Git repository as a single commit. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "branch": {
+                        "type": "string",
+                        "description": "Branch to commit to"
+                    },
+                    "message": {
+                        "type": "string",
+                        "description": "Commit message"
+                    },
+                    "changes": {
+                        "type": "array",
+                        "description": "Files to write or delete",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "path": {
+                                    "type": "string",
+                                    "description": "Path of the file within the repository"
+                                },
+                                "content": {
+                                    "type": "string",
+                                    "description": "New content of the file"
+                                },
+                                "delete": {
+                                    "type": "boolean",
+                                    "description": "Delete the file instead of writing it"
+                                }
+                            },
+                            "required": ["path"]
+                        }
+                    }
+                },
+                "required": ["repo", "branch", "message", "changes"]
+            }),
+        }
+    }
+}
+
+pub struct CreatePullRequest {
+    providers: Vec<Arc<dyn GitProvider>>,
+    policy: WritePolicy,
+}
+
+impl CreatePullRequest {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>, policy: WritePolicy) -> Self {
+        Self { providers, policy }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for CreatePullRequest {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+
+        let request = PullRequestDraft {
+            title: string_argument(&args, "title")?.to_string(),
+            body: args.get("body").and_then(|v| v.as_str()).map(String::from),
+            head_branch: string_argument(&args, "head_branch")?.to_string(),
+            head_repo: args
+                .get("head_repo")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(String::from),
+            base_branch: args
+                .get("base_branch")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(String::from),
+        };
+        // Opening one modifies no branch, the head branch was checked when committed to
+        self.policy.check(provider.name(), repo_path, None)?;
+
+        match provider.create_pull_request(repo_path, request).await {
+            Ok(url) => Ok(vec![ToolContent::Text {
+                text: format!("Opened pull request: {}", url),
+            }]),
+            Err(e) => Err(anyhow!("Error opening pull request: {}", e)),
+        }
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "create_pull_request".into(),
+            description: Some(format!(
+                "Open a pull request (merge request) in a Git repository. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier of the target repository in format 'gitprovider:username/reponame'"
+                    },
+                    "title": {
+                        "type": "string",
+                        "description": "Title of the pull request"
+                    },
+                    "body": {
+                        "type": "string",
+                        "description": "Optional description of the pull request"
+                    },
+                    "head_branch": {
+                        "type": "string",
+                        "description": "Branch containing the changes"
+                    },
+                    "head_repo": {
+                        "type": "string",
+                        "description": "Optional path of the repository holding the head branch (e.g., a fork 'username/reponame'). Default: the target repository"
+                    },
+                    "base_branch": {
+                        "type": "string",
+                        "description": "Optional branch to merge into. Default: main branch"
+                    }
+                },
+                "required": ["repo", "title", "head_branch"]
+            }),
+        }
+    }
+}
+
+pub struct CreateIssue {
+    providers: Vec<Arc<dyn GitProvider>>,
+    policy: WritePolicy,
+}
+
+impl CreateIssue {
+    pub fn new(providers: Vec<Arc<dyn GitProvider>>, policy: WritePolicy) -> Self {
+        Self { providers, policy }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for CreateIssue {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        self.policy.check(provider.name(), repo_path, None)?;
+
+        let issue = IssueDraft {
+            title: string_argument(&args, "title")?.to_string(),
+            body: args.get("body").and_then(|v| v.as_str()).map(String::from),
+            labels: list_argument(&args, "labels"),
+        };
+
+        match provider.create_issue(repo_path, issue).await {
+            Ok(url) => Ok(vec![ToolContent::Text {
+                text: format!("Opened issue: {}", url),
+            }]),
+            Err(e) => Err(anyhow!("Error opening issue: {}", e)),
+        }
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+
+        Tool {
+            name: "create_issue".into(),
+            description: Some(format!(
+                "Open an issue in a Git repository's tracker. Supported providers: {}",
+                providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "title": {
+                        "type": "string",
+                        "description": "Title of the issue"
+                    },
+                    "body": {
+                        "type": "string",
+                        "description": "Optional description of the issue (markdown)"
+                    },
+                    "labels": {
+                        "type": "string",
+                        "description": "Optional comma-separated list of labels to apply"
+                    }
+                },
+                "required": ["repo", "title"]
+            }),
+        }
+    }
+}
+
+/// Posts a comment on either issues or pull requests, depending on the target kind
+pub struct CreateComment {
+    providers: Vec<Arc<dyn GitProvider>>,
+    policy: WritePolicy,
+    pull_requests: bool,
+}
+
+impl CreateComment {
+    /// Comment tool for issues (`comment_on_issue`)
+    pub fn on_issues(providers: Vec<Arc<dyn GitProvider>>, policy: WritePolicy) -> Self {
+        Self {
+            providers,
+            policy,
+            pull_requests: false,
+        }
+    }
+
+    /// Comment tool for pull requests and merge requests (`comment_on_pr`)
+    pub fn on_pull_requests(providers: Vec<Arc<dyn GitProvider>>, policy: WritePolicy) -> Self {
+        Self {
+            providers,
+            policy,
+            pull_requests: true,
+        }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for CreateComment {
+    async fn execute(&self, arguments: Option<Value>) -> Result<Vec<ToolContent>> {
+        let args = required_arguments(arguments)?;
+
+        let (provider, repo_path) = repo_argument(&self.providers, &args)?;
+        let number = usize_argument(&args, "number")
+            .ok_or_else(|| anyhow!("Missing or invalid number"))? as u64;
+        let body = string_argument(&args, "body")?;
+        self.policy.check(provider.name(), repo_path, None)?;
+
+        let target = if self.pull_requests {
+            CommentTarget::PullRequest(number)
+        } else {
+            CommentTarget::Issue(number)
+        };
+
+        match provider.create_comment(repo_path, target, body).await {
+            Ok(url) => Ok(vec![ToolContent::Text {
+                text: format!("Posted comment: {}", url),
+            }]),
+            Err(e) => Err(anyhow!("Error posting comment: {}", e)),
+        }
+    }
+
+    fn to_tool(&self) -> Tool {
+        let providers = supported_providers(&self.providers).join(", ");
+        let (name, subject) = if self.pull_requests {
+            ("comment_on_pr", "pull request (merge request)")
+        } else {
+            ("comment_on_issue", "issue")
+        };
+
+        Tool {
+            name: name.into(),
+            description: Some(format!(
+                "Post a comment on a {} of a Git repository. Supported providers: {}",
+                subject, providers
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository identifier in format 'gitprovider:username/reponame' (e.g., 'github:rust-lang/rust')"
+                    },
+                    "number": {
+                        "type": "integer",
+                        "description": format!("Number of the {} to comment on", subject)
+                    },
+                    "body": {
+                        "type": "string",
+                        "description": "Comment text (markdown)"
+                    }
+                },
+                "required": ["repo", "number", "body"]
+            }),
+        }
+    }
+}
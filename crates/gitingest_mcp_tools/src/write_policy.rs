@@ -0,0 +1,142 @@
+use std::env;
+
+use anyhow::{Result, anyhow};
+use glob::{MatchOptions, Pattern};
+
+/// A repository, and optionally the branches within it, tools may write to
+#[derive(Debug, Clone)]
+struct Rule {
+    provider: String,
+    repo: Pattern,
+    /// Every branch when missing
+    branch: Option<Pattern>,
+}
+
+/// The remote repositories and branches write tools may modify
+#[derive(Debug, Clone, Default)]
+pub struct WritePolicy {
+    rules: Vec<Rule>,
+}
+
+impl WritePolicy {
+    /// Restricts writes to the comma-separated rules of GITINGEST_WRITE_POLICY. Nothing is
+    /// written until it is set
+    pub fn from_env() -> Result<Self> {
+        match env::var("GITINGEST_WRITE_POLICY") {
+            Ok(rules) => Self::with_rules(&rules),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Parses rules like `github:owner/*` or `gitlab:group/project@agent/*`: a provider, a
+    /// repository glob and an optional branch glob
+    pub fn with_rules(rules: &str) -> Result<Self> {
+        let rules = rules
+            .split(',')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+            .map(|rule| {
+                let (provider, target) = rule.split_once(':').ok_or_else(|| {
+                    anyhow!(
+                        "Invalid write policy rule '{}', expected 'provider:owner/repo[@branch]'",
+                        rule
+                    )
+                })?;
+                let (repo, branch) = match target.split_once('@') {
+                    Some((repo, branch)) => (repo, Some(branch)),
+                    None => (target, None),
+                };
+                let pattern = |glob: &str| {
+                    Pattern::new(glob)
+                        .map_err(|e| anyhow!("Invalid write policy rule '{}': {}", rule, e))
+                };
+
+                Ok(Rule {
+                    provider: provider.to_string(),
+                    repo: pattern(repo)?,
+                    branch: branch.map(pattern).transpose()?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { rules })
+    }
+
+    /// Refuses writes to repositories, or branches, no rule allows. Actions on a whole
+    /// repository, e.g. issues and comments, pass without a branch
+    pub(crate) fn check(&self, provider: &str, repo: &str, branch: Option<&str>) -> Result<()> {
+        if self.rules.is_empty() {
+            return Err(anyhow!(
+                "Writing to repositories is disabled, list the repositories and branches tools may write to in GITINGEST_WRITE_POLICY (e.g., 'github:owner/*@agent/*')"
+            ));
+        }
+
+        // Hosts treat owner and repository names case-insensitively, branches are not
+        let repo_options = MatchOptions {
+            case_sensitive: false,
+            ..MatchOptions::new()
+        };
+        let allowed = self.rules.iter().any(|rule| {
+            rule.provider == provider
+                && rule.repo.matches_with(repo, repo_options)
+                && match (&rule.branch, branch) {
+                    (Some(pattern), Some(branch)) => pattern.matches(branch),
+                    _ => true,
+                }
+        });
+        if !allowed {
+            return Err(anyhow!(
+                "{}:{}{} is not allowed by GITINGEST_WRITE_POLICY",
+                provider,
+                repo,
+                branch
+                    .map(|branch| format!("@{}", branch))
+                    .unwrap_or_default()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_allow_repositories_and_branches() {
+        let policy = WritePolicy::with_rules("github:me/*@agent/*, gitlab:group/project").unwrap();
+
+        assert!(policy.check("github", "me/tool", Some("agent/fix")).is_ok());
+        assert!(policy.check("github", "Me/Tool", Some("agent/fix")).is_ok());
+        assert!(policy.check("github", "me/tool", None).is_ok());
+        assert!(policy.check("github", "me/tool", Some("main")).is_err());
+        assert!(
+            policy
+                .check("github", "other/tool", Some("agent/fix"))
+                .is_err()
+        );
+        assert!(
+            policy
+                .check("gitlab", "me/tool", Some("agent/fix"))
+                .is_err()
+        );
+        assert!(
+            policy
+                .check("gitlab", "group/project", Some("main"))
+                .is_ok()
+        );
+        assert!(
+            WritePolicy::default()
+                .check("github", "me/tool", None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn invalid_rules_are_refused() {
+        assert!(WritePolicy::with_rules("me/tool").is_err());
+        assert!(WritePolicy::with_rules("github:me/[tool").is_err());
+        assert!(WritePolicy::with_rules("").unwrap().rules.is_empty());
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    env,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{Result, anyhow};
+
+/// The local directories tools may write files into, e.g. downloaded directories, archives and
+/// extracted CI artifacts
+#[derive(Debug, Clone, Default)]
+pub struct WriteRoots {
+    allowed_roots: Vec<PathBuf>,
+}
+
+impl WriteRoots {
+    /// Restricts writes to the directories listed in GITINGEST_WRITE_ROOTS, separated like PATH
+    /// entries. Nothing is written until it is set
+    pub fn from_env() -> Self {
+        let roots = env::var_os("GITINGEST_WRITE_ROOTS")
+            .map(|roots| env::split_paths(&roots).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        Self::with_roots(roots)
+    }
+
+    /// Restricts writes to the given directories, the ones missing being left out
+    pub fn with_roots(roots: impl IntoIterator<Item = PathBuf>) -> Self {
+        let allowed_roots = roots
+            .into_iter()
+            .filter(|root| !root.as_os_str().is_empty())
+            .filter_map(|root| root.canonicalize().ok())
+            .collect();
+
+        Self { allowed_roots }
+    }
+
+    /// The path a tool may write to for `target`, relative paths being taken from the first
+    /// root. Paths climbing out with `..` or resolving outside of every root are refused
+    pub(crate) fn resolve(&self, target: &Path) -> Result<PathBuf> {
+        // The server could otherwise overwrite any file it has access to, e.g. shell profiles
+        let Some(first_root) = self.allowed_roots.first() else {
+            return Err(anyhow!(
+                "Writing local files is disabled, start the server with --enable-write and list the directories files may be written to in GITINGEST_WRITE_ROOTS"
+            ));
+        };
+
+        if target
+            .components()
+            .any(|component| component == Component::ParentDir)
+        {
+            return Err(anyhow!(
+                "{} climbs out of its directory with '..'",
+                target.display()
+            ));
+        }
+        let target = if target.is_absolute() {
+            target.to_path_buf()
+        } else {
+            first_root.join(target)
+        };
+
+        // The part not created yet holds no symbolic link, the rest is resolved
+        let mut existing = target.as_path();
+        let mut missing = Vec::new();
+        let resolved = loop {
+            match existing.canonicalize() {
+                Ok(resolved) => break resolved,
+                Err(_) => {
+                    let (Some(parent), Some(name)) = (existing.parent(), existing.file_name())
+                    else {
+                        return Err(anyhow!("Cannot resolve {}", target.display()));
+                    };
+                    missing.push(name);
+                    existing = parent;
+                }
+            }
+        };
+        let resolved = missing
+            .into_iter()
+            .rev()
+            .fold(resolved, |path, name| path.join(name));
+
+        if !self
+            .allowed_roots
+            .iter()
+            .any(|allowed| resolved.starts_with(allowed))
+        {
+            return Err(anyhow!(
+                "{} is outside of the directories allowed by GITINGEST_WRITE_ROOTS",
+                target.display()
+            ));
+        }
+
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn targets_stay_below_the_roots() {
+        let base = env::temp_dir().join(format!("write-roots-{}", std::process::id()));
+        let root = base.join("root");
+        fs::create_dir_all(root.join("existing")).unwrap();
+        let root = root.canonicalize().unwrap();
+        let roots = WriteRoots::with_roots([root.clone()]);
+
+        assert_eq!(
+            roots
+                .resolve(Path::new("existing/new/file.tar.gz"))
+                .unwrap(),
+            root.join("existing/new/file.tar.gz")
+        );
+        assert_eq!(roots.resolve(&root.join("out")).unwrap(), root.join("out"));
+        assert!(roots.resolve(Path::new("../outside")).is_err());
+        assert!(roots.resolve(Path::new("existing/../../outside")).is_err());
+        assert!(roots.resolve(&base.join("outside")).is_err());
+        assert!(
+            WriteRoots::default()
+                .resolve(Path::new("existing"))
+                .is_err()
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
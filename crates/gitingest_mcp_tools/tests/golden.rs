@@ -0,0 +1,402 @@
+//! Golden-file tests asserting that tool output is byte-identical for a pinned repository.
+//!
+//! Run with `UPDATE_GOLDEN=1` to rewrite the expected files after an intended format change.
+
+use std::{env, fs, path::PathBuf, sync::Arc};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use context_server::{ToolContent, ToolExecutor};
+use git_provider::{
+    ChangedFile, CommitDetails, CommitInfo, CompareMode, FileStatus, GitProvider, GitRef,
+    RefComparison, RepoItem, RepoItemType, RepoSearchResult, TreeOptions,
+};
+use gitingest_mcp_tools::{
+    CommitDiff, CompareRepositories, DocsSiteMap, ExplainFilters, FindRepositories, RepositoryDiff,
+    RepositoryDigest, RepositoryGrep, RepositoryKeyFiles, RepositoryManifest, SynthesizeChangelog,
+};
+use serde_json::{Value, json};
+
+const FILES: &[(&str, &str)] = &[
+    (
+        "README.md",
+        "# Fixture\n\nA pinned repository for golden tests.\n",
+    ),
+    (
+        "Cargo.toml",
+        "[package]\nname = \"fixture\"\nversion = \"0.1.0\"\n",
+    ),
+    (
+        "src/main.rs",
+        "mod util;\n\nfn main() {\n    util::greet();\n}\n",
+    ),
+    (
+        "src/util.rs",
+        "pub fn greet() {\n    println!(\"hello\");\n}\n\npub fn shout() {\n    println!(\"HELLO\");\n}\n",
+    ),
+    ("docs/guide.md", "# Guide\n\nStart with src/main.rs.\n"),
+    ("tests/smoke.rs", "#[test]\nfn smoke() {}\n"),
+];
+
+/// An in-memory provider serving a fixed repository
+struct FixtureProvider;
+
+fn commit(sha: &str, message: &str) -> CommitInfo {
+    CommitInfo {
+        sha: sha.to_string(),
+        message: message.to_string(),
+        author: "Fixture Author".to_string(),
+        author_email: Some("author@fixture.example".to_string()),
+        date: "2024-01-01T00:00:00Z".to_string(),
+        verification: None,
+    }
+}
+
+/// The files changed between the fixture's release and its main branch, all by its latest commit
+fn changed_files() -> Vec<ChangedFile> {
+    vec![
+        ChangedFile {
+            path: "src/util.rs".to_string(),
+            previous_path: None,
+            similarity: None,
+            status: FileStatus::Modified,
+            additions: 4,
+            deletions: 0,
+            patch: Some(
+                "@@ -2,3 +2,7 @@ pub fn greet() {\n     println!(\"hello\");\n }\n+\n+pub fn shout() {\n+    println!(\"HELLO\");\n+}".to_string(),
+            ),
+        },
+        ChangedFile {
+            path: "docs/guide.md".to_string(),
+            previous_path: None,
+            similarity: None,
+            status: FileStatus::Added,
+            additions: 3,
+            deletions: 0,
+            patch: Some(
+                "@@ -0,0 +1,3 @@\n+# Guide\n+\n+Start with src/main.rs.".to_string(),
+            ),
+        },
+    ]
+}
+
+#[async_trait]
+impl GitProvider for FixtureProvider {
+    fn name(&self) -> &str {
+        "fixture"
+    }
+
+    async fn get_tree_structure(
+        &self,
+        _repo_path: &str,
+        _git_ref: Option<GitRef>,
+        _options: TreeOptions,
+    ) -> Result<String> {
+        Err(anyhow!("Not part of the fixture"))
+    }
+
+    async fn get_file_content(
+        &self,
+        _repo_path: &str,
+        file_path: &str,
+        _git_ref: Option<GitRef>,
+    ) -> Result<String> {
+        FILES
+            .iter()
+            .find(|(path, _)| *path == file_path)
+            .map(|(_, content)| content.to_string())
+            .ok_or_else(|| anyhow!("File not found: {}", file_path))
+    }
+
+    async fn find_repositories(
+        &self,
+        _query: &str,
+        _limit: Option<usize>,
+    ) -> Result<Vec<RepoSearchResult>> {
+        // Ties in star count must not depend on the order the host returns them in
+        Ok(["fixture/zeta", "fixture/alpha", "fixture/popular"]
+            .iter()
+            .map(|name| RepoSearchResult {
+                provider: "fixture".to_string(),
+                full_name: name.to_string(),
+                description: Some(format!("The {} repository", name)),
+                stargazers_count: if *name == "fixture/popular" { 100 } else { 5 },
+            })
+            .collect())
+    }
+
+    async fn list_files(
+        &self,
+        _repo_path: &str,
+        _git_ref: Option<GitRef>,
+        _options: &TreeOptions,
+    ) -> Result<Vec<RepoItem>> {
+        // Reversed on purpose, the tools may not rely on the listing order
+        Ok(FILES
+            .iter()
+            .rev()
+            .map(|(path, content)| RepoItem {
+                name: path.rsplit('/').next().unwrap_or(path).to_string(),
+                path: path.to_string(),
+                item_type: RepoItemType::File,
+                size: Some(content.len() as u64),
+                sha: None,
+            })
+            .collect())
+    }
+
+    async fn compare_refs(
+        &self,
+        _repo_path: &str,
+        _base: Option<GitRef>,
+        _head: Option<GitRef>,
+        mode: CompareMode,
+    ) -> Result<RefComparison> {
+        Ok(RefComparison {
+            base: "v0.1.0".to_string(),
+            head: "main".to_string(),
+            merge_base: (mode == CompareMode::MergeBase).then(|| "0000000".to_string()),
+            commits: vec![
+                commit("1111111", "feat(util): add shout helper"),
+                commit("2222222", "fix: greet with a newline\n\nCloses #4"),
+                commit("3333333", "docs: write the guide"),
+            ],
+            files: changed_files(),
+        })
+    }
+
+    async fn get_commit(&self, _repo_path: &str, sha: &str) -> Result<CommitDetails> {
+        Ok(CommitDetails {
+            commit: commit(
+                sha,
+                "feat(util): add shout helper\n\nAlso documents the helpers.",
+            ),
+            files: changed_files(),
+        })
+    }
+}
+
+fn providers() -> Vec<Arc<dyn GitProvider>> {
+    vec![Arc::new(FixtureProvider)]
+}
+
+async fn run(tool: &dyn ToolExecutor, arguments: Value) -> String {
+    let content = tool
+        .execute(Some(arguments))
+        .await
+        .expect("tool execution failed");
+
+    content
+        .into_iter()
+        .map(|content| match content {
+            ToolContent::Text { text } => text,
+            _ => panic!("expected text content"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Compares `actual` with `tests/golden/{name}.txt`, running the tool twice to catch
+/// output that differs between identical calls
+async fn assert_golden(name: &str, tool: &dyn ToolExecutor, arguments: Value) {
+    let actual = run(tool, arguments.clone()).await;
+    assert_eq!(
+        actual,
+        run(tool, arguments).await,
+        "{} is not stable across calls",
+        name
+    );
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{}.txt", name));
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("missing golden file {}: {}", path.display(), e));
+    assert_eq!(actual, expected, "output of {} changed", name);
+}
+
+#[tokio::test]
+async fn digest_full() {
+    assert_golden(
+        "digest_full",
+        &RepositoryDigest::new(providers()),
+        json!({ "repo": "fixture:pinned/repo" }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn digest_sample_priority() {
+    assert_golden(
+        "digest_sample_priority",
+        &RepositoryDigest::new(providers()),
+        json!({
+            "repo": "fixture:pinned/repo",
+            "mode": "sample",
+            "sample_lines": 2,
+            "order": "priority",
+            "max_files": 4
+        }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn digest_incremental() {
+    assert_golden(
+        "digest_incremental",
+        &RepositoryDigest::new(providers()),
+        json!({
+            "repo": "fixture:pinned/repo",
+            "previous_manifest": "-\t45\tCargo.toml\n-\t40\tREADME.md\n-\t33\tdocs/guide.md\n-\t44\tsrc/main.rs\n-\t85\tsrc/util.rs\n-\t10\tsrc/old.rs\n"
+        }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn digest_summary() {
+    assert_golden(
+        "digest_summary",
+        &RepositoryDigest::new(providers()),
+        json!({
+            "repo": "fixture:pinned/repo",
+            "summary": true,
+            "front_matter": false,
+            "max_tokens": 40
+        }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn grep() {
+    assert_golden(
+        "grep",
+        &RepositoryGrep::new(providers()),
+        json!({
+            "repo": "fixture:pinned/repo",
+            "pattern": "PRINTLN!",
+            "ignore_case": true,
+            "context_lines": 1
+        }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn key_files() {
+    assert_golden(
+        "key_files",
+        &RepositoryKeyFiles::new(providers()),
+        json!({ "repo": "fixture:pinned/repo", "recent_commits": 0 }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn find_repositories() {
+    assert_golden(
+        "find_repositories",
+        &FindRepositories::new(providers()),
+        json!({ "query": "fixture" }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn diff_unified() {
+    assert_golden(
+        "diff_unified",
+        &RepositoryDiff::new(providers()),
+        json!({ "repo": "fixture:pinned/repo", "base": "tag:v0.1.0" }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn commit_diff() {
+    assert_golden(
+        "commit_diff",
+        &CommitDiff::new(providers()),
+        json!({ "repo": "fixture:pinned/repo", "sha": "1111111", "paths": "src" }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn diff_stats() {
+    assert_golden(
+        "diff_stats",
+        &RepositoryDiff::new(providers()),
+        json!({ "repo": "fixture:pinned/repo", "base": "tag:v0.1.0", "render": "stats" }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn changelog() {
+    assert_golden(
+        "changelog",
+        &SynthesizeChangelog::new(providers()),
+        json!({ "repo": "fixture:pinned/repo", "from": "tag:v0.1.0", "version": "0.2.0" }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn explain_filters() {
+    assert_golden(
+        "explain_filters",
+        &ExplainFilters::new(providers()),
+        json!({
+            "repo": "fixture:pinned/repo",
+            "include_patterns": "*.rs,Cargo.toml,!src/util.rs",
+            "exclude_patterns": "tests/**"
+        }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn manifest_changes() {
+    assert_golden(
+        "manifest_changes",
+        &RepositoryManifest::new(providers()),
+        json!({
+            "repo": "fixture:pinned/repo",
+            "previous": "-\t45\tCargo.toml\n-\t40\tREADME.md\n-\t33\tdocs/guide.md\n-\t44\tsrc/main.rs\n-\t85\tsrc/util.rs\n-\t10\tsrc/old.rs\n"
+        }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn compare_repositories() {
+    assert_golden(
+        "compare_repositories",
+        &CompareRepositories::new(providers()),
+        json!({
+            "repo_a": "fixture:pinned/repo",
+            "repo_b": "fixture:pinned/other"
+        }),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn docs_site_map() {
+    assert_golden(
+        "docs_site_map",
+        &DocsSiteMap::new(providers()),
+        json!({ "repo": "fixture:pinned/repo" }),
+    )
+    .await;
+}
@@ -1,13 +1,40 @@
-use std::{env, sync::Arc};
+mod auth;
+mod snippet;
+
+use std::{collections::BTreeMap, env, sync::Arc};
 
 use anyhow::Result;
 use async_trait::async_trait;
 use futures::future;
 use git_provider::{
-    GitProvider, GitRef, RepoItem, RepoItemType, RepoNode, RepoSearchResult, create_tree_structure,
+    BoardColumn, BoardItem, BranchDivergence, ChangedFile, CiArtifact, CloneRemote, CommentTarget,
+    CommitDetails, CommitInfo, CompareMode, ContributorInfo, FileChange, FileStatus, GitProvider,
+    GitRef, IssueComment, IssueDetails, IssueDraft, IssueFilter, LanguageShare, MAX_README_SIZE,
+    Milestone, ProjectBoard, PullRequestDetails, PullRequestDraft, PullRequestSummary,
+    RefComparison, ReleaseInfo, RepoEvent, RepoEventKind, RepoItem, RepoItemType, RepoLabel,
+    RepoNode, RepoSearchResult, RepositoryInfo, ReviewComment, SignatureVerification, TagInfo,
+    TreeOptions,
+    api_compat::{self, Api},
+    depth_limited_directory,
+    encoding::encode_component,
     ignore_patterns::DEFAULT_IGNORE_PATTERNS,
+    is_readme,
+    patterns::PathFilter,
+    pii,
+    provenance::MirrorOrigin,
+    readme_summary, render_tree,
+};
+use http_client::{
+    HttpClient, Request, RequestBuilderExt, ResponseAsyncBodyExt,
+    http::{HeaderMap, StatusCode},
 };
-use http_client::{HttpClient, Request, RequestBuilderExt, ResponseAsyncBodyExt, http::HeaderMap};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+pub use crate::{auth::GitLabToken, snippet::SnippetProvider};
+
+/// The public instance, used unless another host is configured
+const DEFAULT_HOST: &str = "https://gitlab.com";
 
 const MAX_FILES: usize = 500;
 
@@ -15,11 +42,58 @@ const MAX_FILES: usize = 500;
 struct GitLabProject {
     // Make all fields optional to handle different API response formats
     #[serde(default)]
+    id: Option<u64>,
+    #[serde(default)]
     name: Option<String>,
     #[serde(default)]
     default_branch: Option<String>,
 }
 
+#[derive(Debug, serde::Deserialize)]
+struct GitLabProjectInfo {
+    path_with_namespace: String,
+    description: Option<String>,
+    web_url: String,
+    default_branch: Option<String>,
+    #[serde(default)]
+    star_count: u64,
+    #[serde(default)]
+    forks_count: u64,
+    /// Absent when issues are disabled
+    open_issues_count: Option<u64>,
+    license: Option<GitLabLicense>,
+    #[serde(default)]
+    topics: Vec<String>,
+    #[serde(default)]
+    archived: bool,
+    forked_from_project: Option<GitLabProjectPath>,
+    /// Whether GitLab pulls the project from another repository
+    #[serde(default)]
+    mirror: bool,
+    /// Origin of imported and mirrored projects, only returned to maintainers
+    import_url: Option<String>,
+    /// Only returned to project members
+    statistics: Option<GitLabProjectStatistics>,
+    created_at: String,
+    last_activity_at: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabProjectStatistics {
+    /// In bytes
+    repository_size: u64,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabLicense {
+    name: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabProjectPath {
+    path_with_namespace: String,
+}
+
 // GitLab repositories search response
 #[derive(Debug, serde::Deserialize)]
 struct GitLabRepoItem {
@@ -29,6 +103,474 @@ struct GitLabRepoItem {
     star_count: usize,
 }
 
+#[derive(Debug, serde::Deserialize)]
+struct GitLabJob {
+    id: u64,
+    name: String,
+    #[serde(default)]
+    artifacts_file: Option<GitLabArtifactsFile>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabArtifactsFile {
+    #[serde(default)]
+    size: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabEvent {
+    action_name: String,
+    #[serde(default)]
+    target_type: Option<String>,
+    #[serde(default)]
+    target_iid: Option<u64>,
+    #[serde(default)]
+    target_title: Option<String>,
+    #[serde(default)]
+    author_username: Option<String>,
+    created_at: String,
+    #[serde(default)]
+    push_data: Option<GitLabPushData>,
+    #[serde(default)]
+    note: Option<GitLabEventNote>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabPushData {
+    #[serde(default)]
+    commit_count: u64,
+    #[serde(default)]
+    action: String,
+    #[serde(default)]
+    ref_type: String,
+    #[serde(rename = "ref", default)]
+    ref_name: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabEventNote {
+    #[serde(default)]
+    noteable_type: Option<String>,
+    #[serde(default)]
+    noteable_iid: Option<u64>,
+}
+
+impl GitLabEvent {
+    fn into_repo_event(self) -> RepoEvent {
+        let title = self.target_title.clone().unwrap_or_default();
+        let iid = self
+            .target_iid
+            .map(|iid| iid.to_string())
+            .unwrap_or_default();
+
+        let (kind, summary) = match (&self.push_data, self.target_type.as_deref()) {
+            (Some(push), _) if push.action == "pushed" => (
+                RepoEventKind::Push,
+                format!(
+                    "pushed {} commit(s) to {}",
+                    push.commit_count,
+                    push.ref_name.clone().unwrap_or_default()
+                ),
+            ),
+            (Some(push), _) => (
+                RepoEventKind::Ref,
+                format!(
+                    "{} {} {}",
+                    push.action,
+                    push.ref_type,
+                    push.ref_name.clone().unwrap_or_default()
+                ),
+            ),
+            (None, Some("Issue")) => (
+                RepoEventKind::Issue,
+                format!("{} issue #{}: {}", self.action_name, iid, title),
+            ),
+            (None, Some("MergeRequest")) => (
+                RepoEventKind::PullRequest,
+                format!("{} merge request !{}: {}", self.action_name, iid, title),
+            ),
+            (None, Some("Note" | "DiffNote" | "DiscussionNote")) => {
+                let on = self
+                    .note
+                    .as_ref()
+                    .map(|note| {
+                        format!(
+                            " on {} {}",
+                            note.noteable_type.clone().unwrap_or_default(),
+                            note.noteable_iid
+                                .map(|iid| iid.to_string())
+                                .unwrap_or_default()
+                        )
+                    })
+                    .unwrap_or_default();
+
+                (
+                    RepoEventKind::Comment,
+                    format!("commented{}: {}", on, title),
+                )
+            }
+            (None, Some("Release")) => (
+                RepoEventKind::Release,
+                format!("{} release {}", self.action_name, title),
+            ),
+            (None, _) => (
+                RepoEventKind::Other,
+                format!("{} {}", self.action_name, title).trim().to_string(),
+            ),
+        };
+
+        RepoEvent {
+            kind,
+            actor: self.author_username.unwrap_or_default(),
+            created_at: self.created_at,
+            summary,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabUser {
+    username: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabMergeRequest {
+    iid: u64,
+    title: String,
+    author: GitLabUser,
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    source_branch: String,
+    target_branch: String,
+    #[serde(default)]
+    labels: Vec<String>,
+    web_url: String,
+    updated_at: String,
+    #[serde(default)]
+    references: Option<GitLabReferences>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabIssue {
+    iid: u64,
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    state: String,
+    author: GitLabUser,
+    #[serde(default)]
+    labels: Vec<String>,
+    web_url: String,
+    created_at: String,
+}
+
+impl GitLabIssue {
+    fn into_issue_details(self) -> IssueDetails {
+        IssueDetails {
+            number: self.iid,
+            title: self.title,
+            body: self.description.filter(|d| !d.is_empty()),
+            state: self.state,
+            author: self.author.username,
+            labels: self.labels,
+            url: self.web_url,
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabMergeRequestChanges {
+    changes: Vec<GitLabDiff>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabNote {
+    body: String,
+    author: GitLabUser,
+    created_at: String,
+    #[serde(default)]
+    updated_at: Option<String>,
+    /// Notes GitLab writes itself, e.g. "added 1 commit"
+    #[serde(default)]
+    system: bool,
+    /// Where a note on the diff sits
+    #[serde(default)]
+    position: Option<GitLabNotePosition>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabNotePosition {
+    #[serde(default)]
+    new_path: Option<String>,
+    #[serde(default)]
+    new_line: Option<u64>,
+    #[serde(default)]
+    old_line: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabReferences {
+    full: String,
+}
+
+impl GitLabMergeRequest {
+    fn into_summary(self, repository: Option<&str>) -> PullRequestSummary {
+        // "group/project!12" identifies the project when listing across projects
+        let repository = repository.map(String::from).unwrap_or_else(|| {
+            self.references
+                .as_ref()
+                .and_then(|r| r.full.split('!').next())
+                .unwrap_or_default()
+                .to_string()
+        });
+
+        PullRequestSummary {
+            repository,
+            number: self.iid,
+            title: self.title,
+            author: self.author.username,
+            state: self.state,
+            draft: self.draft,
+            source_branch: Some(self.source_branch),
+            target_branch: Some(self.target_branch),
+            labels: self.labels,
+            url: self.web_url,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabTreeEntry {
+    id: String,
+    name: String,
+    path: String,
+    #[serde(rename = "type")]
+    entry_type: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabComparison {
+    #[serde(default)]
+    commits: Vec<GitLabCommit>,
+    #[serde(default)]
+    diffs: Vec<GitLabDiff>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabCommitId {
+    id: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabCommit {
+    id: String,
+    message: String,
+    author_name: String,
+    author_email: Option<String>,
+    created_at: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabSignature {
+    signature_type: String,
+    verification_status: String,
+    #[serde(default)]
+    gpg_key_user_name: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabBlobMatch {
+    path: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabTag {
+    name: String,
+    #[serde(default)]
+    message: Option<String>,
+    /// The tag object of an annotated tag, the commit itself otherwise
+    target: String,
+    commit: GitLabCommitId,
+    #[serde(default)]
+    created_at: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabRelease {
+    tag_name: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    released_at: Option<String>,
+    /// Set when `released_at` is in the future
+    #[serde(default)]
+    upcoming_release: bool,
+    #[serde(default, rename = "_links")]
+    links: Option<GitLabReleaseLinks>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabReleaseLinks {
+    #[serde(rename = "self")]
+    web_url: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabContributor {
+    name: String,
+    #[serde(default)]
+    email: Option<String>,
+    commits: u64,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabDiff {
+    old_path: String,
+    new_path: String,
+    #[serde(default)]
+    new_file: bool,
+    #[serde(default)]
+    renamed_file: bool,
+    #[serde(default)]
+    deleted_file: bool,
+    #[serde(default)]
+    diff: String,
+}
+
+impl GitLabCommit {
+    fn into_commit_info(self) -> CommitInfo {
+        CommitInfo {
+            sha: self.id,
+            message: self.message,
+            author: pii::author(self.author_name),
+            author_email: self.author_email.filter(|e| !e.is_empty()),
+            date: self.created_at,
+            verification: None,
+        }
+    }
+}
+
+impl GitLabSignature {
+    fn into_verification(self) -> SignatureVerification {
+        let format = match self.signature_type.to_ascii_lowercase().as_str() {
+            "pgp" => "gpg".to_string(),
+            other => other.to_string(),
+        };
+
+        SignatureVerification {
+            // "verified_system" and "verified_ca" are verified too
+            verified: self.verification_status.starts_with("verified"),
+            reason: self.verification_status,
+            format: Some(format),
+            signer: self.gpg_key_user_name,
+        }
+    }
+}
+
+impl GitLabDiff {
+    fn into_changed_file(self) -> ChangedFile {
+        let status = if self.new_file {
+            FileStatus::Added
+        } else if self.deleted_file {
+            FileStatus::Removed
+        } else if self.renamed_file {
+            FileStatus::Renamed
+        } else {
+            FileStatus::Modified
+        };
+
+        // GitLab does not report line counts, derive them from the diff
+        let count = |prefix: char| {
+            self.diff
+                .lines()
+                .filter(|l| l.starts_with(prefix) && !l.starts_with("+++") && !l.starts_with("---"))
+                .count()
+        };
+        let additions = count('+');
+        let deletions = count('-');
+
+        ChangedFile {
+            previous_path: self.renamed_file.then_some(self.old_path),
+            similarity: (self.renamed_file && additions == 0 && deletions == 0).then_some(100),
+            path: self.new_path,
+            status,
+            additions,
+            deletions,
+            patch: (!self.diff.is_empty()).then_some(self.diff),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabNamedRef {
+    name: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabBranch {
+    name: String,
+    commit: Option<GitLabBranchCommit>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabBranchCommit {
+    committed_date: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabLabel {
+    name: String,
+    color: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    open_issues_count: u64,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabMilestone {
+    title: String,
+    state: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    due_date: Option<String>,
+    #[serde(default)]
+    web_url: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabBoard {
+    name: String,
+    #[serde(default)]
+    lists: Vec<GitLabBoardList>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabBoardList {
+    #[serde(default)]
+    label: Option<GitLabLabelRef>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabLabelRef {
+    name: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabIssueRef {
+    iid: u64,
+    title: String,
+    state: String,
+}
+
 #[derive(Debug, serde::Deserialize)]
 struct GitLabRepositoryFile {
     #[serde(default)]
@@ -39,21 +581,65 @@ struct GitLabRepositoryFile {
     size: Option<u64>,
     #[serde(rename = "type", default)]
     item_type: String,
+    #[serde(default)]
+    id: Option<String>,
 }
 
 pub struct GitLabProvider {
     http_client: Arc<dyn HttpClient>,
-    gitlab_token: Option<String>,
+    token: Option<GitLabToken>,
+    name: String,
+    /// Base URL of the instance, without a trailing slash
+    host: String,
 }
 
 impl GitLabProvider {
+    /// A provider for GitLab.com authenticated with GITLAB_TOKEN. Inside GitLab CI, without
+    /// GITLAB_TOKEN, the job's own token is used against the instance running the job.
     pub fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        if let Ok(token) = env::var("GITLAB_TOKEN") {
+            return Self::with_host(
+                http_client,
+                "gitlab",
+                DEFAULT_HOST,
+                Some(GitLabToken::detect(&token)),
+            );
+        }
+
+        match env::var("CI_JOB_TOKEN") {
+            Ok(token) => {
+                let host = env::var("CI_SERVER_URL").unwrap_or_else(|_| DEFAULT_HOST.to_string());
+                Self::with_host(http_client, "gitlab", &host, Some(GitLabToken::Job(token)))
+            }
+            Err(_) => Self::with_host(http_client, "gitlab", DEFAULT_HOST, None),
+        }
+    }
+
+    /// A provider for a self-managed GitLab instance, registered under its own name
+    pub fn with_host(
+        http_client: Arc<dyn HttpClient>,
+        name: &str,
+        host: &str,
+        token: Option<GitLabToken>,
+    ) -> Self {
         Self {
             http_client,
-            gitlab_token: env::var("GITLAB_TOKEN").ok(),
+            token,
+            name: name.to_string(),
+            host: normalize_host(host),
         }
     }
 
+    /// Base URL of the instance, without a trailing slash
+    pub(crate) fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Base URL of the instance's API, in the version the provider is written against
+    pub(crate) fn api_base(&self) -> String {
+        api_compat::gitlab_api_base(&self.host)
+    }
+
     async fn search_repositories(
         &self,
         query: &str,
@@ -66,7 +652,8 @@ impl GitLabProvider {
 
         // Build the GitLab API URL for searching repositories
         let mut url = format!(
-            "https://gitlab.com/api/v4/projects?search={}",
+            "{}/projects?search={}",
+            self.api_base(),
             urlencoding::encode(query)
         );
 
@@ -81,8 +668,8 @@ impl GitLabProvider {
         let mut headers = HeaderMap::new();
         headers.insert("User-Agent", "GitIngest-MCP".parse()?);
 
-        if let Some(gitlab_token) = &self.gitlab_token {
-            headers.insert("PRIVATE-TOKEN", gitlab_token.parse()?);
+        if let Some(token) = &self.token {
+            token.authorize(&mut headers)?;
         }
 
         let response = self
@@ -98,7 +685,7 @@ impl GitLabProvider {
 
         let status = response.status();
         let response_text = response.text().await?;
-        dbg!(&response_text);
+
         // Check response status
         if !status.is_success() {
             return match status.as_u16() {
@@ -112,34 +699,21 @@ impl GitLabProvider {
             };
         }
 
-        // Try to parse the response
-        match serde_json::from_str::<Vec<GitLabRepoItem>>(&response_text) {
-            Ok(results) => Ok(results),
-            Err(e) => {
-                // Check for error responses that might be valid JSON but not the expected format
-                if let Ok(error_obj) = serde_json::from_str::<serde_json::Value>(&response_text) {
-                    if error_obj.get("message").is_some() {
-                        eprintln!("GitLab API returned error message: {:?}", error_obj);
-                        return Ok(Vec::new()); // Return empty results for tests
-                    }
-                }
-
-                // Return empty results or handle specific error cases
-                eprintln!("Error parsing GitLab repository search response: {}", e);
-                Ok(Vec::new())
-            }
-        }
+        api_compat::parse_listing(Api::GitLab, api_compat::endpoint(&url), &response_text)
     }
 
     async fn fetch_repo_metadata(&self, repo_path: &str) -> Result<GitLabProject> {
-        let encoded_path = urlencoding::encode(repo_path);
-        let url = format!("https://gitlab.com/api/v4/projects/{}", encoded_path);
+        let url = format!(
+            "{}/projects/{}",
+            self.api_base(),
+            encode_component(repo_path)
+        );
 
         let mut headers = HeaderMap::new();
         headers.insert("User-Agent", "GitIngest-MCP".parse()?);
 
-        if let Some(gitlab_token) = &self.gitlab_token {
-            headers.insert("PRIVATE-TOKEN", gitlab_token.parse()?);
+        if let Some(token) = &self.token {
+            token.authorize(&mut headers)?;
         }
 
         let response = self
@@ -153,29 +727,16 @@ impl GitLabProvider {
             )
             .await?;
 
-        // Try to parse the response - if it fails, use default values
-        let project: GitLabProject = match response.json().await {
-            Ok(project) => project,
-            Err(e) => {
-                eprintln!("Error parsing GitLab project response: {}", e);
-                // Return a default project with minimal info
-                GitLabProject {
-                    name: Some("Unknown".to_string()),
-                    default_branch: None,
-                }
-            }
-        };
+        let response_text = response.text().await?;
 
-        Ok(project)
+        api_compat::parse(Api::GitLab, api_compat::endpoint(&url), &response_text)
     }
 
+    /// Splits a repository path into the project path and the branch of a `/-/tree/` suffix
     fn parse_repo_path(&self, repo_path: &str) -> Result<(String, Option<String>)> {
-        // GitLab uses URL-encoded paths in the API
-        let encoded_path = urlencoding::encode(repo_path);
-
         // Extract branch if specified
         let segments: Vec<&str> = repo_path.split("/-/").collect();
-        let _path = segments[0].to_string();
+        let project_path = segments[0].to_string();
 
         let branch = if segments.len() > 1 {
             if segments[1].starts_with("tree/") {
@@ -187,7 +748,7 @@ impl GitLabProvider {
             None
         };
 
-        Ok((encoded_path.to_string(), branch))
+        Ok((project_path, branch))
     }
 
     async fn fetch_repository_tree(
@@ -196,10 +757,10 @@ impl GitLabProvider {
         path: &str,
         ref_name: Option<&str>,
     ) -> Result<Vec<RepoItem>> {
-        let encoded_path = urlencoding::encode(repo_path);
         let mut url = format!(
-            "https://gitlab.com/api/v4/projects/{}/repository/tree",
-            encoded_path
+            "{}/projects/{}/repository/tree",
+            self.api_base(),
+            encode_component(repo_path)
         );
 
         // Add query parameters
@@ -209,7 +770,7 @@ impl GitLabProvider {
             url.push_str(&format!(
                 "{}path={}",
                 if has_param { "&" } else { "?" },
-                path
+                encode_component(path)
             ));
             has_param = true;
         }
@@ -218,15 +779,15 @@ impl GitLabProvider {
             url.push_str(&format!(
                 "{}ref={}",
                 if has_param { "&" } else { "?" },
-                ref_name
+                encode_component(ref_name)
             ));
         }
 
         let mut headers = HeaderMap::new();
         headers.insert("User-Agent", "GitIngest-MCP".parse()?);
 
-        if let Some(gitlab_token) = &self.gitlab_token {
-            headers.insert("PRIVATE-TOKEN", gitlab_token.parse()?);
+        if let Some(token) = &self.token {
+            token.authorize(&mut headers)?;
         }
 
         let response = self
@@ -240,14 +801,9 @@ impl GitLabProvider {
             )
             .await?;
 
-        // Try to parse the response - if it fails, return an empty tree
-        let tree: Vec<GitLabRepositoryFile> = match response.json().await {
-            Ok(tree) => tree,
-            Err(e) => {
-                eprintln!("Error parsing GitLab tree response: {}", e);
-                Vec::new()
-            }
-        };
+        let response_text = response.text().await?;
+        let tree: Vec<GitLabRepositoryFile> =
+            api_compat::parse_listing(Api::GitLab, api_compat::endpoint(&url), &response_text)?;
 
         let items = tree
             .into_iter()
@@ -261,6 +817,7 @@ impl GitLabProvider {
                         _ => RepoItemType::File, // Default to file for anything else
                     },
                     size: item.size,
+                    sha: item.id,
                 }
             })
             .collect();
@@ -274,24 +831,24 @@ impl GitLabProvider {
         file_path: &str,
         git_ref: Option<&str>,
     ) -> Result<String> {
-        let encoded_repo_path = urlencoding::encode(repo_path);
-        let encoded_file_path = urlencoding::encode(file_path);
-
+        // The file path is a single segment, its slashes included
         let mut url = format!(
-            "https://gitlab.com/api/v4/projects/{}/repository/files/{}",
-            encoded_repo_path, encoded_file_path
+            "{}/projects/{}/repository/files/{}",
+            self.api_base(),
+            encode_component(repo_path),
+            encode_component(file_path)
         );
 
         // Add ref parameter if provided
         if let Some(ref_name) = git_ref {
-            url.push_str(&format!("?ref={}", ref_name));
+            url.push_str(&format!("?ref={}", encode_component(ref_name)));
         }
 
         let mut headers = HeaderMap::new();
         headers.insert("User-Agent", "GitIngest-MCP".parse()?);
 
-        if let Some(gitlab_token) = &self.gitlab_token {
-            headers.insert("PRIVATE-TOKEN", gitlab_token.parse()?);
+        if let Some(token) = &self.token {
+            token.authorize(&mut headers)?;
         }
 
         let response = self
@@ -324,15 +881,9 @@ impl GitLabProvider {
             encoding: String,
         }
 
-        let file_data: GitLabFileContent = match response.json().await {
-            Ok(data) => data,
-            Err(e) => {
-                return Err(anyhow::anyhow!(
-                    "Failed to parse GitLab API response: {}",
-                    e
-                ));
-            }
-        };
+        let response_text = response.text().await?;
+        let file_data: GitLabFileContent =
+            api_compat::parse(Api::GitLab, api_compat::endpoint(&url), &response_text)?;
 
         // GitLab returns base64-encoded content
         if file_data.encoding == "base64" {
@@ -360,61 +911,34 @@ impl GitLabProvider {
         Ok(ignore_patterns)
     }
 
-    fn should_include(&self, path: &str, include_patterns: &[String]) -> bool {
-        if include_patterns.is_empty() {
-            return true;
-        }
-
-        include_patterns.iter().any(|pattern| {
-            if let Ok(glob) = glob::Pattern::new(pattern) {
-                glob.matches(path)
-            } else {
-                false
-            }
-        })
-    }
-
-    fn should_exclude(
-        &self,
-        path: &str,
-        exclude_patterns: &[String],
-        ignore_patterns: &[String],
-    ) -> bool {
-        exclude_patterns.iter().any(|pattern| {
-            if let Ok(glob) = glob::Pattern::new(pattern) {
-                glob.matches(path)
-            } else {
-                false
-            }
-        }) || ignore_patterns.iter().any(|p| path.contains(p.as_str()))
-    }
-
+    #[allow(clippy::too_many_arguments)]
     async fn build_tree(
         &self,
         repo_path: String,
         ref_name: Option<String>,
         path: String,
-        exclude_patterns: Vec<String>,
-        include_patterns: Vec<String>,
+        options: TreeOptions,
         ignore_patterns: Vec<String>,
         depth: usize,
         max_depth: usize,
     ) -> Result<RepoNode> {
         if depth > max_depth {
-            return Ok(RepoNode {
-                name: path.split('/').last().unwrap_or(&path).to_string(),
-                node_type: RepoItemType::Directory,
-                size: 0,
-                children: vec![],
-                file_count: 0,
-                dir_count: 1,
-            });
+            return Ok(depth_limited_directory(
+                path.split('/').next_back().unwrap_or(&path),
+            ));
         }
 
         let contents = self
             .fetch_repository_tree(&repo_path, &path, ref_name.as_deref())
             .await?;
 
+        let description = if options.describe_directories {
+            self.fetch_readme_summary(&repo_path, ref_name.as_deref(), &contents)
+                .await
+        } else {
+            None
+        };
+
         let mut children = Vec::new();
         let mut file_count = 0;
         let mut dir_count = 1; // Count self
@@ -422,10 +946,13 @@ impl GitLabProvider {
 
         let mut tasks = Vec::new();
 
+        let filter = PathFilter::from_options(&options, &ignore_patterns);
         for item in contents {
-            if !self.should_include(&item.path, &include_patterns)
-                || self.should_exclude(&item.path, &exclude_patterns, &ignore_patterns)
-            {
+            let keep = match item.item_type {
+                RepoItemType::File => filter.keeps(&item.path),
+                RepoItemType::Directory => filter.keeps_directory(&item.path),
+            };
+            if !keep {
                 continue;
             }
 
@@ -442,22 +969,21 @@ impl GitLabProvider {
                         children: vec![],
                         file_count: 1,
                         dir_count: 0,
+                        description: None,
                     });
                 }
                 RepoItemType::Directory => {
                     let repo_path = repo_path.clone();
                     let ref_name = ref_name.clone();
                     let path = item.path;
-                    let exclude_patterns = exclude_patterns.clone();
-                    let include_patterns = include_patterns.clone();
+                    let options = options.clone();
                     let ignore_patterns = ignore_patterns.clone();
 
                     tasks.push(self.build_tree(
                         repo_path,
                         ref_name,
                         path,
-                        exclude_patterns,
-                        include_patterns,
+                        options,
                         ignore_patterns,
                         depth + 1,
                         max_depth,
@@ -495,35 +1021,222 @@ impl GitLabProvider {
             name: if path.is_empty() {
                 "root".to_string()
             } else {
-                path.split('/').last().unwrap_or(&path).to_string()
+                path.split('/').next_back().unwrap_or(&path).to_string()
             },
             node_type: RepoItemType::Directory,
             size: total_size,
             children,
             file_count,
             dir_count,
+            description,
         })
     }
+
+    /// Summarize the README found among a directory's entries, if any
+    async fn fetch_readme_summary(
+        &self,
+        repo_path: &str,
+        ref_name: Option<&str>,
+        contents: &[RepoItem],
+    ) -> Option<String> {
+        // The tree API reports no sizes, so large READMEs are only caught after download
+        let readme = contents
+            .iter()
+            .find(|item| item.item_type == RepoItemType::File && is_readme(&item.name))?;
+
+        let content = self
+            .fetch_file_content(repo_path, &readme.path, ref_name)
+            .await
+            .ok()?;
+
+        if content.len() as u64 > MAX_README_SIZE {
+            return None;
+        }
+
+        readme_summary(&content)
+    }
+
+    fn api_headers(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.insert("User-Agent", "GitIngest-MCP".parse()?);
+
+        if let Some(token) = &self.token {
+            token.authorize(&mut headers)?;
+        }
+
+        Ok(headers)
+    }
+
+    /// Base API URL of a project, ignoring any "/-/tree/..." suffix
+    fn project_url(&self, repo_path: &str) -> String {
+        let project_path = repo_path.split("/-/").next().unwrap_or(repo_path);
+
+        format!(
+            "{}/projects/{}",
+            self.api_base(),
+            urlencoding::encode(project_path)
+        )
+    }
+
+    /// Whether a file exists on a branch, failing on any answer but found or not found
+    async fn file_exists(&self, repo_path: &str, file_path: &str, branch: &str) -> Result<bool> {
+        // The file path is a single segment, its slashes included
+        let request = Request::builder()
+            .uri(format!(
+                "{}/repository/files/{}?ref={}",
+                self.project_url(repo_path),
+                encode_component(file_path),
+                encode_component(branch)
+            ))
+            .method("HEAD")
+            .headers(self.api_headers()?)
+            .end()?;
+
+        let status = self.http_client.send(request).await?.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        if !status.is_success() {
+            return Err(anyhow::anyhow!(
+                "GitLab API error ({}) checking whether {} exists",
+                status,
+                file_path
+            ));
+        }
+
+        Ok(true)
+    }
+
+    /// The signature of a commit, `None` when it is unsigned
+    async fn commit_signature(&self, commit_url: &str) -> Result<Option<GitLabSignature>> {
+        let request = Request::builder()
+            .uri(format!("{}/signature", commit_url))
+            .method("GET")
+            .headers(self.api_headers()?)
+            .end()?;
+
+        let response = self.http_client.send(request).await?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let response_text = response.text().await?;
+        if !status.is_success() {
+            return Err(anyhow::anyhow!(
+                "GitLab API error ({}): {}",
+                status,
+                response_text
+            ));
+        }
+
+        api_compat::parse(
+            Api::GitLab,
+            api_compat::endpoint(commit_url),
+            &response_text,
+        )
+        .map(Some)
+    }
+
+    /// Send an API request, failing with GitLab's error message on non-success statuses
+    async fn api_request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        url: &str,
+        body: Option<Value>,
+    ) -> Result<T> {
+        let builder = Request::builder()
+            .uri(url)
+            .method(method)
+            .headers(self.api_headers()?);
+
+        let request = match body {
+            Some(body) => builder.json(body)?,
+            None => builder.end()?,
+        };
+
+        let response = self.http_client.send(request).await?;
+        let status = response.status();
+        let response_text = response.text().await?;
+
+        if !status.is_success() {
+            let message = serde_json::from_str::<Value>(&response_text)
+                .ok()
+                .and_then(|v| {
+                    v.get("message")
+                        .or_else(|| v.get("error"))
+                        .map(|m| m.as_str().map(String::from).unwrap_or(m.to_string()))
+                })
+                .unwrap_or(response_text);
+
+            return Err(anyhow::anyhow!(
+                "GitLab API error ({}): {}",
+                status,
+                message
+            ));
+        }
+
+        api_compat::parse(Api::GitLab, api_compat::endpoint(url), &response_text)
+    }
+
+    /// Resolve an optional git reference to a name the API accepts
+    async fn resolve_ref_name(&self, repo_path: &str, git_ref: Option<GitRef>) -> Result<String> {
+        match git_ref {
+            Some(GitRef::Branch(name)) | Some(GitRef::Tag(name)) | Some(GitRef::Commit(name)) => {
+                Ok(name)
+            }
+            Some(GitRef::Default) | None => {
+                let project: GitLabProject = self
+                    .api_request("GET", &self.project_url(repo_path), None)
+                    .await?;
+
+                project
+                    .default_branch
+                    .ok_or_else(|| anyhow::anyhow!("Project {} has no default branch", repo_path))
+            }
+        }
+    }
+}
+
+/// Base URL of an instance, defaulting to HTTPS when no scheme is given
+fn normalize_host(host: &str) -> String {
+    let host = host.trim().trim_end_matches('/');
+    if host.starts_with("http://") || host.starts_with("https://") {
+        host.to_string()
+    } else {
+        format!("https://{}", host)
+    }
 }
 
 #[async_trait]
 impl GitProvider for GitLabProvider {
     fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> &str {
         "gitlab"
     }
 
+    fn web_url(&self) -> Option<String> {
+        Some(self.host.clone())
+    }
+
+    fn is_anonymous(&self) -> bool {
+        self.token.is_none()
+    }
+
     async fn get_tree_structure(
         &self,
         repo_path: &str,
         git_ref: Option<GitRef>,
-        exclude_patterns: Vec<String>,
-        include_patterns: Vec<String>,
+        options: TreeOptions,
     ) -> Result<String> {
         // Parse the repository path
-        let (encoded_path, path_branch) = self.parse_repo_path(repo_path)?;
+        let (project_path, path_branch) = self.parse_repo_path(repo_path)?;
 
         // Fetch repository metadata
-        let metadata = self.fetch_repo_metadata(&encoded_path).await?;
+        let metadata = self.fetch_repo_metadata(&project_path).await?;
 
         // Determine which reference to use
         let ref_name = match git_ref {
@@ -534,20 +1247,23 @@ impl GitProvider for GitLabProvider {
             None => path_branch,
         };
 
-        // Set up ignored patterns
-        let ignore_patterns = self
-            .set_ignore_patterns(&encoded_path, ref_name.as_deref())
-            .await?;
+        // Set up ignored patterns, unless the caller gave its own
+        let ignore_patterns = match &options.ignore_patterns {
+            Some(ignore_patterns) => ignore_patterns.clone(),
+            None => {
+                self.set_ignore_patterns(&project_path, ref_name.as_deref())
+                    .await?
+            }
+        };
 
         // Build repository tree
-        let max_depth = 10; // Limit recursion depth
+        let max_depth = options.max_depth();
         let root_node = self
             .build_tree(
-                encoded_path.clone(),
+                project_path.clone(),
                 ref_name,
-                "".into(),
-                exclude_patterns,
-                include_patterns,
+                options.subtree().to_string(),
+                options.clone(),
                 ignore_patterns,
                 0,
                 max_depth,
@@ -555,24 +1271,26 @@ impl GitProvider for GitLabProvider {
             .await?;
 
         // Get the actual repo name from the path
-        let repo_name = metadata
-            .name
-            .unwrap_or_else(|| repo_path.split('/').last().unwrap_or(repo_path).to_string());
+        let repo_name = metadata.name.unwrap_or_else(|| {
+            repo_path
+                .split('/')
+                .next_back()
+                .unwrap_or(repo_path)
+                .to_string()
+        });
 
         // Add the repo name as the root
         let tree_node = RepoNode {
-            name: repo_name,
+            name: options.root_name(&repo_name),
             node_type: RepoItemType::Directory,
             size: root_node.size,
             children: root_node.children,
             file_count: root_node.file_count,
             dir_count: root_node.dir_count,
+            description: root_node.description,
         };
 
-        // Create the tree structure string
-        let tree_str = create_tree_structure(&tree_node, "", true);
-
-        Ok(tree_str)
+        Ok(render_tree(&tree_node, &options))
     }
 
     async fn get_file_content(
@@ -582,7 +1300,7 @@ impl GitProvider for GitLabProvider {
         git_ref: Option<GitRef>,
     ) -> Result<String> {
         // Parse the repository path
-        let (encoded_path, path_branch) = self.parse_repo_path(repo_path)?;
+        let (project_path, path_branch) = self.parse_repo_path(repo_path)?;
 
         // Determine which reference to use
         let ref_name = match git_ref {
@@ -591,14 +1309,14 @@ impl GitProvider for GitLabProvider {
             Some(GitRef::Commit(commit)) => Some(commit),
             Some(GitRef::Default) => {
                 // Fetch repository metadata to get default branch
-                let metadata = self.fetch_repo_metadata(&encoded_path).await?;
+                let metadata = self.fetch_repo_metadata(&project_path).await?;
                 metadata.default_branch
             }
             None => path_branch,
         };
 
         // Fetch the file content
-        self.fetch_file_content(&encoded_path, file_path, ref_name.as_deref())
+        self.fetch_file_content(&project_path, file_path, ref_name.as_deref())
             .await
     }
 
@@ -614,7 +1332,7 @@ impl GitProvider for GitLabProvider {
         let results = repos
             .into_iter()
             .map(|repo| RepoSearchResult {
-                provider: "gitlab".to_string(),
+                provider: self.name.clone(),
                 full_name: repo.path_with_namespace,
                 description: repo.description,
                 stargazers_count: repo.star_count,
@@ -623,4 +1341,1156 @@ impl GitProvider for GitLabProvider {
 
         Ok(results)
     }
+
+    async fn fork_repository(&self, repo_path: &str) -> Result<String> {
+        let fork: Value = self
+            .api_request(
+                "POST",
+                &format!("{}/fork", self.project_url(repo_path)),
+                Some(json!({})),
+            )
+            .await?;
+
+        fork.get("path_with_namespace")
+            .and_then(|n| n.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow::anyhow!("Fork response did not include the project path"))
+    }
+
+    async fn create_branch(
+        &self,
+        repo_path: &str,
+        branch: &str,
+        from: Option<GitRef>,
+    ) -> Result<()> {
+        let ref_name = self.resolve_ref_name(repo_path, from).await?;
+
+        let _: Value = self
+            .api_request(
+                "POST",
+                &format!(
+                    "{}/repository/branches?branch={}&ref={}",
+                    self.project_url(repo_path),
+                    urlencoding::encode(branch),
+                    urlencoding::encode(&ref_name)
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn commit_files(
+        &self,
+        repo_path: &str,
+        branch: &str,
+        message: &str,
+        changes: Vec<FileChange>,
+    ) -> Result<String> {
+        // GitLab needs to know whether each file is created or updated, only a file not found
+        // is created
+        let mut actions = Vec::new();
+        for change in changes {
+            let action = match &change.content {
+                None => "delete",
+                Some(_) => {
+                    if self.file_exists(repo_path, &change.path, branch).await? {
+                        "update"
+                    } else {
+                        "create"
+                    }
+                }
+            };
+
+            actions.push(json!({
+                "action": action,
+                "file_path": change.path,
+                "content": change.content,
+            }));
+        }
+
+        let commit: Value = self
+            .api_request(
+                "POST",
+                &format!("{}/repository/commits", self.project_url(repo_path)),
+                Some(json!({
+                    "branch": branch,
+                    "commit_message": message,
+                    "actions": actions,
+                })),
+            )
+            .await?;
+
+        commit
+            .get("id")
+            .and_then(|s| s.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow::anyhow!("Commit creation did not return a SHA"))
+    }
+
+    async fn create_pull_request(
+        &self,
+        repo_path: &str,
+        request: PullRequestDraft,
+    ) -> Result<String> {
+        let target: GitLabProject = self
+            .api_request("GET", &self.project_url(repo_path), None)
+            .await?;
+
+        let target_branch = request
+            .base_branch
+            .or(target.default_branch)
+            .ok_or_else(|| anyhow::anyhow!("Project {} has no default branch", repo_path))?;
+
+        // Merge requests from a fork are opened on the fork, targeting the upstream project
+        let (source_project, target_project_id) = match &request.head_repo {
+            Some(head_repo) => (head_repo.as_str(), target.id),
+            None => (repo_path, None),
+        };
+
+        let merge_request: Value = self
+            .api_request(
+                "POST",
+                &format!("{}/merge_requests", self.project_url(source_project)),
+                Some(json!({
+                    "source_branch": request.head_branch,
+                    "target_branch": target_branch,
+                    "title": request.title,
+                    "description": request.body,
+                    "target_project_id": target_project_id,
+                })),
+            )
+            .await?;
+
+        merge_request
+            .get("web_url")
+            .and_then(|u| u.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow::anyhow!("Merge request response did not include a URL"))
+    }
+
+    async fn create_issue(&self, repo_path: &str, issue: IssueDraft) -> Result<String> {
+        let created: Value = self
+            .api_request(
+                "POST",
+                &format!("{}/issues", self.project_url(repo_path)),
+                Some(json!({
+                    "title": issue.title,
+                    "description": issue.body,
+                    "labels": issue.labels.join(","),
+                })),
+            )
+            .await?;
+
+        created
+            .get("web_url")
+            .and_then(|u| u.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow::anyhow!("Issue response did not include a URL"))
+    }
+
+    async fn create_comment(
+        &self,
+        repo_path: &str,
+        target: CommentTarget,
+        body: &str,
+    ) -> Result<String> {
+        let (collection, iid) = match target {
+            CommentTarget::Issue(iid) => ("issues", iid),
+            CommentTarget::PullRequest(iid) => ("merge_requests", iid),
+        };
+
+        let note: Value = self
+            .api_request(
+                "POST",
+                &format!(
+                    "{}/{}/{}/notes",
+                    self.project_url(repo_path),
+                    collection,
+                    iid
+                ),
+                Some(json!({ "body": body })),
+            )
+            .await?;
+
+        // Notes carry no URL of their own, link to the anchor on the parent page
+        let note_id = note
+            .get("id")
+            .and_then(|id| id.as_u64())
+            .ok_or_else(|| anyhow::anyhow!("Comment response did not include an id"))?;
+        let project_path = repo_path.split("/-/").next().unwrap_or(repo_path);
+
+        Ok(format!(
+            "{}/{}/-/{}/{}#note_{}",
+            self.host, project_path, collection, iid, note_id
+        ))
+    }
+
+    async fn get_job_log(&self, repo_path: &str, job_id: &str) -> Result<String> {
+        let url = format!(
+            "{}/jobs/{}/trace",
+            self.project_url(repo_path),
+            urlencoding::encode(job_id)
+        );
+
+        let response = self
+            .http_client
+            .send(
+                Request::builder()
+                    .uri(&url)
+                    .method("GET")
+                    .headers(self.api_headers()?)
+                    .end()?,
+            )
+            .await?;
+
+        let status = response.status();
+        let log = response.text().await?;
+
+        match status.as_u16() {
+            200..=299 => Ok(log),
+            404 => Err(anyhow::anyhow!("Job {} not found", job_id)),
+            _ => Err(anyhow::anyhow!("GitLab API error: {}", status)),
+        }
+    }
+
+    async fn list_artifacts(&self, repo_path: &str, run_id: &str) -> Result<Vec<CiArtifact>> {
+        let jobs: Vec<GitLabJob> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/pipelines/{}/jobs?per_page=100",
+                    self.project_url(repo_path),
+                    urlencoding::encode(run_id)
+                ),
+                None,
+            )
+            .await?;
+
+        // Each job with an artifacts archive is one artifact, addressed by the job id
+        Ok(jobs
+            .into_iter()
+            .filter_map(|job| {
+                let archive = job.artifacts_file?;
+                Some(CiArtifact {
+                    id: job.id.to_string(),
+                    name: job.name,
+                    size: archive.size,
+                    // Expired archives are removed from the job altogether
+                    expired: false,
+                })
+            })
+            .collect())
+    }
+
+    async fn download_artifact(&self, repo_path: &str, artifact_id: &str) -> Result<Vec<u8>> {
+        let url = format!(
+            "{}/jobs/{}/artifacts",
+            self.project_url(repo_path),
+            urlencoding::encode(artifact_id)
+        );
+
+        let response = self
+            .http_client
+            .send(
+                Request::builder()
+                    .uri(&url)
+                    .method("GET")
+                    .headers(self.api_headers()?)
+                    .end()?,
+            )
+            .await?;
+
+        let status = response.status();
+        match status.as_u16() {
+            200..=299 => Ok(response.bytes().await?),
+            404 => Err(anyhow::anyhow!(
+                "Artifacts for job {} not found or expired",
+                artifact_id
+            )),
+            _ => Err(anyhow::anyhow!("GitLab API error: {}", status)),
+        }
+    }
+
+    async fn list_events(&self, repo_path: &str, since: Option<&str>) -> Result<Vec<RepoEvent>> {
+        let mut events = Vec::new();
+        for page in 1..=3 {
+            let batch: Vec<GitLabEvent> = self
+                .api_request(
+                    "GET",
+                    &format!(
+                        "{}/events?per_page=100&sort=desc&page={}",
+                        self.project_url(repo_path),
+                        page
+                    ),
+                    None,
+                )
+                .await?;
+
+            let exhausted = batch.len() < 100
+                || since.is_some_and(|since| {
+                    batch
+                        .last()
+                        .is_some_and(|event| event.created_at.as_str() < since)
+                });
+
+            events.extend(
+                batch
+                    .into_iter()
+                    .filter(|event| since.is_none_or(|since| event.created_at.as_str() >= since))
+                    .map(GitLabEvent::into_repo_event),
+            );
+
+            if exhausted {
+                break;
+            }
+        }
+
+        Ok(events)
+    }
+
+    async fn list_review_requests(&self, limit: Option<usize>) -> Result<Vec<PullRequestSummary>> {
+        if self.token.is_none() {
+            return Err(anyhow::anyhow!(
+                "A GITLAB_TOKEN is required to list review requests"
+            ));
+        }
+
+        let user: GitLabUser = self
+            .api_request("GET", &format!("{}/user", self.api_base()), None)
+            .await?;
+
+        let merge_requests: Vec<GitLabMergeRequest> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/merge_requests?scope=all&state=opened&reviewer_username={}&order_by=updated_at&per_page={}",
+                    self.api_base(),
+                    urlencoding::encode(&user.username),
+                    limit.unwrap_or(50).min(100)
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(merge_requests
+            .into_iter()
+            .map(|mr| mr.into_summary(None))
+            .collect())
+    }
+
+    async fn list_pull_requests(
+        &self,
+        repo_path: &str,
+        state: &str,
+        target_branch: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<PullRequestSummary>> {
+        let (project_path, _) = self.parse_repo_path(repo_path)?;
+
+        // GitLab calls open merge requests "opened"
+        let mut url = format!(
+            "{}/merge_requests?state={}&order_by=updated_at&sort=desc&per_page={}",
+            self.project_url(&project_path),
+            if state == "open" { "opened" } else { state },
+            limit.min(100)
+        );
+        if let Some(target_branch) = target_branch {
+            url.push_str(&format!(
+                "&target_branch={}",
+                encode_component(target_branch)
+            ));
+        }
+
+        let merge_requests: Vec<GitLabMergeRequest> = self.api_request("GET", &url, None).await?;
+
+        Ok(merge_requests
+            .into_iter()
+            .map(|mr| mr.into_summary(Some(&project_path)))
+            .collect())
+    }
+
+    async fn list_milestones(&self, repo_path: &str, state: &str) -> Result<Vec<Milestone>> {
+        let project_url = self.project_url(repo_path);
+        let mut url = format!("{}/milestones?per_page=100", project_url);
+
+        // GitLab calls open milestones "active"
+        match state {
+            "open" => url.push_str("&state=active"),
+            "closed" => url.push_str("&state=closed"),
+            _ => {}
+        }
+
+        let milestones: Vec<GitLabMilestone> = self.api_request("GET", &url, None).await?;
+
+        // Milestones carry no issue counts, ask the statistics endpoint for each
+        let statistics = future::join_all(milestones.iter().map(|milestone| {
+            let url = format!(
+                "{}/issues_statistics?milestone={}",
+                project_url,
+                urlencoding::encode(&milestone.title)
+            );
+            async move { self.api_request::<Value>("GET", &url, None).await }
+        }))
+        .await;
+
+        Ok(milestones
+            .into_iter()
+            .zip(statistics)
+            .map(|(milestone, statistics)| {
+                let count = |key: &str| {
+                    statistics
+                        .as_ref()
+                        .ok()
+                        .and_then(|s| s.pointer(&format!("/statistics/counts/{}", key)))
+                        .and_then(|c| c.as_u64())
+                        .unwrap_or_default()
+                };
+
+                Milestone {
+                    title: milestone.title,
+                    state: if milestone.state == "active" {
+                        "open".to_string()
+                    } else {
+                        milestone.state
+                    },
+                    description: milestone.description.filter(|d| !d.is_empty()),
+                    due_on: milestone.due_date,
+                    open_issues: count("opened"),
+                    closed_issues: count("closed"),
+                    url: milestone.web_url,
+                }
+            })
+            .collect())
+    }
+
+    async fn list_boards(&self, repo_path: &str) -> Result<Vec<ProjectBoard>> {
+        let project_url = self.project_url(repo_path);
+
+        let boards: Vec<GitLabBoard> = self
+            .api_request("GET", &format!("{}/boards", project_url), None)
+            .await?;
+
+        let mut result = Vec::new();
+        for board in boards {
+            // Each list of a board is backed by a label
+            let labels: Vec<String> = board
+                .lists
+                .into_iter()
+                .filter_map(|list| list.label.map(|l| l.name))
+                .collect();
+
+            let issues = future::join_all(labels.iter().map(|label| {
+                let url = format!(
+                    "{}/issues?state=opened&labels={}&per_page=50",
+                    project_url,
+                    urlencoding::encode(label)
+                );
+                async move {
+                    self.api_request::<Vec<GitLabIssueRef>>("GET", &url, None)
+                        .await
+                }
+            }))
+            .await;
+
+            let mut columns = Vec::new();
+            for (label, issues) in labels.into_iter().zip(issues) {
+                columns.push(BoardColumn {
+                    name: label,
+                    items: issues?
+                        .into_iter()
+                        .map(|issue| BoardItem {
+                            title: issue.title,
+                            number: Some(issue.iid),
+                            state: Some(issue.state),
+                        })
+                        .collect(),
+                });
+            }
+
+            result.push(ProjectBoard {
+                name: board.name,
+                url: None,
+                columns,
+            });
+        }
+
+        Ok(result)
+    }
+
+    async fn list_labels(&self, repo_path: &str) -> Result<Vec<RepoLabel>> {
+        let project_url = self.project_url(repo_path);
+
+        let mut labels: Vec<GitLabLabel> = Vec::new();
+        for page in 1.. {
+            let batch: Vec<GitLabLabel> = self
+                .api_request(
+                    "GET",
+                    &format!(
+                        "{}/labels?with_counts=true&per_page=100&page={}",
+                        project_url, page
+                    ),
+                    None,
+                )
+                .await?;
+
+            let done = batch.len() < 100;
+            labels.extend(batch);
+            if done {
+                break;
+            }
+        }
+
+        Ok(labels
+            .into_iter()
+            .map(|label| RepoLabel {
+                name: label.name,
+                color: label.color.trim_start_matches('#').to_string(),
+                description: label.description.filter(|d| !d.is_empty()),
+                open_issues: label.open_issues_count,
+            })
+            .collect())
+    }
+
+    async fn compare_refs(
+        &self,
+        repo_path: &str,
+        base: Option<GitRef>,
+        head: Option<GitRef>,
+        mode: CompareMode,
+    ) -> Result<RefComparison> {
+        let base = self.resolve_ref_name(repo_path, base).await?;
+        let head = self.resolve_ref_name(repo_path, head).await?;
+
+        // straight=false compares against the merge base of both references
+        let straight = mode == CompareMode::Direct;
+        let comparison: GitLabComparison = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repository/compare?from={}&to={}&straight={}",
+                    self.project_url(repo_path),
+                    urlencoding::encode(&base),
+                    urlencoding::encode(&head),
+                    straight
+                ),
+                None,
+            )
+            .await?;
+
+        // The compare endpoint does not report the merge base it used
+        let merge_base = match mode {
+            CompareMode::MergeBase => {
+                let commit: GitLabCommitId = self
+                    .api_request(
+                        "GET",
+                        &format!(
+                            "{}/repository/merge_base?refs[]={}&refs[]={}",
+                            self.project_url(repo_path),
+                            urlencoding::encode(&base),
+                            urlencoding::encode(&head)
+                        ),
+                        None,
+                    )
+                    .await?;
+                Some(commit.id)
+            }
+            CompareMode::Direct => None,
+        };
+
+        Ok(RefComparison {
+            base,
+            head,
+            merge_base,
+            commits: comparison
+                .commits
+                .into_iter()
+                .map(GitLabCommit::into_commit_info)
+                .collect(),
+            files: comparison
+                .diffs
+                .into_iter()
+                .map(GitLabDiff::into_changed_file)
+                .collect(),
+        })
+    }
+
+    async fn list_files(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: &TreeOptions,
+    ) -> Result<Vec<RepoItem>> {
+        let (_, path_branch) = self.parse_repo_path(repo_path)?;
+        let git_ref = git_ref.or(path_branch.map(GitRef::Branch));
+        let ref_name = self.resolve_ref_name(repo_path, git_ref).await?;
+        let ignore_patterns = match &options.ignore_patterns {
+            Some(ignore_patterns) => ignore_patterns.clone(),
+            None => self.set_ignore_patterns(repo_path, Some(&ref_name)).await?,
+        };
+        let filter = PathFilter::from_options(options, &ignore_patterns);
+
+        let mut files = Vec::new();
+        for page in 1..=50 {
+            let entries: Vec<GitLabTreeEntry> = self
+                .api_request(
+                    "GET",
+                    &format!(
+                        "{}/repository/tree?recursive=true&per_page=100&page={}&ref={}",
+                        self.project_url(repo_path),
+                        page,
+                        urlencoding::encode(&ref_name)
+                    ),
+                    None,
+                )
+                .await?;
+
+            let done = entries.len() < 100;
+            files.extend(
+                entries
+                    .into_iter()
+                    .filter(|entry| entry.entry_type == "blob")
+                    .filter(|entry| filter.keeps(&entry.path))
+                    .map(|entry| RepoItem {
+                        name: entry.name,
+                        path: entry.path,
+                        item_type: RepoItemType::File,
+                        // The tree API does not report blob sizes
+                        size: None,
+                        sha: Some(entry.id),
+                    }),
+            );
+
+            if done {
+                break;
+            }
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(files)
+    }
+
+    async fn list_commits(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        path: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        let ref_name = self.resolve_ref_name(repo_path, git_ref).await?;
+
+        let mut url = format!(
+            "{}/repository/commits?ref_name={}&per_page={}",
+            self.project_url(repo_path),
+            urlencoding::encode(&ref_name),
+            limit.clamp(1, 100)
+        );
+        if let Some(path) = path {
+            url.push_str(&format!("&path={}", urlencoding::encode(path)));
+        }
+
+        let commits: Vec<GitLabCommit> = self.api_request("GET", &url, None).await?;
+
+        Ok(commits
+            .into_iter()
+            .map(GitLabCommit::into_commit_info)
+            .collect())
+    }
+
+    async fn get_commit(&self, repo_path: &str, sha: &str) -> Result<CommitDetails> {
+        let commit_url = format!(
+            "{}/repository/commits/{}",
+            self.project_url(repo_path),
+            urlencoding::encode(sha)
+        );
+
+        let diff_url = format!("{}/diff?per_page=100", commit_url);
+
+        let (commit, diffs, signature) = futures::join!(
+            self.api_request::<GitLabCommit>("GET", &commit_url, None),
+            self.api_request::<Vec<GitLabDiff>>("GET", &diff_url, None),
+            self.commit_signature(&commit_url),
+        );
+
+        let mut commit = commit?.into_commit_info();
+        // Failing to read the signature only leaves it unreported
+        commit.verification = signature.ok().map(|signature| match signature {
+            Some(signature) => signature.into_verification(),
+            None => SignatureVerification::unsigned(),
+        });
+
+        Ok(CommitDetails {
+            commit,
+            files: diffs?
+                .into_iter()
+                .map(GitLabDiff::into_changed_file)
+                .collect(),
+        })
+    }
+
+    async fn list_issues(
+        &self,
+        repo_path: &str,
+        filter: &IssueFilter,
+        page: usize,
+        per_page: usize,
+    ) -> Result<Vec<IssueDetails>> {
+        let mut url = format!(
+            "{}/issues?order_by=updated_at&sort=desc&page={}&per_page={}",
+            self.project_url(repo_path),
+            page.max(1),
+            per_page.min(100)
+        );
+        // GitLab calls open issues "opened", and lists every state unless given one
+        match filter.state.as_str() {
+            "open" => url.push_str("&state=opened"),
+            "closed" => url.push_str("&state=closed"),
+            _ => {}
+        }
+        if !filter.labels.is_empty() {
+            let labels: Vec<String> = filter.labels.iter().map(|l| encode_component(l)).collect();
+            url.push_str(&format!("&labels={}", labels.join(",")));
+        }
+        if let Some(assignee) = &filter.assignee {
+            url.push_str(&format!(
+                "&assignee_username={}",
+                encode_component(assignee)
+            ));
+        }
+
+        let issues: Vec<GitLabIssue> = self.api_request("GET", &url, None).await?;
+
+        Ok(issues
+            .into_iter()
+            .map(GitLabIssue::into_issue_details)
+            .collect())
+    }
+
+    async fn get_issue(&self, repo_path: &str, number: u64) -> Result<IssueDetails> {
+        let issue: GitLabIssue = self
+            .api_request(
+                "GET",
+                &format!("{}/issues/{}", self.project_url(repo_path), number),
+                None,
+            )
+            .await?;
+
+        Ok(issue.into_issue_details())
+    }
+
+    async fn list_issue_comments(&self, repo_path: &str, number: u64) -> Result<Vec<IssueComment>> {
+        let notes: Vec<GitLabNote> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/issues/{}/notes?sort=asc&order_by=created_at&per_page=100",
+                    self.project_url(repo_path),
+                    number
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(notes
+            .into_iter()
+            .filter(|note| !note.system)
+            .map(|note| IssueComment {
+                author: note.author.username,
+                body: note.body,
+                updated_at: note
+                    .updated_at
+                    .filter(|updated_at| *updated_at != note.created_at),
+                created_at: note.created_at,
+            })
+            .collect())
+    }
+
+    async fn get_pull_request(&self, repo_path: &str, number: u64) -> Result<PullRequestDetails> {
+        let mut merge_request: GitLabMergeRequest = self
+            .api_request(
+                "GET",
+                &format!("{}/merge_requests/{}", self.project_url(repo_path), number),
+                None,
+            )
+            .await?;
+
+        let body = merge_request.description.take().filter(|d| !d.is_empty());
+        let project_path = repo_path.split("/-/").next().unwrap_or(repo_path);
+
+        Ok(PullRequestDetails {
+            summary: merge_request.into_summary(Some(project_path)),
+            body,
+        })
+    }
+
+    async fn list_pull_request_files(
+        &self,
+        repo_path: &str,
+        number: u64,
+    ) -> Result<Vec<ChangedFile>> {
+        let changes: GitLabMergeRequestChanges = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/merge_requests/{}/changes",
+                    self.project_url(repo_path),
+                    number
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(changes
+            .changes
+            .into_iter()
+            .map(GitLabDiff::into_changed_file)
+            .collect())
+    }
+
+    async fn list_pull_request_comments(
+        &self,
+        repo_path: &str,
+        number: u64,
+    ) -> Result<Vec<ReviewComment>> {
+        let notes: Vec<GitLabNote> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/merge_requests/{}/notes?sort=asc&order_by=created_at&per_page=100",
+                    self.project_url(repo_path),
+                    number
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(notes
+            .into_iter()
+            .filter(|note| !note.system)
+            .map(|note| {
+                let position = note.position.as_ref();
+                ReviewComment {
+                    author: note.author.username,
+                    body: note.body,
+                    path: position.and_then(|p| p.new_path.clone()),
+                    line: position.and_then(|p| p.new_line.or(p.old_line)),
+                    created_at: note.created_at,
+                }
+            })
+            .collect())
+    }
+
+    async fn list_refs(&self, repo_path: &str, prefix: &str) -> Result<Vec<String>> {
+        let project_url = self.project_url(repo_path);
+        // A leading '^' restricts the search to names starting with it
+        let search = match prefix.trim_start_matches("tag:") {
+            "" => String::new(),
+            name => format!("&search=^{}", urlencoding::encode(name)),
+        };
+        let branches_url = format!("{}/repository/branches?per_page=100{}", project_url, search);
+        let tags_url = format!("{}/repository/tags?per_page=100{}", project_url, search);
+
+        let (branches, tags): (Vec<GitLabNamedRef>, Vec<GitLabNamedRef>) = futures::try_join!(
+            self.api_request("GET", &branches_url, None),
+            self.api_request("GET", &tags_url, None)
+        )?;
+
+        Ok(branches
+            .into_iter()
+            .map(|branch| branch.name)
+            .chain(tags.into_iter().map(|tag| format!("tag:{}", tag.name)))
+            .filter(|name| name.starts_with(prefix))
+            .collect())
+    }
+
+    async fn search_code(&self, repo_path: &str, query: &str, limit: usize) -> Result<Vec<String>> {
+        let matches: Vec<GitLabBlobMatch> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/search?scope=blobs&search={}&per_page={}",
+                    self.project_url(repo_path),
+                    urlencoding::encode(query),
+                    limit.clamp(1, 100)
+                ),
+                None,
+            )
+            .await?;
+
+        // Every matching chunk of a file is a separate result
+        let mut paths: Vec<String> = Vec::new();
+        for blob in matches {
+            if !paths.contains(&blob.path) {
+                paths.push(blob.path);
+            }
+        }
+        Ok(paths)
+    }
+
+    async fn list_tags(&self, repo_path: &str, limit: usize) -> Result<Vec<TagInfo>> {
+        let tags: Vec<GitLabTag> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repository/tags?order_by=updated&sort=desc&per_page={}",
+                    self.project_url(repo_path),
+                    limit.clamp(1, 100)
+                ),
+                None,
+            )
+            .await?;
+
+        // Tag signatures are not exposed, apart from X.509 ones through a request per tag
+        Ok(tags
+            .into_iter()
+            .take(limit)
+            .map(|tag| TagInfo {
+                annotated: tag.target != tag.commit.id,
+                name: tag.name,
+                commit_sha: tag.commit.id,
+                message: tag
+                    .message
+                    .map(|m| m.trim_end().to_string())
+                    .filter(|m| !m.is_empty()),
+                tagger: None,
+                date: tag.created_at,
+                verification: None,
+            })
+            .collect())
+    }
+
+    async fn list_releases(&self, repo_path: &str, limit: usize) -> Result<Vec<ReleaseInfo>> {
+        let releases: Vec<GitLabRelease> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/releases?order_by=released_at&sort=desc&per_page={}",
+                    self.project_url(repo_path),
+                    limit.clamp(1, 100)
+                ),
+                None,
+            )
+            .await?;
+
+        // GitLab has neither drafts nor pre-releases, only releases dated in the future
+        Ok(releases
+            .into_iter()
+            .take(limit)
+            .map(|release| ReleaseInfo {
+                tag_name: release.tag_name,
+                name: release.name.filter(|name| !name.trim().is_empty()),
+                published_at: release.released_at.filter(|_| !release.upcoming_release),
+                notes: release
+                    .description
+                    .map(|description| description.trim_end().to_string())
+                    .filter(|description| !description.is_empty()),
+                prerelease: false,
+                draft: false,
+                url: release.links.and_then(|links| links.web_url),
+            })
+            .collect())
+    }
+
+    async fn list_contributors(
+        &self,
+        repo_path: &str,
+        limit: usize,
+    ) -> Result<Vec<ContributorInfo>> {
+        // Contributors are commit authors rather than accounts, named as in the commits
+        let contributors: Vec<GitLabContributor> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repository/contributors?order_by=commits&sort=desc&per_page={}",
+                    self.project_url(repo_path),
+                    limit.clamp(1, 100)
+                ),
+                None,
+            )
+            .await?;
+
+        Ok(contributors
+            .into_iter()
+            .take(limit)
+            .map(|contributor| ContributorInfo {
+                login: pii::author(contributor.name),
+                email: contributor.email.filter(|email| !email.is_empty()),
+                contributions: contributor.commits,
+                bot: false,
+            })
+            .collect())
+    }
+
+    async fn list_languages(&self, repo_path: &str) -> Result<Vec<LanguageShare>> {
+        // Already in percent, GitLab does not expose the byte counts
+        let languages: BTreeMap<String, f64> = self
+            .api_request(
+                "GET",
+                &format!("{}/languages", self.project_url(repo_path)),
+                None,
+            )
+            .await?;
+
+        let mut shares: Vec<LanguageShare> = languages
+            .into_iter()
+            .map(|(name, percent)| LanguageShare {
+                name,
+                percent,
+                bytes: None,
+            })
+            .collect();
+        shares.sort_by(|a, b| b.percent.total_cmp(&a.percent));
+        Ok(shares)
+    }
+
+    async fn get_repository_info(&self, repo_path: &str) -> Result<RepositoryInfo> {
+        let info: GitLabProjectInfo = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}?license=true&statistics=true",
+                    self.project_url(repo_path)
+                ),
+                None,
+            )
+            .await?;
+
+        let description = info.description.filter(|d| !d.is_empty());
+        let mirror = if info.mirror {
+            Some(MirrorOrigin::flagged(info.import_url.as_deref()))
+        } else {
+            description
+                .as_deref()
+                .and_then(MirrorOrigin::from_description)
+        };
+
+        // GitLab does not record which template a project was created from
+        Ok(RepositoryInfo {
+            full_name: info.path_with_namespace,
+            description,
+            url: info.web_url,
+            default_branch: info.default_branch,
+            stars: info.star_count,
+            forks: info.forks_count,
+            open_issues: info.open_issues_count,
+            language: None,
+            license: info.license.map(|license| license.name),
+            topics: info.topics,
+            archived: info.archived,
+            size_kb: info
+                .statistics
+                .map(|statistics| statistics.repository_size.div_ceil(1024)),
+            is_template: false,
+            generated_from: None,
+            forked_from: info.forked_from_project.map(|p| p.path_with_namespace),
+            mirror,
+            created_at: info.created_at,
+            last_activity_at: info.last_activity_at,
+        })
+    }
+
+    async fn list_commits_since(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        since: &str,
+        max_commits: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        let ref_name = self.resolve_ref_name(repo_path, git_ref).await?;
+
+        let mut commits = Vec::new();
+        for page in 1..=max_commits.div_ceil(100) {
+            let batch: Vec<GitLabCommit> = self
+                .api_request(
+                    "GET",
+                    &format!(
+                        "{}/repository/commits?ref_name={}&since={}&per_page=100&page={}",
+                        self.project_url(repo_path),
+                        urlencoding::encode(&ref_name),
+                        urlencoding::encode(since),
+                        page
+                    ),
+                    None,
+                )
+                .await?;
+
+            let exhausted = batch.len() < 100;
+            commits.extend(batch.into_iter().map(GitLabCommit::into_commit_info));
+            if exhausted {
+                break;
+            }
+        }
+
+        commits.truncate(max_commits);
+        Ok(commits)
+    }
+
+    async fn branch_divergence(
+        &self,
+        repo_path: &str,
+        limit: usize,
+    ) -> Result<(String, Vec<BranchDivergence>)> {
+        let default_branch = self.resolve_ref_name(repo_path, None).await?;
+
+        let branches: Vec<GitLabBranch> = self
+            .api_request(
+                "GET",
+                &format!(
+                    "{}/repository/branches?per_page=100",
+                    self.project_url(repo_path)
+                ),
+                None,
+            )
+            .await?;
+
+        let divergences = future::join_all(
+            branches
+                .into_iter()
+                .filter(|branch| branch.name != default_branch)
+                .take(limit)
+                .map(|branch| {
+                    let default_branch = &default_branch;
+                    async move {
+                        // The compare endpoint has no counts, each direction lists its commits
+                        let compare_url = |from: &str, to: &str| {
+                            format!(
+                                "{}/repository/compare?from={}&to={}",
+                                self.project_url(repo_path),
+                                urlencoding::encode(from),
+                                urlencoding::encode(to)
+                            )
+                        };
+                        let ahead_url = compare_url(default_branch, &branch.name);
+                        let behind_url = compare_url(&branch.name, default_branch);
+
+                        let (ahead, behind): (GitLabComparison, GitLabComparison) = futures::try_join!(
+                            self.api_request("GET", &ahead_url, None),
+                            self.api_request("GET", &behind_url, None)
+                        )?;
+
+                        Ok::<_, anyhow::Error>(BranchDivergence {
+                            branch: branch.name,
+                            ahead: ahead.commits.len(),
+                            behind: behind.commits.len(),
+                            last_commit_date: branch.commit.map(|commit| commit.committed_date),
+                        })
+                    }
+                }),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        Ok((default_branch, divergences))
+    }
+
+    async fn clone_remote(&self, repo_path: &str) -> Result<CloneRemote> {
+        let project_path = repo_path.split("/-/").next().unwrap_or(repo_path);
+
+        Ok(CloneRemote {
+            url: format!("{}/{}.git", self.host, project_path.trim_matches('/')),
+            credentials: self.token.as_ref().map(GitLabToken::clone_credentials),
+        })
+    }
 }
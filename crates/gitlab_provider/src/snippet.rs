@@ -0,0 +1,359 @@
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use git_provider::{
+    CloneRemote, GitProvider, GitRef, RepoItem, RepoItemType, RepoNode, RepoSearchResult,
+    RepositoryInfo, TreeOptions, create_tree_structure, encoding::encode_component,
+    ignore_patterns::DEFAULT_IGNORE_PATTERNS, patterns::PathFilter,
+};
+use http_client::{HttpClient, Request, RequestBuilderExt, ResponseAsyncBodyExt};
+
+use crate::{GitLabProvider, GitLabToken};
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabSnippet {
+    id: u64,
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    /// The only file of snippets created before multi-file snippets existed
+    #[serde(default)]
+    file_name: Option<String>,
+    #[serde(default)]
+    files: Vec<GitLabSnippetFile>,
+    web_url: String,
+    #[serde(default)]
+    http_url_to_repo: Option<String>,
+    created_at: String,
+    #[serde(default)]
+    updated_at: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GitLabSnippetFile {
+    path: String,
+    raw_url: String,
+}
+
+impl GitLabSnippet {
+    /// Paths of the snippet's files, sorted
+    fn paths(&self) -> Vec<&str> {
+        let mut paths: Vec<&str> = match &self.files[..] {
+            [] => self.file_name.as_deref().into_iter().collect(),
+            files => files.iter().map(|file| file.path.as_str()).collect(),
+        };
+        paths.sort_unstable();
+        paths
+    }
+
+    /// The branch the raw URLs of the snippet's files point at, e.g. "main" in
+    /// ".../-/snippets/42/raw/main/notes.md"
+    fn default_ref(&self) -> Option<&str> {
+        self.files.iter().find_map(|file| {
+            let url = file.raw_url.strip_suffix(&format!("/{}", file.path))?;
+            url.rsplit_once("/raw/").map(|(_, ref_name)| ref_name)
+        })
+    }
+}
+
+/// Where a snippet lives: in a project, or on its author's personal page
+enum SnippetPath<'a> {
+    Project { project: &'a str, id: &'a str },
+    Personal { id: &'a str },
+}
+
+/// Provider for GitLab snippets, identified as "gitlab-snippet:group/project/123" for project
+/// snippets and "gitlab-snippet:123" for personal ones. A snippet is a flat list of files.
+pub struct SnippetProvider {
+    gitlab: GitLabProvider,
+}
+
+impl SnippetProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        Self {
+            gitlab: GitLabProvider::new(http_client),
+        }
+    }
+
+    /// Split a snippet path into the project, if any, and the snippet id
+    fn parse_snippet_path<'a>(&self, repo_path: &'a str) -> Result<SnippetPath<'a>> {
+        let repo_path = repo_path.trim_matches('/');
+        let (project, id) = match repo_path.rsplit_once('/') {
+            Some((project, id)) => (Some(project), id),
+            None => (None, repo_path),
+        };
+
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(anyhow!(
+                "Invalid snippet path '{}'. Expected 'group/project/snippet_id' or 'snippet_id'",
+                repo_path
+            ));
+        }
+
+        Ok(match project {
+            Some(project) => SnippetPath::Project { project, id },
+            None => SnippetPath::Personal { id },
+        })
+    }
+
+    /// API URL of a snippet
+    fn snippet_url(&self, repo_path: &str) -> Result<String> {
+        Ok(match self.parse_snippet_path(repo_path)? {
+            SnippetPath::Project { project, id } => format!(
+                "{}/projects/{}/snippets/{}",
+                self.gitlab.api_base(),
+                encode_component(project),
+                id
+            ),
+            SnippetPath::Personal { id } => {
+                format!("{}/snippets/{}", self.gitlab.api_base(), id)
+            }
+        })
+    }
+
+    async fn fetch_snippet(&self, repo_path: &str) -> Result<GitLabSnippet> {
+        self.gitlab
+            .api_request("GET", &self.snippet_url(repo_path)?, None)
+            .await
+    }
+
+    /// The files of a snippet kept by the filter
+    fn filtered_paths<'a>(
+        &self,
+        snippet: &'a GitLabSnippet,
+        options: &TreeOptions,
+    ) -> Vec<&'a str> {
+        let ignore_patterns = match &options.ignore_patterns {
+            Some(ignore_patterns) => ignore_patterns.clone(),
+            None => DEFAULT_IGNORE_PATTERNS
+                .iter()
+                .map(|&s| s.to_string())
+                .collect(),
+        };
+        let filter = PathFilter::from_options(options, &ignore_patterns);
+
+        snippet
+            .paths()
+            .into_iter()
+            .filter(|path| filter.keeps(path))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl GitProvider for SnippetProvider {
+    fn name(&self) -> &str {
+        "gitlab-snippet"
+    }
+
+    fn web_url(&self) -> Option<String> {
+        Some(self.gitlab.host().to_string())
+    }
+
+    fn is_anonymous(&self) -> bool {
+        self.gitlab.is_anonymous()
+    }
+
+    async fn get_tree_structure(
+        &self,
+        repo_path: &str,
+        _git_ref: Option<GitRef>,
+        options: TreeOptions,
+    ) -> Result<String> {
+        let snippet = self.fetch_snippet(repo_path).await?;
+
+        // The API does not report file sizes
+        let children: Vec<RepoNode> = self
+            .filtered_paths(&snippet, &options)
+            .into_iter()
+            .map(|path| RepoNode {
+                name: path.to_string(),
+                node_type: RepoItemType::File,
+                size: 0,
+                children: vec![],
+                file_count: 1,
+                dir_count: 0,
+                description: None,
+            })
+            .collect();
+
+        let tree_node = RepoNode {
+            name: snippet.title.clone(),
+            node_type: RepoItemType::Directory,
+            size: 0,
+            file_count: children.len(),
+            dir_count: 1,
+            children,
+            description: options
+                .describe_directories
+                .then(|| snippet.description.clone())
+                .flatten()
+                .filter(|description| !description.is_empty()),
+        };
+
+        Ok(create_tree_structure(&tree_node, "", true))
+    }
+
+    async fn get_file_content(
+        &self,
+        repo_path: &str,
+        file_path: &str,
+        git_ref: Option<GitRef>,
+    ) -> Result<String> {
+        let file_path = file_path.trim_start_matches('/');
+        let snippet_url = self.snippet_url(repo_path)?;
+
+        let ref_name = match git_ref {
+            Some(GitRef::Branch(name)) | Some(GitRef::Tag(name)) | Some(GitRef::Commit(name)) => {
+                name
+            }
+            Some(GitRef::Default) | None => {
+                let snippet = self.fetch_snippet(repo_path).await?;
+                if !snippet.paths().contains(&file_path) {
+                    return Err(anyhow!(
+                        "File {} not found in snippet {}",
+                        file_path,
+                        snippet.id
+                    ));
+                }
+                snippet.default_ref().unwrap_or("main").to_string()
+            }
+        };
+
+        let response = self
+            .gitlab
+            .http_client
+            .send(
+                Request::builder()
+                    .uri(format!(
+                        "{}/files/{}/{}/raw",
+                        snippet_url,
+                        encode_component(&ref_name),
+                        encode_component(file_path)
+                    ))
+                    .method("GET")
+                    .headers(self.gitlab.api_headers()?)
+                    .end()?,
+            )
+            .await?;
+
+        let status = response.status();
+        if status.as_u16() == 404 {
+            return Err(anyhow!("File not found: {}", file_path));
+        } else if !status.is_success() {
+            return Err(anyhow!("GitLab error ({}) reading {}", status, file_path));
+        }
+        Ok(response.text().await?)
+    }
+
+    async fn find_repositories(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<RepoSearchResult>> {
+        // Snippets cannot be searched, the query names the project to list, or is empty for
+        // the snippets of the token's owner
+        let project = query.trim().trim_matches('/');
+        let url = if project.is_empty() {
+            format!("{}/snippets", self.gitlab.api_base())
+        } else if project.contains('/') && !project.contains(char::is_whitespace) {
+            format!(
+                "{}/projects/{}/snippets",
+                self.gitlab.api_base(),
+                encode_component(project)
+            )
+        } else {
+            return Err(anyhow!(
+                "Snippets are listed by project, pass a project path as the query, or an empty query for your own snippets"
+            ));
+        };
+
+        let snippets: Vec<GitLabSnippet> = self
+            .gitlab
+            .api_request(
+                "GET",
+                &format!("{}?per_page={}", url, limit.unwrap_or(20).clamp(1, 100)),
+                None,
+            )
+            .await?;
+
+        Ok(snippets
+            .into_iter()
+            .map(|snippet| RepoSearchResult {
+                provider: self.name().to_string(),
+                full_name: match project {
+                    "" => snippet.id.to_string(),
+                    project => format!("{}/{}", project, snippet.id),
+                },
+                description: Some(snippet.title).filter(|title| !title.is_empty()),
+                stargazers_count: 0,
+            })
+            .collect())
+    }
+
+    async fn list_files(
+        &self,
+        repo_path: &str,
+        _git_ref: Option<GitRef>,
+        options: &TreeOptions,
+    ) -> Result<Vec<RepoItem>> {
+        let snippet = self.fetch_snippet(repo_path).await?;
+
+        Ok(self
+            .filtered_paths(&snippet, options)
+            .into_iter()
+            .map(|path| RepoItem {
+                name: path.rsplit('/').next().unwrap_or(path).to_string(),
+                path: path.to_string(),
+                item_type: RepoItemType::File,
+                size: None,
+                sha: None,
+            })
+            .collect())
+    }
+
+    async fn get_repository_info(&self, repo_path: &str) -> Result<RepositoryInfo> {
+        let snippet = self.fetch_snippet(repo_path).await?;
+        let default_branch = snippet.default_ref().map(String::from);
+
+        Ok(RepositoryInfo {
+            full_name: repo_path.trim_matches('/').to_string(),
+            description: Some(match snippet.description.filter(|d| !d.is_empty()) {
+                Some(description) => format!("{}: {}", snippet.title, description),
+                None => snippet.title,
+            }),
+            url: snippet.web_url,
+            default_branch,
+            stars: 0,
+            forks: 0,
+            open_issues: None,
+            language: None,
+            license: None,
+            topics: vec![],
+            archived: false,
+            size_kb: None,
+            is_template: false,
+            generated_from: None,
+            forked_from: None,
+            mirror: None,
+            created_at: snippet.created_at,
+            last_activity_at: snippet.updated_at,
+        })
+    }
+
+    async fn clone_remote(&self, repo_path: &str) -> Result<CloneRemote> {
+        let snippet = self.fetch_snippet(repo_path).await?;
+
+        Ok(CloneRemote {
+            url: snippet
+                .http_url_to_repo
+                .ok_or_else(|| anyhow!("Snippet {} has no repository", snippet.id))?,
+            credentials: self
+                .gitlab
+                .token
+                .as_ref()
+                .map(GitLabToken::clone_credentials),
+        })
+    }
+}
@@ -0,0 +1,681 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    env,
+    sync::Arc,
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use git_provider::{
+    CloneRemote, CommitInfo, GitProvider, GitRef, MAX_README_SIZE, RepoItem, RepoItemType,
+    RepoNode, RepoSearchResult, RepositoryInfo, TreeOptions, depth_limited_directory,
+    encoding::{encode_component, encode_path},
+    ignore_patterns::DEFAULT_IGNORE_PATTERNS,
+    is_readme,
+    patterns::PathFilter,
+    readme_summary, render_tree,
+};
+use http_client::{HttpClient, Request, RequestBuilderExt, ResponseAsyncBodyExt, http::HeaderMap};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+const MAX_FILES: usize = 500;
+const HOST: &str = "https://huggingface.co";
+/// Pages of a recursive tree listing followed at most
+const MAX_TREE_PAGES: usize = 20;
+
+/// Weights, checkpoints and dataset shards, skipped along with the default ignore patterns
+const WEIGHT_PATTERNS: &[&str] = &[
+    "*.safetensors",
+    "*.bin",
+    "*.pt",
+    "*.pth",
+    "*.ckpt",
+    "*.gguf",
+    "*.ggml",
+    "*.onnx",
+    "*.onnx_data",
+    "*.h5",
+    "*.msgpack",
+    "*.tflite",
+    "*.pb",
+    "*.ot",
+    "*.npz",
+    "*.mlmodel",
+    "*.parquet",
+    "*.arrow",
+];
+
+/// The kinds of repositories the Hub hosts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RepoKind {
+    Model,
+    Dataset,
+    Space,
+}
+
+impl RepoKind {
+    /// Segment of the API paths, e.g. "/api/models/..."
+    fn api_segment(&self) -> &'static str {
+        match self {
+            Self::Model => "models",
+            Self::Dataset => "datasets",
+            Self::Space => "spaces",
+        }
+    }
+
+    /// Prefix of the web paths, models having none
+    fn web_prefix(&self) -> &'static str {
+        match self {
+            Self::Model => "",
+            Self::Dataset => "datasets/",
+            Self::Space => "spaces/",
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HubRepository {
+    id: String,
+    #[serde(default)]
+    likes: u64,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    pipeline_tag: Option<String>,
+    #[serde(default)]
+    library_name: Option<String>,
+    #[serde(default)]
+    card_data: Option<Value>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
+    #[serde(default)]
+    used_storage: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HubSearchItem {
+    id: String,
+    #[serde(default)]
+    likes: usize,
+    #[serde(default)]
+    pipeline_tag: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HubTreeEntry {
+    #[serde(rename = "type")]
+    entry_type: String,
+    path: String,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    oid: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HubCommit {
+    id: String,
+    title: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    authors: Vec<HubAuthor>,
+    date: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HubAuthor {
+    user: String,
+}
+
+/// Provider for model, dataset and Space repositories of the Hugging Face Hub, identified as
+/// "org/model", "datasets/org/name" or "spaces/org/name"
+pub struct HuggingFaceProvider {
+    http_client: Arc<dyn HttpClient>,
+    hf_token: Option<String>,
+}
+
+impl HuggingFaceProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        Self {
+            http_client,
+            hf_token: env::var("HF_TOKEN").ok(),
+        }
+    }
+
+    /// Split a repository path into its kind, its id and the revision of a "/tree/..." suffix
+    fn parse_repo_path(&self, repo_path: &str) -> Result<(RepoKind, String, Option<String>)> {
+        let mut parts: Vec<&str> = repo_path.trim_matches('/').split('/').collect();
+        let kind = match parts.first() {
+            Some(&"datasets") => RepoKind::Dataset,
+            Some(&"spaces") => RepoKind::Space,
+            _ => RepoKind::Model,
+        };
+        if kind != RepoKind::Model {
+            parts.remove(0);
+        }
+
+        // Ids are "name" for some older models and datasets, "org/name" otherwise
+        let id_len = match parts.get(1) {
+            Some(&"tree") | Some(&"blob") | None => 1,
+            Some(_) => 2,
+        };
+        if parts.len() < id_len || parts[..id_len].iter().any(|part| part.is_empty()) {
+            return Err(anyhow!(
+                "Invalid repository path '{}', expected 'org/name', 'datasets/org/name' or 'spaces/org/name'",
+                repo_path
+            ));
+        }
+
+        let revision = match parts.get(id_len..id_len + 2) {
+            Some(["tree", revision]) => Some(revision.to_string()),
+            _ => None,
+        };
+
+        Ok((kind, parts[..id_len].join("/"), revision))
+    }
+
+    /// Base API URL of a repository
+    fn repo_url(&self, kind: RepoKind, id: &str) -> String {
+        format!("{}/api/{}/{}", HOST, kind.api_segment(), encode_path(id))
+    }
+
+    fn api_headers(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.insert("User-Agent", "GitIngest-MCP".parse()?);
+
+        if let Some(hf_token) = &self.hf_token {
+            headers.insert("Authorization", format!("Bearer {}", hf_token).parse()?);
+        }
+
+        Ok(headers)
+    }
+
+    /// Send an API request, returning the URL of the next page from the Link header along
+    /// with the response
+    async fn api_page<T: DeserializeOwned>(&self, url: &str) -> Result<(T, Option<String>)> {
+        let request = Request::builder()
+            .uri(url)
+            .method("GET")
+            .headers(self.api_headers()?)
+            .end()?;
+
+        let response = self.http_client.send(request).await?;
+        let status = response.status();
+        let next = response
+            .headers()
+            .get("link")
+            .and_then(|link| link.to_str().ok())
+            .and_then(next_page_url);
+        let response_text = response.text().await?;
+
+        if !status.is_success() {
+            let message = serde_json::from_str::<Value>(&response_text)
+                .ok()
+                .and_then(|v| v.get("error").and_then(|m| m.as_str()).map(String::from))
+                .unwrap_or(response_text);
+
+            return Err(anyhow!("Hugging Face API error ({}): {}", status, message));
+        }
+
+        Ok((serde_json::from_str(&response_text)?, next))
+    }
+
+    async fn api_request<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        Ok(self.api_page(url).await?.0)
+    }
+
+    /// The revision a reference names, "main" being the default branch of every repository
+    fn revision(&self, git_ref: Option<GitRef>) -> String {
+        match git_ref {
+            Some(GitRef::Branch(name)) | Some(GitRef::Tag(name)) | Some(GitRef::Commit(name)) => {
+                name
+            }
+            Some(GitRef::Default) | None => "main".to_string(),
+        }
+    }
+
+    /// Every file and directory of a revision
+    async fn fetch_tree(&self, kind: RepoKind, id: &str, revision: &str) -> Result<Vec<RepoItem>> {
+        let mut url = Some(format!(
+            "{}/tree/{}?recursive=true",
+            self.repo_url(kind, id),
+            encode_component(revision)
+        ));
+
+        let mut items = Vec::new();
+        for _ in 0..MAX_TREE_PAGES {
+            let Some(page_url) = url.take() else {
+                break;
+            };
+            let (entries, next): (Vec<HubTreeEntry>, _) = self.api_page(&page_url).await?;
+
+            items.extend(entries.into_iter().map(|entry| {
+                RepoItem {
+                    name: entry
+                        .path
+                        .rsplit('/')
+                        .next()
+                        .unwrap_or(&entry.path)
+                        .to_string(),
+                    item_type: match entry.entry_type.as_str() {
+                        "directory" => RepoItemType::Directory,
+                        _ => RepoItemType::File,
+                    },
+                    path: entry.path,
+                    size: entry.size,
+                    sha: entry.oid,
+                }
+            }));
+            url = next;
+        }
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(items)
+    }
+
+    async fn fetch_file_content(
+        &self,
+        kind: RepoKind,
+        id: &str,
+        file_path: &str,
+        revision: &str,
+    ) -> Result<String> {
+        // "resolve" serves the content of files stored with LFS too, "raw" only their pointers
+        let url = format!(
+            "{}/{}{}/resolve/{}/{}",
+            HOST,
+            kind.web_prefix(),
+            encode_path(id),
+            encode_component(revision),
+            encode_path(file_path.trim_start_matches('/'))
+        );
+
+        let response = self
+            .http_client
+            .send(
+                Request::builder()
+                    .uri(&url)
+                    .method("GET")
+                    .headers(self.api_headers()?)
+                    .end()?,
+            )
+            .await?;
+
+        let status = response.status();
+        if status.as_u16() == 404 {
+            return Err(anyhow!("File not found: {}", file_path));
+        } else if !status.is_success() {
+            return Err(anyhow!("Failed to fetch file content. Status: {}", status));
+        }
+
+        response.text().await
+    }
+
+    fn ignore_patterns(&self, options: &TreeOptions) -> Vec<String> {
+        match &options.ignore_patterns {
+            Some(ignore_patterns) => ignore_patterns.clone(),
+            None => DEFAULT_IGNORE_PATTERNS
+                .iter()
+                .chain(WEIGHT_PATTERNS)
+                .map(|&s| s.to_string())
+                .collect(),
+        }
+    }
+
+    /// The entries kept by the filter, without the contents of dropped directories
+    fn filter_entries(&self, entries: Vec<RepoItem>, options: &TreeOptions) -> Vec<RepoItem> {
+        let ignore_patterns = self.ignore_patterns(options);
+        let filter = PathFilter::from_options(options, &ignore_patterns);
+
+        // Entries are sorted by path, so directories come before their contents
+        let mut dropped: HashSet<String> = HashSet::new();
+        entries
+            .into_iter()
+            .filter(|entry| {
+                let parent = entry.path.rsplit_once('/').map(|(parent, _)| parent);
+                if parent.is_some_and(|parent| dropped.contains(parent)) {
+                    if entry.item_type == RepoItemType::Directory {
+                        dropped.insert(entry.path.clone());
+                    }
+                    return false;
+                }
+
+                match entry.item_type {
+                    RepoItemType::Directory if !filter.keeps_directory(&entry.path) => {
+                        dropped.insert(entry.path.clone());
+                        false
+                    }
+                    RepoItemType::Directory => true,
+                    RepoItemType::File => filter.keeps(&entry.path),
+                }
+            })
+            .collect()
+    }
+}
+
+/// The URL of the `rel="next"` link of a Link header
+fn next_page_url(link: &str) -> Option<String> {
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        params.contains("rel=\"next\"").then(|| {
+            url.trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string()
+        })
+    })
+}
+
+/// Nest the listed entries below the directory at `path`
+fn build_tree(
+    path: &str,
+    children_by_parent: &BTreeMap<&str, Vec<&RepoItem>>,
+    descriptions: &HashMap<&str, String>,
+    depth: usize,
+    max_depth: usize,
+) -> RepoNode {
+    let mut children = Vec::new();
+    let mut file_count = 0;
+    let mut dir_count = 1; // Count self
+    let mut total_size = 0;
+
+    for entry in children_by_parent.get(path).into_iter().flatten() {
+        match entry.item_type {
+            RepoItemType::File => {
+                // Check file limit
+                if file_count >= MAX_FILES {
+                    continue;
+                }
+
+                let size = entry.size.unwrap_or(0);
+                total_size += size;
+                file_count += 1;
+                children.push(RepoNode {
+                    name: entry.name.clone(),
+                    node_type: RepoItemType::File,
+                    size,
+                    children: vec![],
+                    file_count: 1,
+                    dir_count: 0,
+                    description: None,
+                });
+            }
+            RepoItemType::Directory if depth >= max_depth => {
+                dir_count += 1;
+                children.push(depth_limited_directory(&entry.name));
+            }
+            RepoItemType::Directory => {
+                let mut child = build_tree(
+                    &entry.path,
+                    children_by_parent,
+                    descriptions,
+                    depth + 1,
+                    max_depth,
+                );
+                child.name = entry.name.clone();
+                file_count += child.file_count;
+                dir_count += child.dir_count;
+                total_size += child.size;
+                children.push(child);
+            }
+        }
+    }
+
+    // Sort children: directories first, then files, both alphabetically
+    children.sort_by(|a, b| match (a.node_type, b.node_type) {
+        (RepoItemType::Directory, RepoItemType::File) => std::cmp::Ordering::Less,
+        (RepoItemType::File, RepoItemType::Directory) => std::cmp::Ordering::Greater,
+        _ => a.name.cmp(&b.name),
+    });
+
+    RepoNode {
+        name: "root".to_string(),
+        node_type: RepoItemType::Directory,
+        size: total_size,
+        children,
+        file_count,
+        dir_count,
+        description: descriptions.get(path).cloned(),
+    }
+}
+
+#[async_trait]
+impl GitProvider for HuggingFaceProvider {
+    fn name(&self) -> &str {
+        "huggingface"
+    }
+
+    fn web_url(&self) -> Option<String> {
+        Some(HOST.to_string())
+    }
+
+    fn is_anonymous(&self) -> bool {
+        self.hf_token.is_none()
+    }
+
+    async fn get_tree_structure(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: TreeOptions,
+    ) -> Result<String> {
+        let (kind, id, path_revision) = self.parse_repo_path(repo_path)?;
+        let revision = self.revision(git_ref.or(path_revision.map(GitRef::Branch)));
+        let entries = self.filter_entries(self.fetch_tree(kind, &id, &revision).await?, &options);
+
+        let mut children_by_parent: BTreeMap<&str, Vec<&RepoItem>> = BTreeMap::new();
+        for entry in &entries {
+            let parent = entry.path.rsplit_once('/').map_or("", |(parent, _)| parent);
+            children_by_parent.entry(parent).or_default().push(entry);
+        }
+
+        let subtree = options.subtree();
+        if !subtree.is_empty() && !children_by_parent.contains_key(subtree) {
+            return Err(anyhow!("No directory '{}' in {}", subtree, repo_path));
+        }
+
+        // Model and dataset cards are READMEs, one per directory at most
+        let mut descriptions = HashMap::new();
+        if options.describe_directories {
+            for (parent, children) in &children_by_parent {
+                let Some(readme) = children.iter().find(|entry| {
+                    entry.item_type == RepoItemType::File
+                        && is_readme(&entry.name)
+                        && entry.size.unwrap_or(0) <= MAX_README_SIZE
+                }) else {
+                    continue;
+                };
+                if let Some(summary) = self
+                    .fetch_file_content(kind, &id, &readme.path, &revision)
+                    .await
+                    .ok()
+                    .and_then(|content| readme_summary(&content))
+                {
+                    descriptions.insert(*parent, summary);
+                }
+            }
+        }
+
+        let mut tree_node = build_tree(
+            subtree,
+            &children_by_parent,
+            &descriptions,
+            0,
+            options.max_depth(),
+        );
+        tree_node.name = options.root_name(id.rsplit('/').next().unwrap_or(&id));
+
+        Ok(render_tree(&tree_node, &options))
+    }
+
+    async fn get_file_content(
+        &self,
+        repo_path: &str,
+        file_path: &str,
+        git_ref: Option<GitRef>,
+    ) -> Result<String> {
+        let (kind, id, path_revision) = self.parse_repo_path(repo_path)?;
+        let revision = self.revision(git_ref.or(path_revision.map(GitRef::Branch)));
+
+        self.fetch_file_content(kind, &id, file_path, &revision)
+            .await
+    }
+
+    async fn find_repositories(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<RepoSearchResult>> {
+        if query.trim().is_empty() {
+            return Err(anyhow!("Empty search query is not allowed"));
+        }
+
+        // Models are searched unless the query asks for datasets or Spaces, like a path does
+        let (kind, query) = match query.trim().split_once(':') {
+            Some(("datasets", query)) => (RepoKind::Dataset, query),
+            Some(("spaces", query)) => (RepoKind::Space, query),
+            _ => (RepoKind::Model, query.trim()),
+        };
+
+        let items: Vec<HubSearchItem> = self
+            .api_request(&format!(
+                "{}/api/{}?search={}&sort=likes&direction=-1&limit={}",
+                HOST,
+                kind.api_segment(),
+                encode_component(query),
+                limit.unwrap_or(20).clamp(1, 100)
+            ))
+            .await?;
+
+        Ok(items
+            .into_iter()
+            .map(|item| RepoSearchResult {
+                provider: self.name().to_string(),
+                full_name: format!("{}{}", kind.web_prefix(), item.id),
+                description: item.pipeline_tag,
+                stargazers_count: item.likes,
+            })
+            .collect())
+    }
+
+    async fn list_files(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: &TreeOptions,
+    ) -> Result<Vec<RepoItem>> {
+        let (kind, id, path_revision) = self.parse_repo_path(repo_path)?;
+        let revision = self.revision(git_ref.or(path_revision.map(GitRef::Branch)));
+
+        Ok(self
+            .filter_entries(self.fetch_tree(kind, &id, &revision).await?, options)
+            .into_iter()
+            .filter(|entry| entry.item_type == RepoItemType::File)
+            .collect())
+    }
+
+    async fn get_repository_info(&self, repo_path: &str) -> Result<RepositoryInfo> {
+        let (kind, id, _) = self.parse_repo_path(repo_path)?;
+        let info: HubRepository = self.api_request(&self.repo_url(kind, &id)).await?;
+
+        // The card's metadata holds the license, as a name or a list of names
+        let license = info
+            .card_data
+            .as_ref()
+            .and_then(|card| card.get("license"))
+            .and_then(|license| match license {
+                Value::String(license) => Some(license.clone()),
+                Value::Array(licenses) => {
+                    licenses.first().and_then(|l| l.as_str()).map(String::from)
+                }
+                _ => None,
+            });
+        let description = match (&info.pipeline_tag, &info.library_name) {
+            (Some(task), Some(library)) => Some(format!("{} ({})", task, library)),
+            (Some(task), None) => Some(task.clone()),
+            (None, library) => library.clone(),
+        };
+
+        Ok(RepositoryInfo {
+            url: format!("{}/{}{}", HOST, kind.web_prefix(), info.id),
+            full_name: format!("{}{}", kind.web_prefix(), info.id),
+            description,
+            default_branch: Some("main".to_string()),
+            stars: info.likes,
+            forks: 0,
+            open_issues: None,
+            language: None,
+            license,
+            topics: info.tags,
+            archived: false,
+            size_kb: info.used_storage.map(|bytes| bytes.div_ceil(1024)),
+            is_template: false,
+            generated_from: None,
+            forked_from: None,
+            mirror: None,
+            created_at: info.created_at.unwrap_or_default(),
+            last_activity_at: info.last_modified,
+        })
+    }
+
+    async fn list_commits(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        path: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        if path.is_some() {
+            return Err(anyhow!("Hugging Face commits cannot be filtered by path"));
+        }
+        let (kind, id, path_revision) = self.parse_repo_path(repo_path)?;
+        let revision = self.revision(git_ref.or(path_revision.map(GitRef::Branch)));
+
+        let commits: Vec<HubCommit> = self
+            .api_request(&format!(
+                "{}/commits/{}",
+                self.repo_url(kind, &id),
+                encode_component(&revision)
+            ))
+            .await?;
+
+        Ok(commits
+            .into_iter()
+            .take(limit)
+            .map(|commit| CommitInfo {
+                sha: commit.id,
+                message: match commit.message.trim() {
+                    "" => commit.title,
+                    body => format!("{}\n\n{}", commit.title, body),
+                },
+                author: commit
+                    .authors
+                    .into_iter()
+                    .next()
+                    .map(|author| author.user)
+                    .unwrap_or_else(|| "unknown".to_string()),
+                author_email: None,
+                date: commit.date,
+                verification: None,
+            })
+            .collect())
+    }
+
+    async fn clone_remote(&self, repo_path: &str) -> Result<CloneRemote> {
+        let (kind, id, _) = self.parse_repo_path(repo_path)?;
+
+        Ok(CloneRemote {
+            url: format!("{}/{}{}", HOST, kind.web_prefix(), id),
+            // Access tokens authenticate git over HTTPS with any user name
+            credentials: self
+                .hf_token
+                .clone()
+                .map(|token| ("hf_user".to_string(), token)),
+        })
+    }
+}
@@ -0,0 +1,454 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use git_provider::{
+    GitProvider, GitRef, MAX_README_SIZE, RepoItem, RepoItemType, RepoNode, RepoSearchResult,
+    TreeOptions, depth_limited_directory, ignore_patterns::DEFAULT_IGNORE_PATTERNS, is_readme,
+    patterns::PathFilter, readme_summary, render_tree,
+};
+use ignore::WalkBuilder;
+
+const MAX_FILES: usize = 500;
+
+/// An entry of a working copy, relative to its root
+struct Entry {
+    path: String,
+    item_type: RepoItemType,
+    size: u64,
+}
+
+/// Provider reading working copies from the local filesystem, uncommitted changes included,
+/// and skipping what their .gitignore files exclude
+pub struct LocalProvider {
+    /// Directories repositories must lie under, none being readable when empty
+    allowed_roots: Vec<PathBuf>,
+}
+
+impl LocalProvider {
+    /// Restricts repositories to the directories listed in GITINGEST_LOCAL_ROOTS, separated
+    /// like PATH entries. No directory is readable until it is set
+    pub fn new() -> Self {
+        let roots = env::var_os("GITINGEST_LOCAL_ROOTS")
+            .map(|roots| env::split_paths(&roots).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        Self::with_roots(roots)
+    }
+
+    /// Restricts repositories to the given directories, the ones missing being left out
+    pub fn with_roots(roots: impl IntoIterator<Item = PathBuf>) -> Self {
+        let allowed_roots = roots
+            .into_iter()
+            .filter(|root| !root.as_os_str().is_empty())
+            .filter_map(|root| root.canonicalize().ok())
+            .collect();
+
+        Self { allowed_roots }
+    }
+
+    /// The directory of a repository path, checked to exist and to be allowed
+    fn resolve_root(&self, repo_path: &str) -> Result<PathBuf> {
+        // The server could otherwise read any file it has access to, e.g. SSH keys
+        if self.allowed_roots.is_empty() {
+            return Err(anyhow!(
+                "Local repositories are disabled, list the directories they may be read from in GITINGEST_LOCAL_ROOTS"
+            ));
+        }
+
+        let root = Path::new(repo_path)
+            .canonicalize()
+            .map_err(|e| anyhow!("Cannot open {}: {}", repo_path, e))?;
+
+        if !root.is_dir() {
+            return Err(anyhow!("{} is not a directory", repo_path));
+        }
+
+        if !self
+            .allowed_roots
+            .iter()
+            .any(|allowed| root.starts_with(allowed))
+        {
+            return Err(anyhow!(
+                "{} is outside of the directories allowed by GITINGEST_LOCAL_ROOTS",
+                repo_path
+            ));
+        }
+
+        Ok(root)
+    }
+
+    /// Working copies only have one state, so references other than the default are refused
+    fn check_ref(&self, git_ref: Option<&GitRef>) -> Result<()> {
+        match git_ref {
+            None | Some(GitRef::Default) => Ok(()),
+            Some(_) => Err(anyhow!(
+                "Local repositories are read from the working copy, git references are not supported"
+            )),
+        }
+    }
+
+    /// Every file and directory kept by the filter and the .gitignore files, sorted by path
+    fn walk(&self, root: &Path, options: &TreeOptions) -> Result<Vec<Entry>> {
+        let ignore_patterns = match &options.ignore_patterns {
+            Some(ignore_patterns) => ignore_patterns.clone(),
+            None => DEFAULT_IGNORE_PATTERNS
+                .iter()
+                .map(|&s| s.to_string())
+                .collect(),
+        };
+        let filter = PathFilter::from_options(options, &ignore_patterns);
+
+        // Dropped directories are not descended into
+        let walk_root = root.to_path_buf();
+        let walk_filter = filter.clone();
+        let walker = WalkBuilder::new(root)
+            .hidden(false)
+            .require_git(false)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .filter_entry(move |entry| {
+                if entry.file_name() == ".git" {
+                    return false;
+                }
+
+                match (
+                    entry.file_type().is_some_and(|t| t.is_dir()),
+                    relative_path(&walk_root, entry.path()),
+                ) {
+                    (true, Some(path)) if !path.is_empty() => walk_filter.keeps_directory(&path),
+                    _ => true,
+                }
+            })
+            .build();
+
+        let mut entries = Vec::new();
+        for result in walker {
+            let entry = result?;
+            let Some(path) = relative_path(root, entry.path()).filter(|p| !p.is_empty()) else {
+                continue;
+            };
+
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            if is_dir {
+                entries.push(Entry {
+                    path,
+                    item_type: RepoItemType::Directory,
+                    size: 0,
+                });
+            } else if filter.keeps(&path) {
+                entries.push(Entry {
+                    path,
+                    item_type: RepoItemType::File,
+                    size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                });
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+impl Default for LocalProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The slash-separated path of `path` below `root`
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+
+    Some(
+        relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
+}
+
+/// Nest the walked entries below the directory at `path`
+fn build_tree(
+    root: &Path,
+    path: &str,
+    children_by_parent: &BTreeMap<&str, Vec<&Entry>>,
+    options: &TreeOptions,
+    depth: usize,
+) -> RepoNode {
+    let mut children = Vec::new();
+    let mut file_count = 0;
+    let mut dir_count = 1; // Count self
+    let mut total_size = 0;
+    let mut description = None;
+
+    for entry in children_by_parent.get(path).into_iter().flatten() {
+        let name = entry.path.rsplit('/').next().unwrap_or(&entry.path);
+        match entry.item_type {
+            RepoItemType::File => {
+                // Check file limit
+                if file_count >= MAX_FILES {
+                    continue;
+                }
+
+                if options.describe_directories
+                    && description.is_none()
+                    && is_readme(name)
+                    && entry.size <= MAX_README_SIZE
+                {
+                    description = fs::read_to_string(root.join(&entry.path))
+                        .ok()
+                        .and_then(|content| readme_summary(&content));
+                }
+
+                total_size += entry.size;
+                file_count += 1;
+                children.push(RepoNode {
+                    name: name.to_string(),
+                    node_type: RepoItemType::File,
+                    size: entry.size,
+                    children: vec![],
+                    file_count: 1,
+                    dir_count: 0,
+                    description: None,
+                });
+            }
+            RepoItemType::Directory if depth >= options.max_depth() => {
+                dir_count += 1;
+                children.push(depth_limited_directory(name));
+            }
+            RepoItemType::Directory => {
+                let mut child =
+                    build_tree(root, &entry.path, children_by_parent, options, depth + 1);
+                child.name = name.to_string();
+                file_count += child.file_count;
+                dir_count += child.dir_count;
+                total_size += child.size;
+                children.push(child);
+            }
+        }
+    }
+
+    // Sort children: directories first, then files, both alphabetically
+    children.sort_by(|a, b| match (a.node_type, b.node_type) {
+        (RepoItemType::Directory, RepoItemType::File) => std::cmp::Ordering::Less,
+        (RepoItemType::File, RepoItemType::Directory) => std::cmp::Ordering::Greater,
+        _ => a.name.cmp(&b.name),
+    });
+
+    RepoNode {
+        name: "root".to_string(),
+        node_type: RepoItemType::Directory,
+        size: total_size,
+        children,
+        file_count,
+        dir_count,
+        description,
+    }
+}
+
+#[async_trait]
+impl GitProvider for LocalProvider {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    async fn get_tree_structure(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: TreeOptions,
+    ) -> Result<String> {
+        self.check_ref(git_ref.as_ref())?;
+        let root = self.resolve_root(repo_path)?;
+        let entries = self.walk(&root, &options)?;
+
+        let mut children_by_parent: BTreeMap<&str, Vec<&Entry>> = BTreeMap::new();
+        for entry in &entries {
+            let parent = entry.path.rsplit_once('/').map_or("", |(parent, _)| parent);
+            children_by_parent.entry(parent).or_default().push(entry);
+        }
+
+        let subtree = options.subtree();
+        if !subtree.is_empty() && !children_by_parent.contains_key(subtree) {
+            return Err(anyhow!("No directory '{}' in {}", subtree, repo_path));
+        }
+
+        let mut tree_node = build_tree(&root, subtree, &children_by_parent, &options, 0);
+        let name = root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| repo_path.to_string());
+        tree_node.name = options.root_name(&name);
+
+        Ok(render_tree(&tree_node, &options))
+    }
+
+    async fn get_file_content(
+        &self,
+        repo_path: &str,
+        file_path: &str,
+        git_ref: Option<GitRef>,
+    ) -> Result<String> {
+        self.check_ref(git_ref.as_ref())?;
+        let root = self.resolve_root(repo_path)?;
+
+        // Only plain components, so the path cannot leave the repository
+        let relative = Path::new(file_path.trim_start_matches('/'));
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(anyhow!("Invalid file path: {}", file_path));
+        }
+
+        let path = root.join(relative);
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| anyhow!("Cannot read {}: {}", file_path, e))?;
+        if !canonical.starts_with(&root) {
+            return Err(anyhow!("{} links outside of the repository", file_path));
+        }
+
+        fs::read_to_string(&path).map_err(|e| anyhow!("Cannot read {}: {}", file_path, e))
+    }
+
+    async fn find_repositories(
+        &self,
+        _query: &str,
+        _limit: Option<usize>,
+    ) -> Result<Vec<RepoSearchResult>> {
+        Err(anyhow!(
+            "Searching repositories is not supported by local, pass the path of a working copy"
+        ))
+    }
+
+    async fn list_files(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: &TreeOptions,
+    ) -> Result<Vec<RepoItem>> {
+        self.check_ref(git_ref.as_ref())?;
+        let root = self.resolve_root(repo_path)?;
+
+        let mut files: Vec<RepoItem> = self
+            .walk(&root, options)?
+            .into_iter()
+            .filter(|entry| entry.item_type == RepoItemType::File)
+            .map(|entry| RepoItem {
+                name: entry
+                    .path
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(&entry.path)
+                    .to_string(),
+                path: entry.path,
+                item_type: RepoItemType::File,
+                size: Some(entry.size),
+                // Uncommitted files have no blob yet
+                sha: None,
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A working copy holding a README, inside a directory that also holds a secret
+    fn working_copy(name: &str) -> (PathBuf, PathBuf) {
+        let parent =
+            env::temp_dir().join(format!("gitingest-local-{}-{}", name, std::process::id()));
+        let repo = parent.join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        fs::write(repo.join("README.md"), "# Repo\n").unwrap();
+        fs::write(parent.join("secret"), "token").unwrap();
+
+        (parent, repo)
+    }
+
+    #[tokio::test]
+    async fn paths_are_refused_outside_of_the_allowed_roots() {
+        let (parent, repo) = working_copy("roots");
+        let repo_path = repo.to_string_lossy();
+
+        let unconfigured = LocalProvider::with_roots([]);
+        assert!(
+            unconfigured
+                .get_file_content(&repo_path, "README.md", None)
+                .await
+                .is_err()
+        );
+
+        let provider = LocalProvider::with_roots([repo.clone()]);
+        assert_eq!(
+            provider
+                .get_file_content(&repo_path, "README.md", None)
+                .await
+                .unwrap(),
+            "# Repo\n"
+        );
+        assert!(
+            provider
+                .get_file_content(&parent.to_string_lossy(), "secret", None)
+                .await
+                .is_err()
+        );
+
+        fs::remove_dir_all(parent).unwrap();
+    }
+
+    #[tokio::test]
+    async fn file_paths_cannot_leave_the_repository() {
+        let (parent, repo) = working_copy("traversal");
+        let repo_path = repo.to_string_lossy();
+        let provider = LocalProvider::with_roots([parent.clone()]);
+
+        for file_path in ["../secret", "docs/../../secret", "/../secret"] {
+            assert!(
+                provider
+                    .get_file_content(&repo_path, file_path, None)
+                    .await
+                    .is_err(),
+                "{} was read",
+                file_path
+            );
+        }
+
+        fs::remove_dir_all(parent).unwrap();
+    }
+
+    #[tokio::test]
+    async fn git_references_are_refused() {
+        let (parent, repo) = working_copy("refs");
+        let repo_path = repo.to_string_lossy();
+        let provider = LocalProvider::with_roots([repo.clone()]);
+
+        assert!(
+            provider
+                .get_file_content(
+                    &repo_path,
+                    "README.md",
+                    Some(GitRef::Branch("main".to_string()))
+                )
+                .await
+                .is_err()
+        );
+        assert!(
+            provider
+                .get_file_content(&repo_path, "README.md", Some(GitRef::Default))
+                .await
+                .is_ok()
+        );
+
+        fs::remove_dir_all(parent).unwrap();
+    }
+}
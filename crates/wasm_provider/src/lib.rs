@@ -0,0 +1,432 @@
+//! Providers implemented by WebAssembly components, loaded at runtime so hosts the server has no
+//! built-in provider for can be added without recompiling it. Components implement the
+//! `git-provider-plugin` world of `wit/git-provider.wit`.
+//!
+//! Experimental: plugins only implement the required, read-only methods of `GitProvider`.
+
+use std::{fs, path::Path, sync::Arc};
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use git_provider::{GitProvider, GitRef, RepoSearchResult, TreeOptions};
+use http_client::{AsyncBody, HttpClient, Request, ResponseAsyncBodyExt};
+use wasmtime::{
+    Config, Engine, Store, StoreLimits, StoreLimitsBuilder,
+    component::{Component, Linker},
+};
+
+wasmtime::component::bindgen!({
+    path: "wit",
+    async: true,
+});
+
+use exports::gitingest::provider::provider as plugin;
+use gitingest::provider::http as plugin_http;
+
+/// Instructions a call may run, not counting the time spent waiting on HTTP requests
+const DEFAULT_FUEL: u64 = 1_000_000_000;
+
+/// Instructions run between two yields to the runtime, so a busy plugin does not hold a thread
+const FUEL_YIELD_INTERVAL: u64 = 100_000;
+
+/// Memory a plugin instance may grow to
+const MAX_MEMORY: usize = 256 * 1024 * 1024;
+
+/// What a plugin instance reaches of the server, for a single call
+struct PluginState {
+    http_client: Arc<dyn HttpClient>,
+    token: Option<String>,
+    limits: StoreLimits,
+}
+
+impl PluginState {
+    async fn send(
+        &self,
+        method: String,
+        uri: String,
+        headers: Vec<plugin_http::Header>,
+        body: Vec<u8>,
+    ) -> Result<plugin_http::Response> {
+        let mut request = Request::builder().method(method.as_str()).uri(uri);
+        for header in headers {
+            request = request.header(header.name, header.value);
+        }
+        let response = self
+            .http_client
+            .send(request.body(AsyncBody::from(body))?)
+            .await?;
+
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| plugin_http::Header {
+                name: name.to_string(),
+                value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            })
+            .collect();
+
+        Ok(plugin_http::Response {
+            status,
+            headers,
+            body: response.bytes().await?,
+        })
+    }
+}
+
+impl plugin_http::Host for PluginState {
+    async fn send(
+        &mut self,
+        method: String,
+        uri: String,
+        headers: Vec<plugin_http::Header>,
+        body: Vec<u8>,
+    ) -> Result<plugin_http::Response, String> {
+        PluginState::send(self, method, uri, headers, body)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn token(&mut self) -> Option<String> {
+        self.token.clone()
+    }
+}
+
+/// A provider implemented by a WebAssembly component. Every call runs in a fresh instance,
+/// bounded in instructions and memory
+pub struct WasmProvider {
+    name: String,
+    engine: Engine,
+    plugin: GitProviderPluginPre<PluginState>,
+    http_client: Arc<dyn HttpClient>,
+    token: Option<String>,
+    fuel: u64,
+}
+
+impl WasmProvider {
+    /// Compiles the component at `path`, registered as `name`
+    pub fn load(
+        http_client: Arc<dyn HttpClient>,
+        name: &str,
+        path: &Path,
+        token: Option<String>,
+    ) -> Result<Self> {
+        let component =
+            fs::read(path).with_context(|| format!("Failed to read plugin {}", path.display()))?;
+
+        Self::from_component(http_client, name, &component, token)
+            .with_context(|| format!("Invalid plugin {}", path.display()))
+    }
+
+    fn from_component(
+        http_client: Arc<dyn HttpClient>,
+        name: &str,
+        component: &[u8],
+        token: Option<String>,
+    ) -> Result<Self> {
+        let mut config = Config::new();
+        config.async_support(true).consume_fuel(true);
+        let engine = Engine::new(&config)?;
+
+        let component = Component::new(&engine, component)?;
+        let mut linker = Linker::new(&engine);
+        GitProviderPlugin::add_to_linker(&mut linker, |state: &mut PluginState| state)?;
+        // Checked once here, rather than on every call, that the component fits the interface
+        let plugin = GitProviderPluginPre::new(linker.instantiate_pre(&component)?)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            engine,
+            plugin,
+            http_client,
+            token,
+            fuel: DEFAULT_FUEL,
+        })
+    }
+
+    /// Sets the instructions a call may run before it is stopped
+    pub fn with_fuel(mut self, fuel: u64) -> Self {
+        self.fuel = fuel;
+        self
+    }
+
+    async fn instantiate(&self) -> Result<(Store<PluginState>, GitProviderPlugin)> {
+        let mut store = Store::new(
+            &self.engine,
+            PluginState {
+                http_client: self.http_client.clone(),
+                token: self.token.clone(),
+                limits: StoreLimitsBuilder::new().memory_size(MAX_MEMORY).build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(self.fuel)?;
+        store.fuel_async_yield_interval(Some(FUEL_YIELD_INTERVAL))?;
+
+        let plugin = self.plugin.instantiate_async(&mut store).await?;
+        Ok((store, plugin))
+    }
+
+    /// Reads the outcome of a call, telling the plugin's own errors from its failures
+    fn outcome<T>(&self, outcome: Result<Result<T, String>>) -> Result<T> {
+        outcome
+            .map_err(|e| anyhow!("Plugin {} failed: {}", self.name, e))?
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+fn plugin_ref(git_ref: Option<GitRef>) -> Option<plugin::GitRef> {
+    git_ref.map(|git_ref| match git_ref {
+        GitRef::Default => plugin::GitRef::DefaultBranch,
+        GitRef::Branch(branch) => plugin::GitRef::Branch(branch),
+        GitRef::Tag(tag) => plugin::GitRef::Tag(tag),
+        GitRef::Commit(sha) => plugin::GitRef::Commit(sha),
+    })
+}
+
+#[async_trait]
+impl GitProvider for WasmProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> &str {
+        "wasm"
+    }
+
+    async fn get_tree_structure(
+        &self,
+        repo_path: &str,
+        git_ref: Option<GitRef>,
+        options: TreeOptions,
+    ) -> Result<String> {
+        let options = plugin::TreeOptions {
+            exclude_patterns: options.exclude_patterns,
+            include_patterns: options.include_patterns,
+            ignore_patterns: options.ignore_patterns,
+            case_insensitive: options.case_insensitive,
+            describe_directories: options.describe_directories,
+            max_depth: options
+                .max_depth
+                .map(|depth| depth.try_into().unwrap_or(u32::MAX)),
+            subtree: options.subtree,
+        };
+
+        let (mut store, plugin) = self.instantiate().await?;
+        let outcome = plugin
+            .gitingest_provider_provider()
+            .call_get_tree_structure(
+                &mut store,
+                repo_path,
+                plugin_ref(git_ref).as_ref(),
+                &options,
+            )
+            .await;
+        self.outcome(outcome)
+    }
+
+    async fn get_file_content(
+        &self,
+        repo_path: &str,
+        file_path: &str,
+        git_ref: Option<GitRef>,
+    ) -> Result<String> {
+        let (mut store, plugin) = self.instantiate().await?;
+        let outcome = plugin
+            .gitingest_provider_provider()
+            .call_get_file_content(
+                &mut store,
+                repo_path,
+                file_path,
+                plugin_ref(git_ref).as_ref(),
+            )
+            .await;
+        self.outcome(outcome)
+    }
+
+    async fn find_repositories(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<RepoSearchResult>> {
+        let limit = limit.map(|limit| limit.try_into().unwrap_or(u32::MAX));
+
+        let (mut store, plugin) = self.instantiate().await?;
+        let outcome = plugin
+            .gitingest_provider_provider()
+            .call_find_repositories(&mut store, query, limit)
+            .await;
+
+        Ok(self
+            .outcome(outcome)?
+            .into_iter()
+            .map(|result| RepoSearchResult {
+                provider: self.name.clone(),
+                full_name: result.full_name,
+                description: result.description,
+                stargazers_count: result.stargazers_count.try_into().unwrap_or(usize::MAX),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http_client::Response;
+
+    use super::*;
+
+    /// Answers every request with the URI it was sent to
+    struct EchoClient;
+
+    #[async_trait]
+    impl HttpClient for EchoClient {
+        async fn send(&self, request: Request<AsyncBody>) -> Result<Response<AsyncBody>> {
+            Ok(Response::builder()
+                .status(200)
+                .header("x-echo", "1")
+                .body(AsyncBody::from(request.uri().to_string().into_bytes()))?)
+        }
+    }
+
+    /// A component echoing its arguments back: the file path as the content, the search query
+    /// as an error, and the body fetched from `https://example.com/tree` as the tree. Searches
+    /// with a limit of 0 never return
+    const PLUGIN: &str = r#"
+(component
+  (import "gitingest:provider/http@0.1.0" (instance $http
+    (type $header (record (field "name" string) (field "value" string)))
+    (export "header" (type $header-e (eq $header)))
+    (type $response (record (field "status" u16) (field "headers" (list $header-e)) (field "body" (list u8))))
+    (export "response" (type $response-e (eq $response)))
+    (export "send" (func (param "method" string) (param "uri" string) (param "headers" (list $header-e)) (param "body" (list u8)) (result (result $response-e (error string)))))
+    (export "token" (func (result (option string))))))
+
+  (core module $memory
+    (memory (export "memory") 1)
+    (global $next (mut i32) (i32.const 1024))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $ptr i32)
+      (local.set $ptr
+        (i32.and
+          (i32.add (global.get $next) (i32.sub (local.get 2) (i32.const 1)))
+          (i32.sub (i32.const 0) (local.get 2))))
+      (global.set $next (i32.add (local.get $ptr) (local.get 3)))
+      (local.get $ptr)))
+  (core instance $memory (instantiate $memory))
+  (core func $send (canon lower (func $http "send") (memory $memory "memory") (realloc (func $memory "realloc"))))
+
+  (core module $plugin
+    (import "host" "memory" (memory 1))
+    (import "host" "send" (func $send (param i32 i32 i32 i32 i32 i32 i32 i32 i32)))
+    (data (i32.const 16) "GET")
+    (data (i32.const 32) "https://example.com/tree")
+    (func (export "get-tree-structure") (param i32) (result i32)
+      ;; The response record is at 68, after the result's discriminant, its body at 80
+      (call $send (i32.const 16) (i32.const 3) (i32.const 32) (i32.const 24)
+        (i32.const 0) (i32.const 0) (i32.const 0) (i32.const 0) (i32.const 64))
+      (i32.store (i32.const 132) (i32.load (i32.const 80)))
+      (i32.store (i32.const 136) (i32.load (i32.const 84)))
+      (i32.store8 (i32.const 128) (i32.load8_u (i32.const 64)))
+      (i32.const 128))
+    (func (export "get-file-content") (param i32 i32 i32 i32 i32 i32 i32 i32) (result i32)
+      (i32.store8 (i32.const 80) (i32.const 0))
+      (i32.store (i32.const 84) (local.get 2))
+      (i32.store (i32.const 88) (local.get 3))
+      (i32.const 80))
+    (func (export "find-repositories") (param i32 i32 i32 i32) (result i32)
+      (if (i32.and (local.get 2) (i32.eqz (local.get 3)))
+        (then (loop $forever (br $forever))))
+      (i32.store8 (i32.const 96) (i32.const 1))
+      (i32.store (i32.const 100) (local.get 0))
+      (i32.store (i32.const 104) (local.get 1))
+      (i32.const 96)))
+  (core instance $plugin (instantiate $plugin
+    (with "host" (instance
+      (export "memory" (memory $memory "memory"))
+      (export "send" (func $send))))))
+
+  (type $git-ref (variant (case "default-branch") (case "branch" string) (case "tag" string) (case "commit" string)))
+  (type $tree-options (record
+    (field "exclude-patterns" (list string))
+    (field "include-patterns" (list string))
+    (field "ignore-patterns" (option (list string)))
+    (field "case-insensitive" bool)
+    (field "describe-directories" bool)
+    (field "max-depth" (option u32))
+    (field "subtree" (option string))))
+  (type $repo-search-result (record
+    (field "full-name" string)
+    (field "description" (option string))
+    (field "stargazers-count" u64)))
+  (func $get-tree-structure (param "repo-path" string) (param "git-ref" (option $git-ref)) (param "options" $tree-options) (result (result string (error string)))
+    (canon lift (core func $plugin "get-tree-structure") (memory $memory "memory") (realloc (func $memory "realloc"))))
+  (func $get-file-content (param "repo-path" string) (param "file-path" string) (param "git-ref" (option $git-ref)) (result (result string (error string)))
+    (canon lift (core func $plugin "get-file-content") (memory $memory "memory") (realloc (func $memory "realloc"))))
+  (func $find-repositories (param "query" string) (param "limit" (option u32)) (result (result (list $repo-search-result) (error string)))
+    (canon lift (core func $plugin "find-repositories") (memory $memory "memory") (realloc (func $memory "realloc"))))
+  (instance $provider
+    (export "git-ref" (type $git-ref))
+    (export "tree-options" (type $tree-options))
+    (export "repo-search-result" (type $repo-search-result))
+    (export "get-tree-structure" (func $get-tree-structure))
+    (export "get-file-content" (func $get-file-content))
+    (export "find-repositories" (func $find-repositories)))
+  (export "gitingest:provider/provider@0.1.0" (instance $provider)))
+"#;
+
+    fn provider() -> WasmProvider {
+        WasmProvider::from_component(Arc::new(EchoClient), "acme", PLUGIN.as_bytes(), None).unwrap()
+    }
+
+    #[tokio::test]
+    async fn calls_reach_the_plugin() {
+        let provider = provider();
+
+        assert_eq!(
+            provider
+                .get_file_content("org/repo", "src/lib.rs", Some(GitRef::Tag("v1".into())))
+                .await
+                .unwrap(),
+            "src/lib.rs"
+        );
+        assert_eq!(
+            provider
+                .find_repositories("needle", Some(5))
+                .await
+                .unwrap_err()
+                .to_string(),
+            "needle"
+        );
+    }
+
+    #[tokio::test]
+    async fn plugins_send_requests_through_the_server() {
+        assert_eq!(
+            provider()
+                .get_tree_structure("org/repo", None, TreeOptions::default())
+                .await
+                .unwrap(),
+            "https://example.com/tree"
+        );
+    }
+
+    #[tokio::test]
+    async fn runaway_plugins_are_stopped() {
+        let error = provider()
+            .with_fuel(1_000_000)
+            .find_repositories("loop", Some(0))
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().starts_with("Plugin acme failed"));
+    }
+
+    #[test]
+    fn components_not_fitting_the_interface_are_refused() {
+        assert!(
+            WasmProvider::from_component(Arc::new(EchoClient), "acme", b"(component)", None)
+                .is_err()
+        );
+    }
+}
@@ -433,3 +433,16 @@ check_bin_dir "${BIN_DIR}"
 
 install "${EXT}"
 completed "gitingest-mcp installed"
+
+# Plain git servers and repository mirrors are cloned with the git command line, configured
+# through the environment, which git supports since 2.31
+GIT_VERSION="$(git --version 2>/dev/null | sed -n 's/^git version \([0-9]*\)\.\([0-9]*\).*/\1 \2/p')"
+if [ -z "${GIT_VERSION}" ]; then
+  warn "git was not found: repositories cannot be mirrored or cloned until git 2.31 or later is installed"
+else
+  # shellcheck disable=SC2086
+  set -- ${GIT_VERSION}
+  if [ "$1" -lt 2 ] || { [ "$1" -eq 2 ] && [ "$2" -lt 31 ]; }; then
+    warn "git $1.$2 is too old to mirror or clone repositories, git 2.31 or later is needed"
+  fi
+fi
@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+
+use git_provider::middleware::{PlannedCall, RateLimits};
+use gitingest_mcp_tools::FanOut;
+use serde_json::Value;
+
+/// Whether a tool call asks for a dry run with an `explain` argument
+pub fn explain_requested(arguments: &Value) -> bool {
+    match arguments.get("explain") {
+        Some(Value::Bool(explain)) => *explain,
+        Some(Value::String(explain)) => explain == "true",
+        _ => false,
+    }
+}
+
+/// Describes the calls a dry run of `tool` kept from the network, with how many requests each
+/// host still accepts and the calls `fan_out` estimates would follow them
+pub fn render_plan(
+    tool: &str,
+    calls: &[PlannedCall],
+    fan_out: Option<FanOut>,
+    rate_limits: &RateLimits,
+) -> String {
+    if calls.is_empty() {
+        return format!(
+            "Request plan for {}: no API calls, the tool runs locally or from a mirror clone",
+            tool
+        );
+    }
+
+    let mut per_host: BTreeMap<&str, usize> = BTreeMap::new();
+    for call in calls {
+        *per_host.entry(&call.host).or_default() += 1;
+    }
+
+    let mut output = format!(
+        "Request plan for {}: {} API calls, none of them sent\n\n",
+        tool,
+        calls.len()
+    );
+    for (host, count) in per_host {
+        let headroom = match rate_limits.headroom(host) {
+            Some(limit) => format!("{} of {} requests left", limit.remaining, limit.limit),
+            None => "rate limit not reported yet".to_string(),
+        };
+        output.push_str(&format!("- {}: {} calls, {}\n", host, count, headroom));
+    }
+
+    output.push('\n');
+    for (index, call) in calls.iter().enumerate() {
+        output.push_str(&format!(
+            "{}. {} {}{}\n",
+            index + 1,
+            call.method,
+            call.host,
+            call.path
+        ));
+    }
+
+    // Nothing was answered, so the calls a response would have led to are only estimated
+    match fan_out {
+        Some(fan_out) => output.push_str(&format!(
+            "\nEstimated total: up to {} API calls, the calls above then one per {} read, at most {}",
+            calls.len() + fan_out.calls,
+            fan_out.item,
+            fan_out.calls
+        )),
+        None => output.push_str(
+            "\nCalls depending on the responses of these, if any, are not included",
+        ),
+    }
+    output
+}
@@ -1,45 +1,253 @@
+mod explain;
 mod prompt_registry;
+mod provider_registry;
 mod resource_registry;
+mod subscriptions;
 mod tool_registry;
 
-use std::{env, sync::Arc};
+use std::{collections::BTreeMap, env, sync::Arc, time::Duration};
 
 use anyhow::Result;
 use context_server::{ContextServer, ContextServerRpcRequest, ContextServerRpcResponse};
-use git_provider::GitProvider;
-use github_provider::GitHubProvider;
-use gitingest_mcp_tools::{FindRepositories, RepositoryRead, RepositoryTreeView};
-use gitlab_provider::GitLabProvider;
+use git_mirror::{
+    CloneOptions, Decision, MirrorCache, MirroredProvider, Planner, RemoteGitProvider,
+};
+use git_provider::{
+    GitProvider, api_compat,
+    middleware::{
+        AuditLog, Compression, DryRun, HostAllowlist, MiddlewareStack, RateLimits, ReqwestClient,
+        SingleFlight, Transfer,
+    },
+    pii,
+    warnings::{self, Warning, WarningKind},
+};
+use gitingest_mcp_tools::{
+    AccessHistory, ApplyPatchPreview, BisectHelper, BranchDivergence, BuildInstructions,
+    ChangelogResource, CheckAnnotations, CheckLinks, CiArtifacts, CiJobLog, CloneToCache,
+    CommitDiff, CommitFiles, CompareRepositories, Completions, ConfigInventory, ContainerAnalysis,
+    ContributorAffiliation, CreateBranch, CreateComment, CreateIssue, CreatePullRequest,
+    DetectLicense, DigestResource, DocsSiteMap, DownloadDirectory, ExpandCuratedList,
+    ExplainFilters, FindRepositories, ForkRepository, KubernetesInventory, Language,
+    ListContributors, ListIssues, ListPullRequests, ListReleases, MAX_COMPLETIONS,
+    MyReviewRequests, Permalink, ProjectBoards, ReadIssue, ReadPullRequest, ReadReadme,
+    RecentActivity, RepositoryActivity, RepositoryArchive, RepositoryDiff, RepositoryDigest,
+    RepositoryGrep, RepositoryHotspots, RepositoryInfo, RepositoryKeyFiles, RepositoryLabels,
+    RepositoryLanguages, RepositoryManifest, RepositoryMilestones, RepositoryRead,
+    RepositorySchemas, RepositorySummary, RepositoryTags, RepositoryTreeView, ResolveLink,
+    SynthesizeChangelog, TerraformInventory, WhoOwnsPath, WritePolicy, WriteRoots,
+    estimate_fan_out, set_language,
+};
 use http_client::HttpClient;
-use http_client_reqwest::HttpClientReqwest;
-use tokio::io::{self, AsyncBufReadExt, AsyncWriteExt, BufReader};
+use local_provider::LocalProvider;
+use serde_json::json;
+use tokio::{
+    io::{self, AsyncBufReadExt, AsyncWriteExt, BufReader},
+    sync::mpsc,
+    task::{JoinError, JoinSet},
+};
 
 use crate::{
-    prompt_registry::PromptRegistry, resource_registry::ResourceRegistry,
+    prompt_registry::PromptRegistry,
+    provider_registry::ProviderRegistry,
+    resource_registry::ResourceRegistry,
+    subscriptions::{Subscriptions, spawn_refresh},
     tool_registry::ToolRegistry,
 };
 
+/// How often subscribed resources are checked for new commits, unless overridden by
+/// GITINGEST_REFRESH_INTERVAL (in seconds)
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
 struct ContextServerState {
+    resource_registry: Arc<ResourceRegistry>,
+    subscriptions: Arc<Subscriptions>,
+    completions: Completions,
+    history: Arc<AccessHistory>,
+    rate_limits: Arc<RateLimits>,
+    providers: Vec<Arc<dyn GitProvider>>,
+    /// Whether every tool call is a dry run, not only the ones asking with `explain`
+    explain_all: bool,
     rpc: ContextServer,
 }
 
 impl ContextServerState {
-    fn new(http_client: Arc<dyn HttpClient>) -> Result<Self> {
+    fn new(
+        http_client: Arc<dyn HttpClient>,
+        rate_limits: Arc<RateLimits>,
+        allowlist: Arc<HostAllowlist>,
+        enable_write: bool,
+        explain_all: bool,
+    ) -> Result<Self> {
         let resource_registry = Arc::new(ResourceRegistry::default());
 
-        let providers: Vec<Arc<dyn GitProvider>> = vec![
-            Arc::new(GitHubProvider::new(http_client.clone())),
-            Arc::new(GitLabProvider::new(http_client.clone())),
-        ];
+        // Kept in memory only, unless a file to persist it is given
+        let history = Arc::new(match env::var("GITINGEST_HISTORY_FILE") {
+            Ok(path) => AccessHistory::persisted(path.into())?,
+            Err(_) => AccessHistory::default(),
+        });
+
+        // Repositories cloned with `clone_to_cache`, or by the planner when the API runs low,
+        // answer history and diff requests locally
+        let mirrors = Arc::new(
+            MirrorCache::new(MirrorCache::default_root())
+                .with_clone_options(CloneOptions::from_env()?),
+        );
+        let mut planner = Planner::new(rate_limits.clone());
+        if let Some(max_clone_mb) = env::var("GITINGEST_MAX_CLONE_SIZE_MB")
+            .ok()
+            .and_then(|mb| mb.parse::<u64>().ok())
+        {
+            planner = planner.with_max_clone_size(max_clone_mb * 1024);
+        }
+        let planner = Arc::new(planner);
+        let registry = ProviderRegistry::from_env(http_client, &allowlist)?;
+        let providers: Vec<Arc<dyn GitProvider>> =
+            registry
+                .providers()
+                .map(|provider| {
+                    Arc::new(MirroredProvider::new(
+                        provider.clone(),
+                        mirrors.clone(),
+                        planner.clone(),
+                    )) as Arc<dyn GitProvider>
+                })
+                // Working copies are already local, there is nothing to mirror, and plain git servers
+                // are only ever read from their clone
+                .chain([
+                    Arc::new(LocalProvider::new()) as Arc<dyn GitProvider>,
+                    Arc::new(
+                        RemoteGitProvider::new(mirrors.clone())
+                            .with_allowlist(allowlist.clone())
+                            .with_http(env::var("GITINGEST_ALLOW_HTTP").is_ok_and(|allow| {
+                                allow == "1" || allow.eq_ignore_ascii_case("true")
+                            })),
+                    ),
+                ])
+                .filter(|provider| registry.is_enabled(provider.name()))
+                .collect();
+
+        // Local files are only written in write mode, and only below GITINGEST_WRITE_ROOTS
+        let write_roots = if enable_write {
+            WriteRoots::from_env()
+        } else {
+            WriteRoots::default()
+        };
+        // Likewise, remote repositories only where GITINGEST_WRITE_POLICY allows
+        let write_policy = if enable_write {
+            WritePolicy::from_env()?
+        } else {
+            WritePolicy::default()
+        };
 
         let tool_registry = Arc::new(ToolRegistry::default());
         tool_registry.register(Arc::new(RepositoryTreeView::new(providers.clone())));
         tool_registry.register(Arc::new(RepositoryRead::new(providers.clone())));
+        tool_registry.register(Arc::new(RepositoryGrep::new(providers.clone())));
         tool_registry.register(Arc::new(FindRepositories::new(providers.clone())));
+        tool_registry.register(Arc::new(ExpandCuratedList::new(providers.clone())));
+        tool_registry.register(Arc::new(RepositoryDigest::new(providers.clone())));
+        tool_registry.register(Arc::new(RepositoryKeyFiles::new(providers.clone())));
+        tool_registry.register(Arc::new(ExplainFilters::new(providers.clone())));
+        tool_registry.register(Arc::new(RepositoryArchive::new(
+            providers.clone(),
+            write_roots.clone(),
+        )));
+        tool_registry.register(Arc::new(RepositoryManifest::new(providers.clone())));
+        tool_registry.register(Arc::new(RepositorySchemas::new(providers.clone())));
+        tool_registry.register(Arc::new(ConfigInventory::new(providers.clone())));
+        tool_registry.register(Arc::new(BuildInstructions::new(providers.clone())));
+        tool_registry.register(Arc::new(ContainerAnalysis::new(providers.clone())));
+        tool_registry.register(Arc::new(KubernetesInventory::new(providers.clone())));
+        tool_registry.register(Arc::new(TerraformInventory::new(providers.clone())));
+        tool_registry.register(Arc::new(WhoOwnsPath::new(providers.clone())));
+        tool_registry.register(Arc::new(ResolveLink::new(providers.clone())));
+        tool_registry.register(Arc::new(Permalink::new(providers.clone())));
+        tool_registry.register(Arc::new(CheckLinks::new(providers.clone())));
+        tool_registry.register(Arc::new(DocsSiteMap::new(providers.clone())));
+        tool_registry.register(Arc::new(ApplyPatchPreview::new(providers.clone())));
+        tool_registry.register(Arc::new(CheckAnnotations::new(providers.clone())));
+        tool_registry.register(Arc::new(CiJobLog::new(providers.clone())));
+        tool_registry.register(Arc::new(CiArtifacts::new(
+            providers.clone(),
+            write_roots.clone(),
+        )));
+        tool_registry.register(Arc::new(RepositoryActivity::new(providers.clone())));
+        tool_registry.register(Arc::new(MyReviewRequests::new(providers.clone())));
+        tool_registry.register(Arc::new(ListPullRequests::new(providers.clone())));
+        tool_registry.register(Arc::new(ReadPullRequest::new(providers.clone())));
+        tool_registry.register(Arc::new(ListIssues::new(providers.clone())));
+        tool_registry.register(Arc::new(ReadIssue::new(providers.clone())));
+        tool_registry.register(Arc::new(ReadReadme::new(providers.clone())));
+        tool_registry.register(Arc::new(DetectLicense::new(providers.clone())));
+        tool_registry.register(Arc::new(RepositoryMilestones::new(providers.clone())));
+        tool_registry.register(Arc::new(ProjectBoards::new(providers.clone())));
+        tool_registry.register(Arc::new(RepositoryLabels::new(providers.clone())));
+        tool_registry.register(Arc::new(SynthesizeChangelog::new(providers.clone())));
+        tool_registry.register(Arc::new(RepositoryDiff::new(providers.clone())));
+        tool_registry.register(Arc::new(CommitDiff::new(providers.clone())));
+        tool_registry.register(Arc::new(BisectHelper::new(providers.clone())));
+        tool_registry.register(Arc::new(RecentActivity::new(history.clone())));
+        tool_registry.register(Arc::new(RepositoryInfo::new(providers.clone())));
+        tool_registry.register(Arc::new(RepositorySummary::new(providers.clone())));
+        tool_registry.register(Arc::new(RepositoryLanguages::new(providers.clone())));
+        tool_registry.register(Arc::new(RepositoryTags::new(providers.clone())));
+        tool_registry.register(Arc::new(ListReleases::new(providers.clone())));
+        tool_registry.register(Arc::new(ListContributors::new(providers.clone())));
+        tool_registry.register(Arc::new(CompareRepositories::new(providers.clone())));
+        tool_registry.register(Arc::new(RepositoryHotspots::new(providers.clone())));
+        tool_registry.register(Arc::new(ContributorAffiliation::new(providers.clone())));
+        tool_registry.register(Arc::new(BranchDivergence::new(providers.clone())));
+        tool_registry.register(Arc::new(CloneToCache::new(providers.clone(), mirrors)));
+
+        // Tools that modify remote repositories or write local files are strictly opt-in
+        if enable_write {
+            tool_registry.register(Arc::new(DownloadDirectory::new(
+                providers.clone(),
+                write_roots.clone(),
+            )));
+            tool_registry.register(Arc::new(ForkRepository::new(
+                providers.clone(),
+                write_policy.clone(),
+            )));
+            tool_registry.register(Arc::new(CreateBranch::new(
+                providers.clone(),
+                write_policy.clone(),
+            )));
+            tool_registry.register(Arc::new(CommitFiles::new(
+                providers.clone(),
+                write_policy.clone(),
+            )));
+            tool_registry.register(Arc::new(CreatePullRequest::new(
+                providers.clone(),
+                write_policy.clone(),
+            )));
+            tool_registry.register(Arc::new(CreateIssue::new(
+                providers.clone(),
+                write_policy.clone(),
+            )));
+            tool_registry.register(Arc::new(CreateComment::on_issues(
+                providers.clone(),
+                write_policy.clone(),
+            )));
+            tool_registry.register(Arc::new(CreateComment::on_pull_requests(
+                providers.clone(),
+                write_policy.clone(),
+            )));
+        }
+
+        resource_registry.register_template(Arc::new(ChangelogResource::new(providers.clone())));
+        resource_registry.register_template(Arc::new(DigestResource::new(providers.clone())));
 
         let prompt_registry = Arc::new(PromptRegistry::default());
 
         Ok(Self {
+            resource_registry: resource_registry.clone(),
+            subscriptions: Arc::new(Subscriptions::default()),
+            completions: Completions::new(providers.clone(), history.clone()),
+            history,
+            rate_limits,
+            providers,
+            explain_all,
             rpc: ContextServer::builder()
                 .with_server_info((env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")))
                 .with_resources(resource_registry)
@@ -53,24 +261,407 @@ impl ContextServerState {
         &self,
         request: ContextServerRpcRequest,
     ) -> Result<Option<ContextServerRpcResponse>> {
-        self.rpc.handle_incoming_message(request).await
+        // Resource templates are not handled by the context server, answer them directly
+        if request.method == "resources/templates/list" {
+            return Ok(Some(serde_json::from_value(json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "result": {
+                    "resourceTemplates": self.resource_registry.list_templates(),
+                },
+            }))?));
+        }
+
+        if request.method == "resources/subscribe" || request.method == "resources/unsubscribe" {
+            let uri = request
+                .params
+                .as_ref()
+                .and_then(|params| params.get("uri"))
+                .and_then(|uri| uri.as_str());
+
+            match uri {
+                Some(uri) if request.method == "resources/subscribe" => {
+                    self.subscriptions
+                        .subscribe(&self.resource_registry, uri)
+                        .await
+                }
+                Some(uri) => self.subscriptions.unsubscribe(uri),
+                None => eprintln!("Missing resource uri in {} request", request.method),
+            }
+
+            return Ok(Some(serde_json::from_value(json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "result": {},
+            }))?));
+        }
+
+        if request.method == "completion/complete" {
+            let params = request.params.unwrap_or_default();
+            let name = params
+                .pointer("/argument/name")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let value = params
+                .pointer("/argument/value")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let arguments = params
+                .pointer("/context/arguments")
+                .cloned()
+                .unwrap_or_default();
+
+            // Completion is best effort, a failed lookup just suggests nothing
+            let values = self
+                .completions
+                .complete(name, value, &arguments)
+                .await
+                .unwrap_or_else(|e| {
+                    eprintln!("Error completing {}: {}", name, e);
+                    Vec::new()
+                });
+
+            return Ok(Some(serde_json::from_value(json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "result": {
+                    "completion": {
+                        "values": values.iter().take(MAX_COMPLETIONS).collect::<Vec<_>>(),
+                        "total": values.len(),
+                        "hasMore": values.len() > MAX_COMPLETIONS,
+                    },
+                },
+            }))?));
+        }
+
+        // Dry runs list the API calls a tool would make, without making them
+        let mut explained_tool = None;
+        if request.method == "tools/call"
+            && let Some(params) = &request.params
+            && let Some(tool) = params.get("name").and_then(|v| v.as_str())
+            && let Some(arguments) = params.get("arguments")
+        {
+            if self.explain_all || explain::explain_requested(arguments) {
+                let fan_out = estimate_fan_out(&self.providers, tool, arguments);
+                explained_tool = Some((tool.to_string(), fan_out));
+            } else {
+                self.history.record(tool, arguments);
+            }
+        }
+
+        let is_tool_call = request.method == "tools/call";
+        let is_initialize = request.method == "initialize";
+        let is_tools_list = request.method == "tools/list";
+        if let Some((tool, fan_out)) = explained_tool {
+            // The warnings of a dry run only tell calls were not sent, the plan says as much
+            let ((response, calls), _) =
+                warnings::collect(DryRun::plan(self.rpc.handle_incoming_message(request))).await;
+            let Some(response) = response? else {
+                return Ok(None);
+            };
+
+            // The tool's own output only tells the first call failed, the plan replaces it
+            let mut response = serde_json::to_value(response)?;
+            if let Some(result) = response.pointer_mut("/result") {
+                *result = json!({
+                    "content": [{
+                        "type": "text",
+                        "text": explain::render_plan(&tool, &calls, fan_out, &self.rate_limits),
+                    }],
+                });
+            }
+            return Ok(Some(serde_json::from_value(response)?));
+        }
+        // What a call chose, moved and met belongs to it, not to the calls running alongside
+        let (((response, warnings), transfers), decisions) = Planner::collect(
+            Compression::collect(warnings::collect(self.rpc.handle_incoming_message(request))),
+        )
+        .await;
+        let response = response?;
+
+        // Advertise the subscriptions and completions handled above alongside the context
+        // server's capabilities
+        match response {
+            Some(response) if is_initialize => {
+                let mut response = serde_json::to_value(response)?;
+                if let Some(resources) = response.pointer_mut("/result/capabilities/resources")
+                    && let Some(resources) = resources.as_object_mut()
+                {
+                    resources.insert("subscribe".into(), true.into());
+                }
+                if let Some(capabilities) = response.pointer_mut("/result/capabilities")
+                    && let Some(capabilities) = capabilities.as_object_mut()
+                {
+                    capabilities.insert("completions".into(), json!({}));
+                }
+                Ok(Some(serde_json::from_value(response)?))
+            }
+            // Every tool accepts `explain`, handled above rather than by the tools themselves
+            Some(response) if is_tools_list => {
+                let mut response = serde_json::to_value(response)?;
+                if let Some(tools) = response.pointer_mut("/result/tools")
+                    && let Some(tools) = tools.as_array_mut()
+                {
+                    for tool in tools {
+                        if let Some(properties) = tool.pointer_mut("/inputSchema/properties")
+                            && let Some(properties) = properties.as_object_mut()
+                        {
+                            properties.insert(
+                                "explain".into(),
+                                json!({
+                                    "type": "boolean",
+                                    "description": "Optional flag to list the API calls the tool would make, with the rate limit left on each host, instead of making them. Default: false"
+                                }),
+                            );
+                        }
+                    }
+                }
+                Ok(Some(serde_json::from_value(response)?))
+            }
+            Some(response) if is_tool_call => {
+                let mut meta = serde_json::Map::new();
+                let strategies = Self::strategies(decisions);
+                if !strategies.is_empty() {
+                    meta.insert("gitingest/strategies".into(), strategies.into());
+                }
+                let transfers = Self::transfers(transfers);
+                if !transfers.is_empty() {
+                    meta.insert("gitingest/transfers".into(), transfers.into());
+                }
+                // Tools cannot tell a host withheld results, so the notice joins their output
+                let withheld: Vec<String> = warnings
+                    .iter()
+                    .filter(|warning| warning.kind == WarningKind::Withheld)
+                    .map(|warning| warning.message.clone())
+                    .collect();
+                let warnings = Self::warnings(warnings);
+                if !warnings.is_empty() {
+                    meta.insert("gitingest/warnings".into(), warnings.into());
+                }
+                if meta.is_empty() {
+                    return Ok(Some(response));
+                }
+
+                let mut response = serde_json::to_value(response)?;
+                if let Some(result) = response.pointer_mut("/result")
+                    && let Some(result) = result.as_object_mut()
+                {
+                    result.insert("_meta".into(), meta.into());
+                }
+                if let Some(content) = response.pointer_mut("/result/content")
+                    && let Some(content) = content.as_array_mut()
+                {
+                    for message in withheld {
+                        content
+                            .push(json!({ "type": "text", "text": format!("Note: {}", message) }));
+                    }
+                }
+                Ok(Some(serde_json::from_value(response)?))
+            }
+            response => Ok(response),
+        }
+    }
+
+    /// The non-fatal issues met during a tool call, so clients can tell degraded results apart
+    fn warnings(warnings: Vec<Warning>) -> Vec<serde_json::Value> {
+        warnings
+            .into_iter()
+            .map(|warning| {
+                json!({
+                    "kind": warning.kind.as_str(),
+                    "message": warning.message,
+                })
+            })
+            .collect()
+    }
+
+    /// The bytes each host sent during a tool call, as received and once decompressed
+    fn transfers(transfers: Vec<Transfer>) -> Vec<serde_json::Value> {
+        transfers
+            .into_iter()
+            .map(|transfer| {
+                json!({
+                    "host": transfer.host,
+                    "requests": transfer.requests,
+                    "received_bytes": transfer.received_bytes,
+                    "decoded_bytes": transfer.decoded_bytes,
+                })
+            })
+            .collect()
+    }
+
+    /// The backends the planner chose during a tool call, one entry per repository, operation
+    /// and strategy
+    fn strategies(decisions: Vec<Decision>) -> Vec<serde_json::Value> {
+        let mut grouped: BTreeMap<(String, &str, String), (usize, String)> = BTreeMap::new();
+        for decision in decisions {
+            let entry = grouped
+                .entry((
+                    format!("{}:{}", decision.provider, decision.repo_path),
+                    decision.operation,
+                    decision.strategy.to_string(),
+                ))
+                .or_default();
+            entry.0 += 1;
+            // The latest reason reflects the rate limit after the previous calls
+            entry.1 = decision.reason;
+        }
+
+        grouped
+            .into_iter()
+            .map(|((repo, operation, strategy), (calls, reason))| {
+                json!({
+                    "repo": repo,
+                    "operation": operation,
+                    "strategy": strategy,
+                    "calls": calls,
+                    "reason": reason,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Masks the personal data in the texts of a response: tool results and resource contents. A
+/// text that cannot be scrubbed is withheld rather than sent as is
+fn scrub_response(response: &mut serde_json::Value) {
+    for pointer in ["/result/content", "/result/contents"] {
+        let Some(items) = response
+            .pointer_mut(pointer)
+            .and_then(|items| items.as_array_mut())
+        else {
+            continue;
+        };
+        for item in items {
+            if let Some(text) = item.get("text").and_then(|text| text.as_str()) {
+                item["text"] = match pii::scrub(text) {
+                    Ok(scrubbed) => scrubbed.into(),
+                    Err(e) => {
+                        eprintln!("Error scrubbing personal data: {}", e);
+                        "[withheld: personal data could not be masked]".into()
+                    }
+                };
+            }
+        }
+    }
+}
+
+/// Logs a request that could not be served, the server going on with the others
+fn report_failure(served: Result<Result<()>, JoinError>) {
+    match served {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => eprintln!("Error processing request: {}", e),
+        Err(e) => eprintln!("Error processing request: {}", e),
     }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let http_client = Arc::new(HttpClientReqwest::default());
+    // Outermost, so calls to hosts outside the allowlist reach no other layer
+    let allowlist = Arc::new(HostAllowlist::from_env());
+    let mut http_client = MiddlewareStack::new(Arc::new(ReqwestClient::new(allowlist.clone())?))
+        .with(allowlist.clone());
 
     // Optional GitHub token for API rate limits
-    if env::var("GITHUB_TOKEN").is_err() {
-        eprintln!("Warning: GITHUB_TOKEN environment variable not set. API rate limits may apply.");
+    let github_anonymous = env::var("GITHUB_TOKEN").is_err();
+    if github_anonymous {
+        eprintln!(
+            "Warning: GITHUB_TOKEN environment variable not set. GitHub allows 60 unauthenticated API requests an hour: tools read fewer files by default, and repositories are cloned once half of the requests are spent."
+        );
+    }
+
+    // Headers, warnings and errors of the tools, in the language the client works in
+    set_language(Language::from_env()?)?;
+
+    let enable_write = env::args().any(|arg| arg == "--enable-write");
+    let explain_all = env::args().any(|arg| arg == "--explain");
+    if explain_all {
+        eprintln!("Explain mode enabled: tool calls list the API calls they would make instead.");
+    }
+    if enable_write {
+        eprintln!(
+            "Write mode enabled: tools may create branches, commits, pull requests, issues and comments in the repositories allowed by GITINGEST_WRITE_POLICY, and write files below GITINGEST_WRITE_ROOTS."
+        );
+    }
+
+    // Listings that fail to parse are errors unless the old empty results are asked for
+    if env::args().any(|arg| arg == "--lenient") {
+        api_compat::set_lenient(true);
+        eprintln!(
+            "Lenient mode enabled: listings the server cannot parse are read as empty instead of failing."
+        );
+    }
+
+    if env::args().any(|arg| arg == "--scrub-pii") {
+        pii::enable();
+        eprintln!(
+            "PII scrubbing enabled: email addresses, phone numbers and commit author names are masked in results."
+        );
+    }
+
+    if env::args().any(|arg| arg == "--audit") {
+        http_client = http_client.with(Arc::new(AuditLog));
+    }
+
+    // Tree listings and search results are large JSON payloads, compressed about tenfold
+    http_client = http_client.with(Arc::new(Compression));
+
+    let rate_limits = Arc::new(RateLimits::default());
+    if github_anonymous {
+        // Planned from the first call rather than once GitHub reports the limit
+        rate_limits.assume("api.github.com", 60);
+    }
+    http_client = http_client.with(rate_limits.clone());
+
+    // Innermost, so dry runs record the calls exactly as they would be sent
+    http_client = http_client.with(Arc::new(DryRun));
+
+    // Outside every layer, so a request shared by concurrent tool calls is audited and counted
+    // once
+    let http_client = SingleFlight::new(Arc::new(http_client));
+
+    let state = ContextServerState::new(
+        Arc::new(http_client),
+        rate_limits,
+        allowlist.clone(),
+        enable_write,
+        explain_all,
+    )?;
+    if allowlist.is_restricted() {
+        eprintln!(
+            "Host allowlist enabled: only the hosts of GITINGEST_ALLOWED_HOSTS and of the providers file are contacted."
+        );
     }
 
-    let state = ContextServerState::new(http_client)?;
+    // Responses and notifications share stdout, a single task writes them in order
+    let (outgoing, mut outgoing_receiver) = mpsc::unbounded_channel::<String>();
+    let writer = tokio::spawn(async move {
+        let mut stdout = io::stdout();
+        while let Some(message) = outgoing_receiver.recv().await {
+            stdout.write_all(message.as_bytes()).await?;
+            stdout.write_all(b"\n").await?;
+            stdout.flush().await?;
+        }
+        Ok::<_, io::Error>(())
+    });
+
+    let refresh_interval = env::var("GITINGEST_REFRESH_INTERVAL")
+        .ok()
+        .and_then(|seconds| seconds.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_REFRESH_INTERVAL);
+    spawn_refresh(
+        state.resource_registry.clone(),
+        state.subscriptions.clone(),
+        refresh_interval,
+        outgoing.downgrade(),
+    );
 
     let mut stdin = BufReader::new(io::stdin()).lines();
-    let mut stdout = io::stdout();
 
+    // Requests are served concurrently, so identical calls in flight share their API requests
+    let state = Arc::new(state);
+    let mut in_flight = JoinSet::new();
     while let Some(line) = stdin.next_line().await? {
         let request: ContextServerRpcRequest = match serde_json::from_str(&line) {
             Ok(req) => req,
@@ -80,13 +671,30 @@ async fn main() -> Result<()> {
             }
         };
 
-        if let Some(response) = state.process_request(request).await? {
-            let response_json = serde_json::to_string(&response)?;
-            stdout.write_all(response_json.as_bytes()).await?;
-            stdout.write_all(b"\n").await?;
-            stdout.flush().await?;
+        let state = state.clone();
+        let outgoing = outgoing.clone();
+        in_flight.spawn(async move {
+            if let Some(response) = state.process_request(request).await? {
+                let mut response = serde_json::to_value(response)?;
+                if pii::is_enabled() {
+                    scrub_response(&mut response);
+                }
+                outgoing.send(serde_json::to_string(&response)?)?;
+            }
+            Ok(())
+        });
+
+        while let Some(served) = in_flight.try_join_next() {
+            report_failure(served);
         }
     }
 
+    // Requests still running are answered before the output closes
+    while let Some(served) = in_flight.join_next().await {
+        report_failure(served);
+    }
+    drop(outgoing);
+    writer.await??;
+
     Ok(())
 }
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::BTreeMap, sync::Arc};
 
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
@@ -7,7 +7,7 @@ use parking_lot::RwLock;
 use serde_json::Value;
 
 #[derive(Default)]
-pub struct PromptRegistry(RwLock<HashMap<String, Arc<dyn PromptExecutor>>>);
+pub struct PromptRegistry(RwLock<BTreeMap<String, Arc<dyn PromptExecutor>>>);
 
 impl PromptRegistry {
     #[allow(unused)]
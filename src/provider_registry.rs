@@ -0,0 +1,350 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context, Result, anyhow};
+use git_provider::{GitProvider, middleware::HostAllowlist};
+use gitea_provider::GiteaProvider;
+use github_provider::{GistProvider, GitHubProvider};
+use gitlab_provider::{GitLabProvider, GitLabToken, SnippetProvider};
+use http_client::HttpClient;
+use huggingface_provider::HuggingFaceProvider;
+use serde::Deserialize;
+use wasm_provider::WasmProvider;
+
+/// Names taken by the providers added after the registry, which cannot be redefined
+const RESERVED_NAMES: &[&str] = &["local", "git"];
+
+/// Built-in providers calling the same host as another, disabled along with it
+const COMPANIONS: &[(&str, &str)] = &[("github", "github-gist"), ("gitlab", "gitlab-snippet")];
+
+/// The hosting services an instance can be configured for
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ProviderKind {
+    Github,
+    Gitlab,
+    Gitea,
+}
+
+impl ProviderKind {
+    fn name(self) -> &'static str {
+        match self {
+            ProviderKind::Github => "github",
+            ProviderKind::Gitlab => "gitlab",
+            ProviderKind::Gitea => "gitea",
+        }
+    }
+
+    /// The public instance, used when a definition has no base URL
+    fn default_url(self) -> &'static str {
+        match self {
+            ProviderKind::Github => "https://api.github.com",
+            ProviderKind::Gitlab => "https://gitlab.com",
+            ProviderKind::Gitea => "https://gitea.com",
+        }
+    }
+}
+
+/// A provider instance, as declared in the configuration file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProviderDefinition {
+    #[serde(rename = "type")]
+    kind: ProviderKind,
+    /// Name used in repository identifiers, the type's name unless given
+    alias: Option<String>,
+    /// Web URL of the instance, or its API URL for GitHub Enterprise Server
+    /// (e.g., "https://ghe.example.com/api/v3")
+    base_url: Option<String>,
+    /// Token of the instance. GitLab tells access, OAuth and CI job tokens apart by their
+    /// shape, or by a "private:", "oauth:" or "job:" prefix
+    token: Option<String>,
+    /// Environment variable holding the token, `<ALIAS>_TOKEN` unless given
+    token_env: Option<String>,
+}
+
+/// A provider implemented by a WebAssembly component, as declared in the configuration file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PluginDefinition {
+    /// Name used in repository identifiers
+    alias: String,
+    /// The component, implementing the `git-provider-plugin` world of the wasm_provider crate.
+    /// Relative paths are taken from the configuration file's directory
+    path: PathBuf,
+    /// Token handed to the plugin
+    token: Option<String>,
+    /// Environment variable holding the token, `<ALIAS>_TOKEN` unless given
+    token_env: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProvidersConfig {
+    #[serde(default)]
+    providers: Vec<ProviderDefinition>,
+    /// Experimental: providers loaded from WebAssembly components
+    #[serde(default)]
+    plugins: Vec<PluginDefinition>,
+    /// Names of the providers never to call, built-in or defined above
+    #[serde(default)]
+    disabled: Vec<String>,
+    /// Hosts the server may contact, added to GITINGEST_ALLOWED_HOSTS
+    #[serde(default)]
+    allowed_hosts: Vec<String>,
+}
+
+impl ProvidersConfig {
+    fn read(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read providers file {}", path.display()))?;
+        let mut config: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid providers file {}", path.display()))?;
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for plugin in &mut config.plugins {
+            plugin.path = dir.join(&plugin.path);
+        }
+
+        Ok(config)
+    }
+}
+
+/// The token given in a definition, or else the one of its environment variable
+fn configured_token(alias: &str, token: Option<&str>, token_env: Option<&str>) -> Option<String> {
+    if let Some(token) = token {
+        return Some(token.to_string());
+    }
+
+    let variable = match token_env {
+        Some(variable) => variable.to_string(),
+        None => format!("{}_TOKEN", alias.to_ascii_uppercase().replace('-', "_")),
+    };
+    env::var(variable).ok()
+}
+
+fn check_alias(alias: &str) -> Result<()> {
+    if alias.is_empty()
+        || !alias
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "Invalid provider alias '{}': use letters, digits, '-' and '_' only",
+            alias
+        ));
+    }
+    if RESERVED_NAMES.contains(&alias) {
+        return Err(anyhow!("The provider alias '{}' is reserved", alias));
+    }
+
+    Ok(())
+}
+
+impl ProviderDefinition {
+    fn alias(&self) -> &str {
+        self.alias.as_deref().unwrap_or(self.kind.name())
+    }
+
+    fn token(&self) -> Option<String> {
+        configured_token(
+            self.alias(),
+            self.token.as_deref(),
+            self.token_env.as_deref(),
+        )
+    }
+
+    fn build(
+        &self,
+        http_client: Arc<dyn HttpClient>,
+        allowlist: &HostAllowlist,
+    ) -> Result<Arc<dyn GitProvider>> {
+        let alias = self.alias();
+        check_alias(alias)?;
+
+        let base_url = self.base_url.as_deref().unwrap_or(self.kind.default_url());
+        allowlist
+            .check_url(base_url)
+            .with_context(|| format!("Invalid base URL of provider '{}'", alias))?;
+        let token = self.token();
+
+        Ok(match self.kind {
+            ProviderKind::Github => Arc::new(GitHubProvider::with_host(
+                http_client,
+                alias,
+                base_url,
+                token,
+            )),
+            ProviderKind::Gitlab => Arc::new(GitLabProvider::with_host(
+                http_client,
+                alias,
+                base_url,
+                token.as_deref().map(GitLabToken::detect),
+            )),
+            ProviderKind::Gitea => Arc::new(GiteaProvider::with_host(
+                http_client,
+                alias,
+                base_url,
+                token,
+            )),
+        })
+    }
+}
+
+impl PluginDefinition {
+    fn build(&self, http_client: Arc<dyn HttpClient>) -> Result<Arc<dyn GitProvider>> {
+        check_alias(&self.alias)?;
+        let token = configured_token(
+            &self.alias,
+            self.token.as_deref(),
+            self.token_env.as_deref(),
+        );
+
+        Ok(Arc::new(WasmProvider::load(
+            http_client,
+            &self.alias,
+            &self.path,
+            token,
+        )?))
+    }
+}
+
+/// The hosted providers, in registration order. The built-in instances come first, then the
+/// hosts of GITHUB_HOSTS and of the file named by GITINGEST_PROVIDERS_FILE, a definition
+/// replacing any earlier provider of the same name.
+///
+/// The file is JSON, e.g.:
+///
+/// ```json
+/// {
+///   "providers": [
+///     { "type": "gitlab", "alias": "gitlab-work", "base_url": "https://gitlab.example.com" },
+///     { "type": "gitea", "alias": "codeberg", "base_url": "https://codeberg.org", "token_env": "CODEBERG_TOKEN" }
+///   ],
+///   "plugins": [
+///     { "alias": "acme", "path": "plugins/acme-provider.wasm", "token_env": "ACME_TOKEN" }
+///   ],
+///   "disabled": ["gitlab", "huggingface"],
+///   "allowed_hosts": ["api.github.com", "gitlab.example.com", "*.codeberg.org"]
+/// }
+/// ```
+///
+/// Disabled providers, listed in the file or in the comma-separated GITINGEST_DISABLED_PROVIDERS,
+/// are left out entirely: their tools do not list them and their hosts are never called.
+/// Disabling "github" or "gitlab" also disables the gists or snippets of the same host.
+///
+/// Plugins are WebAssembly components implementing a provider, see the wasm_provider crate. They
+/// are experimental, read-only, and reach their host through the server's HTTP client only.
+///
+/// Allowed hosts, listed in the file or in GITINGEST_ALLOWED_HOSTS, restrict the hosts called
+/// to these: the base URLs of GITHUB_HOSTS and of the file must point to one of them.
+#[derive(Default)]
+pub struct ProviderRegistry {
+    providers: Vec<Arc<dyn GitProvider>>,
+    disabled: Vec<String>,
+}
+
+impl ProviderRegistry {
+    pub fn from_env(http_client: Arc<dyn HttpClient>, allowlist: &HostAllowlist) -> Result<Self> {
+        // Read first, so the hosts the file allows apply to GITHUB_HOSTS too
+        let config = match env::var("GITINGEST_PROVIDERS_FILE") {
+            Ok(path) => Some(ProvidersConfig::read(Path::new(&path))?),
+            Err(_) => None,
+        };
+        for host in config.iter().flat_map(|config| &config.allowed_hosts) {
+            allowlist.allow(host);
+        }
+
+        let mut registry = Self::default();
+        registry.register(Arc::new(GitHubProvider::new(http_client.clone())));
+        registry.register(Arc::new(GistProvider::new(http_client.clone())));
+        registry.register(Arc::new(GitLabProvider::new(http_client.clone())));
+        registry.register(Arc::new(SnippetProvider::new(http_client.clone())));
+        registry.register(Arc::new(GiteaProvider::new(http_client.clone())));
+        registry.register(Arc::new(HuggingFaceProvider::new(http_client.clone())));
+
+        // GitHub Enterprise Server instances listed in GITHUB_HOSTS get their own names
+        for provider in GitHubProvider::hosts_from_env(http_client.clone())? {
+            allowlist
+                .check_url(provider.api_base())
+                .with_context(|| format!("Invalid GITHUB_HOSTS entry '{}'", provider.name()))?;
+            registry.register(Arc::new(provider));
+        }
+
+        if let Some(config) = config {
+            registry.apply(http_client, allowlist, config)?;
+        }
+
+        if let Ok(disabled) = env::var("GITINGEST_DISABLED_PROVIDERS") {
+            for name in disabled.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                registry.disable(name);
+            }
+        }
+
+        Ok(registry)
+    }
+
+    pub fn register(&mut self, provider: Arc<dyn GitProvider>) {
+        match self
+            .providers
+            .iter_mut()
+            .find(|p| p.name() == provider.name())
+        {
+            Some(existing) => *existing = provider,
+            None => self.providers.push(provider),
+        }
+    }
+
+    /// Keeps a provider, and the built-in ones sharing its host, from being used
+    pub fn disable(&mut self, name: &str) {
+        let companions = COMPANIONS
+            .iter()
+            .filter(|(provider, _)| *provider == name)
+            .map(|(_, companion)| *companion);
+
+        for name in [name].into_iter().chain(companions) {
+            if !self.disabled.iter().any(|disabled| disabled == name) {
+                self.disabled.push(name.to_string());
+            }
+        }
+    }
+
+    /// Whether a provider, hosted or not, may be used
+    pub fn is_enabled(&self, name: &str) -> bool {
+        !self.disabled.iter().any(|disabled| disabled == name)
+    }
+
+    /// Register every provider defined in a configuration file
+    fn apply(
+        &mut self,
+        http_client: Arc<dyn HttpClient>,
+        allowlist: &HostAllowlist,
+        config: ProvidersConfig,
+    ) -> Result<()> {
+        for definition in &config.providers {
+            self.register(definition.build(http_client.clone(), allowlist)?);
+        }
+        for plugin in &config.plugins {
+            self.register(
+                plugin
+                    .build(http_client.clone())
+                    .with_context(|| format!("Failed to load plugin '{}'", plugin.alias))?,
+            );
+        }
+        for name in &config.disabled {
+            self.disable(name);
+        }
+
+        Ok(())
+    }
+
+    /// The hosted providers left enabled, in registration order
+    pub fn providers(&self) -> impl Iterator<Item = &Arc<dyn GitProvider>> {
+        self.providers
+            .iter()
+            .filter(|provider| self.is_enabled(provider.name()))
+    }
+}
@@ -1,8 +1,10 @@
-use std::collections::HashMap;
+use std::{collections::BTreeMap, sync::Arc};
 
-use anyhow::{anyhow, Result};
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use context_server::{Resource, ResourceContent, ResourceContentType, ResourceDelegate};
+use gitingest_mcp_tools::ResourceTemplate;
+use serde_json::{Value, json};
 
 use parking_lot::RwLock;
 
@@ -13,8 +15,9 @@ pub struct ResourceRegistry {
 
 #[derive(Default)]
 struct Inner {
-    resources: HashMap<String, Resource>,
-    contents: HashMap<String, String>,
+    resources: BTreeMap<String, Resource>,
+    contents: BTreeMap<String, String>,
+    templates: Vec<Arc<dyn ResourceTemplate>>,
 }
 
 impl ResourceRegistry {
@@ -39,6 +42,40 @@ impl ResourceRegistry {
         let guard = self.inner.read();
         guard.contents.get(uri).cloned()
     }
+
+    pub fn register_template(&self, template: Arc<dyn ResourceTemplate>) {
+        self.inner.write().templates.push(template);
+    }
+
+    /// Lists the registered templates as expected by `resources/templates/list`
+    pub fn list_templates(&self) -> Vec<Value> {
+        let guard = self.inner.read();
+        guard
+            .templates
+            .iter()
+            .map(|t| {
+                json!({
+                    "uriTemplate": t.uri_template(),
+                    "name": t.name(),
+                    "description": t.description(),
+                    "mimeType": t.mime_type(),
+                })
+            })
+            .collect()
+    }
+
+    fn find_template(&self, uri: &str) -> Option<Arc<dyn ResourceTemplate>> {
+        let guard = self.inner.read();
+        guard.templates.iter().find(|t| t.matches(uri)).cloned()
+    }
+
+    /// Current revision of a templated resource, `None` when it cannot be watched
+    pub async fn revision(&self, uri: &str) -> Result<Option<String>> {
+        match self.find_template(uri) {
+            Some(template) => template.revision(uri).await,
+            None => Ok(None),
+        }
+    }
 }
 
 #[async_trait]
@@ -48,10 +85,32 @@ impl ResourceDelegate for ResourceRegistry {
     }
 
     async fn get(&self, uri: &str) -> Result<Option<Resource>> {
-        Ok(self.get_resource(uri).clone())
+        if let Some(resource) = self.get_resource(uri) {
+            return Ok(Some(resource));
+        }
+
+        Ok(self.find_template(uri).map(|template| Resource {
+            uri: uri.to_string(),
+            name: template.name().to_string(),
+            description: Some(template.description().to_string()),
+            mime_type: Some(template.mime_type().to_string()),
+        }))
     }
 
     async fn read(&self, uri: &str) -> Result<ResourceContent> {
+        // Templated resources are rendered on demand
+        if self.get_resource(uri).is_none()
+            && let Some(template) = self.find_template(uri)
+        {
+            return Ok(ResourceContent {
+                uri: uri.to_string(),
+                mime_type: template.mime_type().to_string(),
+                content: ResourceContentType::Text {
+                    text: template.read(uri).await?,
+                },
+            });
+        }
+
         let resource = self
             .get_resource(uri)
             .ok_or_else(|| anyhow!("Resource not found: {}", uri))?;
@@ -0,0 +1,83 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use parking_lot::Mutex;
+use serde_json::json;
+use tokio::sync::mpsc::WeakUnboundedSender;
+
+use crate::resource_registry::ResourceRegistry;
+
+/// Resources clients subscribed to, with the last revision they were sent
+#[derive(Default)]
+pub struct Subscriptions(Mutex<BTreeMap<String, Option<String>>>);
+
+impl Subscriptions {
+    pub async fn subscribe(&self, registry: &ResourceRegistry, uri: &str) {
+        let revision = registry.revision(uri).await.ok().flatten();
+        self.0.lock().insert(uri.to_string(), revision);
+    }
+
+    pub fn unsubscribe(&self, uri: &str) {
+        self.0.lock().remove(uri);
+    }
+
+    fn uris(&self) -> Vec<String> {
+        self.0.lock().keys().cloned().collect()
+    }
+
+    /// Records the latest revision of `uri`, returning whether it differs from a known one
+    fn update(&self, uri: &str, revision: String) -> bool {
+        let mut guard = self.0.lock();
+        let Some(known) = guard.get_mut(uri) else {
+            // Unsubscribed while its revision was fetched
+            return false;
+        };
+
+        let changed = known.as_ref().is_some_and(|known| *known != revision);
+        *known = Some(revision);
+        changed
+    }
+}
+
+/// Polls the revision of every subscribed resource, sending a `notifications/resources/updated`
+/// message through `outgoing` whenever one changes, until the server stops writing
+pub fn spawn_refresh(
+    registry: Arc<ResourceRegistry>,
+    subscriptions: Arc<Subscriptions>,
+    interval: Duration,
+    outgoing: WeakUnboundedSender<String>,
+) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        // The first tick completes immediately, subscriptions already hold a fresh revision
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+
+            for uri in subscriptions.uris() {
+                let revision = match registry.revision(&uri).await {
+                    Ok(Some(revision)) => revision,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        eprintln!("Error refreshing {}: {}", uri, e);
+                        continue;
+                    }
+                };
+
+                if subscriptions.update(&uri, revision) {
+                    let notification = json!({
+                        "jsonrpc": "2.0",
+                        "method": "notifications/resources/updated",
+                        "params": { "uri": uri },
+                    });
+                    let Some(outgoing) = outgoing.upgrade() else {
+                        return;
+                    };
+                    if outgoing.send(notification.to_string()).is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+}
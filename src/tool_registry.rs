@@ -1,13 +1,13 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::BTreeMap, sync::Arc};
 
-use anyhow::{anyhow, Result};
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use context_server::{Tool, ToolContent, ToolDelegate, ToolExecutor};
 use parking_lot::RwLock;
 use serde_json::Value;
 
 #[derive(Default)]
-pub struct ToolRegistry(RwLock<HashMap<String, Arc<dyn ToolExecutor>>>);
+pub struct ToolRegistry(RwLock<BTreeMap<String, Arc<dyn ToolExecutor>>>);
 
 impl ToolRegistry {
     pub fn register(&self, tool: Arc<dyn ToolExecutor>) {